@@ -46,6 +46,36 @@ fn main() {
 
     // Setup automatic CLI scanner copying
     setup_cli_scanner_distribution();
+
+    // Generate the C header for the C interface
+    #[cfg(feature = "cdylib")]
+    generate_c_header();
+}
+
+/// Write `include/rustpods.h` from the declarations in `src/ffi.rs`
+#[cfg(feature = "cdylib")]
+fn generate_c_header() {
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let header_path = manifest_dir.join("include").join("rustpods.h");
+
+    // Only the FFI module is parsed, so the rest of the crate stays out of the header
+    let result = cbindgen::Builder::new()
+        .with_src(manifest_dir.join("src").join("ffi.rs"))
+        .with_language(cbindgen::Language::C)
+        .with_include_guard("RUSTPODS_H")
+        .with_header("/* Generated by cbindgen from src/ffi.rs - do not edit */")
+        .generate();
+
+    match result {
+        Ok(bindings) => {
+            bindings.write_to_file(&header_path);
+        }
+        Err(e) => {
+            println!("cargo:warning=Failed to generate C header: {}", e);
+        }
+    }
 }
 
 fn build_cli_scanner() {
@@ -1,3 +1,4 @@
+use super::model_table::ModelTable;
 use serde::Deserialize;
 use std::process::Command;
 
@@ -26,6 +27,53 @@ pub struct AirPodsBatteryInfo {
     pub raw_manufacturer_data: Option<String>,
 }
 
+impl AirPodsBatteryInfo {
+    /// Whether two reports carry the same readings, ignoring signal strength, raw
+    /// advertisement bytes and when they were received
+    pub fn same_readings(&self, other: &Self) -> bool {
+        self.canonical_address == other.canonical_address
+            && self.name == other.name
+            && self.model_id == other.model_id
+            && (self.left_battery, self.right_battery, self.case_battery)
+                == (other.left_battery, other.right_battery, other.case_battery)
+            && (self.left_charging, self.right_charging, self.case_charging)
+                == (
+                    other.left_charging,
+                    other.right_charging,
+                    other.case_charging,
+                )
+            && (self.left_in_ear, self.right_in_ear, self.case_lid_open)
+                == (other.left_in_ear, other.right_in_ear, other.case_lid_open)
+            && self.side == other.side
+            && self.both_in_case == other.both_in_case
+            && self.color == other.color
+            && self.switch_count == other.switch_count
+    }
+
+    /// One line with each battery and whether it is charging, as `rustpods status` prints
+    ///
+    /// Unknown levels, which the scanner reports as negative, are shown as `--`.
+    pub fn status_line(&self) -> String {
+        let batteries: Vec<String> = [
+            ("L", self.left_battery, self.left_charging),
+            ("R", self.right_battery, self.right_charging),
+            ("Case", self.case_battery, self.case_charging),
+        ]
+        .into_iter()
+        .map(|(label, level, charging)| {
+            let level = if level < 0 {
+                "--".to_string()
+            } else {
+                format!("{}%", level.min(100))
+            };
+            let charging = if charging { " (charging)" } else { "" };
+            format!("{} {}{}", label, level, charging)
+        })
+        .collect();
+        format!("{}: {}", self.name, batteries.join(", "))
+    }
+}
+
 pub fn get_airpods_battery_info(helper_path: &str) -> Vec<AirPodsBatteryInfo> {
     let output = Command::new(helper_path)
         .output()
@@ -51,18 +99,11 @@ pub fn get_airpods_battery_info(helper_path: &str) -> Vec<AirPodsBatteryInfo> {
     infos
 }
 
+/// Name of a model ID from the [model table](super::model_table), or "Unknown"
 pub fn model_name(model_id: u16) -> &'static str {
-    match model_id {
-        0x2002 => "AirPods 1",
-        0x200F => "AirPods 2",
-        0x2013 => "AirPods 3",
-        0x200E => "AirPods Pro",
-        0x2014 => "AirPods Pro 2",
-        0x2024 => "AirPods Pro 2 (USB-C)",
-        0x200A => "AirPods Max",
-        0x2012 => "Beats Fit Pro",
-        _ => "Unknown",
-    }
+    ModelTable::global()
+        .model_name(model_id)
+        .unwrap_or("Unknown")
 }
 
 #[cfg(test)]
@@ -94,4 +135,28 @@ mod tests {
         assert_eq!(infos[1].canonical_address, "0000000001c8");
         assert_eq!(infos[1].model_id, 0x2013);
     }
+
+    #[test]
+    fn test_same_readings_ignores_signal_and_time() {
+        let line = r#"{"address":123,"canonical_address":"00000000007b","name":"AirPods","model_id":8206,"left_battery":90,"left_charging":true,"right_battery":80,"right_charging":false,"case_battery":70,"case_charging":true,"rssi":-60,"timestamp":1}"#;
+        let first: AirPodsBatteryInfo = serde_json::from_str(line).unwrap();
+
+        let mut second = first.clone();
+        second.rssi = Some(-72);
+        second.timestamp = Some(2);
+        assert!(first.same_readings(&second));
+
+        second.left_battery = 80;
+        assert!(!first.same_readings(&second));
+    }
+
+    #[test]
+    fn test_status_line() {
+        let line = r#"{"address":123,"canonical_address":"00000000007b","name":"AirPods Pro","model_id":8206,"left_battery":90,"left_charging":true,"right_battery":-1,"right_charging":false,"case_battery":70,"case_charging":false}"#;
+        let info: AirPodsBatteryInfo = serde_json::from_str(line).unwrap();
+        assert_eq!(
+            info.status_line(),
+            "AirPods Pro: L 90% (charging), R --, Case 70%"
+        );
+    }
 }
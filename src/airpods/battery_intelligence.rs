@@ -14,10 +14,21 @@
 
 use log;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
+use super::capacity::{Capacity, Health};
+use super::history_import::{read_import_file, ImportReport, ImportedReading};
+use super::history_log::{HistoryLog, HistoryRecord};
+use super::profile_snapshots::{
+    list_snapshots, restore_snapshot_files, take_snapshot, ProfileSnapshot, SnapshotReason,
+};
+use super::profile_writer::{write_atomic, ProfileWriter};
+use crate::config::canonical_address;
+use crate::quarantine::{quarantine, salvage, QuarantinedFile};
+use crate::system::disk_space;
+
 /// Maximum number of significant events to store
 const MAX_EVENTS: usize = 200;
 
@@ -42,6 +53,31 @@ const LOW_CONFIDENCE_THRESHOLD: u64 = 60;
 /// Rolling buffer size for depletion rate calculation
 const MAX_DEPLETION_SAMPLES: usize = 100;
 
+/// History log records written before it is compacted to the records still in use
+const COMPACT_HISTORY_AFTER: usize = 4 * (MAX_EVENTS + 3 * MAX_DEPLETION_SAMPLES);
+
+/// Number of recent system suspend periods to remember
+const MAX_SUSPEND_PERIODS: usize = 20;
+
+/// Number of completed usage sessions to keep in history
+const MAX_SESSION_HISTORY: usize = 500;
+
+/// Sessions shorter than this are treated as noise (earbud briefly taken out and back in)
+const MIN_SESSION_DURATION_SECS: u64 = 60;
+
+/// Estimates made longer than this before a reading aren't scored; by then nothing was
+/// showing them
+const MAX_EVALUATION_GAP: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Scored estimates needed before the accuracy is worth showing
+pub const MIN_ACCURACY_SAMPLES: u32 = 5;
+
+/// Months of charge-cycle history to keep for monthly reports
+const MAX_MONTHLY_DISCHARGE: usize = 24;
+
+/// Imported events this close to a known one with the same levels are the same event
+const DUPLICATE_EVENT_WINDOW: Duration = Duration::from_secs(60);
+
 /// Kalman filter parameters for battery state estimation
 const PROCESS_NOISE_VARIANCE: f32 = 0.01; // How much we expect the battery state to change unpredictably
 const MEASUREMENT_NOISE_VARIANCE: f32 = 1.0; // How noisy we expect the battery measurements to be
@@ -132,6 +168,18 @@ pub struct BatteryIntelligence {
     pub storage_dir: PathBuf,
     /// Fixed profile filename (no more renaming)
     profile_filename: String,
+    /// Writes saves in the background when set; otherwise they are written straight away
+    #[serde(skip)]
+    writer: Option<ProfileWriter>,
+    /// Records in the history log, to know when to compact it
+    #[serde(skip)]
+    history_records_on_disk: usize,
+    /// Where the complete records in the history log end, when a crash left a partial one
+    #[serde(skip)]
+    history_torn_at: Option<u64>,
+    /// Neither loads from nor saves to `storage_dir`, as in safe mode
+    #[serde(skip)]
+    read_only: bool,
 }
 
 /// Intelligent battery profile for a single device
@@ -156,7 +204,8 @@ pub struct DeviceBatteryProfile {
     pub left_in_ear: bool,
     pub right_in_ear: bool,
 
-    /// Significant events history (limited to MAX_EVENTS)
+    /// Significant events history (limited to MAX_EVENTS), stored in the history log
+    #[serde(default, skip_serializing)]
     pub events: VecDeque<BatteryEvent>,
 
     /// Learned discharge models for different usage patterns
@@ -168,13 +217,39 @@ pub struct DeviceBatteryProfile {
     /// Battery health metrics
     pub health_metrics: BatteryHealthMetrics,
 
-    /// NEW: Depletion rate buffer for the 1% precision prediction
+    /// NEW: Depletion rate buffer for the 1% precision prediction, stored in the history log
+    #[serde(default = "default_depletion_rates", skip_serializing)]
     pub depletion_rates: DepletionRateBuffer,
 
     /// Last recorded battery levels for depletion calculation
     pub last_left_level: Option<(u8, SystemTime)>,
     pub last_right_level: Option<(u8, SystemTime)>,
     pub last_case_level: Option<(u8, SystemTime)>,
+
+    /// Recent periods where the host system was suspended (sleep/hibernate)
+    #[serde(default)]
+    pub suspend_periods: VecDeque<SuspendPeriod>,
+
+    /// Completed listening sessions (limited to MAX_SESSION_HISTORY)
+    #[serde(default)]
+    pub session_history: VecDeque<CompletedSession>,
+
+    /// Events and samples recorded since the last save, waiting to go to the history log
+    #[serde(skip)]
+    unsaved_history: Vec<HistoryRecord>,
+}
+
+fn default_depletion_rates() -> DepletionRateBuffer {
+    DepletionRateBuffer::new(MAX_DEPLETION_SAMPLES)
+}
+
+/// A period where the host system was suspended and no readings could be taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuspendPeriod {
+    /// When the system went to sleep
+    pub start: SystemTime,
+    /// When the system resumed
+    pub end: SystemTime,
 }
 
 /// A significant battery event worth logging
@@ -280,6 +355,213 @@ pub struct UsageSession {
     pub usage_pattern: UsagePattern,
 }
 
+/// Summary of a finished listening session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletedSession {
+    /// When the earbuds went in
+    pub start_time: SystemTime,
+
+    /// When both earbuds were removed
+    pub end_time: SystemTime,
+
+    /// Battery consumed over the session (percentage points)
+    pub left_consumed: Option<u8>,
+    pub right_consumed: Option<u8>,
+
+    /// Usage intensity inferred from the drain rate
+    pub usage_pattern: UsagePattern,
+}
+
+impl CompletedSession {
+    /// Length of the session
+    pub fn duration(&self) -> Duration {
+        self.end_time
+            .duration_since(self.start_time)
+            .unwrap_or(Duration::ZERO)
+    }
+
+    /// Average drain across both earbuds in percent per hour
+    pub fn drain_per_hour(&self) -> Option<f32> {
+        let consumed: Vec<f32> = [self.left_consumed, self.right_consumed]
+            .iter()
+            .flatten()
+            .map(|&c| c as f32)
+            .collect();
+        let hours = self.duration().as_secs_f32() / 3600.0;
+        if consumed.is_empty() || hours <= 0.0 {
+            return None;
+        }
+        Some(consumed.iter().sum::<f32>() / consumed.len() as f32 / hours)
+    }
+}
+
+/// Listening time and drain statistics derived from the session history
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageSummary {
+    /// Total listening time since local midnight
+    pub today: Duration,
+
+    /// Total listening time over the last 7 days
+    pub this_week: Duration,
+
+    /// Number of sessions over the last 7 days
+    pub sessions_this_week: usize,
+
+    /// Average earbud drain in percent per hour over the last 7 days
+    pub average_drain_per_hour: Option<f32>,
+
+    /// Discharge counted towards charge cycles since tracking began
+    pub total_discharge: DischargeTotals,
+
+    /// Charge cycles and drain per calendar month, newest first
+    pub monthly: Vec<MonthlyReport>,
+}
+
+/// Discharge counted towards charge cycles, per component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DischargeTotals {
+    /// Percentage points discharged; every 100 is one cycle
+    pub left: u32,
+    pub right: u32,
+    pub case: u32,
+}
+
+impl DischargeTotals {
+    /// Add discharge to one component
+    fn add(&mut self, target: DepletionTarget, percent: u32) {
+        match target {
+            DepletionTarget::LeftEarbud => self.left += percent,
+            DepletionTarget::RightEarbud => self.right += percent,
+            DepletionTarget::Case => self.case += percent,
+        }
+    }
+
+    /// Charge cycles of the earbuds, averaged over both
+    pub fn earbud_cycles(&self) -> f32 {
+        (self.left + self.right) as f32 / 200.0
+    }
+
+    /// Charge cycles of the case
+    pub fn case_cycles(&self) -> f32 {
+        self.case as f32 / 100.0
+    }
+}
+
+/// How close estimates came to the readings that followed them, for one component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct AccuracyStats {
+    /// Estimates scored against a reading
+    pub samples: u32,
+    /// Sum of the absolute errors, percentage points
+    pub abs_error_sum: f64,
+    /// Sum of the signed errors (estimate minus reading), percentage points
+    pub error_sum: f64,
+    /// Sum of the absolute errors of simply showing the previous reading, for comparison
+    pub baseline_abs_error_sum: f64,
+}
+
+impl AccuracyStats {
+    /// Score an estimate against the reading that followed it
+    fn record(&mut self, estimate: f32, previous: u8, reading: u8) {
+        let error = f64::from(estimate) - f64::from(reading);
+        self.samples += 1;
+        self.abs_error_sum += error.abs();
+        self.error_sum += error;
+        self.baseline_abs_error_sum += (f64::from(previous) - f64::from(reading)).abs();
+    }
+
+    /// Stats of two components taken together
+    pub fn combined(&self, other: &AccuracyStats) -> AccuracyStats {
+        AccuracyStats {
+            samples: self.samples + other.samples,
+            abs_error_sum: self.abs_error_sum + other.abs_error_sum,
+            error_sum: self.error_sum + other.error_sum,
+            baseline_abs_error_sum: self.baseline_abs_error_sum + other.baseline_abs_error_sum,
+        }
+    }
+
+    /// Mean absolute error of the estimates, percentage points
+    pub fn mean_absolute_error(&self) -> Option<f32> {
+        self.mean(self.abs_error_sum)
+    }
+
+    /// Mean signed error: positive when estimates run high
+    pub fn bias(&self) -> Option<f32> {
+        self.mean(self.error_sum)
+    }
+
+    /// Mean absolute error of showing the previous reading instead of an estimate
+    pub fn baseline_mean_absolute_error(&self) -> Option<f32> {
+        self.mean(self.baseline_abs_error_sum)
+    }
+
+    fn mean(&self, sum: f64) -> Option<f32> {
+        (self.samples > 0).then(|| (sum / f64::from(self.samples)) as f32)
+    }
+}
+
+/// Accuracy of the battery estimates, per component
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct EstimationAccuracy {
+    pub left: AccuracyStats,
+    pub right: AccuracyStats,
+    pub case: AccuracyStats,
+}
+
+impl EstimationAccuracy {
+    /// Both earbuds taken together
+    pub fn earbuds(&self) -> AccuracyStats {
+        self.left.combined(&self.right)
+    }
+}
+
+/// Charge cycles and drain over one calendar month
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlyReport {
+    /// First day of the month
+    pub month: chrono::NaiveDate,
+
+    /// Discharge counted towards charge cycles during the month
+    pub discharge: DischargeTotals,
+
+    /// Average earbud drain of the sessions that ended in the month, percent per hour
+    pub average_drain_per_hour: Option<f32>,
+}
+
+impl MonthlyReport {
+    /// One line summary, e.g. "Your AirPods went through 14 cycles in May, average drain
+    /// 8.2%/hour"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "Your AirPods went through {} in {}",
+            format_cycles(self.discharge.earbud_cycles()),
+            self.month.format("%B")
+        );
+        if let Some(rate) = self.average_drain_per_hour {
+            summary.push_str(&format!(", average drain {:.1}%/hour", rate));
+        }
+        summary
+    }
+}
+
+/// Cycle counts as people say them: whole numbers once there are a few
+pub fn format_cycles(cycles: f32) -> String {
+    if cycles >= 10.0 {
+        format!("{:.0} cycles", cycles)
+    } else if (cycles - 1.0).abs() < 0.05 {
+        "1 cycle".to_string()
+    } else {
+        format!("{:.1} cycles", cycles)
+    }
+}
+
+/// Key of the month `time` falls in, e.g. "2026-05"
+fn month_key(time: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time)
+        .format("%Y-%m")
+        .to_string()
+}
+
 /// Session type classification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum SessionType {
@@ -308,8 +590,76 @@ pub struct BatteryHealthMetrics {
     /// Total usage cycles approximation
     pub estimated_cycles: u32,
 
-    /// Health score (0.0 to 1.0)
+    /// Health score (0.0 to 1.0), the earbuds' share of capacity left after their cycles
     pub health_score: f32,
+
+    /// Discharge counted towards charge cycles since tracking began
+    #[serde(default)]
+    pub total_discharge: DischargeTotals,
+
+    /// Discharge counted towards charge cycles per calendar month, keyed "2026-05"
+    #[serde(default)]
+    pub monthly_discharge: BTreeMap<String, DischargeTotals>,
+
+    /// Month the last monthly report notification was for
+    #[serde(default)]
+    pub last_reported_month: Option<String>,
+
+    /// How close estimates came to the readings that followed them
+    #[serde(default)]
+    pub estimation_accuracy: EstimationAccuracy,
+
+    /// Previous reading of each component (left, right, case), the baseline for the
+    /// next discharge
+    #[serde(skip)]
+    cycle_baseline: [Option<u8>; 3],
+}
+
+impl BatteryHealthMetrics {
+    /// Count the discharge since the previous reading of a component towards its cycles
+    ///
+    /// Charging only moves the baseline. A reading of 0% is how the scanner reports a
+    /// component it can't see, so it neither counts nor moves the baseline.
+    fn record_discharge(
+        &mut self,
+        target: DepletionTarget,
+        level: Option<u8>,
+        charging: bool,
+        now: SystemTime,
+    ) {
+        let slot = match target {
+            DepletionTarget::LeftEarbud => 0,
+            DepletionTarget::RightEarbud => 1,
+            DepletionTarget::Case => 2,
+        };
+        let Some(level) = level.filter(|&level| level > 0) else {
+            return;
+        };
+        let previous = self.cycle_baseline[slot].replace(level);
+        let Some(previous) = previous.filter(|&previous| !charging && level < previous) else {
+            return;
+        };
+
+        let discharged = u32::from(previous - level);
+        self.total_discharge.add(target, discharged);
+        self.monthly_discharge
+            .entry(month_key(now))
+            .or_default()
+            .add(target, discharged);
+        while self.monthly_discharge.len() > MAX_MONTHLY_DISCHARGE {
+            self.monthly_discharge.pop_first();
+        }
+        self.estimated_cycles = self.total_discharge.earbud_cycles() as u32;
+        self.health_score = self.capacity_health().earbuds;
+    }
+
+    /// Share of their nominal capacity the batteries still hold after the cycles counted
+    pub fn capacity_health(&self) -> Health {
+        Health::after_cycles(
+            self.total_discharge.earbud_cycles(),
+            self.total_discharge.case_cycles(),
+        )
+    }
 }
 
 /// Global intelligence settings
@@ -355,13 +705,23 @@ pub struct BatteryEstimate {
 
 impl BatteryIntelligence {
     /// Create a new BatteryIntelligence system with the specified storage directory
+    ///
+    /// In safe mode the files in `storage_dir` are left alone: it starts empty and
+    /// keeps what it learns in memory.
     pub fn new(storage_dir: PathBuf) -> Self {
         let mut intelligence = Self {
             device_profile: None,
             settings: IntelligenceSettings::default(),
             storage_dir,
             profile_filename: "battery_profile.json".to_string(),
+            writer: None,
+            history_records_on_disk: 0,
+            history_torn_at: None,
+            read_only: crate::safe_mode::is_enabled(),
         };
+        if intelligence.read_only {
+            return intelligence;
+        }
 
         // Load existing profiles
         if let Err(e) = intelligence.load() {
@@ -384,6 +744,24 @@ impl BatteryIntelligence {
         intelligence
     }
 
+    /// Create a BatteryIntelligence that keeps everything in memory
+    ///
+    /// Used when the data folder couldn't be read in time: nothing is loaded, so saving
+    /// would overwrite what was learned before with an empty profile. It has no storage
+    /// directory and never writes, like safe mode.
+    pub fn in_memory() -> Self {
+        Self {
+            device_profile: None,
+            settings: IntelligenceSettings::default(),
+            storage_dir: PathBuf::new(),
+            profile_filename: "battery_profile.json".to_string(),
+            writer: None,
+            history_records_on_disk: 0,
+            history_torn_at: None,
+            read_only: true,
+        }
+    }
+
     /// Clean up old profile files created with decimal addresses or test data
     fn cleanup_old_profile_files(&self) -> Result<(), Box<dyn std::error::Error>> {
         if !self.storage_dir.exists() {
@@ -445,9 +823,11 @@ impl BatteryIntelligence {
         Ok(())
     }
 
-    /// Consolidate old battery profile files into the new intelligent system
+    /// Import the per-device logs older versions wrote to `logs/battery`
+    ///
+    /// Events imported on an earlier launch are recognized as duplicates, so the logs
+    /// only add to the history once.
     fn consolidate_old_battery_data(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // Look for old battery profile files in the logs/battery directory
         let old_battery_dir = self
             .storage_dir
             .parent()
@@ -459,89 +839,184 @@ impl BatteryIntelligence {
             return Ok(()); // No old data to consolidate
         }
 
-        println!(
-            "Consolidating old battery profile data from {:?}",
-            old_battery_dir
-        );
-
-        // Read all old battery profile files
+        let mut files = Vec::new();
         for entry in std::fs::read_dir(&old_battery_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
+            let path = entry?.path();
             if path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Err(e) = self.consolidate_single_file(&path) {
-                    eprintln!("Warning: Failed to consolidate {}: {}", path.display(), e);
-                }
+                files.push(path);
             }
         }
 
-        println!("Battery data consolidation completed");
+        let report = self.import_history(&files);
+        for (path, e) in &report.failed {
+            eprintln!("Warning: Failed to consolidate {}: {}", path.display(), e);
+        }
+        if report.events_added > 0 {
+            log::info!("Consolidated old battery data: {}", report);
+        }
         Ok(())
     }
 
-    /// Consolidate a single old battery profile file
-    fn consolidate_single_file(
-        &mut self,
-        file_path: &Path,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let content = std::fs::read_to_string(file_path)?;
-        let old_data: serde_json::Value = serde_json::from_str(&content)?;
+    /// Import battery readings from scanner captures and legacy logs
+    ///
+    /// The readings are replayed in time order through the same significance filter as
+    /// live updates. Events the profile already has are skipped, so importing a file
+    /// twice adds nothing. Afterwards the discharge models are rebuilt from the merged
+    /// history and the history log and profile are rewritten.
+    pub fn import_history(&mut self, paths: &[PathBuf]) -> ImportReport {
+        let mut report = ImportReport::default();
+        let mut readings = Vec::new();
+        for path in paths {
+            match read_import_file(path) {
+                Ok(found) => {
+                    report.files_read += 1;
+                    readings.extend(found);
+                }
+                Err(e) => report.failed.push((path.clone(), e)),
+            }
+        }
+        report.readings = readings.len();
 
-        // Extract device ID from filename (e.g., battery_profile_85524103014148_20250616_192253.json)
-        let filename = file_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .ok_or("Invalid filename")?;
+        let (added, duplicates, other_devices) = self.import_readings(readings);
+        report.events_added = added;
+        report.duplicates = duplicates;
+        report.other_devices = other_devices;
 
-        let device_id = if let Some(parts) = filename.split('_').nth(2) {
-            parts.to_string()
-        } else {
-            return Err("Could not extract device ID from filename".into());
-        };
+        if added > 0 {
+            self.snapshot(SnapshotReason::Import);
+            if let Err(e) = self.rewrite_history() {
+                log::warn!("Failed to save imported battery history: {}", e);
+            }
+        }
+        report
+    }
 
-        // Parse old entries and convert to significant events
-        if let Some(entries) = old_data.get("entries").and_then(|e| e.as_array()) {
-            for entry in entries {
-                if let (Some(_timestamp), Some(left), Some(right), Some(case)) = (
-                    entry.get("timestamp").and_then(|t| t.as_str()),
-                    entry.get("left_battery").and_then(|l| l.as_i64()),
-                    entry.get("right_battery").and_then(|r| r.as_i64()),
-                    entry.get("case_battery").and_then(|c| c.as_i64()),
-                ) {
-                    // Convert old entry to new system (only if it represents a significant change)
-                    self.update_device_battery(
-                        &device_id,
-                        &format!("AirPods {}", &device_id[..8]), // Shortened device name
-                        if left >= 0 { Some(left as u8) } else { None },
-                        if right >= 0 { Some(right as u8) } else { None },
-                        if case >= 0 { Some(case as u8) } else { None },
-                        entry
-                            .get("left_charging")
-                            .and_then(|c| c.as_bool())
-                            .unwrap_or(false),
-                        entry
-                            .get("right_charging")
-                            .and_then(|c| c.as_bool())
-                            .unwrap_or(false),
-                        entry
-                            .get("case_charging")
-                            .and_then(|c| c.as_bool())
-                            .unwrap_or(false),
-                        entry
-                            .get("left_in_ear")
-                            .and_then(|e| e.as_bool())
-                            .unwrap_or(false),
-                        entry
-                            .get("right_in_ear")
-                            .and_then(|e| e.as_bool())
-                            .unwrap_or(false),
-                        Some(entry.get("rssi").and_then(|r| r.as_i64()).unwrap_or(-50) as i16),
-                    );
-                }
+    /// Merge readings into the profile's events
+    ///
+    /// Only readings of one device are used: the profile's if they include it, otherwise
+    /// the device with the most readings. Returns the events added, the duplicates
+    /// skipped and the readings of other devices.
+    fn import_readings(&mut self, mut readings: Vec<ImportedReading>) -> (usize, usize, usize) {
+        readings.sort_by_key(|reading| reading.timestamp);
+        readings.dedup_by(|a, b| {
+            a.timestamp == b.timestamp && a.address == b.address && a.same_state(b)
+        });
+
+        let device = self.import_device(&readings);
+        let (readings, others): (Vec<_>, Vec<_>) = readings
+            .into_iter()
+            .partition(|reading| reading.address == device);
+        let Some(first) = readings.first() else {
+            return (0, 0, others.len());
+        };
+        if self.device_profile.is_none() {
+            let address = first.address.as_deref().unwrap_or_default();
+            self.device_profile = Some(DeviceBatteryProfile::new(&first.name, address));
+        }
+
+        // Replay the readings on a scratch profile, as if they arrived live
+        let mut replay = DeviceBatteryProfile::new(&first.name, "");
+        let mut events = Vec::new();
+        for reading in &readings {
+            let significant = self.is_significant_update(
+                &replay,
+                reading.left,
+                reading.right,
+                reading.case,
+                reading.left_charging,
+                reading.right_charging,
+                reading.case_charging,
+                reading.left_in_ear,
+                reading.right_in_ear,
+                reading.timestamp,
+            );
+            if significant {
+                events.push(BatteryEvent {
+                    timestamp: reading.timestamp,
+                    event_type: Self::classify_event_type_from_data(
+                        &replay,
+                        reading.left,
+                        reading.right,
+                        reading.case,
+                        reading.left_charging,
+                        reading.right_charging,
+                        reading.case_charging,
+                        reading.left_in_ear,
+                        reading.right_in_ear,
+                        reading.timestamp,
+                    ),
+                    left_battery: reading.left,
+                    right_battery: reading.right,
+                    case_battery: reading.case,
+                    left_charging: reading.left_charging,
+                    right_charging: reading.right_charging,
+                    case_charging: reading.case_charging,
+                    left_in_ear: reading.left_in_ear,
+                    right_in_ear: reading.right_in_ear,
+                    rssi: reading.rssi,
+                    session_duration: None,
+                });
             }
+            replay.current_left = reading.left.or(replay.current_left);
+            replay.current_right = reading.right.or(replay.current_right);
+            replay.current_case = reading.case.or(replay.current_case);
+            replay.left_charging = reading.left_charging;
+            replay.right_charging = reading.right_charging;
+            replay.case_charging = reading.case_charging;
+            replay.left_in_ear = reading.left_in_ear;
+            replay.right_in_ear = reading.right_in_ear;
+            replay.last_update = Some(reading.timestamp);
         }
 
+        let profile = self.device_profile.as_mut().unwrap();
+        let (added, duplicates) = profile.merge_events(events);
+        if added > 0 {
+            profile.discharge_models.clear();
+            profile.update_models();
+        }
+        (added, duplicates, others.len())
+    }
+
+    /// Address of the device whose readings to import; `None` for readings without one
+    fn import_device(&self, readings: &[ImportedReading]) -> Option<String> {
+        let profile_address = self
+            .device_profile
+            .as_ref()
+            .and_then(|profile| canonical_address(&profile.device_address));
+        if profile_address.is_some()
+            && readings
+                .iter()
+                .any(|reading| reading.address == profile_address)
+        {
+            return profile_address;
+        }
+
+        let mut counts: BTreeMap<&Option<String>, usize> = BTreeMap::new();
+        for reading in readings {
+            *counts.entry(&reading.address).or_default() += 1;
+        }
+        counts
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .and_then(|(address, _)| address.clone())
+    }
+
+    /// Rewrite the history log and profile after the events changed out of order
+    fn rewrite_history(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(profile) = self.device_profile.as_mut() else {
+            return Ok(());
+        };
+        std::fs::create_dir_all(&self.storage_dir)?;
+        let records = profile.history_records();
+        HistoryLog::in_dir(&self.storage_dir).rewrite(&records)?;
+        self.history_records_on_disk = records.len();
+        self.history_torn_at = None;
+        // The rewrite holds everything that was waiting to be appended
+        profile.unsaved_history.clear();
+
+        if let Some(profile) = self.device_profile.as_ref() {
+            self.save_device_profile(profile)?;
+        }
         Ok(())
     }
 
@@ -641,6 +1116,7 @@ impl BatteryIntelligence {
                 case_charging,
                 left_in_ear,
                 right_in_ear,
+                SystemTime::now(),
             )
         };
 
@@ -658,6 +1134,7 @@ impl BatteryIntelligence {
                 case_charging,
                 left_in_ear,
                 right_in_ear,
+                SystemTime::now(),
             );
 
             let event = BatteryEvent {
@@ -744,9 +1221,8 @@ impl BatteryIntelligence {
         case_charging: bool,
         left_in_ear: bool,
         right_in_ear: bool,
+        now: SystemTime,
     ) -> bool {
-        let now = SystemTime::now();
-
         // Always log first update
         if profile.last_update.is_none() {
             return true;
@@ -837,6 +1313,7 @@ impl BatteryIntelligence {
         case_charging: bool,
         left_in_ear: bool,
         right_in_ear: bool,
+        now: SystemTime,
     ) -> BatteryEventType {
         // Check for charging state changes
         if (left_charging && !profile.left_charging)
@@ -869,9 +1346,7 @@ impl BatteryIntelligence {
 
         // Check for reconnection after gap
         if let Some(last_update) = profile.last_update {
-            let time_since = SystemTime::now()
-                .duration_since(last_update)
-                .unwrap_or(Duration::ZERO);
+            let time_since = now.duration_since(last_update).unwrap_or(Duration::ZERO);
             if time_since >= Duration::from_secs(300) {
                 // 5 minutes
                 return BatteryEventType::ReconnectedAfterGap;
@@ -946,12 +1421,68 @@ impl BatteryIntelligence {
         }
     }
 
+    /// Get listening statistics for the current device
+    pub fn get_usage_summary(&self) -> Option<UsageSummary> {
+        self.device_profile
+            .as_ref()
+            .map(|profile| profile.usage_summary(chrono::Local::now()))
+    }
+
+    /// How close estimates have come to the readings that followed them
+    pub fn get_estimation_accuracy(&self) -> Option<EstimationAccuracy> {
+        self.device_profile
+            .as_ref()
+            .map(|profile| profile.health_metrics.estimation_accuracy)
+    }
+
+    /// Report on the last month with charge-cycle data once it is over
+    ///
+    /// Returns each month's report once, for the monthly notification.
+    pub fn take_monthly_report(&mut self) -> Option<MonthlyReport> {
+        self.device_profile
+            .as_mut()
+            .and_then(|profile| profile.take_monthly_report(SystemTime::now()))
+    }
+
+    /// Record a system suspend so depletion samples spanning it are discarded
+    pub fn record_system_suspend(&mut self, start: SystemTime, end: SystemTime) {
+        if let Some(profile) = self.device_profile.as_mut() {
+            profile.record_suspend_period(start, end);
+            log::info!(
+                "Recorded system suspend of {}s for battery intelligence",
+                end.duration_since(start)
+                    .unwrap_or(Duration::ZERO)
+                    .as_secs()
+            );
+        }
+    }
+
+    /// Hand saves to a background writer instead of writing on the caller's thread
+    ///
+    /// Call [`Self::flush`] before exiting so the last save isn't lost.
+    pub fn enable_background_saving(&mut self) {
+        if self.writer.is_none() {
+            self.writer = Some(ProfileWriter::spawn());
+        }
+    }
+
+    /// Wait for queued saves to reach the disk
+    pub fn flush(&self) {
+        if let Some(writer) = &self.writer {
+            writer.flush();
+        }
+    }
+
     /// Save all device profiles to disk
-    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         if self.device_profile.is_none() {
             return Ok(());
         }
 
+        if let Err(e) = self.save_history() {
+            log::warn!("Failed to write battery history: {}", e);
+        }
+
         // Save the single device profile
         if let Some(profile) = self.device_profile.as_ref() {
             if let Err(e) = self.save_device_profile(profile) {
@@ -961,9 +1492,75 @@ impl BatteryIntelligence {
         Ok(())
     }
 
+    /// Write the device profile to `path`, e.g. to share it or move it to another computer
+    ///
+    /// Unlike the saved profile, the backup holds the events and samples inline, so it is
+    /// a single self-contained file. With the model's `capacity`, the nominal capacity and
+    /// the charge left at the current levels and learned health are included in mAh;
+    /// importing ignores them.
+    pub fn export_profile(
+        &self,
+        path: &Path,
+        capacity: Option<Capacity>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let profile = self
+            .device_profile
+            .as_ref()
+            .ok_or("No battery profile has been recorded yet")?;
+        let mut backup = serde_json::to_value(profile)?;
+        backup["events"] = serde_json::to_value(&profile.events)?;
+        backup["depletion_rates"] = serde_json::to_value(&profile.depletion_rates)?;
+        if let Some(capacity) = capacity {
+            let remaining = capacity.estimate(
+                profile.health_metrics.capacity_health(),
+                profile.current_left,
+                profile.current_right,
+                profile.current_case,
+            );
+            backup["capacity_mah"] = serde_json::to_value(capacity)?;
+            backup["remaining_mah"] = serde_json::to_value(remaining)?;
+        }
+        let json = serde_json::to_string_pretty(&backup)?;
+        write_atomic(path, crate::privacy::redact(&json).as_bytes())?;
+        Ok(())
+    }
+
+    /// Replace the device profile with a backup written by [`Self::export_profile`]
+    ///
+    /// Fails rather than waiting for room when the drive is low on space.
+    pub fn import_profile(&mut self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        let profile: DeviceBatteryProfile = serde_json::from_str(&json)?;
+        if self.read_only {
+            self.device_profile = Some(profile);
+            return Ok(());
+        }
+        disk_space::ensure_room(&self.storage_dir)?;
+
+        self.snapshot(SnapshotReason::Import);
+
+        std::fs::create_dir_all(&self.storage_dir)?;
+        let records = profile.history_records();
+        HistoryLog::in_dir(&self.storage_dir).rewrite(&records)?;
+        self.history_records_on_disk = records.len();
+        self.history_torn_at = None;
+        self.save_device_profile(&profile)?;
+        self.device_profile = Some(profile);
+        Ok(())
+    }
+
     /// Purge all battery intelligence profiles (reset all data)
     pub fn purge_all_profiles(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.device_profile = None;
+        if self.read_only {
+            return Ok(());
+        }
+        // A queued save would bring the profile back after it is removed
+        self.flush();
+        self.snapshot(SnapshotReason::Purge);
+        HistoryLog::in_dir(&self.storage_dir).remove()?;
+        self.history_records_on_disk = 0;
+        self.history_torn_at = None;
 
         // Remove all profile files from disk
         if self.storage_dir.exists() {
@@ -989,8 +1586,75 @@ impl BatteryIntelligence {
         Ok(())
     }
 
+    /// Snapshots of the battery data, newest first
+    pub fn snapshots(&self) -> Vec<ProfileSnapshot> {
+        if self.read_only {
+            return Vec::new();
+        }
+        list_snapshots(&self.storage_dir)
+    }
+
+    /// Put the battery data back the way it was when `snapshot` was taken
+    ///
+    /// The current data is snapshotted first, so a restore can be undone too.
+    pub fn restore_snapshot(
+        &mut self,
+        snapshot: &ProfileSnapshot,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.read_only {
+            return Err("Battery data isn't being saved this session".into());
+        }
+        disk_space::ensure_room(&self.storage_dir)?;
+        self.snapshot(SnapshotReason::Rollback);
+        restore_snapshot_files(&self.storage_dir, snapshot)?;
+
+        self.device_profile = None;
+        self.history_records_on_disk = 0;
+        self.history_torn_at = None;
+        self.load()?;
+        log::info!(
+            "Restored battery data from snapshot {}",
+            snapshot.path.display()
+        );
+        Ok(())
+    }
+
+    /// Put back a profile that was quarantined because it couldn't be read
+    ///
+    /// The salvaged profile replaces the current one, which is snapshotted first; the
+    /// history log wasn't quarantined, so the history is kept.
+    pub fn recover_quarantined(
+        &mut self,
+        file: &QuarantinedFile,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let profile: DeviceBatteryProfile = salvage(file)?;
+        disk_space::ensure_room(&self.storage_dir)?;
+        self.snapshot(SnapshotReason::Import);
+        self.save_device_profile(&profile)?;
+        self.flush();
+
+        self.device_profile = None;
+        self.history_records_on_disk = 0;
+        self.history_torn_at = None;
+        self.load()?;
+        Ok(())
+    }
+
+    /// Where the device profile is saved
+    pub fn profile_path(&self) -> PathBuf {
+        self.storage_dir.join(&self.profile_filename)
+    }
+
+    /// Snapshot the battery data before it is rewritten, see [`take_snapshot`]
+    fn snapshot(&self, reason: SnapshotReason) {
+        snapshot_files(&self.storage_dir, self.writer.as_ref(), reason);
+    }
+
     /// Load device profile from disk (singleton version - fixed filename)
     pub fn load(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.read_only {
+            return Ok(());
+        }
         let file_path = self.storage_dir.join(&self.profile_filename);
 
         if file_path.exists() {
@@ -1000,6 +1664,12 @@ impl BatteryIntelligence {
                     file_path.display(),
                     e
                 );
+                // Keep a profile that doesn't parse rather than saving over it
+                if e.is::<serde_json::Error>() {
+                    if let Err(e) = quarantine(&file_path, &e.to_string()) {
+                        log::error!("Failed to quarantine {}: {}", file_path.display(), e);
+                    }
+                }
             }
         } else {
             // Migration: Look for old profile files and migrate first one found
@@ -1019,6 +1689,7 @@ impl BatteryIntelligence {
                                     "Migrating old profile file {} to singleton format",
                                     filename
                                 );
+                                self.snapshot(SnapshotReason::Migration);
                                 if self.load_device_profile(&path).is_ok() {
                                     // Save using new format
                                     if let Some(profile) = self.device_profile.as_ref() {
@@ -1041,16 +1712,97 @@ impl BatteryIntelligence {
     /// Load a single device profile from disk
     fn load_device_profile(&mut self, file_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
         let json = std::fs::read_to_string(file_path)?;
-        let profile: DeviceBatteryProfile = serde_json::from_str(&json)?;
+        let mut profile: DeviceBatteryProfile = serde_json::from_str(&json)?;
+
+        let log = HistoryLog::in_dir(&self.storage_dir);
+        let mut migrated = false;
+        if log.exists() {
+            let (records, torn_at) = log.read_frames()?;
+            self.history_records_on_disk = records.len();
+            self.history_torn_at = torn_at;
+            profile.restore_history(records);
+        } else {
+            // Profiles saved before the history log carry their history inline
+            let records = profile.history_records();
+            if !records.is_empty() {
+                self.snapshot(SnapshotReason::Migration);
+                log.rewrite(&records)?;
+                log::info!(
+                    "Moved {} history records from the profile to {}",
+                    records.len(),
+                    log.path().display()
+                );
+                self.history_records_on_disk = records.len();
+                self.history_torn_at = None;
+                migrated = true;
+            }
+        }
+
         self.device_profile = Some(profile);
+        if migrated {
+            // Rewrite the profile without the history it used to hold
+            if let Some(profile) = self.device_profile.as_ref() {
+                self.save_device_profile(profile)?;
+            }
+        }
         Ok(())
     }
 
+    /// Append new events and samples to the history log, compacting it when it has grown
+    fn save_history(&mut self) -> std::io::Result<()> {
+        let Some(profile) = self.device_profile.as_mut() else {
+            return Ok(());
+        };
+        let unsaved = profile.take_unsaved_history();
+        if unsaved.is_empty() || self.read_only {
+            return Ok(());
+        }
+        if !disk_space::writes_allowed(&self.storage_dir) {
+            // Keep it in memory until there is room again
+            profile.unsaved_history = unsaved;
+            return Ok(());
+        }
+
+        std::fs::create_dir_all(&self.storage_dir)?;
+        let log = HistoryLog::in_dir(&self.storage_dir);
+        let result = if self.history_records_on_disk + unsaved.len() > COMPACT_HISTORY_AFTER {
+            snapshot_files(
+                &self.storage_dir,
+                self.writer.as_ref(),
+                SnapshotReason::Compaction,
+            );
+            let records = profile.history_records();
+            log.rewrite(&records)
+                .map(|()| self.history_records_on_disk = records.len())
+        } else {
+            // A frame cut short by a crash would swallow whatever follows it
+            let truncated = match self.history_torn_at {
+                Some(length) => log.truncate(length),
+                None => Ok(()),
+            };
+            truncated
+                .and_then(|()| log.append(&unsaved))
+                .map(|()| self.history_records_on_disk += unsaved.len())
+        };
+        match &result {
+            Ok(()) => self.history_torn_at = None,
+            // Try again with the next save
+            Err(_) => profile.unsaved_history = unsaved,
+        }
+        result
+    }
+
     /// Save a device profile to disk (singleton version - fixed filename)
     fn save_device_profile(
         &self,
         profile: &DeviceBatteryProfile,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        // The profile in memory is saved with the next change once there is room again;
+        // writes the user asked for check for room first, see `disk_space::ensure_room`
+        if self.read_only || !disk_space::writes_allowed(&self.storage_dir) {
+            return Ok(());
+        }
+
         // Ensure storage directory exists
         std::fs::create_dir_all(&self.storage_dir)?;
 
@@ -1058,11 +1810,33 @@ impl BatteryIntelligence {
         let file_path = self.storage_dir.join(&self.profile_filename);
 
         let json = serde_json::to_string_pretty(profile)?;
-        std::fs::write(file_path, json)?;
+        match &self.writer {
+            Some(writer) => writer.save(file_path, json.into_bytes())?,
+            None => write_atomic(&file_path, json.as_bytes())?,
+        }
         Ok(())
     }
 }
 
+/// Snapshot the files in `storage_dir` once `writer` has saved what it has queued
+///
+/// A failed snapshot is logged rather than stopping the rewrite it precedes.
+fn snapshot_files(storage_dir: &Path, writer: Option<&ProfileWriter>, reason: SnapshotReason) {
+    if !disk_space::writes_allowed(storage_dir) {
+        log::warn!(
+            "Skipped battery data snapshot {}: low on disk space",
+            reason
+        );
+        return;
+    }
+    if let Some(writer) = writer {
+        writer.flush();
+    }
+    if let Err(e) = take_snapshot(storage_dir, reason) {
+        log::warn!("Failed to snapshot battery data {}: {}", reason, e);
+    }
+}
+
 impl DeviceBatteryProfile {
     /// Create new device profile
     pub fn new(device_name: &str, device_address: &str) -> Self {
@@ -1086,11 +1860,227 @@ impl DeviceBatteryProfile {
             last_left_level: None,
             last_right_level: None,
             last_case_level: None,
+            suspend_periods: VecDeque::with_capacity(MAX_SUSPEND_PERIODS),
+            session_history: VecDeque::new(),
+            unsaved_history: Vec::new(),
         }
     }
 
+    /// Close the active session and record it in the session history
+    fn finish_session(&mut self, end_time: SystemTime, left: Option<u8>, right: Option<u8>) {
+        let Some(session) = self.current_session.take() else {
+            return;
+        };
+
+        let duration = end_time
+            .duration_since(session.start_time)
+            .unwrap_or(Duration::ZERO);
+        if duration.as_secs() < MIN_SESSION_DURATION_SECS {
+            return;
+        }
+
+        let consumed = |start: Option<u8>, end: Option<u8>| match (start, end) {
+            (Some(start), Some(end)) => Some(start.saturating_sub(end)),
+            _ => None,
+        };
+
+        let mut completed = CompletedSession {
+            start_time: session.start_time,
+            end_time,
+            left_consumed: consumed(session.start_left, left),
+            right_consumed: consumed(session.start_right, right),
+            usage_pattern: UsagePattern::Light,
+        };
+        completed.usage_pattern = match completed.drain_per_hour() {
+            Some(rate) if rate >= 25.0 => UsagePattern::Extreme,
+            Some(rate) if rate >= 15.0 => UsagePattern::Heavy,
+            Some(rate) if rate >= 8.0 => UsagePattern::Moderate,
+            _ => UsagePattern::Light,
+        };
+
+        log::debug!(
+            "Usage session finished: {} min, L-{:?}% R-{:?}%, {:?}",
+            duration.as_secs() / 60,
+            completed.left_consumed,
+            completed.right_consumed,
+            completed.usage_pattern
+        );
+
+        self.session_history.push_back(completed);
+        while self.session_history.len() > MAX_SESSION_HISTORY {
+            self.session_history.pop_front();
+        }
+    }
+
+    /// Summarize listening time relative to `now`
+    pub fn usage_summary(&self, now: chrono::DateTime<chrono::Local>) -> UsageSummary {
+        let today_start: SystemTime = now
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(chrono::Local).earliest())
+            .map(SystemTime::from)
+            .unwrap_or_else(|| SystemTime::from(now));
+        let week_start = SystemTime::from(now) - Duration::from_secs(7 * 24 * 60 * 60);
+
+        let mut summary = UsageSummary::default();
+        let mut drain_rates = Vec::new();
+
+        for session in &self.session_history {
+            if session.end_time < week_start {
+                continue;
+            }
+            summary.this_week += session.duration();
+            summary.sessions_this_week += 1;
+            if session.end_time >= today_start {
+                summary.today += session.duration();
+            }
+            if let Some(rate) = session.drain_per_hour() {
+                drain_rates.push(rate);
+            }
+        }
+
+        if !drain_rates.is_empty() {
+            summary.average_drain_per_hour =
+                Some(drain_rates.iter().sum::<f32>() / drain_rates.len() as f32);
+        }
+
+        summary.total_discharge = self.health_metrics.total_discharge;
+        summary.monthly = self.monthly_reports();
+        summary
+    }
+
+    /// Charge cycles and drain per calendar month, newest first
+    pub fn monthly_reports(&self) -> Vec<MonthlyReport> {
+        self.health_metrics
+            .monthly_discharge
+            .iter()
+            .rev()
+            .filter_map(|(key, discharge)| {
+                let month =
+                    chrono::NaiveDate::parse_from_str(&format!("{}-01", key), "%Y-%m-%d").ok()?;
+                let rates: Vec<f32> = self
+                    .session_history
+                    .iter()
+                    .filter(|session| month_key(session.end_time) == *key)
+                    .filter_map(CompletedSession::drain_per_hour)
+                    .collect();
+                Some(MonthlyReport {
+                    month,
+                    discharge: *discharge,
+                    average_drain_per_hour: (!rates.is_empty())
+                        .then(|| rates.iter().sum::<f32>() / rates.len() as f32),
+                })
+            })
+            .collect()
+    }
+
+    /// The report for the latest finished month with data, unless it was already taken
+    pub fn take_monthly_report(&mut self, now: SystemTime) -> Option<MonthlyReport> {
+        let current = month_key(now);
+        let finished = self
+            .health_metrics
+            .monthly_discharge
+            .range(..current)
+            .next_back()
+            .map(|(key, _)| key.clone())?;
+        if self
+            .health_metrics
+            .last_reported_month
+            .as_ref()
+            .is_some_and(|reported| *reported >= finished)
+        {
+            return None;
+        }
+
+        self.health_metrics.last_reported_month = Some(finished.clone());
+        let month =
+            chrono::NaiveDate::parse_from_str(&format!("{}-01", finished), "%Y-%m-%d").ok()?;
+        self.monthly_reports()
+            .into_iter()
+            .find(|report| report.month == month)
+    }
+
+    /// Score what the estimator would have shown against a fresh reading
+    ///
+    /// Runs before the reading is applied. Nothing is scored when the previous reading is
+    /// still recent enough to be shown as is, when the gap is long or spans a suspend, or
+    /// when the component started or stopped charging in between.
+    fn evaluate_estimates(&mut self, readings: [(Option<u8>, bool); 3], now: SystemTime) {
+        let Some(last_update) = self.last_update else {
+            return;
+        };
+        let gap = now.duration_since(last_update).unwrap_or(Duration::ZERO);
+        if gap > MAX_EVALUATION_GAP || self.spans_suspend(last_update, now) {
+            return;
+        }
+
+        let estimates = [
+            (
+                self.estimate_left_battery(),
+                self.current_left,
+                self.left_charging,
+            ),
+            (
+                self.estimate_right_battery(),
+                self.current_right,
+                self.right_charging,
+            ),
+            (
+                self.estimate_case_battery(),
+                self.current_case,
+                self.case_charging,
+            ),
+        ];
+        let accuracy = &mut self.health_metrics.estimation_accuracy;
+        let stats = [&mut accuracy.left, &mut accuracy.right, &mut accuracy.case];
+
+        for (((estimate, previous, was_charging), (reading, charging)), stats) in
+            estimates.into_iter().zip(readings).zip(stats)
+        {
+            // 0% is how an unseen component is reported
+            let (Some(previous), Some(reading)) = (
+                previous.filter(|&level| level > 0),
+                reading.filter(|&level| level > 0),
+            ) else {
+                continue;
+            };
+            if estimate.is_real_data || was_charging != charging {
+                continue;
+            }
+            stats.record(estimate.level, previous, reading);
+        }
+    }
+
+    /// Record a period where the host system was suspended
+    pub fn record_suspend_period(&mut self, start: SystemTime, end: SystemTime) {
+        if end <= start {
+            return;
+        }
+
+        self.suspend_periods.push_back(SuspendPeriod { start, end });
+        while self.suspend_periods.len() > MAX_SUSPEND_PERIODS {
+            self.suspend_periods.pop_front();
+        }
+    }
+
+    /// Check whether the interval between two readings overlaps a system suspend
+    ///
+    /// Depletion samples spanning a suspend are unreliable: the earbuds may have been
+    /// used, charged or left idle while nothing was being observed.
+    pub fn spans_suspend(&self, from: SystemTime, to: SystemTime) -> bool {
+        self.suspend_periods
+            .iter()
+            .any(|period| period.start < to && period.end > from)
+    }
+
     /// Add a significant event to history
     pub fn add_event(&mut self, event: BatteryEvent) {
+        self.unsaved_history
+            .push(HistoryRecord::Event(event.clone()));
+        self.push_event(event);
+    }
+
+    fn push_event(&mut self, event: BatteryEvent) {
         self.events.push_back(event);
 
         // Limit history size
@@ -1099,6 +2089,82 @@ impl DeviceBatteryProfile {
         }
     }
 
+    /// Merge imported events into the history in time order
+    ///
+    /// An event is a duplicate when the history has one with the same levels within
+    /// [`DUPLICATE_EVENT_WINDOW`]. Returns the events added and the duplicates skipped.
+    fn merge_events(&mut self, imported: Vec<BatteryEvent>) -> (usize, usize) {
+        let mut events: Vec<BatteryEvent> = self.events.drain(..).collect();
+        let known = events.len();
+        let mut duplicates = 0;
+        for event in imported {
+            let duplicate = events[..known].iter().any(|existing| {
+                existing.left_battery == event.left_battery
+                    && existing.right_battery == event.right_battery
+                    && existing.case_battery == event.case_battery
+                    && existing
+                        .timestamp
+                        .duration_since(event.timestamp)
+                        .unwrap_or_else(|e| e.duration())
+                        <= DUPLICATE_EVENT_WINDOW
+            });
+            if duplicate {
+                duplicates += 1;
+            } else {
+                events.push(event);
+            }
+        }
+        let added = events.len() - known;
+
+        events.sort_by_key(|event| event.timestamp);
+        for event in events {
+            self.push_event(event);
+        }
+        (added, duplicates)
+    }
+
+    /// Add a depletion rate sample to the buffer and the history
+    fn record_sample(&mut self, sample: DepletionRateSample) {
+        self.unsaved_history
+            .push(HistoryRecord::Sample(sample.clone()));
+        self.depletion_rates.add_sample(sample);
+    }
+
+    /// Events and samples not yet written to the history log
+    fn take_unsaved_history(&mut self) -> Vec<HistoryRecord> {
+        std::mem::take(&mut self.unsaved_history)
+    }
+
+    /// Every event and sample held in memory, as history log records
+    fn history_records(&self) -> Vec<HistoryRecord> {
+        let samples = self
+            .depletion_rates
+            .left_samples
+            .iter()
+            .chain(&self.depletion_rates.right_samples)
+            .chain(&self.depletion_rates.case_samples)
+            .cloned()
+            .map(HistoryRecord::Sample);
+        self.events
+            .iter()
+            .cloned()
+            .map(HistoryRecord::Event)
+            .chain(samples)
+            .collect()
+    }
+
+    /// Replace the events and samples with those read from the history log
+    fn restore_history(&mut self, records: Vec<HistoryRecord>) {
+        self.events.clear();
+        self.depletion_rates = default_depletion_rates();
+        for record in records {
+            match record {
+                HistoryRecord::Event(event) => self.push_event(event),
+                HistoryRecord::Sample(sample) => self.depletion_rates.add_sample(sample),
+            }
+        }
+    }
+
     /// Update current device state and track significant changes
     pub fn update_current_state(
         &mut self,
@@ -1113,6 +2179,16 @@ impl DeviceBatteryProfile {
     ) {
         let now = SystemTime::now();
 
+        // Score the estimates shown since the last reading against this one
+        self.evaluate_estimates(
+            [
+                (left, left_charging),
+                (right, right_charging),
+                (case, case_charging),
+            ],
+            now,
+        );
+
         // --- Process left earbud depletion data ---
         if let Some(level) = left {
             // If charging, reset last level tracking
@@ -1124,7 +2200,11 @@ impl DeviceBatteryProfile {
                 // Only process if battery is discharging and we have >= 10% drop
                 if level < last_level && (last_level - level) >= SIGNIFICANT_BATTERY_DROP {
                     // Calculate time difference in minutes
-                    if let Ok(elapsed) = now.duration_since(last_time) {
+                    if self.spans_suspend(last_time, now) {
+                        log::debug!(
+                            "Discarding left earbud depletion sample spanning a system suspend"
+                        );
+                    } else if let Ok(elapsed) = now.duration_since(last_time) {
                         let minutes = elapsed.as_secs() as f32 / 60.0;
                         let percent_drop = last_level - level;
 
@@ -1140,7 +2220,7 @@ impl DeviceBatteryProfile {
                             end_percent: level,
                         };
 
-                        self.depletion_rates.add_sample(sample);
+                        self.record_sample(sample);
 
                         // Debug logging of rate change
                         log::debug!(
@@ -1171,7 +2251,11 @@ impl DeviceBatteryProfile {
                 // Only process if battery is discharging and we have >= 10% drop
                 if level < last_level && (last_level - level) >= SIGNIFICANT_BATTERY_DROP {
                     // Calculate time difference in minutes
-                    if let Ok(elapsed) = now.duration_since(last_time) {
+                    if self.spans_suspend(last_time, now) {
+                        log::debug!(
+                            "Discarding right earbud depletion sample spanning a system suspend"
+                        );
+                    } else if let Ok(elapsed) = now.duration_since(last_time) {
                         let minutes = elapsed.as_secs() as f32 / 60.0;
                         let percent_drop = last_level - level;
 
@@ -1187,7 +2271,7 @@ impl DeviceBatteryProfile {
                             end_percent: level,
                         };
 
-                        self.depletion_rates.add_sample(sample);
+                        self.record_sample(sample);
 
                         // Debug logging of rate change
                         log::debug!(
@@ -1216,7 +2300,9 @@ impl DeviceBatteryProfile {
                 // Only process if battery is discharging and we have >= 10% drop
                 if level < last_level && (last_level - level) >= SIGNIFICANT_BATTERY_DROP {
                     // Calculate time difference in minutes
-                    if let Ok(elapsed) = now.duration_since(last_time) {
+                    if self.spans_suspend(last_time, now) {
+                        log::debug!("Discarding case depletion sample spanning a system suspend");
+                    } else if let Ok(elapsed) = now.duration_since(last_time) {
                         let minutes = elapsed.as_secs() as f32 / 60.0;
                         let percent_drop = last_level - level;
 
@@ -1232,7 +2318,7 @@ impl DeviceBatteryProfile {
                             end_percent: level,
                         };
 
-                        self.depletion_rates.add_sample(sample);
+                        self.record_sample(sample);
 
                         // Debug logging of rate change
                         log::debug!(
@@ -1277,10 +2363,22 @@ impl DeviceBatteryProfile {
                 });
             }
         } else if self.current_session.is_some() {
-            // End session
-            self.current_session = None;
+            // End session and keep a summary of it
+            self.finish_session(now, left, right);
         }
 
+        // Count discharge towards charge cycles
+        self.health_metrics
+            .record_discharge(DepletionTarget::LeftEarbud, left, left_charging, now);
+        self.health_metrics.record_discharge(
+            DepletionTarget::RightEarbud,
+            right,
+            right_charging,
+            now,
+        );
+        self.health_metrics
+            .record_discharge(DepletionTarget::Case, case, case_charging, now);
+
         // Update max observed values for health tracking
         if let Some(left_level) = left {
             if !left_charging && left_level > self.health_metrics.max_observed_left {
@@ -1745,6 +2843,11 @@ impl Default for BatteryHealthMetrics {
             charging_efficiency: 1.0,
             estimated_cycles: 0,
             health_score: 1.0,
+            total_discharge: DischargeTotals::default(),
+            monthly_discharge: BTreeMap::new(),
+            last_reported_month: None,
+            estimation_accuracy: EstimationAccuracy::default(),
+            cycle_baseline: [None; 3],
         }
     }
 }
@@ -1853,6 +2956,178 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_completed_sessions_are_summarized() {
+        let mut profile = DeviceBatteryProfile::new("Test Device", "test_device");
+
+        // Two-hour session that used 20% per bud
+        profile.current_session = Some(UsageSession {
+            start_time: SystemTime::now() - Duration::from_secs(2 * 60 * 60),
+            start_left: Some(90),
+            start_right: Some(85),
+            start_case: Some(100),
+            session_type: SessionType::Unknown,
+            usage_pattern: UsagePattern::Moderate,
+        });
+        profile.update_current_state(
+            Some(70),
+            Some(65),
+            Some(100),
+            false,
+            false,
+            false,
+            false,
+            false,
+        );
+
+        assert!(profile.current_session.is_none());
+        assert_eq!(profile.session_history.len(), 1);
+        let session = &profile.session_history[0];
+        assert_eq!(session.left_consumed, Some(20));
+        assert_eq!(session.right_consumed, Some(20));
+        assert_eq!(session.usage_pattern, UsagePattern::Moderate);
+
+        let summary = profile.usage_summary(chrono::Local::now());
+        assert_eq!(summary.sessions_this_week, 1);
+        assert!(summary.this_week >= Duration::from_secs(2 * 60 * 60 - 5));
+        let drain = summary.average_drain_per_hour.unwrap();
+        assert!((drain - 10.0).abs() < 0.1);
+
+        // Very short sessions are not recorded
+        profile.current_session = Some(UsageSession {
+            start_time: SystemTime::now(),
+            start_left: Some(70),
+            start_right: Some(65),
+            start_case: Some(100),
+            session_type: SessionType::Unknown,
+            usage_pattern: UsagePattern::Moderate,
+        });
+        profile.update_current_state(
+            Some(70),
+            Some(65),
+            Some(100),
+            false,
+            false,
+            false,
+            false,
+            false,
+        );
+        assert_eq!(profile.session_history.len(), 1);
+    }
+
+    #[test]
+    fn test_charge_cycles_are_counted_per_month() {
+        let mut profile = DeviceBatteryProfile::new("Test Device", "test_device");
+        let update = |profile: &mut DeviceBatteryProfile, level: u8, charging: bool| {
+            profile.update_current_state(
+                Some(level),
+                Some(level),
+                Some(0),
+                charging,
+                charging,
+                false,
+                false,
+                false,
+            )
+        };
+
+        // Drain 100 -> 40, charge back to 100, drain 100 -> 60: one cycle per bud
+        update(&mut profile, 100, false);
+        update(&mut profile, 40, false);
+        update(&mut profile, 70, true);
+        update(&mut profile, 100, true);
+        update(&mut profile, 60, false);
+
+        let metrics = &profile.health_metrics;
+        assert_eq!(metrics.total_discharge.left, 100);
+        assert_eq!(metrics.total_discharge.right, 100);
+        // The unseen case (0%) is not counted
+        assert_eq!(metrics.total_discharge.case, 0);
+        assert_eq!(metrics.estimated_cycles, 1);
+        assert_eq!(metrics.monthly_discharge.len(), 1);
+        // A cycle wears the earbuds a little, and the estimate in mAh with them
+        assert!(metrics.health_score < 1.0);
+        assert_eq!(metrics.capacity_health().earbuds, metrics.health_score);
+        assert_eq!(metrics.capacity_health().case, 1.0);
+
+        // Nothing to report until the month is over, and each month is reported once
+        assert!(profile.take_monthly_report(SystemTime::now()).is_none());
+        let next_month = SystemTime::now() + Duration::from_secs(32 * 24 * 60 * 60);
+        let report = profile.take_monthly_report(next_month).unwrap();
+        assert_eq!(report.discharge.earbud_cycles(), 1.0);
+        assert!(report
+            .summary()
+            .starts_with("Your AirPods went through 1 cycle in "));
+        assert!(profile.take_monthly_report(next_month).is_none());
+    }
+
+    #[test]
+    fn test_estimates_are_scored_against_later_readings() {
+        let mut profile = DeviceBatteryProfile::new("Test Device", "test_device");
+        profile.update_current_state(Some(80), Some(80), Some(0), false, false, false, true, true);
+
+        // A reading within 30 seconds is shown as is, so there is nothing to score
+        profile.update_current_state(Some(79), Some(79), Some(0), false, false, false, true, true);
+        assert_eq!(profile.health_metrics.estimation_accuracy.left.samples, 0);
+
+        // Twenty minutes later the estimator has been predicting a drop
+        profile.last_update = Some(SystemTime::now() - Duration::from_secs(20 * 60));
+        profile.update_current_state(Some(75), Some(76), Some(0), false, false, false, true, true);
+
+        let accuracy = profile.health_metrics.estimation_accuracy;
+        assert_eq!(accuracy.left.samples, 1);
+        assert_eq!(accuracy.right.samples, 1);
+        // The unseen case isn't scored
+        assert_eq!(accuracy.case.samples, 0);
+        assert_eq!(accuracy.left.baseline_mean_absolute_error(), Some(4.0));
+        assert!(accuracy.left.mean_absolute_error().unwrap() < 4.0);
+        assert!(accuracy.left.bias().unwrap() > 0.0);
+        assert_eq!(accuracy.earbuds().samples, 2);
+    }
+
+    #[test]
+    fn test_depletion_samples_spanning_suspend_are_discarded() {
+        let mut profile = DeviceBatteryProfile::new("Test Device", "test_device");
+        let two_hours_ago = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+
+        // Last reading taken before an overnight sleep
+        profile.last_left_level = Some((90, two_hours_ago));
+        profile.last_right_level = Some((90, two_hours_ago));
+        profile.record_suspend_period(
+            two_hours_ago + Duration::from_secs(60),
+            SystemTime::now() - Duration::from_secs(60),
+        );
+        assert!(profile.spans_suspend(two_hours_ago, SystemTime::now()));
+
+        profile.update_current_state(Some(70), Some(70), None, false, false, false, true, true);
+
+        // No bogus sample, but the baseline moves forward to the new reading
+        assert_eq!(
+            profile
+                .depletion_rates
+                .get_sample_count(DepletionTarget::LeftEarbud),
+            0
+        );
+        assert_eq!(
+            profile
+                .depletion_rates
+                .get_sample_count(DepletionTarget::RightEarbud),
+            0
+        );
+        assert_eq!(profile.last_left_level.map(|(level, _)| level), Some(70));
+
+        // Without a suspend in between the same drop produces a sample
+        profile.suspend_periods.clear();
+        profile.last_left_level = Some((90, two_hours_ago));
+        profile.update_current_state(Some(70), None, None, false, false, false, true, true);
+        assert_eq!(
+            profile
+                .depletion_rates
+                .get_sample_count(DepletionTarget::LeftEarbud),
+            1
+        );
+    }
+
     #[test]
     fn test_significance_filtering() {
         let temp_dir = TempDir::new().unwrap();
@@ -1983,6 +3258,158 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_history_is_kept_outside_the_profile() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut intelligence = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        intelligence.ensure_device_profile("test_device", "Test Device");
+        intelligence.update_device_battery(
+            "test_device",
+            "Test Device",
+            Some(50),
+            Some(50),
+            Some(50),
+            true,
+            true,
+            true,
+            false,
+            false,
+            Some(-45),
+        );
+        intelligence.save().unwrap();
+
+        let profile_path = temp_dir.path().join("battery_profile.json");
+        let json = fs::read_to_string(&profile_path).unwrap();
+        assert!(!json.contains("\"events\""));
+        let reloaded = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        assert_eq!(reloaded.device_profile.as_ref().unwrap().events.len(), 1);
+
+        // A profile from before the history log holds its events inline
+        let event = reloaded.device_profile.as_ref().unwrap().events[0].clone();
+        let mut old: serde_json::Value = serde_json::from_str(&json).unwrap();
+        old["events"] = serde_json::to_value([&event, &event]).unwrap();
+        fs::write(&profile_path, old.to_string()).unwrap();
+        HistoryLog::in_dir(temp_dir.path()).remove().unwrap();
+
+        let migrated = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        assert_eq!(migrated.device_profile.as_ref().unwrap().events.len(), 2);
+        assert!(HistoryLog::in_dir(temp_dir.path()).exists());
+        assert!(!fs::read_to_string(&profile_path)
+            .unwrap()
+            .contains("\"events\""));
+    }
+
+    #[test]
+    fn test_history_saved_after_a_torn_append_reads_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let update = |intelligence: &mut BatteryIntelligence, charging: bool| {
+            intelligence.update_device_battery(
+                "test_device",
+                "Test Device",
+                Some(50),
+                Some(50),
+                Some(50),
+                charging,
+                charging,
+                charging,
+                false,
+                false,
+                Some(-45),
+            );
+            intelligence.save().unwrap();
+        };
+        let mut intelligence = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        intelligence.ensure_device_profile("test_device", "Test Device");
+        update(&mut intelligence, true);
+
+        // A crash in the middle of an append leaves a partial frame
+        let log = HistoryLog::in_dir(temp_dir.path());
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(log.path())
+            .unwrap();
+        std::io::Write::write_all(&mut file, &[200, 0, 0, 0, 1, 2]).unwrap();
+
+        let mut reloaded = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        update(&mut reloaded, false);
+        assert_eq!(log.read_frames().unwrap().1, None);
+
+        let reloaded = BatteryIntelligence::new(temp_dir.path().to_path_buf());
+        let events = &reloaded.device_profile.as_ref().unwrap().events;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].event_type, BatteryEventType::ChargingStopped);
+    }
+
+    #[test]
+    fn test_in_memory_intelligence_never_touches_disk() {
+        let mut intelligence = BatteryIntelligence::in_memory();
+        intelligence.ensure_device_profile("test_device", "Test Device");
+        intelligence.update_device_battery(
+            "test_device",
+            "Test Device",
+            Some(50),
+            Some(50),
+            Some(50),
+            false,
+            false,
+            false,
+            true,
+            true,
+            Some(-45),
+        );
+        assert!(intelligence.device_profile.is_some());
+
+        intelligence.save().unwrap();
+        assert!(!intelligence.profile_path().exists());
+        assert!(intelligence.snapshots().is_empty());
+        intelligence.purge_all_profiles().unwrap();
+        assert!(intelligence.device_profile.is_none());
+    }
+
+    #[test]
+    fn test_imported_captures_are_merged_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let capture_path = temp_dir.path().join("capture.json");
+        let scans: Vec<String> = (0..5)
+            .map(|i| {
+                serde_json::json!({
+                    "scan_timestamp": 1_750_000_000 + i * 240,
+                    "devices": [{
+                        "address": "AA:BB:CC:DD:EE:FF",
+                        "rssi": -55,
+                        "airpods_data": {
+                            "model": "AirPods Pro",
+                            "left_battery": 100 - i * 10,
+                            "right_battery": 100 - i * 10,
+                            "case_battery": 80,
+                            "left_in_ear": true,
+                            "right_in_ear": true
+                        }
+                    }]
+                })
+                .to_string()
+            })
+            .collect();
+        fs::write(&capture_path, scans.join("\n")).unwrap();
+
+        let storage = temp_dir.path().join("intelligence");
+        let mut intelligence = BatteryIntelligence::new(storage.clone());
+        let report = intelligence.import_history(&[capture_path.clone()]);
+        assert_eq!(report.readings, 5);
+        assert_eq!(report.events_added, 5);
+        let profile = intelligence.device_profile.as_ref().unwrap();
+        assert_eq!(profile.device_address, "aabbccddeeff");
+        assert_eq!(profile.events[1].event_type, BatteryEventType::Discharge);
+        assert!(profile.discharge_models.contains_key(&UsagePattern::Light));
+
+        let again = intelligence.import_history(&[capture_path]);
+        assert_eq!(again.events_added, 0);
+        assert_eq!(again.duplicates, 5);
+
+        let reloaded = BatteryIntelligence::new(storage);
+        assert_eq!(reloaded.device_profile.as_ref().unwrap().events.len(), 5);
+    }
+
     #[test]
     fn test_device_name_change_and_singleton_behavior() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,161 @@
+//! Battery capacities, and the charge left in mAh
+//!
+//! A percentage says how full a battery is but not how much charge that is: half an
+//! AirPods Pro 2 earbud holds more than half an AirPods 3 earbud. The nominal capacity of
+//! each model's earbuds and case is kept in the [model table](super::model_table) next to
+//! its name, so a model without one can be given one in `models.json`. The estimate
+//! scales the nominal capacity by the [health](Health) learned from the charge cycles
+//! counted so far, then by the level.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::model_table::ModelTable;
+
+/// Charge cycles after which a battery is taken to hold 80% of its nominal capacity
+const CYCLES_TO_80_PERCENT: f32 = 500.0;
+
+/// The least share of its nominal capacity a worn battery is taken to hold
+const MIN_HEALTH: f32 = 0.5;
+
+/// Nominal capacity of a model's batteries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Capacity {
+    /// Each earbud, or the only battery of headphones, in mAh
+    pub earbud_mah: u32,
+    /// The charging case in mAh, for models with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_mah: Option<u32>,
+}
+
+impl Capacity {
+    /// Nominal capacity of a model ID, if the model table has one
+    pub fn for_model(model_id: u16) -> Option<Self> {
+        ModelTable::global().capacity(model_id)
+    }
+
+    /// Charge left at these levels, in batteries of this health
+    pub fn estimate(
+        &self,
+        health: Health,
+        left: Option<u8>,
+        right: Option<u8>,
+        case: Option<u8>,
+    ) -> MahEstimate {
+        let remaining = |nominal: u32, health: f32, level: u8| {
+            (nominal as f32 * health * f32::from(level.min(100)) / 100.0).round() as u32
+        };
+        MahEstimate {
+            left: left.map(|level| remaining(self.earbud_mah, health.earbuds, level)),
+            right: right.map(|level| remaining(self.earbud_mah, health.earbuds, level)),
+            case: self
+                .case_mah
+                .zip(case)
+                .map(|(nominal, level)| remaining(nominal, health.case, level)),
+        }
+    }
+}
+
+/// Share of their nominal capacity the batteries still hold, from 0.0 to 1.0
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Health {
+    pub earbuds: f32,
+    pub case: f32,
+}
+
+impl Health {
+    /// Batteries that hold their full nominal capacity
+    pub const NEW: Self = Self {
+        earbuds: 1.0,
+        case: 1.0,
+    };
+
+    /// Health after these charge cycles, losing capacity at a steady rate as lithium-ion
+    /// batteries roughly do
+    pub fn after_cycles(earbud_cycles: f32, case_cycles: f32) -> Self {
+        let health =
+            |cycles: f32| (1.0 - 0.2 * cycles.max(0.0) / CYCLES_TO_80_PERCENT).max(MIN_HEALTH);
+        Self {
+            earbuds: health(earbud_cycles),
+            case: health(case_cycles),
+        }
+    }
+}
+
+/// Estimated charge left in each battery, in mAh
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq, Eq)]
+pub struct MahEstimate {
+    pub left: Option<u32>,
+    pub right: Option<u32>,
+    pub case: Option<u32>,
+}
+
+impl MahEstimate {
+    /// Whether no battery has an estimate
+    pub fn is_empty(&self) -> bool {
+        self.left.is_none() && self.right.is_none() && self.case.is_none()
+    }
+}
+
+impl fmt::Display for MahEstimate {
+    /// Batteries with an estimate, e.g. "L 42 mAh  R 40 mAh  Case 410 mAh"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [("L", self.left), ("R", self.right), ("Case", self.case)]
+            .into_iter()
+            .filter_map(|(label, mah)| mah.map(|mah| format!("{} {} mAh", label, mah)))
+            .collect();
+        write!(f, "{}", parts.join("  "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_scales_by_level() {
+        let capacity = ModelTable::bundled().capacity(0x2014).unwrap();
+        assert_eq!(capacity.earbud_mah, 49);
+        assert_eq!(capacity.case_mah, Some(523));
+
+        let estimate = capacity.estimate(Health::NEW, Some(100), Some(50), Some(80));
+        assert_eq!(estimate.left, Some(49));
+        assert_eq!(estimate.right, Some(25));
+        assert_eq!(estimate.case, Some(418));
+        assert_eq!(estimate.to_string(), "L 49 mAh  R 25 mAh  Case 418 mAh");
+        assert_eq!(
+            capacity
+                .estimate(Health::NEW, Some(100), None, None)
+                .to_string(),
+            "L 49 mAh"
+        );
+
+        // Headphones have no case to estimate
+        let single = Capacity {
+            earbud_mah: 1328,
+            case_mah: None,
+        };
+        assert_eq!(
+            single.estimate(Health::NEW, None, None, Some(50)),
+            MahEstimate::default()
+        );
+        assert!(MahEstimate::default().is_empty());
+    }
+    #[test]
+    fn test_worn_batteries_hold_less() {
+        let capacity = ModelTable::bundled().capacity(0x2014).unwrap();
+        assert_eq!(Health::after_cycles(0.0, 0.0), Health::NEW);
+
+        let worn = Health::after_cycles(500.0, 250.0);
+        assert!((worn.earbuds - 0.8).abs() < 1e-6);
+        assert!((worn.case - 0.9).abs() < 1e-6);
+        let estimate = capacity.estimate(worn, Some(100), Some(50), Some(80));
+        assert_eq!(estimate.left, Some(39));
+        assert_eq!(estimate.right, Some(20));
+        assert_eq!(estimate.case, Some(377));
+
+        // However many cycles, a battery keeps some of its capacity
+        assert_eq!(Health::after_cycles(10_000.0, 10_000.0).earbuds, MIN_HEALTH);
+    }
+}
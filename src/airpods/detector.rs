@@ -2,7 +2,8 @@ use btleplug::api::BDAddr;
 use std::default::Default;
 // use std::collections::HashMap;
 
-use super::{parse_airpods_data, AirPodsBattery, AirPodsType, Result};
+use super::model_table::ModelTable;
+use super::{decode_proximity_message, AirPodsBattery, AirPodsType, ProximityExtras, Result};
 use crate::bluetooth::scanner::DiscoveredDevice;
 use crate::error::{AirPodsError, ErrorContext, ErrorManager};
 
@@ -20,8 +21,9 @@ impl Default for AirPodsDetector {
 }
 
 impl AirPodsDetector {
-    /// Create a new AirPods detector
+    /// Create a new AirPods detector, loading the model table if it isn't yet
     pub fn new() -> Self {
+        ModelTable::global();
         Self {
             min_rssi: -70, // Default RSSI threshold
         }
@@ -51,15 +53,9 @@ impl AirPodsDetector {
 pub const APPLE_COMPANY_ID: u16 = 0x004C;
 #[allow(dead_code)]
 const AIRPODS_DATA_LENGTH: usize = 27;
-const AIRPODS_1_2_PREFIX: &[u8] = &[0x07, 0x19];
-const AIRPODS_PRO_PREFIX: &[u8] = &[0x0E, 0x19];
-const AIRPODS_PRO_2_PREFIX: &[u8] = &[0x0F, 0x19];
-const AIRPODS_3_PREFIX: &[u8] = &[0x13, 0x19];
-const AIRPODS_MAX_PREFIX: &[u8] = &[0x0A, 0x19];
 
 /// Offset positions for AirPods device flags
-#[allow(dead_code)]
-pub const FLIP_STATUS_OFFSET: usize = 11;
+pub use super::FLIP_STATUS_OFFSET;
 #[allow(dead_code)]
 pub const LEFT_BATTERY_OFFSET: usize = 12;
 #[allow(dead_code)]
@@ -86,6 +82,8 @@ pub struct DetectedAirPods {
     pub last_seen: std::time::Instant,
     /// Whether the device is connected
     pub is_connected: bool,
+    /// Extended proximity message fields (lid, color, in-ear, connection state)
+    pub extras: Option<ProximityExtras>,
 }
 
 impl DetectedAirPods {
@@ -106,6 +104,7 @@ impl DetectedAirPods {
             battery,
             last_seen: std::time::Instant::now(),
             is_connected,
+            extras: None,
         }
     }
 }
@@ -120,6 +119,7 @@ impl Default for DetectedAirPods {
             battery: None,
             last_seen: std::time::Instant::now(),
             is_connected: false,
+            extras: None,
         }
     }
 }
@@ -222,9 +222,9 @@ pub fn detect_airpods(device: &DiscoveredDevice) -> Result<Option<DetectedAirPod
         }
     };
 
-    // Try to parse battery data - graceful degradation if battery parsing fails
-    let battery = match parse_airpods_data(apple_data) {
-        Ok(battery) => Some(battery),
+    // Try to decode the proximity message - graceful degradation if parsing fails
+    let (battery, extras) = match decode_proximity_message(apple_data) {
+        Ok(message) => (Some(message.battery), Some(message.extras)),
         Err(err) => {
             // We can still return the device without battery info
             // Log error but don't abort detection
@@ -235,12 +235,12 @@ pub fn detect_airpods(device: &DiscoveredDevice) -> Result<Option<DetectedAirPod
             );
 
             // Continue with None battery
-            None
+            (None, None)
         }
     };
 
     // Create and return the detected AirPods
-    let airpods = DetectedAirPods::new(
+    let mut airpods = DetectedAirPods::new(
         device.address,
         device.name.clone(),
         device.rssi,
@@ -248,6 +248,9 @@ pub fn detect_airpods(device: &DiscoveredDevice) -> Result<Option<DetectedAirPod
         battery,
         device.is_connected,
     );
+    // Keep when the advertisement was received so fusion can order advertisements
+    airpods.last_seen = device.last_seen;
+    airpods.extras = extras;
 
     Ok(Some(airpods))
 }
@@ -271,26 +274,10 @@ pub fn identify_airpods_type(name: &Option<String>, data: &[u8]) -> Result<AirPo
         )));
     }
 
-    // Try to identify by prefix
-    let device_type = match &data[0..2] {
-        prefix if prefix == AIRPODS_1_2_PREFIX => {
-            // Distinguish between AirPods 1 and AirPods 2
-            if let Some(name) = name {
-                if name.contains("2") || name.contains("II") {
-                    AirPodsType::AirPods2
-                } else {
-                    AirPodsType::AirPods1
-                }
-            } else {
-                // Default to AirPods2 if we can't distinguish
-                AirPodsType::AirPods2
-            }
-        }
-        prefix if prefix == AIRPODS_3_PREFIX => AirPodsType::AirPods3,
-        prefix if prefix == AIRPODS_PRO_PREFIX => AirPodsType::AirPodsPro,
-        prefix if prefix == AIRPODS_PRO_2_PREFIX => AirPodsType::AirPodsPro2,
-        prefix if prefix == AIRPODS_MAX_PREFIX => AirPodsType::AirPodsMax,
-        _ => {
+    // Try to identify by prefix; models sharing one are told apart by name
+    let device_type = match ModelTable::global().prefix(data) {
+        Some(entry) => entry.device_type(name.as_deref()),
+        None => {
             // Use name-based detection as fallback
             if let Some(name) = name {
                 if name.contains("AirPods") {
@@ -0,0 +1,321 @@
+//! Fusion of AirPods advertisements from both buds
+//!
+//! A pair advertises from whichever bud is currently primary, and each bud uses its own
+//! rotating random address. Without fusion the same pair shows up as several devices
+//! whose readings alternate. Advertisements are grouped into pairs by address and by
+//! consistent readings, and the newest known value of each component is kept. Readings
+//! are already normalized to physical sides by the parser's flip flag, so a fused pair
+//! never swaps its left and right values.
+
+use std::time::{Duration, Instant};
+
+use btleplug::api::BDAddr;
+
+use super::{AirPodsBattery, AirPodsType, DetectedAirPods};
+
+/// How long an advertisement keeps contributing to a pair
+pub const DEFAULT_FUSION_WINDOW: Duration = Duration::from_secs(30);
+
+/// Largest difference (percentage points) between readings of the same pair
+///
+/// Levels are reported in 10% steps, so one step of drift is tolerated.
+pub const READING_TOLERANCE: u8 = 10;
+
+/// Addresses remembered per pair, newest last
+const MAX_ADDRESSES_PER_PAIR: usize = 4;
+
+/// A pair assembled from one or more advertisements
+#[derive(Debug, Clone)]
+struct FusedPair {
+    /// Fused view of the pair
+    device: DetectedAirPods,
+    /// Addresses the pair has advertised from recently
+    addresses: Vec<BDAddr>,
+    /// When each battery component was last reported (left, right, case)
+    component_seen: [Option<Instant>; 3],
+}
+
+/// Fuses advertisements from both buds of a pair into one device
+#[derive(Debug, Clone)]
+pub struct AdvertisementFusion {
+    pairs: Vec<FusedPair>,
+    window: Duration,
+}
+
+impl Default for AdvertisementFusion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdvertisementFusion {
+    /// Create a fusion with the default window
+    pub fn new() -> Self {
+        Self::with_window(DEFAULT_FUSION_WINDOW)
+    }
+
+    /// Create a fusion that forgets advertisements older than `window`
+    pub fn with_window(window: Duration) -> Self {
+        Self {
+            pairs: Vec::new(),
+            window,
+        }
+    }
+
+    /// Add an advertisement and return the fused view of its pair
+    pub fn ingest(&mut self, advertisement: DetectedAirPods) -> DetectedAirPods {
+        let now = advertisement.last_seen;
+        self.prune(now);
+
+        let index = match self.find_pair(&advertisement) {
+            Some(index) => {
+                self.pairs[index].merge(advertisement, now, self.window);
+                index
+            }
+            None => {
+                self.pairs.push(FusedPair::new(advertisement, now));
+                self.pairs.len() - 1
+            }
+        };
+        self.pairs[index].device.clone()
+    }
+
+    /// Fused view of every pair seen within the window
+    pub fn devices(&self) -> Vec<DetectedAirPods> {
+        self.pairs.iter().map(|pair| pair.device.clone()).collect()
+    }
+
+    /// Forget pairs that haven't advertised within the window
+    pub fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.pairs
+            .retain(|pair| now.saturating_duration_since(pair.device.last_seen) <= window);
+    }
+
+    /// The pair an advertisement belongs to: a known address first, then consistent readings
+    fn find_pair(&self, advertisement: &DetectedAirPods) -> Option<usize> {
+        self.pairs
+            .iter()
+            .position(|pair| pair.addresses.contains(&advertisement.address))
+            .or_else(|| {
+                self.pairs.iter().position(|pair| {
+                    same_model(&pair.device.device_type, &advertisement.device_type)
+                        && readings_consistent(
+                            pair.device.battery.as_ref(),
+                            advertisement.battery.as_ref(),
+                        )
+                })
+            })
+    }
+}
+
+impl FusedPair {
+    fn new(advertisement: DetectedAirPods, now: Instant) -> Self {
+        let component_seen = match &advertisement.battery {
+            Some(battery) => [
+                battery.left.map(|_| now),
+                battery.right.map(|_| now),
+                battery.case.map(|_| now),
+            ],
+            None => [None; 3],
+        };
+        Self {
+            addresses: vec![advertisement.address],
+            device: advertisement,
+            component_seen,
+        }
+    }
+
+    fn merge(&mut self, advertisement: DetectedAirPods, now: Instant, window: Duration) {
+        if !self.addresses.contains(&advertisement.address) {
+            self.addresses.push(advertisement.address);
+            if self.addresses.len() > MAX_ADDRESSES_PER_PAIR {
+                self.addresses.remove(0);
+            }
+        }
+
+        let previous = self.device.battery.take().unwrap_or_default();
+        let battery = match advertisement.battery {
+            Some(update) => {
+                let mut merged = AirPodsBattery {
+                    charging: update.charging.or(previous.charging),
+                    ..AirPodsBattery::default()
+                };
+                let components = [
+                    (&mut merged.left, update.left, previous.left),
+                    (&mut merged.right, update.right, previous.right),
+                    (&mut merged.case, update.case, previous.case),
+                ];
+                for ((slot, new, old), seen) in
+                    components.into_iter().zip(self.component_seen.iter_mut())
+                {
+                    if new.is_some() {
+                        *slot = new;
+                        *seen = Some(now);
+                    } else if seen.is_some_and(|at| now.saturating_duration_since(at) <= window) {
+                        // Keep what the other bud reported until it goes stale
+                        *slot = old;
+                    } else {
+                        *seen = None;
+                    }
+                }
+                Some(merged)
+            }
+            None => Some(previous).filter(|battery| *battery != AirPodsBattery::default()),
+        };
+
+        let name = advertisement.name.or(self.device.name.take());
+        let extras = advertisement.extras.or(self.device.extras.take());
+        self.device = DetectedAirPods {
+            name,
+            extras,
+            battery,
+            is_connected: advertisement.is_connected || self.device.is_connected,
+            ..advertisement
+        };
+    }
+}
+
+/// Fuse a snapshot of advertisements (e.g. one per address) into one device per pair
+pub fn fuse_advertisements(
+    advertisements: impl IntoIterator<Item = DetectedAirPods>,
+) -> Vec<DetectedAirPods> {
+    let mut advertisements: Vec<_> = advertisements.into_iter().collect();
+    advertisements.sort_by_key(|advertisement| advertisement.last_seen);
+
+    let mut fusion = AdvertisementFusion::new();
+    for advertisement in advertisements {
+        fusion.ingest(advertisement);
+    }
+    fusion.devices()
+}
+
+/// Whether two model detections can belong to the same pair
+fn same_model(a: &AirPodsType, b: &AirPodsType) -> bool {
+    a == b || *a == AirPodsType::Unknown || *b == AirPodsType::Unknown
+}
+
+/// Whether two readings could come from the same pair at about the same time
+///
+/// Components missing from either reading don't count against a match, but at least
+/// one component has to be present in both.
+fn readings_consistent(a: Option<&AirPodsBattery>, b: Option<&AirPodsBattery>) -> bool {
+    let (Some(a), Some(b)) = (a, b) else {
+        return false;
+    };
+
+    let pairs = [(a.left, b.left), (a.right, b.right), (a.case, b.case)];
+    let mut compared = 0;
+    for (x, y) in pairs {
+        if let (Some(x), Some(y)) = (x, y) {
+            if x.abs_diff(y) > READING_TOLERANCE {
+                return false;
+            }
+            compared += 1;
+        }
+    }
+    compared > 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advertisement(
+        address: [u8; 6],
+        left: Option<u8>,
+        right: Option<u8>,
+        case: Option<u8>,
+        seen_at: Instant,
+    ) -> DetectedAirPods {
+        DetectedAirPods {
+            address: BDAddr::from(address),
+            device_type: AirPodsType::AirPodsPro,
+            battery: Some(AirPodsBattery {
+                left,
+                right,
+                case,
+                charging: None,
+            }),
+            last_seen: seen_at,
+            ..DetectedAirPods::default()
+        }
+    }
+
+    #[test]
+    fn test_rotating_addresses_fuse_into_one_pair() {
+        let start = Instant::now();
+        let devices = fuse_advertisements([
+            advertisement([1, 0, 0, 0, 0, 1], Some(80), Some(40), Some(60), start),
+            advertisement(
+                [2, 0, 0, 0, 0, 2],
+                Some(80),
+                Some(40),
+                None,
+                start + Duration::from_secs(2),
+            ),
+        ]);
+
+        assert_eq!(devices.len(), 1);
+        let battery = devices[0].battery.as_ref().unwrap();
+        assert_eq!(battery.left, Some(80));
+        assert_eq!(battery.right, Some(40));
+        // The case reading from the first bud is kept
+        assert_eq!(battery.case, Some(60));
+        assert_eq!(devices[0].address, BDAddr::from([2, 0, 0, 0, 0, 2]));
+    }
+
+    #[test]
+    fn test_sides_never_swap() {
+        let start = Instant::now();
+        let mut fusion = AdvertisementFusion::new();
+        fusion.ingest(advertisement(
+            [1, 0, 0, 0, 0, 1],
+            Some(90),
+            Some(30),
+            None,
+            start,
+        ));
+
+        // Readings with the sides swapped look like a different pair, not a flip
+        fusion.ingest(advertisement(
+            [2, 0, 0, 0, 0, 2],
+            Some(30),
+            Some(90),
+            None,
+            start + Duration::from_secs(1),
+        ));
+        let devices = fusion.devices();
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].battery.as_ref().unwrap().left, Some(90));
+    }
+
+    #[test]
+    fn test_stale_components_expire() {
+        let start = Instant::now();
+        let mut fusion = AdvertisementFusion::with_window(Duration::from_secs(10));
+        fusion.ingest(advertisement(
+            [1, 0, 0, 0, 0, 1],
+            Some(70),
+            Some(70),
+            Some(50),
+            start,
+        ));
+        fusion.ingest(advertisement(
+            [1, 0, 0, 0, 0, 1],
+            Some(70),
+            Some(70),
+            None,
+            start + Duration::from_secs(5),
+        ));
+        let fused = fusion.ingest(advertisement(
+            [1, 0, 0, 0, 0, 1],
+            Some(70),
+            Some(70),
+            None,
+            start + Duration::from_secs(12),
+        ));
+
+        assert_eq!(fused.battery.unwrap().case, None);
+    }
+}
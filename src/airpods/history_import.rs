@@ -0,0 +1,354 @@
+//! Import of battery readings captured outside the running app
+//!
+//! Battery history used to be lost whenever the profile was reset or the app wasn't
+//! running. Two kinds of files still hold it: JSON captured from the CLI scanner (one
+//! result, an array of them, or one per line, as saved for `rustpods diff`) and the
+//! per-device logs older versions wrote to `logs/battery`. Both are read into dated
+//! [`ImportedReading`]s here; [`BatteryIntelligence::import_history`] replays them through
+//! the significance filter, drops the events it already has and rebuilds the discharge
+//! models from the merged history.
+//!
+//! [`BatteryIntelligence::import_history`]: super::BatteryIntelligence::import_history
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde_json::Value;
+
+use crate::config::canonical_address;
+
+/// Timestamps above this are taken as milliseconds rather than seconds
+const MILLISECOND_TIMESTAMPS_FROM: u64 = 100_000_000_000;
+
+/// Formats of timestamps written in local time without an offset
+const LOCAL_TIME_FORMATS: [&str; 3] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y%m%d_%H%M%S",
+];
+
+/// Name used when a file doesn't say what the device is called
+const DEFAULT_DEVICE_NAME: &str = "AirPods";
+
+/// One dated battery reading from an imported file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedReading {
+    /// When the reading was taken
+    pub timestamp: SystemTime,
+    /// Canonical address of the device, if the file records it
+    pub address: Option<String>,
+    /// Model or device name
+    pub name: String,
+    pub left: Option<u8>,
+    pub right: Option<u8>,
+    pub case: Option<u8>,
+    pub left_charging: bool,
+    pub right_charging: bool,
+    pub case_charging: bool,
+    pub left_in_ear: bool,
+    pub right_in_ear: bool,
+    pub rssi: Option<i16>,
+}
+
+impl ImportedReading {
+    /// Whether both readings report the same device state
+    pub fn same_state(&self, other: &ImportedReading) -> bool {
+        (
+            self.left,
+            self.right,
+            self.case,
+            self.left_charging,
+            self.right_charging,
+            self.case_charging,
+            self.left_in_ear,
+            self.right_in_ear,
+        ) == (
+            other.left,
+            other.right,
+            other.case,
+            other.left_charging,
+            other.right_charging,
+            other.case_charging,
+            other.left_in_ear,
+            other.right_in_ear,
+        )
+    }
+}
+
+/// Outcome of an import
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    /// Files that were read
+    pub files_read: usize,
+    /// Files that couldn't be read, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+    /// Dated readings found in the files
+    pub readings: usize,
+    /// Readings of other devices, which were left out
+    pub other_devices: usize,
+    /// Events the profile already had
+    pub duplicates: usize,
+    /// Events added to the profile
+    pub events_added: usize,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Read {} readings from {} files: {} new events, {} already known",
+            self.readings, self.files_read, self.events_added, self.duplicates
+        )?;
+        if self.other_devices > 0 {
+            write!(f, ", {} from other devices skipped", self.other_devices)?;
+        }
+        if !self.failed.is_empty() {
+            write!(f, ", {} files unreadable", self.failed.len())?;
+        }
+        Ok(())
+    }
+}
+
+/// Readings in the file at `path`, which is either a scanner capture or a legacy log
+pub fn read_import_file(path: &Path) -> Result<Vec<ImportedReading>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let values: Vec<Value> = serde_json::Deserializer::from_str(&text)
+        .into_iter::<Value>()
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("not JSON: {}", e))?;
+
+    let legacy_device = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(legacy_device_address);
+    let mut readings = Vec::new();
+    for value in &values {
+        if value.get("entries").is_some() {
+            readings.extend(parse_legacy_log(value, legacy_device.clone()));
+        } else {
+            collect_scanner_readings(value, None, &mut readings);
+        }
+    }
+    Ok(readings)
+}
+
+/// Readings in scanner output: one result, an array of them, or one per line
+pub fn parse_scanner_capture(text: &str) -> Vec<ImportedReading> {
+    let mut readings = Vec::new();
+    for value in serde_json::Deserializer::from_str(text)
+        .into_iter::<Value>()
+        .flatten()
+    {
+        collect_scanner_readings(&value, None, &mut readings);
+    }
+    readings
+}
+
+/// Readings in a scanner result, a device entry or an array of either
+///
+/// A device takes its time from its own `timestamp` if it has one, otherwise from the
+/// `scan_timestamp` of the result it is in.
+fn collect_scanner_readings(
+    value: &Value,
+    scan_time: Option<SystemTime>,
+    readings: &mut Vec<ImportedReading>,
+) {
+    if let Some(items) = value.as_array() {
+        for item in items {
+            collect_scanner_readings(item, scan_time, readings);
+        }
+        return;
+    }
+    if let Some(devices) = value.get("devices") {
+        let scan_time = value
+            .get("scan_timestamp")
+            .and_then(parse_timestamp)
+            .or(scan_time);
+        collect_scanner_readings(devices, scan_time, readings);
+        return;
+    }
+
+    let Some(data) = value.get("airpods_data").filter(|data| data.is_object()) else {
+        return;
+    };
+    let Some(timestamp) = value
+        .get("timestamp")
+        .and_then(parse_timestamp)
+        .or(scan_time)
+    else {
+        return;
+    };
+    readings.push(ImportedReading {
+        timestamp,
+        address: value
+            .get("address")
+            .and_then(Value::as_str)
+            .and_then(canonical_address),
+        name: data
+            .get("model")
+            .and_then(Value::as_str)
+            .unwrap_or(DEFAULT_DEVICE_NAME)
+            .to_string(),
+        left: level(data, "left_battery"),
+        right: level(data, "right_battery"),
+        case: level(data, "case_battery"),
+        left_charging: flag(data, "left_charging"),
+        right_charging: flag(data, "right_charging"),
+        case_charging: flag(data, "case_charging"),
+        left_in_ear: flag(data, "left_in_ear"),
+        right_in_ear: flag(data, "right_in_ear"),
+        rssi: value
+            .get("rssi")
+            .and_then(Value::as_i64)
+            .map(|rssi| rssi as i16),
+    });
+}
+
+/// Readings in a log written by older versions to `logs/battery`
+pub fn parse_legacy_log(log: &Value, address: Option<String>) -> Vec<ImportedReading> {
+    let name = log
+        .get("device_name")
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_DEVICE_NAME);
+    log.get("entries")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            Some(ImportedReading {
+                timestamp: entry.get("timestamp").and_then(parse_timestamp)?,
+                address: address.clone(),
+                name: name.to_string(),
+                left: level(entry, "left_battery"),
+                right: level(entry, "right_battery"),
+                case: level(entry, "case_battery"),
+                left_charging: flag(entry, "left_charging"),
+                right_charging: flag(entry, "right_charging"),
+                case_charging: flag(entry, "case_charging"),
+                left_in_ear: flag(entry, "left_in_ear"),
+                right_in_ear: flag(entry, "right_in_ear"),
+                rssi: entry
+                    .get("rssi")
+                    .and_then(Value::as_i64)
+                    .map(|rssi| rssi as i16),
+            })
+        })
+        .collect()
+}
+
+/// Address of the device a legacy log belongs to
+///
+/// Legacy logs are named `battery_profile_<address>_<date>_<time>`, with the address
+/// written as a decimal number.
+fn legacy_device_address(file_stem: &str) -> Option<String> {
+    let decimal: u64 = file_stem
+        .strip_prefix("battery_profile_")?
+        .split('_')
+        .next()?
+        .parse()
+        .ok()?;
+    canonical_address(&format!("{:012x}", decimal))
+}
+
+/// A battery level, where scanners write -1 or leave it out when unknown
+fn level(value: &Value, key: &str) -> Option<u8> {
+    value
+        .get(key)
+        .and_then(Value::as_i64)
+        .filter(|level| (0..=100).contains(level))
+        .map(|level| level as u8)
+}
+
+fn flag(value: &Value, key: &str) -> bool {
+    value.get(key).and_then(Value::as_bool).unwrap_or(false)
+}
+
+/// A timestamp as RFC 3339, local date and time, Unix seconds or milliseconds, or a
+/// serialized `SystemTime`
+pub fn parse_timestamp(value: &Value) -> Option<SystemTime> {
+    match value {
+        Value::String(text) => {
+            if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+                return Some(time.into());
+            }
+            LOCAL_TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+                .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+                .map(SystemTime::from)
+                .or_else(|| text.parse::<u64>().ok().map(from_unix))
+        }
+        Value::Number(number) => number.as_u64().map(from_unix),
+        Value::Object(_) => {
+            let secs = value.get("secs_since_epoch")?.as_u64()?;
+            let nanos = value
+                .get("nanos_since_epoch")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            Some(UNIX_EPOCH + Duration::new(secs, nanos as u32))
+        }
+        _ => None,
+    }
+}
+
+fn from_unix(value: u64) -> SystemTime {
+    if value >= MILLISECOND_TIMESTAMPS_FROM {
+        UNIX_EPOCH + Duration::from_millis(value)
+    } else {
+        UNIX_EPOCH + Duration::from_secs(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanner_capture_readings_take_the_scan_time() {
+        let capture = r#"
+            {"scan_timestamp": "2025-06-16T19:22:53Z", "devices": [
+                {"address": "AA:BB:CC:DD:EE:FF", "rssi": -60, "manufacturer_data_hex": "0719",
+                 "airpods_data": {"model": "AirPods Pro", "left_battery": 80, "right_battery": 70,
+                                  "case_battery": -1, "left_in_ear": true}},
+                {"address": "11:22:33:44:55:66", "rssi": -80, "manufacturer_data_hex": "1007"}
+            ]}
+            {"scan_timestamp": 1750101773, "devices": []}
+        "#;
+        let readings = parse_scanner_capture(capture);
+
+        assert_eq!(readings.len(), 1);
+        let reading = &readings[0];
+        assert_eq!(reading.address.as_deref(), Some("aabbccddeeff"));
+        assert_eq!(reading.name, "AirPods Pro");
+        assert_eq!(
+            (reading.left, reading.right, reading.case),
+            (Some(80), Some(70), None)
+        );
+        assert!(reading.left_in_ear && !reading.right_in_ear);
+        assert_eq!(reading.rssi, Some(-60));
+        assert_eq!(
+            reading.timestamp,
+            UNIX_EPOCH + Duration::from_secs(1_750_101_773)
+        );
+    }
+
+    #[test]
+    fn test_legacy_log_address_comes_from_the_file_name() {
+        assert_eq!(
+            legacy_device_address("battery_profile_187723572702975_20250616_192253").as_deref(),
+            Some("aabbccddeeff")
+        );
+        assert_eq!(legacy_device_address("battery_profile_x_20250616"), None);
+
+        let log = serde_json::json!({"entries": [
+            {"timestamp": "2025-06-16T19:22:53Z", "left_battery": 90, "right_battery": 90,
+             "case_battery": 50, "case_charging": true},
+            {"left_battery": 80}
+        ]});
+        let readings = parse_legacy_log(&log, Some("aabbccddeeff".to_string()));
+        assert_eq!(readings.len(), 1);
+        assert!(readings[0].case_charging);
+    }
+}
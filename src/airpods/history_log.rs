@@ -0,0 +1,206 @@
+//! Append-only storage for battery history
+//!
+//! Significant events and depletion samples make up most of a battery profile and only
+//! ever grow, so rewriting them as JSON with every save costs far more than the change
+//! itself. They are kept in a separate log of bincode records instead: each save appends
+//! just the new records, and the log is compacted down to the records still in use once
+//! it has grown to several times that. The JSON profile keeps the summary and models.
+//!
+//! Each record is framed by its length as a little-endian `u32`. A frame cut short by a
+//! crash is ignored when reading and cut off before the next append, so an interrupted
+//! append loses only that record.
+
+use std::fs::OpenOptions;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::battery_intelligence::{BatteryEvent, DepletionRateSample};
+use super::profile_writer::write_atomic;
+
+/// File name of the history log, next to the profile
+pub const HISTORY_LOG_FILE: &str = "battery_history.bin";
+
+/// Largest record accepted when reading; anything bigger means the log is corrupt
+const MAX_RECORD_SIZE: usize = 64 * 1024;
+
+/// One entry in the history log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HistoryRecord {
+    /// A significant battery event
+    Event(BatteryEvent),
+    /// A measured depletion rate
+    Sample(DepletionRateSample),
+}
+
+/// Log of history records stored at a path
+#[derive(Debug, Clone)]
+pub struct HistoryLog {
+    path: PathBuf,
+}
+
+impl HistoryLog {
+    /// Log kept in `storage_dir`
+    pub fn in_dir(storage_dir: &Path) -> Self {
+        Self {
+            path: storage_dir.join(HISTORY_LOG_FILE),
+        }
+    }
+
+    /// Location of the log
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the log has been written
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    /// All records in the order they were written
+    pub fn read_all(&self) -> io::Result<Vec<HistoryRecord>> {
+        self.read_frames().map(|(records, _)| records)
+    }
+
+    /// All records, and where the complete frames end if a partial one follows them
+    ///
+    /// Pass that offset to [`truncate`](Self::truncate) before appending, or the new
+    /// records would be read as part of the partial frame.
+    pub fn read_frames(&self) -> io::Result<(Vec<HistoryRecord>, Option<u64>)> {
+        let mut data = Vec::new();
+        std::fs::File::open(&self.path)?.read_to_end(&mut data)?;
+
+        let mut records = Vec::new();
+        let mut rest = data.as_slice();
+        while rest.len() >= 4 {
+            let length = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+            if length > MAX_RECORD_SIZE || rest.len() < 4 + length {
+                break;
+            }
+            match bincode::deserialize(&rest[4..4 + length]) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("Skipping unreadable history record: {}", e),
+            }
+            rest = &rest[4 + length..];
+        }
+
+        let torn_at = (!rest.is_empty()).then(|| {
+            log::warn!(
+                "Ignoring {} bytes at the end of {}",
+                rest.len(),
+                self.path.display()
+            );
+            (data.len() - rest.len()) as u64
+        });
+        Ok((records, torn_at))
+    }
+
+    /// Add records to the end of the log
+    pub fn append(&self, records: &[HistoryRecord]) -> io::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let data = encode(records)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let complete = file.metadata()?.len();
+        let written = file.write_all(&data).and_then(|()| file.sync_data());
+        if written.is_err() {
+            // Don't leave a partial frame for the next append to follow
+            let _ = file.set_len(complete);
+        }
+        written
+    }
+
+    /// Cut the log down to its first `length` bytes
+    pub fn truncate(&self, length: u64) -> io::Result<()> {
+        let file = OpenOptions::new().write(true).open(&self.path)?;
+        file.set_len(length)?;
+        file.sync_data()
+    }
+
+    /// Replace the log with just `records`
+    pub fn rewrite(&self, records: &[HistoryRecord]) -> io::Result<()> {
+        write_atomic(&self.path, &encode(records)?)
+    }
+
+    /// Delete the log
+    pub fn remove(&self) -> io::Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Frame records for writing
+fn encode(records: &[HistoryRecord]) -> io::Result<Vec<u8>> {
+    let mut data = Vec::new();
+    for record in records {
+        let payload = bincode::serialize(record)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(&payload);
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airpods::battery_intelligence::DepletionTarget;
+    use std::time::SystemTime;
+
+    fn sample(end_percent: u8) -> HistoryRecord {
+        HistoryRecord::Sample(DepletionRateSample {
+            timestamp: SystemTime::now(),
+            minutes_per_percent: 2.5,
+            target: DepletionTarget::LeftEarbud,
+            start_percent: end_percent + 1,
+            end_percent,
+        })
+    }
+
+    fn end_percents(records: &[HistoryRecord]) -> Vec<u8> {
+        records
+            .iter()
+            .filter_map(|record| match record {
+                HistoryRecord::Sample(sample) => Some(sample.end_percent),
+                HistoryRecord::Event(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_append_compact_and_truncated_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = HistoryLog::in_dir(dir.path());
+
+        log.append(&[sample(90), sample(89)]).unwrap();
+        log.append(&[sample(88)]).unwrap();
+        assert_eq!(end_percents(&log.read_all().unwrap()), vec![90, 89, 88]);
+
+        // A crash in the middle of an append leaves a partial frame
+        let mut file = OpenOptions::new().append(true).open(log.path()).unwrap();
+        file.write_all(&[200, 0, 0, 0, 1, 2]).unwrap();
+        let (records, torn_at) = log.read_frames().unwrap();
+        assert_eq!(records.len(), 3);
+        let torn_at = torn_at.unwrap();
+        assert_eq!(torn_at + 6, std::fs::metadata(log.path()).unwrap().len());
+
+        // Records saved after the crash line up behind the last complete one
+        log.truncate(torn_at).unwrap();
+        assert_eq!(log.read_frames().unwrap().1, None);
+        log.append(&[sample(87), sample(86)]).unwrap();
+        assert_eq!(
+            end_percents(&log.read_all().unwrap()),
+            vec![90, 89, 88, 87, 86]
+        );
+
+        log.rewrite(&[sample(88)]).unwrap();
+        assert_eq!(end_percents(&log.read_all().unwrap()), vec![88]);
+    }
+}
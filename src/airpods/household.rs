@@ -0,0 +1,180 @@
+//! Battery intelligence for every AirPods set at a shared desk
+//!
+//! [`BatteryIntelligence`] learns the drain of one set. In household mode the main
+//! window lists every set nearby, so each set other than the selected one gets a
+//! controller of its own, stored in a directory of its own under the selected set's
+//! storage directory. Estimates for one set are then never applied to another.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::airpods::battery::AirPodsBatteryInfo;
+use crate::airpods::battery_intelligence::{BatteryEstimate, BatteryIntelligence};
+
+/// A set tracked in household mode
+#[derive(Debug, Clone)]
+struct TrackedSet {
+    intelligence: BatteryIntelligence,
+    /// Readings last passed to the controller, to skip saves for repeated frames
+    last_reading: Option<AirPodsBatteryInfo>,
+}
+
+/// Battery intelligence for each set beside the selected one
+#[derive(Debug, Clone)]
+pub struct HouseholdIntelligence {
+    /// Directory holding a subdirectory per set
+    root: PathBuf,
+    /// Whether controllers save in the background
+    background_saving: bool,
+    /// Sets by device identifier
+    sets: HashMap<String, TrackedSet>,
+}
+
+impl HouseholdIntelligence {
+    /// Track sets in subdirectories of `root`
+    pub fn new(root: PathBuf, background_saving: bool) -> Self {
+        Self {
+            root,
+            background_saving,
+            sets: HashMap::new(),
+        }
+    }
+
+    /// Record the latest readings of the set `device_id`, loading its profile the first
+    /// time the set is seen
+    pub fn update(&mut self, device_id: &str, airpods: &AirPodsBatteryInfo) {
+        let set = self.sets.entry(device_id.to_string()).or_insert_with(|| {
+            let directory: String = device_id
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            let mut intelligence = BatteryIntelligence::new(self.root.join(directory));
+            if self.background_saving {
+                intelligence.enable_background_saving();
+            }
+            TrackedSet {
+                intelligence,
+                last_reading: None,
+            }
+        });
+        if set
+            .last_reading
+            .as_ref()
+            .is_some_and(|last| last.same_readings(airpods))
+        {
+            return;
+        }
+
+        let level = |level: i32| (level >= 0).then(|| level.min(100) as u8);
+        set.intelligence
+            .ensure_device_profile(device_id, &airpods.name);
+        set.intelligence.update_device_battery(
+            device_id,
+            &airpods.name,
+            level(airpods.left_battery),
+            level(airpods.right_battery),
+            level(airpods.case_battery),
+            airpods.left_charging,
+            airpods.right_charging,
+            airpods.case_charging,
+            airpods.left_in_ear.unwrap_or(false),
+            airpods.right_in_ear.unwrap_or(false),
+            airpods.rssi.map(|r| r as i16),
+        );
+        if let Err(e) = set.intelligence.save() {
+            log::warn!("Failed to save battery profile for {}: {}", device_id, e);
+        }
+        set.last_reading = Some(airpods.clone());
+    }
+
+    /// Estimates for the left bud, right bud and case of the set `device_id`
+    pub fn estimates(
+        &self,
+        device_id: &str,
+    ) -> Option<(BatteryEstimate, BatteryEstimate, BatteryEstimate)> {
+        self.sets
+            .get(device_id)?
+            .intelligence
+            .get_battery_estimates()
+    }
+
+    /// Number of sets tracked
+    pub fn len(&self) -> usize {
+        self.sets.len()
+    }
+
+    /// Whether no set is tracked
+    pub fn is_empty(&self) -> bool {
+        self.sets.is_empty()
+    }
+
+    /// Wait for queued saves of every set to reach the disk
+    pub fn flush(&self) {
+        for set in self.sets.values() {
+            set.intelligence.flush();
+        }
+    }
+}
+
+/// Identifiers telling apart every set in a scan
+///
+/// Sets of the same model with default names share a stable identifier; the first keeps
+/// it, so the selected set matches as before, and the rest are told apart by address.
+pub fn distinct_device_ids(ids: Vec<String>, addresses: &[&str]) -> Vec<String> {
+    let mut distinct: Vec<String> = Vec::with_capacity(ids.len());
+    for (index, id) in ids.iter().enumerate() {
+        if ids[..index].contains(id) {
+            distinct.push(format!("{}_{}", id, addresses.get(index).unwrap_or(&"")));
+        } else {
+            distinct.push(id.clone());
+        }
+    }
+    distinct
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sets_are_tracked_separately() {
+        let ids = distinct_device_ids(
+            vec![
+                "model_airpods_pro".to_string(),
+                "model_airpods_pro".to_string(),
+                "custom_desk".to_string(),
+            ],
+            &["aa", "bb", "cc"],
+        );
+        assert_eq!(
+            ids,
+            vec!["model_airpods_pro", "model_airpods_pro_bb", "custom_desk"]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut household = HouseholdIntelligence::new(dir.path().to_path_buf(), false);
+        let mut airpods = AirPodsBatteryInfo {
+            right_battery: 70,
+            case_battery: 50,
+            case_charging: true,
+            ..crate::airpods::test_support::report("bb")
+        };
+        household.update(&ids[1], &airpods);
+        airpods.canonical_address = "cc".to_string();
+        airpods.left_battery = 20;
+        household.update(&ids[2], &airpods);
+
+        assert_eq!(household.len(), 2);
+        let (left, _, _) = household.estimates(&ids[1]).unwrap();
+        let (other_left, _, _) = household.estimates(&ids[2]).unwrap();
+        assert_eq!(left.level.round(), 80.0);
+        assert_eq!(other_left.level.round(), 20.0);
+        assert!(household.estimates("model_airpods_max").is_none());
+    }
+}
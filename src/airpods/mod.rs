@@ -3,8 +3,19 @@
 pub mod battery;
 pub mod battery_estimator;
 pub mod battery_intelligence;
+pub mod capacity;
 pub mod detector;
 mod filter;
+pub mod fusion;
+pub mod history_import;
+pub mod history_log;
+pub mod household;
+pub mod model_table;
+pub mod pairing;
+pub mod profile_snapshots;
+pub mod profile_writer;
+pub mod test_support;
+pub mod threshold_latch;
 
 pub use detector::{
     create_airpods_filter, create_custom_airpods_filter, detect_airpods, identify_airpods_type,
@@ -16,10 +27,22 @@ pub use filter::{
     airpods_with_battery_filter, AirPodsFilter, AirPodsFilterOptions, APPLE_COMPANY_ID,
 };
 
+pub use capacity::{Capacity, MahEstimate};
+
+pub use fusion::{fuse_advertisements, AdvertisementFusion};
+
+pub use history_import::{ImportReport, ImportedReading};
+
+pub use pairing::{DeviceFingerprint, FingerprintMatch};
+
+pub use profile_snapshots::{ProfileSnapshot, SnapshotReason};
+
+pub use threshold_latch::ThresholdLatch;
+
 pub use battery_intelligence::{
-    BatteryEstimate, BatteryEvent, BatteryEventType, BatteryHealthMetrics, BatteryIntelligence,
-    DeviceBatteryProfile, DischargeModel, IntelligenceSettings, SessionType, UsagePattern,
-    UsageSession,
+    AccuracyStats, BatteryEstimate, BatteryEvent, BatteryEventType, BatteryHealthMetrics,
+    BatteryIntelligence, DeviceBatteryProfile, DischargeModel, DischargeTotals, EstimationAccuracy,
+    IntelligenceSettings, MonthlyReport, SessionType, UsagePattern, UsageSession,
 };
 
 use crate::error::{AirPodsError, ErrorContext};
@@ -29,7 +52,7 @@ use serde::{Deserialize, Serialize};
 pub type Result<T> = std::result::Result<T, AirPodsError>;
 
 /// AirPods device types
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AirPodsType {
     /// Original AirPods
     AirPods1,
@@ -90,6 +113,15 @@ pub enum AirPodsChargingState {
 }
 
 impl AirPodsChargingState {
+    /// The same state seen from the other bud's perspective (left and right swapped)
+    pub fn flipped(self) -> Self {
+        match self {
+            Self::LeftCharging => Self::RightCharging,
+            Self::RightCharging => Self::LeftCharging,
+            other => other,
+        }
+    }
+
     /// Returns true if any component is charging
     pub fn is_any_charging(&self) -> bool {
         !matches!(self, Self::NotCharging)
@@ -139,9 +171,264 @@ pub fn extract_battery_level(raw_value: u8) -> Option<u8> {
     }
 }
 
-/// Helper function to parse AirPods data from manufacturer data
-pub fn parse_airpods_data(data: &[u8]) -> Result<AirPodsBattery> {
-    let _ctx = ErrorContext::new("AirPods", "parse_airpods_data")
+/// Status byte holding the flip and in-ear flags
+pub const FLIP_STATUS_OFFSET: usize = 11;
+
+/// Flip flag: set when the right bud is broadcasting, so the left and right fields
+/// are reported from its perspective and have to be swapped
+pub const FLIP_FLAG_MASK: u8 = 0x20;
+
+/// Status flag: the broadcasting (primary) bud is in an ear
+pub const PRIMARY_IN_EAR_MASK: u8 = 0x02;
+
+/// Status flag: the other (secondary) bud is in an ear
+pub const SECONDARY_IN_EAR_MASK: u8 = 0x08;
+
+/// Status flag: both buds are in the case
+pub const BOTH_IN_CASE_MASK: u8 = 0x04;
+
+/// Offsets of the battery and charging fields
+pub const LEFT_BATTERY_OFFSET: usize = 12;
+pub const RIGHT_BATTERY_OFFSET: usize = 13;
+pub const CHARGING_STATUS_OFFSET: usize = 14;
+pub const CASE_BATTERY_OFFSET: usize = 15;
+
+/// Lid byte: open counter in the low bits, closed flag above it
+pub const LID_STATUS_OFFSET: usize = 16;
+pub const LID_OPEN_COUNT_MASK: u8 = 0x07;
+pub const LID_CLOSED_MASK: u8 = 0x08;
+
+/// Device color code
+pub const COLOR_OFFSET: usize = 17;
+
+/// Connection state code
+pub const CONNECTION_STATE_OFFSET: usize = 18;
+
+/// Name of the proximity message field at byte `offset`, for annotating raw frames
+pub fn proximity_field_name(offset: usize) -> &'static str {
+    match offset {
+        0 => "message type",
+        1 => "message length",
+        2 => "prefix",
+        3 | 4 => "model ID",
+        FLIP_STATUS_OFFSET => "status (flip, in-ear)",
+        LEFT_BATTERY_OFFSET => "left battery",
+        RIGHT_BATTERY_OFFSET => "right battery",
+        CHARGING_STATUS_OFFSET => "charging status",
+        CASE_BATTERY_OFFSET => "case battery",
+        LID_STATUS_OFFSET => "lid",
+        COLOR_OFFSET => "color",
+        CONNECTION_STATE_OFFSET => "connection state",
+        _ => "unknown",
+    }
+}
+
+/// Whether the advertisement is reported from the right bud's perspective
+pub fn is_flipped(data: &[u8]) -> bool {
+    data.get(FLIP_STATUS_OFFSET)
+        .is_some_and(|status| status & FLIP_FLAG_MASK != 0)
+}
+
+/// A single bud of a pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Bud {
+    /// Left bud
+    Left,
+    /// Right bud
+    Right,
+}
+
+impl std::fmt::Display for Bud {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bud::Left => write!(f, "Left"),
+            Bud::Right => write!(f, "Right"),
+        }
+    }
+}
+
+/// Housing color reported in the proximity message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeviceColor {
+    /// White
+    White,
+    /// Black
+    Black,
+    /// Red
+    Red,
+    /// Blue
+    Blue,
+    /// Pink
+    Pink,
+    /// Gray
+    Gray,
+    /// Silver
+    Silver,
+    /// Gold
+    Gold,
+    /// Rose gold
+    RoseGold,
+    /// Space gray
+    SpaceGray,
+    /// Dark blue
+    DarkBlue,
+    /// Light blue
+    LightBlue,
+    /// Yellow
+    Yellow,
+    /// A code this version doesn't know
+    Other(u8),
+}
+
+impl DeviceColor {
+    /// Map a color code from the proximity message
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x00 => Self::White,
+            0x01 => Self::Black,
+            0x02 => Self::Red,
+            0x03 => Self::Blue,
+            0x04 => Self::Pink,
+            0x05 => Self::Gray,
+            0x06 => Self::Silver,
+            0x07 => Self::Gold,
+            0x08 => Self::RoseGold,
+            0x09 => Self::SpaceGray,
+            0x0A => Self::DarkBlue,
+            0x0B => Self::LightBlue,
+            0x0C => Self::Yellow,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceColor::White => write!(f, "White"),
+            DeviceColor::Black => write!(f, "Black"),
+            DeviceColor::Red => write!(f, "Red"),
+            DeviceColor::Blue => write!(f, "Blue"),
+            DeviceColor::Pink => write!(f, "Pink"),
+            DeviceColor::Gray => write!(f, "Gray"),
+            DeviceColor::Silver => write!(f, "Silver"),
+            DeviceColor::Gold => write!(f, "Gold"),
+            DeviceColor::RoseGold => write!(f, "Rose Gold"),
+            DeviceColor::SpaceGray => write!(f, "Space Gray"),
+            DeviceColor::DarkBlue => write!(f, "Dark Blue"),
+            DeviceColor::LightBlue => write!(f, "Light Blue"),
+            DeviceColor::Yellow => write!(f, "Yellow"),
+            DeviceColor::Other(code) => write!(f, "Unknown (0x{:02X})", code),
+        }
+    }
+}
+
+/// What the pair is doing with its connected host
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProximityConnectionState {
+    /// Not connected to a host
+    Disconnected,
+    /// Connected but idle
+    Idle,
+    /// Playing audio
+    Music,
+    /// In a call
+    Call,
+    /// A call is ringing
+    Ringing,
+    /// Hanging up a call
+    HangingUp,
+    /// A code this version doesn't know
+    Other(u8),
+}
+
+impl ProximityConnectionState {
+    /// Map a connection state code from the proximity message
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0x00 => Self::Disconnected,
+            0x04 => Self::Idle,
+            0x05 => Self::Music,
+            0x06 => Self::Call,
+            0x07 => Self::Ringing,
+            0x09 => Self::HangingUp,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl std::fmt::Display for ProximityConnectionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProximityConnectionState::Disconnected => write!(f, "Disconnected"),
+            ProximityConnectionState::Idle => write!(f, "Idle"),
+            ProximityConnectionState::Music => write!(f, "Playing audio"),
+            ProximityConnectionState::Call => write!(f, "In a call"),
+            ProximityConnectionState::Ringing => write!(f, "Ringing"),
+            ProximityConnectionState::HangingUp => write!(f, "Hanging up"),
+            ProximityConnectionState::Other(code) => write!(f, "Unknown (0x{:02X})", code),
+        }
+    }
+}
+
+/// Proximity message fields beyond battery and charging
+///
+/// Fields past the battery bytes are optional because older models send shorter
+/// messages. The encrypted tail of the message can't be read without the pair's key
+/// and is ignored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProximityExtras {
+    /// Bud that sent the advertisement
+    pub primary: Bud,
+    /// Whether the primary bud is in an ear
+    pub primary_in_ear: bool,
+    /// Whether the secondary bud is in an ear
+    pub secondary_in_ear: bool,
+    /// Whether both buds are in the case
+    pub both_in_case: bool,
+    /// Whether the case lid is open
+    pub lid_open: Option<bool>,
+    /// Rolling count of lid openings (wraps at 8)
+    pub lid_open_count: Option<u8>,
+    /// Housing color
+    pub color: Option<DeviceColor>,
+    /// Connection state with the host
+    pub connection_state: Option<ProximityConnectionState>,
+}
+
+impl ProximityExtras {
+    /// Whether the left bud is in an ear
+    pub fn left_in_ear(&self) -> bool {
+        match self.primary {
+            Bud::Left => self.primary_in_ear,
+            Bud::Right => self.secondary_in_ear,
+        }
+    }
+
+    /// Whether the right bud is in an ear
+    pub fn right_in_ear(&self) -> bool {
+        match self.primary {
+            Bud::Left => self.secondary_in_ear,
+            Bud::Right => self.primary_in_ear,
+        }
+    }
+}
+
+/// A decoded proximity pairing message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProximityMessage {
+    /// Battery and charging state, normalized to physical sides
+    pub battery: AirPodsBattery,
+    /// Status, lid, color and connection fields
+    pub extras: ProximityExtras,
+}
+
+/// Decode the proximity pairing message from Apple manufacturer data
+///
+/// Left and right values are normalized using the flip flag, so they always refer to
+/// the physical left and right buds whichever one is broadcasting.
+pub fn decode_proximity_message(data: &[u8]) -> Result<ProximityMessage> {
+    let _ctx = ErrorContext::new("AirPods", "decode_proximity_message")
         .with_metadata("data_length", data.len().to_string())
         .with_metadata("data_hex", format!("{:02X?}", data));
 
@@ -154,65 +441,21 @@ pub fn parse_airpods_data(data: &[u8]) -> Result<AirPodsBattery> {
         )));
     }
 
-    // Offset constants for battery data
-    const LEFT_BATTERY_OFFSET: usize = 12;
-    const RIGHT_BATTERY_OFFSET: usize = 13;
-    const CASE_BATTERY_OFFSET: usize = 15;
-    const CHARGING_STATUS_OFFSET: usize = 14;
-
-    // Parse left earbud battery
-    let left_battery = if data.len() > LEFT_BATTERY_OFFSET {
-        extract_battery_level(data[LEFT_BATTERY_OFFSET])
-    } else {
-        log::debug!(
-            "Data too short for left battery value at offset {}",
-            LEFT_BATTERY_OFFSET
-        );
-        None
-    };
-
-    // Parse right earbud battery
-    let right_battery = if data.len() > RIGHT_BATTERY_OFFSET {
-        extract_battery_level(data[RIGHT_BATTERY_OFFSET])
-    } else {
-        log::debug!(
-            "Data too short for right battery value at offset {}",
-            RIGHT_BATTERY_OFFSET
-        );
-        None
-    };
-
-    // Parse case battery
-    let case_battery = if data.len() > CASE_BATTERY_OFFSET {
-        extract_battery_level(data[CASE_BATTERY_OFFSET])
-    } else {
-        log::debug!(
-            "Data too short for case battery value at offset {}",
-            CASE_BATTERY_OFFSET
-        );
-        None
-    };
-
-    // Parse charging status
-    let charging_status = if data.len() > CHARGING_STATUS_OFFSET {
-        let raw_status = data[CHARGING_STATUS_OFFSET];
-        match raw_status {
-            0 => Some(AirPodsChargingState::NotCharging),
-            1 => Some(AirPodsChargingState::LeftCharging),
-            2 => Some(AirPodsChargingState::RightCharging),
-            4 => Some(AirPodsChargingState::CaseCharging),
-            5 => Some(AirPodsChargingState::BothBudsCharging),
-            _ => {
-                log::debug!("Unknown charging status value: {}", raw_status);
-                None
-            }
+    let left_battery = extract_battery_level(data[LEFT_BATTERY_OFFSET]);
+    let right_battery = extract_battery_level(data[RIGHT_BATTERY_OFFSET]);
+    let case_battery = extract_battery_level(data[CASE_BATTERY_OFFSET]);
+
+    let raw_status = data[CHARGING_STATUS_OFFSET];
+    let charging_status = match raw_status {
+        0 => Some(AirPodsChargingState::NotCharging),
+        1 => Some(AirPodsChargingState::LeftCharging),
+        2 => Some(AirPodsChargingState::RightCharging),
+        4 => Some(AirPodsChargingState::CaseCharging),
+        5 => Some(AirPodsChargingState::BothBudsCharging),
+        _ => {
+            log::debug!("Unknown charging status value: {}", raw_status);
+            None
         }
-    } else {
-        log::debug!(
-            "Data too short for charging status at offset {}",
-            CHARGING_STATUS_OFFSET
-        );
-        None
     };
 
     // Create battery info object - if we have at least some data
@@ -222,12 +465,48 @@ pub fn parse_airpods_data(data: &[u8]) -> Result<AirPodsBattery> {
         ));
     }
 
-    Ok(AirPodsBattery {
-        left: left_battery,
-        right: right_battery,
-        case: case_battery,
-        charging: charging_status,
-    })
+    // The broadcasting bud reports itself first; swap back to physical sides
+    let flipped = is_flipped(data);
+    let battery = if flipped {
+        AirPodsBattery {
+            left: right_battery,
+            right: left_battery,
+            case: case_battery,
+            charging: charging_status.map(AirPodsChargingState::flipped),
+        }
+    } else {
+        AirPodsBattery {
+            left: left_battery,
+            right: right_battery,
+            case: case_battery,
+            charging: charging_status,
+        }
+    };
+
+    let status = data[FLIP_STATUS_OFFSET];
+    let lid = data.get(LID_STATUS_OFFSET);
+    let extras = ProximityExtras {
+        primary: if flipped { Bud::Right } else { Bud::Left },
+        primary_in_ear: status & PRIMARY_IN_EAR_MASK != 0,
+        secondary_in_ear: status & SECONDARY_IN_EAR_MASK != 0,
+        both_in_case: status & BOTH_IN_CASE_MASK != 0,
+        lid_open: lid.map(|lid| lid & LID_CLOSED_MASK == 0),
+        lid_open_count: lid.map(|lid| lid & LID_OPEN_COUNT_MASK),
+        color: data.get(COLOR_OFFSET).copied().map(DeviceColor::from_code),
+        connection_state: data
+            .get(CONNECTION_STATE_OFFSET)
+            .copied()
+            .map(ProximityConnectionState::from_code),
+    };
+
+    Ok(ProximityMessage { battery, extras })
+}
+
+/// Helper function to parse AirPods battery data from manufacturer data
+///
+/// Returns the battery part of [`decode_proximity_message`].
+pub fn parse_airpods_data(data: &[u8]) -> Result<AirPodsBattery> {
+    decode_proximity_message(data).map(|message| message.battery)
 }
 
 /// Struct version of charging status for individual components
@@ -365,6 +644,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_airpods_data_flipped() {
+        let mut data = vec![0u8; 27];
+        data[12] = 9; // First bud field: 90%
+        data[13] = 4; // Second bud field: 40%
+        data[14] = 1; // First bud charging
+        data[15] = 6;
+
+        let unflipped = parse_airpods_data(&data).unwrap();
+        assert_eq!(unflipped.left, Some(90));
+        assert_eq!(unflipped.right, Some(40));
+        assert_eq!(unflipped.charging, Some(AirPodsChargingState::LeftCharging));
+
+        // Broadcast from the right bud: the same physical values in swapped fields
+        data[FLIP_STATUS_OFFSET] = FLIP_FLAG_MASK;
+        data[12] = 4;
+        data[13] = 9;
+        data[14] = 2;
+        assert!(is_flipped(&data));
+        assert_eq!(parse_airpods_data(&data).unwrap(), unflipped);
+    }
+
+    #[test]
+    fn test_decode_proximity_extras() {
+        let mut data = vec![0u8; 27];
+        data[FLIP_STATUS_OFFSET] = FLIP_FLAG_MASK | PRIMARY_IN_EAR_MASK;
+        data[12] = 8;
+        data[13] = 6;
+        data[15] = 5;
+        data[LID_STATUS_OFFSET] = 0x03; // Open, third opening
+        data[COLOR_OFFSET] = 0x01;
+        data[CONNECTION_STATE_OFFSET] = 0x05;
+
+        let message = decode_proximity_message(&data).unwrap();
+        let extras = message.extras;
+        assert_eq!(extras.primary, Bud::Right);
+        assert!(extras.right_in_ear());
+        assert!(!extras.left_in_ear());
+        assert_eq!(extras.lid_open, Some(true));
+        assert_eq!(extras.lid_open_count, Some(3));
+        assert_eq!(extras.color, Some(DeviceColor::Black));
+        assert_eq!(
+            extras.connection_state,
+            Some(ProximityConnectionState::Music)
+        );
+        assert_eq!(message.battery.left, Some(60));
+
+        // Older, shorter messages have no lid, color or connection fields
+        let extras = decode_proximity_message(&data[..16]).unwrap().extras;
+        assert_eq!(extras.lid_open, None);
+        assert_eq!(extras.color, None);
+        assert_eq!(extras.connection_state, None);
+    }
+
     #[test]
     fn test_extract_battery_percentage() {
         assert_eq!(extract_battery_level(0), Some(0));
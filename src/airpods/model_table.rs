@@ -0,0 +1,259 @@
+//! Model table: which advertisement prefix and model ID belong to which AirPods
+//!
+//! Apple and Beats release new models more often than RustPods is released, so the
+//! mapping from the bytes in an advertisement to a model lives in a data file rather
+//! than in code. The table in `assets/models.json` is bundled with the application; a
+//! `models.json` in the configuration directory adds entries or replaces bundled ones
+//! with the same model ID or prefix, so a new model can be recognized by editing a file.
+//!
+//! A new model has to be given one of the existing [`AirPodsType`]s, which decide how
+//! its batteries are shown; its name comes from the table.
+
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::capacity::Capacity;
+use super::{decode_proximity_message, AirPodsType};
+
+/// Type byte of the proximity pairing messages AirPods and Beats advertise
+const PROXIMITY_PAIRING: u8 = 0x07;
+
+/// Table bundled with this build
+const BUNDLED_TABLE: &str = include_str!("../../assets/models.json");
+
+/// Name of a model ID
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModelEntry {
+    /// Model ID as four hex digits, e.g. `"2014"`
+    pub model_id: String,
+    /// Name shown for the model
+    pub name: String,
+    /// Nominal battery capacity, for estimates in mAh
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<Capacity>,
+}
+
+/// A name hint that picks a different type for devices sharing a prefix
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NameHint {
+    /// Texts any of which the device name has to contain
+    pub contains: Vec<String>,
+    /// Type of a device whose name matches
+    pub device_type: AirPodsType,
+}
+
+/// Device type advertised under a two-byte prefix
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrefixEntry {
+    /// First two bytes of the manufacturer data as hex, e.g. `"0E19"`
+    pub prefix: String,
+    /// Type of a named device no hint matches
+    pub device_type: AirPodsType,
+    /// Type of a device without a name, if different
+    #[serde(default)]
+    pub unnamed_type: Option<AirPodsType>,
+    /// Hints for models sharing the prefix, checked in order
+    #[serde(default)]
+    pub name_hints: Vec<NameHint>,
+}
+
+impl PrefixEntry {
+    /// Type of a device with this prefix and `name`
+    pub fn device_type(&self, name: Option<&str>) -> AirPodsType {
+        let Some(name) = name else {
+            return self
+                .unnamed_type
+                .clone()
+                .unwrap_or_else(|| self.device_type.clone());
+        };
+        self.name_hints
+            .iter()
+            .find(|hint| {
+                hint.contains
+                    .iter()
+                    .any(|text| name.contains(text.as_str()))
+            })
+            .map_or_else(|| self.device_type.clone(), |hint| hint.device_type.clone())
+    }
+}
+
+/// Every known model ID and prefix
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ModelTable {
+    /// Format version
+    pub version: u32,
+    /// Names of model IDs
+    #[serde(default)]
+    pub models: Vec<ModelEntry>,
+    /// Types of advertisement prefixes
+    #[serde(default)]
+    pub prefixes: Vec<PrefixEntry>,
+}
+
+impl ModelTable {
+    /// Table bundled with this build
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_TABLE).unwrap_or_default()
+    }
+
+    /// Bundled table with the user's table from [`override_path`] merged in
+    pub fn load() -> Self {
+        let mut table = Self::bundled();
+        let path = override_path();
+        let Ok(json) = std::fs::read_to_string(&path) else {
+            return table;
+        };
+        match serde_json::from_str::<ModelTable>(&json) {
+            Ok(user) => {
+                log::info!(
+                    "Loaded {} model(s) and {} prefix(es) from {}",
+                    user.models.len(),
+                    user.prefixes.len(),
+                    path.display()
+                );
+                table.merge(user);
+            }
+            Err(e) => log::warn!("Ignoring unreadable model table {}: {}", path.display(), e),
+        }
+        table
+    }
+
+    /// Table the detector uses, loaded the first time it is needed
+    pub fn global() -> &'static Self {
+        static TABLE: OnceLock<ModelTable> = OnceLock::new();
+        TABLE.get_or_init(Self::load)
+    }
+
+    /// Add `other`'s entries, replacing entries for the same model ID or prefix
+    pub fn merge(&mut self, other: ModelTable) {
+        for model in other.models {
+            let id = parse_model_id(&model.model_id);
+            self.models
+                .retain(|existing| id.is_none() || parse_model_id(&existing.model_id) != id);
+            self.models.push(model);
+        }
+        for prefix in other.prefixes {
+            let bytes = parse_prefix(&prefix.prefix);
+            self.prefixes
+                .retain(|existing| bytes.is_none() || parse_prefix(&existing.prefix) != bytes);
+            self.prefixes.push(prefix);
+        }
+    }
+
+    /// Entry for a model ID, if the table has it
+    fn model(&self, model_id: u16) -> Option<&ModelEntry> {
+        self.models
+            .iter()
+            .find(|model| parse_model_id(&model.model_id) == Some(model_id))
+    }
+
+    /// Name of a model ID, if the table has it
+    pub fn model_name(&self, model_id: u16) -> Option<&str> {
+        self.model(model_id).map(|model| model.name.as_str())
+    }
+
+    /// Nominal battery capacity of a model ID, if the table has one
+    pub fn capacity(&self, model_id: u16) -> Option<Capacity> {
+        self.model(model_id).and_then(|model| model.capacity)
+    }
+
+    /// Model ID of a proximity pairing message from a model the table doesn't know
+    ///
+    /// Only frames that decode like AirPods battery reports count, so other Apple
+    /// devices' advertisements aren't taken for new headphones.
+    pub fn unknown_model(&self, payload: &[u8]) -> Option<u16> {
+        if payload.first() != Some(&PROXIMITY_PAIRING) || decode_proximity_message(payload).is_err()
+        {
+            return None;
+        }
+        let model_id = u16::from_le_bytes([payload[3], payload[4]]);
+        self.model_name(model_id).is_none().then_some(model_id)
+    }
+
+    /// Entry for the prefix `data` starts with, if the table has it
+    pub fn prefix(&self, data: &[u8]) -> Option<&PrefixEntry> {
+        let start = data.get(0..2)?;
+        self.prefixes
+            .iter()
+            .find(|entry| parse_prefix(&entry.prefix).is_some_and(|bytes| bytes == start))
+    }
+}
+
+/// Where the user's table is looked for
+pub fn override_path() -> PathBuf {
+    dirs_next::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rustpods")
+        .join("models.json")
+}
+
+/// Model ID from hex, with or without `0x`
+fn parse_model_id(text: &str) -> Option<u16> {
+    let digits = text.trim();
+    let digits = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .unwrap_or(digits);
+    u16::from_str_radix(digits, 16).ok()
+}
+
+/// Two prefix bytes from hex, ignoring spaces between them
+fn parse_prefix(text: &str) -> Option<[u8; 2]> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    Some([
+        u8::from_str_radix(&digits[0..2], 16).ok()?,
+        u8::from_str_radix(&digits[2..4], 16).ok()?,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_table_and_overrides() {
+        let mut table = ModelTable::bundled();
+        assert_eq!(table.model_name(0x2014), Some("AirPods Pro 2"));
+        assert_eq!(table.model_name(0xFFFF), None);
+
+        let shared = table.prefix(&[0x07, 0x19, 0x01]).unwrap();
+        assert_eq!(shared.device_type(None), AirPodsType::AirPods2);
+        assert_eq!(shared.device_type(Some("AirPods")), AirPodsType::AirPods1);
+        assert_eq!(
+            shared.device_type(Some("AirPods II")),
+            AirPodsType::AirPods2
+        );
+        assert!(table.prefix(&[0x07]).is_none());
+
+        let user: ModelTable = serde_json::from_str(
+            r#"{"version": 1,
+                "models": [{"model_id": "0x2014", "name": "My Pros"},
+                           {"model_id": "2019", "name": "AirPods 4"}],
+                "prefixes": [{"prefix": "19 19", "device_type": "AirPods3"}]}"#,
+        )
+        .unwrap();
+        table.merge(user);
+        assert_eq!(table.model_name(0x2014), Some("My Pros"));
+        assert_eq!(table.model_name(0x2019), Some("AirPods 4"));
+        assert_eq!(
+            table.prefix(&[0x19, 0x19]).unwrap().device_type(None),
+            AirPodsType::AirPods3
+        );
+        assert_eq!(table.models.len(), ModelTable::bundled().models.len() + 1);
+
+        let unknown = [
+            0x07, 0x19, 0x01, 0x99, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x05, 0x07,
+            0x05, 0x08, 0x01, 0x01, 0x00,
+        ];
+        assert_eq!(table.unknown_model(&unknown), Some(0x2099));
+        let mut known = unknown;
+        known[3] = 0x14;
+        assert_eq!(table.unknown_model(&known), None);
+        assert_eq!(table.unknown_model(&[0x10, 0x05, 0x01]), None);
+    }
+}
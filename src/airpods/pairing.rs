@@ -0,0 +1,158 @@
+//! Fingerprints for the user's own AirPods
+//!
+//! AirPods rotate their random address, so a pair can't be recognized by address
+//! alone, and the model name is shared by every pair of the same model. When the user
+//! pairs RustPods with their AirPods, the identifying data seen in the scan is kept:
+//! model, color and every address the pair has advertised from. Later scans are
+//! matched against that fingerprint, and newly seen addresses are added to it.
+
+use serde::{Deserialize, Serialize};
+
+use super::battery::AirPodsBatteryInfo;
+
+/// Addresses remembered per fingerprint, newest last
+pub const MAX_ADDRESS_HISTORY: usize = 16;
+
+/// Identifying data recorded when the user paired their AirPods
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeviceFingerprint {
+    /// Apple model identifier from the proximity message
+    pub model_id: u16,
+    /// Model name at pairing time, for display
+    pub model_name: String,
+    /// Color code from the proximity message, if the pair reports one
+    #[serde(default)]
+    pub color: Option<i32>,
+    /// Canonical addresses (lowercase hex, no separators) the pair has used, newest last
+    #[serde(default)]
+    pub addresses: Vec<String>,
+}
+
+/// How a scanned device matched a fingerprint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FingerprintMatch {
+    /// Same model and color, but an address the pair hasn't used before
+    Characteristics,
+    /// An address the pair has used before
+    Address,
+}
+
+impl DeviceFingerprint {
+    /// Record the identifying data of a scanned pair
+    pub fn from_airpods(airpods: &AirPodsBatteryInfo) -> Self {
+        let mut fingerprint = Self {
+            model_id: airpods.model_id,
+            model_name: airpods.name.clone(),
+            color: airpods.color,
+            addresses: Vec::new(),
+        };
+        fingerprint.observe(airpods);
+        fingerprint
+    }
+
+    /// Identifier that stays the same across address rotations
+    pub fn stable_id(&self) -> String {
+        let first = self.addresses.first().map(String::as_str).unwrap_or("");
+        format!("paired_{:04x}_{}", self.model_id, first)
+    }
+
+    /// Whether the pair has advertised from this canonical address
+    pub fn knows_address(&self, address: &str) -> bool {
+        !address.is_empty() && self.addresses.iter().any(|known| known == address)
+    }
+
+    /// How a scanned device matches, if at all
+    pub fn match_device(&self, airpods: &AirPodsBatteryInfo) -> Option<FingerprintMatch> {
+        if self.knows_address(&airpods.canonical_address) {
+            return Some(FingerprintMatch::Address);
+        }
+        if airpods.model_id != self.model_id {
+            return None;
+        }
+        match (self.color, airpods.color) {
+            (Some(known), Some(seen)) if known != seen => None,
+            _ => Some(FingerprintMatch::Characteristics),
+        }
+    }
+
+    /// The paired device among scan results
+    ///
+    /// A known address wins; otherwise the strongest signal among devices with the
+    /// same model and color is taken, since the user's own pair is usually closest.
+    pub fn find<'a>(&self, devices: &'a [AirPodsBatteryInfo]) -> Option<&'a AirPodsBatteryInfo> {
+        devices
+            .iter()
+            .filter_map(|airpods| self.match_device(airpods).map(|kind| (kind, airpods)))
+            .max_by_key(|(kind, airpods)| (*kind, airpods.rssi.unwrap_or(i32::MIN)))
+            .map(|(_, airpods)| airpods)
+    }
+
+    /// Add what a matched scan reported; returns whether anything changed
+    pub fn observe(&mut self, airpods: &AirPodsBatteryInfo) -> bool {
+        let mut changed = false;
+        if self.color.is_none() && airpods.color.is_some() {
+            self.color = airpods.color;
+            changed = true;
+        }
+        let address = &airpods.canonical_address;
+        if !address.is_empty() && !self.knows_address(address) {
+            self.addresses.push(address.clone());
+            // Keep the first address; the stable id is derived from it
+            if self.addresses.len() > MAX_ADDRESS_HISTORY {
+                self.addresses.remove(1);
+            }
+            changed = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn airpods(address: &str, model_id: u16, color: Option<i32>, rssi: i32) -> AirPodsBatteryInfo {
+        AirPodsBatteryInfo {
+            model_id,
+            color,
+            rssi: Some(rssi),
+            ..crate::airpods::test_support::report(address)
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_follows_rotating_address() {
+        let mut fingerprint =
+            DeviceFingerprint::from_airpods(&airpods("5826d745ad8b", 0x0e20, Some(0), -50));
+        let id = fingerprint.stable_id();
+
+        let scan = [
+            airpods("112233445566", 0x0e20, Some(1), -40),
+            airpods("aabbccddeeff", 0x0e20, Some(0), -55),
+            airpods("665544332211", 0x1420, Some(0), -30),
+        ];
+        let found = fingerprint.find(&scan).unwrap();
+        assert_eq!(found.canonical_address, "aabbccddeeff");
+
+        assert!(fingerprint.observe(found));
+        assert_eq!(
+            fingerprint.match_device(found),
+            Some(FingerprintMatch::Address)
+        );
+        assert_eq!(fingerprint.stable_id(), id);
+    }
+
+    #[test]
+    fn test_known_address_beats_signal() {
+        let fingerprint =
+            DeviceFingerprint::from_airpods(&airpods("5826d745ad8b", 0x0e20, None, -50));
+        let scan = [
+            airpods("aabbccddeeff", 0x0e20, None, -30),
+            airpods("5826d745ad8b", 0x0e20, None, -70),
+        ];
+        assert_eq!(
+            fingerprint.find(&scan).unwrap().canonical_address,
+            "5826d745ad8b"
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Snapshots of the battery intelligence files, taken before they are rewritten
+//!
+//! Compacting the history log, migrating a profile from an older format, importing and
+//! purging all rewrite or delete the files in the battery intelligence folder. Just
+//! before, the files are copied into `snapshots/<time>_<reason>`, so a bad migration or
+//! an accidental purge can be rolled back from the advanced settings. Only the newest
+//! [`MAX_SNAPSHOTS`] are kept.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+
+/// Folder inside the battery intelligence folder holding the snapshots
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Snapshots kept; older ones are deleted
+pub const MAX_SNAPSHOTS: usize = 10;
+
+/// Local time at the start of a snapshot's folder name
+const SNAPSHOT_TIME_FORMAT: &str = "%Y%m%d_%H%M%S_%3f";
+
+/// What was about to happen when a snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotReason {
+    /// The history log was compacted
+    Compaction,
+    /// A profile was converted from an older format
+    Migration,
+    /// All battery data was deleted
+    Purge,
+    /// A profile or history was imported over the current one
+    Import,
+    /// Another snapshot was restored
+    Rollback,
+}
+
+impl SnapshotReason {
+    const ALL: [SnapshotReason; 5] = [
+        SnapshotReason::Compaction,
+        SnapshotReason::Migration,
+        SnapshotReason::Purge,
+        SnapshotReason::Import,
+        SnapshotReason::Rollback,
+    ];
+
+    /// Name used in the snapshot's folder name
+    fn slug(self) -> &'static str {
+        match self {
+            SnapshotReason::Compaction => "compaction",
+            SnapshotReason::Migration => "migration",
+            SnapshotReason::Purge => "purge",
+            SnapshotReason::Import => "import",
+            SnapshotReason::Rollback => "rollback",
+        }
+    }
+}
+
+impl fmt::Display for SnapshotReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotReason::Compaction => write!(f, "before compacting history"),
+            SnapshotReason::Migration => write!(f, "before converting the profile"),
+            SnapshotReason::Purge => write!(f, "before deleting all data"),
+            SnapshotReason::Import => write!(f, "before importing"),
+            SnapshotReason::Rollback => write!(f, "before restoring a snapshot"),
+        }
+    }
+}
+
+/// A saved copy of the battery intelligence files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSnapshot {
+    /// Folder holding the copied files
+    pub path: PathBuf,
+    /// When it was taken, local time
+    pub taken: NaiveDateTime,
+    /// What was about to happen
+    pub reason: SnapshotReason,
+}
+
+impl ProfileSnapshot {
+    /// Snapshot stored in the folder at `path`, if it is named like one
+    fn from_dir(path: PathBuf) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let reason = SnapshotReason::ALL
+            .into_iter()
+            .find(|reason| name.ends_with(&format!("_{}", reason.slug())))?;
+        let time = name.strip_suffix(reason.slug())?.strip_suffix('_')?;
+        let taken = NaiveDateTime::parse_from_str(time, SNAPSHOT_TIME_FORMAT).ok()?;
+        Some(Self {
+            path,
+            taken,
+            reason,
+        })
+    }
+}
+
+impl fmt::Display for ProfileSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, {}",
+            self.taken.format("%Y-%m-%d %H:%M"),
+            self.reason
+        )
+    }
+}
+
+/// Files in the battery intelligence folder that a snapshot holds
+fn data_files(storage_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(storage_dir)? {
+        let path = entry?.path();
+        let leftover = path
+            .extension()
+            .is_some_and(|ext| ext == "tmp" || ext == "bak");
+        if path.is_file() && !leftover {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Copy the files in `storage_dir` into a new snapshot
+///
+/// Returns `None` when there is nothing to copy yet. The oldest snapshots are deleted
+/// beyond [`MAX_SNAPSHOTS`].
+pub fn take_snapshot(
+    storage_dir: &Path,
+    reason: SnapshotReason,
+) -> io::Result<Option<ProfileSnapshot>> {
+    if !storage_dir.exists() {
+        return Ok(None);
+    }
+    let files = data_files(storage_dir)?;
+    if files.is_empty() {
+        return Ok(None);
+    }
+
+    let taken = Local::now().naive_local();
+    let path = storage_dir.join(SNAPSHOT_DIR).join(format!(
+        "{}_{}",
+        taken.format(SNAPSHOT_TIME_FORMAT),
+        reason.slug()
+    ));
+    fs::create_dir_all(&path)?;
+    for file in &files {
+        if let Some(name) = file.file_name() {
+            fs::copy(file, path.join(name))?;
+        }
+    }
+    log::info!(
+        "Saved battery data snapshot {} ({} files)",
+        path.display(),
+        files.len()
+    );
+
+    for old in list_snapshots(storage_dir).iter().skip(MAX_SNAPSHOTS) {
+        if let Err(e) = fs::remove_dir_all(&old.path) {
+            log::warn!(
+                "Failed to delete old snapshot {}: {}",
+                old.path.display(),
+                e
+            );
+        }
+    }
+
+    Ok(ProfileSnapshot::from_dir(path))
+}
+
+/// Snapshots in `storage_dir`, newest first
+pub fn list_snapshots(storage_dir: &Path) -> Vec<ProfileSnapshot> {
+    let Ok(entries) = fs::read_dir(storage_dir.join(SNAPSHOT_DIR)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<ProfileSnapshot> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(ProfileSnapshot::from_dir)
+        .collect();
+    snapshots.sort_by(|a, b| b.taken.cmp(&a.taken));
+    snapshots
+}
+
+/// Replace the files in `storage_dir` with those in `snapshot`
+///
+/// Each file is copied next to its target first and then renamed over it; files the
+/// snapshot doesn't have are removed afterwards.
+pub fn restore_snapshot_files(storage_dir: &Path, snapshot: &ProfileSnapshot) -> io::Result<()> {
+    let saved = data_files(&snapshot.path)?;
+    if saved.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Snapshot {} holds no files", snapshot.path.display()),
+        ));
+    }
+
+    let current = data_files(storage_dir)?;
+    for file in &saved {
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        let target = storage_dir.join(name);
+        let staged = target.with_extension("tmp");
+        fs::copy(file, &staged)?;
+        fs::rename(&staged, &target)?;
+    }
+    for file in current {
+        if !saved
+            .iter()
+            .any(|saved| saved.file_name() == file.file_name())
+        {
+            fs::remove_file(&file)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let dir = tempdir().unwrap();
+        let storage = dir.path();
+        assert_eq!(take_snapshot(storage, SnapshotReason::Purge).unwrap(), None);
+
+        fs::write(storage.join("battery_profile.json"), "before").unwrap();
+        fs::write(storage.join("battery_history.bin"), "history").unwrap();
+        let snapshot = take_snapshot(storage, SnapshotReason::Purge)
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot.reason, SnapshotReason::Purge);
+        assert_eq!(list_snapshots(storage), vec![snapshot.clone()]);
+
+        fs::write(storage.join("battery_profile.json"), "after").unwrap();
+        fs::remove_file(storage.join("battery_history.bin")).unwrap();
+        fs::write(storage.join("device_1234abcd_profile.json"), "new").unwrap();
+        restore_snapshot_files(storage, &snapshot).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(storage.join("battery_profile.json")).unwrap(),
+            "before"
+        );
+        assert!(storage.join("battery_history.bin").exists());
+        assert!(!storage.join("device_1234abcd_profile.json").exists());
+    }
+
+    #[test]
+    fn test_old_snapshots_are_pruned() {
+        let dir = tempdir().unwrap();
+        let storage = dir.path();
+        fs::write(storage.join("battery_profile.json"), "{}").unwrap();
+        for _ in 0..MAX_SNAPSHOTS + 2 {
+            take_snapshot(storage, SnapshotReason::Compaction).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        assert_eq!(list_snapshots(storage).len(), MAX_SNAPSHOTS);
+    }
+}
@@ -0,0 +1,158 @@
+//! Background persistence for battery intelligence profiles
+//!
+//! Profiles change on nearly every scan, and writing them from the UI update path
+//! stalls it whenever the disk is slow. Saves are handed to a worker thread that
+//! collects them for up to [`SAVE_DEBOUNCE`] and writes only the newest. Every write
+//! goes to a temporary file that is synced and then renamed over the profile, so a
+//! crash part way through leaves the previous profile in place rather than truncated
+//! JSON.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::retry::{self, ReplaceError, RetryDomain};
+
+/// Longest a queued save waits before it is written
+pub const SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Replace `path` with `contents` so readers see either the old file or the new one
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp_path = path.with_extension("tmp");
+    {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    // Another process briefly holding the profile open makes the rename fail
+    retry::retry_blocking("Saving battery profile", RetryDomain::Storage, || {
+        std::fs::rename(&temp_path, path).map_err(ReplaceError)
+    })
+    .map_err(|ReplaceError(e)| e)
+}
+
+/// Work sent to the writer thread
+enum Request {
+    /// Write `contents` to the path, replacing any save still waiting for it
+    Save(PathBuf, Vec<u8>),
+    /// Write everything waiting now, then reply
+    Flush(mpsc::Sender<()>),
+}
+
+/// Handle to the thread writing profiles; clones share the thread
+#[derive(Debug, Clone)]
+pub struct ProfileWriter {
+    sender: mpsc::Sender<Request>,
+}
+
+impl ProfileWriter {
+    /// Start a writer that holds saves for [`SAVE_DEBOUNCE`]
+    pub fn spawn() -> Self {
+        Self::with_debounce(SAVE_DEBOUNCE)
+    }
+
+    /// Start a writer that holds saves for `debounce`
+    pub fn with_debounce(debounce: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || run(receiver, debounce));
+        Self { sender }
+    }
+
+    /// Queue `contents` to be written to `path`
+    ///
+    /// Writes straight away if the writer thread has stopped.
+    pub fn save(&self, path: PathBuf, contents: Vec<u8>) -> io::Result<()> {
+        match self.sender.send(Request::Save(path, contents)) {
+            Ok(()) => Ok(()),
+            Err(mpsc::SendError(Request::Save(path, contents))) => write_atomic(&path, &contents),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Write every queued save and wait until they are on disk
+    pub fn flush(&self) {
+        let (done, finished) = mpsc::channel();
+        if self.sender.send(Request::Flush(done)).is_ok() {
+            let _ = finished.recv();
+        }
+    }
+}
+
+/// Writer thread: hold saves until their deadline, a flush, or every handle is dropped
+fn run(receiver: mpsc::Receiver<Request>, debounce: Duration) {
+    let mut pending: Option<(PathBuf, Vec<u8>)> = None;
+    let mut deadline = Instant::now();
+
+    loop {
+        let request = if pending.is_some() {
+            match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(request) => Some(request),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            match receiver.recv() {
+                Ok(request) => Some(request),
+                Err(_) => break,
+            }
+        };
+
+        match request {
+            Some(Request::Save(path, contents)) => {
+                match pending.take() {
+                    // Another file can't wait behind this one
+                    Some((pending_path, pending_contents)) if pending_path != path => {
+                        write_logged(&pending_path, &pending_contents);
+                        deadline = Instant::now() + debounce;
+                    }
+                    Some(_) => {}
+                    None => deadline = Instant::now() + debounce,
+                }
+                pending = Some((path, contents));
+            }
+            Some(Request::Flush(done)) => {
+                if let Some((path, contents)) = pending.take() {
+                    write_logged(&path, &contents);
+                }
+                let _ = done.send(());
+            }
+            None => {
+                if let Some((path, contents)) = pending.take() {
+                    write_logged(&path, &contents);
+                }
+            }
+        }
+    }
+
+    if let Some((path, contents)) = pending {
+        write_logged(&path, &contents);
+    }
+}
+
+fn write_logged(path: &Path, contents: &[u8]) {
+    if let Err(e) = write_atomic(path, contents) {
+        log::error!("Failed to write profile {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_saves_are_coalesced_until_flushed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("battery_profile.json");
+        let writer = ProfileWriter::with_debounce(Duration::from_secs(60));
+
+        writer.save(path.clone(), b"first".to_vec()).unwrap();
+        writer.save(path.clone(), b"second".to_vec()).unwrap();
+        assert!(!path.exists());
+
+        writer.flush();
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        assert!(!path.with_extension("tmp").exists());
+    }
+}
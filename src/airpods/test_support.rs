@@ -0,0 +1,32 @@
+//! Fixtures shared by tests that feed scanner reports, the integration tests included
+
+use super::battery::AirPodsBatteryInfo;
+
+/// A report from AirPods Pro at `address`: both earbuds at 80%, the case at 60%,
+/// nothing charging and no optional fields
+///
+/// Tests change what they need with struct update syntax.
+pub fn report(address: &str) -> AirPodsBatteryInfo {
+    AirPodsBatteryInfo {
+        address: 0,
+        canonical_address: address.to_string(),
+        name: "AirPods Pro".to_string(),
+        model_id: 0x200E,
+        left_battery: 80,
+        left_charging: false,
+        right_battery: 80,
+        right_charging: false,
+        case_battery: 60,
+        case_charging: false,
+        left_in_ear: None,
+        right_in_ear: None,
+        case_lid_open: None,
+        side: None,
+        both_in_case: None,
+        color: None,
+        switch_count: None,
+        rssi: None,
+        timestamp: None,
+        raw_manufacturer_data: None,
+    }
+}
@@ -0,0 +1,68 @@
+//! Latching battery components at a threshold
+//!
+//! Alerts for a low battery should fire once as a component drops to its threshold, not
+//! again on every scan while it stays there. [`ThresholdLatch`] remembers which
+//! components have tripped until they recover.
+
+use std::collections::HashSet;
+
+/// Which battery components are at or below a threshold
+///
+/// A component trips once when it first drops to or below the threshold and re-arms once
+/// it climbs back above it or starts charging.
+#[derive(Debug, Clone, Default)]
+pub struct ThresholdLatch {
+    tripped: HashSet<&'static str>,
+}
+
+impl ThresholdLatch {
+    /// Create a latch with nothing tripped yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check a component's level; returns true only when it has just tripped
+    ///
+    /// An unknown level leaves the component as it was.
+    pub fn check(
+        &mut self,
+        component: &'static str,
+        level: Option<u8>,
+        charging: bool,
+        threshold: u8,
+    ) -> bool {
+        let Some(level) = level else {
+            return false;
+        };
+
+        if charging || level > threshold {
+            self.tripped.remove(component);
+            false
+        } else {
+            self.tripped.insert(component)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trips_once_until_recovered() {
+        let mut latch = ThresholdLatch::new();
+
+        assert!(!latch.check("Case", Some(30), false, 20));
+        assert!(latch.check("Case", Some(20), false, 20));
+        assert!(!latch.check("Case", Some(15), false, 20));
+        assert!(!latch.check("Case", None, false, 20));
+        // Components latch on their own
+        assert!(latch.check("Left AirPod", Some(5), false, 20));
+
+        // Charging or climbing back above the threshold re-arms it
+        assert!(!latch.check("Case", Some(15), true, 20));
+        assert!(latch.check("Case", Some(15), false, 20));
+        assert!(!latch.check("Case", Some(21), false, 20));
+        assert!(latch.check("Case", Some(19), false, 20));
+    }
+}
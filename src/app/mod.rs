@@ -7,7 +7,7 @@ use tokio::sync::mpsc;
 // Main app imports
 // Temporarily disable system tray
 // use crate::ui::{Message, SystemTray};
-use crate::airpods::{detect_airpods, DetectedAirPods};
+use crate::airpods::{detect_airpods, AdvertisementFusion, DetectedAirPods};
 use crate::bluetooth::{AirPodsBatteryStatus, BleScanner};
 use crate::error::RustPodsError;
 use crate::ui::Message;
@@ -35,6 +35,8 @@ pub struct App {
     running: bool,
     /// Current AirPods device
     current_airpods: Arc<Mutex<Option<DetectedAirPods>>>,
+    /// Fuses advertisements from both buds into one device
+    advertisement_fusion: AdvertisementFusion,
     /// Current battery status
     battery_status: Arc<Mutex<AirPodsBatteryStatus>>,
     /// Battery monitoring task handle
@@ -73,6 +75,7 @@ impl App {
             // tray, // Temporarily disabled
             running: false,
             current_airpods: Arc::new(Mutex::new(None)),
+            advertisement_fusion: AdvertisementFusion::new(),
             battery_status: Arc::new(Mutex::new(AirPodsBatteryStatus::default())),
             battery_monitor_task: None,
         })
@@ -124,21 +127,24 @@ impl App {
             self.config.bluetooth.auto_scan_on_startup
         );
 
-        // TEMP: Debug WinRT device query
-        crate::debug_log!(
-            "bluetooth",
-            "About to call get_connected_bluetooth_devices()"
-        );
-        // WinRT async device enumeration is currently disabled due to interop issues.
-        // Use the Win32 SetupAPI fallback or comment out this block for now.
-        crate::debug_log!(
-            "bluetooth",
-            "Finished call to get_connected_bluetooth_devices()"
-        );
-
-        // TEMP: Query and print connected AirPods using WinRT
-        // WinRT async AirPods enumeration is currently disabled due to interop issues.
-        // Use the Win32 SetupAPI fallback or comment out this block for now.
+        // WinRT device queries don't work from this runtime; the Win32 API reports which
+        // paired devices are connected
+        match tokio::task::spawn_blocking(crate::bluetooth::win32_enum::connected_devices).await {
+            Ok(Ok(devices)) => {
+                for device in &devices {
+                    crate::debug_log!(
+                        "bluetooth",
+                        "Connected classic device: {} ({})",
+                        device.name,
+                        device.address
+                    );
+                }
+            }
+            Ok(Err(e)) => {
+                crate::debug_log!("bluetooth", "Classic device enumeration unavailable: {}", e)
+            }
+            Err(e) => log::warn!("Classic device enumeration panicked: {}", e),
+        }
 
         // Start a scan if auto-scan is enabled
         // if self.config.bluetooth.auto_scan_on_startup {
@@ -191,6 +197,7 @@ impl App {
                 // If this is a potential AirPods device
                 // Extract AirPods specific details if available
                 if let Ok(Some(airpods)) = detect_airpods(&device) {
+                    let airpods = self.advertisement_fusion.ingest(airpods);
                     // Store the detected airpods in our state
                     if let Ok(mut current) = self.current_airpods.lock() {
                         *current = Some(airpods.clone());
@@ -199,6 +206,12 @@ impl App {
                     std::mem::drop(self.ui_tx.send(Message::AirPodsConnected(airpods)));
                 }
             }
+            Message::SelectDevice(address) => {
+                // Non-Apple devices don't advertise their battery; read it over GATT
+                if let Err(e) = self.start_gatt_battery_fallback(&address).await {
+                    log::info!("No GATT battery fallback for {}: {}", address, e);
+                }
+            }
             Message::BatteryStatusUpdated(status) => {
                 // Update battery status
                 *self.battery_status.lock().unwrap() = status.clone();
@@ -242,32 +255,10 @@ impl App {
         // Get the refresh interval from config
         let refresh_interval = self.config.bluetooth.battery_refresh_interval;
 
-        // Create the callback for battery updates
-        let ui_tx = self.ui_tx.clone();
-        let battery_status = self.battery_status.clone();
-        let error_tx = self.ui_tx.clone();
-
-        let callback = move |status: AirPodsBatteryStatus| {
-            // Check if we got valid battery information
-            if !status.has_battery_info() {
-                // No battery info available, might indicate connection issue
-                std::mem::drop(
-                    error_tx.send(Message::ShowToast("Reconnection attempt".to_string())),
-                );
-                return;
-            }
-
-            // Update the battery status
-            *battery_status.lock().unwrap() = status.clone();
-
-            // Send battery update to UI
-            std::mem::drop(ui_tx.send(Message::BatteryStatusUpdated(status)));
-        };
-
         // Start the battery monitoring with error handling
         let handle = match crate::bluetooth::start_battery_monitoring(
             &peripheral,
-            callback,
+            self.battery_update_callback(),
             refresh_interval,
         )
         .await
@@ -282,6 +273,64 @@ impl App {
         Ok(())
     }
 
+    /// Monitor a selected non-Apple device through its GATT Battery Service
+    ///
+    /// Devices advertising Apple proximity data are left to proximity monitoring.
+    pub async fn start_gatt_battery_fallback(
+        &mut self,
+        address: &str,
+    ) -> Result<(), RustPodsError> {
+        let address =
+            crate::bluetooth::parse_bdaddr(address).map_err(RustPodsError::InvalidData)?;
+        let peripheral = match self.scanner.get_peripherals_by_address(&address).await {
+            Ok(peripherals) if !peripherals.is_empty() => peripherals[0].clone(),
+            _ => return Err(RustPodsError::DeviceNotFound),
+        };
+        if crate::bluetooth::has_proximity_data(&peripheral).await {
+            return Ok(());
+        }
+
+        // Cancel any existing battery monitoring task
+        if let Some(task) = self.battery_monitor_task.take() {
+            task.abort();
+        }
+
+        let handle = crate::bluetooth::start_gatt_battery_monitoring(
+            &peripheral,
+            self.battery_update_callback(),
+            self.config.bluetooth.battery_refresh_interval,
+        )
+        .await
+        .map_err(|e| RustPodsError::BatteryMonitorError(e.to_string()))?;
+
+        log::info!("Monitoring battery of {} over GATT", address);
+        self.battery_monitor_task = Some(handle);
+        Ok(())
+    }
+
+    /// Callback that stores a battery reading and forwards it to the UI
+    fn battery_update_callback(&self) -> impl Fn(AirPodsBatteryStatus) + Send + Sync + 'static {
+        let ui_tx = self.ui_tx.clone();
+        let battery_status = self.battery_status.clone();
+
+        move |status: AirPodsBatteryStatus| {
+            // Check if we got valid battery information
+            if !status.has_battery_info() {
+                // No battery info available, might indicate connection issue
+                std::mem::drop(
+                    ui_tx.try_send(Message::ShowToast("Reconnection attempt".to_string())),
+                );
+                return;
+            }
+
+            // Update the battery status
+            *battery_status.lock().unwrap() = status.clone();
+
+            // Send battery update to UI
+            std::mem::drop(ui_tx.try_send(Message::BatteryStatusUpdated(status)));
+        }
+    }
+
     /// Get the peripheral device for the given AirPods
     async fn get_peripheral_for_airpods(
         &self,
@@ -7,7 +7,9 @@ use tokio::task::JoinHandle;
 
 use crate::airpods::DetectedAirPods;
 use crate::bluetooth::battery::AirPodsBatteryStatus;
-use crate::bluetooth::{BleEvent, BleScanner, EventBroker, EventFilter, ScanConfig};
+use crate::bluetooth::{
+    AirPodsTopic, Backpressure, BleEvent, BleScanner, EventBroker, EventFilter, ScanConfig,
+};
 use crate::ui::Message;
 
 /// Main application controller that manages Bluetooth connections and UI state
@@ -86,7 +88,8 @@ impl AppController {
 
         // Subscribe to AirPods events
         let mut broker_clone = event_broker.clone();
-        let (_id, mut rx_airpods) = broker_clone.subscribe(EventFilter::airpods_only());
+        let (_id, mut rx_airpods) =
+            broker_clone.subscribe_topic(AirPodsTopic, Backpressure::DropNewest);
 
         // Clone UI sender to move into task
         let ui_sender_clone = ui_sender.clone();
@@ -94,17 +97,15 @@ impl AppController {
 
         // Spawn task to handle events
         tokio::spawn(async move {
-            while let Some(event) = rx_airpods.recv().await {
-                if let BleEvent::AirPodsDetected(airpods) = event {
-                    // Send AirPods discovered event to UI
-                    ui_sender_clone
-                        .send(Message::AirPodsConnected(airpods.clone()))
-                        .ok();
-
-                    // Store connected device
-                    let mut device = device_clone.lock().unwrap();
-                    *device = Some(airpods);
-                }
+            while let Some(airpods) = rx_airpods.recv().await {
+                // Send AirPods discovered event to UI
+                ui_sender_clone
+                    .send(Message::AirPodsConnected(airpods.clone()))
+                    .ok();
+
+                // Store connected device
+                let mut device = device_clone.lock().unwrap();
+                *device = Some(airpods);
             }
         });
 
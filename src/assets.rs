@@ -17,6 +17,9 @@ pub mod app {
 
 /// System tray icons
 pub mod tray {
+    use image::RgbaImage;
+    use std::path::{Path, PathBuf};
+
     /// System tray icon for dark theme, connected state (ICO format)
     pub const DARK_CONNECTED: &[u8] =
         include_bytes!("../assets/icons/tray/rustpods-tray-dark-connected.ico");
@@ -32,6 +35,98 @@ pub mod tray {
     /// System tray icon for light theme, disconnected state (ICO format)
     pub const LIGHT_DISCONNECTED: &[u8] =
         include_bytes!("../assets/icons/tray/rustpods-tray-light-disconnected.ico");
+
+    /// File a custom icon set shows while AirPods are connected
+    pub const CUSTOM_CONNECTED: &str = "connected.ico";
+
+    /// File a custom icon set shows otherwise
+    pub const CUSTOM_DISCONNECTED: &str = "disconnected.ico";
+
+    /// Smallest icon accepted in a custom set, in pixels
+    pub const MIN_CUSTOM_SIZE: u32 = 16;
+
+    /// Bundled tray icon for a theme and connection state
+    pub fn bundled(dark: bool, connected: bool) -> &'static [u8] {
+        match (dark, connected) {
+            (true, true) => DARK_CONNECTED,
+            (true, false) => DARK_DISCONNECTED,
+            (false, true) => LIGHT_CONNECTED,
+            (false, false) => LIGHT_DISCONNECTED,
+        }
+    }
+
+    /// Why a custom icon set can't be used
+    #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+    pub enum IconSetError {
+        #[error("{0} is missing")]
+        Missing(PathBuf),
+
+        #[error("{path} is not a readable icon: {message}")]
+        Decode { path: PathBuf, message: String },
+
+        #[error("{path} is {width}x{height}; tray icons must be square and at least {MIN_CUSTOM_SIZE}x{MIN_CUSTOM_SIZE}")]
+        BadSize {
+            path: PathBuf,
+            width: u32,
+            height: u32,
+        },
+    }
+
+    /// A user-provided pair of tray icons, decoded
+    #[derive(Debug, Clone)]
+    pub struct IconSet {
+        /// Shown while AirPods are connected
+        pub connected: RgbaImage,
+        /// Shown otherwise
+        pub disconnected: RgbaImage,
+    }
+
+    impl IconSet {
+        /// The icon for a connection state
+        pub fn icon(&self, connected: bool) -> &RgbaImage {
+            if connected {
+                &self.connected
+            } else {
+                &self.disconnected
+            }
+        }
+    }
+
+    /// Load and check the icon set in `folder`
+    ///
+    /// The folder holds [`CUSTOM_CONNECTED`] and [`CUSTOM_DISCONNECTED`]; both have to
+    /// decode and be square, at least [`MIN_CUSTOM_SIZE`] pixels across.
+    pub fn load_icon_set(folder: &Path) -> Result<IconSet, IconSetError> {
+        Ok(IconSet {
+            connected: load_custom_icon(&folder.join(CUSTOM_CONNECTED))?,
+            disconnected: load_custom_icon(&folder.join(CUSTOM_DISCONNECTED))?,
+        })
+    }
+
+    fn load_custom_icon(path: &Path) -> Result<RgbaImage, IconSetError> {
+        if !path.is_file() {
+            return Err(IconSetError::Missing(path.to_path_buf()));
+        }
+        let decode_error = |message: String| IconSetError::Decode {
+            path: path.to_path_buf(),
+            message,
+        };
+        // Go by the content, so a PNG saved as .ico still loads
+        let image = image::ImageReader::open(path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| decode_error(e.to_string()))?
+            .decode()
+            .map_err(|e| decode_error(e.to_string()))?;
+        let (width, height) = (image.width(), image.height());
+        if width != height || width < MIN_CUSTOM_SIZE {
+            return Err(IconSetError::BadSize {
+                path: path.to_path_buf(),
+                width,
+                height,
+            });
+        }
+        Ok(image.to_rgba8())
+    }
 }
 
 /// UI element assets
@@ -44,6 +139,9 @@ pub mod ui {
 
     /// Settings gear icon SVG for settings buttons
     pub const SETTINGS_ICON: &[u8] = include_bytes!("../assets/icons/settings.svg");
+
+    /// Minimize icon SVG for the title bar
+    pub const MINIMIZE_ICON: &[u8] = include_bytes!("../assets/icons/minimize.svg");
 }
 
 /// Font assets
@@ -52,3 +150,44 @@ pub mod fonts {
     pub const SPACE_MONO_NERD_FONT: &[u8] =
         include_bytes!("../assets/fonts/SpaceMonoNerdFont-Regular.ttf");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::tray::*;
+
+    #[test]
+    fn test_custom_icon_set_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            load_icon_set(dir.path()).unwrap_err(),
+            IconSetError::Missing(dir.path().join(CUSTOM_CONNECTED))
+        );
+
+        std::fs::write(dir.path().join(CUSTOM_CONNECTED), DARK_CONNECTED).unwrap();
+        std::fs::write(dir.path().join(CUSTOM_DISCONNECTED), b"not an icon").unwrap();
+        assert!(matches!(
+            load_icon_set(dir.path()),
+            Err(IconSetError::Decode { .. })
+        ));
+
+        image::RgbaImage::new(32, 16)
+            .save(dir.path().join("wide.png"))
+            .unwrap();
+        std::fs::rename(
+            dir.path().join("wide.png"),
+            dir.path().join(CUSTOM_DISCONNECTED),
+        )
+        .unwrap();
+        assert!(matches!(
+            load_icon_set(dir.path()),
+            Err(IconSetError::BadSize {
+                width: 32,
+                height: 16,
+                ..
+            })
+        ));
+
+        std::fs::write(dir.path().join(CUSTOM_DISCONNECTED), DARK_DISCONNECTED).unwrap();
+        assert!(load_icon_set(dir.path()).is_ok());
+    }
+}
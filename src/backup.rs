@@ -0,0 +1,575 @@
+//! Backup and restore of all application data
+//!
+//! A backup is a zip holding the settings folder (`settings.json`, known devices, the
+//! settings audit log) under `config/` and the learned data (battery intelligence
+//! profile and history, saved UI state) under `data/`, with a `manifest.json` naming the
+//! format version and the files. Logs and downloaded caches are left out; they come back
+//! by themselves.
+//!
+//! Restoring reads and checks the whole archive before touching anything, brings files
+//! from older formats up to date, writes every file next to its target and only then
+//! swaps them in. If a swap fails, the files already swapped are put back, so a restore
+//! either completes or leaves the data as it was.
+//!
+//! Scheduled backups go to a folder of their own, named by the time they were made, and
+//! only the newest few are kept.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::config::{parse_error::from_json_str, AppConfig};
+
+/// Version of the backup format written by this build
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+
+/// Name of the manifest inside the archive
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Files in the data folder that are backed up; folders are taken whole
+const DATA_ENTRIES: [&str; 2] = ["app_state.json", "battery_intelligence"];
+
+/// Extensions of leftovers and backups that are never backed up
+const SKIPPED_EXTENSIONS: [&str; 6] = ["bak", "broken", "tmp", "restore-tmp", "pre-restore", "zip"];
+
+/// Start of the file names of scheduled backups
+const SCHEDULED_PREFIX: &str = "rustpods_auto_backup_";
+
+/// Local time in the file names of scheduled backups
+const SCHEDULED_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// Errors raised while backing up or restoring
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Archive error: {0}")]
+    Archive(String),
+
+    #[error("Not a RustPods backup: {0}")]
+    NotABackup(String),
+
+    /// The backup was made by a newer version of RustPods
+    #[error("The backup uses format {0}, newer than this version of RustPods supports")]
+    NewerFormat(u32),
+
+    #[error("The settings in the backup are invalid: {0}")]
+    InvalidSettings(String),
+}
+
+impl From<zip::result::ZipError> for BackupError {
+    fn from(error: zip::result::ZipError) -> Self {
+        BackupError::Archive(error.to_string())
+    }
+}
+
+/// Description of a backup, stored in the archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Format of the archive
+    pub format_version: u32,
+    /// RustPods version that made the backup
+    pub app_version: String,
+    /// When the backup was made, RFC 3339
+    pub created: String,
+    /// Archive paths of the files in the backup
+    pub files: Vec<String>,
+}
+
+/// Outcome of a scheduled backup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledBackup {
+    /// Where the backup was written
+    pub path: PathBuf,
+    /// Number of files in the backup
+    pub files: usize,
+    /// Older scheduled backups deleted to keep the retention count
+    pub removed: usize,
+}
+
+/// Folders whose contents are backed up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupRoots {
+    /// Settings folder, `%APPDATA%\rustpods`
+    pub config_dir: PathBuf,
+    /// Data folder, `%LOCALAPPDATA%\RustPods`
+    pub data_dir: PathBuf,
+}
+
+impl Default for BackupRoots {
+    fn default() -> Self {
+        Self {
+            config_dir: dirs_next::config_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("rustpods"),
+            data_dir: dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("RustPods"),
+        }
+    }
+}
+
+impl BackupRoots {
+    /// Folder scheduled backups go to when none is configured
+    pub fn default_backup_folder(&self) -> PathBuf {
+        self.data_dir.join("backups")
+    }
+
+    /// Files to back up, with their archive paths
+    fn files(&self) -> Vec<(String, PathBuf)> {
+        let mut files = Vec::new();
+        collect_files(&self.config_dir, "config", &mut files);
+        for entry in DATA_ENTRIES {
+            collect_files(
+                &self.data_dir.join(entry),
+                &format!("data/{}", entry),
+                &mut files,
+            );
+        }
+        files
+    }
+
+    /// Where an archive path is restored to; `None` if it isn't one this build restores
+    fn target(&self, name: &str) -> Option<PathBuf> {
+        let (root, rest) = name.split_once('/')?;
+        let base = match root {
+            "config" => &self.config_dir,
+            "data"
+                if DATA_ENTRIES
+                    .iter()
+                    .any(|entry| rest.split('/').next() == Some(*entry)) =>
+            {
+                &self.data_dir
+            }
+            _ => return None,
+        };
+        Some(
+            rest.split('/')
+                .fold(base.clone(), |path, part| path.join(part)),
+        )
+    }
+
+    /// Files currently in the folders a restore replaces
+    fn restored_files(&self) -> Vec<PathBuf> {
+        self.files().into_iter().map(|(_, path)| path).collect()
+    }
+}
+
+/// Files under `path`, or `path` itself if it is a file
+fn collect_files(path: &Path, name: &str, files: &mut Vec<(String, PathBuf)>) {
+    if path.is_file() {
+        let skipped = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SKIPPED_EXTENSIONS.contains(&ext));
+        if !skipped {
+            files.push((name.to_string(), path.to_path_buf()));
+        }
+    } else if let Ok(entries) = fs::read_dir(path) {
+        let mut entries: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        for entry in entries {
+            if let Some(file_name) = entry.file_name().and_then(|n| n.to_str()) {
+                collect_files(&entry, &format!("{}/{}", name, file_name), files);
+            }
+        }
+    }
+}
+
+/// Write every backed-up file into a zip at `path`
+pub fn create_backup(path: &Path, roots: &BackupRoots) -> Result<BackupManifest, BackupError> {
+    let files = roots.files();
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created: chrono::Local::now().to_rfc3339(),
+        files: files.iter().map(|(name, _)| name.clone()).collect(),
+    };
+
+    let mut archive = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options =
+        zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    archive.start_file(MANIFEST_FILE, options)?;
+    archive.write_all(
+        serde_json::to_string_pretty(&manifest)
+            .map_err(|e| BackupError::Archive(e.to_string()))?
+            .as_bytes(),
+    )?;
+    for (name, file) in &files {
+        archive.start_file(name.as_str(), options)?;
+        archive.write_all(&fs::read(file)?)?;
+    }
+    let bytes = archive.finish()?.into_inner();
+
+    crate::airpods::profile_writer::write_atomic(path, &bytes)?;
+    log::info!(
+        "Backed up {} files to {}",
+        manifest.files.len(),
+        path.display()
+    );
+    Ok(manifest)
+}
+
+/// Manifest of the backup at `path`, without restoring it
+pub fn read_manifest(path: &Path) -> Result<BackupManifest, BackupError> {
+    read_archive(path).map(|(manifest, _)| manifest)
+}
+
+/// Read and check a backup, returning its manifest and files
+fn read_archive(path: &Path) -> Result<(BackupManifest, BTreeMap<String, Vec<u8>>), BackupError> {
+    let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+    let mut manifest = None;
+    let mut files = BTreeMap::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // Paths that would leave the target folders are never extracted
+        let name = entry
+            .enclosed_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.replace('\\', "/"))
+            .ok_or_else(|| BackupError::NotABackup(format!("unsafe path {}", entry.name())))?;
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        if name == MANIFEST_FILE {
+            manifest = Some(
+                serde_json::from_slice::<BackupManifest>(&bytes)
+                    .map_err(|e| BackupError::NotABackup(e.to_string()))?,
+            );
+        } else {
+            files.insert(name, bytes);
+        }
+    }
+
+    let manifest =
+        manifest.ok_or_else(|| BackupError::NotABackup(format!("no {}", MANIFEST_FILE)))?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        return Err(BackupError::NewerFormat(manifest.format_version));
+    }
+    Ok((manifest, files))
+}
+
+/// Bring files from a backup of an older format up to the current one
+///
+/// The settings are also read and written back with this build's schema, so fields added
+/// since the backup get their defaults and anything unreadable stops the restore.
+fn migrate(files: &mut BTreeMap<String, Vec<u8>>, format_version: u32) -> Result<(), BackupError> {
+    match format_version {
+        // The first format; later ones convert from it here
+        1 => {}
+        other => return Err(BackupError::NewerFormat(other)),
+    }
+
+    if let Some(settings) = files.get_mut("config/settings.json") {
+        let text = String::from_utf8_lossy(settings).into_owned();
+        let config: AppConfig =
+            from_json_str(&text, None).map_err(|e| BackupError::InvalidSettings(e.to_string()))?;
+        config
+            .validate()
+            .map_err(|e| BackupError::InvalidSettings(e.to_string()))?;
+        *settings = serde_json::to_vec_pretty(&config)
+            .map_err(|e| BackupError::InvalidSettings(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Replace the application data with the backup at `path`
+///
+/// Files in the restored folders that aren't in the backup are removed, so the history
+/// matches the profile it belongs to.
+pub fn restore_backup(path: &Path, roots: &BackupRoots) -> Result<BackupManifest, BackupError> {
+    let (manifest, mut files) = read_archive(path)?;
+    migrate(&mut files, manifest.format_version)?;
+
+    let targets: Vec<(PathBuf, Vec<u8>)> = files
+        .into_iter()
+        .filter_map(|(name, bytes)| match roots.target(&name) {
+            Some(target) => Some((target, bytes)),
+            None => {
+                log::warn!("Skipping unknown file {} in backup", name);
+                None
+            }
+        })
+        .collect();
+    let stale: Vec<PathBuf> = roots
+        .restored_files()
+        .into_iter()
+        .filter(|file| !targets.iter().any(|(target, _)| target == file))
+        .collect();
+
+    // Write everything next to its target first, so a full disk stops the restore early
+    let mut staged = Vec::new();
+    let result = targets.iter().try_for_each(|(target, bytes)| {
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp = with_extension(target, "restore-tmp");
+        fs::write(&temp, bytes)?;
+        staged.push((temp, target.clone()));
+        Ok::<_, std::io::Error>(())
+    });
+    if let Err(e) = result {
+        for (temp, _) in &staged {
+            let _ = fs::remove_file(temp);
+        }
+        return Err(e.into());
+    }
+
+    swap_in(&staged, &stale)?;
+    log::info!(
+        "Restored {} files from {} (made by RustPods {} on {})",
+        staged.len(),
+        path.display(),
+        manifest.app_version,
+        manifest.created
+    );
+    Ok(manifest)
+}
+
+/// Move staged files over their targets and stale files aside, undoing it all on failure
+fn swap_in(staged: &[(PathBuf, PathBuf)], stale: &[PathBuf]) -> Result<(), BackupError> {
+    // (original, where it was moved) for each file replaced so far
+    let mut replaced: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let mut swapped: Vec<PathBuf> = Vec::new();
+
+    let result = (|| {
+        for file in staged
+            .iter()
+            .map(|(_, target)| target)
+            .chain(stale)
+            .filter(|file| file.exists())
+        {
+            let aside = with_extension(file, "pre-restore");
+            fs::rename(file, &aside)?;
+            replaced.push((file.clone(), aside));
+        }
+        for (temp, target) in staged {
+            fs::rename(temp, target)?;
+            swapped.push(target.clone());
+        }
+        Ok::<_, std::io::Error>(())
+    })();
+
+    if let Err(e) = result {
+        for target in &swapped {
+            let _ = fs::remove_file(target);
+        }
+        for (original, aside) in replaced.iter().rev() {
+            let _ = fs::rename(aside, original);
+        }
+        for (temp, _) in staged {
+            let _ = fs::remove_file(temp);
+        }
+        return Err(e.into());
+    }
+
+    for (_, aside) in &replaced {
+        let _ = fs::remove_file(aside);
+    }
+    Ok(())
+}
+
+/// Scheduled backups in `folder` with when they were made, oldest first
+///
+/// Other files, such as backups made by hand, are left out.
+pub fn scheduled_backups(folder: &Path) -> Vec<(NaiveDateTime, PathBuf)> {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<(NaiveDateTime, PathBuf)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let made = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(SCHEDULED_PREFIX)?
+                .strip_suffix(".zip")
+                .and_then(|time| NaiveDateTime::parse_from_str(time, SCHEDULED_TIME_FORMAT).ok())?;
+            Some((made, path))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+/// Whether `folder` has no scheduled backup made within `interval` before `now`
+pub fn scheduled_backup_due(folder: &Path, interval: Duration, now: NaiveDateTime) -> bool {
+    match scheduled_backups(folder).last() {
+        None => true,
+        // A backup dated in the future (the clock was changed) counts as recent
+        Some((made, _)) => (now - *made).to_std().is_ok_and(|age| age >= interval),
+    }
+}
+
+/// Back up into `folder`, then delete all but the newest `keep` scheduled backups there
+pub fn run_scheduled_backup(
+    folder: &Path,
+    roots: &BackupRoots,
+    keep: usize,
+    now: NaiveDateTime,
+) -> Result<ScheduledBackup, BackupError> {
+    fs::create_dir_all(folder)?;
+    let path = folder.join(format!(
+        "{}{}.zip",
+        SCHEDULED_PREFIX,
+        now.format(SCHEDULED_TIME_FORMAT)
+    ));
+    let manifest = create_backup(&path, roots)?;
+
+    let backups = scheduled_backups(folder);
+    let excess = backups.len().saturating_sub(keep.max(1));
+    let mut removed = 0;
+    for (_, old) in backups.iter().take(excess) {
+        match fs::remove_file(old) {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("Failed to delete old backup {}: {}", old.display(), e),
+        }
+    }
+
+    Ok(ScheduledBackup {
+        path,
+        files: manifest.files.len(),
+        removed,
+    })
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn roots(base: &Path) -> BackupRoots {
+        BackupRoots {
+            config_dir: base.join("config"),
+            data_dir: base.join("data"),
+        }
+    }
+
+    #[test]
+    fn test_backup_and_restore_round_trip() {
+        let dir = tempdir().unwrap();
+        let roots = roots(dir.path());
+        let intelligence = roots.data_dir.join("battery_intelligence");
+        fs::create_dir_all(&intelligence).unwrap();
+        fs::create_dir_all(&roots.config_dir).unwrap();
+        fs::write(roots.config_dir.join("settings.json"), "{}").unwrap();
+        fs::write(roots.config_dir.join("settings.json.bak"), "{}").unwrap();
+        fs::write(intelligence.join("battery_profile.json"), "profile").unwrap();
+        fs::write(roots.data_dir.join("rustpods.log"), "log").unwrap();
+
+        let backup = dir.path().join("backup.zip");
+        let manifest = create_backup(&backup, &roots).unwrap();
+        assert_eq!(
+            manifest.files,
+            vec![
+                "config/settings.json".to_string(),
+                "data/battery_intelligence/battery_profile.json".to_string(),
+            ]
+        );
+        assert_eq!(read_manifest(&backup).unwrap(), manifest);
+
+        fs::write(intelligence.join("battery_profile.json"), "relearned").unwrap();
+        fs::write(intelligence.join("battery_history.bin"), "stale").unwrap();
+        restore_backup(&backup, &roots).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(intelligence.join("battery_profile.json")).unwrap(),
+            "profile"
+        );
+        assert!(!intelligence.join("battery_history.bin").exists());
+        assert!(roots.data_dir.join("rustpods.log").exists());
+        // Settings come back in the current schema
+        let settings = AppConfig::load_from_path(&roots.config_dir.join("settings.json")).unwrap();
+        assert_eq!(settings.ui.theme, AppConfig::default().ui.theme);
+    }
+
+    #[test]
+    fn test_scheduled_backups_keep_the_newest() {
+        let dir = tempdir().unwrap();
+        let roots = roots(dir.path());
+        fs::create_dir_all(&roots.config_dir).unwrap();
+        fs::write(roots.config_dir.join("settings.json"), "{}").unwrap();
+        let folder = roots.default_backup_folder();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let first = chrono::NaiveDate::from_ymd_opt(2025, 6, 1)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+
+        assert!(scheduled_backup_due(&folder, day, first));
+        for days in 0..3 {
+            let now = first + chrono::Duration::days(days);
+            let backup = run_scheduled_backup(&folder, &roots, 2, now).unwrap();
+            assert_eq!(backup.files, 1);
+            assert_eq!(backup.removed, usize::from(days == 2));
+        }
+        fs::write(folder.join("rustpods_backup_20250101.zip"), "by hand").unwrap();
+
+        let kept: Vec<NaiveDateTime> = scheduled_backups(&folder)
+            .into_iter()
+            .map(|(made, _)| made)
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                first + chrono::Duration::days(1),
+                first + chrono::Duration::days(2)
+            ]
+        );
+        assert!(folder.join("rustpods_backup_20250101.zip").exists());
+
+        let last = first + chrono::Duration::days(2);
+        assert!(!scheduled_backup_due(
+            &folder,
+            day,
+            last + chrono::Duration::hours(23)
+        ));
+        assert!(scheduled_backup_due(
+            &folder,
+            day,
+            last + chrono::Duration::days(1)
+        ));
+    }
+
+    #[test]
+    fn test_newer_format_is_refused() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("backup.zip");
+        let mut archive = zip::ZipWriter::new(fs::File::create(&path).unwrap());
+        archive
+            .start_file(MANIFEST_FILE, zip::write::FileOptions::default())
+            .unwrap();
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION + 1,
+            app_version: "9.0.0".to_string(),
+            created: String::new(),
+            files: Vec::new(),
+        };
+        archive
+            .write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        archive.finish().unwrap();
+
+        assert!(matches!(
+            restore_backup(&path, &roots(dir.path())),
+            Err(BackupError::NewerFormat(2))
+        ));
+    }
+}
@@ -12,6 +12,7 @@ use tokio::time::sleep;
 use crate::bluetooth::scanner::DiscoveredDevice;
 use crate::bluetooth::BluetoothError;
 use crate::error::{ErrorContext, RecoveryAction};
+use crate::retry::{Retry, RetryDomain};
 
 /// Adapter capabilities
 #[derive(Debug, Clone, Default)]
@@ -152,47 +153,26 @@ impl AdapterManager {
         Ok(adapter_manager)
     }
 
-    /// Refresh the list of available adapters with retry logic
+    /// Refresh the list of available adapters, retrying as the Bluetooth policy allows
     pub async fn refresh_adapters(&mut self) -> Result<(), BluetoothError> {
         let ctx = ErrorContext::new("AdapterManager", "refresh_adapters");
+        let mut retry = Retry::new("Refreshing Bluetooth adapters", RetryDomain::Bluetooth);
 
-        // Try to refresh the adapters
-        let mut result = self.try_refresh_adapters().await;
-        let mut attempt = 1;
-        let mut _last_error = None;
-        let max_attempts = 3;
-        let retry_delay = Duration::from_millis(500);
-
-        while result.is_err() && attempt < max_attempts {
-            match result {
-                Ok(_) => break, // Success, break out of the loop
-                Err(e) => {
-                    attempt += 1;
-                    let error_string = format!("{}", e);
-                    _last_error = Some(e);
-
-                    if attempt < max_attempts {
-                        warn!(
-                            "{}Attempt {}/{} to refresh adapters failed: {}. Retrying...",
-                            ctx, attempt, max_attempts, error_string
-                        );
-                    } else {
-                        error!(
-                            "{}All {} attempts to refresh adapters failed: {}",
-                            ctx, max_attempts, error_string
-                        );
-                    }
+        loop {
+            match self.try_refresh_adapters().await {
+                Ok(()) => {
+                    retry.succeeded();
+                    return Ok(());
                 }
+                Err(e) => match retry.failed(&e) {
+                    Some(delay) => sleep(delay).await,
+                    None => {
+                        error!("{}Failed to refresh adapters: {}", ctx, e);
+                        return Err(e);
+                    }
+                },
             }
-
-            // Add a delay before retrying
-            tokio::time::sleep(retry_delay).await;
-
-            // Try again
-            result = self.try_refresh_adapters().await;
         }
-
-        result
     }
 
     /// Try to refresh adapter list once
@@ -475,6 +455,36 @@ impl AdapterManager {
         })
     }
 
+    /// Every adapter that can scan, for scanning on several radios at once
+    ///
+    /// Adapters whose capabilities haven't been checked are included.
+    pub async fn get_scanning_adapters(
+        &self,
+    ) -> Result<Vec<(AdapterInfo, Adapter)>, BluetoothError> {
+        let adapters = crate::bluetooth::handle_bluetooth_error(
+            self.manager.adapters().await,
+            "AdapterManager",
+            "get_scanning_adapters",
+            Some(RecoveryAction::RestartApplication),
+        )?;
+
+        let scanning: Vec<(AdapterInfo, Adapter)> = adapters
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, adapter)| {
+                let info = self.available_adapters.get(index)?;
+                let usable =
+                    info.capabilities.last_checked.is_none() || info.capabilities.supports_scanning;
+                usable.then(|| (info.clone(), adapter))
+            })
+            .collect();
+
+        if scanning.is_empty() {
+            return Err(BluetoothError::NoAdapter);
+        }
+        Ok(scanning)
+    }
+
     /// Get info about the currently selected adapter
     pub fn get_selected_adapter_info(&self) -> Option<&AdapterInfo> {
         self.selected_index
@@ -877,24 +887,25 @@ impl BluetoothAdapter {
         Ok(devices)
     }
 
-    /// Create a new adapter with retry logic
+    /// Create a new adapter, retrying as the Bluetooth policy allows
     pub async fn new_with_retry() -> Result<Self, BluetoothError> {
         let ctx = ErrorContext::new("AdapterManager", "new");
-        let mut result = Self::new().await;
-        let mut attempt = 0;
-        let max_attempts = 3;
-        let retry_delay = Duration::from_millis(500);
-
-        while result.is_err() && attempt < max_attempts {
-            attempt += 1;
-            debug!(
-                "{}Adapter initialization attempt {}/{} failed, retrying...",
-                ctx, attempt, max_attempts
-            );
-            sleep(retry_delay).await;
-            result = Self::new().await;
-        }
+        let mut retry = Retry::new("Initializing the Bluetooth adapter", RetryDomain::Bluetooth);
 
-        result
+        loop {
+            match Self::new().await {
+                Ok(manager) => {
+                    retry.succeeded();
+                    return Ok(manager);
+                }
+                Err(e) => match retry.failed(&e) {
+                    Some(delay) => sleep(delay).await,
+                    None => {
+                        debug!("{}Adapter initialization failed: {}", ctx, e);
+                        return Err(e);
+                    }
+                },
+            }
+        }
     }
 }
@@ -1,14 +1,31 @@
 //! Bluetooth battery status monitoring for AirPods devices
+//!
+//! AirPods report their battery in proximity advertisements. Other headphones often
+//! expose the standard GATT Battery Service once connected, which is used as a
+//! connection-based fallback for selected non-Apple devices.
 
 #[cfg(test)]
 use crate::airpods::AirPodsChargingState;
-use crate::airpods::{parse_airpods_data, AirPodsBattery};
+use crate::airpods::{parse_airpods_data, AirPodsBattery, APPLE_COMPANY_ID};
+use crate::bluetooth::BlePeripheral;
 use crate::error::BluetoothError;
+use btleplug::api::bleuuid::uuid_from_u16;
 use btleplug::api::Peripheral as _; // Import the Peripheral trait
 use btleplug::platform::Peripheral;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
+use std::sync::Arc;
 use std::time::Instant;
+use uuid::Uuid;
+
+/// Standard GATT Battery Service
+pub const BATTERY_SERVICE_UUID: Uuid = uuid_from_u16(0x180F);
+
+/// Battery Level characteristic of the Battery Service
+pub const BATTERY_LEVEL_CHARACTERISTIC_UUID: Uuid = uuid_from_u16(0x2A19);
+
+/// Callback shared between the GATT notification handler and the polling task
+type GattBatteryCallback = Arc<dyn Fn(AirPodsBatteryStatus) + Send + Sync + 'static>;
 
 /// Battery status information for AirPods devices
 #[derive(Debug, Clone, PartialEq)]
@@ -132,8 +149,6 @@ pub async fn extract_battery_data(
     };
 
     // Check if manufacturer data exists and if it contains Apple data
-    const APPLE_COMPANY_ID: u16 = 0x004C;
-
     let apple_data = match properties.manufacturer_data.get(&APPLE_COMPANY_ID) {
         Some(data) => data,
         None => {
@@ -181,6 +196,129 @@ pub async fn start_battery_monitoring(
     Ok(handle)
 }
 
+/// Whether the peripheral advertises Apple proximity data with its battery levels
+pub async fn has_proximity_data(peripheral: &Peripheral) -> bool {
+    matches!(
+        peripheral.properties().await,
+        Ok(Some(properties)) if properties.manufacturer_data.contains_key(&APPLE_COMPANY_ID)
+    )
+}
+
+/// Parse a Battery Level characteristic value (a single percentage byte)
+pub fn parse_battery_level(value: &[u8]) -> Result<u8, BluetoothError> {
+    match value.first() {
+        Some(&level) if level <= 100 => Ok(level),
+        Some(&level) => Err(BluetoothError::InvalidData(format!(
+            "Battery level out of range: {}",
+            level
+        ))),
+        None => Err(BluetoothError::InvalidData(
+            "Empty battery level value".to_string(),
+        )),
+    }
+}
+
+/// Battery reading for a device with a single GATT battery level
+///
+/// The Battery Service reports one level for the whole headset, so it's shown for
+/// both sides; there is no case reading.
+pub fn gatt_battery(level: u8) -> AirPodsBattery {
+    AirPodsBattery {
+        left: Some(level),
+        right: Some(level),
+        case: None,
+        charging: None,
+    }
+}
+
+/// Read the battery level over a GATT connection, connecting if needed
+pub async fn read_gatt_battery(peripheral: &Peripheral) -> Result<AirPodsBattery, BluetoothError> {
+    let mut device = BlePeripheral::new(peripheral.clone());
+    device.connect_service(BATTERY_SERVICE_UUID).await?;
+    let value = device
+        .read_characteristic(BATTERY_LEVEL_CHARACTERISTIC_UUID)
+        .await?;
+    parse_battery_level(&value).map(gatt_battery)
+}
+
+/// Start monitoring the battery of a non-Apple device through the GATT Battery Service
+///
+/// Connects, reports the current level straight away and then follows notifications.
+/// Devices whose characteristic can't notify are polled every `refresh_interval`
+/// instead. A dropped connection is re-established on the next interval.
+pub async fn start_gatt_battery_monitoring(
+    peripheral: &Peripheral,
+    callback: impl Fn(AirPodsBatteryStatus) + Send + Sync + 'static,
+    refresh_interval: std::time::Duration,
+) -> Result<tokio::task::JoinHandle<()>, BluetoothError> {
+    let callback: GattBatteryCallback = Arc::new(callback);
+    let (mut device, mut subscribed) = connect_gatt_battery(peripheral, callback.clone()).await?;
+    let peripheral = peripheral.clone();
+
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(refresh_interval);
+        // The first tick completes immediately and the level was just read
+        interval.tick().await;
+
+        loop {
+            interval.tick().await;
+
+            if !device.is_connected().await.unwrap_or(false) {
+                match connect_gatt_battery(&peripheral, callback.clone()).await {
+                    Ok((reconnected, notifies)) => {
+                        device = reconnected;
+                        subscribed = notifies;
+                    }
+                    Err(e) => log::debug!("GATT battery reconnect failed: {}", e),
+                }
+            } else if !subscribed {
+                match device
+                    .read_characteristic(BATTERY_LEVEL_CHARACTERISTIC_UUID)
+                    .await
+                    .and_then(|value| parse_battery_level(&value))
+                {
+                    Ok(level) => callback(AirPodsBatteryStatus::new(gatt_battery(level))),
+                    Err(e) => log::debug!("GATT battery read failed: {}", e),
+                }
+            }
+        }
+    });
+
+    Ok(handle)
+}
+
+/// Connect to the Battery Service, report the current level and subscribe to changes
+///
+/// Returns the connected peripheral, which has to stay alive for notifications to be
+/// delivered, and whether the subscription succeeded.
+async fn connect_gatt_battery(
+    peripheral: &Peripheral,
+    callback: GattBatteryCallback,
+) -> Result<(BlePeripheral, bool), BluetoothError> {
+    let mut device = BlePeripheral::new(peripheral.clone());
+    device.connect_service(BATTERY_SERVICE_UUID).await?;
+
+    let value = device
+        .read_characteristic(BATTERY_LEVEL_CHARACTERISTIC_UUID)
+        .await?;
+    callback(AirPodsBatteryStatus::new(gatt_battery(
+        parse_battery_level(&value)?,
+    )));
+
+    let subscribed = device
+        .subscribe(
+            BATTERY_LEVEL_CHARACTERISTIC_UUID,
+            Box::new(move |value| match parse_battery_level(&value) {
+                Ok(level) => callback(AirPodsBatteryStatus::new(gatt_battery(level))),
+                Err(e) => log::debug!("Ignoring battery level notification: {}", e),
+            }),
+        )
+        .await
+        .is_ok();
+
+    Ok((device, subscribed))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -435,4 +573,22 @@ mod tests {
         // Check that the display string includes the charging status
         assert!(status.to_string().contains("CaseCharging"));
     }
+
+    #[test]
+    fn test_parse_gatt_battery_level() {
+        assert_eq!(parse_battery_level(&[85]).unwrap(), 85);
+        // Extra bytes are ignored
+        assert_eq!(parse_battery_level(&[0, 1]).unwrap(), 0);
+        assert!(parse_battery_level(&[]).is_err());
+        assert!(parse_battery_level(&[101]).is_err());
+
+        let battery = gatt_battery(40);
+        assert_eq!(battery.left, Some(40));
+        assert_eq!(battery.right, Some(40));
+        assert_eq!(battery.case, None);
+        assert_eq!(
+            BATTERY_SERVICE_UUID.to_string(),
+            "0000180f-0000-1000-8000-00805f9b34fb"
+        );
+    }
 }
@@ -0,0 +1,547 @@
+//! Byte-level comparison of two advertisement captures
+//!
+//! `rustpods diff` reads two recorded captures, lines their frames up device by
+//! device and reports every byte that differs, named after the proximity message field
+//! it belongs to, along with the decoded values that changed as a result. When a
+//! firmware update moves or reinterprets a field, the diff of a capture from before
+//! and after shows it directly instead of as a wrong battery level.
+//!
+//! A capture is either scanner JSON (one result, an array of them, or one per line)
+//! or text with a hex payload on each line, such as the output of
+//! `rustpods scan --address`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::airpods::battery::model_name;
+use crate::airpods::{decode_proximity_message, proximity_field_name};
+use crate::config::canonical_address;
+use crate::error::BluetoothError;
+
+/// Key for frames whose capture doesn't say which device sent them
+const UNKNOWN_DEVICE: &str = "unknown device";
+
+/// Apple's company identifier as it appears in front of some payloads
+const APPLE_COMPANY_PREFIX: [u8; 2] = [0x4C, 0x00];
+
+/// Shortest run of hex bytes on a text line taken as a payload
+const MIN_PAYLOAD_LEN: usize = 8;
+
+/// One advertisement from a capture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedFrame {
+    /// Canonical address of the sender, if the capture records it
+    pub address: Option<String>,
+    /// Apple manufacturer data
+    pub payload: Vec<u8>,
+}
+
+/// Read the frames of the capture at `path`
+pub fn load_capture(path: &Path) -> Result<Vec<CapturedFrame>, BluetoothError> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        BluetoothError::InvalidData(format!("Can't read {}: {}", path.display(), e))
+    })?;
+    let frames = parse_capture(&text);
+    if frames.is_empty() {
+        return Err(BluetoothError::InvalidData(format!(
+            "No advertisement frames found in {}",
+            path.display()
+        )));
+    }
+    Ok(frames)
+}
+
+/// Frames in a capture's text, JSON or plain
+pub fn parse_capture(text: &str) -> Vec<CapturedFrame> {
+    let values: Result<Vec<serde_json::Value>, _> = serde_json::Deserializer::from_str(text)
+        .into_iter()
+        .collect();
+    let mut frames = Vec::new();
+    for value in values.iter().flatten() {
+        collect_json_frames(value, &mut frames);
+    }
+    if frames.is_empty() {
+        // Lines of bare hex bytes can parse as JSON numbers
+        frames = text.lines().filter_map(parse_text_line).collect();
+    }
+    frames
+}
+
+/// Frames in a scanner result, a device entry, a saved battery reading or an array
+fn collect_json_frames(value: &serde_json::Value, frames: &mut Vec<CapturedFrame>) {
+    if let Some(items) = value.as_array() {
+        for item in items {
+            collect_json_frames(item, frames);
+        }
+        return;
+    }
+    if let Some(devices) = value.get("devices") {
+        collect_json_frames(devices, frames);
+        return;
+    }
+    let payload = value
+        .get("manufacturer_data_hex")
+        .or_else(|| value.get("raw_manufacturer_data"))
+        .and_then(|payload| payload.as_str())
+        .and_then(parse_hex);
+    let Some(payload) = payload else {
+        return;
+    };
+    let address = value
+        .get("address")
+        .or_else(|| value.get("canonical_address"))
+        .and_then(|address| address.as_str())
+        .and_then(canonical_address);
+    frames.push(frame(address, payload));
+}
+
+/// The payload on a text line, with the address in front of it if there is one
+fn parse_text_line(line: &str) -> Option<CapturedFrame> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let address = tokens
+        .iter()
+        .filter(|token| token.len() == 17)
+        .find_map(|token| canonical_address(token));
+
+    // Longest run of single hex bytes, as in "07 19 01 ..."
+    let mut best: Vec<u8> = Vec::new();
+    let mut run = Vec::new();
+    for token in &tokens {
+        match (token.len() == 2).then(|| u8::from_str_radix(token, 16).ok()) {
+            Some(Some(byte)) => run.push(byte),
+            _ => {
+                if run.len() > best.len() {
+                    best = std::mem::take(&mut run);
+                }
+                run.clear();
+            }
+        }
+    }
+    if run.len() > best.len() {
+        best = run;
+    }
+    // Or a single unbroken hex string
+    if best.len() < MIN_PAYLOAD_LEN {
+        if let Some(bytes) = tokens
+            .iter()
+            .filter(|token| token.len() >= MIN_PAYLOAD_LEN * 2)
+            .find_map(|token| parse_hex(token))
+        {
+            best = bytes;
+        }
+    }
+    (best.len() >= MIN_PAYLOAD_LEN).then(|| frame(address, best))
+}
+
+fn frame(address: Option<String>, mut payload: Vec<u8>) -> CapturedFrame {
+    if payload.starts_with(&APPLE_COMPANY_PREFIX) && payload.get(2) == Some(&0x07) {
+        payload.drain(..APPLE_COMPANY_PREFIX.len());
+    }
+    CapturedFrame { address, payload }
+}
+
+/// Apple manufacturer data from a scanner's hex, without the company identifier
+pub fn payload_from_hex(text: &str) -> Option<Vec<u8>> {
+    parse_hex(text).map(|payload| frame(None, payload).payload)
+}
+
+/// An anonymized capture of `payloads` from a model the model table doesn't know
+///
+/// Only the manufacturer data is kept; the sender's address and name are left out. The
+/// capture reads back with [`parse_capture`], so `rustpods diff` works on it too.
+pub fn unknown_model_capture(model_id: u16, payloads: &[Vec<u8>]) -> String {
+    let devices: Vec<serde_json::Value> = payloads
+        .iter()
+        .map(|payload| {
+            serde_json::json!({
+                "manufacturer_data_hex": payload
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<String>(),
+            })
+        })
+        .collect();
+    let capture = serde_json::json!({
+        "rustpods_version": env!("CARGO_PKG_VERSION"),
+        "model_id": format!("0x{:04X}", model_id),
+        "devices": devices,
+    });
+    serde_json::to_string_pretty(&capture).unwrap_or_default()
+}
+
+/// Bytes from hex, ignoring spaces between them
+fn parse_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// A byte that differs between two aligned frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ByteChange {
+    /// Offset in the payload
+    pub offset: usize,
+    /// Byte in the first capture, if the frame is that long
+    pub before: Option<u8>,
+    /// Byte in the second capture, if the frame is that long
+    pub after: Option<u8>,
+}
+
+/// A decoded value that differs between two aligned frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Field name
+    pub field: &'static str,
+    /// Value in the first capture
+    pub before: String,
+    /// Value in the second capture
+    pub after: String,
+}
+
+/// Differences between the `index`th distinct frames of a device in both captures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameDiff {
+    /// Position among the device's distinct frames
+    pub index: usize,
+    /// Bytes that differ
+    pub bytes: Vec<ByteChange>,
+    /// Decoded values that differ
+    pub fields: Vec<FieldChange>,
+}
+
+/// Comparison of one device's frames in both captures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceDiff {
+    /// Device in the first capture
+    pub before: String,
+    /// Device in the second capture
+    pub after: String,
+    /// Aligned frame pairs that differ
+    pub frames: Vec<FrameDiff>,
+    /// Aligned frame pairs compared
+    pub compared: usize,
+    /// Distinct frames only in the first capture
+    pub only_before: usize,
+    /// Distinct frames only in the second capture
+    pub only_after: usize,
+}
+
+/// Comparison of two captures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureDiff {
+    /// Devices present in both captures
+    pub devices: Vec<DeviceDiff>,
+    /// Devices only in the first capture
+    pub only_before: Vec<String>,
+    /// Devices only in the second capture
+    pub only_after: Vec<String>,
+}
+
+impl CaptureDiff {
+    /// Whether the captures differ at all
+    pub fn is_empty(&self) -> bool {
+        self.only_before.is_empty()
+            && self.only_after.is_empty()
+            && self.devices.iter().all(|device| {
+                device.frames.is_empty() && device.only_before == 0 && device.only_after == 0
+            })
+    }
+}
+
+/// Distinct frames of each device, in capture order
+///
+/// AirPods repeat the same advertisement many times a second, so runs of identical
+/// frames count once; otherwise a longer recording would throw the alignment off.
+fn distinct_frames(frames: &[CapturedFrame]) -> BTreeMap<String, Vec<&[u8]>> {
+    let mut devices: BTreeMap<String, Vec<&[u8]>> = BTreeMap::new();
+    for frame in frames {
+        let key = frame
+            .address
+            .clone()
+            .unwrap_or_else(|| UNKNOWN_DEVICE.to_string());
+        let payloads = devices.entry(key).or_default();
+        if payloads.last() != Some(&frame.payload.as_slice()) {
+            payloads.push(&frame.payload);
+        }
+    }
+    devices
+}
+
+/// Compare two captures
+///
+/// Devices are matched by address. When each capture holds a single device they are
+/// compared whatever their addresses, since that is usually the same model recorded
+/// before and after an update, or two units side by side.
+pub fn diff_captures(before: &[CapturedFrame], after: &[CapturedFrame]) -> CaptureDiff {
+    let before = distinct_frames(before);
+    let after = distinct_frames(after);
+
+    let pairs: Vec<(&String, &String)> = if before.len() == 1 && after.len() == 1 {
+        vec![(before.keys().next().unwrap(), after.keys().next().unwrap())]
+    } else {
+        before
+            .keys()
+            .filter_map(|address| after.get_key_value(address).map(|(key, _)| (address, key)))
+            .collect()
+    };
+
+    let devices = pairs
+        .iter()
+        .map(|(a, b)| diff_device(a, &before[*a], b, &after[*b]))
+        .collect();
+    let unmatched = |side: &BTreeMap<String, Vec<&[u8]>>, first: bool| {
+        side.keys()
+            .filter(|key| {
+                !pairs
+                    .iter()
+                    .any(|(a, b)| if first { a == key } else { b == key })
+            })
+            .cloned()
+            .collect()
+    };
+    CaptureDiff {
+        only_before: unmatched(&before, true),
+        only_after: unmatched(&after, false),
+        devices,
+    }
+}
+
+fn diff_device(
+    before_name: &str,
+    before: &[&[u8]],
+    after_name: &str,
+    after: &[&[u8]],
+) -> DeviceDiff {
+    let frames = before
+        .iter()
+        .zip(after)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(index, (a, b))| FrameDiff {
+            index,
+            bytes: byte_changes(a, b),
+            fields: field_changes(a, b),
+        })
+        .collect();
+    let compared = before.len().min(after.len());
+    DeviceDiff {
+        before: before_name.to_string(),
+        after: after_name.to_string(),
+        frames,
+        compared,
+        only_before: before.len() - compared,
+        only_after: after.len() - compared,
+    }
+}
+
+fn byte_changes(before: &[u8], after: &[u8]) -> Vec<ByteChange> {
+    (0..before.len().max(after.len()))
+        .map(|offset| ByteChange {
+            offset,
+            before: before.get(offset).copied(),
+            after: after.get(offset).copied(),
+        })
+        .filter(|change| change.before != change.after)
+        .collect()
+}
+
+/// Values the parser reads from `payload`, by field
+pub(crate) fn decoded_fields(payload: &[u8]) -> Vec<(&'static str, String)> {
+    let mut fields = Vec::new();
+    if let (Some(low), Some(high)) = (payload.get(3), payload.get(4)) {
+        let model_id = u16::from_le_bytes([*low, *high]);
+        fields.push((
+            "model",
+            format!("{} (0x{:04X})", model_name(model_id), model_id),
+        ));
+    }
+    let message = match decode_proximity_message(payload) {
+        Ok(message) => message,
+        Err(e) => {
+            fields.push(("decoding", format!("failed: {}", e)));
+            return fields;
+        }
+    };
+    let level = |level: Option<u8>| level.map_or("unknown".to_string(), |l| format!("{}%", l));
+    let optional = |value: Option<String>| value.unwrap_or_else(|| "absent".to_string());
+    fields.extend([
+        ("left battery", level(message.battery.left)),
+        ("right battery", level(message.battery.right)),
+        ("case battery", level(message.battery.case)),
+        ("charging", format!("{:?}", message.battery.charging)),
+        ("broadcasting bud", format!("{:?}", message.extras.primary)),
+        ("left in ear", message.extras.left_in_ear().to_string()),
+        ("right in ear", message.extras.right_in_ear().to_string()),
+        ("both in case", message.extras.both_in_case.to_string()),
+        (
+            "lid open",
+            optional(message.extras.lid_open.map(|open| open.to_string())),
+        ),
+        (
+            "lid open count",
+            optional(message.extras.lid_open_count.map(|count| count.to_string())),
+        ),
+        (
+            "color",
+            optional(message.extras.color.map(|color| color.to_string())),
+        ),
+        (
+            "connection state",
+            optional(
+                message
+                    .extras
+                    .connection_state
+                    .map(|state| state.to_string()),
+            ),
+        ),
+    ]);
+    fields
+}
+
+fn field_changes(before: &[u8], after: &[u8]) -> Vec<FieldChange> {
+    let before = decoded_fields(before);
+    let after = decoded_fields(after);
+    let value = |fields: &[(&'static str, String)], name: &str| {
+        fields
+            .iter()
+            .find(|(field, _)| *field == name)
+            .map_or_else(|| "absent".to_string(), |(_, value)| value.clone())
+    };
+    let mut names: Vec<&'static str> = before.iter().map(|(name, _)| *name).collect();
+    for (name, _) in &after {
+        if !names.contains(name) {
+            names.push(name);
+        }
+    }
+    names
+        .into_iter()
+        .filter_map(|field| {
+            let (a, b) = (value(&before, field), value(&after, field));
+            (a != b).then_some(FieldChange {
+                field,
+                before: a,
+                after: b,
+            })
+        })
+        .collect()
+}
+
+impl fmt::Display for CaptureDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let byte = |byte: Option<u8>| byte.map_or("--".to_string(), |b| format!("{:02X}", b));
+        for device in &self.devices {
+            if device.before == device.after {
+                writeln!(f, "Device {}", device.before)?;
+            } else {
+                writeln!(f, "Device {} -> {}", device.before, device.after)?;
+            }
+            writeln!(
+                f,
+                "  {} aligned frames, {} differ",
+                device.compared,
+                device.frames.len()
+            )?;
+            for frame in &device.frames {
+                writeln!(f, "  Frame {}:", frame.index + 1)?;
+                for change in &frame.bytes {
+                    writeln!(
+                        f,
+                        "    byte {:>2} {} -> {}  {}",
+                        change.offset,
+                        byte(change.before),
+                        byte(change.after),
+                        proximity_field_name(change.offset)
+                    )?;
+                }
+                for change in &frame.fields {
+                    writeln!(
+                        f,
+                        "    {}: {} -> {}",
+                        change.field, change.before, change.after
+                    )?;
+                }
+            }
+            if device.only_before > 0 {
+                writeln!(
+                    f,
+                    "  {} more frames in the first capture",
+                    device.only_before
+                )?;
+            }
+            if device.only_after > 0 {
+                writeln!(
+                    f,
+                    "  {} more frames in the second capture",
+                    device.only_after
+                )?;
+            }
+        }
+        for device in &self.only_before {
+            writeln!(f, "Only in the first capture: {}", device)?;
+        }
+        for device in &self.only_after {
+            writeln!(f, "Only in the second capture: {}", device)?;
+        }
+        if self.is_empty() {
+            writeln!(f, "Captures are identical")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_capture_formats() {
+        let json = r#"{"scanner_version":"1","devices":[{"address":"58:26:D7:45:AD:8B","manufacturer_data_hex":"4C000719011420000000000004080900FF0801"}]}"#;
+        let frames = parse_capture(json);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].address.as_deref(), Some("5826d745ad8b"));
+        assert_eq!(frames[0].payload[0], 0x07);
+
+        let text = "12:00:00.000   -40 dBm  07 19 01 14 20 00 00 00 00 00 00 04 08 09 00 FF 08 01 02  L 80%\nnothing here\n";
+        let frames = parse_capture(text);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].address, None);
+        assert_eq!(frames[0].payload.len(), 19);
+    }
+
+    #[test]
+    fn test_diff_annotates_changed_fields() {
+        let frame = |payload: &str| CapturedFrame {
+            address: Some("5826d745ad8b".to_string()),
+            payload: parse_hex(payload).unwrap(),
+        };
+        let before = vec![
+            frame("07190114200000000000000408090000080102"),
+            frame("07190114200000000000000408090000080102"),
+        ];
+        let after = vec![frame("07190114200000000000000407090000080102")];
+
+        let diff = diff_captures(&before, &after);
+        assert_eq!(diff.devices.len(), 1);
+        let device = &diff.devices[0];
+        assert_eq!(device.compared, 1);
+        assert_eq!(device.only_before, 0);
+        assert_eq!(
+            device.frames[0].bytes,
+            vec![ByteChange {
+                offset: 12,
+                before: Some(0x08),
+                after: Some(0x07),
+            }]
+        );
+        assert_eq!(device.frames[0].fields[0].field, "left battery");
+        assert!(diff.to_string().contains("left battery: 80% -> 70%"));
+
+        assert!(diff_captures(&before, &before).is_empty());
+    }
+}
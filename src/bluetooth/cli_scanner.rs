@@ -416,6 +416,7 @@ impl CliScanner {
             battery: Some(battery),
             last_seen: std::time::Instant::now(),
             is_connected: false, // CLI scanner doesn't provide connection status
+            extras: None,
         })
     }
 
@@ -1,19 +1,22 @@
 //! Bluetooth event system for managing device discovery events
 
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 use futures::Stream;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
-use crate::airpods::DetectedAirPods;
-use crate::bluetooth::DiscoveredDevice;
+use crate::airpods::{AirPodsBattery, AirPodsType, DetectedAirPods};
+use crate::bluetooth::{AdapterInfo, DiscoveredDevice, ScanStatistics};
+use crate::config::DeviceAccessList;
 use btleplug::api::BDAddr;
 
 /// Type of BLE event
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EventType {
     /// Device discovered event
     DeviceDiscovered,
@@ -83,6 +86,25 @@ impl BleEvent {
             _ => None,
         }
     }
+
+    /// Get the device name from this event, if available
+    pub fn get_device_name(&self) -> Option<&str> {
+        match self {
+            Self::DeviceDiscovered(device) | Self::DeviceUpdated(device) => device.name.as_deref(),
+            Self::AirPodsDetected(airpods) => airpods.name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether the allow/deny lists let this event through
+    ///
+    /// Events that aren't about a specific device always pass.
+    pub fn is_allowed_by(&self, access: &DeviceAccessList) -> bool {
+        match self.get_device_address() {
+            Some(address) => access.allows(&address.to_string(), self.get_device_name()),
+            None => true,
+        }
+    }
 }
 
 /// Defines which types of events a subscriber is interested in
@@ -168,11 +190,201 @@ impl EventFilter {
     }
 }
 
+/// A kind of event subscribers can ask for by type instead of matching on `BleEvent`
+///
+/// Each subscription owns its topic value, so a topic can remember what it has
+/// already passed on.
+pub trait Topic: Send + 'static {
+    /// What subscribers to this topic receive
+    type Item: Send + 'static;
+
+    /// The item for `event`, or None when the event isn't part of this topic
+    fn extract(&mut self, event: &BleEvent) -> Option<Self::Item>;
+}
+
+/// Every AirPods advertisement
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AirPodsTopic;
+
+impl Topic for AirPodsTopic {
+    type Item = DetectedAirPods;
+
+    fn extract(&mut self, event: &BleEvent) -> Option<DetectedAirPods> {
+        match event {
+            BleEvent::AirPodsDetected(airpods) => Some(airpods.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Adapter and scan lifecycle events
+#[derive(Debug, Clone)]
+pub enum AdapterEvent {
+    /// The adapter was changed
+    Changed(AdapterInfo),
+    /// Scanning has begun
+    ScanStarted,
+    /// A scan cycle has completed
+    ScanCycleCompleted { devices_found: usize },
+    /// Scanning has been manually stopped
+    ScanStopped,
+    /// All scanning has completed
+    ScanningCompleted,
+    /// An error occurred during scanning
+    Error(String),
+}
+
+/// Adapter changes, scan starts and stops, and scan errors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdapterTopic;
+
+impl Topic for AdapterTopic {
+    type Item = AdapterEvent;
+
+    fn extract(&mut self, event: &BleEvent) -> Option<AdapterEvent> {
+        match event {
+            BleEvent::AdapterChanged(adapter) => Some(AdapterEvent::Changed(adapter.clone())),
+            BleEvent::ScanStarted => Some(AdapterEvent::ScanStarted),
+            BleEvent::ScanCycleCompleted { devices_found } => {
+                Some(AdapterEvent::ScanCycleCompleted {
+                    devices_found: *devices_found,
+                })
+            }
+            BleEvent::ScanStopped => Some(AdapterEvent::ScanStopped),
+            BleEvent::ScanningCompleted => Some(AdapterEvent::ScanningCompleted),
+            BleEvent::Error(message) => Some(AdapterEvent::Error(message.clone())),
+            _ => None,
+        }
+    }
+}
+
+/// New battery levels for one device
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatteryChange {
+    /// Device address
+    pub address: BDAddr,
+    /// Type of AirPods device
+    pub device_type: AirPodsType,
+    /// Battery levels and charging state now
+    pub battery: AirPodsBattery,
+}
+
+/// AirPods battery levels, only when they differ from the last ones sent for the device
+///
+/// A device that is lost and found again sends its levels again.
+#[derive(Debug, Clone, Default)]
+pub struct BatteryTopic {
+    /// Last levels sent per device
+    last_sent: HashMap<BDAddr, AirPodsBattery>,
+}
+
+impl Topic for BatteryTopic {
+    type Item = BatteryChange;
+
+    fn extract(&mut self, event: &BleEvent) -> Option<BatteryChange> {
+        match event {
+            BleEvent::AirPodsDetected(airpods) => {
+                let battery = airpods.battery.clone()?;
+                if self.last_sent.get(&airpods.address) == Some(&battery) {
+                    return None;
+                }
+                self.last_sent.insert(airpods.address, battery.clone());
+                Some(BatteryChange {
+                    address: airpods.address,
+                    device_type: airpods.device_type.clone(),
+                    battery,
+                })
+            }
+            BleEvent::DeviceLost(address) => {
+                self.last_sent.remove(address);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// What the broker does when a subscriber's queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backpressure {
+    /// Drop the event for this subscriber and count it
+    #[default]
+    DropNewest,
+    /// End the subscription; the receiver sees its channel close
+    Disconnect,
+}
+
+/// Number of events queued for a subscriber before backpressure applies
+pub const SUBSCRIBER_QUEUE_SIZE: usize = 100;
+
 /// Subscriber ID type
 pub type SubscriberId = u32;
 
+/// Result of handing an event to one subscriber
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Delivery {
+    /// Queued for the subscriber
+    Sent,
+    /// The subscriber's queue was full
+    Full,
+    /// The subscriber dropped its receiver
+    Closed,
+}
+
+/// Queue an item without waiting, so one slow subscriber can't hold up the rest
+fn deliver<T>(sender: &Sender<T>, item: T) -> Delivery {
+    match sender.try_send(item) {
+        Ok(()) => Delivery::Sent,
+        Err(TrySendError::Full(_)) => Delivery::Full,
+        Err(TrySendError::Closed(_)) => Delivery::Closed,
+    }
+}
+
+/// Events a subscriber missed because it fell behind
+#[derive(Debug, Clone, Default)]
+struct Backlog {
+    /// What to do when the queue is full
+    backpressure: Backpressure,
+    /// Events dropped so far
+    dropped: u64,
+}
+
+impl Backlog {
+    fn new(backpressure: Backpressure) -> Self {
+        Self {
+            backpressure,
+            dropped: 0,
+        }
+    }
+
+    /// Account for a delivery; false when the subscription should end
+    fn keep(&mut self, id: SubscriberId, delivery: Delivery) -> bool {
+        match delivery {
+            Delivery::Sent => true,
+            Delivery::Closed => false,
+            Delivery::Full => {
+                self.dropped += 1;
+                match self.backpressure {
+                    Backpressure::DropNewest => {
+                        if self.dropped == 1 {
+                            log::warn!(
+                                "Event subscriber {} is falling behind, dropping events",
+                                id
+                            );
+                        }
+                        true
+                    }
+                    Backpressure::Disconnect => {
+                        log::warn!("Event subscriber {} fell behind and was disconnected", id);
+                        false
+                    }
+                }
+            }
+        }
+    }
+}
+
 /// A subscriber to BLE events
-#[derive(Clone)]
 struct Subscriber {
     /// Unique ID for this subscriber
     id: SubscriberId,
@@ -182,6 +394,37 @@ struct Subscriber {
     filter: EventFilter,
     /// Last activity timestamp
     last_active: Instant,
+    /// Events dropped while the subscriber was behind
+    backlog: Backlog,
+}
+
+/// A topic subscription with its item type erased, so all topics share one list
+trait TopicSink: Send {
+    /// Hand the event to the subscriber; None when it isn't part of the topic
+    fn offer(&mut self, event: &BleEvent) -> Option<Delivery>;
+}
+
+/// The sending half of a topic subscription
+struct TopicSender<T: Topic> {
+    topic: T,
+    sender: Sender<T::Item>,
+}
+
+impl<T: Topic> TopicSink for TopicSender<T> {
+    fn offer(&mut self, event: &BleEvent) -> Option<Delivery> {
+        let item = self.topic.extract(event)?;
+        Some(deliver(&self.sender, item))
+    }
+}
+
+/// A subscriber to one typed topic
+struct TopicSubscriber {
+    /// Unique ID for this subscriber
+    id: SubscriberId,
+    /// Topic and sender channel
+    sink: Box<dyn TopicSink>,
+    /// Events dropped while the subscriber was behind
+    backlog: Backlog,
 }
 
 /// The Bluetooth event broker manages subscribers and distributes events
@@ -190,6 +433,8 @@ pub struct EventBroker {
     next_subscriber_id: SubscriberId,
     /// Active subscribers
     subscribers: Arc<Mutex<Vec<Subscriber>>>,
+    /// Active typed topic subscribers
+    topic_subscribers: Arc<Mutex<Vec<TopicSubscriber>>>,
     /// Timeout for inactive subscribers (set to None to disable)
     inactive_timeout: Option<Duration>,
     /// Handle for the cleanup task
@@ -198,6 +443,13 @@ pub struct EventBroker {
     event_sender: Sender<BleEvent>,
     /// Receiver for internal events
     event_receiver: Arc<Mutex<Option<Receiver<BleEvent>>>>,
+    /// Allow/deny lists; events for excluded devices are dropped
+    device_access: Arc<Mutex<DeviceAccessList>>,
+    /// Window in which an advertisement heard again with the same payload is merged
+    /// into the first copy (None to forward every copy)
+    merge_window: Arc<Mutex<Option<Duration>>>,
+    /// Advertisements and AirPods frames that reached the broker, and copies merged
+    statistics: Arc<Mutex<ScanStatistics>>,
 }
 
 impl Default for EventBroker {
@@ -213,10 +465,14 @@ impl EventBroker {
         Self {
             next_subscriber_id: 1,
             subscribers: Arc::new(Mutex::new(Vec::new())),
+            topic_subscribers: Arc::new(Mutex::new(Vec::new())),
             inactive_timeout: Some(Duration::from_secs(60)), // 1 minute default timeout
             cleanup_task: None,
             event_sender: tx,
             event_receiver: Arc::new(Mutex::new(Some(rx))),
+            device_access: Arc::new(Mutex::new(DeviceAccessList::default())),
+            merge_window: Arc::new(Mutex::new(None)),
+            statistics: Arc::new(Mutex::new(ScanStatistics::default())),
         }
     }
 
@@ -230,25 +486,71 @@ impl EventBroker {
         // Take the receiver
         let rx = self.take_receiver();
         let subscribers = self.subscribers.clone();
+        let topic_subscribers = self.topic_subscribers.clone();
+        let device_access = self.device_access.clone();
+        let merge_window = self.merge_window.clone();
+        let statistics = self.statistics.clone();
 
         // Use tokio::spawn and return the JoinHandle
         let task = tokio::spawn(async move {
             let mut rx = rx;
+            let mut last_forwarded: HashMap<(BDAddr, EventType), (Instant, BleEvent)> =
+                HashMap::new();
             while let Some(event) = rx.recv().await {
+                record_statistics(&mut statistics.lock().unwrap(), &event);
+
+                // Drop events for devices the user has excluded
+                if !event.is_allowed_by(&device_access.lock().unwrap()) {
+                    continue;
+                }
+
+                // Devices repeat their advertisement several times a second, and with
+                // several adapters scanning each one arrives once per adapter; within
+                // the window only the first copy and changes are forwarded
+                let window = *merge_window.lock().unwrap();
+                if let (Some(window), Some(address)) = (window, event.get_device_address()) {
+                    let now = Instant::now();
+                    if let BleEvent::DeviceLost(_) = event {
+                        // Whatever is heard next is news again
+                        last_forwarded.retain(|(seen_address, _), _| *seen_address != address);
+                    } else {
+                        // Updates share the discovery type, so either merges with the other
+                        let key = (address, event.get_type());
+                        if let Some((seen, previous)) = last_forwarded.get(&key) {
+                            if now.duration_since(*seen) < window && same_payload(previous, &event)
+                            {
+                                statistics.lock().unwrap().record_merged_duplicate();
+                                continue;
+                            }
+                        }
+                        last_forwarded.retain(|_, (seen, _)| now.duration_since(*seen) < window);
+                        last_forwarded.insert(key, (now, event.clone()));
+                    }
+                }
+
                 // Distribute the event to all subscribers
                 let mut subscribers_guard = subscribers.lock().unwrap();
                 let now = Instant::now();
 
-                for subscriber in subscribers_guard.iter_mut() {
+                subscribers_guard.retain_mut(|subscriber| {
                     // Update last active timestamp
                     subscriber.last_active = now;
 
                     // Check if the subscriber's filter accepts this event
-                    if subscriber.filter.matches(&event) {
-                        // Try to send the event, ignoring errors if the channel is closed
-                        let _ = subscriber.sender.try_send(event.clone());
+                    if !subscriber.filter.matches(&event) {
+                        return true;
                     }
-                }
+                    let delivery = deliver(&subscriber.sender, event.clone());
+                    subscriber.backlog.keep(subscriber.id, delivery)
+                });
+                drop(subscribers_guard);
+
+                topic_subscribers.lock().unwrap().retain_mut(|subscriber| {
+                    match subscriber.sink.offer(&event) {
+                        Some(delivery) => subscriber.backlog.keep(subscriber.id, delivery),
+                        None => true,
+                    }
+                });
             }
         });
 
@@ -276,7 +578,17 @@ impl EventBroker {
 
     /// Subscribe to events with a custom filter
     pub fn subscribe(&mut self, filter: EventFilter) -> (SubscriberId, Receiver<BleEvent>) {
-        let (tx, rx) = channel(100);
+        self.subscribe_with(filter, Backpressure::default())
+    }
+
+    /// Subscribe to events with a custom filter and a choice of what happens when the
+    /// subscriber falls behind
+    pub fn subscribe_with(
+        &mut self,
+        filter: EventFilter,
+        backpressure: Backpressure,
+    ) -> (SubscriberId, Receiver<BleEvent>) {
+        let (tx, rx) = channel(SUBSCRIBER_QUEUE_SIZE);
         let id = self.next_subscriber_id;
         self.next_subscriber_id += 1;
 
@@ -285,6 +597,7 @@ impl EventBroker {
             sender: tx,
             filter,
             last_active: Instant::now(),
+            backlog: Backlog::new(backpressure),
         };
 
         // Add to shared subscribers list
@@ -293,9 +606,51 @@ impl EventBroker {
         (id, rx)
     }
 
+    /// Subscribe to a typed topic, receiving its items instead of `BleEvent`s
+    ///
+    /// Topic subscriptions last until unsubscribed or until the receiver is dropped;
+    /// the inactive timeout doesn't apply to them.
+    pub fn subscribe_topic<T: Topic>(
+        &mut self,
+        topic: T,
+        backpressure: Backpressure,
+    ) -> (SubscriberId, Receiver<T::Item>) {
+        let (tx, rx) = channel(SUBSCRIBER_QUEUE_SIZE);
+        let id = self.next_subscriber_id;
+        self.next_subscriber_id += 1;
+
+        self.topic_subscribers
+            .lock()
+            .unwrap()
+            .push(TopicSubscriber {
+                id,
+                sink: Box::new(TopicSender { topic, sender: tx }),
+                backlog: Backlog::new(backpressure),
+            });
+
+        (id, rx)
+    }
+
     /// Unsubscribe from events
     pub fn unsubscribe(&mut self, id: SubscriberId) {
         self.subscribers.lock().unwrap().retain(|s| s.id != id);
+        self.topic_subscribers
+            .lock()
+            .unwrap()
+            .retain(|s| s.id != id);
+    }
+
+    /// Events a subscriber missed because its queue was full, or None if it isn't subscribed
+    pub fn dropped_events(&self, id: SubscriberId) -> Option<u64> {
+        if let Some(subscriber) = self.subscribers.lock().unwrap().iter().find(|s| s.id == id) {
+            return Some(subscriber.backlog.dropped);
+        }
+        self.topic_subscribers
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.backlog.dropped)
     }
 
     /// Modify a subscriber's filter
@@ -309,6 +664,26 @@ impl EventBroker {
         }
     }
 
+    /// Replace the allow/deny lists events are checked against
+    pub fn set_device_access(&mut self, access: DeviceAccessList) {
+        *self.device_access.lock().unwrap() = access;
+    }
+
+    /// Merge copies of an advertisement heard within `window` (None to forward all)
+    pub fn set_merge_window(&mut self, window: Option<Duration>) {
+        *self.merge_window.lock().unwrap() = window;
+    }
+
+    /// Number of duplicate events dropped by merging so far
+    pub fn merged_duplicates(&self) -> u64 {
+        self.statistics.lock().unwrap().duplicates_merged
+    }
+
+    /// Totals for the events received so far
+    pub fn statistics(&self) -> ScanStatistics {
+        self.statistics.lock().unwrap().clone()
+    }
+
     /// Set timeout for inactive subscribers (None to disable)
     pub fn set_inactive_timeout(&mut self, timeout: Option<Duration>) {
         self.inactive_timeout = timeout;
@@ -321,8 +696,9 @@ impl EventBroker {
             task.abort();
         }
 
-        // Clear the subscribers list
+        // Clear the subscribers lists
         self.subscribers.lock().unwrap().clear();
+        self.topic_subscribers.lock().unwrap().clear();
 
         // Create a new channel so the old one gets dropped
         let (tx, _) = channel(1);
@@ -353,11 +729,45 @@ impl Clone for EventBroker {
         Self {
             next_subscriber_id: self.next_subscriber_id,
             subscribers: self.subscribers.clone(),
+            topic_subscribers: self.topic_subscribers.clone(),
             inactive_timeout: self.inactive_timeout,
             cleanup_task: None,
             event_sender: self.event_sender.clone(),
             event_receiver: self.event_receiver.clone(),
+            device_access: self.device_access.clone(),
+            merge_window: self.merge_window.clone(),
+            statistics: self.statistics.clone(),
+        }
+    }
+}
+
+/// Count an event towards the scan statistics
+fn record_statistics(statistics: &mut ScanStatistics, event: &BleEvent) {
+    match event {
+        BleEvent::DeviceDiscovered(device) | BleEvent::DeviceUpdated(device) => {
+            statistics.record_advertisement(device.rssi);
+            statistics.last_success = Some(std::time::SystemTime::now());
+        }
+        BleEvent::AirPodsDetected(_) => statistics.record_airpods_frame(),
+        _ => {}
+    }
+}
+
+/// Whether two events carry the same advertisement, ignoring signal strength and time
+fn same_payload(a: &BleEvent, b: &BleEvent) -> bool {
+    match (a, b) {
+        (
+            BleEvent::DeviceDiscovered(a) | BleEvent::DeviceUpdated(a),
+            BleEvent::DeviceDiscovered(b) | BleEvent::DeviceUpdated(b),
+        ) => {
+            a.name == b.name
+                && a.manufacturer_data == b.manufacturer_data
+                && a.service_data == b.service_data
+        }
+        (BleEvent::AirPodsDetected(a), BleEvent::AirPodsDetected(b)) => {
+            a.device_type == b.device_type && a.battery == b.battery && a.extras == b.extras
         }
+        _ => false,
     }
 }
 
@@ -370,8 +780,8 @@ impl Drop for EventBroker {
     }
 }
 
-/// A helper to create a Stream from an event receiver
-pub fn receiver_to_stream(mut rx: Receiver<BleEvent>) -> impl Stream<Item = BleEvent> {
+/// A helper to create a Stream from an event or topic receiver
+pub fn receiver_to_stream<T>(mut rx: Receiver<T>) -> impl Stream<Item = T> {
     async_stream::stream! {
         while let Some(event) = rx.recv().await {
             yield event;
@@ -466,4 +876,151 @@ mod tests {
             "Subscribers should be cleared after shutdown"
         );
     }
+
+    #[tokio::test]
+    async fn test_copies_from_other_adapters_are_merged() {
+        let mut broker = EventBroker::new();
+        broker.set_merge_window(Some(Duration::from_secs(5)));
+        broker.start();
+        let (_, mut rx) = broker.subscribe(EventFilter::all());
+
+        let advertisement = DiscoveredDevice {
+            manufacturer_data: [(0x004C, vec![0x07, 0x19])].into_iter().collect(),
+            ..DiscoveredDevice::default()
+        };
+        let changed = DiscoveredDevice {
+            manufacturer_data: [(0x004C, vec![0x07, 0x1A])].into_iter().collect(),
+            ..DiscoveredDevice::default()
+        };
+        let sender = broker.get_sender();
+        for device in [&advertisement, &advertisement, &changed] {
+            sender
+                .send(BleEvent::DeviceDiscovered(DiscoveredDevice {
+                    rssi: Some(-60),
+                    ..device.clone()
+                }))
+                .await
+                .unwrap();
+        }
+
+        for expected in [&advertisement, &changed] {
+            match rx.recv().await {
+                Some(BleEvent::DeviceDiscovered(device)) => {
+                    assert_eq!(device.manufacturer_data, expected.manufacturer_data)
+                }
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+        assert_eq!(broker.merged_duplicates(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_repeats_after_loss_are_forwarded() {
+        let mut broker = EventBroker::new();
+        broker.set_merge_window(Some(Duration::from_secs(5)));
+        broker.start();
+        let (_, mut rx) = broker.subscribe(EventFilter::all());
+
+        let device = DiscoveredDevice {
+            manufacturer_data: [(0x004C, vec![0x07, 0x19])].into_iter().collect(),
+            ..DiscoveredDevice::default()
+        };
+        let sender = broker.get_sender();
+        for event in [
+            BleEvent::DeviceDiscovered(device.clone()),
+            // An update carrying the same advertisement is a repeat too
+            BleEvent::DeviceUpdated(device.clone()),
+            BleEvent::DeviceLost(device.address),
+            BleEvent::DeviceDiscovered(device.clone()),
+        ] {
+            sender.send(event).await.unwrap();
+        }
+
+        for expected in [
+            EventType::DeviceDiscovered,
+            EventType::DeviceLost,
+            EventType::DeviceDiscovered,
+        ] {
+            assert_eq!(
+                rx.recv().await.map(|event| event.get_type()),
+                Some(expected)
+            );
+        }
+        assert_eq!(broker.merged_duplicates(), 1);
+        assert_eq!(broker.statistics().duplicates_merged, 1);
+    }
+
+    #[test]
+    fn test_battery_topic_sends_changes_only() {
+        let airpods = |left| {
+            BleEvent::AirPodsDetected(DetectedAirPods {
+                battery: Some(AirPodsBattery {
+                    left: Some(left),
+                    ..AirPodsBattery::default()
+                }),
+                ..DetectedAirPods::default()
+            })
+        };
+        let mut topic = BatteryTopic::default();
+
+        assert!(topic.extract(&airpods(80)).is_some());
+        assert!(topic.extract(&airpods(80)).is_none());
+        assert_eq!(
+            topic
+                .extract(&airpods(70))
+                .map(|change| change.battery.left),
+            Some(Some(70))
+        );
+        assert!(topic
+            .extract(&BleEvent::ScanCycleCompleted { devices_found: 1 })
+            .is_none());
+
+        // Found again after being lost, the same levels are news
+        assert!(topic
+            .extract(&BleEvent::DeviceLost(BDAddr::default()))
+            .is_none());
+        assert!(topic.extract(&airpods(70)).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_slow_topic_subscribers() {
+        let mut broker = EventBroker::new();
+        broker.start();
+        let (dropping, mut airpods_rx) =
+            broker.subscribe_topic(AirPodsTopic, Backpressure::DropNewest);
+        let (disconnected, mut adapter_rx) =
+            broker.subscribe_topic(AdapterTopic, Backpressure::Disconnect);
+
+        let sender = broker.get_sender();
+        for _ in 0..SUBSCRIBER_QUEUE_SIZE + 2 {
+            sender
+                .send(BleEvent::AirPodsDetected(DetectedAirPods::default()))
+                .await
+                .unwrap();
+            sender.send(BleEvent::ScanStarted).await.unwrap();
+        }
+        // Round trip through the broker so everything above has been distributed
+        sender.send(BleEvent::ScanStopped).await.unwrap();
+        while sender.capacity() < sender.max_capacity() {
+            tokio::task::yield_now().await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(broker.dropped_events(dropping), Some(2));
+        assert_eq!(broker.dropped_events(disconnected), None);
+
+        let mut received = 0;
+        while airpods_rx.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, SUBSCRIBER_QUEUE_SIZE);
+
+        // The disconnected subscriber keeps what was queued, then sees the end
+        let mut queued = 0;
+        while let Some(event) = adapter_rx.recv().await {
+            assert!(matches!(event, AdapterEvent::ScanStarted));
+            queued += 1;
+        }
+        assert_eq!(queued, SUBSCRIBER_QUEUE_SIZE);
+    }
 }
@@ -44,9 +44,11 @@ impl crate::bluetooth::scanner::AdapterEventsProvider for ExampleMockAdapterEven
     }
 }
 
+/// How long [`scan_with_adapter`] scans
+pub const SCAN_DURATION: Duration = Duration::from_secs(5);
+
 /// Basic adapter discovery example
 pub async fn discover_adapters() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Discovering Bluetooth adapters...");
     let mut scanner = BleScanner::new(
         Arc::new(ExampleMockAdapterEventsProvider),
         ScanConfig::default(),
@@ -55,15 +57,13 @@ pub async fn discover_adapters() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize the scanner (which will find the first available adapter)
     scanner.initialize().await?;
 
-    println!("Found adapter");
+    crate::cli_output::line(crate::cli_output::success("Found adapter"));
 
     Ok(())
 }
 
 /// Basic scanning example with a specific adapter
 pub async fn scan_with_adapter() -> Result<(), Box<dyn std::error::Error>> {
-    println!("Scanning with adapter...");
-
     // Create scanner and initialize
     let mut scanner = BleScanner::new(
         Arc::new(ExampleMockAdapterEventsProvider),
@@ -75,67 +75,66 @@ pub async fn scan_with_adapter() -> Result<(), Box<dyn std::error::Error>> {
     let mut events = scanner.start_scanning().await?;
 
     // Set a timeout for scanning
-    let timeout = tokio::time::sleep(Duration::from_secs(5));
+    let timeout = tokio::time::sleep(SCAN_DURATION);
     tokio::pin!(timeout);
 
     // Process events
-    println!("Scanning for 5 seconds...");
     loop {
         tokio::select! {
             Some(event) = events.recv() => {
                 match event {
                     BleEvent::DeviceDiscovered(device) => {
-                        println!(
+                        crate::cli_output::line(format_args!(
                             "Found device: {} ({}), RSSI: {}, AirPods: {}",
                             device.name.as_deref().unwrap_or("Unnamed"),
                             device.address,
                             device.rssi.unwrap_or(0),
                             if device.is_potential_airpods { "Yes" } else { "No" }
-                        );
+                        ));
                     },
                     BleEvent::DeviceUpdated(device) => {
-                        println!(
+                        crate::cli_output::line(format_args!(
                             "Updated device: {} ({}), RSSI: {}, AirPods: {}",
                             device.name.as_deref().unwrap_or("Unnamed"),
                             device.address,
                             device.rssi.unwrap_or(0),
                             if device.is_potential_airpods { "Yes" } else { "No" }
-                        );
+                        ));
                     },
                     BleEvent::DeviceLost(addr) => {
-                        println!("Lost device: {}", addr);
+                        crate::cli_output::line(format_args!("Lost device: {}", addr));
                     },
                     BleEvent::Error(err) => {
-                        println!("Error: {}", err);
+                        crate::cli_output::line(format_args!("Error: {}", err));
                     },
                     BleEvent::AdapterChanged(info) => {
-                        println!("Adapter changed: {}", info);
+                        crate::cli_output::line(format_args!("Adapter changed: {}", info));
                     },
                     BleEvent::ScanCycleCompleted { devices_found } => {
-                        println!("Scan cycle completed. Found {} devices.", devices_found);
+                        crate::cli_output::line(format_args!("Scan cycle completed. Found {} devices.", devices_found));
                     },
                     BleEvent::ScanningCompleted => {
-                        println!("Scanning completed.");
+                        crate::cli_output::line(format_args!("Scanning completed."));
                         break;
                     },
                     BleEvent::ScanStarted => {
-                        println!("Scanning started.");
+                        crate::cli_output::line(format_args!("Scanning started."));
                     },
                     BleEvent::ScanStopped => {
-                        println!("Scanning stopped.");
+                        crate::cli_output::line(format_args!("Scanning stopped."));
                         break;
                     },
                     BleEvent::AirPodsDetected(airpods) => {
-                        println!("AirPods detected: {:?} - Battery: L:{}% R:{}% Case:{}%",
+                        crate::cli_output::line(format_args!("AirPods detected: {:?} - Battery: L:{}% R:{}% Case:{}%",
                             airpods.device_type,
                             airpods.battery.as_ref().and_then(|b| b.left).unwrap_or(0),
                             airpods.battery.as_ref().and_then(|b| b.right).unwrap_or(0),
-                            airpods.battery.as_ref().and_then(|b| b.case).unwrap_or(0));
+                            airpods.battery.as_ref().and_then(|b| b.case).unwrap_or(0)));
                     }
                 }
             },
             _ = &mut timeout => {
-                println!("Scan timeout reached.");
+                crate::cli_output::line(format_args!("Scan timeout reached."));
                 break;
             }
         }
@@ -233,6 +232,48 @@ pub async fn interval_scanning() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Scan on every adapter at once and report what each one heard
+pub async fn scan_all_adapters() -> Result<(), Box<dyn std::error::Error>> {
+    println!("Scanning on all adapters...");
+
+    let adapters = crate::bluetooth::AdapterManager::new()
+        .await?
+        .get_scanning_adapters()
+        .await?;
+    println!("Found {} adapters that can scan", adapters.len());
+
+    let mut scanner = BleScanner::new(
+        Arc::new(ExampleMockAdapterEventsProvider),
+        ScanConfig::default(),
+    );
+    let mut events = scanner.start_scanning_on_adapters(adapters).await?;
+
+    let deadline = tokio::time::sleep(Duration::from_secs(10));
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            event = events.recv() => match event {
+                Some(BleEvent::AirPodsDetected(airpods)) => {
+                    println!("  - AirPods detected: {:?}", airpods.device_type);
+                }
+                Some(_) => {}
+                None => break,
+            },
+        }
+    }
+
+    for stats in scanner.adapter_stats() {
+        println!(
+            "{}: {} advertisements from {} devices, {} errors",
+            stats.adapter, stats.advertisements, stats.devices, stats.errors
+        );
+    }
+    scanner.stop_scanning().await?;
+
+    Ok(())
+}
+
 /// AirPods filtering example using CLI scanner
 pub async fn airpods_filtering() -> Result<(), Box<dyn std::error::Error>> {
     println!("AirPods filtering demo...");
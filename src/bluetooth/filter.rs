@@ -6,6 +6,7 @@ use btleplug::api::BDAddr;
 use std::collections::HashSet;
 
 use crate::bluetooth::DiscoveredDevice;
+use crate::config::DeviceAccessList;
 
 /// Filter for Bluetooth devices
 pub trait DeviceFilter: Send + Sync {
@@ -168,6 +169,20 @@ impl DeviceFilter for FunctionFilter {
     }
 }
 
+impl DeviceFilter for DeviceAccessList {
+    fn apply_filter(&self, devices: &[DiscoveredDevice]) -> Vec<DiscoveredDevice> {
+        devices
+            .iter()
+            .filter(|d| self.matches(d))
+            .cloned()
+            .collect()
+    }
+
+    fn matches(&self, device: &DiscoveredDevice) -> bool {
+        self.allows(&device.address.to_string(), device.name.as_deref())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
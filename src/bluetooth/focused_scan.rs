@@ -0,0 +1,276 @@
+//! Scanning for one device only
+//!
+//! Debugging a single pair of AirPods is hard when every device in the room goes
+//! through detection, merging and estimation. A focused scan drops every other address
+//! as soon as it is known, before properties are read or frames parsed, and reports
+//! each frame from the one device in full instead.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use btleplug::api::{Central, CentralEvent, Manager as _, Peripheral as _, ScanFilter};
+use btleplug::platform::Manager;
+use futures::StreamExt;
+
+use crate::airpods::detect_airpods;
+use crate::bluetooth::cli_scanner::CliDeviceInfo;
+use crate::bluetooth::DiscoveredDevice;
+use crate::config::canonical_address;
+use crate::error::BluetoothError;
+
+/// Apple's Bluetooth company identifier, the key of AirPods manufacturer data
+const APPLE_COMPANY_ID: u16 = 0x004C;
+
+/// Frames kept for display while focused
+const FRAMES_KEPT: usize = 20;
+
+/// Everything received in one frame from the focused device
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameReport {
+    /// When the frame arrived
+    pub received: SystemTime,
+    /// Signal strength, if reported
+    pub rssi: Option<i16>,
+    /// Apple manufacturer data as hex
+    pub payload: String,
+    /// Battery readings decoded from the frame
+    pub battery: Option<String>,
+}
+
+impl FrameReport {
+    /// Report a device from a native scan
+    pub fn from_device(device: &DiscoveredDevice) -> Self {
+        let payload = device
+            .manufacturer_data
+            .get(&APPLE_COMPANY_ID)
+            .map(|data| hex(data))
+            .unwrap_or_default();
+        let battery = detect_airpods(device)
+            .ok()
+            .flatten()
+            .and_then(|airpods| airpods.battery)
+            .map(|battery| {
+                let level =
+                    |level: Option<u8>| level.map_or("?".to_string(), |l| format!("{}%", l));
+                format!(
+                    "L {} R {} C {}",
+                    level(battery.left),
+                    level(battery.right),
+                    level(battery.case)
+                )
+            });
+        Self {
+            received: SystemTime::now(),
+            rssi: device.rssi,
+            payload,
+            battery,
+        }
+    }
+
+    /// Report a device from the CLI scanner's output
+    pub fn from_cli(device: &CliDeviceInfo) -> Self {
+        let battery = device.airpods_data.as_ref().map(|data| {
+            let charging = [
+                ("left", data.left_charging),
+                ("right", data.right_charging),
+                ("case", data.case_charging),
+            ]
+            .into_iter()
+            .filter(|(_, charging)| *charging)
+            .map(|(part, _)| part)
+            .collect::<Vec<_>>();
+            let mut text = format!(
+                "{} L {}% R {}% C {}%",
+                data.model, data.left_battery, data.right_battery, data.case_battery
+            );
+            if !charging.is_empty() {
+                text.push_str(&format!(" charging: {}", charging.join(", ")));
+            }
+            text
+        });
+        Self {
+            received: SystemTime::now(),
+            rssi: i16::try_from(device.rssi).ok().filter(|rssi| *rssi != 0),
+            payload: device.manufacturer_data_hex.clone(),
+            battery,
+        }
+    }
+}
+
+impl fmt::Display for FrameReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = chrono::DateTime::<chrono::Local>::from(self.received);
+        write!(f, "{}", time.format("%H:%M:%S%.3f"))?;
+        match self.rssi {
+            Some(rssi) => write!(f, "  {:>4} dBm", rssi)?,
+            None => write!(f, "     ? dBm")?,
+        }
+        write!(f, "  {}", self.payload)?;
+        if let Some(battery) = &self.battery {
+            write!(f, "  {}", battery)?;
+        }
+        Ok(())
+    }
+}
+
+/// The address a focused scan is limited to, and the frames received from it
+///
+/// Clones share the same state, so the scan running in the background and the UI
+/// showing its frames see the same thing.
+#[derive(Debug, Clone, Default)]
+pub struct FocusedScan {
+    state: Arc<Mutex<Option<(String, VecDeque<FrameReport>)>>>,
+}
+
+impl FocusedScan {
+    /// Not focused on any device
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit scanning to `address` (canonical form), dropping earlier frames
+    pub fn focus(&self, address: String) {
+        *self.state.lock().unwrap() = Some((address, VecDeque::new()));
+    }
+
+    /// Go back to processing every device
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = None;
+    }
+
+    /// Canonical address scanning is limited to
+    pub fn address(&self) -> Option<String> {
+        self.state
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(address, _)| address.clone())
+    }
+
+    /// Whether a device at `address` should be processed
+    pub fn allows(&self, address: &str) -> bool {
+        match self.state.lock().unwrap().as_ref() {
+            Some((focus, _)) => canonical_address(address).as_ref() == Some(focus),
+            None => true,
+        }
+    }
+
+    /// Keep a frame from the focused device, logging it
+    pub fn record(&self, frame: FrameReport) {
+        if let Some((address, frames)) = self.state.lock().unwrap().as_mut() {
+            log::info!("Focused scan {}: {}", address, frame);
+            if frames.len() == FRAMES_KEPT {
+                frames.pop_front();
+            }
+            frames.push_back(frame);
+        }
+    }
+
+    /// Frames received while focused, oldest first
+    pub fn frames(&self) -> Vec<FrameReport> {
+        self.state
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(_, frames)| frames.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Scan natively for `duration`, printing every frame from the device at `address`
+///
+/// Returns how many frames were received.
+pub async fn scan_address(address: &str, duration: Duration) -> Result<usize, BluetoothError> {
+    let focus = canonical_address(address)
+        .ok_or_else(|| BluetoothError::InvalidData(format!("'{}' is not an address", address)))?;
+
+    let manager = Manager::new().await?;
+    let adapter = manager
+        .adapters()
+        .await?
+        .into_iter()
+        .next()
+        .ok_or(BluetoothError::NoAdapter)?;
+    let mut events = adapter.events().await?;
+    adapter.start_scan(ScanFilter::default()).await?;
+
+    let mut frames = 0;
+    let deadline = tokio::time::sleep(duration);
+    tokio::pin!(deadline);
+    loop {
+        let event = tokio::select! {
+            _ = &mut deadline => break,
+            event = events.next() => match event {
+                Some(event) => event,
+                None => break,
+            },
+        };
+        let id = match event {
+            CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id) => id,
+            _ => continue,
+        };
+        let Ok(peripheral) = adapter.peripheral(&id).await else {
+            continue;
+        };
+        // Other devices are dropped here, before their properties are read
+        if canonical_address(&peripheral.address().to_string()).as_ref() != Some(&focus) {
+            continue;
+        }
+        match DiscoveredDevice::from_peripheral(&peripheral).await {
+            Ok(device) => {
+                frames += 1;
+                crate::cli_output::line(FrameReport::from_device(&device));
+            }
+            Err(e) => crate::cli_output::line(crate::cli_output::warning(format!(
+                "Unreadable frame: {}",
+                e
+            ))),
+        }
+    }
+
+    if let Err(e) = adapter.stop_scan().await {
+        log::warn!("Failed to stop focused scan: {}", e);
+    }
+    Ok(frames)
+}
+
+/// Bytes as space-separated hex pairs
+fn hex(data: &[u8]) -> String {
+    data.iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_focus_limits_to_one_address() {
+        let focused = FocusedScan::new();
+        assert!(focused.allows("11:22:33:44:55:66"));
+
+        focused.focus("5826d745ad8b".to_string());
+        assert!(focused.allows("58:26:D7:45:AD:8B"));
+        assert!(!focused.allows("11:22:33:44:55:66"));
+
+        for rssi in 0..FRAMES_KEPT as i16 + 5 {
+            focused.record(FrameReport {
+                received: SystemTime::now(),
+                rssi: Some(-rssi),
+                payload: String::new(),
+                battery: None,
+            });
+        }
+        let frames = focused.frames();
+        assert_eq!(frames.len(), FRAMES_KEPT);
+        assert_eq!(frames[0].rssi, Some(-5));
+
+        focused.clear();
+        assert!(focused.frames().is_empty());
+        assert!(focused.allows("11:22:33:44:55:66"));
+    }
+}
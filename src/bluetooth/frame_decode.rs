@@ -0,0 +1,298 @@
+//! Annotated decoding of a single advertisement
+//!
+//! `rustpods decode <hex>` takes Apple manufacturer data as users paste it into
+//! support threads and prints every byte with the field it belongs to and what the
+//! parser makes of it, followed by the decoded values. It also says which layout and
+//! model table entry were applied, so a wrong reading can be traced to the byte and the
+//! rule that produced it.
+
+use std::fmt;
+
+use crate::airpods::model_table::ModelTable;
+use crate::airpods::{
+    extract_battery_level, is_flipped, proximity_field_name, BOTH_IN_CASE_MASK,
+    CASE_BATTERY_OFFSET, CHARGING_STATUS_OFFSET, COLOR_OFFSET, CONNECTION_STATE_OFFSET,
+    FLIP_FLAG_MASK, FLIP_STATUS_OFFSET, LEFT_BATTERY_OFFSET, LID_CLOSED_MASK, LID_OPEN_COUNT_MASK,
+    LID_STATUS_OFFSET, PRIMARY_IN_EAR_MASK, RIGHT_BATTERY_OFFSET, SECONDARY_IN_EAR_MASK,
+};
+use crate::airpods::{DeviceColor, ProximityConnectionState};
+use crate::bluetooth::capture_diff::{decoded_fields, payload_from_hex};
+use crate::error::BluetoothError;
+
+/// Type byte of proximity pairing messages
+const PROXIMITY_PAIRING: u8 = 0x07;
+
+/// Length of a message carrying the lid, color and connection state bytes
+const FULL_LAYOUT_LEN: usize = CONNECTION_STATE_OFFSET + 1;
+
+/// Length of the shortest message the parser accepts
+const SHORT_LAYOUT_LEN: usize = 16;
+
+/// One byte of a decoded advertisement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnnotatedByte {
+    /// Offset in the payload
+    pub offset: usize,
+    /// Raw value
+    pub value: u8,
+    /// Field the byte belongs to
+    pub field: &'static str,
+    /// What the parser reads from the byte, if anything
+    pub meaning: String,
+}
+
+/// A decoded advertisement with every byte annotated
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedFrame {
+    /// Apple manufacturer data, without the company identifier
+    pub payload: Vec<u8>,
+    /// Message layout the parser applied
+    pub layout: String,
+    /// Model table entry matching the prefix, if any
+    pub table_entry: Option<String>,
+    /// Every byte with its field
+    pub bytes: Vec<AnnotatedByte>,
+    /// Values the parser reads, by field
+    pub fields: Vec<(&'static str, String)>,
+}
+
+/// Decode manufacturer data pasted as hex
+///
+/// Spaces, colons, dashes and commas between bytes and a leading `0x` are ignored, and
+/// Apple's company identifier is dropped if it is in front.
+pub fn decode_hex(text: &str) -> Result<DecodedFrame, BluetoothError> {
+    let trimmed = text.trim();
+    let digits: String = trimmed
+        .strip_prefix("0x")
+        .or_else(|| trimmed.strip_prefix("0X"))
+        .unwrap_or(trimmed)
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '\t' | ':' | '-' | ','))
+        .collect();
+    let payload = payload_from_hex(&digits).ok_or_else(|| {
+        BluetoothError::InvalidData(format!("'{}' is not a hex string of whole bytes", text))
+    })?;
+    Ok(decode_payload(&payload))
+}
+
+/// Decode manufacturer data without the company identifier
+pub fn decode_payload(payload: &[u8]) -> DecodedFrame {
+    let proximity = payload.first() == Some(&PROXIMITY_PAIRING);
+    let layout = if !proximity {
+        format!(
+            "not a proximity pairing message (type 0x{:02X}); no parser applies",
+            payload.first().copied().unwrap_or_default()
+        )
+    } else if payload.len() >= FULL_LAYOUT_LEN {
+        format!(
+            "proximity pairing, full layout ({} bytes: battery, lid, color, connection)",
+            payload.len()
+        )
+    } else if payload.len() >= SHORT_LAYOUT_LEN {
+        format!(
+            "proximity pairing, short layout ({} bytes: battery only)",
+            payload.len()
+        )
+    } else {
+        format!(
+            "proximity pairing, truncated ({} bytes; the parser needs at least {})",
+            payload.len(),
+            SHORT_LAYOUT_LEN
+        )
+    };
+    let table_entry = ModelTable::global().prefix(payload).map(|entry| {
+        format!(
+            "prefix {} -> {:?}{}",
+            entry.prefix,
+            entry.device_type,
+            if entry.name_hints.is_empty() {
+                ""
+            } else {
+                " (refined by device name)"
+            }
+        )
+    });
+
+    let bytes = payload
+        .iter()
+        .enumerate()
+        .map(|(offset, &value)| AnnotatedByte {
+            offset,
+            value,
+            field: if proximity {
+                proximity_field_name(offset)
+            } else {
+                "unknown"
+            },
+            meaning: if proximity {
+                byte_meaning(payload, offset, value)
+            } else {
+                String::new()
+            },
+        })
+        .collect();
+    let fields = if proximity {
+        decoded_fields(payload)
+    } else {
+        Vec::new()
+    };
+
+    DecodedFrame {
+        payload: payload.to_vec(),
+        layout,
+        table_entry,
+        bytes,
+        fields,
+    }
+}
+
+/// What the parser reads from the byte at `offset` of a proximity message
+fn byte_meaning(payload: &[u8], offset: usize, value: u8) -> String {
+    let level = |value: u8| {
+        extract_battery_level(value).map_or("unknown (0xFF)".to_string(), |l| format!("{}%", l))
+    };
+    // Left and right are reported from the broadcasting bud's side
+    let flipped = is_flipped(payload);
+    match offset {
+        1 => format!("{} bytes follow", value),
+        3 => {
+            let model_id = u16::from_le_bytes([value, payload.get(4).copied().unwrap_or(0)]);
+            let name = ModelTable::global().model_name(model_id);
+            format!(
+                "0x{:04X} {}",
+                model_id,
+                name.unwrap_or("(not in the model table)")
+            )
+        }
+        4 => "model ID high byte".to_string(),
+        FLIP_STATUS_OFFSET => {
+            let flags: Vec<&str> = [
+                (FLIP_FLAG_MASK, "flipped (right bud broadcasting)"),
+                (PRIMARY_IN_EAR_MASK, "primary in ear"),
+                (SECONDARY_IN_EAR_MASK, "secondary in ear"),
+                (BOTH_IN_CASE_MASK, "both in case"),
+            ]
+            .iter()
+            .filter(|(mask, _)| value & mask != 0)
+            .map(|(_, name)| *name)
+            .collect();
+            format!(
+                "{:08b}: {}",
+                value,
+                if flags.is_empty() {
+                    "no flags".to_string()
+                } else {
+                    flags.join(", ")
+                }
+            )
+        }
+        LEFT_BATTERY_OFFSET => format!(
+            "{} ({} bud)",
+            level(value),
+            if flipped { "right" } else { "left" }
+        ),
+        RIGHT_BATTERY_OFFSET => format!(
+            "{} ({} bud)",
+            level(value),
+            if flipped { "left" } else { "right" }
+        ),
+        CHARGING_STATUS_OFFSET => {
+            let state = match value {
+                0 => "nothing charging",
+                1 => "first bud charging",
+                2 => "second bud charging",
+                4 => "case charging",
+                5 => "both buds charging",
+                _ => "unknown code",
+            };
+            format!("{:08b}: {}", value, state)
+        }
+        CASE_BATTERY_OFFSET => level(value),
+        LID_STATUS_OFFSET => format!(
+            "{:08b}: lid {}, opened {} times (mod 8)",
+            value,
+            if value & LID_CLOSED_MASK == 0 {
+                "open"
+            } else {
+                "closed"
+            },
+            value & LID_OPEN_COUNT_MASK
+        ),
+        COLOR_OFFSET => DeviceColor::from_code(value).to_string(),
+        CONNECTION_STATE_OFFSET => ProximityConnectionState::from_code(value).to_string(),
+        offset if offset > CONNECTION_STATE_OFFSET => "encrypted".to_string(),
+        _ => String::new(),
+    }
+}
+
+impl fmt::Display for DecodedFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Payload: {}",
+            self.payload
+                .iter()
+                .map(|byte| format!("{:02X}", byte))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )?;
+        writeln!(f, "Parser:  {}", self.layout)?;
+        writeln!(
+            f,
+            "Table:   {}",
+            self.table_entry
+                .as_deref()
+                .unwrap_or("no model table prefix matches")
+        )?;
+        writeln!(f)?;
+        for byte in &self.bytes {
+            writeln!(
+                f,
+                "  byte {:>2}  {:02X}  {:<22} {}",
+                byte.offset, byte.value, byte.field, byte.meaning
+            )?;
+        }
+        if !self.fields.is_empty() {
+            writeln!(f)?;
+            writeln!(f, "Decoded:")?;
+            for (field, value) in &self.fields {
+                writeln!(f, "  {:<18} {}", field, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_annotates_every_byte() {
+        let frame =
+            decode_hex("4C 00 07 19 01 14 20 00 00 00 00 00 00 22 08 09 01 05 31 03 05 AA BB")
+                .unwrap();
+        assert_eq!(frame.payload.len(), 21);
+        assert!(frame.layout.contains("full layout"));
+        assert!(frame.table_entry.is_some());
+
+        assert!(frame.bytes[3].meaning.contains("0x2014"));
+        assert_eq!(frame.bytes[11].field, "status (flip, in-ear)");
+        assert!(frame.bytes[11].meaning.contains("flipped"));
+        // Flipped, so the first battery byte is the right bud's
+        assert_eq!(frame.bytes[12].meaning, "80% (right bud)");
+        assert!(frame.bytes[16].meaning.contains("lid open, opened 1 times"));
+        assert_eq!(frame.bytes[20].meaning, "encrypted");
+        assert!(frame
+            .fields
+            .iter()
+            .any(|(field, value)| *field == "left battery" && value == "90%"));
+
+        assert_eq!(decode_hex("07:19:01").unwrap().bytes.len(), 3);
+        assert!(decode_hex("12 02 00")
+            .unwrap()
+            .layout
+            .starts_with("not a proximity"));
+        assert!(decode_hex("0x07 1").is_err());
+    }
+}
@@ -3,29 +3,58 @@
 pub mod adapter;
 pub mod battery;
 pub mod battery_monitor;
+pub mod capture_diff;
 pub mod cli_scanner;
 pub mod events;
 pub mod examples;
 mod filter;
+pub mod focused_scan;
+pub mod frame_decode;
 mod peripheral;
+pub mod preflight;
+pub mod scan_statistics;
 pub mod scanner;
 pub mod scanner_config;
+pub mod scanner_supervisor;
+pub mod synthetic_frames;
+pub mod win32_enum;
 
 // Import error types from crate root
 use crate::error::{BluetoothError, ErrorContext, RecoveryAction};
 use std::fmt::Debug;
 
 // Re-export all necessary types from scanner
-pub use scanner::{parse_bdaddr, BleScanner, BleScannerConfig, DiscoveredDevice};
+pub use scanner::{
+    parse_bdaddr, AdapterScanStats, BleScanner, BleScannerConfig, DiscoveredDevice,
+    ADAPTER_MERGE_WINDOW,
+};
 
 // Re-export ScanConfig
 pub use scanner_config::ScanConfig;
 
+pub use capture_diff::{diff_captures, load_capture, CaptureDiff, CapturedFrame};
+pub use focused_scan::{scan_address, FocusedScan, FrameReport};
+pub use frame_decode::{decode_hex, DecodedFrame};
+pub use preflight::RadioStatus;
+pub use scan_statistics::ScanStatistics;
+pub use scanner_supervisor::{ScannerHealth, ScannerSupervisor, SCANNER_TIMEOUT};
+pub use synthetic_frames::{
+    frame_from_payload, SyntheticFrames, SyntheticPreset, DEFAULT_SYNTHETIC_ADDRESS,
+};
+pub use win32_enum::{is_classically_connected, ClassicDevice};
+
 pub use adapter::{AdapterInfo, AdapterManager};
 
-pub use events::{receiver_to_stream, BleEvent, EventBroker, EventFilter, SubscriberId};
+pub use events::{
+    receiver_to_stream, AdapterEvent, AdapterTopic, AirPodsTopic, Backpressure, BatteryChange,
+    BatteryTopic, BleEvent, EventBroker, EventFilter, SubscriberId, Topic,
+};
 
-pub use battery::{extract_battery_status, start_battery_monitoring, AirPodsBatteryStatus};
+pub use battery::{
+    extract_battery_status, has_proximity_data, read_gatt_battery, start_battery_monitoring,
+    start_gatt_battery_monitoring, AirPodsBatteryStatus, BATTERY_LEVEL_CHARACTERISTIC_UUID,
+    BATTERY_SERVICE_UUID,
+};
 
 pub use battery_monitor::{BatteryAlert, BatteryMonitor, BatteryMonitorOptions};
 
@@ -34,7 +63,9 @@ pub use cli_scanner::{
 };
 
 // Export examples for testing
-pub use examples::{airpods_filtering, discover_adapters, interval_scanning, scan_with_adapter};
+pub use examples::{
+    airpods_filtering, discover_adapters, interval_scanning, scan_all_adapters, scan_with_adapter,
+};
 
 pub struct BluetoothManager;
 
@@ -60,6 +91,7 @@ pub fn bluetooth_error_with_recovery(
     match error_type {
         BluetoothError::ApiError(e) => BluetoothError::ApiError(e),
         BluetoothError::NoAdapter => BluetoothError::NoAdapter,
+        BluetoothError::RadioOff => BluetoothError::RadioOff,
         BluetoothError::ScanFailed(msg) => BluetoothError::ScanFailed(msg),
         BluetoothError::DeviceNotFound(msg) => BluetoothError::DeviceNotFound(msg),
         BluetoothError::InvalidData(msg) => BluetoothError::InvalidData(msg),
@@ -108,6 +140,7 @@ where
             BluetoothError::DeviceNotFound(_) => RecoveryAction::Retry,
             BluetoothError::ScanFailed(_) => RecoveryAction::RestartApplication,
             BluetoothError::NoAdapter => RecoveryAction::SelectDifferentAdapter,
+            BluetoothError::RadioOff => RecoveryAction::TurnOnBluetooth,
             BluetoothError::Timeout(_) => RecoveryAction::Retry,
             _ => RecoveryAction::NotifyUser,
         });
@@ -2,20 +2,16 @@
 //!
 //! Provides utilities for managing Bluetooth peripherals and connections
 
-use std::time::Duration;
-
 use btleplug::api::{BDAddr, CharPropFlags, Characteristic, Peripheral as _, WriteType};
 use btleplug::platform::Peripheral;
-use log::{debug, error, info, warn};
+use log::{debug, error, info};
 use std::fmt;
 use std::sync::Arc;
 use std::sync::Mutex;
 use uuid::Uuid;
 
 use crate::error::{BluetoothError, ErrorContext};
-
-/// Maximum connection attempts
-const MAX_CONNECTION_ATTEMPTS: u8 = 3;
+use crate::retry::{Retry, RetryDomain};
 
 /// Connection timeout in seconds
 #[allow(dead_code)]
@@ -98,50 +94,44 @@ impl BlePeripheral {
             return Ok(());
         }
 
-        // Try to connect with retries
-        let max_attempts = MAX_CONNECTION_ATTEMPTS;
-        let mut attempt = 0;
+        // Try to connect, retrying as the Bluetooth retry policy allows
+        let mut retry = Retry::new("Connecting to the device", RetryDomain::Bluetooth);
 
-        while attempt < max_attempts {
-            attempt += 1;
+        loop {
             debug!(
-                "{}Attempting to connect (attempt {}/{})",
-                ctx, attempt, max_attempts
+                "{}Attempting to connect (attempt {})",
+                ctx,
+                retry.attempts() + 1
             );
 
             match self.peripheral.connect().await {
                 Ok(()) => {
                     self.is_connected = true;
+                    retry.succeeded();
                     info!("{}Successfully connected", ctx);
                     return Ok(());
                 }
                 Err(e) => {
-                    if attempt < max_attempts {
-                        warn!(
-                            "{}Connection attempt {} failed: {}. Retrying...",
-                            ctx, attempt, e
-                        );
-                        // Wait before retry with increasing delay
-                        tokio::time::sleep(Duration::from_millis(500 * attempt as u64)).await;
-                        continue;
-                    } else {
-                        error!(
-                            "{}Failed to connect after {} attempts: {}",
-                            ctx, max_attempts, e
-                        );
-                        return Err(BluetoothError::ConnectionFailed(format!(
-                            "Failed after {} attempts: {}",
-                            max_attempts, e
-                        )));
+                    let error = BluetoothError::from(e);
+                    match retry.failed(&error) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => {
+                            error!(
+                                "{}Failed to connect after {} attempts: {}",
+                                ctx,
+                                retry.attempts(),
+                                error
+                            );
+                            return Err(BluetoothError::ConnectionFailed(format!(
+                                "Failed after {} attempts: {}",
+                                retry.attempts(),
+                                error
+                            )));
+                        }
                     }
                 }
             }
         }
-
-        // This should never be reached due to the error in the loop
-        Err(BluetoothError::ConnectionFailed(
-            "Failed to connect".to_string(),
-        ))
     }
 
     /// Disconnect from the device
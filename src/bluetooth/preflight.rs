@@ -0,0 +1,154 @@
+//! Checks that Bluetooth can be used before a scan starts
+//!
+//! With the radio switched off or radio access turned off in the Windows privacy
+//! settings, the scanner fails with errors that don't say why. The radio state and
+//! access are read through `Windows.Devices.Radios` first, so the scan can be skipped
+//! and the user told what to turn on. When the state can't be read, for example with a
+//! Bluetooth stack that doesn't expose its radio to Windows, scanning goes ahead.
+
+use crate::error::{BluetoothError, RecoveryAction};
+
+/// Windows Settings page with the Bluetooth switch
+pub const BLUETOOTH_SETTINGS_URI: &str = "ms-settings:bluetooth";
+
+/// Windows Settings page deciding which apps may control radios
+pub const RADIO_PRIVACY_SETTINGS_URI: &str = "ms-settings:privacy-radios";
+
+/// State of the Bluetooth radio, as far as scanning is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadioStatus {
+    /// A Bluetooth radio is on and may be used
+    Ready,
+    /// Every Bluetooth radio is switched off
+    Off,
+    /// The adapter is disabled in Device Manager
+    Disabled,
+    /// Radio access is turned off in the Windows privacy settings
+    DeniedByUser,
+    /// Radio access is blocked by policy
+    DeniedBySystem,
+    /// The state couldn't be read
+    Unknown,
+}
+
+impl RadioStatus {
+    /// The error a scan would run into, `None` if it can go ahead
+    pub fn error(self) -> Option<BluetoothError> {
+        match self {
+            RadioStatus::Ready | RadioStatus::Unknown => None,
+            RadioStatus::Off => Some(BluetoothError::RadioOff),
+            RadioStatus::Disabled => Some(BluetoothError::AdapterNotAvailable {
+                reason: "The Bluetooth adapter is disabled in Device Manager".to_string(),
+                recovery: RecoveryAction::NotifyUser,
+            }),
+            RadioStatus::DeniedByUser => Some(BluetoothError::PermissionDenied(
+                "Apps aren't allowed to control radios in the Windows privacy settings".to_string(),
+            )),
+            RadioStatus::DeniedBySystem => Some(BluetoothError::PermissionDenied(
+                "Radio access is blocked by your organization's policy".to_string(),
+            )),
+        }
+    }
+}
+
+/// Read the state of the Bluetooth radio
+pub fn radio_status() -> RadioStatus {
+    platform::radio_status()
+}
+
+/// Whether a scan can start, or the error explaining why not
+pub fn check() -> Result<(), BluetoothError> {
+    match radio_status().error() {
+        Some(error) => {
+            log::debug!("Bluetooth preflight failed: {}", error);
+            Err(error)
+        }
+        None => Ok(()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::RadioStatus;
+    use windows::Devices::Radios::{Radio, RadioAccessStatus, RadioKind, RadioState};
+
+    pub fn radio_status() -> RadioStatus {
+        read_status().unwrap_or_else(|e| {
+            log::debug!("Couldn't read the Bluetooth radio state: {}", e);
+            RadioStatus::Unknown
+        })
+    }
+
+    fn read_status() -> windows::core::Result<RadioStatus> {
+        match Radio::RequestAccessAsync()?.get()? {
+            RadioAccessStatus::DeniedByUser => return Ok(RadioStatus::DeniedByUser),
+            RadioAccessStatus::DeniedBySystem => return Ok(RadioStatus::DeniedBySystem),
+            _ => {}
+        }
+
+        let mut off = false;
+        let mut disabled = false;
+        for radio in Radio::GetRadiosAsync()?.get()? {
+            if radio.Kind()? != RadioKind::Bluetooth {
+                continue;
+            }
+            match radio.State()? {
+                RadioState::On => return Ok(RadioStatus::Ready),
+                RadioState::Disabled => disabled = true,
+                _ => off = true,
+            }
+        }
+        // No radio listed at all happens with stacks Windows can't manage; let the scan try
+        Ok(if off {
+            RadioStatus::Off
+        } else if disabled {
+            RadioStatus::Disabled
+        } else {
+            RadioStatus::Unknown
+        })
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::RadioStatus;
+
+    pub fn radio_status() -> RadioStatus {
+        RadioStatus::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_blocked_radios_stop_the_scan() {
+        assert!(RadioStatus::Ready.error().is_none());
+        assert!(RadioStatus::Unknown.error().is_none());
+        assert!(matches!(
+            RadioStatus::Off.error(),
+            Some(BluetoothError::RadioOff)
+        ));
+        assert!(matches!(
+            RadioStatus::DeniedByUser.error(),
+            Some(BluetoothError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            RadioStatus::Disabled.error(),
+            Some(BluetoothError::AdapterNotAvailable { .. })
+        ));
+    }
+
+    #[test]
+    fn test_banner_offers_the_settings_page() {
+        use crate::error::RustPodsError;
+
+        let off = RustPodsError::BluetoothError(BluetoothError::RadioOff);
+        assert_eq!(off.recovery_action(), RecoveryAction::TurnOnBluetooth);
+        assert_eq!(off.user_message(), "Bluetooth is turned off");
+
+        let denied = RustPodsError::BluetoothError(RadioStatus::DeniedByUser.error().unwrap());
+        assert_eq!(denied.recovery_action(), RecoveryAction::AllowRadioAccess);
+    }
+}
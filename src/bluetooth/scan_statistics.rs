@@ -0,0 +1,180 @@
+//! Counters describing how well scanning is going
+//!
+//! When battery levels stop updating, the first question is whether the scanner hears
+//! anything at all, hears AirPods but can't read them, or has stopped succeeding. These
+//! counters answer that for the settings panel and the diagnostic report.
+
+use std::collections::BTreeMap;
+use std::time::SystemTime;
+
+use crate::airpods::model_table::ModelTable;
+use crate::bluetooth::capture_diff::payload_from_hex;
+use crate::bluetooth::cli_scanner::CliScannerResult;
+
+/// Distinct frames kept of each unknown model, for a capture to attach to an issue
+const FRAMES_KEPT_PER_UNKNOWN_MODEL: usize = 20;
+
+/// Frames heard from a model the model table doesn't know
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnknownModelSighting {
+    /// Frames heard
+    pub frames: u64,
+    /// Distinct manufacturer data heard, oldest first
+    pub payloads: Vec<Vec<u8>>,
+}
+
+/// Running totals kept while scanning
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScanStatistics {
+    /// Advertisements received from any device
+    pub advertisements_seen: u64,
+    /// Advertisements read as AirPods battery frames
+    pub airpods_frames_parsed: u64,
+    /// Scanner results or frames that couldn't be read
+    pub parse_failures: u64,
+    /// Repeats of an unchanged advertisement merged instead of forwarded
+    pub duplicates_merged: u64,
+    /// When a scan last completed and was read
+    pub last_success: Option<SystemTime>,
+    /// Models heard that the model table doesn't know, by model ID
+    pub unknown_models: BTreeMap<u16, UnknownModelSighting>,
+    rssi_total: i64,
+    rssi_samples: u64,
+}
+
+impl ScanStatistics {
+    /// Count one advertisement and its signal strength
+    pub fn record_advertisement(&mut self, rssi: Option<i16>) {
+        self.advertisements_seen += 1;
+        if let Some(rssi) = rssi {
+            self.rssi_total += i64::from(rssi);
+            self.rssi_samples += 1;
+        }
+    }
+
+    /// Count an advertisement that was read as AirPods
+    pub fn record_airpods_frame(&mut self) {
+        self.airpods_frames_parsed += 1;
+    }
+
+    /// Count a repeated advertisement that was merged into an earlier copy
+    pub fn record_merged_duplicate(&mut self) {
+        self.duplicates_merged += 1;
+    }
+
+    /// Count output that couldn't be read
+    pub fn record_parse_failure(&mut self) {
+        self.parse_failures += 1;
+    }
+
+    /// Count a frame from a model the model table doesn't know
+    pub fn record_unknown_model(&mut self, model_id: u16, payload: &[u8]) {
+        let sighting = self.unknown_models.entry(model_id).or_default();
+        if sighting.frames == 0 {
+            log::info!(
+                "Heard an AirPods-like frame from unknown model 0x{:04X}",
+                model_id
+            );
+        }
+        sighting.frames += 1;
+        if sighting.payloads.len() < FRAMES_KEPT_PER_UNKNOWN_MODEL
+            && !sighting.payloads.iter().any(|kept| kept == payload)
+        {
+            sighting.payloads.push(payload.to_vec());
+        }
+    }
+
+    /// Count everything in a CLI scanner result and mark the scan as successful
+    pub fn record_cli_result(&mut self, result: &CliScannerResult) {
+        let models = ModelTable::global();
+        for device in &result.devices {
+            let rssi = i16::try_from(device.rssi).ok().filter(|rssi| *rssi != 0);
+            self.record_advertisement(rssi);
+            if device.airpods_data.is_some() {
+                self.record_airpods_frame();
+            }
+            if let Some(payload) = payload_from_hex(&device.manufacturer_data_hex) {
+                if let Some(model_id) = models.unknown_model(&payload) {
+                    self.record_unknown_model(model_id, &payload);
+                }
+            }
+        }
+        self.last_success = Some(SystemTime::now());
+    }
+
+    /// Mean signal strength over every advertisement that reported one
+    pub fn average_rssi(&self) -> Option<f32> {
+        (self.rssi_samples > 0).then(|| self.rssi_total as f32 / self.rssi_samples as f32)
+    }
+
+    /// Lines describing the counters, for the settings panel and diagnostic report
+    pub fn summary_lines(&self) -> Vec<String> {
+        vec![
+            format!("Advertisements seen: {}", self.advertisements_seen),
+            format!("AirPods frames parsed: {}", self.airpods_frames_parsed),
+            format!("Parse failures: {}", self.parse_failures),
+            format!("Duplicates merged: {}", self.duplicates_merged),
+            match self.average_rssi() {
+                Some(rssi) => format!("Average signal: {:.0} dBm", rssi),
+                None => "Average signal: unknown".to_string(),
+            },
+            match self.last_success {
+                Some(time) => format!(
+                    "Last successful scan: {}",
+                    chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M:%S")
+                ),
+                None => "Last successful scan: never".to_string(),
+            },
+            if self.unknown_models.is_empty() {
+                "Unknown models: none".to_string()
+            } else {
+                format!(
+                    "Unknown models: {}",
+                    self.unknown_models
+                        .iter()
+                        .map(|(model_id, sighting)| {
+                            format!("0x{:04X} ({} frames)", model_id, sighting.frames)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_average_rssi_skips_unknown_signal() {
+        let mut statistics = ScanStatistics::default();
+        assert_eq!(statistics.average_rssi(), None);
+
+        statistics.record_advertisement(Some(-60));
+        statistics.record_advertisement(Some(-70));
+        statistics.record_advertisement(None);
+        statistics.record_airpods_frame();
+
+        assert_eq!(statistics.advertisements_seen, 3);
+        assert_eq!(statistics.average_rssi(), Some(-65.0));
+        assert!(statistics.summary_lines()[1].ends_with('1'));
+    }
+
+    #[test]
+    fn test_unknown_models_keep_distinct_frames() {
+        let mut statistics = ScanStatistics::default();
+        statistics.record_unknown_model(0x2099, &[0x07, 0x19]);
+        statistics.record_unknown_model(0x2099, &[0x07, 0x19]);
+        statistics.record_unknown_model(0x2099, &[0x07, 0x1A]);
+
+        let sighting = &statistics.unknown_models[&0x2099];
+        assert_eq!(sighting.frames, 3);
+        assert_eq!(sighting.payloads.len(), 2);
+        assert_eq!(
+            statistics.summary_lines().last().unwrap(),
+            "Unknown models: 0x2099 (3 frames)"
+        );
+    }
+}
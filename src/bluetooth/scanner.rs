@@ -14,13 +14,16 @@ use tokio::task::JoinHandle;
 use tokio::time::{interval, sleep};
 use uuid::Uuid;
 
-use crate::airpods::{create_airpods_filter, detect_airpods, DetectedAirPods};
-use crate::bluetooth::events::{BleEvent, EventBroker, EventFilter};
+use crate::airpods::{create_airpods_filter, detect_airpods, fuse_advertisements, DetectedAirPods};
+use crate::bluetooth::adapter::AdapterInfo;
+use crate::bluetooth::events::{Backpressure, BleEvent, EventBroker, EventFilter, Topic};
+use crate::bluetooth::scan_statistics::ScanStatistics;
 use crate::bluetooth::scanner_config::ScanConfig;
 use crate::config::{AppConfig, Configurable};
 
 // Import new error types
 use crate::error::{BluetoothError, ErrorContext, RecoveryAction};
+use crate::retry::{self, Retry, RetryDomain, RetryPolicy};
 
 /// Trait for providing Bluetooth adapter events and peripheral lookup, enabling dependency injection for testing.
 #[allow(clippy::type_complexity)]
@@ -121,8 +124,23 @@ pub struct BleScannerConfig {
     pub retry_delay: std::time::Duration,
 }
 
+impl BleScannerConfig {
+    /// The Bluetooth retry policy with this scanner's attempts and first delay
+    pub fn retry_policy(&self) -> RetryPolicy {
+        let policy = retry::policy(RetryDomain::Bluetooth);
+        let initial_delay_ms = self.retry_delay.as_millis() as u64;
+        RetryPolicy {
+            max_attempts: u32::from(self.max_retries.max(1)),
+            initial_delay_ms,
+            max_delay_ms: policy.max_delay_ms.max(initial_delay_ms),
+            ..policy
+        }
+    }
+}
+
 impl Default for BleScannerConfig {
     fn default() -> Self {
+        let retry = retry::policy(RetryDomain::Bluetooth);
         Self {
             scan_duration: std::time::Duration::from_secs(10),
             interval_between_scans: std::time::Duration::from_secs(30),
@@ -130,8 +148,8 @@ impl Default for BleScannerConfig {
             update_rssi_only: false,
             update_interval: std::time::Duration::from_secs(5),
             scan_timeout: None,
-            max_retries: 3,
-            retry_delay: std::time::Duration::from_millis(500),
+            max_retries: retry.max_attempts.min(u32::from(u8::MAX)) as u8,
+            retry_delay: std::time::Duration::from_millis(retry.initial_delay_ms),
         }
     }
 }
@@ -280,6 +298,24 @@ impl Default for DiscoveredDevice {
     }
 }
 
+/// How long copies of one advertisement heard by different adapters are merged
+pub const ADAPTER_MERGE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Counters for one adapter while scanning on several at once
+#[derive(Debug, Clone, Default)]
+pub struct AdapterScanStats {
+    /// Adapter the counters belong to
+    pub adapter: String,
+    /// Advertisements received, including copies other adapters also heard
+    pub advertisements: u64,
+    /// Distinct devices heard
+    pub devices: usize,
+    /// Advertisements that couldn't be read
+    pub errors: u64,
+    /// When the last advertisement arrived
+    pub last_advertisement: Option<Instant>,
+}
+
 /// BLE scanner
 ///
 /// Example usage:
@@ -316,6 +352,12 @@ pub struct BleScanner {
     scan_cycles_completed: usize,
     /// Events provider
     events_provider: Arc<dyn AdapterEventsProvider + Send + Sync>,
+    /// Scan tasks when scanning on several adapters
+    adapter_tasks: Vec<JoinHandle<()>>,
+    /// Adapters those tasks scan on, so the scans can be stopped
+    scan_adapters: Vec<Arc<Adapter>>,
+    /// Counters per adapter, in the order the adapters were given
+    adapter_stats: Arc<std::sync::Mutex<Vec<AdapterScanStats>>>,
 }
 
 impl Clone for BleScanner {
@@ -331,6 +373,9 @@ impl Clone for BleScanner {
             event_broker: self.event_broker.clone(),
             scan_cycles_completed: self.scan_cycles_completed,
             events_provider: self.events_provider.clone(),
+            adapter_tasks: Vec::new(),
+            scan_adapters: Vec::new(),
+            adapter_stats: self.adapter_stats.clone(),
         }
     }
 }
@@ -352,6 +397,9 @@ impl BleScanner {
             event_broker: None,
             scan_cycles_completed: 0,
             events_provider,
+            adapter_tasks: Vec::new(),
+            scan_adapters: Vec::new(),
+            adapter_stats: Arc::new(std::sync::Mutex::new(Vec::new())),
         }
     }
 
@@ -376,6 +424,10 @@ impl BleScanner {
 
     /// Update the scanner configuration
     pub fn set_config(&mut self, config: ScanConfig) {
+        if let Some(broker) = self.event_broker.as_mut() {
+            broker.set_device_access(config.device_access.clone());
+            broker.set_merge_window(config.merge_window);
+        }
         self.config = config;
     }
 
@@ -481,19 +533,19 @@ impl BleScanner {
         }
 
         // Try to initialize the adapter with retries
-        let mut attempts = 0;
-        let max_attempts = self.config.max_retries;
+        let mut retry = Retry::with_policy("Initializing the adapter", self.config.retry_policy());
 
-        while attempts < max_attempts {
-            attempts += 1;
+        loop {
             debug!(
-                "{}Initializing adapter (attempt {}/{})",
-                _ctx, attempts, max_attempts
+                "{}Initializing adapter (attempt {})",
+                _ctx,
+                retry.attempts() + 1
             );
 
-            match self.try_initialize().await {
-                Ok(()) => {
-                    if let Some(adapter) = &self.adapter {
+            let error = match self.try_initialize().await {
+                Ok(()) => match &self.adapter {
+                    Some(adapter) => {
+                        retry.succeeded();
                         info!("{}Successfully initialized adapter", _ctx);
                         tracing::trace!(
                             function = "get_or_init_adapter",
@@ -502,34 +554,32 @@ impl BleScanner {
                         info!("BleScanner::get_or_init_adapter returning");
                         return Ok(adapter.clone());
                     }
-                }
-                Err(e) => {
-                    if self.is_error_retryable(&e) && attempts < max_attempts {
-                        warn!("{}Adapter initialization failed with retryable error: {}. Retrying ({}/{})", 
-                            _ctx, e, attempts, max_attempts);
-                        sleep(self.config.retry_delay).await;
-                        continue;
-                    } else {
-                        error!(
-                            "{}Failed to initialize adapter after {} attempts: {}",
-                            _ctx, attempts, e
-                        );
-                        tracing::trace!(
-                            function = "get_or_init_adapter",
-                            "Exiting get_or_init_adapter"
-                        );
-                        info!("BleScanner::get_or_init_adapter returning");
-                        return Err(BluetoothError::AdapterNotAvailable {
-                            reason: format!("Failed to initialize adapter: {}", e),
-                            recovery: RecoveryAction::SelectDifferentAdapter,
-                        });
-                    }
+                    None => BluetoothError::NoAdapter,
+                },
+                Err(e) => e,
+            };
+
+            match retry.failed(&error) {
+                Some(delay) => sleep(delay).await,
+                None => {
+                    error!(
+                        "{}Failed to initialize adapter after {} attempts: {}",
+                        _ctx,
+                        retry.attempts(),
+                        error
+                    );
+                    tracing::trace!(
+                        function = "get_or_init_adapter",
+                        "Exiting get_or_init_adapter"
+                    );
+                    info!("BleScanner::get_or_init_adapter returning");
+                    return Err(BluetoothError::AdapterNotAvailable {
+                        reason: format!("Failed to initialize adapter: {}", error),
+                        recovery: RecoveryAction::SelectDifferentAdapter,
+                    });
                 }
             }
         }
-
-        // This should not be reached due to the returns in the loop
-        Err(BluetoothError::NoAdapter)
     }
 
     /// Start the scan task
@@ -757,6 +807,16 @@ impl BleScanner {
             }
         }
 
+        // Scans on further adapters have no cancel channel; stop them directly
+        for task in self.adapter_tasks.drain(..) {
+            task.abort();
+        }
+        for adapter in self.scan_adapters.drain(..) {
+            if let Err(e) = adapter.stop_scan().await {
+                warn!("{}Failed to stop scan on adapter: {}", _ctx, e);
+            }
+        }
+
         // Reset the state
         self.is_scanning = false;
         self.cancel_sender = None;
@@ -770,6 +830,105 @@ impl BleScanner {
         Ok(())
     }
 
+    /// Scan on every given adapter at once, e.g. an internal radio and a USB dongle
+    ///
+    /// Each adapter catches advertisements the others miss. Events from all of them go
+    /// through one broker, which merges the copies of an advertisement that more than
+    /// one adapter heard, and the device list keeps the strongest signal seen.
+    pub async fn start_scanning_on_adapters(
+        &mut self,
+        adapters: Vec<(AdapterInfo, Adapter)>,
+    ) -> Result<Receiver<BleEvent>, BluetoothError> {
+        let _ctx = ErrorContext::new("BleScanner", "start_scanning_on_adapters");
+
+        if self.is_scanning {
+            return Err(BluetoothError::ScanFailed(
+                "Scan already in progress".to_string(),
+            ));
+        }
+        if adapters.is_empty() {
+            return Err(BluetoothError::NoAdapter);
+        }
+
+        // Copies from other adapters arrive a little apart, so keep the window wide
+        // enough to catch them even if the configured one is shorter or off
+        let merge_window = if adapters.len() > 1 {
+            Some(
+                self.config
+                    .merge_window
+                    .map_or(ADAPTER_MERGE_WINDOW, |window| {
+                        window.max(ADAPTER_MERGE_WINDOW)
+                    }),
+            )
+        } else {
+            self.config.merge_window
+        };
+        let broker = self.event_broker();
+        broker.set_merge_window(merge_window);
+        broker.start();
+        let (_, rx) = broker.subscribe(EventFilter::All);
+        let event_tx = broker.get_sender();
+
+        *self.adapter_stats.lock().unwrap() = adapters
+            .iter()
+            .map(|(info, _)| AdapterScanStats {
+                adapter: info.to_string(),
+                ..AdapterScanStats::default()
+            })
+            .collect();
+
+        for (index, (info, adapter)) in adapters.into_iter().enumerate() {
+            let adapter = Arc::new(adapter);
+            let started = async {
+                let events = adapter.events().await?;
+                adapter.start_scan(ScanFilter::default()).await?;
+                Ok::<_, btleplug::Error>(events)
+            }
+            .await;
+            let events = match started {
+                Ok(events) => events,
+                Err(e) => {
+                    // One radio failing shouldn't stop the others
+                    warn!("{}Can't scan on {}: {}", _ctx, info, e);
+                    if let Some(stats) = self.adapter_stats.lock().unwrap().get_mut(index) {
+                        stats.errors += 1;
+                    }
+                    continue;
+                }
+            };
+            info!("{}Scanning on {}", _ctx, info);
+
+            self.adapter_tasks.push(tokio::spawn(scan_adapter_events(
+                index,
+                adapter.clone(),
+                events,
+                event_tx.clone(),
+                self.devices.clone(),
+                self.adapter_stats.clone(),
+            )));
+            self.scan_adapters.push(adapter);
+        }
+
+        if self.adapter_tasks.is_empty() {
+            return Err(BluetoothError::ScanFailed(
+                "No adapter could start scanning".to_string(),
+            ));
+        }
+        self.is_scanning = true;
+        let _ = event_tx.send(BleEvent::ScanStarted).await;
+        Ok(rx)
+    }
+
+    /// Totals for everything the scanner has heard
+    pub fn scan_statistics(&mut self) -> ScanStatistics {
+        self.event_broker().statistics()
+    }
+
+    /// Counters for each adapter in the current multi-adapter scan
+    pub fn adapter_stats(&self) -> Vec<AdapterScanStats> {
+        self.adapter_stats.lock().unwrap().clone()
+    }
+
     /// Get a list of currently known devices
     pub async fn get_devices(&self) -> Vec<DiscoveredDevice> {
         let devices = self.devices.lock().await;
@@ -787,12 +946,16 @@ impl BleScanner {
     }
 
     /// Get fully detected AirPods devices with battery information
+    ///
+    /// Advertisements from both buds of a pair (under rotating addresses) are fused
+    /// into a single device.
     pub async fn get_detected_airpods(&self) -> Vec<DetectedAirPods> {
         let devices = self.devices.lock().await;
-        devices
-            .values()
-            .filter_map(|d| detect_airpods(d).ok().flatten())
-            .collect()
+        fuse_advertisements(
+            devices
+                .values()
+                .filter_map(|d| detect_airpods(d).ok().flatten()),
+        )
     }
 
     /// Check if scanning is currently in progress
@@ -818,17 +981,26 @@ impl BleScanner {
     }
 
     /// Process a discovered device (static version)
-    #[tracing::instrument(name = "process_discovered_device", skip(devices, event_tx, _config), fields(address = %device.address))]
+    #[tracing::instrument(name = "process_discovered_device", skip(devices, event_tx, config), fields(address = %device.address))]
     async fn process_discovered_device(
         device: &DiscoveredDevice,
         devices: &Arc<tokio::sync::Mutex<HashMap<BDAddr, DiscoveredDevice>>>,
         event_tx: &Sender<BleEvent>,
-        _config: &ScanConfig,
+        config: &ScanConfig,
     ) -> Result<(), BluetoothError> {
         tracing::trace!(function = "process_discovered_device", address = %device.address, "Entering process_discovered_device");
         let _ctx = ErrorContext::new("BleScanner", "process_discovered_device")
             .with_metadata("address", device.address.to_string());
 
+        // Devices excluded by the allow/deny lists are never tracked
+        if !config
+            .device_access
+            .allows(&device.address.to_string(), device.name.as_deref())
+        {
+            debug!("{}Ignoring filtered device {}", _ctx, device.address);
+            return Ok(());
+        }
+
         // Lock the devices map
         let mut devices_map = devices.lock().await;
 
@@ -899,11 +1071,12 @@ impl BleScanner {
         filter: &crate::airpods::AirPodsFilter,
     ) -> Vec<DetectedAirPods> {
         let devices = self.get_devices().await;
-        devices
-            .into_iter()
-            .filter(|d| filter(d))
-            .filter_map(|d| detect_airpods(&d).ok().flatten())
-            .collect()
+        fuse_advertisements(
+            devices
+                .into_iter()
+                .filter(|d| filter(d))
+                .filter_map(|d| detect_airpods(&d).ok().flatten()),
+        )
     }
 
     /// Check if there are any AirPods matching the filter
@@ -924,7 +1097,10 @@ impl BleScanner {
     /// Get or create an event broker
     fn event_broker(&mut self) -> &mut EventBroker {
         if self.event_broker.is_none() {
-            self.event_broker = Some(EventBroker::new());
+            let mut broker = EventBroker::new();
+            broker.set_device_access(self.config.device_access.clone());
+            broker.set_merge_window(self.config.merge_window);
+            self.event_broker = Some(broker);
         }
         self.event_broker.as_mut().unwrap()
     }
@@ -947,6 +1123,14 @@ impl BleScanner {
         rx
     }
 
+    /// Subscribe to a typed topic, dropping items the receiver is too slow to take
+    pub fn subscribe_topic<T: Topic>(&mut self, topic: T) -> Receiver<T::Item> {
+        let (_, rx) = self
+            .event_broker()
+            .subscribe_topic(topic, Backpressure::DropNewest);
+        rx
+    }
+
     /// Get peripherals by Bluetooth address
     pub async fn get_peripherals_by_address(
         &self,
@@ -1044,37 +1228,22 @@ impl BleScanner {
     pub async fn initialize(&mut self) -> Result<(), BluetoothError> {
         tracing::trace!(function = "initialize", "Entering initialize");
         let _ctx = ErrorContext::new("BleScanner", "initialize");
-        let max_retries = 3; // Default retry count
-        let retry_delay = Duration::from_millis(500); // Default delay between retries
+        let mut retry = Retry::new("Initializing Bluetooth", RetryDomain::Bluetooth);
 
         // Try to initialize with retries for transient failures
-        for attempt in 0..=max_retries {
+        loop {
             match self.try_initialize().await {
-                Ok(_) => {
-                    if attempt > 0 {
-                        debug!(
-                            "Successfully initialized Bluetooth adapter after {} retries",
-                            attempt
-                        );
-                    }
+                Ok(()) => {
+                    retry.succeeded();
                     tracing::trace!(function = "initialize", "Exiting initialize");
                     return Ok(());
                 }
-                Err(e) if attempt < max_retries && self.is_error_retryable(&e) => {
-                    warn!(
-                        "Bluetooth initialization attempt {} failed: {}. Retrying...",
-                        attempt + 1,
-                        e
-                    );
-                    sleep(retry_delay).await;
-                    continue;
-                }
-                Err(e) => return Err(e),
+                Err(e) => match retry.failed(&e) {
+                    Some(delay) => sleep(delay).await,
+                    None => return Err(e),
+                },
             }
         }
-
-        // Should never reach here due to the loop structure, but the compiler needs this
-        Err(BluetoothError::NoAdapter)
     }
 
     // Helper method that actually attempts the initialization
@@ -1120,25 +1289,6 @@ impl BleScanner {
         }
     }
 
-    // Helper method to check if an error is retryable
-    fn is_error_retryable(&self, error: &BluetoothError) -> bool {
-        match error {
-            BluetoothError::ConnectionFailed(_) => true,
-            BluetoothError::ScanFailed(_) => true,
-            BluetoothError::DeviceDisconnected(_) => true,
-            BluetoothError::NoAdapter => false, // Adapter missing is not retryable without user action
-            BluetoothError::ApiError(_) => true, // API errors might be transient
-            BluetoothError::InvalidData(_) => false, // Data validation errors aren't retryable
-            BluetoothError::DeviceNotFound(_) => false, // Missing device not retryable
-            BluetoothError::PermissionDenied(_) => false, // Permission issues need user intervention
-            BluetoothError::Timeout(_) => true,
-            BluetoothError::Other(_) => true, // Generic errors, attempt to retry
-            BluetoothError::AdapterRefreshFailed { .. } => true,
-            BluetoothError::AdapterNotAvailable { .. } => false, // Adapter unavailable needs user action
-            BluetoothError::AdapterScanFailed { .. } => true,
-        }
-    }
-
     /// Create a dummy scanner that does nothing (for non-BLE builds)
     pub fn dummy() -> Self {
         struct DummyProvider;
@@ -1200,6 +1350,8 @@ impl Configurable for BleScanner {
         scan_config = scan_config.with_scan_duration(config.bluetooth.scan_duration);
         scan_config = scan_config.with_interval(config.bluetooth.scan_interval);
         scan_config = scan_config.with_min_rssi(config.bluetooth.min_rssi);
+        scan_config = scan_config.with_device_access(config.bluetooth.device_access());
+        scan_config = scan_config.with_merge_window(config.bluetooth.event_merge_window());
 
         // Set a reasonable default for max cycles
         scan_config = scan_config.with_max_cycles(Some(5));
@@ -1214,6 +1366,77 @@ impl Drop for BleScanner {
         if let Some(task) = self.scan_task.take() {
             task.abort();
         }
+        for task in self.adapter_tasks.drain(..) {
+            task.abort();
+        }
+    }
+}
+
+/// Forward one adapter's advertisements to the broker and keep its counters
+async fn scan_adapter_events(
+    index: usize,
+    adapter: Arc<Adapter>,
+    mut events: Pin<Box<dyn Stream<Item = CentralEvent> + Send>>,
+    event_tx: Sender<BleEvent>,
+    devices: Arc<tokio::sync::Mutex<HashMap<BDAddr, DiscoveredDevice>>>,
+    stats: Arc<std::sync::Mutex<Vec<AdapterScanStats>>>,
+) {
+    let mut heard = std::collections::HashSet::new();
+
+    while let Some(event) = events.next().await {
+        let id = match event {
+            CentralEvent::DeviceDiscovered(id) | CentralEvent::DeviceUpdated(id) => id,
+            _ => continue,
+        };
+        let device = match adapter.peripheral(&id).await {
+            Ok(peripheral) => DiscoveredDevice::from_peripheral(&peripheral).await,
+            Err(e) => Err(BluetoothError::from(e)),
+        };
+
+        let device = match device {
+            Ok(device) => device,
+            Err(e) => {
+                debug!("Adapter {} advertisement unreadable: {}", index, e);
+                if let Some(stats) = stats.lock().unwrap().get_mut(index) {
+                    stats.errors += 1;
+                }
+                continue;
+            }
+        };
+
+        heard.insert(device.address);
+        if let Some(stats) = stats.lock().unwrap().get_mut(index) {
+            stats.advertisements += 1;
+            stats.devices = heard.len();
+            stats.last_advertisement = Some(Instant::now());
+        }
+
+        let is_new = {
+            let mut devices = devices.lock().await;
+            let is_new = !devices.contains_key(&device.address);
+            // Keep the strongest signal any adapter reported recently
+            let stronger = devices.get(&device.address).map_or(true, |known| {
+                device.rssi >= known.rssi || known.last_seen.elapsed() > ADAPTER_MERGE_WINDOW
+            });
+            if stronger {
+                devices.insert(device.address, device.clone());
+            }
+            is_new
+        };
+
+        if device.is_potential_airpods {
+            if let Ok(Some(airpods)) = detect_airpods(&device) {
+                let _ = event_tx.send(BleEvent::AirPodsDetected(airpods)).await;
+            }
+        }
+        let event = if is_new {
+            BleEvent::DeviceDiscovered(device)
+        } else {
+            BleEvent::DeviceUpdated(device)
+        };
+        if event_tx.send(event).await.is_err() {
+            break;
+        }
     }
 }
 
@@ -1,5 +1,7 @@
 use std::time::Duration;
 
+use crate::config::DeviceAccessList;
+
 /// Configuration for the Bluetooth scanner
 #[derive(Debug, Clone)]
 pub struct ScanConfig {
@@ -31,6 +33,11 @@ pub struct ScanConfig {
     pub max_retries: u8,
     /// Delay between retries
     pub retry_delay: Duration,
+    /// Allow/deny lists; devices they exclude are ignored
+    pub device_access: DeviceAccessList,
+    /// Window in which repeats of an unchanged advertisement are merged
+    /// None forwards every repeat
+    pub merge_window: Option<Duration>,
 }
 
 impl Default for ScanConfig {
@@ -49,6 +56,8 @@ impl Default for ScanConfig {
             scan_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            device_access: DeviceAccessList::default(),
+            merge_window: None,
         }
     }
 }
@@ -75,6 +84,8 @@ impl ScanConfig {
             scan_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            device_access: DeviceAccessList::default(),
+            merge_window: None,
         }
     }
 
@@ -94,6 +105,8 @@ impl ScanConfig {
             scan_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            device_access: DeviceAccessList::default(),
+            merge_window: None,
         }
     }
 
@@ -113,6 +126,8 @@ impl ScanConfig {
             scan_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            device_access: DeviceAccessList::default(),
+            merge_window: None,
         }
     }
 
@@ -132,6 +147,8 @@ impl ScanConfig {
             scan_timeout: None,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            device_access: DeviceAccessList::default(),
+            merge_window: None,
         }
     }
 
@@ -207,11 +224,23 @@ impl ScanConfig {
         self
     }
 
+    /// Set the allow/deny lists devices are checked against
+    pub fn with_device_access(mut self, device_access: DeviceAccessList) -> Self {
+        self.device_access = device_access;
+        self
+    }
+
     /// Set the delay between retries
     pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
         self.retry_delay = retry_delay;
         self
     }
+
+    /// Set the window in which unchanged advertisements are merged
+    pub fn with_merge_window(mut self, merge_window: Option<Duration>) -> Self {
+        self.merge_window = merge_window;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,252 @@
+//! Supervision of the external CLI scanner
+//!
+//! The scanner is a separate process, and if it hangs (a wedged Bluetooth stack is
+//! enough) a plain `output()` call never returns and scans stop without a word. Every
+//! run here gets a deadline after which the child is killed, and failed runs push the
+//! next attempt back under the scanner retry policy so a broken scanner isn't restarted
+//! every tick. After a few failures in a row the scanner is reported as unhealthy.
+
+use std::io::{self, Read};
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::cli_scanner::CliScannerResult;
+use super::scan_statistics::ScanStatistics;
+use crate::retry::{self, RetryDomain, RetryPolicy};
+
+/// Longest a single scanner run may take before it is killed
+pub const SCANNER_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Failures in a row after which the scanner counts as unhealthy
+const UNHEALTHY_AFTER: u32 = 3;
+
+/// How often a running scanner is checked for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Run `command` to completion, killing it if it takes longer than `timeout`
+///
+/// A timeout is returned as an error of kind [`io::ErrorKind::TimedOut`].
+pub fn output_with_timeout(command: &mut Command, timeout: Duration) -> io::Result<Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain the pipes while waiting so a chatty scanner can't block on a full pipe
+    let stdout = child.stdout.take().map(read_in_background);
+    let stderr = child.stderr.take().map(read_in_background);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("scanner did not finish within {}s", timeout.as_secs()),
+            ));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let collect = |reader: Option<std::thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut data = Vec::new();
+        let _ = pipe.read_to_end(&mut data);
+        data
+    })
+}
+
+/// Health of the scanner as seen by its recent runs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScannerHealth {
+    /// Failed runs since the last success
+    pub consecutive_failures: u32,
+    /// Why the most recent run failed
+    pub last_error: Option<String>,
+    /// Earliest time the next run should start
+    pub retry_at: Option<Instant>,
+}
+
+impl ScannerHealth {
+    /// Whether enough runs have failed in a row to warn the user
+    pub fn is_unhealthy(&self) -> bool {
+        self.consecutive_failures >= UNHEALTHY_AFTER
+    }
+
+    /// Time left before the next run is allowed
+    pub fn retry_in(&self, now: Instant) -> Option<Duration> {
+        self.retry_at
+            .map(|retry_at| retry_at.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+}
+
+/// Shared record of scanner runs; clones refer to the same record
+#[derive(Debug, Clone, Default)]
+pub struct ScannerSupervisor {
+    health: Arc<Mutex<ScannerHealth>>,
+    statistics: Arc<Mutex<ScanStatistics>>,
+    /// Backoff after failed runs; `None` follows the configured scanner policy
+    policy: Option<RetryPolicy>,
+}
+
+impl ScannerSupervisor {
+    /// Supervisor with no runs recorded, backing off under the configured scanner policy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Supervisor with no runs recorded, backing off under `policy`
+    pub fn with_policy(policy: RetryPolicy) -> Self {
+        Self {
+            policy: Some(policy),
+            ..Self::default()
+        }
+    }
+
+    /// Policy the next backoff is taken from
+    fn policy(&self) -> RetryPolicy {
+        self.policy
+            .unwrap_or_else(|| retry::policy(RetryDomain::Scanner))
+    }
+
+    /// Current health
+    pub fn health(&self) -> ScannerHealth {
+        self.health.lock().map(|h| h.clone()).unwrap_or_default()
+    }
+
+    /// Totals over every run so far
+    pub fn statistics(&self) -> ScanStatistics {
+        self.statistics
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+
+    /// Count what a run's output contained
+    pub fn record_result(&self, result: &CliScannerResult) {
+        if let Ok(mut statistics) = self.statistics.lock() {
+            statistics.record_cli_result(result);
+        }
+    }
+
+    /// Count a run whose output couldn't be read
+    pub fn record_parse_failure(&self) {
+        if let Ok(mut statistics) = self.statistics.lock() {
+            statistics.record_parse_failure();
+        }
+    }
+
+    /// Whether a run may start now, or the scanner is still backing off
+    pub fn ready(&self, now: Instant) -> bool {
+        self.health().retry_in(now).is_none()
+    }
+
+    /// Run the scanner under the timeout and record the outcome
+    ///
+    /// A run that exits with an error status counts as a failure as well.
+    pub fn run(&self, command: &mut Command, timeout: Duration) -> io::Result<Output> {
+        let result = output_with_timeout(command, timeout);
+        match &result {
+            Ok(output) if output.status.success() => self.record_success(),
+            Ok(output) => self.record_failure(format!(
+                "scanner exited with code {:?}",
+                output.status.code()
+            )),
+            Err(e) => self.record_failure(e.to_string()),
+        }
+        result
+    }
+
+    /// Forget earlier failures after a good run
+    pub fn record_success(&self) {
+        if let Ok(mut health) = self.health.lock() {
+            if health.consecutive_failures > 0 {
+                log::info!(
+                    "Scanner recovered after {} failed runs",
+                    health.consecutive_failures
+                );
+            }
+            *health = ScannerHealth::default();
+        }
+    }
+
+    /// Count a failed run and push the next one back
+    pub fn record_failure(&self, error: String) {
+        if let Ok(mut health) = self.health.lock() {
+            health.consecutive_failures += 1;
+            let backoff = self.policy().jittered_delay(health.consecutive_failures);
+            log::warn!(
+                "Scanner run failed ({} in a row): {}; next attempt in {}s",
+                health.consecutive_failures,
+                error,
+                backoff.as_secs()
+            );
+            retry::report("Running the scanner", false);
+            health.last_error = Some(error);
+            health.retry_at = Some(Instant::now() + backoff);
+        }
+    }
+
+    /// Allow the next run straight away, e.g. when the user asks to retry
+    pub fn retry_now(&self) {
+        if let Ok(mut health) = self.health.lock() {
+            health.retry_at = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_and_recovery() {
+        let policy = RetryPolicy::default_for(RetryDomain::Scanner);
+        assert_eq!(policy.delay(1), Duration::from_secs(5));
+        assert_eq!(policy.delay(3), Duration::from_secs(20));
+        assert_eq!(policy.delay(30), Duration::from_secs(300));
+
+        let supervisor = ScannerSupervisor::with_policy(policy);
+        assert!(supervisor.ready(Instant::now()));
+        for _ in 0..UNHEALTHY_AFTER {
+            supervisor.record_failure("timed out".to_string());
+        }
+        let health = supervisor.health();
+        assert!(health.is_unhealthy());
+        assert!(!supervisor.ready(Instant::now()));
+        assert!(supervisor.ready(Instant::now() + Duration::from_secs(300)));
+
+        supervisor.record_success();
+        assert_eq!(supervisor.health(), ScannerHealth::default());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hung_scanner_is_killed() {
+        let mut command = Command::new("sleep");
+        command.arg("30");
+        let started = Instant::now();
+        let error = output_with_timeout(&mut command, Duration::from_millis(200)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
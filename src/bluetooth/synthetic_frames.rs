@@ -0,0 +1,245 @@
+//! Synthetic advertisement frames for exercising rare states
+//!
+//! Some states are hard to produce on demand: a bud at 0%, everything charging at
+//! once, a clone reporting a model nobody has heard of. In developer mode a payload can
+//! be typed in or picked from a preset; it is decoded like real manufacturer data and
+//! added to every scan result until cleared, so it passes through the same filtering,
+//! merging and estimation as frames from the scanner.
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::airpods::battery::{model_name, AirPodsBatteryInfo};
+use crate::airpods::decode_proximity_message;
+use crate::config::canonical_address;
+use crate::error::BluetoothError;
+
+/// Address used for synthetic frames unless another is given (locally administered,
+/// so it can't belong to real hardware)
+pub const DEFAULT_SYNTHETIC_ADDRESS: &str = "02:00:00:00:00:01";
+
+/// Ready-made payloads for states that are rare in practice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyntheticPreset {
+    /// Both buds and the case at 0%
+    Empty,
+    /// Both buds charging (the most a single frame can report), case lid open
+    AllCharging,
+    /// Buds in ears, case level unknown
+    InEars,
+    /// Unknown model ID and out-of-range levels, as sent by some clones
+    Clone,
+}
+
+impl SyntheticPreset {
+    /// Every preset, for pick lists
+    pub const ALL: [SyntheticPreset; 4] = [
+        SyntheticPreset::Empty,
+        SyntheticPreset::AllCharging,
+        SyntheticPreset::InEars,
+        SyntheticPreset::Clone,
+    ];
+
+    /// Apple manufacturer data for the preset, as hex
+    pub fn payload(self) -> &'static str {
+        match self {
+            SyntheticPreset::Empty => "07 19 01 14 20 00 00 00 00 00 00 04 00 00 00 00 00 01 00",
+            SyntheticPreset::AllCharging => {
+                "07 19 01 14 20 00 00 00 00 00 00 04 05 07 05 08 01 01 00"
+            }
+            SyntheticPreset::InEars => "07 19 01 14 20 00 00 00 00 00 00 0A 08 09 00 FF 08 01 02",
+            SyntheticPreset::Clone => "07 19 01 FF FF 00 00 00 00 00 00 24 0B 0C 00 0F 00 00 00",
+        }
+    }
+}
+
+impl fmt::Display for SyntheticPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SyntheticPreset::Empty => "Everything at 0%",
+            SyntheticPreset::AllCharging => "All charging",
+            SyntheticPreset::InEars => "In ears",
+            SyntheticPreset::Clone => "Clone",
+        })
+    }
+}
+
+/// Decode `payload` (hex, separators optional) as an advertisement from `address`
+pub fn frame_from_payload(
+    address: &str,
+    payload: &str,
+) -> Result<AirPodsBatteryInfo, BluetoothError> {
+    let canonical = canonical_address(address)
+        .ok_or_else(|| BluetoothError::InvalidData(format!("'{}' is not an address", address)))?;
+    let data = parse_hex(payload)?;
+    let message =
+        decode_proximity_message(&data).map_err(|e| BluetoothError::InvalidData(e.to_string()))?;
+
+    // Checked by the decoder, which needs at least 16 bytes
+    let model_id = u16::from_le_bytes([data[3], data[4]]);
+    let level = |level: Option<u8>| level.map_or(-1, i32::from);
+    let charging = message.battery.charging;
+    Ok(AirPodsBatteryInfo {
+        address: u64::from_str_radix(&canonical, 16).unwrap_or(0),
+        name: format!("{} (synthetic)", model_name(model_id)),
+        canonical_address: canonical,
+        model_id,
+        left_battery: level(message.battery.left),
+        left_charging: charging.is_some_and(|c| c.is_left_charging()),
+        right_battery: level(message.battery.right),
+        right_charging: charging.is_some_and(|c| c.is_right_charging()),
+        case_battery: level(message.battery.case),
+        case_charging: charging.is_some_and(|c| c.is_case_charging()),
+        left_in_ear: Some(message.extras.left_in_ear()),
+        right_in_ear: Some(message.extras.right_in_ear()),
+        case_lid_open: message.extras.lid_open,
+        side: None,
+        both_in_case: Some(message.extras.both_in_case),
+        color: None,
+        switch_count: None,
+        rssi: Some(-40),
+        timestamp: None,
+        raw_manufacturer_data: Some(
+            data.iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>(),
+        ),
+    })
+}
+
+/// Bytes from hex, ignoring spaces, colons and dashes between them
+fn parse_hex(text: &str) -> Result<Vec<u8>, BluetoothError> {
+    let digits: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':' && *c != '-')
+        .collect();
+    let digits = digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+        .unwrap_or(&digits);
+    if digits.len() % 2 != 0 {
+        return Err(BluetoothError::InvalidData(
+            "Payload has an odd number of hex digits".to_string(),
+        ));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| {
+                BluetoothError::InvalidData(format!("'{}' is not a hex byte", &digits[i..i + 2]))
+            })
+        })
+        .collect()
+}
+
+/// Frames injected into scan results; clones share the same frames
+#[derive(Debug, Clone, Default)]
+pub struct SyntheticFrames {
+    frames: Arc<Mutex<Vec<AirPodsBatteryInfo>>>,
+}
+
+impl SyntheticFrames {
+    /// No frames injected
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a frame, replacing any earlier one for the same address
+    pub fn inject(&self, frame: AirPodsBatteryInfo) {
+        log::info!(
+            "Injecting synthetic frame for {}: {:?}",
+            frame.canonical_address,
+            frame.raw_manufacturer_data
+        );
+        let mut frames = self.frames.lock().unwrap();
+        frames.retain(|existing| existing.canonical_address != frame.canonical_address);
+        frames.push(frame);
+    }
+
+    /// Remove every injected frame
+    pub fn clear(&self) {
+        self.frames.lock().unwrap().clear();
+    }
+
+    /// Number of injected frames
+    pub fn len(&self) -> usize {
+        self.frames.lock().unwrap().len()
+    }
+
+    /// Whether no frames are injected
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add the injected frames to a scan result, in place of real frames from the
+    /// same addresses
+    pub fn apply(&self, scan: &mut Vec<AirPodsBatteryInfo>) {
+        let frames = self.frames.lock().unwrap();
+        if frames.is_empty() {
+            return;
+        }
+        scan.retain(|airpods| {
+            !frames
+                .iter()
+                .any(|frame| frame.canonical_address == airpods.canonical_address)
+        });
+        scan.extend(frames.iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_decode() {
+        let empty = frame_from_payload(DEFAULT_SYNTHETIC_ADDRESS, SyntheticPreset::Empty.payload())
+            .unwrap();
+        assert_eq!(
+            (empty.left_battery, empty.right_battery, empty.case_battery),
+            (0, 0, 0)
+        );
+        assert_eq!(empty.model_id, 0x2014);
+        assert_eq!(empty.canonical_address, "020000000001");
+
+        let charging = frame_from_payload(
+            DEFAULT_SYNTHETIC_ADDRESS,
+            SyntheticPreset::AllCharging.payload(),
+        )
+        .unwrap();
+        assert!(charging.left_charging && charging.right_charging);
+        assert_eq!(charging.case_lid_open, Some(true));
+
+        let clone = frame_from_payload(DEFAULT_SYNTHETIC_ADDRESS, SyntheticPreset::Clone.payload())
+            .unwrap();
+        assert_eq!(clone.name, "Unknown (synthetic)");
+        assert_eq!(clone.left_battery, 100);
+
+        for preset in SyntheticPreset::ALL {
+            assert!(frame_from_payload(DEFAULT_SYNTHETIC_ADDRESS, preset.payload()).is_ok());
+        }
+        assert!(frame_from_payload(DEFAULT_SYNTHETIC_ADDRESS, "07 19 0").is_err());
+        assert!(frame_from_payload("nowhere", SyntheticPreset::Empty.payload()).is_err());
+    }
+
+    #[test]
+    fn test_injected_frames_replace_real_ones() {
+        let synthetic = SyntheticFrames::new();
+        let frame =
+            frame_from_payload("11:22:33:44:55:66", SyntheticPreset::Empty.payload()).unwrap();
+        let mut real = frame.clone();
+        real.left_battery = 80;
+        let mut scan = vec![real];
+
+        synthetic.apply(&mut scan);
+        assert_eq!(scan[0].left_battery, 80);
+
+        synthetic.inject(frame);
+        synthetic.apply(&mut scan);
+        assert_eq!(scan.len(), 1);
+        assert_eq!(scan[0].left_battery, 0);
+
+        synthetic.clear();
+        assert!(synthetic.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Classic Bluetooth connection state from the Win32 Bluetooth API
+//!
+//! An advertisement only says that the AirPods are nearby; whether they are connected
+//! to this PC for audio is known to the classic (BR/EDR) stack. The WinRT device
+//! queries meant for this don't work from RustPods' runtime, so the paired devices are
+//! enumerated with `BluetoothFindFirstDevice`, which reports each one's connection
+//! state without an inquiry.
+//!
+//! AirPods advertise from a random address that differs from their classic one, so a
+//! scanned device is matched to a paired device by address first and by name second.
+
+use crate::config::canonical_address;
+use crate::error::BluetoothError;
+use crate::system::audio::endpoint_matches;
+
+/// A device paired with the PC's classic Bluetooth stack
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassicDevice {
+    /// Address as twelve lowercase hex digits
+    pub address: String,
+    /// Name the device reported when it was paired
+    pub name: String,
+    /// Whether the device is connected right now
+    pub connected: bool,
+    /// Whether the device is remembered (paired) by the system
+    pub remembered: bool,
+}
+
+/// Whether the scanned device at `address` named `name` is connected to the PC
+///
+/// A paired device with the same address decides; otherwise any connected device whose
+/// name matches the scanned one counts.
+pub fn is_classically_connected(devices: &[ClassicDevice], address: &str, name: &str) -> bool {
+    if let Some(address) = canonical_address(address) {
+        if let Some(device) = devices.iter().find(|device| device.address == address) {
+            return device.connected;
+        }
+    }
+    devices
+        .iter()
+        .any(|device| device.connected && endpoint_matches(&device.name, name))
+}
+
+/// Address as the Win32 API stores it, least significant byte first, in canonical form
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn address_from_le_bytes(bytes: [u8; 6]) -> String {
+    bytes.iter().rev().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{address_from_le_bytes, ClassicDevice};
+    use crate::error::BluetoothError;
+    use windows::Win32::Devices::Bluetooth::{
+        BluetoothFindDeviceClose, BluetoothFindFirstDevice, BluetoothFindNextDevice,
+        BLUETOOTH_DEVICE_INFO, BLUETOOTH_DEVICE_SEARCH_PARAMS,
+    };
+    use windows::Win32::Foundation::{ERROR_NO_MORE_ITEMS, TRUE};
+
+    fn describe(info: &BLUETOOTH_DEVICE_INFO) -> ClassicDevice {
+        let name_len = info
+            .szName
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(info.szName.len());
+        ClassicDevice {
+            address: address_from_le_bytes(unsafe { info.Address.Anonymous.rgBytes }),
+            name: String::from_utf16_lossy(&info.szName[..name_len]),
+            connected: info.fConnected.as_bool(),
+            remembered: info.fRemembered.as_bool(),
+        }
+    }
+
+    pub fn paired_devices() -> Result<Vec<ClassicDevice>, BluetoothError> {
+        let params = BLUETOOTH_DEVICE_SEARCH_PARAMS {
+            dwSize: std::mem::size_of::<BLUETOOTH_DEVICE_SEARCH_PARAMS>() as u32,
+            fReturnAuthenticated: TRUE,
+            fReturnRemembered: TRUE,
+            fReturnConnected: TRUE,
+            // Only what the system already knows; an inquiry takes seconds
+            fIssueInquiry: false.into(),
+            ..Default::default()
+        };
+        let mut info = BLUETOOTH_DEVICE_INFO {
+            dwSize: std::mem::size_of::<BLUETOOTH_DEVICE_INFO>() as u32,
+            ..Default::default()
+        };
+
+        let find = match unsafe { BluetoothFindFirstDevice(&params, &mut info) } {
+            Ok(find) => find,
+            Err(e) if e.code() == ERROR_NO_MORE_ITEMS.to_hresult() => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(BluetoothError::ApiError(format!(
+                    "BluetoothFindFirstDevice failed: {}",
+                    e
+                )))
+            }
+        };
+
+        let mut devices = vec![describe(&info)];
+        loop {
+            info = BLUETOOTH_DEVICE_INFO {
+                dwSize: std::mem::size_of::<BLUETOOTH_DEVICE_INFO>() as u32,
+                ..Default::default()
+            };
+            if unsafe { BluetoothFindNextDevice(find, &mut info) }.is_err() {
+                break;
+            }
+            devices.push(describe(&info));
+        }
+        if let Err(e) = unsafe { BluetoothFindDeviceClose(find) } {
+            log::debug!("BluetoothFindDeviceClose failed: {}", e);
+        }
+        Ok(devices)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::ClassicDevice;
+    use crate::error::BluetoothError;
+
+    pub fn paired_devices() -> Result<Vec<ClassicDevice>, BluetoothError> {
+        Err(BluetoothError::ApiError(
+            "Classic Bluetooth enumeration is only implemented on Windows".to_string(),
+        ))
+    }
+}
+
+/// Devices paired with the PC, with their connection state
+///
+/// Blocks while the Bluetooth stack is queried, so call it off the UI thread.
+pub fn paired_devices() -> Result<Vec<ClassicDevice>, BluetoothError> {
+    platform::paired_devices()
+}
+
+/// Paired devices that are connected right now
+pub fn connected_devices() -> Result<Vec<ClassicDevice>, BluetoothError> {
+    Ok(paired_devices()?
+        .into_iter()
+        .filter(|device| device.connected)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic_connection_matching() {
+        assert_eq!(
+            address_from_le_bytes([0xff, 0xee, 0xdd, 0xcc, 0xbb, 0xaa]),
+            "aabbccddeeff"
+        );
+
+        let devices = vec![
+            ClassicDevice {
+                address: "aabbccddeeff".to_string(),
+                name: "Jay's AirPods Pro".to_string(),
+                connected: true,
+                remembered: true,
+            },
+            ClassicDevice {
+                address: "112233445566".to_string(),
+                name: "Keyboard".to_string(),
+                connected: false,
+                remembered: true,
+            },
+        ];
+        assert!(is_classically_connected(
+            &devices,
+            "AA:BB:CC:DD:EE:FF",
+            "Headphones"
+        ));
+        assert!(!is_classically_connected(
+            &devices,
+            "11:22:33:44:55:66",
+            "AirPods Pro"
+        ));
+        // The random advertising address is matched by name
+        assert!(is_classically_connected(
+            &devices,
+            "01:02:03:04:05:06",
+            "AirPods Pro 2"
+        ));
+        assert!(!is_classically_connected(
+            &devices[1..],
+            "01:02:03:04:05:06",
+            "AirPods Pro 2"
+        ));
+    }
+}
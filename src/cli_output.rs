@@ -0,0 +1,230 @@
+//! Terminal output of the command line commands
+//!
+//! Commands print headings, labels and results through these helpers so they look the
+//! same everywhere, and show a spinner or a countdown while something slow runs. Colors
+//! and animation are only used when stdout is a terminal; piped output, `NO_COLOR` and
+//! `--no-color` get plain text that scripts can read.
+
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use console::{style, StyledObject};
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// Set by `--no-color` or `NO_COLOR`
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Progress indicator currently on screen, so other output can be printed above it
+static ACTIVE: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// How often spinners and countdowns redraw
+const TICK: Duration = Duration::from_millis(100);
+
+/// Turn colors and animation off, or back on where the terminal supports them
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+    console::set_colors_enabled(!plain && console::Term::stdout().features().colors_supported());
+    console::set_colors_enabled_stderr(
+        !plain && console::Term::stderr().features().colors_supported(),
+    );
+}
+
+/// Apply `--no-color` and the `NO_COLOR` convention
+pub fn init(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    set_plain(no_color || no_color_env);
+}
+
+/// Whether output is plain text: colors are off or stdout isn't a terminal
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed) || !console::Term::stdout().is_term()
+}
+
+/// Title of a section
+pub fn heading(text: &str) -> String {
+    if is_plain() {
+        format!("==== {} ====", text)
+    } else {
+        style(text).bold().cyan().to_string()
+    }
+}
+
+/// A result that went well
+pub fn success<D: Display>(text: D) -> StyledObject<D> {
+    style(text).green()
+}
+
+/// A result worth the user's attention
+pub fn warning<D: Display>(text: D) -> StyledObject<D> {
+    style(text).yellow()
+}
+
+/// A failure, for stderr
+pub fn error<D: Display>(text: D) -> StyledObject<D> {
+    style(text).red().bold().for_stderr()
+}
+
+/// Secondary information
+pub fn dim<D: Display>(text: D) -> StyledObject<D> {
+    style(text).dim()
+}
+
+/// A label, drawn bold
+pub fn label<D: Display>(text: D) -> StyledObject<D> {
+    style(text).bold()
+}
+
+/// `key separator value` lines with the values lined up
+pub fn aligned<K: AsRef<str>, V: Display>(rows: &[(K, V)], separator: &str) -> Vec<String> {
+    let width = rows
+        .iter()
+        .map(|(key, _)| key.as_ref().chars().count())
+        .max()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|(key, value)| {
+            let key = key.as_ref();
+            let padding = " ".repeat(width - key.chars().count());
+            format!("{}{} {} {}", label(key), padding, separator, value)
+        })
+        .collect()
+}
+
+/// Print a line of output, above the progress indicator if one is showing
+pub fn line<D: Display>(text: D) {
+    let text = text.to_string();
+    match ACTIVE.lock().ok().and_then(|active| active.clone()) {
+        Some(bar) => bar.println(text),
+        None => println!("{}", text),
+    }
+}
+
+/// Show what the progress indicator on screen is doing now
+///
+/// In plain output each step is printed as a line of its own.
+pub fn status(message: &str) {
+    match ACTIVE.lock().ok().and_then(|active| active.clone()) {
+        Some(bar) => bar.set_message(message.to_string()),
+        None if is_plain() => println!("  {}", message),
+        None => {}
+    }
+}
+
+/// A spinner or countdown shown while a command works
+///
+/// In plain output nothing animates: the message is printed once, and each step and the
+/// final result as a line of its own.
+pub struct Progress {
+    bar: Option<ProgressBar>,
+}
+
+impl Progress {
+    /// Spinner with `message`, for work of unknown length
+    pub fn spinner(message: &str) -> Self {
+        if is_plain() {
+            println!("{}...", message);
+            return Self { bar: None };
+        }
+        let bar = ProgressBar::new_spinner()
+            .with_style(
+                ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}")
+                    .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+            )
+            .with_message(message.to_string());
+        bar.enable_steady_tick(TICK);
+        Self::show(bar)
+    }
+
+    /// Bar counting down `duration`, for work that runs for a fixed time
+    pub fn countdown(message: &str, duration: Duration) -> Self {
+        if is_plain() {
+            println!("{} for {} seconds...", message, duration.as_secs());
+            return Self { bar: None };
+        }
+        let total = duration.as_millis().max(1) as u64;
+        let bar = ProgressBar::new(total)
+            .with_style(
+                ProgressStyle::with_template(
+                    "{spinner:.cyan} {msg} [{bar:30.cyan/blue}] {prefix:.dim} left",
+                )
+                .unwrap_or_else(|_| ProgressStyle::default_bar())
+                .progress_chars("=> "),
+            )
+            .with_message(message.to_string());
+        bar.enable_steady_tick(TICK);
+
+        let ticker = bar.clone();
+        let started = Instant::now();
+        std::thread::spawn(move || {
+            while !ticker.is_finished() {
+                let elapsed = started.elapsed().min(duration);
+                ticker.set_position(elapsed.as_millis() as u64);
+                ticker.set_prefix(format!("{}s", (duration - elapsed).as_secs()));
+                std::thread::sleep(TICK);
+            }
+        });
+        Self::show(bar)
+    }
+
+    fn show(bar: ProgressBar) -> Self {
+        if let Ok(mut active) = ACTIVE.lock() {
+            *active = Some(bar.clone());
+        }
+        Self { bar: Some(bar) }
+    }
+
+    /// Remove the indicator and print `message` as the result
+    pub fn finish(self, message: impl Display) {
+        self.clear();
+        println!("{}", message);
+    }
+
+    /// Remove the indicator without printing anything
+    pub fn clear(mut self) {
+        self.hide();
+    }
+
+    fn hide(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            if let Ok(mut active) = ACTIVE.lock() {
+                *active = None;
+            }
+        }
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.hide();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_output_is_aligned_text() {
+        set_plain(true);
+        assert!(is_plain());
+        assert_eq!(heading("Issues"), "==== Issues ====");
+        assert_eq!(success("ok").to_string(), "ok");
+
+        let rows = [("ui.theme", "dark"), ("bluetooth.scan_interval", "30")];
+        assert_eq!(
+            aligned(&rows, "="),
+            vec![
+                "ui.theme                = dark".to_string(),
+                "bluetooth.scan_interval = 30".to_string(),
+            ]
+        );
+
+        // Without a terminal nothing animates, and lines go straight to stdout
+        let progress = Progress::spinner("Working");
+        assert!(progress.bar.is_none());
+        progress.finish("done");
+    }
+}
@@ -1,9 +1,17 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
 use crate::airpods::battery_estimator::DischargeHistory;
+use crate::airpods::DeviceFingerprint;
 use crate::bluetooth::ScanConfig;
+use crate::retry::{RetryConfig, RetryDomain};
+
+use super::SecretRef;
+
+/// Longest `bluetooth.event_merge_window_ms` accepted
+pub const MAX_EVENT_MERGE_WINDOW_MS: u64 = 10_000;
 
 /// Application configuration
 ///
@@ -31,9 +39,17 @@ pub struct AppConfig {
     #[serde(default)]
     pub battery: BatteryConfig,
 
+    /// Actions run when battery and device events occur
+    #[serde(default)]
+    pub actions: ActionsConfig,
+
     /// Path to save settings (runtime only, not serialized)
     #[serde(skip)]
     pub(crate) settings_path: PathBuf,
+
+    /// Environment and command line overrides in effect (runtime only, not serialized)
+    #[serde(skip)]
+    pub(crate) overrides: Vec<super::AppliedOverride>,
 }
 
 /// Bluetooth scanning and connection configuration
@@ -67,6 +83,10 @@ pub struct BluetoothConfig {
     #[serde(default)]
     pub paired_device_name: Option<String>,
 
+    /// Identifying data of the paired AirPods, matched against scans as addresses rotate
+    #[serde(default)]
+    pub paired_fingerprint: Option<DeviceFingerprint>,
+
     /// Auto-reconnect to last connected device
     #[serde(default = "default_true")]
     pub auto_reconnect: bool,
@@ -78,6 +98,149 @@ pub struct BluetoothConfig {
     /// Use adaptive polling for battery status
     #[serde(default = "default_true")]
     pub adaptive_polling: bool,
+
+    /// Devices to accept; when non-empty, everything else is ignored
+    #[serde(default)]
+    pub allow_list: Vec<DeviceFilterEntry>,
+
+    /// Devices to ignore, e.g. a neighbor's AirPods that drift into range
+    #[serde(default)]
+    pub deny_list: Vec<DeviceFilterEntry>,
+
+    /// Ignore every device except the paired one (`paired_device_id` and the addresses
+    /// in `paired_fingerprint`)
+    #[serde(default)]
+    pub only_paired_device: bool,
+
+    /// Only process devices whose address is listed: allow list addresses (entered
+    /// directly or as stable IDs), tracked devices and the paired device. Name patterns
+    /// don't count, so nothing passes until an address is listed.
+    #[serde(default)]
+    pub allow_listed_only: bool,
+
+    /// Canonical addresses entered by hand to watch before they have been seen
+    #[serde(default)]
+    pub tracked_devices: Vec<String>,
+
+    /// Milliseconds in which repeats of an unchanged advertisement from one device are
+    /// merged into the first, so only changes reach the UI (0 forwards every repeat)
+    #[serde(default = "default_event_merge_window_ms")]
+    pub event_merge_window_ms: u64,
+}
+
+/// An allow or deny list entry
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum DeviceFilterEntry {
+    /// Bluetooth address, with or without separators (e.g. `58:26:D7:45:AD:8B`)
+    Address(String),
+    /// Device name pattern, case-insensitive, where `*` matches any run of characters
+    NamePattern(String),
+}
+
+impl DeviceFilterEntry {
+    /// Parse user input: anything that looks like a Bluetooth address is an address,
+    /// everything else a name pattern
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        if input.is_empty() {
+            return None;
+        }
+        match canonical_address(input) {
+            // A stable ID is stored as the address it ends in
+            Some(hex) if input.contains('_') => Some(Self::Address(hex.to_uppercase())),
+            Some(_) => Some(Self::Address(input.to_uppercase())),
+            None => Some(Self::NamePattern(input.to_string())),
+        }
+    }
+
+    /// The address or pattern as entered
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Address(value) | Self::NamePattern(value) => value,
+        }
+    }
+
+    /// Whether a device with this address and name matches the entry
+    pub fn matches(&self, address: &str, name: Option<&str>) -> bool {
+        match self {
+            Self::Address(value) => normalize_address(value) == normalize_address(address),
+            Self::NamePattern(pattern) => name
+                .is_some_and(|name| wildcard_match(&pattern.to_lowercase(), &name.to_lowercase())),
+        }
+    }
+}
+
+/// Allow and deny lists resolved from [`BluetoothConfig`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceAccessList {
+    /// Entries a device must match one of (ignored when empty)
+    pub allow: Vec<DeviceFilterEntry>,
+    /// Entries that exclude a device, checked before the allow list
+    pub deny: Vec<DeviceFilterEntry>,
+    /// Accept only devices matching an address entry of `allow`, even when it has none
+    pub addresses_only: bool,
+}
+
+impl DeviceAccessList {
+    /// Whether every device is accepted
+    pub fn is_open(&self) -> bool {
+        !self.addresses_only && self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Whether a device with this address and name should be used
+    pub fn allows(&self, address: &str, name: Option<&str>) -> bool {
+        if self.deny.iter().any(|entry| entry.matches(address, name)) {
+            return false;
+        }
+        if self.addresses_only {
+            return self.allow.iter().any(|entry| {
+                matches!(entry, DeviceFilterEntry::Address(_)) && entry.matches(address, name)
+            });
+        }
+        self.allow.is_empty() || self.allow.iter().any(|entry| entry.matches(address, name))
+    }
+}
+
+/// Canonical form (12 lowercase hex digits) of a typed or pasted Bluetooth address
+///
+/// Also accepts a stable device ID such as `paired_2014_aabbccddeeff`, which ends in the
+/// address it was first seen at.
+pub fn canonical_address(input: &str) -> Option<String> {
+    let input = input.trim();
+    let address = input.rsplit('_').next().unwrap_or(input);
+    let hex = normalize_address(address);
+    (hex.len() == 12 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// Lowercase hex digits of an address with separators removed
+fn normalize_address(address: &str) -> String {
+    address
+        .chars()
+        .filter(|c| !matches!(c, ':' | '-' | ' '))
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcard: the whole name has to match
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
 }
 
 /// Window position information
@@ -89,6 +252,15 @@ pub struct WindowPosition {
     pub y: i32,
 }
 
+/// Window size information (logical pixels)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowSize {
+    /// Width
+    pub width: u32,
+    /// Height
+    pub height: u32,
+}
+
 impl From<iced::Point> for WindowPosition {
     fn from(point: iced::Point) -> Self {
         Self {
@@ -105,7 +277,7 @@ impl From<WindowPosition> for iced::Point {
 }
 
 /// User interface configuration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct UiConfig {
     /// Show battery notifications
     #[serde(default = "default_true")]
@@ -127,10 +299,14 @@ pub struct UiConfig {
     #[serde(default = "default_true")]
     pub show_low_battery_warning: bool,
 
-    /// Low battery threshold percentage
+    /// Low battery threshold percentage, for components without their own threshold
     #[serde(default = "default_low_battery_threshold")]
     pub low_battery_threshold: u8,
 
+    /// Low battery thresholds for individual components
+    #[serde(default)]
+    pub low_battery_thresholds: LowBatteryThresholds,
+
     /// Remember window position
     #[serde(default = "default_true")]
     pub remember_window_position: bool,
@@ -139,6 +315,10 @@ pub struct UiConfig {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_window_position: Option<WindowPosition>,
 
+    /// Last window size chosen by resizing (clamped to the resize limits on load)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_size: Option<WindowSize>,
+
     /// Minimize to tray when closed
     #[serde(default = "default_true")]
     pub minimize_to_tray_on_close: bool,
@@ -150,6 +330,494 @@ pub struct UiConfig {
     /// Auto-hide window after inactivity timeout (in seconds)
     #[serde(default)]
     pub auto_hide_timeout: Option<u64>,
+
+    /// Briefly show a battery popup when the AirPods case is opened nearby
+    #[serde(default = "default_false")]
+    pub show_lid_open_popup: bool,
+
+    /// Open the window in the corner by the system tray instead of where it was left
+    #[serde(default = "default_false")]
+    pub open_near_tray: bool,
+
+    /// Offer a button to route audio to the AirPods when they are not the default output
+    #[serde(default = "default_true")]
+    pub show_audio_switch_button: bool,
+
+    /// List every AirPods set nearby with its own battery row, for desks shared by
+    /// several people, instead of showing only the paired set
+    #[serde(default = "default_false")]
+    pub household_mode: bool,
+
+    /// Hide RustPods windows from screen recordings, screen sharing and streams
+    #[serde(default = "default_false")]
+    pub exclude_from_capture: bool,
+
+    /// Show the lowest earbud level on the taskbar button, in red when low
+    #[serde(default = "default_true")]
+    pub taskbar_badge: bool,
+
+    /// Which level the tray tooltip and taskbar button lead with
+    #[serde(default)]
+    pub tray_metric: TrayMetric,
+
+    /// Look of the tray icon
+    #[serde(default)]
+    pub tray_icon_style: TrayIconStyle,
+
+    /// Folder holding `connected.ico` and `disconnected.ico` for the custom tray icon style
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tray_icon_folder: Option<PathBuf>,
+
+    /// Draw the window without the GPU (turned on when hardware rendering fails)
+    #[serde(default = "default_false")]
+    pub software_rendering: bool,
+
+    /// Run without the main window; levels show in the tray and in notifications
+    #[serde(default = "default_false")]
+    pub tray_only: bool,
+
+    /// Show the charge left in mAh next to percentages in the details and exports
+    #[serde(default = "default_false")]
+    pub show_mah_estimates: bool,
+
+    /// Window backdrop material (Mica/Acrylic need Windows 11)
+    #[serde(default)]
+    pub backdrop: WindowBackdrop,
+
+    /// Spoken battery announcements
+    #[serde(default)]
+    pub speech: SpeechConfig,
+
+    /// Sounds played on battery and connection events
+    #[serde(default)]
+    pub sound_alerts: SoundAlertsConfig,
+
+    /// Hours during which automatic audible alerts are suppressed
+    #[serde(default)]
+    pub quiet_hours: QuietHoursConfig,
+
+    /// Automatic switching between the light and dark palettes
+    #[serde(default)]
+    pub theme_schedule: ThemeScheduleConfig,
+
+    /// Installed font family for the interface (None for the bundled font)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub font_family: Option<String>,
+
+    /// Multiplier applied to every text size (0.75 to 2.0)
+    #[serde(default = "default_font_scale")]
+    pub font_scale: f32,
+
+    /// Locale tag (e.g. `ar`, `he-IL`) that decides layout direction; `None` follows the system
+    #[serde(default)]
+    pub locale: Option<String>,
+
+    /// Colors used for battery levels and charging
+    #[serde(default)]
+    pub battery_palette: BatteryPalette,
+
+    /// Mark battery states with shapes as well as color
+    #[serde(default = "default_false")]
+    pub shape_encoding: bool,
+
+    /// Minutes without advertisements before the battery display is grayed out as stale
+    /// (0 never grays it out)
+    #[serde(default = "default_stale_after_minutes")]
+    pub stale_after_minutes: u32,
+
+    /// Rebound in-app shortcuts (e.g. `"refresh": "Ctrl+R"`); unlisted actions keep their default
+    #[serde(default)]
+    pub shortcuts: BTreeMap<ShortcutAction, String>,
+}
+
+/// Text-to-speech announcement configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpeechConfig {
+    /// Enable spoken battery announcements
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Announce when an earbud or the case drops below the low battery threshold
+    #[serde(default = "default_true")]
+    pub announce_low_battery: bool,
+
+    /// Name of the installed voice to use (system default when unset)
+    #[serde(default)]
+    pub voice: Option<String>,
+
+    /// Speaking rate from -10 (slowest) to 10 (fastest)
+    #[serde(default)]
+    pub rate: i8,
+}
+
+/// Sound alert configuration
+///
+/// Each event plays its own WAV file, or the system alert sound when none is set. Like
+/// other automatic audible alerts, sounds are held back during quiet hours.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SoundAlertsConfig {
+    /// Enable sound alerts
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Play a sound when a component drops to its low battery threshold
+    #[serde(default = "default_true")]
+    pub low_battery: bool,
+
+    /// WAV file for low battery (system alert sound when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_battery_sound: Option<String>,
+
+    /// Play a sound when the AirPods disconnect from this PC
+    #[serde(default = "default_true")]
+    pub disconnect: bool,
+
+    /// WAV file for disconnects (system alert sound when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disconnect_sound: Option<String>,
+
+    /// Play a sound when a component finishes charging
+    #[serde(default = "default_true")]
+    pub fully_charged: bool,
+
+    /// WAV file for fully charged (system alert sound when unset)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fully_charged_sound: Option<String>,
+}
+
+/// Event a sound alert can be played for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundAlertEvent {
+    /// A component drops to its low battery threshold
+    LowBattery,
+    /// The AirPods disconnect from this PC
+    Disconnect,
+    /// A component finishes charging
+    FullyCharged,
+}
+
+impl SoundAlertEvent {
+    /// All events, in the order shown in settings
+    pub const ALL: [SoundAlertEvent; 3] = [
+        SoundAlertEvent::LowBattery,
+        SoundAlertEvent::Disconnect,
+        SoundAlertEvent::FullyCharged,
+    ];
+
+    /// Name of the field holding the event's sound file
+    pub fn sound_field(self) -> &'static str {
+        match self {
+            SoundAlertEvent::LowBattery => "low_battery_sound",
+            SoundAlertEvent::Disconnect => "disconnect_sound",
+            SoundAlertEvent::FullyCharged => "fully_charged_sound",
+        }
+    }
+}
+
+impl std::fmt::Display for SoundAlertEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SoundAlertEvent::LowBattery => write!(f, "Low battery"),
+            SoundAlertEvent::Disconnect => write!(f, "Disconnected"),
+            SoundAlertEvent::FullyCharged => write!(f, "Fully charged"),
+        }
+    }
+}
+
+/// Low battery thresholds for each component
+///
+/// Unset components use `ui.low_battery_threshold`, so configurations written before
+/// thresholds could be split keep warning at the same level.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct LowBatteryThresholds {
+    /// Left AirPod threshold percentage
+    #[serde(default)]
+    pub left: Option<u8>,
+
+    /// Right AirPod threshold percentage
+    #[serde(default)]
+    pub right: Option<u8>,
+
+    /// Case threshold percentage
+    #[serde(default)]
+    pub case: Option<u8>,
+}
+
+/// Quiet hours configuration
+///
+/// Times are local, in 24-hour format. A window that starts later than it ends wraps
+/// past midnight (e.g. 22:00 to 07:00) and belongs to the day it starts on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHoursConfig {
+    /// Enable quiet hours
+    #[serde(default = "default_false")]
+    pub enabled: bool,
+
+    /// Hour quiet hours begin (0-23)
+    #[serde(default = "default_quiet_hours_start")]
+    pub start_hour: u8,
+
+    /// Minute quiet hours begin (0-59)
+    #[serde(default)]
+    pub start_minute: u8,
+
+    /// Hour quiet hours end (0-23)
+    #[serde(default = "default_quiet_hours_end")]
+    pub end_hour: u8,
+
+    /// Minute quiet hours end (0-59)
+    #[serde(default)]
+    pub end_minute: u8,
+
+    /// Days quiet hours start on
+    #[serde(default = "default_quiet_hours_days")]
+    pub days: Vec<chrono::Weekday>,
+
+    /// Also be quiet while Windows Focus Assist is on
+    #[serde(default = "default_true")]
+    pub follow_focus_assist: bool,
+
+    /// Hold notifications while quiet and show a summary afterwards, instead of dropping them
+    #[serde(default = "default_true")]
+    pub summarize_held: bool,
+}
+
+/// Window backdrop material
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowBackdrop {
+    /// Solid theme background
+    #[default]
+    Solid,
+    /// Mica, the tinted wallpaper backdrop used by Windows 11 app windows
+    Mica,
+    /// Acrylic, the blurred backdrop used by Windows 11 flyouts
+    Acrylic,
+}
+
+impl WindowBackdrop {
+    /// All backdrops, in the order shown in settings
+    pub const ALL: [WindowBackdrop; 3] = [
+        WindowBackdrop::Solid,
+        WindowBackdrop::Mica,
+        WindowBackdrop::Acrylic,
+    ];
+}
+
+impl std::fmt::Display for WindowBackdrop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WindowBackdrop::Solid => write!(f, "Solid"),
+            WindowBackdrop::Mica => write!(f, "Mica"),
+            WindowBackdrop::Acrylic => write!(f, "Acrylic"),
+        }
+    }
+}
+
+/// Battery level shown when there is only room for one
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayMetric {
+    /// The earbud that will run out first
+    #[default]
+    Lowest,
+    /// Average of both earbuds
+    Average,
+    /// Left AirPod
+    Left,
+    /// Right AirPod
+    Right,
+    /// Charging case
+    Case,
+}
+
+impl TrayMetric {
+    /// All metrics, in the order shown in settings
+    pub const ALL: [TrayMetric; 5] = [
+        TrayMetric::Lowest,
+        TrayMetric::Average,
+        TrayMetric::Left,
+        TrayMetric::Right,
+        TrayMetric::Case,
+    ];
+}
+
+impl std::fmt::Display for TrayMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrayMetric::Lowest => write!(f, "Lowest earbud"),
+            TrayMetric::Average => write!(f, "Average of both earbuds"),
+            TrayMetric::Left => write!(f, "Left earbud"),
+            TrayMetric::Right => write!(f, "Right earbud"),
+            TrayMetric::Case => write!(f, "Case"),
+        }
+    }
+}
+
+/// Look of the tray icon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconStyle {
+    /// The bundled solid icons
+    #[default]
+    Filled,
+    /// The bundled icons drawn as outlines
+    Outline,
+    /// Only the headline level, as a number
+    Percentage,
+    /// Icons from `ui.tray_icon_folder`
+    Custom,
+}
+
+impl TrayIconStyle {
+    /// All styles, in the order shown in settings
+    pub const ALL: [TrayIconStyle; 4] = [
+        TrayIconStyle::Filled,
+        TrayIconStyle::Outline,
+        TrayIconStyle::Percentage,
+        TrayIconStyle::Custom,
+    ];
+}
+
+impl std::fmt::Display for TrayIconStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrayIconStyle::Filled => write!(f, "Filled"),
+            TrayIconStyle::Outline => write!(f, "Outline"),
+            TrayIconStyle::Percentage => write!(f, "Percentage only"),
+            TrayIconStyle::Custom => write!(f, "Custom icons"),
+        }
+    }
+}
+
+/// Colors for the battery level ramp and charging indicators
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryPalette {
+    /// Red, yellow and green
+    #[default]
+    Standard,
+    /// For reduced green sensitivity
+    Deuteranopia,
+    /// For reduced red sensitivity
+    Protanopia,
+    /// For reduced blue sensitivity
+    Tritanopia,
+}
+
+impl BatteryPalette {
+    /// All palettes, in the order shown in settings
+    pub const ALL: [BatteryPalette; 4] = [
+        BatteryPalette::Standard,
+        BatteryPalette::Deuteranopia,
+        BatteryPalette::Protanopia,
+        BatteryPalette::Tritanopia,
+    ];
+}
+
+impl std::fmt::Display for BatteryPalette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatteryPalette::Standard => write!(f, "Standard"),
+            BatteryPalette::Deuteranopia => write!(f, "Deuteranopia (red-green)"),
+            BatteryPalette::Protanopia => write!(f, "Protanopia (red-green)"),
+            BatteryPalette::Tritanopia => write!(f, "Tritanopia (blue-yellow)"),
+        }
+    }
+}
+
+/// In-app action that can be bound to a keyboard shortcut
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// Scan for devices now
+    Refresh,
+    /// Open the settings window
+    OpenSettings,
+    /// Switch between the compact and expanded views
+    ToggleViewMode,
+    /// Exit the application
+    Quit,
+}
+
+impl ShortcutAction {
+    /// All actions, in the order shown in settings
+    pub const ALL: [ShortcutAction; 4] = [
+        ShortcutAction::Refresh,
+        ShortcutAction::OpenSettings,
+        ShortcutAction::ToggleViewMode,
+        ShortcutAction::Quit,
+    ];
+}
+
+impl std::fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShortcutAction::Refresh => write!(f, "Refresh"),
+            ShortcutAction::OpenSettings => write!(f, "Open settings"),
+            ShortcutAction::ToggleViewMode => write!(f, "Toggle compact view"),
+            ShortcutAction::Quit => write!(f, "Quit"),
+        }
+    }
+}
+
+/// How the theme follows the time of day
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeScheduleMode {
+    /// Keep the configured theme
+    #[default]
+    Off,
+    /// Switch at fixed local hours
+    FixedTimes,
+    /// Switch at sunrise and sunset for the configured location
+    SunriseSunset,
+}
+
+impl ThemeScheduleMode {
+    /// All modes, in the order shown in settings
+    pub const ALL: [ThemeScheduleMode; 3] = [
+        ThemeScheduleMode::Off,
+        ThemeScheduleMode::FixedTimes,
+        ThemeScheduleMode::SunriseSunset,
+    ];
+}
+
+impl std::fmt::Display for ThemeScheduleMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeScheduleMode::Off => write!(f, "Off"),
+            ThemeScheduleMode::FixedTimes => write!(f, "Fixed times"),
+            ThemeScheduleMode::SunriseSunset => write!(f, "Sunrise to sunset"),
+        }
+    }
+}
+
+/// Theme schedule configuration
+///
+/// Hours are local time in 24-hour format. Sunrise/sunset mode falls back to the fixed
+/// hours when no location is set or the sun doesn't rise or set that day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ThemeScheduleConfig {
+    /// Scheduling mode
+    #[serde(default)]
+    pub mode: ThemeScheduleMode,
+
+    /// Hour the light palette starts (0-23)
+    #[serde(default = "default_theme_light_hour")]
+    pub light_hour: u8,
+
+    /// Hour the dark palette starts (0-23)
+    #[serde(default = "default_theme_dark_hour")]
+    pub dark_hour: u8,
+
+    /// Latitude in degrees for sunrise/sunset (north positive)
+    #[serde(default)]
+    pub latitude: Option<f64>,
+
+    /// Longitude in degrees for sunrise/sunset (east positive)
+    #[serde(default)]
+    pub longitude: Option<f64>,
 }
 
 /// System configuration
@@ -163,6 +831,10 @@ pub struct SystemConfig {
     #[serde(default)]
     pub log_level: LogLevel,
 
+    /// Levels for single categories, replacing `log_level` for their messages
+    #[serde(default)]
+    pub category_log_levels: BTreeMap<LogCategory, LogLevel>,
+
     /// Enable application telemetry
     #[serde(default)]
     pub enable_telemetry: bool,
@@ -174,6 +846,136 @@ pub struct SystemConfig {
     /// Create crash recovery snapshots
     #[serde(default = "default_true")]
     pub enable_crash_recovery: bool,
+
+    /// Relaunch the application after it crashes
+    #[serde(default = "default_true")]
+    pub relaunch_after_crash: bool,
+
+    /// Replace device addresses with salted hashes in logs, diagnostics and exports
+    #[serde(default)]
+    pub privacy_mode: bool,
+
+    /// Salt for privacy mode hashes, generated the first time it is turned on
+    #[serde(default)]
+    pub privacy_salt: Option<String>,
+
+    /// Download the known issues index weekly instead of only using the bundled one
+    #[serde(default)]
+    pub refresh_known_issues: bool,
+
+    /// Show tools for testing the app itself, such as injecting synthetic frames
+    #[serde(default)]
+    pub developer_mode: bool,
+
+    /// Proxy for downloads and other outbound requests
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+
+    /// Automatic backups of all application data
+    #[serde(default)]
+    pub backup: BackupScheduleConfig,
+
+    /// How failed Bluetooth calls, scanner runs and file writes are retried
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+/// How often all application data is backed up automatically
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupFrequency {
+    /// Only back up when asked to
+    #[default]
+    Off,
+    /// Once a day
+    Daily,
+    /// Once a week
+    Weekly,
+}
+
+impl BackupFrequency {
+    /// All frequencies, in the order shown in settings
+    pub const ALL: [BackupFrequency; 3] = [
+        BackupFrequency::Off,
+        BackupFrequency::Daily,
+        BackupFrequency::Weekly,
+    ];
+
+    /// Time between scheduled backups; `None` when they are off
+    pub fn interval(self) -> Option<std::time::Duration> {
+        match self {
+            BackupFrequency::Off => None,
+            BackupFrequency::Daily => Some(std::time::Duration::from_secs(24 * 60 * 60)),
+            BackupFrequency::Weekly => Some(std::time::Duration::from_secs(7 * 24 * 60 * 60)),
+        }
+    }
+}
+
+impl std::fmt::Display for BackupFrequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackupFrequency::Off => write!(f, "Off"),
+            BackupFrequency::Daily => write!(f, "Daily"),
+            BackupFrequency::Weekly => write!(f, "Weekly"),
+        }
+    }
+}
+
+/// Scheduled backups of all application data
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BackupScheduleConfig {
+    /// How often to back up
+    #[serde(default)]
+    pub frequency: BackupFrequency,
+
+    /// Folder the backups are written to; `backups` in the data folder if unset
+    #[serde(default)]
+    pub folder: Option<PathBuf>,
+
+    /// Number of scheduled backups kept; older ones are deleted
+    #[serde(default = "default_backup_keep")]
+    pub keep: u32,
+}
+
+/// How outbound requests reach the internet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyMode {
+    /// Use the proxy set in Windows' Internet settings, or the proxy environment variables
+    #[default]
+    System,
+    /// Use the proxy configured here
+    Manual,
+    /// Connect directly, ignoring any system proxy
+    Direct,
+}
+
+/// Proxy for outbound requests, for networks that can't reach the internet directly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProxyConfig {
+    /// Where the proxy comes from
+    #[serde(default)]
+    pub mode: ProxyMode,
+
+    /// Proxy host name or address, for the manual mode
+    #[serde(default)]
+    pub host: String,
+
+    /// Proxy port, for the manual mode
+    #[serde(default = "default_proxy_port")]
+    pub port: u16,
+
+    /// User name, if the proxy requires authentication
+    #[serde(default)]
+    pub username: Option<String>,
+
+    /// Password, kept in the Windows Credential Manager
+    #[serde(default)]
+    pub password: Option<SecretRef>,
+
+    /// Hosts reached directly, e.g. `*.corp.example.com`; `<local>` matches plain host names
+    #[serde(default)]
+    pub bypass: Vec<String>,
 }
 
 /// Battery monitoring configuration
@@ -191,6 +993,18 @@ pub struct BatteryConfig {
     #[serde(default = "default_change_threshold")]
     pub change_threshold: u8,
 
+    /// Critical battery threshold percentage
+    #[serde(default = "default_critical_battery_threshold")]
+    pub critical_threshold: u8,
+
+    /// Flash the tray icon when a component drops to the critical threshold
+    #[serde(default = "default_true")]
+    pub flash_tray_on_critical: bool,
+
+    /// How long to flash the tray icon, in seconds (0 flashes until the window is opened)
+    #[serde(default = "default_critical_flash_duration")]
+    pub critical_flash_duration_secs: u64,
+
     /// Send notifications for low battery
     #[serde(default = "default_true")]
     pub notify_low: bool,
@@ -220,6 +1034,161 @@ pub struct BatteryConfig {
     pub case_history: DischargeHistory,
 }
 
+/// Rules mapping battery and device events to actions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct ActionsConfig {
+    /// Rules, evaluated in order on every battery update
+    #[serde(default)]
+    pub rules: Vec<ActionRule>,
+}
+
+/// Runs an action when an event occurs
+///
+/// Notification text and sound paths may contain `{device}`, `{component}` and `{level}`,
+/// which are replaced with the details of the event. Commands receive the same details in
+/// the `RUSTPODS_DEVICE`, `RUSTPODS_COMPONENT` and `RUSTPODS_LEVEL` environment variables.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActionRule {
+    /// Label shown in settings and logs
+    #[serde(default)]
+    pub name: String,
+
+    /// Whether the rule is evaluated
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Event the rule reacts to
+    pub trigger: RuleTrigger,
+
+    /// What happens when the event occurs
+    pub action: RuleAction,
+}
+
+/// Event that fires a rule
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleTrigger {
+    /// A component drops to or below a level while not charging
+    BatteryBelow {
+        /// Component to watch
+        #[serde(default)]
+        component: BatteryComponent,
+        /// Level in percent
+        threshold: u8,
+    },
+    /// A component starts charging
+    ChargingStarted {
+        /// Component to watch
+        #[serde(default)]
+        component: BatteryComponent,
+    },
+    /// An AirPod is taken out of an ear
+    BudsRemoved,
+    /// The AirPods are detected again after being out of range
+    DeviceReconnected,
+    /// The AirPods connect to this PC
+    DeviceConnected,
+    /// The AirPods disconnect from this PC, staying nearby or leaving range
+    DeviceDisconnected,
+    /// A component reaches 100%
+    FullyCharged {
+        /// Component to watch
+        #[serde(default)]
+        component: BatteryComponent,
+    },
+}
+
+impl std::fmt::Display for RuleTrigger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleTrigger::BatteryBelow {
+                component,
+                threshold,
+            } => write!(f, "{} at or below {}%", component, threshold),
+            RuleTrigger::ChargingStarted { component } => {
+                write!(f, "{} starts charging", component)
+            }
+            RuleTrigger::BudsRemoved => write!(f, "AirPod removed from ear"),
+            RuleTrigger::DeviceReconnected => write!(f, "AirPods reconnected"),
+            RuleTrigger::DeviceConnected => write!(f, "AirPods connected to this PC"),
+            RuleTrigger::DeviceDisconnected => write!(f, "AirPods disconnected from this PC"),
+            RuleTrigger::FullyCharged { component } => write!(f, "{} fully charged", component),
+        }
+    }
+}
+
+/// Battery component a trigger watches
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryComponent {
+    /// Any of the three
+    #[default]
+    Any,
+    /// Left AirPod
+    Left,
+    /// Right AirPod
+    Right,
+    /// Charging case
+    Case,
+}
+
+impl BatteryComponent {
+    /// All components, in the order shown in settings
+    pub const ALL: [BatteryComponent; 4] = [
+        BatteryComponent::Any,
+        BatteryComponent::Left,
+        BatteryComponent::Right,
+        BatteryComponent::Case,
+    ];
+}
+
+impl std::fmt::Display for BatteryComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatteryComponent::Any => write!(f, "Any component"),
+            BatteryComponent::Left => write!(f, "Left AirPod"),
+            BatteryComponent::Right => write!(f, "Right AirPod"),
+            BatteryComponent::Case => write!(f, "Case"),
+        }
+    }
+}
+
+/// What a rule does when it fires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// Show a desktop notification
+    Notify {
+        /// Notification title
+        title: String,
+        /// Notification text
+        #[serde(default)]
+        body: String,
+    },
+    /// Play a WAV file, or the system alert sound when no path is given
+    PlaySound {
+        /// Path to a WAV file
+        #[serde(default)]
+        path: Option<String>,
+    },
+    /// Run a shell command
+    RunCommand {
+        /// Command line passed to the system shell
+        command: String,
+    },
+}
+
+impl std::fmt::Display for RuleAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleAction::Notify { title, .. } => write!(f, "Notify \"{}\"", title),
+            RuleAction::PlaySound { path: Some(path) } => write!(f, "Play {}", path),
+            RuleAction::PlaySound { path: None } => write!(f, "Play the system alert sound"),
+            RuleAction::RunCommand { command } => write!(f, "Run {}", command),
+        }
+    }
+}
+
 /// UI theme
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -275,7 +1244,54 @@ impl std::fmt::Display for LogLevel {
     }
 }
 
+/// Area of the application a log message comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LogCategory {
+    /// UI events, window management, system tray
+    Ui,
+    /// Bluetooth scanning, device discovery, CLI scanner
+    Bluetooth,
+    /// AirPods detection, battery parsing
+    AirPods,
+    /// Configuration loading, saving, validation
+    Config,
+    /// Lifecycle, persistence, telemetry, diagnostics
+    System,
+}
+
+impl LogCategory {
+    /// All categories, in the order shown in settings
+    pub const ALL: [LogCategory; 5] = [
+        LogCategory::Ui,
+        LogCategory::Bluetooth,
+        LogCategory::AirPods,
+        LogCategory::Config,
+        LogCategory::System,
+    ];
+}
+
+impl std::fmt::Display for LogCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogCategory::Ui => write!(f, "UI"),
+            LogCategory::Bluetooth => write!(f, "Bluetooth"),
+            LogCategory::AirPods => write!(f, "AirPods"),
+            LogCategory::Config => write!(f, "Settings"),
+            LogCategory::System => write!(f, "System"),
+        }
+    }
+}
+
 // Default functions for serde
+fn default_proxy_port() -> u16 {
+    8080
+}
+
+fn default_backup_keep() -> u32 {
+    7
+}
+
 fn default_true() -> bool {
     true
 }
@@ -297,12 +1313,38 @@ fn default_battery_refresh_interval() -> Duration {
 fn default_reconnect_attempts() -> u32 {
     3
 }
+fn default_event_merge_window_ms() -> u64 {
+    1000
+}
 fn default_low_battery_threshold() -> u8 {
     20
 }
+fn default_font_scale() -> f32 {
+    1.0
+}
+fn default_stale_after_minutes() -> u32 {
+    5
+}
 fn default_change_threshold() -> u8 {
     5
 }
+fn default_critical_battery_threshold() -> u8 {
+    5
+}
+fn default_critical_flash_duration() -> u64 {
+    300
+}
+fn default_quiet_hours_start() -> u8 {
+    22
+}
+fn default_quiet_hours_end() -> u8 {
+    7
+}
+
+fn default_quiet_hours_days() -> Vec<chrono::Weekday> {
+    use chrono::Weekday::*;
+    vec![Mon, Tue, Wed, Thu, Fri, Sat, Sun]
+}
 
 // Custom serialization for Duration
 mod duration_serde {
@@ -335,7 +1377,9 @@ impl Default for AppConfig {
             ui: UiConfig::default(),
             system: SystemConfig::default(),
             battery: BatteryConfig::default(),
+            actions: ActionsConfig::default(),
             settings_path: default_settings_path(),
+            overrides: Vec::new(),
         }
     }
 }
@@ -350,39 +1394,252 @@ impl Default for BluetoothConfig {
             battery_refresh_interval: default_battery_refresh_interval(),
             paired_device_id: None,
             paired_device_name: None,
+            paired_fingerprint: None,
             auto_reconnect: default_true(),
             reconnect_attempts: default_reconnect_attempts(),
             adaptive_polling: default_true(),
+            allow_list: Vec::new(),
+            deny_list: Vec::new(),
+            only_paired_device: false,
+            allow_listed_only: false,
+            tracked_devices: Vec::new(),
+            event_merge_window_ms: default_event_merge_window_ms(),
+        }
+    }
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            show_notifications: default_true(),
+            start_minimized: default_true(),
+            theme: Theme::System,
+            show_percentage_in_tray: default_true(),
+            show_low_battery_warning: default_true(),
+            low_battery_threshold: default_low_battery_threshold(),
+            low_battery_thresholds: LowBatteryThresholds::default(),
+            remember_window_position: default_true(),
+            last_window_position: None,
+            window_size: None,
+            minimize_to_tray_on_close: default_true(),
+            minimize_on_blur: default_false(),
+            auto_hide_timeout: None,
+            show_lid_open_popup: default_false(),
+            open_near_tray: default_false(),
+            show_audio_switch_button: default_true(),
+            household_mode: default_false(),
+            exclude_from_capture: default_false(),
+            taskbar_badge: default_true(),
+            tray_metric: TrayMetric::default(),
+            tray_icon_style: TrayIconStyle::default(),
+            tray_icon_folder: None,
+            software_rendering: false,
+            tray_only: false,
+            show_mah_estimates: false,
+            backdrop: WindowBackdrop::default(),
+            speech: SpeechConfig::default(),
+            sound_alerts: SoundAlertsConfig::default(),
+            quiet_hours: QuietHoursConfig::default(),
+            theme_schedule: ThemeScheduleConfig::default(),
+            font_family: None,
+            font_scale: default_font_scale(),
+            locale: None,
+            battery_palette: BatteryPalette::default(),
+            shape_encoding: default_false(),
+            stale_after_minutes: default_stale_after_minutes(),
+            shortcuts: BTreeMap::new(),
+        }
+    }
+}
+
+impl Default for SoundAlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_false(),
+            low_battery: default_true(),
+            low_battery_sound: None,
+            disconnect: default_true(),
+            disconnect_sound: None,
+            fully_charged: default_true(),
+            fully_charged_sound: None,
+        }
+    }
+}
+
+impl SoundAlertsConfig {
+    /// Whether a sound is played for `event`
+    pub fn plays(&self, event: SoundAlertEvent) -> bool {
+        match event {
+            SoundAlertEvent::LowBattery => self.low_battery,
+            SoundAlertEvent::Disconnect => self.disconnect,
+            SoundAlertEvent::FullyCharged => self.fully_charged,
+        }
+    }
+
+    /// Turn the sound for `event` on or off
+    pub fn set_plays(&mut self, event: SoundAlertEvent, plays: bool) {
+        match event {
+            SoundAlertEvent::LowBattery => self.low_battery = plays,
+            SoundAlertEvent::Disconnect => self.disconnect = plays,
+            SoundAlertEvent::FullyCharged => self.fully_charged = plays,
+        }
+    }
+
+    /// WAV file played for `event`, `None` for the system alert sound
+    pub fn sound(&self, event: SoundAlertEvent) -> Option<&String> {
+        match event {
+            SoundAlertEvent::LowBattery => self.low_battery_sound.as_ref(),
+            SoundAlertEvent::Disconnect => self.disconnect_sound.as_ref(),
+            SoundAlertEvent::FullyCharged => self.fully_charged_sound.as_ref(),
+        }
+    }
+
+    /// Set the WAV file for `event`; an empty path selects the system alert sound
+    pub fn set_sound(&mut self, event: SoundAlertEvent, path: &str) {
+        let path = (!path.trim().is_empty()).then(|| path.trim().to_string());
+        match event {
+            SoundAlertEvent::LowBattery => self.low_battery_sound = path,
+            SoundAlertEvent::Disconnect => self.disconnect_sound = path,
+            SoundAlertEvent::FullyCharged => self.fully_charged_sound = path,
+        }
+    }
+
+    /// The alerts as rules, so they fire on the same events as the user's own rules
+    ///
+    /// Low battery follows each component's threshold from `ui`.
+    pub fn rules(&self, ui: &UiConfig) -> Vec<ActionRule> {
+        if !self.enabled {
+            return Vec::new();
+        }
+        let triggers = [
+            (
+                SoundAlertEvent::LowBattery,
+                [
+                    BatteryComponent::Left,
+                    BatteryComponent::Right,
+                    BatteryComponent::Case,
+                ]
+                .into_iter()
+                .map(|component| RuleTrigger::BatteryBelow {
+                    component,
+                    threshold: ui.low_battery_threshold_for(component),
+                })
+                .collect::<Vec<_>>(),
+            ),
+            (
+                SoundAlertEvent::Disconnect,
+                vec![RuleTrigger::DeviceDisconnected],
+            ),
+            (
+                SoundAlertEvent::FullyCharged,
+                vec![RuleTrigger::FullyCharged {
+                    component: BatteryComponent::Any,
+                }],
+            ),
+        ];
+        triggers
+            .into_iter()
+            .filter(|(event, _)| self.plays(*event))
+            .flat_map(|(event, triggers)| {
+                triggers.into_iter().map(move |trigger| ActionRule {
+                    name: format!("{} sound", event),
+                    enabled: true,
+                    trigger,
+                    action: RuleAction::PlaySound {
+                        path: self.sound(event).cloned(),
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SpeechConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_false(),
+            announce_low_battery: default_true(),
+            voice: None,
+            rate: 0,
         }
     }
 }
 
-impl Default for UiConfig {
+impl Default for QuietHoursConfig {
     fn default() -> Self {
         Self {
-            show_notifications: default_true(),
-            start_minimized: default_true(),
-            theme: Theme::System,
-            show_percentage_in_tray: default_true(),
-            show_low_battery_warning: default_true(),
-            low_battery_threshold: default_low_battery_threshold(),
-            remember_window_position: default_true(),
-            last_window_position: None,
-            minimize_to_tray_on_close: default_true(),
-            minimize_on_blur: default_false(),
-            auto_hide_timeout: None,
+            enabled: default_false(),
+            start_hour: default_quiet_hours_start(),
+            start_minute: 0,
+            end_hour: default_quiet_hours_end(),
+            end_minute: 0,
+            days: default_quiet_hours_days(),
+            follow_focus_assist: default_true(),
+            summarize_held: default_true(),
+        }
+    }
+}
+
+impl Default for ThemeScheduleConfig {
+    fn default() -> Self {
+        Self {
+            mode: ThemeScheduleMode::default(),
+            light_hour: default_theme_light_hour(),
+            dark_hour: default_theme_dark_hour(),
+            latitude: None,
+            longitude: None,
         }
     }
 }
 
+fn default_theme_light_hour() -> u8 {
+    7
+}
+
+fn default_theme_dark_hour() -> u8 {
+    19
+}
+
 impl Default for SystemConfig {
     fn default() -> Self {
         Self {
             launch_at_startup: false,
             log_level: LogLevel::default(),
+            category_log_levels: BTreeMap::new(),
             enable_telemetry: false,
             auto_save_interval: Some(300), // 5 minutes default
             enable_crash_recovery: true,
+            relaunch_after_crash: true,
+            privacy_mode: false,
+            privacy_salt: None,
+            refresh_known_issues: false,
+            developer_mode: false,
+            proxy: ProxyConfig::default(),
+            backup: BackupScheduleConfig::default(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+impl Default for BackupScheduleConfig {
+    fn default() -> Self {
+        Self {
+            frequency: BackupFrequency::default(),
+            folder: None,
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+impl Default for ProxyConfig {
+    fn default() -> Self {
+        Self {
+            mode: ProxyMode::default(),
+            host: String::new(),
+            port: default_proxy_port(),
+            username: None,
+            password: None,
+            bypass: Vec::new(),
         }
     }
 }
@@ -393,6 +1650,9 @@ impl Default for BatteryConfig {
             low_threshold: default_low_battery_threshold(),
             smoothing_enabled: default_true(),
             change_threshold: default_change_threshold(),
+            critical_threshold: default_critical_battery_threshold(),
+            flash_tray_on_critical: default_true(),
+            critical_flash_duration_secs: default_critical_flash_duration(),
             notify_low: default_true(),
             notify_charged: default_true(),
             enable_estimation: default_true(),
@@ -411,6 +1671,8 @@ impl AppConfig {
             .with_scan_duration(self.bluetooth.scan_duration)
             .with_interval(self.bluetooth.scan_interval)
             .with_min_rssi(self.bluetooth.min_rssi)
+            .with_device_access(self.bluetooth.device_access())
+            .with_merge_window(self.bluetooth.event_merge_window())
             .with_continuous(true)
     }
 
@@ -432,10 +1694,21 @@ impl AppConfig {
                 "Default config file does not exist, using defaults: {:?}",
                 config_path
             );
-            return Ok(Self::default());
+            let mut config = Self::default();
+            config.log_override_errors();
+            return Ok(config);
         }
 
-        Self::load_from_path(&config_path)
+        let mut config = Self::load_from_path(&config_path)?;
+        config.log_override_errors();
+        Ok(config)
+    }
+
+    /// Apply this run's overrides, logging the ones that don't fit
+    fn log_override_errors(&mut self) {
+        for e in self.apply_run_overrides() {
+            log::warn!("{}", e);
+        }
     }
 
     /// Load configuration from a specific file path
@@ -472,8 +1745,7 @@ impl AppConfig {
             },
         };
 
-        let mut config: Self =
-            serde_json::from_str(&file_content).map_err(ConfigError::SerializationError)?;
+        let mut config: Self = super::parse_error::from_json_str(&file_content, Some(path))?;
 
         // Update the settings path
         config.settings_path = path.to_path_buf();
@@ -532,14 +1804,33 @@ impl AppConfig {
             );
         }
 
+        // A settings file that failed to load is kept until the user repairs it, and a
+        // good one is backed up before it is replaced
+        if path == self.settings_path {
+            if super::repair::is_protected(path) {
+                return Err(ConfigError::InvalidConfig(format!(
+                    "{} failed to load and needs repair before it can be saved",
+                    path.display()
+                )));
+            }
+            super::repair::back_up(path);
+        }
+
+        // Overrides for this run are not saved
+        let persisted = self.without_overrides();
+
         // Validate before saving
-        self.validate()?;
+        persisted.validate()?;
 
         // Convert to JSON
-        let json = serde_json::to_string_pretty(self).map_err(ConfigError::SerializationError)?;
+        let json =
+            serde_json::to_string_pretty(&persisted).map_err(ConfigError::SerializationError)?;
 
-        // Write to file with error handling
-        std::fs::write(path, json).map_err(|e| match e.kind() {
+        // Write to file with error handling, retrying while another process holds it open
+        crate::retry::retry_blocking("Saving settings", RetryDomain::Storage, || {
+            std::fs::write(path, &json)
+        })
+        .map_err(|e| match e.kind() {
             std::io::ErrorKind::PermissionDenied => {
                 ConfigError::PermissionDenied(path.to_path_buf())
             }
@@ -601,11 +1892,65 @@ impl AppConfig {
             _ => e,
         })?;
 
+        self.actions.validate().map_err(|e| match e {
+            ConfigError::ValidationFailed(field, msg) => {
+                ConfigError::ValidationFailed(format!("actions.{}", field), msg)
+            }
+            _ => e,
+        })?;
+
         Ok(())
     }
 }
 
 impl BluetoothConfig {
+    /// Resolve the allow/deny lists, narrowing to the paired device when requested
+    pub fn device_access(&self) -> DeviceAccessList {
+        let paired: Vec<DeviceFilterEntry> = self
+            .paired_device_id
+            .iter()
+            .chain(
+                self.paired_fingerprint
+                    .iter()
+                    .flat_map(|fingerprint| fingerprint.addresses.iter()),
+            )
+            .cloned()
+            .map(DeviceFilterEntry::Address)
+            .collect();
+        let mut allow = if self.allow_listed_only {
+            // Every listed address counts; name patterns are left out by `addresses_only`
+            self.allow_list.iter().cloned().chain(paired).collect()
+        } else if self.only_paired_device && !paired.is_empty() {
+            paired
+        } else {
+            self.allow_list.clone()
+        };
+        // Devices tracked by hand are wanted even when the list would leave them out
+        if !allow.is_empty() || self.allow_listed_only {
+            allow.extend(
+                self.tracked_devices
+                    .iter()
+                    .cloned()
+                    .map(DeviceFilterEntry::Address),
+            );
+        }
+        DeviceAccessList {
+            allow,
+            deny: self.deny_list.clone(),
+            addresses_only: self.allow_listed_only,
+        }
+    }
+
+    /// Whether a device with this address and name passes the allow/deny lists
+    pub fn allows_device(&self, address: &str, name: Option<&str>) -> bool {
+        self.device_access().allows(address, name)
+    }
+
+    /// Window in which unchanged advertisements are merged, None when turned off
+    pub fn event_merge_window(&self) -> Option<Duration> {
+        (self.event_merge_window_ms > 0).then(|| Duration::from_millis(self.event_merge_window_ms))
+    }
+
     /// Validate Bluetooth configuration
     pub fn validate(&self) -> Result<(), ConfigError> {
         if self.scan_duration.as_secs() == 0 {
@@ -646,6 +1991,17 @@ impl BluetoothConfig {
             }
         }
 
+        // A device missing for longer than this would look unchanged when it returns
+        if self.event_merge_window_ms > MAX_EVENT_MERGE_WINDOW_MS {
+            return Err(ConfigError::ValidationFailed(
+                "event_merge_window_ms".to_string(),
+                format!(
+                    "Event merge window must be at most {} ms",
+                    MAX_EVENT_MERGE_WINDOW_MS
+                ),
+            ));
+        }
+
         if self.reconnect_attempts > 10 {
             log::warn!(
                 "High reconnect_attempts value ({}), this could cause delays",
@@ -653,6 +2009,39 @@ impl BluetoothConfig {
             );
         }
 
+        for (field, list) in [
+            ("allow_list", &self.allow_list),
+            ("deny_list", &self.deny_list),
+        ] {
+            if list.iter().any(|entry| entry.value().trim().is_empty()) {
+                return Err(ConfigError::ValidationFailed(
+                    field.to_string(),
+                    "Device filter entries must not be empty".to_string(),
+                ));
+            }
+        }
+
+        if self.allow_listed_only
+            && !self
+                .device_access()
+                .allow
+                .iter()
+                .any(|entry| matches!(entry, DeviceFilterEntry::Address(_)))
+        {
+            log::warn!("Only allow-listed devices are processed, but no address is listed");
+        }
+
+        if let Some(address) = self
+            .tracked_devices
+            .iter()
+            .find(|address| canonical_address(address).as_ref() != Some(*address))
+        {
+            return Err(ConfigError::ValidationFailed(
+                "tracked_devices".to_string(),
+                format!("'{}' is not a Bluetooth address", address),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -667,6 +2056,19 @@ impl UiConfig {
             ));
         }
 
+        for (field, threshold) in [
+            ("left", self.low_battery_thresholds.left),
+            ("right", self.low_battery_thresholds.right),
+            ("case", self.low_battery_thresholds.case),
+        ] {
+            if threshold.is_some_and(|threshold| threshold > 100) {
+                return Err(ConfigError::ValidationFailed(
+                    format!("low_battery_thresholds.{}", field),
+                    "Low battery threshold cannot exceed 100%".to_string(),
+                ));
+            }
+        }
+
         if let Some(timeout) = self.auto_hide_timeout {
             if timeout < 5 {
                 return Err(ConfigError::ValidationFailed(
@@ -683,6 +2085,190 @@ impl UiConfig {
             }
         }
 
+        if !(crate::ui::fonts::MIN_FONT_SCALE..=crate::ui::fonts::MAX_FONT_SCALE)
+            .contains(&self.font_scale)
+        {
+            return Err(ConfigError::ValidationFailed(
+                "font_scale".to_string(),
+                "Font size must be between 75% and 200%".to_string(),
+            ));
+        }
+
+        if self.stale_after_minutes > 1440 {
+            return Err(ConfigError::ValidationFailed(
+                "stale_after_minutes".to_string(),
+                "Stale data threshold cannot exceed a day (1440 minutes)".to_string(),
+            ));
+        }
+
+        for event in SoundAlertEvent::ALL {
+            let is_wav = |path: &String| {
+                std::path::Path::new(path)
+                    .extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("wav"))
+            };
+            if self
+                .sound_alerts
+                .sound(event)
+                .is_some_and(|path| !is_wav(path))
+            {
+                return Err(ConfigError::ValidationFailed(
+                    format!("sound_alerts.{}", event.sound_field()),
+                    "Alert sounds must be .wav files".to_string(),
+                ));
+            }
+        }
+
+        if !(-10..=10).contains(&self.speech.rate) {
+            return Err(ConfigError::ValidationFailed(
+                "speech.rate".to_string(),
+                "Speech rate must be between -10 and 10".to_string(),
+            ));
+        }
+
+        if self.quiet_hours.start_hour > 23 || self.quiet_hours.end_hour > 23 {
+            return Err(ConfigError::ValidationFailed(
+                "quiet_hours".to_string(),
+                "Quiet hours must be between 0 and 23".to_string(),
+            ));
+        }
+
+        if self.quiet_hours.start_minute > 59 || self.quiet_hours.end_minute > 59 {
+            return Err(ConfigError::ValidationFailed(
+                "quiet_hours".to_string(),
+                "Quiet hours minutes must be between 0 and 59".to_string(),
+            ));
+        }
+
+        for (action, shortcut) in &self.shortcuts {
+            if let Err(e) = shortcut.parse::<crate::ui::KeyboardShortcut>() {
+                return Err(ConfigError::ValidationFailed(
+                    "shortcuts".to_string(),
+                    format!("{}: {}", action, e),
+                ));
+            }
+        }
+
+        self.theme_schedule.validate()?;
+
+        Ok(())
+    }
+}
+
+impl SystemConfig {
+    /// Salt for privacy mode hashes, generating one if there is none yet
+    ///
+    /// The salt has to be saved for hashes to stay the same across runs.
+    pub fn privacy_salt(&mut self) -> &str {
+        self.privacy_salt
+            .get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string())
+    }
+}
+
+impl UiConfig {
+    /// Low battery threshold for one component
+    ///
+    /// `Any` is the shared threshold used by components without their own.
+    pub fn low_battery_threshold_for(&self, component: BatteryComponent) -> u8 {
+        let own = match component {
+            BatteryComponent::Any => None,
+            BatteryComponent::Left => self.low_battery_thresholds.left,
+            BatteryComponent::Right => self.low_battery_thresholds.right,
+            BatteryComponent::Case => self.low_battery_thresholds.case,
+        };
+        own.unwrap_or(self.low_battery_threshold)
+    }
+
+    /// Set the low battery threshold for one component, or the shared one for `Any`
+    pub fn set_low_battery_threshold_for(&mut self, component: BatteryComponent, threshold: u8) {
+        let threshold = threshold.min(100);
+        match component {
+            BatteryComponent::Any => self.low_battery_threshold = threshold,
+            BatteryComponent::Left => self.low_battery_thresholds.left = Some(threshold),
+            BatteryComponent::Right => self.low_battery_thresholds.right = Some(threshold),
+            BatteryComponent::Case => self.low_battery_thresholds.case = Some(threshold),
+        }
+    }
+}
+
+impl QuietHoursConfig {
+    /// Minutes past midnight quiet hours begin
+    pub fn start_minutes(&self) -> u16 {
+        u16::from(self.start_hour) * 60 + u16::from(self.start_minute)
+    }
+
+    /// Minutes past midnight quiet hours end
+    pub fn end_minutes(&self) -> u16 {
+        u16::from(self.end_hour) * 60 + u16::from(self.end_minute)
+    }
+
+    /// Whether a time of day is inside the window, and if so whether the window
+    /// started the day before
+    fn window_at(&self, minutes: u16) -> Option<bool> {
+        let (start, end) = (self.start_minutes(), self.end_minutes());
+        if !self.enabled || start == end {
+            None
+        } else if start < end {
+            (start..end).contains(&minutes).then_some(false)
+        } else if minutes >= start {
+            Some(false)
+        } else {
+            (minutes < end).then_some(true)
+        }
+    }
+
+    /// Check whether the given local hour (0-23) falls within quiet hours on any day
+    pub fn is_active_at(&self, hour: u8) -> bool {
+        self.window_at(u16::from(hour) * 60).is_some()
+    }
+
+    /// Check whether quiet hours cover `minutes` past midnight on `day`
+    pub fn is_active_on(&self, day: chrono::Weekday, minutes: u16) -> bool {
+        match self.window_at(minutes) {
+            Some(false) => self.days.contains(&day),
+            Some(true) => self.days.contains(&day.pred()),
+            None => false,
+        }
+    }
+
+    /// Check whether quiet hours are currently active
+    pub fn is_active_now(&self) -> bool {
+        use chrono::{Datelike, Timelike};
+        let now = chrono::Local::now();
+        self.is_active_on(now.weekday(), (now.hour() * 60 + now.minute()) as u16)
+    }
+}
+
+impl ThemeScheduleConfig {
+    /// Validate theme schedule configuration
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.light_hour > 23 || self.dark_hour > 23 {
+            return Err(ConfigError::ValidationFailed(
+                "theme_schedule".to_string(),
+                "Theme schedule hours must be between 0 and 23".to_string(),
+            ));
+        }
+
+        if self
+            .latitude
+            .is_some_and(|lat| !(-90.0..=90.0).contains(&lat))
+        {
+            return Err(ConfigError::ValidationFailed(
+                "theme_schedule.latitude".to_string(),
+                "Latitude must be between -90 and 90".to_string(),
+            ));
+        }
+
+        if self
+            .longitude
+            .is_some_and(|lon| !(-180.0..=180.0).contains(&lon))
+        {
+            return Err(ConfigError::ValidationFailed(
+                "theme_schedule.longitude".to_string(),
+                "Longitude must be between -180 and 180".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -706,6 +2292,37 @@ impl SystemConfig {
             }
         }
 
+        if self.proxy.mode == ProxyMode::Manual {
+            if self.proxy.host.trim().is_empty() {
+                return Err(ConfigError::ValidationFailed(
+                    "proxy.host".to_string(),
+                    "A manual proxy needs a host".to_string(),
+                ));
+            }
+            if self.proxy.port == 0 {
+                return Err(ConfigError::ValidationFailed(
+                    "proxy.port".to_string(),
+                    "Proxy port must be greater than zero".to_string(),
+                ));
+            }
+        }
+
+        if self.backup.keep == 0 {
+            return Err(ConfigError::ValidationFailed(
+                "backup.keep".to_string(),
+                "At least one scheduled backup must be kept".to_string(),
+            ));
+        }
+
+        for (name, policy) in self.retry.policies() {
+            if let Some(problem) = policy.problem() {
+                return Err(ConfigError::ValidationFailed(
+                    format!("retry.{}", name),
+                    problem.to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -720,6 +2337,13 @@ impl BatteryConfig {
             ));
         }
 
+        if self.critical_threshold > self.low_threshold {
+            return Err(ConfigError::ValidationFailed(
+                "critical_threshold".to_string(),
+                "Critical battery threshold cannot exceed the low battery threshold".to_string(),
+            ));
+        }
+
         if self.change_threshold > 50 {
             return Err(ConfigError::ValidationFailed(
                 "change_threshold".to_string(),
@@ -731,6 +2355,33 @@ impl BatteryConfig {
     }
 }
 
+impl ActionsConfig {
+    /// Validate the action rules
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (index, rule) in self.rules.iter().enumerate() {
+            if let RuleTrigger::BatteryBelow { threshold, .. } = rule.trigger {
+                if threshold > 100 {
+                    return Err(ConfigError::ValidationFailed(
+                        format!("rules[{}].trigger.threshold", index),
+                        "Battery threshold cannot exceed 100%".to_string(),
+                    ));
+                }
+            }
+
+            if let RuleAction::RunCommand { command } = &rule.action {
+                if command.trim().is_empty() {
+                    return Err(ConfigError::ValidationFailed(
+                        format!("rules[{}].action.command", index),
+                        "Command cannot be empty".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Configuration error
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -742,6 +2393,10 @@ pub enum ConfigError {
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
+    /// The settings file couldn't be parsed
+    #[error("Invalid settings: {0}")]
+    ParseError(Box<super::ConfigParseError>),
+
     /// Lock error
     #[error("Failed to lock configuration")]
     LockError,
@@ -771,6 +2426,24 @@ pub enum ConfigError {
     FileSystemError(String),
 }
 
+impl ConfigError {
+    /// Details of a settings file that failed to parse
+    pub fn parse_report(&self) -> Option<&super::ConfigParseError> {
+        match self {
+            ConfigError::ParseError(report) => Some(report),
+            _ => None,
+        }
+    }
+
+    /// The error followed by the offending line of the settings file, if there is one
+    pub fn detailed(&self) -> String {
+        match self.parse_report().and_then(|report| report.snippet()) {
+            Some(snippet) => format!("{}\n{}", self, snippet),
+            None => self.to_string(),
+        }
+    }
+}
+
 impl From<ConfigError> for crate::error::RustPodsError {
     fn from(err: ConfigError) -> Self {
         match err {
@@ -778,6 +2451,7 @@ impl From<ConfigError> for crate::error::RustPodsError {
             ConfigError::SerializationError(e) => {
                 crate::error::RustPodsError::ParseError(e.to_string())
             }
+            ConfigError::ParseError(e) => crate::error::RustPodsError::Config(e.to_string()),
             ConfigError::LockError => {
                 crate::error::RustPodsError::State("Failed to lock configuration".to_string())
             }
@@ -865,4 +2539,226 @@ mod tests {
         );
         assert_eq!(deserialized.ui.theme, config.ui.theme);
     }
+
+    #[test]
+    fn test_quiet_hours_window() {
+        let mut quiet_hours = QuietHoursConfig::default();
+        assert!(!quiet_hours.is_active_at(23));
+
+        quiet_hours.enabled = true;
+        // Default window wraps past midnight (22:00 - 07:00)
+        assert!(quiet_hours.is_active_at(22));
+        assert!(quiet_hours.is_active_at(3));
+        assert!(!quiet_hours.is_active_at(7));
+        assert!(!quiet_hours.is_active_at(12));
+
+        quiet_hours.start_hour = 9;
+        quiet_hours.end_hour = 17;
+        assert!(quiet_hours.is_active_at(9));
+        assert!(!quiet_hours.is_active_at(17));
+        assert!(!quiet_hours.is_active_at(20));
+    }
+
+    #[test]
+    fn test_quiet_hours_days() {
+        use chrono::Weekday;
+
+        let quiet_hours = QuietHoursConfig {
+            enabled: true,
+            start_hour: 22,
+            start_minute: 30,
+            end_hour: 6,
+            end_minute: 45,
+            days: vec![Weekday::Fri, Weekday::Sat],
+            ..Default::default()
+        };
+        assert!(!quiet_hours.is_active_on(Weekday::Fri, 22 * 60 + 15));
+        assert!(quiet_hours.is_active_on(Weekday::Fri, 22 * 60 + 30));
+        // Past midnight the window belongs to the day it started on
+        assert!(quiet_hours.is_active_on(Weekday::Sun, 6 * 60 + 44));
+        assert!(!quiet_hours.is_active_on(Weekday::Sun, 6 * 60 + 45));
+        assert!(quiet_hours.is_active_on(Weekday::Sat, 3 * 60));
+        assert!(!quiet_hours.is_active_on(Weekday::Fri, 3 * 60));
+        assert!(!quiet_hours.is_active_on(Weekday::Thu, 23 * 60));
+    }
+
+    #[test]
+    fn test_font_scale_validation() {
+        let mut ui = UiConfig::default();
+        assert_eq!(ui.font_scale, 1.0);
+        assert!(ui.font_family.is_none());
+
+        ui.font_scale = 1.5;
+        assert!(ui.validate().is_ok());
+
+        ui.font_scale = 3.0;
+        assert!(matches!(
+            ui.validate(),
+            Err(ConfigError::ValidationFailed(field, _)) if field == "font_scale"
+        ));
+    }
+
+    #[test]
+    fn test_device_allow_deny_lists() {
+        let mut bluetooth = BluetoothConfig::default();
+        assert!(bluetooth.allows_device("58:26:D7:45:AD:8B", Some("AirPods Pro")));
+
+        bluetooth.deny_list = vec![DeviceFilterEntry::parse("*alex*").unwrap()];
+        assert!(!bluetooth.allows_device("11:22:33:44:55:66", Some("Alex's AirPods")));
+        assert!(bluetooth.allows_device("11:22:33:44:55:66", Some("AirPods Pro")));
+
+        bluetooth.allow_list = vec![DeviceFilterEntry::parse("58:26:d7:45:ad:8b").unwrap()];
+        assert!(bluetooth.allows_device("5826d745ad8b", Some("AirPods Pro")));
+        assert!(!bluetooth.allows_device("11:22:33:44:55:66", Some("AirPods Pro")));
+
+        // The paired device replaces the allow list when only it is wanted
+        bluetooth.paired_device_id = Some("11:22:33:44:55:66".to_string());
+        bluetooth.only_paired_device = true;
+        assert!(bluetooth.allows_device("11:22:33:44:55:66", None));
+        assert!(!bluetooth.allows_device("58:26:D7:45:AD:8B", Some("AirPods Pro")));
+    }
+
+    #[test]
+    fn test_action_rules() {
+        let json = r#"{"rules": [{
+            "name": "Low left bud",
+            "trigger": {"type": "battery_below", "component": "left", "threshold": 15},
+            "action": {"type": "run_command", "command": "echo low"}
+        }]}"#;
+        let mut actions: ActionsConfig = serde_json::from_str(json).unwrap();
+        assert!(actions.rules[0].enabled);
+        assert_eq!(
+            actions.rules[0].trigger,
+            RuleTrigger::BatteryBelow {
+                component: BatteryComponent::Left,
+                threshold: 15
+            }
+        );
+        assert!(actions.validate().is_ok());
+
+        actions.rules[0].action = RuleAction::RunCommand {
+            command: " ".to_string(),
+        };
+        assert!(matches!(
+            actions.validate(),
+            Err(ConfigError::ValidationFailed(field, _)) if field == "rules[0].action.command"
+        ));
+    }
+
+    #[test]
+    fn test_shortcut_rebinds() {
+        use crate::ui::keyboard_shortcuts::{binding, conflict, default_shortcut};
+
+        let mut ui = UiConfig::default();
+        ui.shortcuts
+            .insert(ShortcutAction::Refresh, "ctrl+shift+f5".to_string());
+        assert!(ui.validate().is_ok());
+
+        let refresh = binding(&ui.shortcuts, ShortcutAction::Refresh);
+        assert_eq!(refresh.to_string(), "Ctrl+Shift+F5");
+        assert_eq!(
+            binding(&ui.shortcuts, ShortcutAction::Quit),
+            default_shortcut(ShortcutAction::Quit)
+        );
+
+        // Taken by another action, or by a shortcut that can't be rebound
+        assert_eq!(
+            conflict(&ui.shortcuts, ShortcutAction::Quit, &refresh),
+            Some("Refresh".to_string())
+        );
+        let hide = "Ctrl+H".parse().unwrap();
+        assert!(conflict(&ui.shortcuts, ShortcutAction::Quit, &hide).is_some());
+        assert!(conflict(&ui.shortcuts, ShortcutAction::Refresh, &refresh).is_none());
+
+        ui.shortcuts
+            .insert(ShortcutAction::Quit, "Ctrl+Nope".to_string());
+        assert!(matches!(
+            ui.validate(),
+            Err(ConfigError::ValidationFailed(field, _)) if field == "shortcuts"
+        ));
+    }
+
+    #[test]
+    fn test_tracked_devices() {
+        assert_eq!(
+            canonical_address(" 58:26:D7:45:AD:8B "),
+            Some("5826d745ad8b".to_string())
+        );
+        assert_eq!(
+            canonical_address("paired_2014_5826d745ad8b"),
+            Some("5826d745ad8b".to_string())
+        );
+        assert_eq!(canonical_address("AirPods"), None);
+
+        let mut bluetooth = BluetoothConfig {
+            allow_list: vec![DeviceFilterEntry::NamePattern("Alex*".to_string())],
+            tracked_devices: vec!["5826d745ad8b".to_string()],
+            ..Default::default()
+        };
+        assert!(bluetooth.validate().is_ok());
+        assert!(bluetooth.allows_device("58:26:D7:45:AD:8B", None));
+        assert!(!bluetooth.allows_device("112233445566", None));
+
+        bluetooth.tracked_devices.push("58:26".to_string());
+        assert!(matches!(
+            bluetooth.validate(),
+            Err(ConfigError::ValidationFailed(field, _)) if field == "tracked_devices"
+        ));
+    }
+
+    #[test]
+    fn test_allow_listed_only() {
+        let mut bluetooth = BluetoothConfig {
+            allow_list: vec![
+                DeviceFilterEntry::parse("*Alex*").unwrap(),
+                DeviceFilterEntry::parse("paired_2014_5826d745ad8b").unwrap(),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            bluetooth.allow_list[1],
+            DeviceFilterEntry::Address("5826D745AD8B".to_string())
+        );
+        assert!(bluetooth.allows_device("112233445566", Some("Alex's AirPods")));
+
+        // Name patterns no longer let devices through
+        bluetooth.allow_listed_only = true;
+        assert!(!bluetooth.allows_device("112233445566", Some("Alex's AirPods")));
+        assert!(bluetooth.allows_device("58:26:D7:45:AD:8B", None));
+
+        // Tracked and paired addresses count as listed
+        bluetooth.tracked_devices = vec!["aabbccddeeff".to_string()];
+        bluetooth.paired_device_id = Some("11:22:33:44:55:66".to_string());
+        assert!(bluetooth.allows_device("AA:BB:CC:DD:EE:FF", None));
+        assert!(bluetooth.allows_device("112233445566", None));
+
+        // Nothing passes while no address is listed
+        let strict = BluetoothConfig {
+            allow_listed_only: true,
+            ..Default::default()
+        };
+        assert!(!strict.device_access().is_open());
+        assert!(!strict.allows_device("58:26:D7:45:AD:8B", Some("AirPods Pro")));
+    }
+
+    #[test]
+    fn test_per_component_low_battery_thresholds() {
+        // A configuration from before the split keeps its single threshold everywhere
+        let legacy: UiConfig = serde_json::from_str(r#"{"low_battery_threshold": 25}"#).unwrap();
+        assert_eq!(
+            legacy.low_battery_thresholds,
+            LowBatteryThresholds::default()
+        );
+        for component in BatteryComponent::ALL {
+            assert_eq!(legacy.low_battery_threshold_for(component), 25);
+        }
+
+        let mut ui = legacy;
+        ui.set_low_battery_threshold_for(BatteryComponent::Case, 10);
+        assert_eq!(ui.low_battery_threshold_for(BatteryComponent::Case), 10);
+        assert_eq!(ui.low_battery_threshold_for(BatteryComponent::Left), 25);
+
+        ui.low_battery_thresholds.right = Some(120);
+        assert!(ui.validate().is_err());
+    }
 }
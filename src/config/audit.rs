@@ -0,0 +1,279 @@
+//! Audit log of settings changes
+//!
+//! Every audited save compares the settings being written with the file already on
+//! disk and appends one entry per changed field. The log sits next to `settings.json`
+//! as JSON lines and keeps the newest [`MAX_AUDIT_ENTRIES`] entries, so support can see
+//! what changed and when without the log growing forever.
+
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{AppConfig, ConfigError};
+
+/// File name of the audit log, in the same directory as the settings
+pub const AUDIT_LOG_FILE: &str = "settings_audit.jsonl";
+
+/// Entries kept in the audit log
+pub const MAX_AUDIT_ENTRIES: usize = 500;
+
+/// Fields whose values are never written to the log, only the fact that they changed
+const HIDDEN_FIELDS: &[&str] = &["system.privacy_salt"];
+
+/// Where a settings change came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeSource {
+    /// The settings window or another part of the interface
+    Ui,
+    /// `rustpods config set`
+    Cli,
+    /// An application using RustPods as a library
+    Api,
+}
+
+impl fmt::Display for ChangeSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Ui => "UI",
+            Self::Cli => "CLI",
+            Self::Api => "API",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// One changed field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// When the change was saved
+    pub timestamp: DateTime<Local>,
+    /// Where the change came from
+    pub source: ChangeSource,
+    /// Dot-path of the field, as used by `rustpods config get`
+    pub field: String,
+    /// Previous value as JSON
+    pub old_value: String,
+    /// New value as JSON
+    pub new_value: String,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {}: {} -> {}",
+            self.timestamp.format("%Y-%m-%d %H:%M:%S"),
+            self.source,
+            self.field,
+            self.old_value,
+            self.new_value
+        )
+    }
+}
+
+/// Bounded log of settings changes stored as JSON lines
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Log stored at `path`
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Log kept next to the settings file of `config`
+    pub fn for_config(config: &AppConfig) -> Self {
+        Self::at(config.settings_path.with_file_name(AUDIT_LOG_FILE))
+    }
+
+    /// Location of the log
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All entries, oldest first; unreadable lines are skipped
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        let Ok(file) = std::fs::File::open(&self.path) else {
+            return Vec::new();
+        };
+        std::io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect()
+    }
+
+    /// The newest `count` entries, newest first
+    pub fn recent(&self, count: usize) -> Vec<AuditEntry> {
+        let mut entries = self.entries();
+        entries.reverse();
+        entries.truncate(count);
+        entries
+    }
+
+    /// Append an entry for each field that differs; returns how many were added
+    pub fn record(
+        &self,
+        old: &AppConfig,
+        new: &AppConfig,
+        source: ChangeSource,
+    ) -> Result<usize, ConfigError> {
+        let changes = diff(old, new)?;
+        let count = changes.len();
+        if count == 0 {
+            return Ok(0);
+        }
+        if !crate::system::disk_space::writes_allowed(&self.path) {
+            log::debug!(
+                "Not recording {} settings changes: low on disk space",
+                count
+            );
+            return Ok(0);
+        }
+
+        let timestamp = Local::now();
+        let mut entries = self.entries();
+        entries.extend(
+            changes
+                .into_iter()
+                .map(|(field, old_value, new_value)| AuditEntry {
+                    timestamp,
+                    source,
+                    field,
+                    old_value,
+                    new_value,
+                }),
+        );
+        let start = entries.len().saturating_sub(MAX_AUDIT_ENTRIES);
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(&self.path)?;
+        for entry in &entries[start..] {
+            writeln!(file, "{}", serde_json::to_string(entry)?)?;
+        }
+        Ok(count)
+    }
+}
+
+impl AppConfig {
+    /// Save, recording every field that differs from the settings file in the audit log
+    ///
+    /// A failure to write the audit log is logged but does not fail the save.
+    pub fn save_audited(&self, source: ChangeSource) -> Result<(), ConfigError> {
+        let previous = if self.settings_path.exists() {
+            AppConfig::load_from_path(&self.settings_path).ok()
+        } else {
+            None
+        };
+
+        self.save()?;
+
+        let previous = previous.unwrap_or_default();
+        match AuditLog::for_config(self).record(&previous, &self.without_overrides(), source) {
+            Ok(0) => {}
+            Ok(count) => log::info!("Recorded {} settings change(s) from {}", count, source),
+            Err(e) => log::warn!("Failed to write settings audit log: {}", e),
+        }
+        Ok(())
+    }
+}
+
+/// Fields that differ between two configurations, as (path, old, new) with JSON values
+///
+/// Lists are compared as a whole, so adding a rule shows up as one change to the list.
+pub fn diff(
+    old: &AppConfig,
+    new: &AppConfig,
+) -> Result<Vec<(String, String, String)>, ConfigError> {
+    fn walk(old: &Value, new: &Value, prefix: &str, out: &mut Vec<(String, String, String)>) {
+        if old == new {
+            return;
+        }
+        if let (Value::Object(old_map), Value::Object(new_map)) = (old, new) {
+            let mut keys: Vec<&String> = old_map.keys().chain(new_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                walk(
+                    old_map.get(key).unwrap_or(&Value::Null),
+                    new_map.get(key).unwrap_or(&Value::Null),
+                    &path,
+                    out,
+                );
+            }
+        } else if HIDDEN_FIELDS.contains(&prefix) {
+            let hidden = "\"(hidden)\"".to_string();
+            out.push((prefix.to_string(), hidden.clone(), hidden));
+        } else {
+            out.push((prefix.to_string(), old.to_string(), new.to_string()));
+        }
+    }
+
+    let mut changes = Vec::new();
+    walk(
+        &serde_json::to_value(old)?,
+        &serde_json::to_value(new)?,
+        "",
+        &mut changes,
+    );
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_changed_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(dir.path().join(AUDIT_LOG_FILE));
+
+        let old = AppConfig::default();
+        let mut new = old.clone();
+        new.ui.low_battery_threshold = 15;
+        new.bluetooth.min_rssi = Some(-60);
+
+        assert_eq!(log.record(&old, &old, ChangeSource::Ui).unwrap(), 0);
+        assert_eq!(log.record(&old, &new, ChangeSource::Cli).unwrap(), 2);
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        let threshold = entries
+            .iter()
+            .find(|entry| entry.field == "ui.low_battery_threshold")
+            .unwrap();
+        assert_eq!(threshold.source, ChangeSource::Cli);
+        assert_eq!(threshold.new_value, "15");
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::at(dir.path().join(AUDIT_LOG_FILE));
+
+        let mut old = AppConfig::default();
+        for i in 1..=MAX_AUDIT_ENTRIES + 10 {
+            let mut new = old.clone();
+            new.ui.low_battery_threshold = (i % 100) as u8;
+            log.record(&old, &new, ChangeSource::Ui).unwrap();
+            old = new;
+        }
+
+        assert_eq!(log.entries().len(), MAX_AUDIT_ENTRIES);
+        assert_eq!(log.recent(1)[0].new_value, "10");
+    }
+}
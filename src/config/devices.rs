@@ -0,0 +1,327 @@
+//! Known devices: nicknames, icons and colors for each pair of AirPods
+//!
+//! The settings hold a single paired pair and a single custom name. A household with
+//! several pairs wants each one told apart, so every pair the user has paired with is
+//! remembered by its [`DeviceFingerprint`] in `devices.json`, next to `settings.json`,
+//! with a nickname, an icon, a color and whether it is the primary pair. The primary
+//! pair is the one RustPods follows; the rest are labelled wherever they show up.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{canonical_address, AppConfig, ConfigError};
+use crate::airpods::DeviceFingerprint;
+
+/// File name of the known devices, in the same directory as the settings
+pub const DEVICES_FILE: &str = "devices.json";
+
+/// Icon shown beside a device's name
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceIcon {
+    /// Headphones
+    #[default]
+    Headphones,
+    /// Music note
+    Music,
+    /// Star
+    Star,
+    /// Heart
+    Heart,
+    /// House
+    Home,
+    /// Briefcase
+    Work,
+}
+
+impl DeviceIcon {
+    /// All icons, in the order shown in settings
+    pub const ALL: [DeviceIcon; 6] = [
+        DeviceIcon::Headphones,
+        DeviceIcon::Music,
+        DeviceIcon::Star,
+        DeviceIcon::Heart,
+        DeviceIcon::Home,
+        DeviceIcon::Work,
+    ];
+
+    /// Glyph of the icon in the bundled Nerd Font
+    pub fn glyph(self) -> char {
+        match self {
+            DeviceIcon::Headphones => '\u{f025}',
+            DeviceIcon::Music => '\u{f001}',
+            DeviceIcon::Star => '\u{f005}',
+            DeviceIcon::Heart => '\u{f004}',
+            DeviceIcon::Home => '\u{f015}',
+            DeviceIcon::Work => '\u{f0b1}',
+        }
+    }
+}
+
+impl fmt::Display for DeviceIcon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeviceIcon::Headphones => "Headphones",
+            DeviceIcon::Music => "Music",
+            DeviceIcon::Star => "Star",
+            DeviceIcon::Heart => "Heart",
+            DeviceIcon::Home => "Home",
+            DeviceIcon::Work => "Work",
+        };
+        write!(f, "{} {}", self.glyph(), name)
+    }
+}
+
+/// Color a device's name is drawn in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceColor {
+    /// The theme's text color
+    #[default]
+    Default,
+    /// Red
+    Red,
+    /// Orange
+    Orange,
+    /// Yellow
+    Yellow,
+    /// Green
+    Green,
+    /// Blue
+    Blue,
+    /// Purple
+    Purple,
+    /// Pink
+    Pink,
+}
+
+impl DeviceColor {
+    /// All colors, in the order shown in settings
+    pub const ALL: [DeviceColor; 8] = [
+        DeviceColor::Default,
+        DeviceColor::Red,
+        DeviceColor::Orange,
+        DeviceColor::Yellow,
+        DeviceColor::Green,
+        DeviceColor::Blue,
+        DeviceColor::Purple,
+        DeviceColor::Pink,
+    ];
+}
+
+impl fmt::Display for DeviceColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeviceColor::Default => "Default",
+            DeviceColor::Red => "Red",
+            DeviceColor::Orange => "Orange",
+            DeviceColor::Yellow => "Yellow",
+            DeviceColor::Green => "Green",
+            DeviceColor::Blue => "Blue",
+            DeviceColor::Purple => "Purple",
+            DeviceColor::Pink => "Pink",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A pair the user has paired with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KnownDevice {
+    /// Identifying data of the pair
+    pub fingerprint: DeviceFingerprint,
+    /// Name chosen by the user
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Icon shown beside the name
+    #[serde(default)]
+    pub icon: DeviceIcon,
+    /// Color the name is drawn in
+    #[serde(default)]
+    pub color: DeviceColor,
+    /// Whether this is the pair RustPods follows
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl KnownDevice {
+    /// Identifier of the pair, the stable id of its fingerprint
+    pub fn id(&self) -> String {
+        self.fingerprint.stable_id()
+    }
+
+    /// Nickname, or the model name if the pair has none
+    pub fn display_name(&self) -> &str {
+        self.nickname
+            .as_deref()
+            .unwrap_or(&self.fingerprint.model_name)
+    }
+}
+
+/// Every pair the user has paired with
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KnownDevices {
+    /// Format version
+    #[serde(default)]
+    pub version: u32,
+    /// Known pairs, in the order they were first paired
+    #[serde(default)]
+    pub devices: Vec<KnownDevice>,
+}
+
+impl KnownDevices {
+    /// Where the known devices of `config` are stored
+    pub fn path_for(config: &AppConfig) -> PathBuf {
+        config.settings_path.with_file_name(DEVICES_FILE)
+    }
+
+    /// Known devices stored at `path`; none if the file is missing or unreadable
+    ///
+    /// A file that doesn't parse is quarantined rather than left to be overwritten.
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(json) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!(
+                "Ignoring unreadable known devices {}: {}",
+                path.display(),
+                e
+            );
+            if let Err(e) = crate::quarantine::quarantine(path, &e.to_string()) {
+                log::error!("Failed to quarantine {}: {}", path.display(), e);
+            }
+            Self::default()
+        })
+    }
+
+    /// Write the known devices to `path`
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&Self {
+            version: 1,
+            devices: self.devices.clone(),
+        })?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Whether no pair is known
+    pub fn is_empty(&self) -> bool {
+        self.devices.is_empty()
+    }
+
+    /// Add a pair, or update the fingerprint of a known one
+    ///
+    /// The first pair added becomes primary.
+    pub fn remember(&mut self, fingerprint: &DeviceFingerprint) {
+        let id = fingerprint.stable_id();
+        if let Some(device) = self.get_mut(&id) {
+            device.fingerprint = fingerprint.clone();
+            return;
+        }
+        let primary = !self.devices.iter().any(|device| device.primary);
+        self.devices.push(KnownDevice {
+            fingerprint: fingerprint.clone(),
+            nickname: None,
+            icon: DeviceIcon::default(),
+            color: DeviceColor::default(),
+            primary,
+        });
+    }
+
+    /// Pair with identifier `id`
+    pub fn get(&self, id: &str) -> Option<&KnownDevice> {
+        self.devices.iter().find(|device| device.id() == id)
+    }
+
+    /// Pair with identifier `id`, for editing
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut KnownDevice> {
+        self.devices.iter_mut().find(|device| device.id() == id)
+    }
+
+    /// Pair that has advertised from `address`, in any address format
+    pub fn find_address(&self, address: &str) -> Option<&KnownDevice> {
+        let address = canonical_address(address)?;
+        self.devices
+            .iter()
+            .find(|device| device.fingerprint.knows_address(&address))
+    }
+
+    /// The pair RustPods follows
+    pub fn primary(&self) -> Option<&KnownDevice> {
+        self.devices.iter().find(|device| device.primary)
+    }
+
+    /// Make the pair `id` primary; returns false if it isn't known
+    pub fn set_primary(&mut self, id: &str) -> bool {
+        if self.get(id).is_none() {
+            return false;
+        }
+        for device in &mut self.devices {
+            device.primary = device.id() == id;
+        }
+        true
+    }
+
+    /// Forget the pair `id`, returning it
+    pub fn remove(&mut self, id: &str) -> Option<KnownDevice> {
+        let index = self.devices.iter().position(|device| device.id() == id)?;
+        Some(self.devices.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(address: &str) -> DeviceFingerprint {
+        DeviceFingerprint {
+            model_id: 0x2014,
+            model_name: "AirPods Pro 2".to_string(),
+            color: None,
+            addresses: vec![address.to_string()],
+        }
+    }
+
+    #[test]
+    fn test_known_devices_round_trip() {
+        let mut known = KnownDevices::default();
+        known.remember(&fingerprint("aabbccddeeff"));
+        known.remember(&fingerprint("112233445566"));
+        assert_eq!(known.devices.len(), 2);
+        assert_eq!(known.primary().unwrap().id(), known.devices[0].id());
+
+        let second = known.devices[1].id();
+        known.get_mut(&second).unwrap().nickname = Some("Kitchen Pods".to_string());
+        assert!(known.set_primary(&second));
+        assert!(!known.devices[0].primary);
+        assert!(!known.set_primary("paired_0000_"));
+
+        let found = known.find_address("11:22:33:44:55:66").unwrap();
+        assert_eq!(found.display_name(), "Kitchen Pods");
+        assert_eq!(known.devices[0].display_name(), "AirPods Pro 2");
+
+        // Remembering again updates the fingerprint instead of adding a pair
+        let mut moved = fingerprint("112233445566");
+        moved.addresses.push("665544332211".to_string());
+        known.remember(&moved);
+        assert_eq!(known.devices.len(), 2);
+        assert!(known.find_address("665544332211").is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(DEVICES_FILE);
+        known.save_to(&path).unwrap();
+        let loaded = KnownDevices::load_from(&path);
+        assert_eq!(loaded.devices, known.devices);
+        assert_eq!(loaded.version, 1);
+
+        assert!(known.remove(&second).is_some());
+        assert!(known.primary().is_none());
+        assert!(KnownDevices::load_from(&dir.path().join("missing.json")).is_empty());
+    }
+}
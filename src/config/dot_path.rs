@@ -0,0 +1,169 @@
+//! Dot-path access to configuration values
+//!
+//! Backs the `rustpods config get/set` commands. Values are addressed by their serialized
+//! field names, e.g. `ui.low_battery_threshold` or `bluetooth.min_rssi`.
+
+use serde_json::Value;
+
+use super::{AppConfig, ConfigError};
+
+impl AppConfig {
+    /// Read a value by dot-path
+    pub fn get_path(&self, path: &str) -> Result<Value, ConfigError> {
+        let root = serde_json::to_value(self)?;
+        lookup(&root, path).cloned()
+    }
+
+    /// Set a value by dot-path, coercing `raw` to the type of the current value
+    ///
+    /// The updated configuration is validated before it replaces `self`; on error `self` is
+    /// left untouched.
+    pub fn set_path(&mut self, path: &str, raw: &str) -> Result<(), ConfigError> {
+        let mut root = serde_json::to_value(&*self)?;
+        let slot = lookup_mut(&mut root, path)?;
+        *slot = coerce(slot, raw).map_err(|msg| ConfigError::ValidationFailed(path.into(), msg))?;
+
+        let mut updated: AppConfig = serde_json::from_value(root)
+            .map_err(|e| ConfigError::ValidationFailed(path.to_string(), e.to_string()))?;
+        updated.settings_path = self.settings_path.clone();
+        updated.overrides = self.overrides.clone();
+        updated.validate()?;
+
+        *self = updated;
+        Ok(())
+    }
+}
+
+/// List every settable leaf path, for help output and error hints
+pub fn leaf_paths(config: &AppConfig) -> Vec<String> {
+    fn walk(value: &Value, prefix: &str, out: &mut Vec<String>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(child, &path, out);
+                }
+            }
+            _ => out.push(prefix.to_string()),
+        }
+    }
+
+    let mut paths = Vec::new();
+    if let Ok(root) = serde_json::to_value(config) {
+        walk(&root, "", &mut paths);
+    }
+    paths
+}
+
+fn unknown_key(path: &str) -> ConfigError {
+    ConfigError::InvalidConfig(format!("Unknown configuration key '{}'", path))
+}
+
+fn lookup<'a>(root: &'a Value, path: &str) -> Result<&'a Value, ConfigError> {
+    path.split('.')
+        .try_fold(root, |value, key| value.get(key))
+        .ok_or_else(|| unknown_key(path))
+}
+
+pub(super) fn lookup_mut<'a>(
+    root: &'a mut Value,
+    path: &str,
+) -> Result<&'a mut Value, ConfigError> {
+    path.split('.')
+        .try_fold(root, |value, key| value.get_mut(key))
+        .ok_or_else(|| unknown_key(path))
+}
+
+/// Convert a command-line string to the JSON type of the value it replaces
+fn coerce(current: &Value, raw: &str) -> Result<Value, String> {
+    let raw = raw.trim();
+
+    match current {
+        Value::Bool(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "on" | "yes" | "1" => Ok(Value::Bool(true)),
+            "false" | "off" | "no" | "0" => Ok(Value::Bool(false)),
+            _ => Err(format!("expected true or false, got '{}'", raw)),
+        },
+        Value::Number(n) => {
+            let parsed = if n.is_f64() {
+                raw.parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+            } else {
+                // Signedness is checked when the result is deserialized into the field type
+                raw.parse::<i64>()
+                    .map(Into::into)
+                    .or_else(|_| raw.parse::<u64>().map(Into::into))
+                    .ok()
+            };
+            parsed
+                .map(Value::Number)
+                .ok_or_else(|| format!("expected a number, got '{}'", raw))
+        }
+        Value::String(_) => Ok(Value::String(raw.to_string())),
+        // Optional values: "none"/"null" clears, otherwise take JSON, falling back to a string
+        Value::Null => match raw.to_ascii_lowercase().as_str() {
+            "none" | "null" | "" => Ok(Value::Null),
+            _ => Ok(serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))),
+        },
+        Value::Array(_) | Value::Object(_) => {
+            serde_json::from_str(raw).map_err(|e| format!("expected JSON: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_and_set_path() {
+        let mut config = AppConfig::default();
+
+        assert_eq!(
+            config.get_path("ui.low_battery_threshold").unwrap(),
+            Value::from(config.ui.low_battery_threshold)
+        );
+
+        config.set_path("ui.low_battery_threshold", "15").unwrap();
+        assert_eq!(config.ui.low_battery_threshold, 15);
+
+        config.set_path("ui.show_notifications", "off").unwrap();
+        assert!(!config.ui.show_notifications);
+
+        config.set_path("ui.speech.rate", "-5").unwrap();
+        assert_eq!(config.ui.speech.rate, -5);
+
+        config.set_path("bluetooth.min_rssi", "-70").unwrap();
+        assert_eq!(config.bluetooth.min_rssi, Some(-70));
+        config.set_path("bluetooth.min_rssi", "none").unwrap();
+        assert_eq!(config.bluetooth.min_rssi, None);
+
+        config
+            .set_path("bluetooth.paired_device_name", "Kitchen Pods")
+            .unwrap();
+        assert_eq!(
+            config.bluetooth.paired_device_name.as_deref(),
+            Some("Kitchen Pods")
+        );
+    }
+
+    #[test]
+    fn test_set_path_rejects_bad_values() {
+        let mut config = AppConfig::default();
+        let before = config.clone();
+
+        assert!(config.set_path("ui.no_such_field", "1").is_err());
+        assert!(config.set_path("ui.show_notifications", "maybe").is_err());
+        assert!(config.set_path("ui.low_battery_threshold", "-5").is_err());
+        // Parses, but fails validation
+        assert!(config.set_path("ui.speech.rate", "50").is_err());
+        assert_eq!(config, before);
+
+        assert!(leaf_paths(&config).contains(&"ui.low_battery_threshold".to_string()));
+    }
+}
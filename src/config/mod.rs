@@ -1,6 +1,13 @@
 //! Settings management
 
 pub mod app_config;
+pub mod audit;
+pub mod devices;
+pub mod dot_path;
+pub mod overrides;
+pub mod parse_error;
+pub mod repair;
+pub mod secrets;
 // Replace the external test module import with the actual tests
 // #[cfg(test)]
 // mod tests;
@@ -8,8 +15,19 @@ pub mod app_config;
 pub use app_config::AppConfig;
 pub use app_config::Theme;
 pub use app_config::{
-    BluetoothConfig, ConfigError, LogLevel, SystemConfig, UiConfig, WindowPosition,
+    canonical_address, ActionRule, ActionsConfig, BackupFrequency, BackupScheduleConfig,
+    BatteryComponent, BatteryPalette, BluetoothConfig, ConfigError, DeviceAccessList,
+    DeviceFilterEntry, LogCategory, LogLevel, LowBatteryThresholds, ProxyConfig, ProxyMode,
+    QuietHoursConfig, RuleAction, RuleTrigger, ShortcutAction, SoundAlertEvent, SoundAlertsConfig,
+    SpeechConfig, SystemConfig, ThemeScheduleConfig, ThemeScheduleMode, TrayIconStyle, TrayMetric,
+    UiConfig, WindowBackdrop, WindowPosition, WindowSize,
 };
+pub use audit::{AuditEntry, AuditLog, ChangeSource};
+pub use devices::{DeviceColor, DeviceIcon, KnownDevice, KnownDevices};
+pub use overrides::{AppliedOverride, ConfigOverride, OverrideSource};
+pub use parse_error::ConfigParseError;
+pub use repair::RepairAction;
+pub use secrets::{CredentialStore, SecretRef, SecretStore};
 
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -89,10 +107,8 @@ impl ConfigManager {
         })?;
 
         // Parse the JSON
-        let config: AppConfig = serde_json::from_str(&contents).map_err(|e| {
-            error!("Failed to parse configuration file: {}", e);
-            ConfigError::SerializationError(e)
-        })?;
+        let config: AppConfig = parse_error::from_json_str(&contents, Some(&self.config_path))
+            .inspect_err(|e| error!("Failed to parse configuration file: {}", e))?;
 
         // Validate the configuration
         config.validate()?;
@@ -264,6 +280,12 @@ pub fn load_or_create_config() -> Result<AppConfig, ConfigError> {
 
     // Attempt to load the config file
     if let Err(e) = manager.load() {
+        // A file that doesn't parse is reported rather than overwritten with defaults,
+        // so the user can still fix it by hand
+        if matches!(e, ConfigError::ParseError(_)) {
+            return Err(e);
+        }
+
         // If the error is because the file doesn't exist, that's ok
         // We'll use defaults and save them below
         if !config_path.exists() {
@@ -275,7 +297,7 @@ pub fn load_or_create_config() -> Result<AppConfig, ConfigError> {
     }
 
     // Get the current configuration (either loaded or default)
-    let config = manager.get_config();
+    let mut config = manager.get_config();
 
     // Save the config to ensure the file exists
     if let Err(e) = manager.save() {
@@ -283,6 +305,11 @@ pub fn load_or_create_config() -> Result<AppConfig, ConfigError> {
         // We continue even if saving fails
     }
 
+    // Environment and command line overrides apply to this run only
+    for e in config.apply_run_overrides() {
+        error!("{}", e);
+    }
+
     Ok(config)
 }
 
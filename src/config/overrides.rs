@@ -0,0 +1,347 @@
+//! Overrides of the loaded configuration for the current run
+//!
+//! A setting can be changed for one run without touching `settings.json`: through a
+//! `RUSTPODS_<KEY>` environment variable, where the key is the dot-path in upper case with
+//! dots as underscores (`RUSTPODS_UI_LOW_BATTERY_THRESHOLD=15`), or through a repeated
+//! `--set section.key=value` flag. The command line beats the environment, which beats the
+//! settings file, which beats the defaults.
+//!
+//! Overridden values are used like any other setting, but saving writes the value each
+//! override replaced, unless the setting has been changed since. Safe mode uses the same
+//! mechanism to put a few settings back to their defaults for one run.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde_json::Value;
+
+use super::dot_path::{leaf_paths, lookup_mut};
+use super::{AppConfig, ConfigError};
+
+/// Prefix of environment variables that override settings
+pub const ENV_PREFIX: &str = "RUSTPODS_";
+
+/// Overrides given on the command line, installed once at startup
+static COMMAND_LINE: OnceLock<Vec<ConfigOverride>> = OnceLock::new();
+
+/// Where an override came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideSource {
+    /// A `RUSTPODS_*` environment variable, by name
+    Environment(String),
+    /// A `--set` flag
+    CommandLine,
+    /// Safe mode, which puts the setting back to its default
+    SafeMode,
+}
+
+impl fmt::Display for OverrideSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverrideSource::Environment(var) => write!(f, "environment variable {}", var),
+            OverrideSource::CommandLine => write!(f, "--set"),
+            OverrideSource::SafeMode => write!(f, "safe mode"),
+        }
+    }
+}
+
+/// A setting to override, before it is checked against the configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigOverride {
+    /// Dot-path of the setting
+    pub path: String,
+    /// Value as given, coerced like `rustpods config set`
+    pub value: String,
+    /// Where the override came from
+    pub source: OverrideSource,
+}
+
+impl ConfigOverride {
+    /// Parse the argument of `--set`, `section.key=value`
+    ///
+    /// Only the shape is checked here; unknown keys and bad values are reported when the
+    /// override is applied.
+    pub fn parse_set(arg: &str) -> Result<Self, String> {
+        let (path, value) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("Expected section.key=value, got '{}'", arg))?;
+        let path = path.trim();
+        if path.is_empty() || path.split('.').any(str::is_empty) {
+            return Err(format!("Expected section.key=value, got '{}'", arg));
+        }
+        Ok(Self {
+            path: path.to_string(),
+            value: value.to_string(),
+            source: OverrideSource::CommandLine,
+        })
+    }
+}
+
+/// An override in effect, with the value it replaced
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppliedOverride {
+    /// Dot-path of the setting
+    pub path: String,
+    /// Where the override came from
+    pub source: OverrideSource,
+    /// Value before the override, written back on save
+    pub original: Value,
+    /// Value the override set
+    pub value: Value,
+}
+
+/// Install the `--set` overrides for this run
+///
+/// Only the first call has an effect.
+pub fn set_command_line(overrides: Vec<ConfigOverride>) {
+    if COMMAND_LINE.set(overrides).is_err() {
+        log::warn!("Command line configuration overrides were already installed");
+    }
+}
+
+/// Overrides from `vars` that name a setting of `config`, sorted by variable name
+///
+/// Variables that don't match a setting, like `RUSTPODS_DEVICE`, are not overrides and are
+/// ignored.
+pub fn from_env<I>(config: &AppConfig, vars: I) -> Vec<ConfigOverride>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let paths = leaf_paths(config);
+    let mut overrides: Vec<ConfigOverride> = vars
+        .into_iter()
+        .filter_map(|(var, value)| {
+            let key = var.strip_prefix(ENV_PREFIX)?;
+            let path = paths
+                .iter()
+                .find(|path| path.replace('.', "_").eq_ignore_ascii_case(key))?;
+            Some(ConfigOverride {
+                path: path.clone(),
+                value,
+                source: OverrideSource::Environment(var),
+            })
+        })
+        .collect();
+    overrides.sort_by(|a, b| a.source.to_string().cmp(&b.source.to_string()));
+    overrides
+}
+
+/// Overrides for this run in the order they apply: environment first, command line last
+pub fn active(config: &AppConfig) -> Vec<ConfigOverride> {
+    let mut overrides = from_env(config, std::env::vars());
+    overrides.extend(COMMAND_LINE.get().into_iter().flatten().cloned());
+    overrides
+}
+
+impl AppConfig {
+    /// Apply the environment and command line overrides for this run
+    ///
+    /// Overrides that don't fit are skipped and returned, so the caller can report them.
+    pub fn apply_run_overrides(&mut self) -> Vec<ConfigError> {
+        let overrides = active(self);
+        let errors = self.apply_overrides(&overrides);
+        if crate::safe_mode::is_enabled() {
+            self.apply_safe_mode();
+        }
+        errors
+    }
+
+    /// Put the settings safe mode leaves out back to their defaults, as overrides
+    pub fn apply_safe_mode(&mut self) {
+        let (Ok(mut root), Ok(mut defaults)) = (
+            serde_json::to_value(&*self),
+            serde_json::to_value(AppConfig::default()),
+        ) else {
+            return;
+        };
+        let mut changed = Vec::new();
+        for path in crate::safe_mode::SAFE_MODE_DEFAULTS {
+            let (Ok(slot), Ok(default)) =
+                (lookup_mut(&mut root, path), lookup_mut(&mut defaults, path))
+            else {
+                continue;
+            };
+            if slot != default {
+                changed.push((path.to_string(), slot.clone(), default.clone()));
+                *slot = default.clone();
+            }
+        }
+
+        let mut updated: AppConfig = match serde_json::from_value(root) {
+            Ok(updated) => updated,
+            Err(e) => {
+                log::warn!("Couldn't apply safe mode to the settings: {}", e);
+                return;
+            }
+        };
+        updated.settings_path = std::mem::take(&mut self.settings_path);
+        updated.overrides = std::mem::take(&mut self.overrides);
+        *self = updated;
+
+        for (path, original, value) in changed {
+            log::info!("{} overridden by safe mode", path);
+            // A setting already overridden keeps the file's value as the original
+            match self.overrides.iter_mut().find(|o| o.path == path) {
+                Some(applied) => {
+                    applied.source = OverrideSource::SafeMode;
+                    applied.value = value;
+                }
+                None => self.overrides.push(AppliedOverride {
+                    path,
+                    source: OverrideSource::SafeMode,
+                    original,
+                    value,
+                }),
+            }
+        }
+    }
+
+    /// Apply `overrides` in order, so a later one for the same setting wins
+    ///
+    /// Each override is checked like `rustpods config set`; one that fails is skipped and
+    /// its error returned.
+    pub fn apply_overrides(&mut self, overrides: &[ConfigOverride]) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        for entry in overrides {
+            let original = match self.get_path(&entry.path) {
+                Ok(value) => value,
+                Err(e) => {
+                    errors.push(override_error(entry, e));
+                    continue;
+                }
+            };
+            if let Err(e) = self.set_path(&entry.path, &entry.value) {
+                errors.push(override_error(entry, e));
+                continue;
+            }
+            let value = self.get_path(&entry.path).unwrap_or(Value::Null);
+            log::info!("{} overridden by {}", entry.path, entry.source);
+
+            // A second override of the same setting keeps the file's value as the original
+            match self.overrides.iter_mut().find(|o| o.path == entry.path) {
+                Some(applied) => {
+                    applied.source = entry.source.clone();
+                    applied.value = value;
+                }
+                None => self.overrides.push(AppliedOverride {
+                    path: entry.path.clone(),
+                    source: entry.source.clone(),
+                    original,
+                    value,
+                }),
+            }
+        }
+        errors
+    }
+
+    /// Overrides in effect
+    pub fn applied_overrides(&self) -> &[AppliedOverride] {
+        &self.overrides
+    }
+
+    /// The configuration as it is saved: overridden settings that haven't been changed
+    /// since get their original value back
+    pub fn without_overrides(&self) -> AppConfig {
+        if self.overrides.is_empty() {
+            return self.clone();
+        }
+        let persisted = serde_json::to_value(self).ok().and_then(|mut root| {
+            for applied in &self.overrides {
+                if let Ok(slot) = lookup_mut(&mut root, &applied.path) {
+                    if *slot == applied.value {
+                        *slot = applied.original.clone();
+                    }
+                }
+            }
+            serde_json::from_value::<AppConfig>(root).ok()
+        });
+        match persisted {
+            Some(mut config) => {
+                config.settings_path = self.settings_path.clone();
+                config
+            }
+            None => self.clone(),
+        }
+    }
+}
+
+fn override_error(entry: &ConfigOverride, error: ConfigError) -> ConfigError {
+    ConfigError::InvalidConfig(format!(
+        "Ignoring {} override of '{}': {}",
+        entry.source, entry.path, error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_precedence_and_persistence() {
+        let mut config = AppConfig::default();
+        config.ui.low_battery_threshold = 20;
+
+        let vars = vec![
+            (
+                "RUSTPODS_UI_LOW_BATTERY_THRESHOLD".to_string(),
+                "15".to_string(),
+            ),
+            (
+                "RUSTPODS_UI_SHOW_NOTIFICATIONS".to_string(),
+                "off".to_string(),
+            ),
+            ("RUSTPODS_DEVICE".to_string(), "AirPods".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ];
+        let mut overrides = from_env(&config, vars);
+        assert_eq!(overrides.len(), 2);
+        overrides.push(ConfigOverride::parse_set("ui.low_battery_threshold=10").unwrap());
+        overrides.push(ConfigOverride::parse_set("ui.no_such_key=1").unwrap());
+
+        let errors = config.apply_overrides(&overrides);
+        assert_eq!(errors.len(), 1);
+        // The command line wins over the environment
+        assert_eq!(config.ui.low_battery_threshold, 10);
+        assert!(!config.ui.show_notifications);
+
+        // Saving writes the file's values back, except for settings changed since
+        config.ui.show_notifications = true;
+        let persisted = config.without_overrides();
+        assert_eq!(persisted.ui.low_battery_threshold, 20);
+        assert!(persisted.ui.show_notifications);
+        assert!(persisted.applied_overrides().is_empty());
+
+        assert!(ConfigOverride::parse_set("ui.theme").is_err());
+        assert!(ConfigOverride::parse_set("ui..theme=dark").is_err());
+    }
+
+    #[test]
+    fn test_safe_mode_keeps_settings_on_save() {
+        let mut config = AppConfig::default();
+        config.ui.font_family = Some("Comic Sans MS".to_string());
+        config.ui.font_scale = 1.5;
+        config.ui.low_battery_threshold = 15;
+        config.actions.rules.push(crate::config::ActionRule {
+            name: "Beep".to_string(),
+            enabled: true,
+            trigger: crate::config::RuleTrigger::DeviceDisconnected,
+            action: crate::config::RuleAction::PlaySound { path: None },
+        });
+
+        config.apply_safe_mode();
+        assert_eq!(config.ui.font_family, None);
+        assert_eq!(config.ui.font_scale, 1.0);
+        assert!(config.actions.rules.is_empty());
+        // Settings safe mode doesn't cover are left alone
+        assert_eq!(config.ui.low_battery_threshold, 15);
+        assert!(config
+            .applied_overrides()
+            .iter()
+            .all(|o| o.source == OverrideSource::SafeMode));
+
+        let persisted = config.without_overrides();
+        assert_eq!(persisted.ui.font_family.as_deref(), Some("Comic Sans MS"));
+        assert_eq!(persisted.ui.font_scale, 1.5);
+        assert_eq!(persisted.actions.rules.len(), 1);
+    }
+}
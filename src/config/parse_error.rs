@@ -0,0 +1,268 @@
+//! Readable reports for settings files that fail to parse
+//!
+//! serde_json only says what went wrong and at which line and column. A report adds the
+//! dot-path of the setting (`ui.theme`, as used by `rustpods config get`), the type that
+//! was expected, the value that was found and the offending line itself, so a hand-edited
+//! `settings.json` can be fixed without guessing.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use super::ConfigError;
+
+/// Where and why a settings file failed to parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigParseError {
+    /// File that was being read, if any
+    pub file: Option<PathBuf>,
+    /// Dot-path of the setting at fault, empty for the top level
+    pub path: String,
+    /// Line of the error, 1-based
+    pub line: usize,
+    /// Column of the error, 1-based
+    pub column: usize,
+    /// The type or values serde expected there
+    pub expected: Option<String>,
+    /// The value found instead
+    pub found: Option<String>,
+    /// serde's message without the position
+    pub message: String,
+    /// Text of the offending line
+    pub source_line: Option<String>,
+    /// Whether the file isn't valid JSON at all, rather than holding a wrong value
+    pub syntax: bool,
+}
+
+impl ConfigParseError {
+    /// Build a report from a serde_json error and the text it was parsing
+    pub fn from_json(contents: &str, error: &serde_json::Error, file: Option<&Path>) -> Self {
+        let line = error.line();
+        let column = error.column();
+        let full = error.to_string();
+        let suffix = format!(" at line {} column {}", line, column);
+        let message = full.strip_suffix(&suffix).unwrap_or(&full).to_string();
+        let (expected, found) = split_message(&message);
+        let syntax = matches!(
+            error.classify(),
+            serde_json::error::Category::Syntax | serde_json::error::Category::Eof
+        );
+
+        Self {
+            file: file.map(Path::to_path_buf),
+            path: if syntax {
+                String::new()
+            } else {
+                path_at(contents, byte_offset(contents, line, column))
+            },
+            line,
+            column,
+            expected,
+            found,
+            message,
+            source_line: line
+                .checked_sub(1)
+                .and_then(|index| contents.lines().nth(index))
+                .map(str::to_string),
+            syntax,
+        }
+    }
+
+    /// Where the error is, e.g. `ui.theme (line 12, column 20)`
+    pub fn location(&self) -> String {
+        let position = format!("line {}, column {}", self.line, self.column);
+        if self.path.is_empty() {
+            position
+        } else {
+            format!("{} ({})", self.path, position)
+        }
+    }
+
+    /// The offending line with a caret under the column, for terminal output
+    pub fn snippet(&self) -> Option<String> {
+        let source = self.source_line.as_ref()?;
+        let gutter = format!("{} | ", self.line);
+        let caret = source
+            .chars()
+            .take(self.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        Some(format!(
+            "{}{}\n{}{}^",
+            gutter,
+            source,
+            " ".repeat(gutter.len()),
+            caret
+        ))
+    }
+}
+
+impl fmt::Display for ConfigParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        if self.syntax {
+            return write!(f, "{} at {}", self.message, self.location());
+        }
+        write!(f, "{}: ", self.location())?;
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => write!(f, "expected {}, found {}", expected, found),
+            (Some(expected), None) => write!(f, "{}, expected {}", self.message, expected),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Parse settings JSON, reporting failures with their path and line
+pub fn from_json_str<T: DeserializeOwned>(
+    contents: &str,
+    file: Option<&Path>,
+) -> Result<T, ConfigError> {
+    serde_json::from_str(contents).map_err(|e| {
+        ConfigError::ParseError(Box::new(ConfigParseError::from_json(contents, &e, file)))
+    })
+}
+
+/// Pull the expected type and found value out of a serde message
+///
+/// Handles `invalid type: string "x", expected u8`, `invalid value: integer `300`,
+/// expected ...` and `unknown variant `x`, expected one of ...`.
+fn split_message(message: &str) -> (Option<String>, Option<String>) {
+    let Some((head, expected)) = message.split_once(", expected ") else {
+        return (None, None);
+    };
+    let found = ["invalid type: ", "invalid value: ", "invalid length "]
+        .iter()
+        .find_map(|prefix| head.strip_prefix(prefix))
+        .or_else(|| head.strip_prefix("unknown variant "))
+        .map(str::to_string);
+    (Some(expected.to_string()), found)
+}
+
+/// Byte offset of a 1-based line and column, clamped to the text
+fn byte_offset(contents: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = contents
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    let mut offset = (line_start + column).min(contents.len());
+    while !contents.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+/// A container open at the error position
+enum Frame {
+    /// An object, with the key whose value is being read
+    Object(Option<String>),
+    /// An array, with the index of the element being read
+    Array(usize),
+}
+
+/// Dot-path of the value being read at `offset`
+///
+/// Walks the JSON text up to the offset, keeping a stack of the open objects and arrays.
+/// Array elements are written as `[index]`.
+fn path_at(contents: &str, offset: usize) -> String {
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut chars = contents[..offset].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => stack.push(Frame::Object(None)),
+            '[' => stack.push(Frame::Array(0)),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(Frame::Object(key)) => *key = None,
+                Some(Frame::Array(index)) => *index += 1,
+                None => {}
+            },
+            '"' => {
+                let mut text = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                text.push(escaped);
+                            }
+                        }
+                        c => text.push(c),
+                    }
+                }
+                if let Some(Frame::Object(key @ None)) = stack.last_mut() {
+                    *key = Some(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut path = String::new();
+    for frame in &stack {
+        match frame {
+            Frame::Object(Some(key)) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Frame::Object(None) => {}
+            Frame::Array(index) => path.push_str(&format!("[{}]", index)),
+        }
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn parse(contents: &str) -> ConfigParseError {
+        let error = serde_json::from_str::<AppConfig>(contents).unwrap_err();
+        ConfigParseError::from_json(contents, &error, None)
+    }
+
+    #[test]
+    fn test_wrong_type_reports_path_and_value() {
+        let report = parse("{\n  \"ui\": {\n    \"low_battery_threshold\": \"low\"\n  }\n}");
+
+        assert!(!report.syntax);
+        assert_eq!(report.path, "ui.low_battery_threshold");
+        assert_eq!(report.line, 3);
+        assert_eq!(report.expected.as_deref(), Some("u8"));
+        assert_eq!(report.found.as_deref(), Some("string \"low\""));
+        assert_eq!(
+            report.source_line.as_deref(),
+            Some("    \"low_battery_threshold\": \"low\"")
+        );
+        assert!(report
+            .to_string()
+            .starts_with("ui.low_battery_threshold (line 3"));
+    }
+
+    #[test]
+    fn test_syntax_error_has_no_path() {
+        let report = parse("{\n  \"ui\": {,\n}");
+
+        assert!(report.syntax);
+        assert!(report.path.is_empty());
+        assert_eq!(report.line, 2);
+        assert!(report.snippet().unwrap().contains("2 |   \"ui\": {,"));
+    }
+
+    #[test]
+    fn test_path_at_tracks_arrays_and_keys() {
+        let text = r#"{"a": {"b": [1, {"c": true"#;
+        assert_eq!(path_at(text, text.len()), "a.b[1].c");
+
+        let text = r#"{"a": 1, "#;
+        assert_eq!(path_at(text, text.len()), "");
+    }
+}
@@ -0,0 +1,259 @@
+//! Repair of a settings file that fails to load
+//!
+//! When `settings.json` doesn't parse or validate at launch, RustPods runs on defaults but
+//! leaves the file alone: it is protected from saves until the user picks a repair, so a
+//! hand edit with one typo doesn't silently become a file full of defaults. The repairs are
+//! restoring the backup taken before the last successful save, dropping only the sections
+//! that fail so they fall back to defaults, or starting over with a copy of the broken file
+//! kept aside.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use super::parse_error::from_json_str;
+use super::{AppConfig, ConfigError};
+
+/// Settings files that failed to load and must not be overwritten until repaired
+static PROTECTED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// What to do about a settings file that fails to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairAction {
+    /// Open the file in the default editor to fix it by hand
+    OpenFile,
+    /// Replace the file with the backup taken before the last save
+    RestoreBackup,
+    /// Reset only the sections that fail, keeping the rest
+    ResetInvalidSections,
+    /// Start over with default settings
+    ResetAll,
+}
+
+impl RepairAction {
+    /// Every repair, in the order the repair dialog offers them
+    pub const ALL: [RepairAction; 4] = [
+        RepairAction::OpenFile,
+        RepairAction::RestoreBackup,
+        RepairAction::ResetInvalidSections,
+        RepairAction::ResetAll,
+    ];
+
+    /// Button label in the repair dialog
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepairAction::OpenFile => "Open file",
+            RepairAction::RestoreBackup => "Restore backup",
+            RepairAction::ResetInvalidSections => "Reset invalid section",
+            RepairAction::ResetAll => "Reset everything",
+        }
+    }
+}
+
+/// Where the copy of a settings file taken before each save is kept
+pub fn backup_path(settings_path: &Path) -> PathBuf {
+    with_suffix(settings_path, "bak")
+}
+
+/// Where a broken settings file is moved when everything is reset
+pub fn broken_path(settings_path: &Path) -> PathBuf {
+    with_suffix(settings_path, "broken")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Keep saves from overwriting `path` until it has been repaired
+pub fn protect(path: &Path) {
+    if let Ok(mut protected) = PROTECTED.lock() {
+        if !protected.iter().any(|p| p == path) {
+            log::warn!(
+                "Settings file {} failed to load; it won't be saved over until repaired",
+                path.display()
+            );
+            protected.push(path.to_path_buf());
+        }
+    }
+}
+
+/// Allow saves to `path` again
+pub fn unprotect(path: &Path) {
+    if let Ok(mut protected) = PROTECTED.lock() {
+        protected.retain(|p| p != path);
+    }
+}
+
+/// Whether `path` failed to load and is waiting for a repair
+pub fn is_protected(path: &Path) -> bool {
+    PROTECTED
+        .lock()
+        .map(|protected| protected.iter().any(|p| p == path))
+        .unwrap_or(false)
+}
+
+/// Copy a settings file that still loads to its backup before it is overwritten
+pub(crate) fn back_up(settings_path: &Path) {
+    if !settings_path.exists() || AppConfig::load_from_path(settings_path).is_err() {
+        return;
+    }
+    if let Err(e) = std::fs::copy(settings_path, backup_path(settings_path)) {
+        log::warn!("Failed to back up {}: {}", settings_path.display(), e);
+    }
+}
+
+/// Replace the settings file with its backup
+pub fn restore_backup(settings_path: &Path) -> Result<AppConfig, ConfigError> {
+    let backup = backup_path(settings_path);
+    if !backup.exists() {
+        return Err(ConfigError::FileNotFound(backup));
+    }
+    let mut config = AppConfig::load_from_path(&backup)?;
+    config.settings_path = settings_path.to_path_buf();
+    save_repaired(&config)?;
+    log::info!("Restored settings from {}", backup.display());
+    Ok(config)
+}
+
+/// Drop the sections that fail to load, so they fall back to defaults
+///
+/// Returns the repaired settings and the names of the sections that were reset. Fails if
+/// the file isn't valid JSON at all, or the error isn't inside a section.
+pub fn reset_invalid_sections(
+    settings_path: &Path,
+) -> Result<(AppConfig, Vec<String>), ConfigError> {
+    let contents = std::fs::read_to_string(settings_path)?;
+    let mut root: Value = from_json_str(&contents, Some(settings_path))?;
+    let mut reset = Vec::new();
+
+    loop {
+        // Reparse from text, so errors come with their path
+        let text = serde_json::to_string_pretty(&root)?;
+        let error = match from_json_str::<AppConfig>(&text, Some(settings_path)) {
+            Ok(config) => match config.validate() {
+                Ok(()) => {
+                    let config = AppConfig {
+                        settings_path: settings_path.to_path_buf(),
+                        ..config
+                    };
+                    save_repaired(&config)?;
+                    log::info!("Reset settings sections: {}", reset.join(", "));
+                    return Ok((config, reset));
+                }
+                Err(e) => e,
+            },
+            Err(e) => e,
+        };
+
+        let section = failing_section(&error)
+            .filter(|section| !reset.contains(section))
+            .filter(|section| root.get(section).is_some_and(Value::is_object));
+        match (section, root.as_object_mut()) {
+            (Some(section), Some(map)) => {
+                map.remove(&section);
+                reset.push(section);
+            }
+            _ => return Err(error),
+        }
+    }
+}
+
+/// Move the broken settings file aside and start over with defaults
+pub fn reset_all(settings_path: &Path) -> Result<AppConfig, ConfigError> {
+    if settings_path.exists() {
+        let broken = broken_path(settings_path);
+        std::fs::rename(settings_path, &broken)?;
+        log::info!("Moved broken settings to {}", broken.display());
+    }
+    let config = AppConfig {
+        settings_path: settings_path.to_path_buf(),
+        ..AppConfig::default()
+    };
+    save_repaired(&config)?;
+    Ok(config)
+}
+
+/// Save a repaired configuration over the protected file
+fn save_repaired(config: &AppConfig) -> Result<(), ConfigError> {
+    unprotect(&config.settings_path);
+    config.save()
+}
+
+/// Top-level section a load error is in, e.g. `ui` for `ui.theme`
+fn failing_section(error: &ConfigError) -> Option<String> {
+    let path = match error {
+        ConfigError::ParseError(report) if !report.syntax => report.path.as_str(),
+        ConfigError::ValidationFailed(field, _) => field.as_str(),
+        _ => return None,
+    };
+    path.split(['.', '['])
+        .next()
+        .filter(|section| !section.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_reset_invalid_sections_keeps_the_rest() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        std::fs::write(
+            &path,
+            r#"{"ui": {"low_battery_threshold": "low"}, "bluetooth": {"min_rssi": -70}}"#,
+        )
+        .unwrap();
+        protect(&path);
+
+        let (config, reset) = reset_invalid_sections(&path).unwrap();
+        assert_eq!(reset, vec!["ui".to_string()]);
+        assert_eq!(config.bluetooth.min_rssi, Some(-70));
+        assert_eq!(
+            config.ui.low_battery_threshold,
+            AppConfig::default().ui.low_battery_threshold
+        );
+        assert!(!is_protected(&path));
+        assert!(AppConfig::load_from_path(&path).is_ok());
+    }
+
+    #[test]
+    fn test_protected_file_is_backed_up_and_restored() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let mut config = AppConfig {
+            settings_path: path.clone(),
+            ..AppConfig::default()
+        };
+        config.ui.low_battery_threshold = 15;
+        config.save().unwrap();
+
+        // The next save backs up the file that loaded
+        config.save().unwrap();
+        assert!(backup_path(&path).exists());
+
+        std::fs::write(&path, "{ not json").unwrap();
+        protect(&path);
+        assert!(config.save().is_err());
+        assert!(reset_invalid_sections(&path).is_err());
+
+        let restored = restore_backup(&path).unwrap();
+        assert_eq!(restored.ui.low_battery_threshold, 15);
+
+        reset_all(&path).unwrap();
+        assert!(broken_path(&path).exists());
+        assert_eq!(
+            AppConfig::load_from_path(&path)
+                .unwrap()
+                .ui
+                .low_battery_threshold,
+            AppConfig::default().ui.low_battery_threshold
+        );
+    }
+}
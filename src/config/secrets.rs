@@ -0,0 +1,141 @@
+//! References to secrets kept outside the settings file
+//!
+//! Passwords and tokens for integrations are not written to `settings.json`. The settings
+//! hold a [`SecretRef`] naming the secret, and the value lives in a [`SecretStore`]: the
+//! Windows Credential Manager in the application, an in-memory store in tests.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::system::credentials::{self, CredentialError};
+
+/// Where secret values are kept
+pub trait SecretStore {
+    /// Read the value stored under `name`
+    fn get(&self, name: &str) -> Result<String, CredentialError>;
+
+    /// Store `value` under `name`, replacing any earlier value
+    fn set(&self, name: &str, value: &str) -> Result<(), CredentialError>;
+
+    /// Remove the value stored under `name`
+    fn delete(&self, name: &str) -> Result<(), CredentialError>;
+}
+
+/// Secrets in the Windows Credential Manager
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CredentialStore;
+
+impl SecretStore for CredentialStore {
+    fn get(&self, name: &str) -> Result<String, CredentialError> {
+        credentials::read_credential(name)
+    }
+
+    fn set(&self, name: &str, value: &str) -> Result<(), CredentialError> {
+        credentials::write_credential(name, value)
+    }
+
+    fn delete(&self, name: &str) -> Result<(), CredentialError> {
+        credentials::delete_credential(name)
+    }
+}
+
+/// A secret named in the settings, e.g. `"mqtt/password"`
+///
+/// Only the name is serialized; formatting never shows the value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SecretRef(String);
+
+impl SecretRef {
+    /// Refer to the secret stored under `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self(name.into())
+    }
+
+    /// Store `value` under `name` and return the reference to keep in the settings
+    pub fn store(
+        name: impl Into<String>,
+        value: &str,
+        store: &dyn SecretStore,
+    ) -> Result<Self, CredentialError> {
+        let secret = Self::new(name);
+        store.set(&secret.0, value)?;
+        Ok(secret)
+    }
+
+    /// Name the secret is stored under
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Look up the value of the secret
+    pub fn resolve(&self, store: &dyn SecretStore) -> Result<String, CredentialError> {
+        store.get(&self.0)
+    }
+
+    /// Remove the secret from the store
+    pub fn forget(&self, store: &dyn SecretStore) -> Result<(), CredentialError> {
+        store.delete(&self.0)
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<secret {}>", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// Secrets held in memory
+    #[derive(Default)]
+    struct MemoryStore(Mutex<HashMap<String, String>>);
+
+    impl SecretStore for MemoryStore {
+        fn get(&self, name: &str) -> Result<String, CredentialError> {
+            self.0
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| CredentialError::NotFound(name.to_string()))
+        }
+
+        fn set(&self, name: &str, value: &str) -> Result<(), CredentialError> {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        }
+
+        fn delete(&self, name: &str) -> Result<(), CredentialError> {
+            self.0.lock().unwrap().remove(name);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_settings_hold_only_the_reference() {
+        let store = MemoryStore::default();
+        let secret = SecretRef::store("mqtt/password", "hunter2", &store).unwrap();
+
+        let json = serde_json::to_string(&secret).unwrap();
+        assert_eq!(json, "\"mqtt/password\"");
+        assert!(!secret.to_string().contains("hunter2"));
+
+        let loaded: SecretRef = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.resolve(&store).unwrap(), "hunter2");
+
+        loaded.forget(&store).unwrap();
+        assert!(matches!(
+            loaded.resolve(&store),
+            Err(CredentialError::NotFound(_))
+        ));
+    }
+}
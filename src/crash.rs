@@ -0,0 +1,362 @@
+//! Crash handling for RustPods
+//!
+//! Installs a panic hook that writes a crash report (panic message, backtrace, recent
+//! log lines and a summary of the application state) into the diagnostics directory.
+//! When the UI thread panics the user is offered to open the report, and the
+//! application can relaunch itself so the tray icon comes back.
+//!
+//! The panic hook reports its own progress on stderr rather than through `log`: the panic
+//! may have happened while the logger's lock was held.
+
+use std::any::Any;
+use std::ffi::OsString;
+use std::io::{self, Write};
+use std::panic::Location;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+/// Number of recent log lines included in a crash report
+pub const CRASH_REPORT_LOG_LINES: usize = 100;
+
+/// Set on relaunched processes so a crash loop is not restarted forever
+const RELAUNCH_ENV_VAR: &str = "RUSTPODS_CRASH_RELAUNCH";
+
+/// A relaunched process must run at least this long before it is relaunched again
+const MIN_UPTIME_BEFORE_RELAUNCH: Duration = Duration::from_secs(60);
+
+/// Latest summary of the application state, included in crash reports
+static STATE_SUMMARY: Mutex<String> = Mutex::new(String::new());
+
+/// Guards against re-entering the handler when a panic happens while handling one
+static HANDLING_CRASH: AtomicBool = AtomicBool::new(false);
+
+/// When the crash handler was installed
+static STARTED_AT: OnceLock<Instant> = OnceLock::new();
+
+/// The thread that installed the crash handler, which goes on to run the UI
+static UI_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// How the crash handler reacts to a fatal panic
+#[derive(Debug, Clone, Default)]
+pub struct CrashHandlerOptions {
+    /// Show a message box offering to open the report
+    pub show_dialog: bool,
+    /// Relaunch the application after the report is written
+    pub relaunch: bool,
+}
+
+/// Details captured from a panic
+#[derive(Debug, Clone)]
+pub struct CrashDetails {
+    /// Panic message
+    pub message: String,
+    /// Source location of the panic, if known
+    pub location: Option<String>,
+    /// Name of the thread that panicked
+    pub thread: String,
+    /// Time since the crash handler was installed
+    pub uptime: Duration,
+    /// Summary of the application state at the time of the crash
+    pub state_summary: String,
+    /// Captured backtrace
+    pub backtrace: String,
+    /// Most recent log lines, oldest first
+    pub recent_log: Vec<String>,
+}
+
+/// Install the panic hook
+///
+/// Call from the thread that runs the UI; only its panics take the application down.
+/// The previous hook still runs afterwards so panics keep reaching stderr.
+pub fn install(options: CrashHandlerOptions) {
+    STARTED_AT.get_or_init(Instant::now);
+    UI_THREAD.get_or_init(|| std::thread::current().id());
+    let previous_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        if !HANDLING_CRASH.swap(true, Ordering::SeqCst) {
+            handle_panic(&options, info.payload(), info.location());
+            HANDLING_CRASH.store(false, Ordering::SeqCst);
+        }
+        previous_hook(info);
+    }));
+}
+
+/// Record a short summary of the application state for crash reports
+pub fn set_state_summary(summary: impl Into<String>) {
+    if let Ok(mut state) = STATE_SUMMARY.lock() {
+        *state = summary.into();
+    }
+}
+
+/// Directory crash reports are written to
+pub fn crash_report_dir() -> PathBuf {
+    crate::diagnostics::diagnostics_dir()
+}
+
+fn handle_panic(
+    options: &CrashHandlerOptions,
+    payload: &(dyn Any + Send),
+    location: Option<&Location<'_>>,
+) {
+    let current = std::thread::current();
+    let thread = current.name().unwrap_or("<unnamed>").to_string();
+    let details = CrashDetails {
+        message: panic_message(payload),
+        location: location.map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        thread,
+        uptime: STARTED_AT.get().map(Instant::elapsed).unwrap_or_default(),
+        state_summary: STATE_SUMMARY
+            .try_lock()
+            .map(|state| state.clone())
+            .unwrap_or_default(),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        recent_log: crate::logging::recent_log_lines(CRASH_REPORT_LOG_LINES),
+    };
+
+    let report_path = match write_crash_report(&crash_report_dir(), &details) {
+        Ok(path) => {
+            eprintln!("Crash report written to {}", path.display());
+            Some(path)
+        }
+        Err(e) => {
+            eprintln!("Failed to write crash report: {}", e);
+            None
+        }
+    };
+
+    // Panics on worker threads don't take the application down
+    if UI_THREAD.get() != Some(&current.id()) {
+        return;
+    }
+
+    // A GPU that can't draw the window gets a software rendered run, not a crash dialog
+    if crate::ui::render_backend::should_fall_back_after_panic(
+        &details.message,
+        details.location.as_deref(),
+    ) {
+        eprintln!(
+            "Hardware rendering failed, restarting with software rendering: {}",
+            details.message
+        );
+        match crate::ui::render_backend::fall_back() {
+            Ok(()) => return,
+            Err(e) => eprintln!("Failed to restart with software rendering: {}", e),
+        }
+    }
+
+    if options.show_dialog {
+        if let Some(path) = &report_path {
+            if platform::ask_to_open_report(path) {
+                if let Err(e) = open_report(path) {
+                    eprintln!("Failed to open crash report: {}", e);
+                }
+            }
+        }
+    }
+
+    let relaunched = std::env::var_os(RELAUNCH_ENV_VAR).is_some();
+    if options.relaunch && should_relaunch(relaunched, details.uptime) {
+        if let Err(e) = relaunch() {
+            eprintln!("Failed to relaunch after crash: {}", e);
+        }
+    }
+}
+
+/// Extract the message from a panic payload
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".to_string()
+    }
+}
+
+/// Whether to relaunch, avoiding a restart loop when a relaunched process crashes early
+pub fn should_relaunch(relaunched: bool, uptime: Duration) -> bool {
+    !relaunched || uptime >= MIN_UPTIME_BEFORE_RELAUNCH
+}
+
+/// Render a crash report as text
+pub fn format_crash_report(details: &CrashDetails) -> String {
+    let mut report = String::new();
+    report.push_str("=== RustPods Crash Report ===\n");
+    report.push_str(&format!(
+        "Generated: {}\n",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    report.push_str(&format!("Version: {}\n", env!("CARGO_PKG_VERSION")));
+    report.push_str(&format!(
+        "Platform: {} ({})\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    report.push_str(&format!("Thread: {}\n", details.thread));
+    report.push_str(&format!("Uptime: {}s\n\n", details.uptime.as_secs()));
+
+    report.push_str("=== Panic ===\n");
+    report.push_str(&format!("{}\n", details.message));
+    if let Some(location) = &details.location {
+        report.push_str(&format!("Location: {}\n", location));
+    }
+
+    report.push_str("\n=== Application State ===\n");
+    if details.state_summary.is_empty() {
+        report.push_str("(not available)\n");
+    } else {
+        report.push_str(&format!("{}\n", details.state_summary));
+    }
+
+    report.push_str("\n=== Backtrace ===\n");
+    report.push_str(&format!("{}\n", details.backtrace));
+
+    report.push_str(&format!(
+        "\n=== Recent Log ({} lines) ===\n",
+        details.recent_log.len()
+    ));
+    for line in &details.recent_log {
+        report.push_str(&format!("{}\n", line));
+    }
+    report
+}
+
+/// Write a crash report into `dir`, returning its path
+pub fn write_crash_report(dir: &Path, details: &CrashDetails) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let file_name = format!(
+        "rustpods_crash_{}.txt",
+        chrono::Local::now().format("%Y%m%d_%H%M%S")
+    );
+    let path = dir.join(file_name);
+    let mut file = std::fs::File::create(&path)?;
+    file.write_all(format_crash_report(details).as_bytes())?;
+    Ok(path)
+}
+
+fn open_report(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer").arg(path).spawn()?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Open {} manually", path.display()),
+        ))
+    }
+}
+
+fn relaunch() -> io::Result<()> {
+    relaunch_with(std::env::args_os().skip(1), RELAUNCH_ENV_VAR)?;
+    eprintln!("Relaunched RustPods after crash");
+    Ok(())
+}
+
+/// Start RustPods again with `args`, marking the new process with `env_var`
+pub fn relaunch_with<I>(args: I, env_var: &str) -> io::Result<()>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let exe = std::env::current_exe()?;
+    std::process::Command::new(exe)
+        .args(args)
+        .env(env_var, "1")
+        .spawn()?;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use crate::system::to_wide;
+    use std::path::Path;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDYES, MB_ICONERROR, MB_SETFOREGROUND, MB_TOPMOST, MB_YESNO,
+    };
+
+    /// Tell the user about the crash and ask whether to open the report
+    pub fn ask_to_open_report(path: &Path) -> bool {
+        let text = to_wide(&format!(
+            "RustPods ran into a problem and has to close.\n\n\
+             A crash report was saved to:\n{}\n\nOpen the report now?",
+            path.display()
+        ));
+        let caption = to_wide("RustPods crashed");
+        let result = unsafe {
+            MessageBoxW(
+                HWND::default(),
+                PCWSTR(text.as_ptr()),
+                PCWSTR(caption.as_ptr()),
+                MB_YESNO | MB_ICONERROR | MB_SETFOREGROUND | MB_TOPMOST,
+            )
+        };
+        result == IDYES
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use std::path::Path;
+
+    /// No native dialog; point at the report on stderr instead
+    pub fn ask_to_open_report(path: &Path) -> bool {
+        eprintln!("RustPods crashed. Crash report: {}", path.display());
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_details() -> CrashDetails {
+        CrashDetails {
+            message: "index out of bounds".to_string(),
+            location: Some("src/ui/state.rs:10:5".to_string()),
+            thread: "main".to_string(),
+            uptime: Duration::from_secs(42),
+            state_summary: "devices: 1".to_string(),
+            backtrace: "0: rustpods::main".to_string(),
+            recent_log: vec!["[INFO] started".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_panic_message_from_payload() {
+        let static_str: Box<dyn Any + Send> = Box::new("boom");
+        let owned: Box<dyn Any + Send> = Box::new(String::from("owned boom"));
+        let other: Box<dyn Any + Send> = Box::new(7u32);
+
+        assert_eq!(panic_message(static_str.as_ref()), "boom");
+        assert_eq!(panic_message(owned.as_ref()), "owned boom");
+        assert_eq!(panic_message(other.as_ref()), "<non-string panic payload>");
+    }
+
+    #[test]
+    fn test_should_relaunch_avoids_crash_loops() {
+        assert!(should_relaunch(false, Duration::from_secs(1)));
+        assert!(!should_relaunch(true, Duration::from_secs(1)));
+        assert!(should_relaunch(true, MIN_UPTIME_BEFORE_RELAUNCH));
+    }
+
+    #[test]
+    fn test_write_crash_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_crash_report(dir.path(), &sample_details()).unwrap();
+
+        let report = std::fs::read_to_string(path).unwrap();
+        assert!(report.contains("index out of bounds"));
+        assert!(report.contains("Location: src/ui/state.rs:10:5"));
+        assert!(report.contains("devices: 1"));
+        assert!(report.contains("=== Recent Log (1 lines) ==="));
+    }
+}
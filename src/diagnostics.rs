@@ -5,13 +5,26 @@
 
 use std::collections::HashMap;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::bluetooth::adapter::BluetoothAdapter;
-use crate::config::AppConfig;
+use crate::bluetooth::preflight::{self, RadioStatus};
+use crate::bluetooth::{ScanStatistics, ScannerHealth};
+use crate::config::{AppConfig, AuditLog};
 use crate::error::{ErrorManager, ErrorSeverity};
+use crate::lifecycle_manager::{startup_report, StartupPhase};
+use crate::system::disk_space;
+
+/// Settings changes included in a saved diagnostic report
+const AUDIT_ENTRIES_IN_REPORT: usize = 100;
+
+/// How often the background health check runs
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// File written and removed to check the data folder can be written to
+const STORAGE_PROBE_FILE: &str = ".health_check";
 
 /// Diagnostic level
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,6 +59,40 @@ pub struct DiagnosticResult {
     pub has_critical_issues: bool,
 }
 
+/// Overall health, from the most severe issue found
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum HealthStatus {
+    /// Nothing found, or only informational issues
+    #[default]
+    Healthy,
+    /// Major or minor issues; RustPods works with something missing
+    Degraded,
+    /// A critical issue stops RustPods from reading batteries
+    Unhealthy,
+}
+
+impl DiagnosticResult {
+    /// Overall health of the run
+    pub fn health(&self) -> HealthStatus {
+        self.issues
+            .iter()
+            .map(|issue| match issue.severity {
+                IssueSeverity::Critical => HealthStatus::Unhealthy,
+                IssueSeverity::Major | IssueSeverity::Minor => HealthStatus::Degraded,
+                IssueSeverity::Info => HealthStatus::Healthy,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Issues that make the run less than healthy
+    pub fn problems(&self) -> impl Iterator<Item = &DiagnosticIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity != IssueSeverity::Info)
+    }
+}
+
 /// Diagnostic issue
 #[derive(Debug, Clone)]
 pub struct DiagnosticIssue {
@@ -118,6 +165,12 @@ pub struct DiagnosticsManager {
 
     /// Whether to include error history
     include_error_history: bool,
+
+    /// Scanner totals to include in the report
+    scan_statistics: Option<ScanStatistics>,
+
+    /// Told the name of each check as it starts
+    step_observer: Option<Box<dyn Fn(&str) + Send + Sync>>,
 }
 
 impl DiagnosticsManager {
@@ -131,6 +184,8 @@ impl DiagnosticsManager {
             level: DiagnosticLevel::Basic,
             include_system_info: true,
             include_error_history: true,
+            scan_statistics: None,
+            step_observer: None,
         }
     }
 
@@ -152,6 +207,24 @@ impl DiagnosticsManager {
         self
     }
 
+    /// Set the scanner totals to include in the report
+    pub fn set_scan_statistics(&mut self, statistics: ScanStatistics) -> &mut Self {
+        self.scan_statistics = Some(statistics);
+        self
+    }
+
+    /// Be told the name of each check as it starts, e.g. to show progress
+    pub fn on_step(&mut self, observer: impl Fn(&str) + Send + Sync + 'static) -> &mut Self {
+        self.step_observer = Some(Box::new(observer));
+        self
+    }
+
+    fn step(&self, name: &str) {
+        if let Some(observer) = &self.step_observer {
+            observer(name);
+        }
+    }
+
     /// Run diagnostics
     pub async fn run_diagnostics(&mut self) -> io::Result<DiagnosticResult> {
         log::info!("Running diagnostics with level: {:?}", self.level);
@@ -164,15 +237,21 @@ impl DiagnosticsManager {
 
         // Add system information
         if self.include_system_info {
+            self.step("Collecting system information");
             self.collect_system_information(&mut raw_data)?;
         }
 
+        self.step("Reading the startup report");
+        self.check_startup(&mut issues, &mut raw_data);
+
         // Add error history
         if self.include_error_history {
+            self.step("Reading error history");
             self.collect_error_history(&mut raw_data, &mut issues, &mut recommendations)?;
         }
 
         // Check Bluetooth capabilities
+        self.step("Checking Bluetooth");
         if let Err(e) = self
             .check_bluetooth(&mut issues, &mut recommendations, &mut raw_data)
             .await
@@ -194,13 +273,19 @@ impl DiagnosticsManager {
         }
 
         // Check configuration
+        self.step("Checking configuration");
         self.check_configuration(&mut issues, &mut recommendations, &mut raw_data)?;
 
+        self.step("Checking disk space");
+        self.check_disk_space(&mut issues, &mut raw_data);
+
         // For advanced or complete diagnostics, perform additional checks
         if self.level != DiagnosticLevel::Basic {
+            self.step("Checking permissions");
             self.check_permissions(&mut issues, &mut recommendations, &mut raw_data)?;
 
             if self.level == DiagnosticLevel::Complete {
+                self.step("Checking hardware compatibility");
                 self.check_hardware_compatibility(
                     &mut issues,
                     &mut recommendations,
@@ -230,6 +315,40 @@ impl DiagnosticsManager {
         Ok(result)
     }
 
+    /// Run the checks cheap enough to repeat in the background
+    ///
+    /// Only looks at whether the Bluetooth radio can be used, whether the scanner keeps
+    /// failing according to `scanner`, and whether the data folder can be written to.
+    /// Querying the radio blocks, so call this off the UI thread.
+    pub fn run_health_check(&mut self, scanner: &ScannerHealth) -> DiagnosticResult {
+        let start_time = Instant::now();
+        let mut issues = Vec::new();
+        let mut raw_data = HashMap::new();
+
+        self.step("Checking the Bluetooth radio");
+        self.check_radio(&mut issues, &mut raw_data);
+
+        self.step("Checking the scanner");
+        self.check_scanner(scanner, &mut issues, &mut raw_data);
+
+        self.step("Checking the data folder");
+        self.check_storage(&mut issues, &mut raw_data);
+
+        let result = DiagnosticResult {
+            has_critical_issues: issues.iter().any(|i| i.severity == IssueSeverity::Critical),
+            issues,
+            recommendations: Vec::new(),
+            raw_data,
+            duration: start_time.elapsed(),
+            timestamp: chrono::Utc::now(),
+        };
+
+        self.last_result = Some(result.clone());
+        self.last_run = Some(start_time);
+
+        result
+    }
+
     /// Collect system information
     fn collect_system_information(&self, raw_data: &mut HashMap<String, String>) -> io::Result<()> {
         // OS information
@@ -465,6 +584,187 @@ impl DiagnosticsManager {
         Ok(())
     }
 
+    /// Check there is room on the data drive for the battery history
+    fn check_disk_space(
+        &self,
+        issues: &mut Vec<DiagnosticIssue>,
+        raw_data: &mut HashMap<String, String>,
+    ) {
+        let data_dir = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("RustPods");
+        let free = match disk_space::free_space(&data_dir) {
+            Ok(free) => free,
+            Err(e) => {
+                raw_data.insert("data_drive_free".to_string(), e.to_string());
+                return;
+            }
+        };
+        raw_data.insert(
+            "data_drive_free".to_string(),
+            format!("{} MB", free / (1024 * 1024)),
+        );
+
+        if free < disk_space::LOW_DISK_SPACE {
+            issues.push(DiagnosticIssue {
+                title: "Data drive is almost full".to_string(),
+                description: format!(
+                    "Only {} MB is free on the drive holding {}. Battery history, profile and settings changes are kept in memory and will be lost if RustPods closes before space is freed.",
+                    free / (1024 * 1024),
+                    data_dir.display()
+                ),
+                solutions: vec![
+                    "Free up space on the drive, e.g. with Disk Cleanup".to_string(),
+                    "Delete old files from the RustPods logs folder".to_string(),
+                ],
+                severity: IssueSeverity::Major,
+                category: IssueCategory::System,
+                auto_repairable: false,
+            });
+        }
+    }
+
+    /// Check a Bluetooth radio is on and RustPods may use it
+    fn check_radio(
+        &self,
+        issues: &mut Vec<DiagnosticIssue>,
+        raw_data: &mut HashMap<String, String>,
+    ) {
+        let status = preflight::radio_status();
+        raw_data.insert("bluetooth_radio".to_string(), format!("{:?}", status));
+
+        let Some(error) = status.error() else {
+            return;
+        };
+        let solution = match status {
+            RadioStatus::Disabled => "Enable the Bluetooth adapter in Device Manager",
+            RadioStatus::DeniedByUser => {
+                "Turn on \"Let apps control device radios\" in Settings > Privacy & security > Radios"
+            }
+            RadioStatus::DeniedBySystem => "Ask your administrator to allow radio access",
+            _ => "Turn on Bluetooth in Settings > Bluetooth & devices",
+        };
+        issues.push(DiagnosticIssue {
+            title: "Bluetooth can't be used".to_string(),
+            description: format!("{}. No scans run until this is fixed.", error),
+            solutions: vec![solution.to_string()],
+            severity: IssueSeverity::Critical,
+            category: IssueCategory::Bluetooth,
+            auto_repairable: false,
+        });
+    }
+
+    /// Check the scanner hasn't been failing run after run
+    fn check_scanner(
+        &self,
+        scanner: &ScannerHealth,
+        issues: &mut Vec<DiagnosticIssue>,
+        raw_data: &mut HashMap<String, String>,
+    ) {
+        raw_data.insert(
+            "scanner_consecutive_failures".to_string(),
+            scanner.consecutive_failures.to_string(),
+        );
+
+        if scanner.is_unhealthy() {
+            issues.push(DiagnosticIssue {
+                title: "The scanner keeps failing".to_string(),
+                description: format!(
+                    "The last {} scanner runs failed: {}",
+                    scanner.consecutive_failures,
+                    scanner
+                        .last_error
+                        .as_deref()
+                        .unwrap_or("no reason was given")
+                ),
+                solutions: vec![
+                    "Reinstall RustPods so airpods_battery_cli.exe is next to rustpods.exe"
+                        .to_string(),
+                    "Check that antivirus software isn't blocking airpods_battery_cli.exe"
+                        .to_string(),
+                ],
+                severity: IssueSeverity::Major,
+                category: IssueCategory::Application,
+                auto_repairable: false,
+            });
+        }
+    }
+
+    /// Check the data folder can be written to
+    fn check_storage(
+        &self,
+        issues: &mut Vec<DiagnosticIssue>,
+        raw_data: &mut HashMap<String, String>,
+    ) {
+        let data_dir = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("RustPods");
+        let probe = data_dir.join(STORAGE_PROBE_FILE);
+        let written = std::fs::create_dir_all(&data_dir)
+            .and_then(|()| std::fs::write(&probe, b""))
+            .and_then(|()| std::fs::remove_file(&probe));
+        raw_data.insert(
+            "data_folder_writable".to_string(),
+            written.as_ref().is_ok().to_string(),
+        );
+
+        if let Err(e) = written {
+            issues.push(DiagnosticIssue {
+                title: "The data folder can't be written to".to_string(),
+                description: format!(
+                    "Writing to {} failed: {}. Battery history and settings changes won't be saved.",
+                    data_dir.display(),
+                    e
+                ),
+                solutions: vec![
+                    "Check that the folder isn't read-only and that you may write to it"
+                        .to_string(),
+                    "Free up space on the drive".to_string(),
+                ],
+                severity: IssueSeverity::Major,
+                category: IssueCategory::System,
+                auto_repairable: false,
+            });
+        }
+    }
+
+    /// Report startup phases that failed or timed out
+    fn check_startup(
+        &self,
+        issues: &mut Vec<DiagnosticIssue>,
+        raw_data: &mut HashMap<String, String>,
+    ) {
+        let report = startup_report();
+        raw_data.insert("startup_report".to_string(), report.to_string());
+
+        for record in report.degraded() {
+            let (severity, solution) = match record.phase {
+                StartupPhase::Storage => (
+                    IssueSeverity::Major,
+                    "Check that the RustPods data folder can be written to, then restart",
+                ),
+                StartupPhase::Scanner => (
+                    IssueSeverity::Major,
+                    "Reinstall RustPods so airpods_battery_cli.exe is next to rustpods.exe",
+                ),
+                _ => (IssueSeverity::Minor, "Restart RustPods"),
+            };
+            issues.push(DiagnosticIssue {
+                title: format!("RustPods started without its {} phase", record.phase),
+                description: format!(
+                    "The {} startup phase didn't complete, so {}. {}",
+                    record.phase,
+                    record.phase.degraded_effect(),
+                    record
+                ),
+                solutions: vec![solution.to_string()],
+                severity,
+                category: IssueCategory::Application,
+                auto_repairable: false,
+            });
+        }
+    }
+
     /// Check system permissions
     fn check_permissions(
         &self,
@@ -551,23 +851,10 @@ impl DiagnosticsManager {
         Ok(())
     }
 
-    /// Save diagnostic results to a file
+    /// Save diagnostic results to a timestamped file in the diagnostics directory
     pub fn save_diagnostics(&self) -> io::Result<PathBuf> {
-        let result = match &self.last_result {
-            Some(r) => r,
-            None => {
-                return Err(io::Error::new(
-                    io::ErrorKind::NotFound,
-                    "No diagnostic results available",
-                ))
-            }
-        };
-
         // Determine diagnostic file path
-        let diagnostics_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("RustPods")
-            .join("diagnostics");
+        let diagnostics_dir = diagnostics_dir();
 
         // Create directory if it doesn't exist
         if !diagnostics_dir.exists() {
@@ -575,64 +862,95 @@ impl DiagnosticsManager {
         }
 
         // Generate file name with timestamp
-        let now = chrono::Local::now();
-        let timestamp = now.format("%Y%m%d_%H%M%S");
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
         let file_name = format!("rustpods_diagnostic_{}.txt", timestamp);
         let file_path = diagnostics_dir.join(&file_name);
 
-        // Create file
-        let mut file = std::fs::File::create(&file_path)?;
+        self.save_diagnostics_to(&file_path)?;
+        Ok(file_path)
+    }
+
+    /// Save diagnostic results to `file_path`
+    pub fn save_diagnostics_to(&self, file_path: &Path) -> io::Result<()> {
+        let result = match &self.last_result {
+            Some(r) => r,
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "No diagnostic results available",
+                ))
+            }
+        };
+        let now = chrono::Local::now();
+
+        // Build the report in memory so addresses can be hashed before it is written
+        let mut report = Vec::new();
 
         // Write diagnostic information
-        writeln!(file, "=== RustPods Diagnostic Report ===")?;
-        writeln!(file, "Generated: {}", now.format("%Y-%m-%d %H:%M:%S"))?;
-        writeln!(file, "Diagnostic level: {:?}", self.level)?;
-        writeln!(file, "Duration: {:?}", result.duration)?;
-        writeln!(file)?;
+        writeln!(report, "=== RustPods Diagnostic Report ===")?;
+        writeln!(report, "Generated: {}", now.format("%Y-%m-%d %H:%M:%S"))?;
+        writeln!(report, "Diagnostic level: {:?}", self.level)?;
+        writeln!(report, "Duration: {:?}", result.duration)?;
+        writeln!(report)?;
 
         // Write issues
-        writeln!(file, "=== Issues ({}) ===", result.issues.len())?;
+        writeln!(report, "=== Issues ({}) ===", result.issues.len())?;
         for (i, issue) in result.issues.iter().enumerate() {
             writeln!(
-                file,
+                report,
                 "Issue {}: {} [{:?}]",
                 i + 1,
                 issue.title,
                 issue.severity
             )?;
-            writeln!(file, "Description: {}", issue.description)?;
-            writeln!(file, "Category: {:?}", issue.category)?;
-            writeln!(file, "Solutions:")?;
+            writeln!(report, "Description: {}", issue.description)?;
+            writeln!(report, "Category: {:?}", issue.category)?;
+            writeln!(report, "Solutions:")?;
             for solution in &issue.solutions {
-                writeln!(file, "  - {}", solution)?;
+                writeln!(report, "  - {}", solution)?;
             }
-            writeln!(file)?;
+            writeln!(report)?;
         }
 
         // Write recommendations
         writeln!(
-            file,
+            report,
             "=== Recommendations ({}) ===",
             result.recommendations.len()
         )?;
         for (i, recommendation) in result.recommendations.iter().enumerate() {
-            writeln!(file, "{}. {}", i + 1, recommendation)?;
+            writeln!(report, "{}. {}", i + 1, recommendation)?;
         }
-        writeln!(file)?;
+        writeln!(report)?;
 
         // Write raw data
-        writeln!(file, "=== System Information ===")?;
+        writeln!(report, "=== System Information ===")?;
         let mut raw_data: Vec<(&String, &String)> = result.raw_data.iter().collect();
         raw_data.sort_by(|a, b| a.0.cmp(b.0));
 
         for (key, value) in raw_data {
-            writeln!(file, "{}: {}", key, value)?;
+            writeln!(report, "{}: {}", key, value)?;
         }
+        writeln!(report)?;
 
-        // Flush file
-        file.flush()?;
+        // Write scanner totals
+        if let Some(statistics) = &self.scan_statistics {
+            writeln!(report, "=== Scanner Status ===")?;
+            for line in statistics.summary_lines() {
+                writeln!(report, "{}", line)?;
+            }
+            writeln!(report)?;
+        }
 
-        Ok(file_path)
+        // Write recent settings changes, newest first
+        let changes = AuditLog::for_config(&self.config).recent(AUDIT_ENTRIES_IN_REPORT);
+        writeln!(report, "=== Settings Changes ({}) ===", changes.len())?;
+        for change in changes {
+            writeln!(report, "{}", change)?;
+        }
+
+        let text = String::from_utf8_lossy(&report);
+        std::fs::write(file_path, crate::privacy::redact(&text).as_bytes())
     }
 
     /// Attempt to auto-repair issues
@@ -737,6 +1055,14 @@ pub struct AutoRepairResult {
     pub repair_time: Duration,
 }
 
+/// Directory diagnostic and crash reports are written to
+pub fn diagnostics_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("RustPods")
+        .join("diagnostics")
+}
+
 /// Check if the system meets minimum requirements
 pub fn meets_minimum_requirements() -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
     // Check operating system
@@ -834,3 +1160,43 @@ pub struct TroubleshootingStep {
     /// How to verify the step worked
     pub verification: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(severity: IssueSeverity) -> DiagnosticIssue {
+        DiagnosticIssue {
+            title: format!("{:?}", severity),
+            description: String::new(),
+            solutions: Vec::new(),
+            severity,
+            category: IssueCategory::Application,
+            auto_repairable: false,
+        }
+    }
+
+    #[test]
+    fn test_health_follows_the_worst_issue() {
+        let mut result = DiagnosticResult {
+            issues: Vec::new(),
+            recommendations: Vec::new(),
+            raw_data: HashMap::new(),
+            duration: Duration::ZERO,
+            timestamp: chrono::Utc::now(),
+            has_critical_issues: false,
+        };
+        assert_eq!(result.health(), HealthStatus::Healthy);
+
+        result.issues.push(issue(IssueSeverity::Info));
+        assert_eq!(result.health(), HealthStatus::Healthy);
+        assert_eq!(result.problems().count(), 0);
+
+        result.issues.push(issue(IssueSeverity::Minor));
+        assert_eq!(result.health(), HealthStatus::Degraded);
+
+        result.issues.push(issue(IssueSeverity::Critical));
+        assert_eq!(result.health(), HealthStatus::Unhealthy);
+        assert_eq!(result.problems().count(), 2);
+    }
+}
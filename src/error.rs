@@ -160,6 +160,10 @@ pub enum RustPodsError {
     #[error("Invalid data: {0}")]
     InvalidData(String),
 
+    /// A data file couldn't be read and was moved to the quarantine folder
+    #[error("Quarantined unreadable file: {0}")]
+    Quarantined(String),
+
     /// Bluetooth error
     #[error("Bluetooth error: {0}")]
     BluetoothError(#[from] BluetoothError),
@@ -190,6 +194,12 @@ pub enum RecoveryAction {
     SelectDifferentAdapter,
     /// Restart the entire application
     RestartApplication,
+    /// Salvage data files moved to the quarantine folder
+    RecoverQuarantined,
+    /// Open the Bluetooth page of Windows Settings to switch the radio on
+    TurnOnBluetooth,
+    /// Open the Windows privacy settings to let apps use the radio
+    AllowRadioAccess,
     /// Custom action with description
     Custom(String),
 }
@@ -208,6 +218,9 @@ impl fmt::Display for RecoveryAction {
             RecoveryAction::PromptUser => write!(f, "Prompt User"),
             RecoveryAction::SelectDifferentAdapter => write!(f, "Select Different Adapter"),
             RecoveryAction::RestartApplication => write!(f, "Restart Application"),
+            RecoveryAction::RecoverQuarantined => write!(f, "Recover Quarantined Files"),
+            RecoveryAction::TurnOnBluetooth => write!(f, "Turn On Bluetooth"),
+            RecoveryAction::AllowRadioAccess => write!(f, "Allow Radio Access"),
             RecoveryAction::Custom(desc) => write!(f, "Custom: {}", desc),
         }
     }
@@ -228,9 +241,26 @@ impl RecoveryAction {
             RecoveryAction::PromptUser => "Prompt user for action",
             RecoveryAction::SelectDifferentAdapter => "Select a different Bluetooth adapter",
             RecoveryAction::RestartApplication => "Restart the application",
+            RecoveryAction::RecoverQuarantined => "Recover quarantined files",
+            RecoveryAction::TurnOnBluetooth => "Turn on Bluetooth in Windows Settings",
+            RecoveryAction::AllowRadioAccess => "Allow radio access in Windows privacy settings",
             RecoveryAction::Custom(_) => "Custom action",
         }
     }
+
+    /// Label for a button that performs this action in the UI, if it can be done in-app
+    pub fn button_label(&self) -> Option<&'static str> {
+        match self {
+            RecoveryAction::Retry
+            | RecoveryAction::Restart
+            | RecoveryAction::ReconnectBluetooth => Some("Retry"),
+            RecoveryAction::ReloadConfig => Some("Reload settings"),
+            RecoveryAction::RecoverQuarantined => Some("Attempt recovery"),
+            RecoveryAction::TurnOnBluetooth => Some("Turn on Bluetooth"),
+            RecoveryAction::AllowRadioAccess => Some("Open privacy settings"),
+            _ => None,
+        }
+    }
 }
 
 /// Error statistics
@@ -276,6 +306,12 @@ pub struct ErrorStats {
     pub recoverable_errors: usize,
     /// Warnings
     pub warnings: usize,
+    /// Failed attempts that were tried again
+    pub retries: usize,
+    /// Operations given up on after retrying
+    pub retries_exhausted: usize,
+    /// Retries by operation
+    pub retries_by_operation: HashMap<String, usize>,
 }
 
 /// Error context to enrich error information
@@ -352,6 +388,27 @@ pub struct ErrorEntry {
     pub recovery: Option<RecoveryAction>,
 }
 
+/// An error shown to the user until it is resolved or dismissed
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveError {
+    /// Identifier used by the UI to dismiss or recover
+    pub id: u64,
+    /// Subsystem that raised the error (e.g. "scanner")
+    pub component: String,
+    /// User-facing message
+    pub message: String,
+    /// Recommended recovery action
+    pub recovery: RecoveryAction,
+    /// When the error was last raised
+    pub raised_at: DateTime<Utc>,
+    /// How many times the error was raised while active
+    pub occurrences: u32,
+    /// Stable error code (see [`RustPodsError::get_type`])
+    pub code: &'static str,
+    /// Known issue with a workaround for this error, if there is one
+    pub known_issue: Option<crate::known_issues::KnownIssue>,
+}
+
 /// Error manager for tracking and reporting errors
 #[derive(Debug)]
 pub struct ErrorManager {
@@ -361,6 +418,10 @@ pub struct ErrorManager {
     stats: ErrorStats,
     /// Detailed error records with context
     detailed_history: Vec<ErrorRecord>,
+    /// Errors currently shown to the user
+    active: Vec<ActiveError>,
+    /// Next identifier for an active error
+    next_active_id: u64,
 }
 
 /// Error record for the history
@@ -390,6 +451,8 @@ impl ErrorManager {
             history: Vec::new(),
             stats: ErrorStats::default(),
             detailed_history: Vec::new(),
+            active: Vec::new(),
+            next_active_id: 1,
         }
     }
 
@@ -429,6 +492,7 @@ impl ErrorManager {
             RustPodsError::Timeout(_) => "timeout",
             RustPodsError::Context { .. } => "context",
             RustPodsError::InvalidData(_) => "invalid_data",
+            RustPodsError::Quarantined(_) => "quarantined",
             RustPodsError::BluetoothError(_) => "bluetooth_error",
         };
 
@@ -506,6 +570,7 @@ impl ErrorManager {
             RustPodsError::Timeout(_) => "timeout",
             RustPodsError::Context { .. } => "context",
             RustPodsError::InvalidData(_) => "invalid_data",
+            RustPodsError::Quarantined(_) => "quarantined",
             RustPodsError::BluetoothError(_) => "bluetooth_error",
         };
 
@@ -567,6 +632,20 @@ impl ErrorManager {
         self.add_to_history(error);
     }
 
+    /// Count a retry of `operation`, or giving up on it
+    pub fn record_retry(&mut self, operation: &str, gave_up: bool) {
+        if gave_up {
+            self.stats.retries_exhausted += 1;
+        } else {
+            self.stats.retries += 1;
+            *self
+                .stats
+                .retries_by_operation
+                .entry(operation.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
     /// Get error history
     pub fn get_error_history(&self) -> &Vec<ErrorEntry> {
         &self.history
@@ -602,6 +681,56 @@ impl ErrorManager {
     pub fn get_latest_detailed_error(&self) -> Option<&ErrorRecord> {
         self.detailed_history.last()
     }
+
+    /// Record an error and keep it active until resolved or dismissed
+    ///
+    /// Raising the same message again for a component updates the existing entry instead of
+    /// adding a duplicate. Returns the active error's identifier.
+    pub fn raise(&mut self, component: &str, error: &RustPodsError) -> u64 {
+        self.add_to_history(error);
+
+        let message = error.user_message();
+        let now = chrono::Utc::now();
+        if let Some(existing) = self
+            .active
+            .iter_mut()
+            .find(|active| active.component == component && active.message == message)
+        {
+            existing.raised_at = now;
+            existing.occurrences += 1;
+            return existing.id;
+        }
+
+        let id = self.next_active_id;
+        self.next_active_id += 1;
+        self.active.push(ActiveError {
+            id,
+            component: component.to_string(),
+            message,
+            recovery: error.recovery_action(),
+            raised_at: now,
+            occurrences: 1,
+            code: error.get_type(),
+            known_issue: None,
+        });
+        id
+    }
+
+    /// Errors currently shown to the user, oldest first
+    pub fn active_errors(&self) -> &[ActiveError] {
+        &self.active
+    }
+
+    /// Remove an active error, returning it
+    pub fn dismiss(&mut self, id: u64) -> Option<ActiveError> {
+        let index = self.active.iter().position(|active| active.id == id)?;
+        Some(self.active.remove(index))
+    }
+
+    /// Clear all active errors raised by a component (e.g. after it recovers)
+    pub fn resolve(&mut self, component: &str) {
+        self.active.retain(|active| active.component != component);
+    }
 }
 
 impl Default for ErrorManager {
@@ -643,6 +772,10 @@ impl RustPodsError {
             RustPodsError::Timeout(msg) => format!("Operation timed out: {}", msg),
             RustPodsError::Context { context, source } => format!("{}: {}", context, source),
             RustPodsError::InvalidData(msg) => format!("Invalid data: {}", msg),
+            RustPodsError::Quarantined(msg) => msg.clone(),
+            RustPodsError::BluetoothError(
+                e @ (BluetoothError::RadioOff | BluetoothError::PermissionDenied(_)),
+            ) => e.to_string(),
             RustPodsError::BluetoothError(_) => format!("Bluetooth error: {}", self),
         }
     }
@@ -676,6 +809,7 @@ impl RustPodsError {
             RustPodsError::Timeout(_) => true,
             RustPodsError::Context { .. } => false,
             RustPodsError::InvalidData(_) => false,
+            RustPodsError::Quarantined(_) => true,
             RustPodsError::BluetoothError(_) => true,
         }
     }
@@ -709,6 +843,13 @@ impl RustPodsError {
             RustPodsError::Timeout(_) => RecoveryAction::Retry,
             RustPodsError::Context { .. } => RecoveryAction::NotifyUser,
             RustPodsError::InvalidData(_) => RecoveryAction::NotifyUser,
+            RustPodsError::Quarantined(_) => RecoveryAction::RecoverQuarantined,
+            RustPodsError::BluetoothError(BluetoothError::RadioOff) => {
+                RecoveryAction::TurnOnBluetooth
+            }
+            RustPodsError::BluetoothError(BluetoothError::PermissionDenied(_)) => {
+                RecoveryAction::AllowRadioAccess
+            }
             RustPodsError::BluetoothError(_) => RecoveryAction::ReconnectBluetooth,
         }
     }
@@ -742,6 +883,7 @@ impl RustPodsError {
             RustPodsError::Timeout(_) => ErrorSeverity::Major,
             RustPodsError::Context { .. } => ErrorSeverity::Error,
             RustPodsError::InvalidData(_) => ErrorSeverity::Major,
+            RustPodsError::Quarantined(_) => ErrorSeverity::Major,
             RustPodsError::BluetoothError(_) => ErrorSeverity::Major,
         }
     }
@@ -773,6 +915,7 @@ impl RustPodsError {
             RustPodsError::Timeout(_) => "timeout",
             RustPodsError::Context { .. } => "context",
             RustPodsError::InvalidData(_) => "invalid_data",
+            RustPodsError::Quarantined(_) => "quarantined",
             RustPodsError::System(_) => "system",
             RustPodsError::General(_) => "general",
             RustPodsError::BluetoothError(_) => "bluetooth_error",
@@ -806,6 +949,7 @@ impl RustPodsError {
             RustPodsError::Timeout(_) => "timeout",
             RustPodsError::Context { .. } => "context",
             RustPodsError::InvalidData(_) => "invalid_data",
+            RustPodsError::Quarantined(_) => "quarantined",
             RustPodsError::System(_) => "system_generic",
             RustPodsError::General(_) => "general",
             RustPodsError::BluetoothError(_) => "bluetooth_error",
@@ -857,6 +1001,9 @@ pub enum BluetoothError {
     /// No suitable adapter found
     NoAdapter,
 
+    /// The Bluetooth radio is switched off
+    RadioOff,
+
     /// Permission error
     PermissionDenied(String),
 
@@ -907,6 +1054,7 @@ impl std::fmt::Display for BluetoothError {
             BluetoothError::ScanFailed(s) => write!(f, "Scan failed: {}", s),
             BluetoothError::DeviceDisconnected(s) => write!(f, "Device disconnected: {}", s),
             BluetoothError::NoAdapter => write!(f, "No suitable Bluetooth adapter found"),
+            BluetoothError::RadioOff => write!(f, "Bluetooth is turned off"),
             BluetoothError::PermissionDenied(s) => write!(f, "Bluetooth permission denied: {}", s),
             BluetoothError::InvalidData(s) => write!(f, "Invalid data received: {}", s),
             BluetoothError::ApiError(e) => write!(f, "Bluetooth API error: {}", e),
@@ -1092,6 +1240,7 @@ impl Clone for BluetoothError {
             BluetoothError::ScanFailed(s) => BluetoothError::ScanFailed(s.clone()),
             BluetoothError::DeviceDisconnected(s) => BluetoothError::DeviceDisconnected(s.clone()),
             BluetoothError::NoAdapter => BluetoothError::NoAdapter,
+            BluetoothError::RadioOff => BluetoothError::RadioOff,
             BluetoothError::PermissionDenied(s) => BluetoothError::PermissionDenied(s.clone()),
             BluetoothError::InvalidData(s) => BluetoothError::InvalidData(s.clone()),
             BluetoothError::Timeout(d) => BluetoothError::Timeout(*d),
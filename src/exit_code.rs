@@ -0,0 +1,301 @@
+//! Exit codes of the command line commands and the error printed when one fails
+//!
+//! Each failure ends the process with an [`ExitCode`] chosen from the category of the
+//! [`RustPodsError`] behind it, so a wrapper script can tell a missing adapter from an
+//! unreadable file without parsing messages. With `--errors json` the failure is also
+//! printed to stderr as one JSON object carrying the code, the error's category and type
+//! and the message.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde_json::json;
+
+use crate::cli_output;
+use crate::error::{BluetoothError, RustPodsError};
+
+/// Set by `--errors json`
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Why a command ended, as the process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExitCode {
+    /// The command finished
+    Success,
+    /// Anything without a more specific code
+    Failure,
+    /// No Bluetooth adapter, or it failed while scanning
+    Bluetooth,
+    /// The command line couldn't be understood
+    Usage,
+    /// No matching AirPods were found
+    NoDevice,
+    /// A setting couldn't be read, changed or saved
+    Config,
+    /// Input or stored data couldn't be parsed
+    InvalidData,
+    /// A file or folder couldn't be read or written
+    Io,
+    /// Windows refused access
+    Permission,
+    /// An operation took too long
+    Timeout,
+}
+
+impl ExitCode {
+    /// Every exit code, in numeric order
+    pub const ALL: [ExitCode; 10] = [
+        ExitCode::Success,
+        ExitCode::Failure,
+        ExitCode::Bluetooth,
+        ExitCode::Usage,
+        ExitCode::NoDevice,
+        ExitCode::Config,
+        ExitCode::InvalidData,
+        ExitCode::Io,
+        ExitCode::Permission,
+        ExitCode::Timeout,
+    ];
+
+    /// Value the process exits with
+    pub fn code(self) -> i32 {
+        match self {
+            ExitCode::Success => 0,
+            ExitCode::Failure => 1,
+            ExitCode::Bluetooth => 2,
+            ExitCode::Usage => 3,
+            ExitCode::NoDevice => 4,
+            ExitCode::Config => 5,
+            ExitCode::InvalidData => 6,
+            ExitCode::Io => 7,
+            ExitCode::Permission => 8,
+            ExitCode::Timeout => 9,
+        }
+    }
+
+    /// Name used in the JSON error object
+    pub fn name(self) -> &'static str {
+        match self {
+            ExitCode::Success => "success",
+            ExitCode::Failure => "failure",
+            ExitCode::Bluetooth => "bluetooth",
+            ExitCode::Usage => "usage",
+            ExitCode::NoDevice => "no_device",
+            ExitCode::Config => "config",
+            ExitCode::InvalidData => "invalid_data",
+            ExitCode::Io => "io",
+            ExitCode::Permission => "permission",
+            ExitCode::Timeout => "timeout",
+        }
+    }
+
+    /// What the code means, for `help`
+    pub fn description(self) -> &'static str {
+        match self {
+            ExitCode::Success => "Finished without errors",
+            ExitCode::Failure => "Unexpected failure",
+            ExitCode::Bluetooth => "Bluetooth adapter missing, off or failing to scan",
+            ExitCode::Usage => "Unknown command, flag or missing argument",
+            ExitCode::NoDevice => "No AirPods found",
+            ExitCode::Config => "Settings couldn't be read, changed or saved",
+            ExitCode::InvalidData => "Input or data file couldn't be parsed",
+            ExitCode::Io => "File or folder couldn't be read or written",
+            ExitCode::Permission => "Access denied",
+            ExitCode::Timeout => "Operation timed out",
+        }
+    }
+
+    /// Exit code for a failure caused by `error`
+    pub fn for_error(error: &RustPodsError) -> Self {
+        if let RustPodsError::BluetoothError(e) = error {
+            return match e {
+                BluetoothError::DeviceNotFound(_) => ExitCode::NoDevice,
+                BluetoothError::PermissionDenied(_) => ExitCode::Permission,
+                BluetoothError::InvalidData(_) => ExitCode::InvalidData,
+                BluetoothError::Timeout(_) => ExitCode::Timeout,
+                _ => ExitCode::Bluetooth,
+            };
+        }
+        match error.get_category() {
+            "bluetooth" | "airpods" => ExitCode::Bluetooth,
+            "device" => ExitCode::NoDevice,
+            "config" => ExitCode::Config,
+            "json" | "parse" | "validation" | "invalid_data" | "quarantined" => {
+                ExitCode::InvalidData
+            }
+            "file_io" | "path" | "file_not_found" => ExitCode::Io,
+            "permission" => ExitCode::Permission,
+            "timeout" => ExitCode::Timeout,
+            _ => ExitCode::Failure,
+        }
+    }
+}
+
+impl fmt::Display for ExitCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.code(), self.name())
+    }
+}
+
+/// Print failures as JSON instead of text
+pub fn set_json_errors(json: bool) {
+    JSON_ERRORS.store(json, Ordering::Relaxed);
+}
+
+/// Whether failures are printed as JSON
+pub fn json_errors() -> bool {
+    JSON_ERRORS.load(Ordering::Relaxed)
+}
+
+/// Whether `value` of `--errors` asks for JSON; `None` if it is neither `json` nor `text`
+pub fn parse_error_format(value: &str) -> Option<bool> {
+    match value {
+        "json" => Some(true),
+        "text" => Some(false),
+        _ => None,
+    }
+}
+
+/// A command that failed, ready to be reported
+#[derive(Debug)]
+pub struct CommandFailure {
+    /// Exit code the process ends with
+    pub code: ExitCode,
+    /// What was being done, e.g. "scanning for devices"
+    pub operation: String,
+    /// The cause
+    pub error: RustPodsError,
+    /// What the user is told; the error's own message if `None`
+    pub message: Option<String>,
+    /// What the user could try
+    pub suggestion: Option<String>,
+}
+
+impl CommandFailure {
+    /// Failure of `operation`, with the exit code for `error`
+    pub fn new(operation: impl Into<String>, error: RustPodsError) -> Self {
+        Self {
+            code: ExitCode::for_error(&error),
+            operation: operation.into(),
+            error,
+            message: None,
+            suggestion: None,
+        }
+    }
+
+    /// A command line that couldn't be parsed
+    pub fn usage(message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self {
+            code: ExitCode::Usage,
+            operation: "parsing arguments".to_string(),
+            error: RustPodsError::Validation(message.clone()),
+            message: Some(message),
+            suggestion: Some("Run `rustpods help` for the commands and flags".to_string()),
+        }
+    }
+
+    /// Tell the user `message` instead of the error's own
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Suggest what the user could try
+    pub fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+
+    /// What the user is told
+    pub fn message(&self) -> String {
+        self.message
+            .clone()
+            .unwrap_or_else(|| self.error.to_string())
+    }
+
+    /// The failure as the object printed by `--errors json`
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "exit_code": self.code.code(),
+            "name": self.code.name(),
+            "operation": self.operation,
+            "category": self.error.get_category(),
+            "type": self.error.get_type(),
+            "message": self.message(),
+            "recoverable": self.error.is_recoverable(),
+            "suggestion": self.suggestion,
+        })
+    }
+
+    /// Print the failure to stderr, as JSON with `--errors json`, and return its code
+    pub fn report(self) -> ExitCode {
+        if json_errors() {
+            eprintln!("{}", self.to_json());
+        } else {
+            eprintln!("{}", cli_output::error(self.message()));
+            if let Some(suggestion) = &self.suggestion {
+                eprintln!("{} {}", cli_output::label("Suggested action:"), suggestion);
+            }
+        }
+        self.code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    #[test]
+    fn test_codes_are_unique_and_ordered() {
+        let codes: Vec<i32> = ExitCode::ALL.iter().map(|code| code.code()).collect();
+        assert_eq!(codes, (0..ExitCode::ALL.len() as i32).collect::<Vec<_>>());
+        let names: HashSet<&str> = ExitCode::ALL.iter().map(|code| code.name()).collect();
+        assert_eq!(names.len(), ExitCode::ALL.len());
+    }
+
+    #[test]
+    fn test_code_follows_error_category() {
+        assert_eq!(
+            ExitCode::for_error(&BluetoothError::NoAdapter.into()),
+            ExitCode::Bluetooth
+        );
+        assert_eq!(
+            ExitCode::for_error(&BluetoothError::Timeout(Duration::from_secs(5)).into()),
+            ExitCode::Timeout
+        );
+        assert_eq!(
+            ExitCode::for_error(&RustPodsError::DeviceNotFound),
+            ExitCode::NoDevice
+        );
+        assert_eq!(
+            ExitCode::for_error(&RustPodsError::Config("bad key".into())),
+            ExitCode::Config
+        );
+        assert_eq!(
+            ExitCode::for_error(&RustPodsError::IoError("disk".into())),
+            ExitCode::Io
+        );
+        assert_eq!(
+            ExitCode::for_error(&RustPodsError::Ui("window".into())),
+            ExitCode::Failure
+        );
+    }
+
+    #[test]
+    fn test_json_error_object() {
+        let failure = CommandFailure::new("reading battery status", RustPodsError::DeviceNotFound)
+            .with_message("No AirPods found");
+        let json = failure.to_json();
+        assert_eq!(json["exit_code"], 4);
+        assert_eq!(json["name"], "no_device");
+        assert_eq!(json["category"], "device");
+        assert_eq!(json["type"], "device_not_found");
+        assert_eq!(json["message"], "No AirPods found");
+        assert!(json["suggestion"].is_null());
+
+        assert_eq!(CommandFailure::usage("Unknown flag").code, ExitCode::Usage);
+    }
+}
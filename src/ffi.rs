@@ -0,0 +1,408 @@
+//! C interface for widgets and other third-party apps
+//!
+//! Built with the `cdylib` feature. Apps written in C, C++ or C# can load the library
+//! and read AirPods battery status without starting the RustPods executable. The
+//! header `include/rustpods.h` is generated from this file by the build script.
+//!
+//! Call [`rustpods_init`] once, then either poll with [`rustpods_poll_status`] or
+//! register a callback with [`rustpods_register_callback`]. [`rustpods_shutdown`] stops
+//! scanning. Every function may be called from any thread.
+
+use std::ffi::{c_char, c_void};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::StreamExt;
+
+use crate::airpods::{AirPodsBattery, AirPodsChargingState, DetectedAirPods};
+use crate::monitor::{monitor, RustPodsEvent};
+
+/// The call succeeded
+pub const RUSTPODS_OK: i32 = 0;
+/// [`rustpods_init`] has not been called, or [`rustpods_shutdown`] has
+pub const RUSTPODS_ERROR_NOT_INITIALIZED: i32 = -1;
+/// [`rustpods_init`] was called twice without [`rustpods_shutdown`] in between
+pub const RUSTPODS_ERROR_ALREADY_INITIALIZED: i32 = -2;
+/// A required pointer was null
+pub const RUSTPODS_ERROR_NULL_POINTER: i32 = -3;
+/// The background runtime could not be started
+pub const RUSTPODS_ERROR_RUNTIME: i32 = -4;
+
+/// Length of [`RustPodsStatus::name`], including the terminating NUL
+pub const RUSTPODS_NAME_LENGTH: usize = 64;
+
+/// How long shutdown waits for the monitoring task to stop
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Battery status of the AirPods seen most recently
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct RustPodsStatus {
+    /// Whether AirPods are in range; the other fields are only meaningful if so
+    pub connected: bool,
+    /// Left AirPod level in percent, or -1 if unknown
+    pub left: i8,
+    /// Right AirPod level in percent, or -1 if unknown
+    pub right: i8,
+    /// Case level in percent, or -1 if unknown
+    pub case_level: i8,
+    /// Whether the left AirPod is charging
+    pub left_charging: bool,
+    /// Whether the right AirPod is charging
+    pub right_charging: bool,
+    /// Whether the case is charging
+    pub case_charging: bool,
+    /// Device name as UTF-8, NUL terminated
+    pub name: [c_char; RUSTPODS_NAME_LENGTH],
+}
+
+impl Default for RustPodsStatus {
+    fn default() -> Self {
+        Self {
+            connected: false,
+            left: -1,
+            right: -1,
+            case_level: -1,
+            left_charging: false,
+            right_charging: false,
+            case_charging: false,
+            name: [0; RUSTPODS_NAME_LENGTH],
+        }
+    }
+}
+
+impl RustPodsStatus {
+    /// Status of a device as last reported
+    fn from_airpods(airpods: &DetectedAirPods) -> Self {
+        let mut status = Self {
+            connected: true,
+            ..Self::default()
+        };
+        status.set_battery(&airpods.battery.clone().unwrap_or_default());
+
+        // Truncate on a character boundary, leaving room for the NUL
+        let name = airpods.name.as_deref().unwrap_or("AirPods");
+        let mut end = name.len().min(RUSTPODS_NAME_LENGTH - 1);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        for (slot, byte) in status.name.iter_mut().zip(&name.as_bytes()[..end]) {
+            *slot = *byte as c_char;
+        }
+        status
+    }
+
+    fn set_battery(&mut self, battery: &AirPodsBattery) {
+        let level = |level: Option<u8>| level.map_or(-1, |level| level.min(100) as i8);
+        self.left = level(battery.left);
+        self.right = level(battery.right);
+        self.case_level = level(battery.case);
+        self.set_charging(
+            battery
+                .charging
+                .unwrap_or(AirPodsChargingState::NotCharging),
+        );
+    }
+
+    fn set_charging(&mut self, charging: AirPodsChargingState) {
+        self.left_charging = charging.is_left_charging();
+        self.right_charging = charging.is_right_charging();
+        self.case_charging = charging.is_case_charging();
+    }
+}
+
+/// Called with the new status whenever it changes
+///
+/// Runs on a background thread. The status pointer is only valid during the call. It
+/// may call the other functions except [`rustpods_shutdown`], which waits for it.
+pub type RustPodsCallback =
+    Option<unsafe extern "C" fn(status: *const RustPodsStatus, user_data: *mut c_void)>;
+
+/// A registered callback and the pointer passed back to it
+#[derive(Clone, Copy)]
+struct Subscriber {
+    callback: unsafe extern "C" fn(*const RustPodsStatus, *mut c_void),
+    user_data: *mut c_void,
+}
+
+// The caller owns `user_data` and promised it can be used from the callback thread
+unsafe impl Send for Subscriber {}
+
+/// State shared with the monitoring task
+#[derive(Default)]
+struct Shared {
+    status: RustPodsStatus,
+    /// Address of the device the status describes
+    address: Option<btleplug::api::BDAddr>,
+    subscriber: Option<Subscriber>,
+}
+
+/// Runtime running the monitor, and the state it updates
+struct Library {
+    runtime: tokio::runtime::Runtime,
+    shared: Arc<Mutex<Shared>>,
+    /// Held while an event is applied and the callback runs, so shutdown can wait for it
+    delivering: Arc<Mutex<()>>,
+}
+
+static LIBRARY: Mutex<Option<Library>> = Mutex::new(None);
+
+impl Shared {
+    /// Fold an event into the status; returns whether it changed
+    fn apply(&mut self, event: RustPodsEvent) -> bool {
+        match event {
+            RustPodsEvent::DeviceFound(airpods) | RustPodsEvent::DeviceUpdated(airpods) => {
+                // Follow the first device until it goes away
+                if self
+                    .address
+                    .is_some_and(|address| address != airpods.address)
+                {
+                    return false;
+                }
+                self.address = Some(airpods.address);
+                self.status = RustPodsStatus::from_airpods(&airpods);
+            }
+            RustPodsEvent::BatteryChanged { address, battery } if self.address == Some(address) => {
+                self.status.set_battery(&battery);
+            }
+            RustPodsEvent::ChargingChanged { address, charging }
+                if self.address == Some(address) =>
+            {
+                self.status.set_charging(charging);
+            }
+            RustPodsEvent::DeviceLost(address) if self.address == Some(address) => {
+                self.address = None;
+                self.status = RustPodsStatus::default();
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Start scanning in the background
+///
+/// Returns [`RUSTPODS_OK`], [`RUSTPODS_ERROR_ALREADY_INITIALIZED`] or
+/// [`RUSTPODS_ERROR_RUNTIME`].
+#[no_mangle]
+pub extern "C" fn rustpods_init() -> i32 {
+    let Ok(mut library) = LIBRARY.lock() else {
+        return RUSTPODS_ERROR_RUNTIME;
+    };
+    if library.is_some() {
+        return RUSTPODS_ERROR_ALREADY_INITIALIZED;
+    }
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            log::error!("Failed to start runtime for the C interface: {}", e);
+            return RUSTPODS_ERROR_RUNTIME;
+        }
+    };
+
+    let shared = Arc::new(Mutex::new(Shared::default()));
+    let delivering = Arc::new(Mutex::new(()));
+    let (task_shared, task_delivering) = (Arc::clone(&shared), Arc::clone(&delivering));
+    runtime.spawn(async move {
+        let mut events = Box::pin(monitor());
+        while let Some(event) = events.next().await {
+            if !deliver(&task_shared, &task_delivering, event) {
+                break;
+            }
+        }
+    });
+
+    *library = Some(Library {
+        runtime,
+        shared,
+        delivering,
+    });
+    RUSTPODS_OK
+}
+
+/// Fold `event` into the status and call the subscriber if it changed; returns false
+/// once the state can't be used any more
+///
+/// The callback runs with `delivering` held but not `shared`, so it may call back in to
+/// poll or register, while shutdown waits for it to return.
+fn deliver(shared: &Mutex<Shared>, delivering: &Mutex<()>, event: RustPodsEvent) -> bool {
+    let Ok(_delivering) = delivering.lock() else {
+        return false;
+    };
+    let notify = {
+        let Ok(mut shared) = shared.lock() else {
+            return false;
+        };
+        shared
+            .apply(event)
+            .then_some((shared.status, shared.subscriber))
+    };
+    if let Some((status, Some(subscriber))) = notify {
+        unsafe { (subscriber.callback)(&status, subscriber.user_data) };
+    }
+    true
+}
+
+/// Copy the current status into `status`
+///
+/// Returns [`RUSTPODS_OK`], [`RUSTPODS_ERROR_NOT_INITIALIZED`] or
+/// [`RUSTPODS_ERROR_NULL_POINTER`].
+///
+/// # Safety
+///
+/// `status` must be null or point to writable memory for a [`RustPodsStatus`].
+#[no_mangle]
+pub unsafe extern "C" fn rustpods_poll_status(status: *mut RustPodsStatus) -> i32 {
+    if status.is_null() {
+        return RUSTPODS_ERROR_NULL_POINTER;
+    }
+    match with_shared(|shared| shared.status) {
+        Some(current) => {
+            status.write(current);
+            RUSTPODS_OK
+        }
+        None => RUSTPODS_ERROR_NOT_INITIALIZED,
+    }
+}
+
+/// Call `callback` with `user_data` whenever the status changes
+///
+/// Replaces any earlier callback; pass null to unregister. Returns [`RUSTPODS_OK`] or
+/// [`RUSTPODS_ERROR_NOT_INITIALIZED`].
+///
+/// # Safety
+///
+/// `callback` must stay callable, and `user_data` valid, until it is replaced or
+/// [`rustpods_shutdown`] returns. Both are used from a background thread. A callback
+/// that is replaced may still be running when this returns; shutdown waits for it.
+#[no_mangle]
+pub unsafe extern "C" fn rustpods_register_callback(
+    callback: RustPodsCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    let subscriber = callback.map(|callback| Subscriber {
+        callback,
+        user_data,
+    });
+    match with_shared(|shared| shared.subscriber = subscriber) {
+        Some(()) => RUSTPODS_OK,
+        None => RUSTPODS_ERROR_NOT_INITIALIZED,
+    }
+}
+
+/// Stop scanning and release the background runtime
+///
+/// Safe to call when not initialized. [`rustpods_init`] may be called again afterwards.
+/// A callback already running is waited for, and no callback runs once this returns,
+/// so `user_data` can be freed straight after. Must not be called from the callback.
+#[no_mangle]
+pub extern "C" fn rustpods_shutdown() {
+    let library = LIBRARY.lock().ok().and_then(|mut library| library.take());
+    if let Some(library) = library {
+        if let Ok(mut shared) = library.shared.lock() {
+            shared.subscriber = None;
+        }
+        // Wait out a callback that took the subscriber before it was cleared
+        drop(library.delivering.lock());
+        library.runtime.shutdown_timeout(SHUTDOWN_TIMEOUT);
+    }
+}
+
+/// Run `f` on the shared state, if initialized
+fn with_shared<T>(f: impl FnOnce(&mut Shared) -> T) -> Option<T> {
+    let library = LIBRARY.lock().ok()?;
+    let mut shared = library.as_ref()?.shared.lock().ok()?;
+    Some(f(&mut shared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airpods::AirPodsType;
+    use btleplug::api::BDAddr;
+
+    #[test]
+    fn test_status_follows_first_device() {
+        let device = |address: [u8; 6], left: u8| {
+            DetectedAirPods::new(
+                BDAddr::from(address),
+                Some("AirPods Pro".to_string()),
+                None,
+                AirPodsType::AirPodsPro,
+                Some(AirPodsBattery {
+                    left: Some(left),
+                    right: None,
+                    case: Some(50),
+                    charging: Some(AirPodsChargingState::CaseCharging),
+                }),
+                false,
+            )
+        };
+        let mut shared = Shared::default();
+
+        assert!(shared.apply(RustPodsEvent::DeviceFound(device([1; 6], 70))));
+        assert!(shared.status.connected);
+        assert_eq!(
+            (
+                shared.status.left,
+                shared.status.right,
+                shared.status.case_level
+            ),
+            (70, -1, 50)
+        );
+        assert!(shared.status.case_charging);
+        assert_eq!(
+            shared.status.name[..3],
+            [b'A' as c_char, b'i' as c_char, b'r' as c_char]
+        );
+
+        assert!(!shared.apply(RustPodsEvent::DeviceFound(device([2; 6], 10))));
+        assert!(shared.apply(RustPodsEvent::DeviceLost(BDAddr::from([1; 6]))));
+        assert!(!shared.status.connected);
+    }
+
+    unsafe extern "C" fn count_calls(_status: *const RustPodsStatus, user_data: *mut c_void) {
+        *(user_data as *mut u32) += 1;
+    }
+
+    #[test]
+    fn test_no_callback_after_subscriber_is_cleared() {
+        let device = DetectedAirPods::new(
+            BDAddr::from([1; 6]),
+            Some("AirPods Pro".to_string()),
+            None,
+            AirPodsType::AirPodsPro,
+            None,
+            false,
+        );
+        let mut calls = 0u32;
+        let shared = Mutex::new(Shared {
+            subscriber: Some(Subscriber {
+                callback: count_calls,
+                user_data: &mut calls as *mut u32 as *mut c_void,
+            }),
+            ..Shared::default()
+        });
+        let delivering = Mutex::new(());
+
+        assert!(deliver(
+            &shared,
+            &delivering,
+            RustPodsEvent::DeviceFound(device)
+        ));
+        // What shutdown does before it returns
+        shared.lock().unwrap().subscriber = None;
+        drop(delivering.lock());
+        assert!(deliver(
+            &shared,
+            &delivering,
+            RustPodsEvent::DeviceLost(BDAddr::from([1; 6]))
+        ));
+        assert_eq!(calls, 1);
+    }
+}
@@ -0,0 +1,198 @@
+//! "Report a problem": a GitHub issue pre-filled with what maintainers ask for
+//!
+//! Bug reports without a version, Windows build or error text take a round trip or two
+//! before anyone can look into them. The report collects those up front, scrubbed of
+//! device addresses and the user's home directory, and opens a new issue with it.
+//! Reports too long for a URL go to the clipboard instead, to be pasted into the issue.
+
+use std::io;
+
+use crate::diagnostics::DiagnosticResult;
+use crate::error::ErrorManager;
+use crate::privacy::scrub;
+
+/// Page for opening a new issue
+pub const NEW_ISSUE_URL: &str = "https://github.com/Jay1/RustPods/issues/new";
+
+/// Longest issue URL opened directly; browsers and GitHub reject much longer ones
+const MAX_URL_LENGTH: usize = 6000;
+
+/// Most recent errors included in a report
+const ERRORS_IN_REPORT: usize = 10;
+
+/// What a problem report says about this installation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IssueReport {
+    /// RustPods version
+    pub version: String,
+    /// Operating system and build
+    pub os: String,
+    /// Bluetooth adapter details from diagnostics
+    pub adapter: Vec<String>,
+    /// Most recent errors, oldest first
+    pub recent_errors: Vec<String>,
+    /// Issues found by diagnostics
+    pub diagnostic_summary: Vec<String>,
+}
+
+impl IssueReport {
+    /// Gather a report from the error history and, if they ran, diagnostics
+    pub fn collect(error_manager: &ErrorManager, diagnostics: Option<&DiagnosticResult>) -> Self {
+        let history = error_manager.get_error_history();
+        let recent_errors = history[history.len().saturating_sub(ERRORS_IN_REPORT)..]
+            .iter()
+            .map(|entry| {
+                scrub(&format!(
+                    "{} {}: {}",
+                    entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+                    entry.error_type,
+                    entry.error_message
+                ))
+            })
+            .collect();
+
+        let mut adapter = Vec::new();
+        let mut diagnostic_summary = Vec::new();
+        if let Some(result) = diagnostics {
+            let mut bluetooth: Vec<_> = result
+                .raw_data
+                .iter()
+                .filter(|(key, _)| key.starts_with("bluetooth"))
+                .collect();
+            bluetooth.sort();
+            adapter = bluetooth
+                .into_iter()
+                .map(|(key, value)| scrub(&format!("{}: {}", key, value)))
+                .collect();
+            diagnostic_summary = result
+                .issues
+                .iter()
+                .map(|issue| scrub(&format!("{} [{:?}]", issue.title, issue.severity)))
+                .collect();
+        }
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os: os_build(),
+            adapter,
+            recent_errors,
+            diagnostic_summary,
+        }
+    }
+
+    /// The issue body as markdown, with places for the user to describe the problem
+    pub fn markdown(&self) -> String {
+        let mut body = String::new();
+        body.push_str("### What happened?\n\n");
+        body.push_str("<!-- Describe the problem and what you expected instead -->\n\n");
+        body.push_str("### Steps to reproduce\n\n1. \n\n");
+
+        body.push_str("### Environment\n\n");
+        body.push_str(&format!("- RustPods: {}\n", self.version));
+        body.push_str(&format!("- OS: {}\n", self.os));
+        if self.adapter.is_empty() {
+            body.push_str("- Bluetooth: unknown\n");
+        }
+        for line in &self.adapter {
+            body.push_str(&format!("- {}\n", line));
+        }
+
+        body.push_str("\n### Recent errors\n\n");
+        if self.recent_errors.is_empty() {
+            body.push_str("None recorded\n");
+        } else {
+            body.push_str("```\n");
+            for error in &self.recent_errors {
+                body.push_str(&format!("{}\n", error));
+            }
+            body.push_str("```\n");
+        }
+
+        body.push_str("\n### Diagnostic summary\n\n");
+        if self.diagnostic_summary.is_empty() {
+            body.push_str("No issues found\n");
+        }
+        for issue in &self.diagnostic_summary {
+            body.push_str(&format!("- {}\n", issue));
+        }
+        body
+    }
+
+    /// New-issue URL with the report filled in, or `None` if it would be too long
+    pub fn url(&self) -> Option<String> {
+        let url = format!("{}?body={}", NEW_ISSUE_URL, encode_query(&self.markdown()));
+        (url.len() <= MAX_URL_LENGTH).then_some(url)
+    }
+}
+
+/// Operating system name and build, e.g. `Microsoft Windows [Version 10.0.22631.4317]`
+pub fn os_build() -> String {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let version = std::process::Command::new("cmd")
+            .args(["/C", "ver"])
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .output()
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+            .filter(|version| !version.is_empty());
+        if let Some(version) = version {
+            return version;
+        }
+    }
+    format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Open `url` in the default browser
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    {
+        std::process::Command::new("explorer").arg(url).spawn()?;
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!("Open {} manually", url),
+        ))
+    }
+}
+
+/// Percent-encode `text` for use as a query parameter value
+fn encode_query(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_url_is_encoded_and_bounded() {
+        let mut report = IssueReport {
+            version: "1.0.0".to_string(),
+            os: "Windows".to_string(),
+            recent_errors: vec!["Scan failed: 58:26:D7:45:AD:8B & more".to_string()],
+            ..Default::default()
+        };
+        let url = report.url().unwrap();
+        assert!(url.starts_with(NEW_ISSUE_URL));
+        assert!(url.contains("RustPods%3A%201.0.0"));
+        assert!(!url.contains(' ') && !url.contains('&'));
+
+        // Too long for a URL: the caller falls back to the clipboard
+        report.recent_errors = vec!["x".repeat(MAX_URL_LENGTH)];
+        assert_eq!(report.url(), None);
+        assert!(report.markdown().contains("### Recent errors"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Known issues, looked up by error code
+//!
+//! When an error has a documented workaround or a tracking issue, the error banner links
+//! straight to it. Entries match on the error's stable code (see
+//! [`RustPodsError::get_type`](crate::error::RustPodsError::get_type)) and optionally on
+//! part of its message. The index is bundled with the application and, if the user opts
+//! in, refreshed from the repository once a week.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::bluetooth::scanner_supervisor::output_with_timeout;
+use crate::config::ProxyConfig;
+use crate::system::proxy;
+
+/// Where a refreshed index is downloaded from
+pub const INDEX_URL: &str =
+    "https://raw.githubusercontent.com/Jay1/RustPods/main/assets/known_issues.json";
+
+/// Index bundled with this build
+const BUNDLED_INDEX: &str = include_str!("../assets/known_issues.json");
+
+/// How old the downloaded index may get before it is fetched again
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Longest a download may take
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A problem with a known workaround or tracking issue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KnownIssue {
+    /// Error code the entry applies to
+    pub code: String,
+    /// Text the error message has to contain (ignoring case), if any
+    #[serde(default)]
+    pub message_contains: Option<String>,
+    /// Short description shown with the link
+    pub title: String,
+    /// Workaround or tracking issue
+    pub url: String,
+}
+
+/// Every known issue
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct KnownIssueIndex {
+    /// Format version
+    pub version: u32,
+    /// Entries, most specific first
+    pub issues: Vec<KnownIssue>,
+}
+
+impl KnownIssueIndex {
+    /// Index bundled with this build
+    pub fn bundled() -> Self {
+        serde_json::from_str(BUNDLED_INDEX).unwrap_or_default()
+    }
+
+    /// Downloaded index if there is a readable one, otherwise the bundled index
+    pub fn load() -> Self {
+        std::fs::read_to_string(cache_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_else(Self::bundled)
+    }
+
+    /// Best entry for an error: one matching the message if there is one, otherwise
+    /// one for the code alone
+    pub fn lookup(&self, code: &str, message: &str) -> Option<&KnownIssue> {
+        let message = message.to_lowercase();
+        let for_code = || self.issues.iter().filter(|issue| issue.code == code);
+        for_code()
+            .find(|issue| {
+                issue
+                    .message_contains
+                    .as_ref()
+                    .is_some_and(|text| message.contains(&text.to_lowercase()))
+            })
+            .or_else(|| for_code().find(|issue| issue.message_contains.is_none()))
+    }
+}
+
+/// Where the downloaded index is kept
+pub fn cache_path() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("RustPods")
+        .join("known_issues.json")
+}
+
+/// Whether the index at `path` is missing or older than [`REFRESH_INTERVAL`]
+pub fn needs_refresh(path: &Path, now: SystemTime) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .map_or(true, |age| age >= REFRESH_INTERVAL)
+}
+
+/// Download the index if it is due, keeping it for [`KnownIssueIndex::load`]
+///
+/// The download goes through the proxy in `proxy`. Returns the new index, or `None` if
+/// no download was due or it failed; a failed download leaves the previous index in place.
+pub fn refresh(proxy: &ProxyConfig) -> Option<KnownIssueIndex> {
+    let path = cache_path();
+    if !needs_refresh(&path, SystemTime::now()) {
+        return None;
+    }
+
+    // curl ships with Windows 10 1803 and later
+    let mut command = Command::new("curl");
+    command.args(["--fail", "--silent", "--location", INDEX_URL]);
+    proxy::configure_command(&mut command, proxy, INDEX_URL);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = match output_with_timeout(&mut command, DOWNLOAD_TIMEOUT) {
+        Ok(output) if output.status.success() => output.stdout,
+        Ok(output) => {
+            log::warn!("Known issues download failed: {:?}", output.status.code());
+            return None;
+        }
+        Err(e) => {
+            log::warn!("Known issues download failed: {}", e);
+            return None;
+        }
+    };
+    let index: KnownIssueIndex = match serde_json::from_slice(&output) {
+        Ok(index) => index,
+        Err(e) => {
+            log::warn!("Downloaded known issues index is unreadable: {}", e);
+            return None;
+        }
+    };
+
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Err(e) = std::fs::write(&path, &output) {
+        log::warn!("Failed to keep known issues index: {}", e);
+    }
+    log::info!(
+        "Refreshed known issues index ({} entries)",
+        index.issues.len()
+    );
+    Some(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_prefers_message_match() {
+        let index = KnownIssueIndex::bundled();
+        assert!(!index.issues.is_empty());
+
+        let hang = index
+            .lookup(
+                "bluetooth_generic",
+                "AirPods scanner stopped responding and was restarted after 15s",
+            )
+            .unwrap();
+        assert!(hang.message_contains.is_some());
+
+        let fallback = index.lookup("bluetooth_generic", "Something else").unwrap();
+        assert_eq!(fallback.message_contains, None);
+
+        assert_eq!(index.lookup("no_such_code", "anything"), None);
+    }
+
+    #[test]
+    fn test_needs_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_issues.json");
+        assert!(needs_refresh(&path, SystemTime::now()));
+
+        std::fs::write(&path, "{}").unwrap();
+        assert!(!needs_refresh(&path, SystemTime::now()));
+        assert!(needs_refresh(&path, SystemTime::now() + REFRESH_INTERVAL));
+    }
+}
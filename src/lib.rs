@@ -1,13 +1,29 @@
 // Root module exports
 pub mod airpods;
 pub mod app_state_controller;
+pub mod backup;
 pub mod bluetooth;
+pub mod cli_output;
 pub mod config;
+pub mod crash;
 pub mod diagnostics;
+pub mod exit_code;
+pub mod issue_report;
+pub mod known_issues;
 pub mod lifecycle_manager;
 pub mod logging;
+pub mod monitor;
+pub mod privacy;
+pub mod quarantine;
+pub mod retry;
+pub mod rules;
+pub mod safe_mode;
+pub mod state_graph;
 pub mod state_persistence;
+pub mod system;
 pub mod telemetry;
+pub mod tray_only;
+pub mod tui;
 pub mod ui;
 
 // Module exports for library users
@@ -16,6 +32,10 @@ pub mod app_controller;
 pub mod assets;
 pub mod error;
 
+// C interface, built with `--features cdylib`
+#[cfg(feature = "cdylib")]
+pub mod ffi;
+
 // Re-export common items for convenience
 pub use airpods::{AirPodsFilter, AirPodsType, DetectedAirPods};
 pub use bluetooth::{BleEvent, BleScanner, EventBroker, EventFilter};
@@ -26,7 +46,8 @@ pub use config::AppConfig;
 pub use diagnostics::{DiagnosticLevel, DiagnosticsManager};
 pub use error::{ErrorManager, ErrorSeverity, RecoveryAction, RustPodsError};
 pub use logging::configure_logging;
-pub use telemetry::TelemetryManager;
+pub use monitor::{monitor, monitor_with_config, RustPodsEvent};
+pub use telemetry::{TelemetryManager, TelemetryReport};
 pub use ui::{run_ui, AppState, Message};
 
 /// Initialize logging with default settings
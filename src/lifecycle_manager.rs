@@ -2,7 +2,14 @@
 //!
 //! This module handles application lifecycle events including startup, shutdown,
 //! and system events like sleep/wake.
+//!
+//! Startup runs as ordered [`StartupPhase`]s: config, logging, storage, tray, scanner
+//! and UI. Each phase has a timeout and a [`FailurePolicy`]; a phase that may degrade
+//! lets startup carry on without it, while a failed phase that must abort ends it. What
+//! happened in each phase is kept in the [`StartupReport`] included in diagnostics.
 
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
@@ -27,6 +34,229 @@ pub enum LifecycleState {
     ShuttingDown,
 }
 
+/// Whether the system is currently suspended, as reported by power broadcast events
+static SYSTEM_SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// Check whether the system is currently suspended
+///
+/// Background loops (such as the periodic scanner) should skip their work while this
+/// returns `true`, since any data gathered across a suspend is unreliable.
+pub fn is_system_suspended() -> bool {
+    SYSTEM_SUSPENDED.load(Ordering::SeqCst)
+}
+
+/// Record a suspend/resume transition
+fn set_system_suspended(suspended: bool) {
+    SYSTEM_SUSPENDED.store(suspended, Ordering::SeqCst);
+}
+
+/// Phases run so far in this process, in the order they finished
+static STARTUP_REPORT: Mutex<Vec<PhaseRecord>> = Mutex::new(Vec::new());
+
+/// Steps of application startup, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StartupPhase {
+    /// Load the settings file
+    Config,
+    /// Set up the log file and console logging
+    Logging,
+    /// Prepare the data folder and load battery profiles
+    Storage,
+    /// Show the system tray icon
+    Tray,
+    /// Find the CLI scanner
+    Scanner,
+    /// Build the window state
+    Ui,
+}
+
+/// What happens to startup when a phase fails or times out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// Stop starting up
+    Abort,
+    /// Carry on without what the phase provides
+    Degrade,
+}
+
+impl StartupPhase {
+    /// Every phase, in the order they run
+    pub const ALL: [StartupPhase; 6] = [
+        StartupPhase::Config,
+        StartupPhase::Logging,
+        StartupPhase::Storage,
+        StartupPhase::Tray,
+        StartupPhase::Scanner,
+        StartupPhase::Ui,
+    ];
+
+    /// Name used in logs and the startup report
+    pub fn name(self) -> &'static str {
+        match self {
+            StartupPhase::Config => "config",
+            StartupPhase::Logging => "logging",
+            StartupPhase::Storage => "storage",
+            StartupPhase::Tray => "tray",
+            StartupPhase::Scanner => "scanner",
+            StartupPhase::Ui => "ui",
+        }
+    }
+
+    /// Longest the phase may take
+    pub fn timeout(self) -> Duration {
+        match self {
+            StartupPhase::Config | StartupPhase::Logging | StartupPhase::Scanner => {
+                Duration::from_secs(5)
+            }
+            // Battery profiles can be large, and the tray waits for Explorer
+            StartupPhase::Storage | StartupPhase::Tray => Duration::from_secs(10),
+            StartupPhase::Ui => Duration::from_secs(30),
+        }
+    }
+
+    /// What a failure of the phase means for startup
+    pub fn failure_policy(self) -> FailurePolicy {
+        match self {
+            StartupPhase::Ui => FailurePolicy::Abort,
+            _ => FailurePolicy::Degrade,
+        }
+    }
+
+    /// What the user loses when the phase degrades
+    pub fn degraded_effect(self) -> &'static str {
+        match self {
+            StartupPhase::Config => "default settings are used",
+            StartupPhase::Logging => "nothing is written to the log file",
+            StartupPhase::Storage => "battery data is kept in memory only",
+            StartupPhase::Tray => "there is no tray icon",
+            StartupPhase::Scanner => "scans fail until the scanner is installed",
+            StartupPhase::Ui => "the window can't be shown",
+        }
+    }
+}
+
+impl fmt::Display for StartupPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// How a startup phase ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PhaseOutcome {
+    /// The phase finished
+    Completed,
+    /// The phase failed with this reason
+    Failed(String),
+    /// The phase took longer than its timeout and was abandoned
+    TimedOut,
+}
+
+/// One phase in the startup report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseRecord {
+    /// The phase
+    pub phase: StartupPhase,
+    /// How it ended
+    pub outcome: PhaseOutcome,
+    /// How long it took, or the timeout if it was abandoned
+    pub elapsed: Duration,
+}
+
+impl PhaseRecord {
+    /// Whether startup carried on without the phase
+    pub fn degraded(&self) -> bool {
+        self.outcome != PhaseOutcome::Completed
+            && self.phase.failure_policy() == FailurePolicy::Degrade
+    }
+
+    /// Whether the phase ended startup
+    pub fn aborted(&self) -> bool {
+        self.outcome != PhaseOutcome::Completed
+            && self.phase.failure_policy() == FailurePolicy::Abort
+    }
+}
+
+impl fmt::Display for PhaseRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let elapsed = self.elapsed.as_millis();
+        match &self.outcome {
+            PhaseOutcome::Completed if self.elapsed > self.phase.timeout() => write!(
+                f,
+                "{}: completed in {}ms, over its {}s timeout",
+                self.phase,
+                elapsed,
+                self.phase.timeout().as_secs()
+            ),
+            PhaseOutcome::Completed => write!(f, "{}: completed in {}ms", self.phase, elapsed),
+            PhaseOutcome::Failed(reason) => {
+                write!(f, "{}: failed after {}ms: {}", self.phase, elapsed, reason)
+            }
+            PhaseOutcome::TimedOut => write!(
+                f,
+                "{}: timed out after {}s",
+                self.phase,
+                self.phase.timeout().as_secs()
+            ),
+        }?;
+        if self.degraded() {
+            write!(f, " ({})", self.phase.degraded_effect())?;
+        }
+        Ok(())
+    }
+}
+
+/// What happened in each startup phase of this process
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StartupReport {
+    /// Phases that have run, in the order they finished
+    pub phases: Vec<PhaseRecord>,
+}
+
+impl StartupReport {
+    /// Record of `phase`, if it has run
+    pub fn phase(&self, phase: StartupPhase) -> Option<&PhaseRecord> {
+        self.phases.iter().find(|record| record.phase == phase)
+    }
+
+    /// Phases startup carried on without
+    pub fn degraded(&self) -> impl Iterator<Item = &PhaseRecord> {
+        self.phases.iter().filter(|record| record.degraded())
+    }
+
+    /// The phase that ended startup, if one did
+    pub fn aborted(&self) -> Option<&PhaseRecord> {
+        self.phases.iter().find(|record| record.aborted())
+    }
+
+    /// Time spent in all phases
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|record| record.elapsed).sum()
+    }
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for phase in StartupPhase::ALL {
+            match self.phase(phase) {
+                Some(record) => writeln!(f, "{}", record)?,
+                None => writeln!(f, "{}: not run", phase)?,
+            }
+        }
+        write!(f, "Total: {}ms", self.total().as_millis())
+    }
+}
+
+/// Startup phases run so far in this process
+pub fn startup_report() -> StartupReport {
+    StartupReport {
+        phases: STARTUP_REPORT
+            .lock()
+            .map(|phases| phases.clone())
+            .unwrap_or_default(),
+    }
+}
+
 /// Application lifecycle manager
 pub struct LifecycleManager {
     /// Current lifecycle state
@@ -70,6 +300,119 @@ impl Clone for LifecycleManager {
 }
 
 impl LifecycleManager {
+    /// Run a startup phase on a worker thread, giving up on it after its timeout
+    ///
+    /// Returns the phase's value, or `None` if it failed or timed out and its policy
+    /// lets startup degrade. An error means startup must stop. A phase that times out
+    /// keeps running in the background, and whatever it returns later is dropped.
+    pub fn run_phase<T, F>(phase: StartupPhase, work: F) -> Result<Option<T>, String>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> Result<T, String> + Send + 'static,
+    {
+        log::info!("Startup phase {} starting", phase);
+        let started = Instant::now();
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        let spawned = std::thread::Builder::new()
+            .name(format!("startup-{}", phase))
+            .spawn(move || {
+                let _ = sender.send(work());
+            });
+
+        let result = match spawned {
+            Ok(_) => match receiver.recv_timeout(phase.timeout()) {
+                Ok(result) => result.map_err(PhaseOutcome::Failed),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => Err(PhaseOutcome::TimedOut),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    Err(PhaseOutcome::Failed("the phase panicked".to_string()))
+                }
+            },
+            Err(e) => Err(PhaseOutcome::Failed(format!(
+                "couldn't start a thread: {}",
+                e
+            ))),
+        };
+        Self::finish_phase(phase, started, result)
+    }
+
+    /// Run a startup phase on this thread, for work that can't leave it
+    ///
+    /// The timeout can't interrupt the work; a phase that overruns it is only reported.
+    pub fn run_phase_here<T>(
+        phase: StartupPhase,
+        work: impl FnOnce() -> Result<T, String>,
+    ) -> Result<Option<T>, String> {
+        log::info!("Startup phase {} starting", phase);
+        let started = Instant::now();
+        let result = work().map_err(PhaseOutcome::Failed);
+        Self::finish_phase(phase, started, result)
+    }
+
+    /// Record a failure of `phase` found after it was run, e.g. the window failing to open
+    ///
+    /// A phase that already completed is left as it was. Returns an error if the phase's
+    /// policy means startup must stop.
+    pub fn fail_phase(phase: StartupPhase, reason: impl Into<String>) -> Result<(), String> {
+        if startup_report()
+            .phase(phase)
+            .is_some_and(|record| record.outcome == PhaseOutcome::Completed)
+        {
+            return Ok(());
+        }
+        Self::finish_phase::<()>(
+            phase,
+            Instant::now(),
+            Err(PhaseOutcome::Failed(reason.into())),
+        )
+        .map(|_| ())
+    }
+
+    fn finish_phase<T>(
+        phase: StartupPhase,
+        started: Instant,
+        result: Result<T, PhaseOutcome>,
+    ) -> Result<Option<T>, String> {
+        let (value, outcome) = match result {
+            Ok(value) => (Some(value), PhaseOutcome::Completed),
+            Err(outcome) => (None, outcome),
+        };
+        let elapsed = match outcome {
+            PhaseOutcome::TimedOut => phase.timeout(),
+            _ => started.elapsed(),
+        };
+        let record = PhaseRecord {
+            phase,
+            outcome,
+            elapsed,
+        };
+
+        if record.aborted() {
+            log::error!("Startup phase {}; stopping", record);
+        } else if record.degraded() {
+            log::warn!("Startup phase {}", record);
+        } else {
+            log::info!("Startup phase {}", record);
+        }
+
+        let aborted = record.aborted().then(|| record.to_string());
+        if let Ok(mut phases) = STARTUP_REPORT.lock() {
+            if let Some(later) = phases.iter().find(|earlier| earlier.phase > phase) {
+                log::warn!(
+                    "Startup phase {} finished after the later {} phase",
+                    phase,
+                    later.phase
+                );
+            }
+            phases.retain(|earlier| earlier.phase != phase);
+            phases.push(record);
+        }
+
+        match aborted {
+            Some(reason) => Err(format!("Startup stopped at {}", reason)),
+            None => Ok(value),
+        }
+    }
+
     /// Create a new lifecycle manager
     pub fn new(
         state_manager: Arc<StateManager>,
@@ -224,9 +567,13 @@ impl LifecycleManager {
         let _persistence_manager = self.persistence_manager.clone();
 
         // Create the system event task
-        // Win32 system event monitoring is currently disabled due to removal of unsupported Win32 imports.
-        // See docs/windows-ble-airpods.md for details. Only platform-agnostic or WinRT-relevant code should remain here.
-        // No 'task' is created for Windows at this time.
+        // On Windows, suspend/resume is delivered as WM_POWERBROADCAST to a hidden
+        // message-only window running on a dedicated thread.
+        #[cfg(target_os = "windows")]
+        {
+            self.system_event_task = spawn_power_event_monitor(self.ui_sender.clone());
+        }
+
         #[cfg(not(target_os = "windows"))]
         let task = tokio::spawn(async move {
             log::info!("Starting generic system event monitor");
@@ -261,10 +608,6 @@ impl LifecycleManager {
         {
             self.system_event_task = Some(task);
         }
-        #[cfg(target_os = "windows")]
-        {
-            self.system_event_task = None;
-        }
     }
 
     /// Handle shutdown request
@@ -347,6 +690,7 @@ impl LifecycleManager {
             let mut state = self.state.lock().unwrap();
             *state = LifecycleState::Sleep;
         }
+        set_system_suspended(true);
 
         // Save state before sleep
         if let Err(e) = self.force_save() {
@@ -367,6 +711,7 @@ impl LifecycleManager {
             let mut state = self.state.lock().unwrap();
             *state = LifecycleState::Running;
         }
+        set_system_suspended(false);
 
         // Notify state manager
         let wake_action = crate::ui::state_manager::Action::SystemWake;
@@ -699,3 +1044,219 @@ fn cleanup_old_recovery_files() -> Result<(), String> {
 
     Ok(())
 }
+
+/// Sender used by the power broadcast window procedure to notify the UI
+///
+/// Weak, so the monitor doesn't keep the UI's channel open after everything else let go.
+static POWER_EVENT_SENDER: Mutex<Option<mpsc::WeakUnboundedSender<Message>>> = Mutex::new(None);
+
+/// Send power events to `ui_sender` from now on; returns whether a sender was set before
+fn set_power_event_sender(ui_sender: &mpsc::UnboundedSender<Message>) -> bool {
+    let mut guard = POWER_EVENT_SENDER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    guard.replace(ui_sender.downgrade()).is_some()
+}
+
+/// The sender power events go to, while its channel is open
+pub fn power_event_sender() -> Option<mpsc::UnboundedSender<Message>> {
+    POWER_EVENT_SENDER
+        .lock()
+        .ok()?
+        .as_ref()
+        .and_then(mpsc::WeakUnboundedSender::upgrade)
+}
+
+/// Start monitoring system suspend/resume events
+///
+/// Messages `Message::SystemSuspended` and `Message::SystemResumed` are sent to
+/// `ui_sender` as the system goes to sleep and wakes up. Only one monitor thread is
+/// started per process; later calls just replace the sender.
+#[cfg(target_os = "windows")]
+pub fn spawn_power_event_monitor(
+    ui_sender: mpsc::UnboundedSender<Message>,
+) -> Option<std::thread::JoinHandle<()>> {
+    if set_power_event_sender(&ui_sender) {
+        log::debug!("Power event monitor already running, updated UI sender");
+        return None;
+    }
+
+    let spawn_result = std::thread::Builder::new()
+        .name("power-events".to_string())
+        .spawn(|| {
+            if let Err(e) = run_power_event_loop() {
+                log::error!("Power event monitor stopped: {}", e);
+            }
+        });
+
+    match spawn_result {
+        Ok(handle) => Some(handle),
+        Err(e) => {
+            log::error!("Failed to start power event monitor thread: {}", e);
+            None
+        }
+    }
+}
+
+/// Start monitoring system suspend/resume events
+///
+/// Power broadcast events are only wired up on Windows; other platforms rely on
+/// `LifecycleManager::handle_sleep`/`handle_wake` being called explicitly.
+#[cfg(not(target_os = "windows"))]
+pub fn spawn_power_event_monitor(
+    ui_sender: mpsc::UnboundedSender<Message>,
+) -> Option<std::thread::JoinHandle<()>> {
+    set_power_event_sender(&ui_sender);
+    log::debug!("Power event monitoring is not available on this platform");
+    None
+}
+
+/// Run a message-only window that receives WM_POWERBROADCAST
+#[cfg(target_os = "windows")]
+fn run_power_event_loop() -> Result<(), String> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{HINSTANCE, HWND};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DispatchMessageW, GetMessageW, RegisterClassW, TranslateMessage, HMENU,
+        HWND_MESSAGE, MSG, WINDOW_EX_STYLE, WINDOW_STYLE, WNDCLASSW,
+    };
+
+    unsafe {
+        let instance: HINSTANCE = GetModuleHandleW(PCWSTR::null())
+            .map_err(|e| format!("GetModuleHandleW failed: {}", e))?
+            .into();
+        let class_name = w!("RustPodsPowerEvents");
+
+        let window_class = WNDCLASSW {
+            lpfnWndProc: Some(power_event_wndproc),
+            hInstance: instance,
+            lpszClassName: class_name,
+            ..Default::default()
+        };
+        if RegisterClassW(&window_class) == 0 {
+            return Err("RegisterClassW failed".to_string());
+        }
+
+        let _hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            class_name,
+            w!("RustPods Power Events"),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            HMENU::default(),
+            instance,
+            None,
+        )
+        .map_err(|e| format!("CreateWindowExW failed: {}", e))?;
+
+        log::info!("Power event monitor started");
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, HWND::default(), 0, 0).as_bool() {
+            let _ = TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+
+    Ok(())
+}
+
+/// Window procedure translating power broadcasts into UI messages
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn power_event_wndproc(
+    hwnd: windows::Win32::Foundation::HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DefWindowProcW, PBT_APMRESUMEAUTOMATIC, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND,
+        WM_POWERBROADCAST,
+    };
+
+    if msg == WM_POWERBROADCAST {
+        let event = match wparam.0 as u32 {
+            PBT_APMSUSPEND => {
+                log::info!("System is suspending");
+                set_system_suspended(true);
+                Some(Message::SystemSuspended)
+            }
+            // Both resume events may arrive for a single wake; only forward the first
+            PBT_APMRESUMEAUTOMATIC | PBT_APMRESUMESUSPEND if is_system_suspended() => {
+                log::info!("System resumed from suspend");
+                set_system_suspended(false);
+                Some(Message::SystemResumed)
+            }
+            _ => None,
+        };
+
+        if let Some(event) = event {
+            if let Some(sender) = power_event_sender() {
+                if let Err(e) = sender.send(event) {
+                    log::warn!("Failed to forward power event to UI: {}", e);
+                }
+            }
+        }
+        return windows::Win32::Foundation::LRESULT(1);
+    }
+
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_phases_degrade_or_abort() {
+        assert_eq!(
+            LifecycleManager::run_phase(StartupPhase::Tray, || Ok(7)),
+            Ok(Some(7))
+        );
+        assert_eq!(
+            LifecycleManager::run_phase::<(), _>(StartupPhase::Scanner, || {
+                Err("scanner missing".to_string())
+            }),
+            Ok(None)
+        );
+
+        let report = startup_report();
+        assert_eq!(
+            report
+                .phase(StartupPhase::Tray)
+                .map(|record| &record.outcome),
+            Some(&PhaseOutcome::Completed)
+        );
+        assert!(report
+            .degraded()
+            .any(|record| record.phase == StartupPhase::Scanner));
+
+        let ui = PhaseRecord {
+            phase: StartupPhase::Ui,
+            outcome: PhaseOutcome::Failed("no graphics adapter".to_string()),
+            elapsed: Duration::from_millis(20),
+        };
+        assert!(ui.aborted());
+        assert!(!ui.degraded());
+    }
+
+    #[test]
+    fn test_report_lists_every_phase() {
+        let report = StartupReport {
+            phases: vec![PhaseRecord {
+                phase: StartupPhase::Storage,
+                outcome: PhaseOutcome::TimedOut,
+                elapsed: StartupPhase::Storage.timeout(),
+            }],
+        };
+        let text = report.to_string();
+        assert!(text.contains("config: not run"));
+        assert!(text.contains("storage: timed out after 10s (battery data is kept in memory only)"));
+        assert_eq!(text.lines().count(), StartupPhase::ALL.len() + 1);
+    }
+}
@@ -2,12 +2,18 @@
 //!
 //! This module provides a structured logging setup that integrates with the
 //! error handling system and provides context-aware logs with selective debug categories.
+//!
+//! Each category (ui, bluetooth, airpods, config, system) can also be given its own
+//! level while the application runs, e.g. from the Advanced settings panel, without
+//! restarting. A category with its own level ignores the process-wide level and debug
+//! flags.
 
 use chrono::Local;
 use log::Level;
 use log::{LevelFilter, Metadata, Record};
 use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Debug;
 use std::fs::{File, OpenOptions};
 use std::io::{self, Write};
@@ -15,7 +21,7 @@ use std::path::PathBuf;
 use std::sync::Mutex;
 use std::sync::{Once, RwLock};
 
-use crate::config::LogLevel;
+use crate::config::{LogCategory, LogLevel};
 use crate::error::ErrorContext;
 
 /// Timestamp format for log entries
@@ -27,9 +33,15 @@ const MAX_LOG_FILES: usize = 1;
 /// Maximum number of battery profile files to keep
 const MAX_BATTERY_FILES: usize = 50;
 
+/// Number of recent log lines kept in memory for crash reports
+const RECENT_LOG_CAPACITY: usize = 200;
+
 /// Global initialization guard
 static INIT_LOGGER: Once = Once::new();
 
+/// Most recent log lines, oldest first
+static RECENT_LOG_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
 /// Get the application data directory
 fn get_app_data_dir() -> Result<PathBuf, String> {
     let data_dir = dirs::data_local_dir()
@@ -71,6 +83,12 @@ static DEBUG_FLAGS: RwLock<DebugFlags> = RwLock::new(DebugFlags {
     all: false,
 });
 
+/// Level the logger was configured with
+static BASE_LEVEL: RwLock<LevelFilter> = RwLock::new(LevelFilter::Info);
+
+/// Levels set for single categories, replacing the base level and debug flags for them
+static CATEGORY_LEVELS: RwLock<BTreeMap<LogCategory, LevelFilter>> = RwLock::new(BTreeMap::new());
+
 /// Custom logger implementation for RustPods
 pub struct RustPodsLogger {
     /// File output for logs
@@ -83,6 +101,10 @@ pub struct RustPodsLogger {
 
 impl log::Log for RustPodsLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
+        if let Some(level) = category_for(metadata.target()).and_then(category_level) {
+            return metadata.level() <= level;
+        }
+
         // Always allow warn and error
         if metadata.level() <= Level::Warn {
             return metadata.level() <= self.level;
@@ -128,14 +150,14 @@ impl log::Log for RustPodsLogger {
             record.line().unwrap_or(0)
         );
 
+        // Hash device addresses when privacy mode is on
+        let message = record.args().to_string();
+        let message = crate::privacy::redact(&message);
+
         // Format log entry with colors for console
         let console_entry = format!(
             "[{}] {} [{}] [{}] {}\n",
-            timestamp,
-            level_str,
-            module,
-            file_info,
-            record.args()
+            timestamp, level_str, module, file_info, message
         );
 
         // Plain format for file
@@ -145,7 +167,7 @@ impl log::Log for RustPodsLogger {
             record.level(),
             module,
             file_info,
-            record.args()
+            message
         );
 
         // Output to console if enabled
@@ -160,6 +182,8 @@ impl log::Log for RustPodsLogger {
                 let _ = file.flush();
             }
         }
+
+        remember_log_line(file_entry.trim_end());
     }
 
     fn flush(&self) {
@@ -257,14 +281,10 @@ pub fn configure_logging(
             }
         }
 
-        // Convert LogLevel to LevelFilter
-        let level_filter = match level {
-            LogLevel::Error => LevelFilter::Error,
-            LogLevel::Warn => LevelFilter::Warn,
-            LogLevel::Info => LevelFilter::Info,
-            LogLevel::Debug => LevelFilter::Debug,
-            LogLevel::Trace => LevelFilter::Trace,
-        };
+        let level_filter = level_filter(&level);
+        if let Ok(mut base) = BASE_LEVEL.write() {
+            *base = level_filter;
+        }
 
         // Open log file if path is provided
         let file = if let Some(path) = log_file.clone() {
@@ -302,7 +322,7 @@ pub fn configure_logging(
             return;
         }
 
-        log::set_max_level(level_filter);
+        update_max_level();
 
         // Log the initialization
         log::info!("Logging initialized at level: {}", level);
@@ -314,6 +334,31 @@ pub fn configure_logging(
     result
 }
 
+/// Keep a log line in the in-memory buffer used for crash reports
+fn remember_log_line(line: &str) {
+    if let Ok(mut lines) = RECENT_LOG_LINES.lock() {
+        if lines.len() == RECENT_LOG_CAPACITY {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_string());
+    }
+}
+
+/// Get up to `count` of the most recent log lines, oldest first
+///
+/// Never blocks, so it is safe to call from a panic hook; returns nothing if the
+/// buffer is busy.
+pub fn recent_log_lines(count: usize) -> Vec<String> {
+    match RECENT_LOG_LINES.try_lock() {
+        Ok(lines) => lines
+            .iter()
+            .skip(lines.len().saturating_sub(count))
+            .cloned()
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
 /// Set global debug flags for selective logging
 pub fn set_debug_flags(flags: DebugFlags) {
     if let Ok(mut debug_flags) = DEBUG_FLAGS.write() {
@@ -321,54 +366,115 @@ pub fn set_debug_flags(flags: DebugFlags) {
     }
 }
 
-/// Check if a debug category should log based on the module path and global flags
-pub fn should_log_debug(module_path: &str) -> bool {
-    if let Ok(flags) = DEBUG_FLAGS.read() {
-        if flags.all {
-            return true;
-        }
+/// Convert a configured level to a filter
+fn level_filter(level: &LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Error => LevelFilter::Error,
+        LogLevel::Warn => LevelFilter::Warn,
+        LogLevel::Info => LevelFilter::Info,
+        LogLevel::Debug => LevelFilter::Debug,
+        LogLevel::Trace => LevelFilter::Trace,
+    }
+}
 
-        // Check module path against debug categories - improved matching
-        // UI category: ui module, system_tray, window management
-        if module_path.contains("ui")
-            || module_path.contains("system_tray")
-            || module_path.contains("window")
-            || module_path.contains("main_window")
-            || module_path.contains("state")
-        // state.rs is in ui module
-        {
-            return flags.ui;
-        }
+/// Replace the per-category levels; categories not listed go back to the base level
+///
+/// Takes effect for the next message, so it can be called at any time.
+pub fn set_category_levels(levels: &BTreeMap<LogCategory, LogLevel>) {
+    if let Ok(mut categories) = CATEGORY_LEVELS.write() {
+        *categories = levels
+            .iter()
+            .map(|(category, level)| (*category, level_filter(level)))
+            .collect();
+    }
+    update_max_level();
+}
 
-        // Bluetooth category: bluetooth module, CLI scanner, adapters
-        if module_path.contains("bluetooth")
-            || module_path.contains("cli_scanner")
-            || module_path.contains("adapter")
-            || module_path.contains("peripheral")
-        {
-            return flags.bluetooth;
-        }
+/// Level set for `category`, if it has its own
+pub fn category_level(category: LogCategory) -> Option<LevelFilter> {
+    CATEGORY_LEVELS
+        .read()
+        .ok()
+        .and_then(|categories| categories.get(&category).copied())
+}
 
-        // AirPods category: airpods module, battery info
-        if module_path.contains("airpods") || module_path.contains("battery") {
-            return flags.airpods;
-        }
+/// Let through the most verbose level any category or the base level asks for
+fn update_max_level() {
+    let base = BASE_LEVEL
+        .read()
+        .map(|base| *base)
+        .unwrap_or(LevelFilter::Info);
+    let categories = CATEGORY_LEVELS
+        .read()
+        .ok()
+        .and_then(|categories| categories.values().max().copied())
+        .unwrap_or(LevelFilter::Off);
+    log::set_max_level(base.max(categories));
+}
 
-        // Config category: config module, validation
-        if module_path.contains("config") || module_path.contains("validation") {
-            return flags.config;
-        }
+/// Category of the module at `module_path`, if it belongs to one
+pub fn category_for(module_path: &str) -> Option<LogCategory> {
+    // UI category: ui module, system_tray, window management; state.rs is in ui module
+    if module_path.contains("ui")
+        || module_path.contains("system_tray")
+        || module_path.contains("window")
+        || module_path.contains("main_window")
+        || module_path.contains("state")
+    {
+        return Some(LogCategory::Ui);
+    }
 
-        // System category: lifecycle, persistence, telemetry, diagnostics
-        if module_path.contains("lifecycle")
-            || module_path.contains("persistence")
-            || module_path.contains("telemetry")
-            || module_path.contains("diagnostics")
-        {
-            return flags.system;
-        }
+    // Bluetooth category: bluetooth module, CLI scanner, adapters
+    if module_path.contains("bluetooth")
+        || module_path.contains("cli_scanner")
+        || module_path.contains("adapter")
+        || module_path.contains("peripheral")
+    {
+        return Some(LogCategory::Bluetooth);
+    }
+
+    // AirPods category: airpods module, battery info
+    if module_path.contains("airpods") || module_path.contains("battery") {
+        return Some(LogCategory::AirPods);
+    }
+
+    // Config category: config module, validation
+    if module_path.contains("config") || module_path.contains("validation") {
+        return Some(LogCategory::Config);
+    }
+
+    // System category: lifecycle, persistence, telemetry, diagnostics
+    if module_path.contains("lifecycle")
+        || module_path.contains("persistence")
+        || module_path.contains("telemetry")
+        || module_path.contains("diagnostics")
+    {
+        return Some(LogCategory::System);
+    }
+    None
+}
+
+/// Check if a debug category should log based on the module path and global flags
+pub fn should_log_debug(module_path: &str) -> bool {
+    let category = category_for(module_path);
+    if let Some(level) = category.and_then(category_level) {
+        return level >= LevelFilter::Debug;
+    }
+
+    let Ok(flags) = DEBUG_FLAGS.read() else {
+        return false;
+    };
+    if flags.all {
+        return true;
+    }
+    match category {
+        Some(LogCategory::Ui) => flags.ui,
+        Some(LogCategory::Bluetooth) => flags.bluetooth,
+        Some(LogCategory::AirPods) => flags.airpods,
+        Some(LogCategory::Config) => flags.config,
+        Some(LogCategory::System) => flags.system,
+        None => false,
     }
-    false
 }
 
 /// Conditional debug logging macro that respects debug flags
@@ -857,6 +963,36 @@ mod tests {
         assert!(log_path.exists());
     }
 
+    #[test]
+    fn test_category_levels_replace_debug_flags() {
+        assert_eq!(
+            category_for("rustpods::bluetooth::scanner"),
+            Some(LogCategory::Bluetooth)
+        );
+        assert_eq!(
+            category_for("rustpods::airpods::detector"),
+            Some(LogCategory::AirPods)
+        );
+        assert_eq!(category_for("rustpods::crash"), None);
+
+        // A module no other test logs from, so the global levels can be changed here
+        let module = "rustpods::config::validation";
+        assert!(!should_log_debug(module));
+
+        let levels = BTreeMap::from([(LogCategory::Config, LogLevel::Debug)]);
+        set_category_levels(&levels);
+        assert_eq!(
+            category_level(LogCategory::Config),
+            Some(LevelFilter::Debug)
+        );
+        assert!(should_log_debug(module));
+        assert!(log::max_level() >= LevelFilter::Debug);
+
+        set_category_levels(&BTreeMap::new());
+        assert_eq!(category_level(LogCategory::Config), None);
+        assert!(!should_log_debug(module));
+    }
+
     #[test]
     fn test_performance_logger() {
         // Setup logger
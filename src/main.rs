@@ -10,22 +10,40 @@ pub mod app;
 pub mod app_controller;
 pub mod app_state_controller;
 pub mod assets;
+pub mod backup;
 pub mod bluetooth;
+pub mod cli_output;
 pub mod config;
+pub mod crash;
 pub mod diagnostics;
 pub mod error;
+pub mod exit_code;
+pub mod issue_report;
+pub mod known_issues;
 pub mod lifecycle_manager;
 pub mod logging;
+pub mod monitor;
+pub mod privacy;
+pub mod quarantine;
+pub mod retry;
+pub mod rules;
+pub mod safe_mode;
+pub mod state_graph;
 pub mod state_persistence;
+pub mod system;
 pub mod telemetry;
+pub mod tray_only;
+pub mod tui;
 pub mod ui;
 
 use std::env;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
-use config::{AppConfig, LogLevel};
-use error::{ErrorContext, ErrorManager, RustPodsError};
+use config::{AppConfig, ChangeSource, LogLevel};
+use error::{BluetoothError, ErrorContext, ErrorManager, RustPodsError};
+use exit_code::{CommandFailure, ExitCode};
+use lifecycle_manager::{LifecycleManager, StartupPhase};
 use log::{error, info};
 use telemetry::TelemetryManager;
 use tokio::sync::mpsc;
@@ -37,16 +55,45 @@ use crate::logging::DebugFlags;
 #[derive(Debug, Clone)]
 pub enum AppCommand {
     Adapters,
-    Scan,
+    /// Scan for nearby devices, or only for the device at `address`
+    Scan {
+        address: Option<String>,
+    },
     Interval,
+    /// Print the battery levels from one quick scan and exit
+    Status,
     AirPods,
     Events,
     UI,
     StateUI,    // New command for using the state-based UI
     Diagnostic, // New command for running diagnostics
+    Config(ConfigAction),
+    /// Compare two advertisement captures byte by byte
+    Diff(PathBuf, PathBuf),
+    /// Print the annotated parse of Apple manufacturer data given as hex
+    Decode(String),
+    /// Import scanner captures and legacy battery logs into the battery history
+    Import(Vec<PathBuf>),
+    /// Interactive terminal dashboard
+    Tui,
+    /// Print DOT graphs of the state machines and message flow; all of them if `None`
+    Graph(Option<state_graph::Graph>),
     Help,
 }
 
+/// `rustpods config` subcommands
+#[derive(Debug, Clone)]
+pub enum ConfigAction {
+    /// Print the value at a dot-path
+    Get(String),
+    /// Set the value at a dot-path
+    Set(String, String),
+    /// Store a secret in the Credential Manager and point the setting at a dot-path to it
+    SetSecret(String, String),
+    /// List all keys with their current values
+    List,
+}
+
 /// Command line arguments structure
 #[derive(Debug, Clone)]
 pub struct AppArgs {
@@ -55,6 +102,12 @@ pub struct AppArgs {
     pub log_level: LogLevel,
     pub verbose: bool,      // Legacy verbose flag (same as --debug-all)
     pub test_battery: bool, // Enable battery estimation test mode
+    pub no_color: bool,     // Plain output even on a terminal
+    pub json_errors: bool,  // Failures printed as a JSON object on stderr
+    pub overrides: Vec<config::ConfigOverride>, // --set settings for this run only
+    pub safe_mode: bool,    // Skip battery history, action rules and custom themes
+    pub software_render: bool, // Draw the window without the GPU
+    pub tray_only: bool,    // Run without the main window
 }
 
 fn main() {
@@ -85,18 +138,42 @@ fn main() {
             }
             #[cfg(not(all(windows, not(debug_assertions))))]
             {
-                eprintln!("Error parsing arguments: {}", e);
-                print_usage();
+                if requested_json_errors() {
+                    exit_code::set_json_errors(true);
+                    CommandFailure::usage(e).report();
+                } else {
+                    eprintln!("Error parsing arguments: {}", e);
+                    print_usage();
+                }
             }
-            std::process::exit(1);
+            std::process::exit(ExitCode::Usage.code());
         }
     };
 
-    // Load or create a configuration file first to get logging settings
-    let mut config = match config::load_or_create_config() {
-        Ok(cfg) => cfg,
-        Err(e) => {
-            eprintln!("Error loading configuration: {}", e);
+    cli_output::init(args.no_color);
+    exit_code::set_json_errors(args.json_errors);
+    config::overrides::set_command_line(args.overrides.clone());
+    if args.safe_mode {
+        safe_mode::enable();
+    }
+    if args.software_render {
+        ui::render_backend::use_software();
+    }
+
+    // Startup phase: load or create a configuration file first to get logging settings
+    let config = LifecycleManager::run_phase(StartupPhase::Config, || {
+        config::load_or_create_config().map_err(|e| e.detailed())
+    });
+    let mut config = match config {
+        Ok(Some(cfg)) => cfg,
+        _ => {
+            eprintln!(
+                "{}",
+                cli_output::error(format!(
+                    "Error loading configuration: {}",
+                    phase_failure(StartupPhase::Config)
+                ))
+            );
             AppConfig::default()
         }
     };
@@ -104,8 +181,19 @@ fn main() {
     // Override config log level with command line arguments
     config.system.log_level = args.log_level.clone();
 
+    // Retries anywhere in the app follow the configured policies
+    retry::configure(&config.system.retry);
+
+    // Hash device addresses before anything is logged
+    if privacy::apply(&mut config.system) {
+        if let Err(e) = config.save() {
+            eprintln!("Failed to save privacy mode salt: {}", e);
+        }
+    }
+
     // Store debug flags globally for use by other modules
     logging::set_debug_flags(args.debug_flags.clone());
+    logging::set_category_levels(&config.system.category_log_levels);
 
     // Determine effective log level: upgrade to Debug if any debug flags are enabled
     let effective_log_level = if args.debug_flags.any_enabled() {
@@ -125,10 +213,26 @@ fn main() {
         chrono::Local::now().format("%Y%m%d_%H%M%S")
     ));
 
-    if let Err(e) = logging::configure_logging(effective_log_level, Some(log_file), true) {
-        eprintln!("Failed to setup logging: {}", e);
+    // Log lines written to the console would tear through the dashboard, and would be
+    // mixed into the JSON a script reads from stderr
+    let console_logging = !matches!(args.command, AppCommand::Tui) && !args.json_errors;
+    let logging = LifecycleManager::run_phase(StartupPhase::Logging, move || {
+        logging::configure_logging(effective_log_level, Some(log_file), console_logging)
+    });
+    if !matches!(logging, Ok(Some(()))) {
+        eprintln!(
+            "Failed to setup logging: {}",
+            phase_failure(StartupPhase::Logging)
+        );
     }
 
+    // Write a crash report (and bring the tray back) if the application panics
+    let is_ui_command = matches!(args.command, AppCommand::UI | AppCommand::StateUI);
+    crash::install(crash::CrashHandlerOptions {
+        show_dialog: is_ui_command,
+        relaunch: is_ui_command && config.system.relaunch_after_crash,
+    });
+
     // Create error context for application startup
     let ctx =
         ErrorContext::new("Main", "startup").with_metadata("version", env!("CARGO_PKG_VERSION"));
@@ -152,10 +256,7 @@ fn main() {
         ) {
             info!("Launching UI (StateUI command is deprecated, redirecting to new UI)...");
         }
-        if let Err(e) = ui::run_ui_with_options(args.test_battery) {
-            error!("Failed to run UI: {}", e);
-            std::process::exit(1);
-        }
+        run_ui(&args, &config);
         return;
     }
 
@@ -165,39 +266,58 @@ fn main() {
         Err(e) => {
             let _ctx = ctx.with_metadata("error", e.to_string());
             error!("Failed to create Tokio runtime: {}", e);
-            std::process::exit(1);
+            std::process::exit(ExitCode::Failure.code());
         }
     };
 
     // Run the main app code inside the runtime
     rt.block_on(async {
-        main_async(args).await;
+        main_async(args, config).await;
     });
 }
 
-async fn main_async(args: AppArgs) {
-    // Create error context for async initialization
-    let ctx = ErrorContext::new("Main", "main_async").with_metadata("runtime", "tokio");
+/// What went wrong in a startup phase, for messages shown before logging is up
+fn phase_failure(phase: StartupPhase) -> String {
+    lifecycle_manager::startup_report()
+        .phase(phase)
+        .map(|record| record.to_string())
+        .unwrap_or_else(|| format!("the {} phase didn't run", phase))
+}
 
-    // Load or create a configuration file
-    let config = match config::load_or_create_config() {
-        Ok(cfg) => {
-            if matches!(
-                args.log_level,
-                LogLevel::Info | LogLevel::Debug | LogLevel::Trace
-            ) {
-                info!("Configuration loaded successfully");
-            }
-            cfg
-        }
-        Err(e) => {
-            let _ctx = ctx.clone().with_metadata("error", e.to_string());
-            logging::log_error(&e, &ctx);
-            error!("Error loading configuration: {}", e);
-            AppConfig::default()
+/// Open the main window, or run in the tray only if asked to
+fn run_ui(args: &AppArgs, config: &AppConfig) {
+    if args.tray_only || config.ui.tray_only {
+        if let Err(e) = tray_only::run(config.clone()) {
+            ui_phase_failed(format!("Failed to run in the tray: {}", e));
         }
-    };
+        return;
+    }
+    if let Err(e) = ui::run_ui_with_options(args.test_battery) {
+        ui_failed(e);
+    }
+}
+
+/// Record that the window couldn't be opened and exit
+fn ui_failed(error: iced::Error) -> ! {
+    // iced has already tried the software renderer after the GPU one
+    if matches!(error, iced::Error::GraphicsCreationFailed(_)) {
+        error!("Neither the GPU nor the software renderer could draw the window");
+    }
 
+    ui_phase_failed(format!("Failed to run UI: {}", error));
+}
+
+/// Record that the UI phase failed and exit
+fn ui_phase_failed(reason: String) -> ! {
+    if let Err(stopped) = LifecycleManager::fail_phase(StartupPhase::Ui, &reason) {
+        error!("{}", stopped);
+    } else {
+        error!("{}", reason);
+    }
+    std::process::exit(ExitCode::Failure.code());
+}
+
+async fn main_async(args: AppArgs, config: AppConfig) {
     // Handle special commands first
     match args.command {
         AppCommand::Help => {
@@ -211,10 +331,7 @@ async fn main_async(args: AppArgs) {
             ) {
                 info!("Launching UI...");
             }
-            if let Err(e) = ui::run_ui_with_options(args.test_battery) {
-                error!("Failed to run UI: {}", e);
-                std::process::exit(1);
-            }
+            run_ui(&args, &config);
             return;
         }
         AppCommand::StateUI => {
@@ -234,6 +351,7 @@ async fn main_async(args: AppArgs) {
 
     // Create error manager
     let error_manager = Arc::new(Mutex::new(ErrorManager::new()));
+    retry::report_to(Arc::clone(&error_manager));
 
     // Create telemetry manager
     #[allow(clippy::arc_with_non_send_sync)]
@@ -247,7 +365,7 @@ async fn main_async(args: AppArgs) {
     if let Err(exit_code) =
         execute_command(args.command, config, error_manager, telemetry_manager).await
     {
-        std::process::exit(exit_code);
+        std::process::exit(exit_code.code());
     }
 }
 
@@ -258,7 +376,14 @@ fn parse_enhanced_args() -> Result<AppArgs, String> {
     let mut log_level = LogLevel::Warn; // Default to warnings and errors only
     let mut verbose = false;
     let mut test_battery = false;
+    let mut no_color = false;
+    let mut json_errors = false;
+    let mut overrides = Vec::new();
     let mut command = AppCommand::UI; // Default command - use new UI
+    let mut focus_address = None;
+    let mut safe_mode = false;
+    let mut software_render = false;
+    let mut tray_only = false;
 
     let mut i = 1;
     while i < args.len() {
@@ -282,6 +407,31 @@ fn parse_enhanced_args() -> Result<AppArgs, String> {
                 println!("Battery estimation test mode enabled");
             }
 
+            "--no-color" => no_color = true,
+            safe_mode::SAFE_MODE_FLAG => safe_mode = true,
+            ui::render_backend::SOFTWARE_RENDER_FLAG => software_render = true,
+            tray_only::TRAY_ONLY_FLAG => tray_only = true,
+            "--errors" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or("Usage: rustpods --errors <text|json>")?;
+                json_errors = exit_code::parse_error_format(value).ok_or_else(|| {
+                    format!("Unknown error format: '{}' (expected text or json)", value)
+                })?;
+                i += 1;
+            }
+            "--set" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or("Usage: rustpods --set <section.key=value>")?;
+                let entry = config::ConfigOverride::parse_set(value)?;
+                AppConfig::default()
+                    .get_path(&entry.path)
+                    .map_err(|e| e.to_string())?;
+                overrides.push(entry);
+                i += 1;
+            }
+
             // Log level flags
             "--quiet" | "-q" => log_level = LogLevel::Error,
             "--info" => log_level = LogLevel::Info,
@@ -290,13 +440,103 @@ fn parse_enhanced_args() -> Result<AppArgs, String> {
 
             // Commands
             "adapters" => command = AppCommand::Adapters,
-            "scan" => command = AppCommand::Scan,
+            "scan" => command = AppCommand::Scan { address: None },
+            "--address" => {
+                let value = args
+                    .get(i + 1)
+                    .ok_or("Usage: rustpods scan --address <address>")?;
+                if config::canonical_address(value).is_none() {
+                    return Err(format!("Not a Bluetooth address: '{}'", value));
+                }
+                focus_address = Some(value.clone());
+                i += 1;
+            }
             "interval" => command = AppCommand::Interval,
+            "status" => command = AppCommand::Status,
             "airpods" => command = AppCommand::AirPods,
             "events" => command = AppCommand::Events,
             "ui" => command = AppCommand::UI,
             "stateui" => command = AppCommand::StateUI,
             "diagnostic" | "diagnostics" => command = AppCommand::Diagnostic,
+            "tui" => command = AppCommand::Tui,
+            "config" => {
+                let action = match args.get(i + 1).map(String::as_str) {
+                    Some("get") => {
+                        let key = args.get(i + 2).ok_or("Usage: rustpods config get <key>")?;
+                        i += 2;
+                        ConfigAction::Get(key.clone())
+                    }
+                    Some("set") => {
+                        let (Some(key), Some(value)) = (args.get(i + 2), args.get(i + 3)) else {
+                            return Err("Usage: rustpods config set <key> <value>".to_string());
+                        };
+                        i += 3;
+                        ConfigAction::Set(key.clone(), value.clone())
+                    }
+                    Some("set-secret") => {
+                        let (Some(key), Some(value)) = (args.get(i + 2), args.get(i + 3)) else {
+                            return Err(
+                                "Usage: rustpods config set-secret <key> <value>".to_string()
+                            );
+                        };
+                        i += 3;
+                        ConfigAction::SetSecret(key.clone(), value.clone())
+                    }
+                    Some("list") | None => {
+                        i += 1;
+                        ConfigAction::List
+                    }
+                    Some(other) => return Err(format!("Unknown config action: '{}'", other)),
+                };
+                command = AppCommand::Config(action);
+            }
+            "diff" => {
+                let (Some(before), Some(after)) = (args.get(i + 1), args.get(i + 2)) else {
+                    return Err("Usage: rustpods diff <capture_a> <capture_b>".to_string());
+                };
+                command = AppCommand::Diff(PathBuf::from(before), PathBuf::from(after));
+                i += 2;
+            }
+            "decode" => {
+                // Pasted bytes often arrive space separated, as several arguments
+                let hex: Vec<&str> = args[i + 1..]
+                    .iter()
+                    .take_while(|arg| !arg.starts_with("--"))
+                    .map(String::as_str)
+                    .collect();
+                if hex.is_empty() {
+                    return Err("Usage: rustpods decode <hex>".to_string());
+                }
+                i += hex.len();
+                command = AppCommand::Decode(hex.join(" "));
+            }
+            "import" => {
+                let paths: Vec<PathBuf> = args[i + 1..]
+                    .iter()
+                    .take_while(|arg| !arg.starts_with("--"))
+                    .map(PathBuf::from)
+                    .collect();
+                if paths.is_empty() {
+                    return Err("Usage: rustpods import <file or folder>...".to_string());
+                }
+                i += paths.len();
+                command = AppCommand::Import(paths);
+            }
+            "graph" => {
+                let graph = match args.get(i + 1) {
+                    Some(name) if !name.starts_with('-') => {
+                        i += 1;
+                        Some(state_graph::Graph::parse(name).ok_or_else(|| {
+                            format!(
+                                "Unknown graph: '{}' (expected detection, presence or messages)",
+                                name
+                            )
+                        })?)
+                    }
+                    _ => None,
+                };
+                command = AppCommand::Graph(graph);
+            }
             "help" | "--help" | "-h" => command = AppCommand::Help,
 
             _ => {
@@ -311,6 +551,13 @@ fn parse_enhanced_args() -> Result<AppArgs, String> {
         i += 1;
     }
 
+    if let Some(focus_address) = focus_address {
+        match &mut command {
+            AppCommand::Scan { address } => *address = Some(focus_address),
+            _ => return Err("--address can only be used with scan".to_string()),
+        }
+    }
+
     // Enable debug categories if all debug is enabled
     if debug_flags.all {
         debug_flags.ui = true;
@@ -338,6 +585,12 @@ fn parse_enhanced_args() -> Result<AppArgs, String> {
         log_level,
         verbose,
         test_battery,
+        no_color,
+        json_errors,
+        overrides,
+        safe_mode,
+        software_render,
+        tray_only,
     })
 }
 
@@ -346,34 +599,109 @@ async fn execute_command(
     config: Arc<Mutex<AppConfig>>,
     error_manager: Arc<Mutex<ErrorManager>>,
     _telemetry_manager: Arc<Mutex<telemetry::TelemetryManager>>,
-) -> Result<(), i32> {
+) -> Result<(), ExitCode> {
+    let scans = matches!(
+        command,
+        AppCommand::Scan { .. } | AppCommand::Interval | AppCommand::Status | AppCommand::AirPods
+    );
+    if scans {
+        if let Err(e) = bluetooth::preflight::check() {
+            return Err(handle_command_error(
+                e.into(),
+                "checking Bluetooth",
+                &error_manager,
+            ));
+        }
+    }
+
     match command {
         AppCommand::Adapters => {
-            println!("Discovering Bluetooth adapters...");
-            if let Err(e) = bluetooth::discover_adapters().await {
-                handle_command_error(e, "discovering Bluetooth adapters", &error_manager);
-                return Err(2); // Error code 2 for Bluetooth adapter issues
+            let progress = cli_output::Progress::spinner("Discovering Bluetooth adapters");
+            let result = bluetooth::discover_adapters().await;
+            progress.clear();
+            if let Err(e) = result {
+                return Err(handle_command_error(
+                    bluetooth_failure(e),
+                    "discovering Bluetooth adapters",
+                    &error_manager,
+                ));
             }
         }
-        AppCommand::Scan => {
-            println!("Running Bluetooth scan...");
-            if let Err(e) = bluetooth::scan_with_adapter().await {
-                handle_command_error(e, "scanning for devices", &error_manager);
-                return Err(3); // Error code 3 for scanning issues
+        AppCommand::Scan {
+            address: Some(address),
+        } => {
+            let duration = std::time::Duration::from_secs(30);
+            let progress = cli_output::Progress::countdown(
+                &format!("Scanning only for {}", address),
+                duration,
+            );
+            let result = bluetooth::scan_address(&address, duration).await;
+            progress.clear();
+            match result {
+                Ok(0) => println!(
+                    "{}",
+                    cli_output::warning(format!("No frames received from {}", address))
+                ),
+                Ok(frames) => println!(
+                    "{}",
+                    cli_output::success(format!("Received {} frames from {}", frames, address))
+                ),
+                Err(e) => {
+                    return Err(handle_command_error(
+                        e.into(),
+                        "scanning for devices",
+                        &error_manager,
+                    ));
+                }
+            }
+        }
+        AppCommand::Scan { address: None } => {
+            let progress = cli_output::Progress::countdown(
+                "Running Bluetooth scan",
+                bluetooth::examples::SCAN_DURATION,
+            );
+            let result = bluetooth::scan_with_adapter().await;
+            progress.clear();
+            if let Err(e) = result {
+                return Err(handle_command_error(
+                    bluetooth_failure(e),
+                    "scanning for devices",
+                    &error_manager,
+                ));
             }
         }
         AppCommand::Interval => {
-            println!("Running interval-based scanning...");
+            println!("{}", cli_output::heading("Interval-based scanning"));
             if let Err(e) = bluetooth::interval_scanning().await {
-                handle_command_error(e, "interval scanning", &error_manager);
-                return Err(4); // Error code 4 for interval scanning issues
+                return Err(handle_command_error(
+                    bluetooth_failure(e),
+                    "interval scanning",
+                    &error_manager,
+                ));
+            }
+        }
+        AppCommand::Status => {
+            let config = config
+                .lock()
+                .map(|config| config.clone())
+                .unwrap_or_default();
+            if let Err(e) = run_status_command(&config).await {
+                return Err(CommandFailure::new(
+                    "reading battery status",
+                    RustPodsError::DeviceNotFound,
+                )
+                .with_message(e)
+                .report());
             }
         }
         AppCommand::AirPods => {
-            println!("Running AirPods filtering demo...");
+            println!("{}", cli_output::heading("AirPods filtering demo"));
             if let Err(e) = bluetooth::airpods_filtering().await {
-                handle_command_error(e, "AirPods filtering", &error_manager);
-                return Err(5); // Error code 5 for AirPods filtering issues
+                return Err(handle_command_error(
+                    bluetooth_failure(e),
+                    "AirPods filtering",
+                    &error_manager,
+                ));
             }
         }
         AppCommand::Events => {
@@ -381,10 +709,81 @@ async fn execute_command(
             println!("To run the event system demo, use: cargo run --example event_system");
         }
         AppCommand::Diagnostic => {
-            println!("Running system diagnostics...");
             if let Err(e) = run_diagnostics(Arc::clone(&config), Arc::clone(&error_manager)).await {
-                eprintln!("Error running diagnostics: {}", e);
-                return Err(7); // Error code 7 for diagnostic issues
+                return Err(CommandFailure::new(
+                    "running diagnostics",
+                    RustPodsError::System(e.clone()),
+                )
+                .with_message(format!("Error running diagnostics: {}", e))
+                .report());
+            }
+        }
+        AppCommand::Config(action) => {
+            if let Err(e) = run_config_command(action) {
+                return Err(CommandFailure::new(
+                    "changing settings",
+                    RustPodsError::Config(e.clone()),
+                )
+                .with_message(e)
+                .report());
+            }
+        }
+        AppCommand::Diff(before, after) => {
+            let captures = bluetooth::load_capture(&before)
+                .and_then(|before| Ok((before, bluetooth::load_capture(&after)?)));
+            match captures {
+                Ok((before, after)) => print!("{}", bluetooth::diff_captures(&before, &after)),
+                Err(e) => {
+                    let message = e.to_string();
+                    return Err(CommandFailure::new("comparing captures", e.into())
+                        .with_message(message)
+                        .report());
+                }
+            }
+        }
+        AppCommand::Tui => {
+            let interval = config
+                .lock()
+                .map(|config| config.bluetooth.scan_interval)
+                .unwrap_or(std::time::Duration::from_secs(30));
+            let result = tokio::task::spawn_blocking(move || tui::run(interval))
+                .await
+                .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+            if let Err(e) = result {
+                return Err(CommandFailure::new(
+                    "running the terminal dashboard",
+                    RustPodsError::System(e.to_string()),
+                )
+                .with_message(format!("Terminal dashboard failed: {}", e))
+                .report());
+            }
+        }
+        AppCommand::Decode(hex) => match bluetooth::decode_hex(&hex) {
+            Ok(frame) => print!("{}", frame),
+            Err(e) => {
+                let message = e.to_string();
+                return Err(CommandFailure::new("decoding manufacturer data", e.into())
+                    .with_message(message)
+                    .report());
+            }
+        },
+        AppCommand::Import(paths) => {
+            if let Err(e) = run_import_command(&paths) {
+                return Err(CommandFailure::new(
+                    "importing battery history",
+                    RustPodsError::InvalidData(e.clone()),
+                )
+                .with_message(e)
+                .report());
+            }
+        }
+        AppCommand::Graph(graph) => {
+            let graphs = match graph {
+                Some(graph) => vec![graph],
+                None => state_graph::Graph::ALL.to_vec(),
+            };
+            for graph in graphs {
+                print!("{}", graph.to_dot());
             }
         }
         AppCommand::UI | AppCommand::StateUI | AppCommand::Help => {
@@ -396,6 +795,151 @@ async fn execute_command(
     Ok(())
 }
 
+// Whether `--errors json` was given, for failures before the arguments are parsed
+fn requested_json_errors() -> bool {
+    let args: Vec<String> = std::env::args().collect();
+    args.windows(2)
+        .any(|pair| pair[0] == "--errors" && exit_code::parse_error_format(&pair[1]) == Some(true))
+}
+
+// The bluetooth demos return boxed errors; keep the Bluetooth error when that's what it is
+fn bluetooth_failure(error: Box<dyn std::error::Error>) -> RustPodsError {
+    match error.downcast::<BluetoothError>() {
+        Ok(error) => RustPodsError::BluetoothError(*error),
+        Err(error) => RustPodsError::Bluetooth(error.to_string()),
+    }
+}
+
+// Helper function to print the battery levels of the paired AirPods, or of the nearest
+// AirPods when none are paired or the paired ones weren't seen
+async fn run_status_command(config: &AppConfig) -> Result<(), String> {
+    let progress = cli_output::Progress::spinner("Scanning for AirPods");
+    let supervisor = bluetooth::ScannerSupervisor::new();
+    let devices =
+        tokio::task::spawn_blocking(move || ui::state::get_airpods_from_cli_scanner(&supervisor))
+            .await
+            .unwrap_or_default();
+    progress.clear();
+
+    let access = config.bluetooth.device_access();
+    let devices: Vec<_> = devices
+        .into_iter()
+        .filter(|device| access.allows(&device.canonical_address, Some(&device.name)))
+        .collect();
+    let paired = config
+        .bluetooth
+        .paired_device_id
+        .as_deref()
+        .and_then(config::canonical_address);
+    let device = devices
+        .iter()
+        .find(|device| Some(&device.canonical_address) == paired.as_ref())
+        .or_else(|| {
+            devices
+                .iter()
+                .max_by_key(|device| device.rssi.unwrap_or(i32::MIN))
+        })
+        .ok_or("No AirPods found")?;
+    println!("{}", device.status_line());
+    Ok(())
+}
+
+// Helper function to import captured readings into the battery history
+fn run_import_command(paths: &[PathBuf]) -> Result<(), String> {
+    // Folders are imported file by file, as the scanner and legacy logs write them
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let entries = std::fs::read_dir(path)
+                .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|file| file.extension().is_some_and(|ext| ext == "json"))
+                .collect();
+            found.sort();
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut intelligence = airpods::battery_intelligence::BatteryIntelligence::new(
+        airpods::battery_intelligence::get_battery_intelligence_dir(),
+    );
+    let report = intelligence.import_history(&files);
+    for (path, e) in &report.failed {
+        eprintln!(
+            "{}",
+            cli_output::warning(format!("Skipped {}: {}", path.display(), e))
+        );
+    }
+    if report.readings == 0 {
+        return Err("No dated battery readings found".to_string());
+    }
+    println!("{}", report);
+    Ok(())
+}
+
+// Helper function to read or write settings by dot-path
+fn run_config_command(action: ConfigAction) -> Result<(), String> {
+    let mut config =
+        AppConfig::load().map_err(|e| format!("Failed to load configuration: {}", e.detailed()))?;
+
+    let format_value = |value: serde_json::Value| match value {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    };
+
+    match action {
+        ConfigAction::Get(key) => {
+            let value = config.get_path(&key).map_err(|e| e.to_string())?;
+            println!("{}", format_value(value));
+        }
+        ConfigAction::Set(key, value) => {
+            config.set_path(&key, &value).map_err(|e| e.to_string())?;
+            config
+                .save_audited(ChangeSource::Cli)
+                .map_err(|e| format!("Failed to save configuration: {}", e))?;
+            let value = config.get_path(&key).map_err(|e| e.to_string())?;
+            println!(
+                "{} = {}",
+                cli_output::label(&key),
+                cli_output::success(format_value(value))
+            );
+        }
+        ConfigAction::SetSecret(key, value) => {
+            // The secret is named after the setting that refers to it
+            let secret = config::SecretRef::store(key.clone(), &value, &config::CredentialStore)
+                .map_err(|e| format!("Failed to store secret: {}", e))?;
+            config
+                .set_path(&key, secret.name())
+                .map_err(|e| e.to_string())?;
+            config
+                .save_audited(ChangeSource::Cli)
+                .map_err(|e| format!("Failed to save configuration: {}", e))?;
+            println!(
+                "{} = {}",
+                cli_output::label(&key),
+                cli_output::success(secret)
+            );
+        }
+        ConfigAction::List => {
+            let rows: Vec<(String, String)> = config::dot_path::leaf_paths(&config)
+                .into_iter()
+                .filter_map(|key| {
+                    let value = config.get_path(&key).ok()?;
+                    Some((key, format_value(value)))
+                })
+                .collect();
+            for row in cli_output::aligned(&rows, "=") {
+                println!("{}", row);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 // Helper function to run system diagnostics
 async fn run_diagnostics(
     config: Arc<Mutex<AppConfig>>,
@@ -411,9 +955,13 @@ async fn run_diagnostics(
 
     // Set to complete diagnostic level
     diagnostics.set_level(diagnostics::DiagnosticLevel::Complete);
+    diagnostics.on_step(cli_output::status);
 
     // Run diagnostics
-    let result = match diagnostics.run_diagnostics().await {
+    let progress = cli_output::Progress::spinner("Running system diagnostics");
+    let result = diagnostics.run_diagnostics().await;
+    progress.clear();
+    let result = match result {
         Ok(r) => r,
         Err(e) => return Err(format!("Failed to run diagnostics: {}", e)),
     };
@@ -425,52 +973,56 @@ async fn run_diagnostics(
     };
 
     // Print summary
-    println!("\n==== Diagnostic Results ====");
-    println!("Issues found: {}", result.issues.len());
-    println!(
-        "Critical issues: {}",
+    let count = |severity| {
         result
             .issues
             .iter()
-            .filter(|i| i.severity == diagnostics::IssueSeverity::Critical)
+            .filter(|i| i.severity == severity)
             .count()
-    );
-    println!(
-        "Major issues: {}",
-        result
-            .issues
-            .iter()
-            .filter(|i| i.severity == diagnostics::IssueSeverity::Major)
-            .count()
-    );
+    };
+    println!("\n{}", cli_output::heading("Diagnostic Results"));
+    for row in cli_output::aligned(
+        &[
+            ("Issues found:", result.issues.len()),
+            (
+                "Critical issues:",
+                count(diagnostics::IssueSeverity::Critical),
+            ),
+            ("Major issues:", count(diagnostics::IssueSeverity::Major)),
+            ("Minor issues:", count(diagnostics::IssueSeverity::Minor)),
+        ],
+        "",
+    ) {
+        println!("{}", row);
+    }
     println!(
-        "Minor issues: {}",
-        result
-            .issues
-            .iter()
-            .filter(|i| i.severity == diagnostics::IssueSeverity::Minor)
-            .count()
+        "\nDiagnostic report saved to: {}",
+        cli_output::dim(report_path.display())
     );
-    println!("\nDiagnostic report saved to: {}", report_path.display());
 
     // Print issues
     if !result.issues.is_empty() {
-        println!("\n==== Issues ====");
+        println!("\n{}", cli_output::heading("Issues"));
         for (i, issue) in result.issues.iter().enumerate() {
             let severity = match issue.severity {
-                diagnostics::IssueSeverity::Critical => "CRITICAL",
-                diagnostics::IssueSeverity::Major => "MAJOR",
-                diagnostics::IssueSeverity::Minor => "MINOR",
-                diagnostics::IssueSeverity::Info => "INFO",
+                diagnostics::IssueSeverity::Critical => cli_output::error("CRITICAL").for_stdout(),
+                diagnostics::IssueSeverity::Major => cli_output::warning("MAJOR"),
+                diagnostics::IssueSeverity::Minor => cli_output::dim("MINOR"),
+                diagnostics::IssueSeverity::Info => cli_output::dim("INFO"),
             };
-            println!("{}. [{}] {}", i + 1, severity, issue.title);
+            println!(
+                "{}. [{}] {}",
+                i + 1,
+                severity,
+                cli_output::label(&issue.title)
+            );
             println!("   {}", issue.description);
         }
     }
 
     // Print recommendations
     if !result.recommendations.is_empty() {
-        println!("\n==== Recommendations ====");
+        println!("\n{}", cli_output::heading("Recommendations"));
         for (i, rec) in result.recommendations.iter().enumerate() {
             println!("{}. {}", i + 1, rec);
         }
@@ -480,10 +1032,11 @@ async fn run_diagnostics(
 }
 
 // Helper function to handle command errors consistently
-fn handle_command_error<E>(error: E, operation: &str, error_manager: &Arc<Mutex<ErrorManager>>)
-where
-    E: std::fmt::Display + std::fmt::Debug,
-{
+fn handle_command_error(
+    error: RustPodsError,
+    operation: &str,
+    error_manager: &Arc<Mutex<ErrorManager>>,
+) -> ExitCode {
     // Create error context
     let _ctx = ErrorContext::new("CommandExecution", operation)
         .with_metadata("operation", operation.to_string());
@@ -518,11 +1071,16 @@ where
         }
         "interval scanning" => "Check if there are any Bluetooth devices in range and try again",
         "AirPods filtering" => "Make sure your AirPods are in pairing mode and try again",
+        "checking Bluetooth" => {
+            "Turn on Bluetooth in Settings > Bluetooth & devices, and allow apps to control radios in Settings > Privacy & security > Radios"
+        }
         _ => "Try running the command again or check system logs for more details",
     };
 
     logging::log_error_with_recovery(&error, &_ctx, recovery_action);
-    println!("Suggested action: {}", recovery_action);
+    CommandFailure::new(operation, error)
+        .with_suggestion(recovery_action)
+        .report()
 }
 
 fn print_usage() {
@@ -537,12 +1095,25 @@ fn print_usage() {
     println!("  (none)                  - Launch the UI application (default)");
     println!("  adapters                - Discover Bluetooth adapters");
     println!("  scan                    - Run a basic Bluetooth scan");
+    println!("  scan --address <addr>   - Scan only for one device and print each frame it sends");
     println!("  interval                - Run interval-based scanning");
+    println!("  status                  - Print battery levels from one quick scan and exit");
     println!("  airpods                 - Run AirPods filtering demo");
     println!("  events                  - Run event system demo");
     println!("  ui                      - Launch the UI with original state management");
     println!("  stateui                 - Launch the UI with new state management");
     println!("  diagnostic              - Run system diagnostics");
+    println!("  tui                     - Live battery dashboard in the terminal");
+    println!("  config list             - Show all settings");
+    println!("  config get <key>        - Show one setting, e.g. ui.low_battery_threshold");
+    println!("  config set <key> <val>  - Change one setting (validated before saving)");
+    println!("  config set-secret <key> <val> - Keep a password in Credential Manager, e.g. system.proxy.password");
+    println!("  diff <a> <b>            - Compare two advertisement captures byte by byte");
+    println!("  decode <hex>            - Explain every byte of raw manufacturer data");
+    println!(
+        "  import <files>          - Add scanner captures and old battery logs to the history"
+    );
+    println!("  graph [name]            - Print DOT graphs (detection, presence, messages)");
     println!("  help                    - Show this help message");
 
     println!("\nLOG LEVEL FLAGS:");
@@ -563,12 +1134,48 @@ fn print_usage() {
     println!("\nTEST FLAGS:");
     println!("  --test-battery          - Enable battery estimation test mode with simulated data");
 
+    println!("\nOUTPUT FLAGS:");
+    println!("  --no-color              - Plain text without colors or progress animation");
+    println!("  --errors json           - Print failures to stderr as one JSON object");
+
+    println!("\nTRAY FLAGS:");
+    println!("  --tray-only             - Run without the main window: levels in the tray");
+    println!("                            tooltip and menu, alerts as notifications");
+
+    println!("\nRECOVERY FLAGS:");
+    println!("  --safe-mode             - Start without battery history, action rules and");
+    println!("                            custom themes, to recover from a broken profile");
+    println!("  --software-render       - Draw the window without the GPU, for VMs and");
+    println!("                            remote desktop sessions");
+
+    println!("\nCONFIGURATION OVERRIDES:");
+    println!("  --set <key>=<value>     - Override a setting for this run (repeatable)");
+    println!("  RUSTPODS_<KEY>=<value>  - Same, from the environment, e.g. RUSTPODS_UI_THEME");
+    println!(
+        "                            Precedence: --set > environment > settings file > defaults"
+    );
+
+    println!("\nEXIT CODES:");
+    for code in ExitCode::ALL {
+        println!(
+            "  {:<2} {:<20} - {}",
+            code.code(),
+            code.name(),
+            code.description()
+        );
+    }
+
     println!("\nEXAMPLES:");
     println!("  rustpods                           # Normal UI with warnings/errors only");
     println!("  rustpods --debug-bluetooth scan    # Debug bluetooth during scan");
+    println!("  rustpods scan --address 58:26:D7:45:AD:8B");
+    println!("  rustpods graph detection | dot -Tsvg > detection.svg");
     println!("  rustpods --debug-ui                # Debug UI messages in normal mode");
     println!("  rustpods -v                        # Full debug output for everything");
     println!("  rustpods --quiet diagnostic        # Run diagnostics with errors only");
+    println!("  rustpods config set ui.low_battery_threshold 15");
+    println!("  rustpods --quiet status            # One line for a status bar; exit code 4 if none found");
+    println!("  rustpods --errors json scan        # Failure as JSON for wrapper scripts");
     println!("  rustpods --test-battery            # Test battery estimation with simulated data");
 }
 
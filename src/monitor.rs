@@ -0,0 +1,422 @@
+//! Event stream for applications embedding RustPods
+//!
+//! [`monitor`] starts the scanner, tracks the devices it reports and yields a
+//! [`RustPodsEvent`] whenever one appears, changes or goes away:
+//!
+//! ```no_run
+//! use futures::StreamExt;
+//!
+//! # async fn run() {
+//! let mut events = Box::pin(rustpods::monitor());
+//! while let Some(event) = events.next().await {
+//!     println!("{:?}", event);
+//! }
+//! # }
+//! ```
+//!
+//! Battery levels are passed through the battery intelligence layer, so they are
+//! smoothed estimates rather than the 10% steps AirPods advertise. The stream must be
+//! polled from a Tokio runtime, and scanning stops when it is dropped.
+
+use std::collections::HashMap;
+
+use btleplug::api::BDAddr;
+use futures::Stream;
+
+use crate::airpods::battery_intelligence::get_battery_intelligence_dir;
+use crate::airpods::{AirPodsBattery, AirPodsChargingState, BatteryIntelligence, DetectedAirPods};
+use crate::bluetooth::{CliScanner, CliScannerConfig};
+use crate::config::AppConfig;
+use crate::state_graph::{self, Transition};
+
+/// Successful scans a device may be missing from before it is reported lost
+pub const MISSED_SCANS_BEFORE_LOST: u32 = 3;
+
+/// Where a device stands with the tracker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Presence {
+    /// Not seen yet, or forgotten after being lost
+    Unseen,
+    /// In the latest scan
+    Present,
+    /// Missing from recent scans, but not yet for long enough to be lost
+    Missing,
+    /// Missing for [`MISSED_SCANS_BEFORE_LOST`] scans and no longer tracked
+    Lost,
+}
+
+impl Presence {
+    /// Name of every state, as returned by [`Self::name`]
+    pub const NAMES: &'static [&'static str] = &["Unseen", "Present", "Missing", "Lost"];
+
+    /// Transitions the tracker makes after each scan
+    pub const TRANSITIONS: &'static [Transition] = &[
+        Transition {
+            from: Some("Unseen"),
+            to: "Present",
+            trigger: "seen (DeviceFound)",
+        },
+        Transition {
+            from: Some("Present"),
+            to: "Missing",
+            trigger: "missed a scan",
+        },
+        Transition {
+            from: Some("Missing"),
+            to: "Present",
+            trigger: "seen again",
+        },
+        Transition {
+            from: Some("Missing"),
+            to: "Lost",
+            trigger: "missed 3 scans (DeviceLost)",
+        },
+        Transition {
+            from: Some("Lost"),
+            to: "Present",
+            trigger: "seen (DeviceFound)",
+        },
+    ];
+
+    /// Name of the state
+    pub fn name(self) -> &'static str {
+        match self {
+            Presence::Unseen => "Unseen",
+            Presence::Present => "Present",
+            Presence::Missing => "Missing",
+            Presence::Lost => "Lost",
+        }
+    }
+
+    /// State of a tracked device that has missed `missed_scans` scans in a row
+    fn after_missing(missed_scans: u32) -> Self {
+        match missed_scans {
+            0 => Presence::Present,
+            n if n < MISSED_SCANS_BEFORE_LOST => Presence::Missing,
+            _ => Presence::Lost,
+        }
+    }
+}
+
+/// Something that happened to a device near the computer
+#[derive(Debug, Clone, PartialEq)]
+pub enum RustPodsEvent {
+    /// A device was seen for the first time, or again after being lost
+    DeviceFound(DetectedAirPods),
+    /// A tracked device reported new data
+    DeviceUpdated(DetectedAirPods),
+    /// A device hasn't been seen for [`MISSED_SCANS_BEFORE_LOST`] scans
+    DeviceLost(BDAddr),
+    /// Estimated battery levels of a tracked device changed
+    BatteryChanged {
+        /// Device address
+        address: BDAddr,
+        /// New levels, with the charging state at the time
+        battery: AirPodsBattery,
+    },
+    /// A component of a tracked device started or stopped charging
+    ChargingChanged {
+        /// Device address
+        address: BDAddr,
+        /// New charging state
+        charging: AirPodsChargingState,
+    },
+    /// A scan failed; monitoring carries on
+    ScanFailed(String),
+}
+
+/// Watch for AirPods using the saved configuration
+///
+/// Falls back to the default configuration if the settings file can't be read.
+pub fn monitor() -> impl Stream<Item = RustPodsEvent> {
+    let config = AppConfig::load().unwrap_or_else(|e| {
+        log::warn!("Monitoring with default settings: {}", e);
+        AppConfig::default()
+    });
+    monitor_with_config(&config)
+}
+
+/// Watch for AirPods using the scanner settings from `config`
+pub fn monitor_with_config(config: &AppConfig) -> impl Stream<Item = RustPodsEvent> {
+    let scanner_config = CliScannerConfig::from_app_config(config);
+
+    async_stream::stream! {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        // Created here rather than above, as the scanner needs a runtime
+        let scanner = CliScanner::new(scanner_config);
+        let _scan_task = AbortOnDrop(scanner.start_monitoring(move |result| {
+            let _ = sender.send(result);
+        }));
+
+        let mut tracker = DeviceTracker::new(get_battery_intelligence_dir().join("monitor"));
+        while let Some(result) = receiver.recv().await {
+            let events = match result {
+                Ok(devices) => tracker.update(devices),
+                Err(e) => vec![RustPodsEvent::ScanFailed(e.to_string())],
+            };
+            for event in events {
+                yield event;
+            }
+        }
+    }
+}
+
+/// Stops the scan task when the stream is dropped
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// A device the tracker has seen
+struct TrackedDevice {
+    /// Last report from the scanner
+    airpods: DetectedAirPods,
+    /// Last battery levels sent out
+    battery: AirPodsBattery,
+    /// Successful scans in a row the device was missing from
+    missed_scans: u32,
+    /// Level estimator for this device
+    intelligence: BatteryIntelligence,
+}
+
+/// Turns successive scan results into events
+struct DeviceTracker {
+    /// Where each device's estimator looks for a saved profile
+    intelligence_dir: std::path::PathBuf,
+    devices: HashMap<BDAddr, TrackedDevice>,
+    /// Devices lost and not seen since
+    lost: std::collections::HashSet<BDAddr>,
+}
+
+impl DeviceTracker {
+    fn new(intelligence_dir: std::path::PathBuf) -> Self {
+        Self {
+            intelligence_dir,
+            devices: HashMap::new(),
+            lost: std::collections::HashSet::new(),
+        }
+    }
+
+    /// Where the device at `address` stands
+    fn presence(&self, address: &BDAddr) -> Presence {
+        match self.devices.get(address) {
+            Some(tracked) => Presence::after_missing(tracked.missed_scans),
+            None if self.lost.contains(address) => Presence::Lost,
+            None => Presence::Unseen,
+        }
+    }
+
+    /// Warn about a move missing from [`Presence::TRANSITIONS`], so the drawn state
+    /// machine stays accurate
+    fn check_transition(address: &BDAddr, from: Presence, to: Presence) {
+        if !state_graph::is_allowed(Presence::TRANSITIONS, from.name(), to.name()) {
+            log::warn!(
+                "Unexpected presence transition for {}: {} -> {}",
+                address,
+                from.name(),
+                to.name()
+            );
+        }
+    }
+
+    /// Compare a scan with the tracked devices and return what changed
+    fn update(&mut self, scan: Vec<DetectedAirPods>) -> Vec<RustPodsEvent> {
+        let mut events = Vec::new();
+
+        let before: HashMap<BDAddr, Presence> = self
+            .devices
+            .keys()
+            .chain(scan.iter().map(|airpods| &airpods.address))
+            .map(|address| (*address, self.presence(address)))
+            .collect();
+
+        for tracked in self.devices.values_mut() {
+            tracked.missed_scans += 1;
+        }
+
+        for airpods in scan {
+            let address = airpods.address;
+            self.lost.remove(&address);
+            match self.devices.get_mut(&address) {
+                Some(tracked) => {
+                    tracked.missed_scans = 0;
+                    let battery = estimate(&mut tracked.intelligence, &airpods);
+                    if !same_report(&tracked.airpods, &airpods) {
+                        events.push(RustPodsEvent::DeviceUpdated(airpods.clone()));
+                    }
+                    if battery.charging != tracked.battery.charging {
+                        events.push(RustPodsEvent::ChargingChanged {
+                            address,
+                            charging: battery
+                                .charging
+                                .unwrap_or(AirPodsChargingState::NotCharging),
+                        });
+                    }
+                    if (battery.left, battery.right, battery.case)
+                        != (
+                            tracked.battery.left,
+                            tracked.battery.right,
+                            tracked.battery.case,
+                        )
+                    {
+                        events.push(RustPodsEvent::BatteryChanged {
+                            address,
+                            battery: battery.clone(),
+                        });
+                    }
+                    tracked.airpods = airpods;
+                    tracked.battery = battery;
+                }
+                None => {
+                    let directory = self
+                        .intelligence_dir
+                        .join(address.to_string().replace(':', ""));
+                    let mut intelligence = BatteryIntelligence::new(directory);
+                    let battery = estimate(&mut intelligence, &airpods);
+                    events.push(RustPodsEvent::DeviceFound(airpods.clone()));
+                    self.devices.insert(
+                        address,
+                        TrackedDevice {
+                            airpods,
+                            battery,
+                            missed_scans: 0,
+                            intelligence,
+                        },
+                    );
+                }
+            }
+        }
+
+        let lost: Vec<BDAddr> = self
+            .devices
+            .iter()
+            .filter(|(_, tracked)| tracked.missed_scans >= MISSED_SCANS_BEFORE_LOST)
+            .map(|(address, _)| *address)
+            .collect();
+        for address in lost {
+            self.devices.remove(&address);
+            self.lost.insert(address);
+            events.push(RustPodsEvent::DeviceLost(address));
+        }
+
+        for (address, from) in before {
+            Self::check_transition(&address, from, self.presence(&address));
+        }
+
+        events
+    }
+}
+
+/// Whether two reports of a device carry the same data, ignoring when it was seen
+fn same_report(a: &DetectedAirPods, b: &DetectedAirPods) -> bool {
+    a.name == b.name
+        && a.rssi == b.rssi
+        && a.device_type == b.device_type
+        && a.battery == b.battery
+        && a.is_connected == b.is_connected
+        && a.extras == b.extras
+}
+
+/// Feed a report to the estimator and return the smoothed levels
+fn estimate(intelligence: &mut BatteryIntelligence, airpods: &DetectedAirPods) -> AirPodsBattery {
+    let battery = airpods.battery.clone().unwrap_or_default();
+    let charging = battery
+        .charging
+        .unwrap_or(AirPodsChargingState::NotCharging);
+    let (left_in_ear, right_in_ear) = airpods
+        .extras
+        .as_ref()
+        .map(|extras| (extras.left_in_ear(), extras.right_in_ear()))
+        .unwrap_or_default();
+
+    intelligence.update_device_battery(
+        &airpods.address.to_string(),
+        airpods.name.as_deref().unwrap_or("AirPods"),
+        battery.left,
+        battery.right,
+        battery.case,
+        charging.is_left_charging(),
+        charging.is_right_charging(),
+        charging.is_case_charging(),
+        left_in_ear,
+        right_in_ear,
+        airpods.rssi,
+    );
+
+    match intelligence.get_display_levels() {
+        Some((left, right, case)) => AirPodsBattery {
+            left: left.or(battery.left),
+            right: right.or(battery.right),
+            case: case.or(battery.case),
+            charging: battery.charging,
+        },
+        None => battery,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airpods::AirPodsType;
+
+    fn airpods(address: [u8; 6], left: u8, charging: AirPodsChargingState) -> DetectedAirPods {
+        DetectedAirPods::new(
+            BDAddr::from(address),
+            Some("AirPods Pro".to_string()),
+            None,
+            AirPodsType::AirPodsPro,
+            Some(AirPodsBattery {
+                left: Some(left),
+                right: Some(80),
+                case: Some(60),
+                charging: Some(charging),
+            }),
+            false,
+        )
+    }
+
+    #[test]
+    fn test_tracker_events() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut tracker = DeviceTracker::new(dir.path().to_path_buf());
+        let address = [1, 2, 3, 4, 5, 6];
+
+        let events = tracker.update(vec![airpods(
+            address,
+            80,
+            AirPodsChargingState::NotCharging,
+        )]);
+        assert!(matches!(events.as_slice(), [RustPodsEvent::DeviceFound(_)]));
+
+        // Nothing new
+        let events = tracker.update(vec![airpods(
+            address,
+            80,
+            AirPodsChargingState::NotCharging,
+        )]);
+        assert!(events.is_empty());
+
+        let events = tracker.update(vec![airpods(
+            address,
+            80,
+            AirPodsChargingState::LeftCharging,
+        )]);
+        assert!(events.contains(&RustPodsEvent::ChargingChanged {
+            address: BDAddr::from(address),
+            charging: AirPodsChargingState::LeftCharging,
+        }));
+
+        // A missed scan or two is scan flicker, not a lost device
+        for _ in 1..MISSED_SCANS_BEFORE_LOST {
+            assert!(tracker.update(Vec::new()).is_empty());
+        }
+        assert_eq!(
+            tracker.update(Vec::new()),
+            vec![RustPodsEvent::DeviceLost(BDAddr::from(address))]
+        );
+        assert_eq!(tracker.presence(&BDAddr::from(address)), Presence::Lost);
+    }
+}
@@ -0,0 +1,155 @@
+//! Privacy mode: device addresses replaced with salted hashes
+//!
+//! Logs, diagnostic reports and exports are often attached to bug reports, and a
+//! Bluetooth address identifies a person's AirPods wherever they go. With privacy mode
+//! on, every address in that output is replaced with a short hash of the address and a
+//! per-installation salt. The same device always gets the same hash, so a log can still
+//! be followed, but the address can't be read back out of it.
+//!
+//! The hash is not a cryptographic commitment; it keeps addresses out of shared files
+//! rather than protecting them from someone who also has the salt.
+
+use std::borrow::Cow;
+use std::sync::{OnceLock, RwLock};
+
+use regex::{Captures, Regex};
+
+use crate::config::{canonical_address, SystemConfig};
+
+/// Salt in use while privacy mode is on
+static SALT: RwLock<Option<String>> = RwLock::new(None);
+
+/// Addresses with separators (`58:26:D7:45:AD:8B`, `58-26-...`) or as 12 bare hex
+/// digits, including the address at the end of a stable device ID
+fn address_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\b(?:[0-9A-Fa-f]{2}[:-]){5}[0-9A-Fa-f]{2}\b|(?:\b|_)([0-9A-Fa-f]{12})\b")
+            .expect("address pattern is valid")
+    })
+}
+
+/// Turn privacy mode on, hashing with `salt`
+pub fn enable(salt: &str) {
+    if let Ok(mut current) = SALT.write() {
+        *current = Some(salt.to_string());
+    }
+}
+
+/// Turn privacy mode off
+pub fn disable() {
+    if let Ok(mut current) = SALT.write() {
+        *current = None;
+    }
+}
+
+/// Turn privacy mode on or off to match `system`, generating a salt if needed
+///
+/// Returns true when a salt was generated, in which case the settings need saving.
+pub fn apply(system: &mut SystemConfig) -> bool {
+    if !system.privacy_mode {
+        disable();
+        return false;
+    }
+    let generated = system.privacy_salt.is_none();
+    enable(system.privacy_salt());
+    generated
+}
+
+/// Whether addresses are currently being hashed
+pub fn is_enabled() -> bool {
+    SALT.read().map(|salt| salt.is_some()).unwrap_or(false)
+}
+
+/// Stable stand-in for `address`, e.g. `dev-3f2a9c1e`
+///
+/// Every spelling of an address (separators, case, stable ID) gets the same hash.
+pub fn hash_address(address: &str, salt: &str) -> String {
+    let canonical = canonical_address(address).unwrap_or_else(|| address.to_lowercase());
+    // FNV-1a, which unlike `DefaultHasher` is the same in every Rust release
+    let hash = salt
+        .bytes()
+        .chain([0])
+        .chain(canonical.bytes())
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("dev-{:08x}", hash >> 32)
+}
+
+/// `text` with every address hashed, or unchanged when privacy mode is off
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let salt = match SALT.read() {
+        Ok(salt) => match salt.as_ref() {
+            Some(salt) => salt.clone(),
+            None => return Cow::Borrowed(text),
+        },
+        Err(_) => return Cow::Borrowed(text),
+    };
+    redact_with(text, &salt)
+}
+
+/// `text` with every address hashed and the home directory shortened to `~`, whether
+/// or not privacy mode is on, for text that is about to be published
+///
+/// Without privacy mode a salt made up for this run is used, so hashes still match
+/// within one report.
+pub fn scrub(text: &str) -> String {
+    static SESSION_SALT: OnceLock<String> = OnceLock::new();
+    let salt = SALT
+        .read()
+        .ok()
+        .and_then(|salt| salt.clone())
+        .unwrap_or_else(|| {
+            SESSION_SALT
+                .get_or_init(|| uuid::Uuid::new_v4().simple().to_string())
+                .clone()
+        });
+    let text = redact_with(text, &salt).into_owned();
+    match dirs::home_dir() {
+        Some(home) => text.replace(&home.display().to_string(), "~"),
+        None => text,
+    }
+}
+
+fn redact_with<'a>(text: &'a str, salt: &str) -> Cow<'a, str> {
+    address_pattern().replace_all(text, |captures: &Captures| {
+        let matched = &captures[0];
+        match captures.get(1) {
+            // Keep the separator in front of an address inside a stable ID
+            Some(bare) => format!(
+                "{}{}",
+                &matched[..matched.len() - bare.as_str().len()],
+                hash_address(bare.as_str(), salt)
+            ),
+            None => hash_address(matched, salt),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_addresses_are_hashed_consistently() {
+        let text = "Found 58:26:D7:45:AD:8B (paired_2014_5826d745ad8b) near 11-22-33-44-55-66";
+        let redacted = redact_with(text, "salt");
+        let hash = hash_address("58:26:D7:45:AD:8B", "salt");
+
+        assert_eq!(
+            redacted,
+            format!(
+                "Found {} (paired_2014_{}) near {}",
+                hash,
+                hash,
+                hash_address("112233445566", "salt")
+            )
+        );
+        assert_ne!(hash, hash_address("58:26:D7:45:AD:8B", "other salt"));
+
+        // Longer hex runs such as manufacturer data are left alone
+        let payload = "07190114200b778f";
+        assert_eq!(redact_with(payload, "salt"), payload);
+    }
+}
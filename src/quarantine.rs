@@ -0,0 +1,233 @@
+//! Quarantine of data files that can't be read
+//!
+//! A battery profile, known devices list or saved state that fails to deserialize used
+//! to be replaced by a fresh one on the next save, destroying whatever was left of the
+//! user's data. Instead, the file is moved into a `quarantine` folder beside it together
+//! with a reason file saying where it came from and why it couldn't be read, and the app
+//! starts fresh. The main window shows a banner for each file that hasn't been dealt with
+//! yet, offering to [`salvage`] it: the common damage, a write cut short or trailing
+//! garbage, is repaired and the data put back.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Folder beside a data file that its quarantined copies are moved to
+pub const QUARANTINE_DIR: &str = "quarantine";
+
+/// Ending of the file recording why a file was quarantined
+const REASON_SUFFIX: &str = ".reason.json";
+
+/// Local time at the start of a quarantined file's name
+const QUARANTINE_TIME_FORMAT: &str = "%Y%m%d_%H%M%S";
+
+/// A data file moved out of the way because it couldn't be read
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuarantinedFile {
+    /// Where the file is now
+    pub path: PathBuf,
+    /// Where it was read from
+    pub original: PathBuf,
+    /// Why it couldn't be read
+    pub reason: String,
+    /// When it was moved
+    pub quarantined: DateTime<Local>,
+    /// Whether recovery has been attempted, so the banner is no longer shown
+    #[serde(default)]
+    pub resolved: bool,
+}
+
+impl QuarantinedFile {
+    /// Name of the original file, e.g. `battery_profile.json`
+    pub fn file_name(&self) -> String {
+        self.original
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn reason_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(REASON_SUFFIX);
+        PathBuf::from(name)
+    }
+
+    /// Record that recovery was attempted
+    pub fn mark_resolved(&mut self) -> io::Result<()> {
+        self.resolved = true;
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(self.reason_path(), json)
+    }
+}
+
+/// Move the file at `path` into the quarantine folder beside it, recording `reason`
+pub fn quarantine(path: &Path, reason: &str) -> io::Result<QuarantinedFile> {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let folder = parent.join(QUARANTINE_DIR);
+    fs::create_dir_all(&folder)?;
+
+    let quarantined = Local::now();
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let target = folder.join(format!(
+        "{}_{}",
+        quarantined.format(QUARANTINE_TIME_FORMAT),
+        name
+    ));
+    if fs::rename(path, &target).is_err() {
+        // Renaming fails across drives; the copy keeps the evidence all the same
+        fs::copy(path, &target)?;
+        fs::remove_file(path)?;
+    }
+
+    let file = QuarantinedFile {
+        path: target,
+        original: path.to_path_buf(),
+        reason: reason.to_string(),
+        quarantined,
+        resolved: false,
+    };
+    let json = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+    fs::write(file.reason_path(), json)?;
+    log::warn!(
+        "Moved unreadable {} to {}: {}",
+        path.display(),
+        file.path.display(),
+        reason
+    );
+    Ok(file)
+}
+
+/// Files quarantined from `folder` that recovery hasn't been attempted for, oldest first
+pub fn unresolved(folder: &Path) -> Vec<QuarantinedFile> {
+    let Ok(entries) = fs::read_dir(folder.join(QUARANTINE_DIR)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<QuarantinedFile> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.to_string_lossy().ends_with(REASON_SUFFIX))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .filter_map(|json| serde_json::from_str::<QuarantinedFile>(&json).ok())
+        .filter(|file| !file.resolved && file.path.exists())
+        .collect();
+    files.sort_by_key(|file| file.quarantined);
+    files
+}
+
+/// Read a quarantined file as `T`, repairing it if it can be
+///
+/// NUL bytes left by a lost write and a byte order mark are removed, anything after the
+/// first complete JSON value is ignored and a value cut short has its open strings,
+/// arrays and objects closed.
+pub fn salvage<T: DeserializeOwned>(file: &QuarantinedFile) -> Result<T, String> {
+    let bytes = fs::read(&file.path).map_err(|e| e.to_string())?;
+    let text = String::from_utf8_lossy(&bytes).replace('\0', "");
+    let text = text.trim_start_matches('\u{feff}').trim();
+
+    let first = serde_json::Deserializer::from_str(text)
+        .into_iter::<T>()
+        .next()
+        .and_then(Result::ok);
+    if let Some(value) = first {
+        return Ok(value);
+    }
+    serde_json::from_str(&close_truncated_json(text)).map_err(|e| e.to_string())
+}
+
+/// Read a quarantined file as `T` and write what could be salvaged back where it came from
+pub fn restore<T: DeserializeOwned + Serialize>(file: &QuarantinedFile) -> Result<T, String> {
+    let value: T = salvage(file)?;
+    let json = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    fs::write(&file.original, json).map_err(|e| e.to_string())?;
+    log::info!(
+        "Restored {} from {}",
+        file.original.display(),
+        file.path.display()
+    );
+    Ok(value)
+}
+
+/// `json` with its unterminated string, arrays and objects closed
+///
+/// A trailing comma or dangling key is dropped first, since the value that followed it
+/// is lost.
+fn close_truncated_json(json: &str) -> String {
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in json.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut repaired = json.to_string();
+    if in_string {
+        repaired.push('"');
+    }
+    let mut trimmed = repaired.trim_end();
+    if trimmed.ends_with(':') {
+        // A key without its value
+        trimmed = trimmed.trim_end_matches(':').trim_end();
+        if let Some(start) = trimmed.strip_suffix('"').and_then(|key| key.rfind('"')) {
+            trimmed = trimmed[..start].trim_end();
+        }
+    }
+    let mut repaired = trimmed.trim_end_matches(',').to_string();
+    repaired.extend(closers.into_iter().rev());
+    repaired
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_quarantine_keeps_the_file_and_reason() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("known_devices.json");
+        fs::write(&path, r#"{"devices": [{"address": "aabb"#).unwrap();
+
+        let mut file = quarantine(&path, "EOF while parsing").unwrap();
+        assert!(!path.exists());
+        assert!(file.path.exists());
+        assert_eq!(unresolved(dir.path()), vec![file.clone()]);
+
+        let value: serde_json::Value = restore(&file).unwrap();
+        assert_eq!(value["devices"][0]["address"], "aabb");
+        assert!(path.exists());
+
+        file.mark_resolved().unwrap();
+        assert!(unresolved(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_truncated_json_is_closed() {
+        assert_eq!(close_truncated_json(r#"{"a": [1, 2,"#), r#"{"a": [1, 2]}"#);
+        assert_eq!(close_truncated_json(r#"{"a": 1, "b":"#), r#"{"a": 1}"#);
+        assert_eq!(close_truncated_json(r#"{"a": "x\"y"#), r#"{"a": "x\"y"}"#);
+    }
+}
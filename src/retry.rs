@@ -0,0 +1,504 @@
+//! Retrying failed operations with exponential backoff
+//!
+//! Bluetooth calls, scanner runs and file writes fail now and then for reasons that pass
+//! on their own: a busy adapter, a scanner that timed out, a settings file held open by
+//! an antivirus scan. Each used to retry in its own way with its own hard-coded counts.
+//! They now share a [`RetryPolicy`] per [`RetryDomain`], set from `system.retry` in the
+//! settings, and classify their errors with [`Classify`]: transient errors are retried,
+//! timeouts only if the policy says so and permanent errors never. Every retry and every
+//! give-up is counted by the [`ErrorManager`] given to [`report_to`].
+
+use std::collections::hash_map::RandomState;
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{BluetoothError, ErrorManager};
+
+/// Policies set from the settings; the defaults until [`configure`] is called
+static POLICIES: Mutex<Option<RetryConfig>> = Mutex::new(None);
+
+/// Error manager retries are reported to
+static REPORTER: Mutex<Option<Arc<Mutex<ErrorManager>>>> = Mutex::new(None);
+
+/// How an error should be treated when deciding whether to try again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// Likely to pass if tried again shortly
+    Transient,
+    /// The operation took too long
+    Timeout,
+    /// Trying again won't help without the user doing something
+    Permanent,
+}
+
+/// Errors that can say whether retrying them is worthwhile
+pub trait Classify {
+    /// How this error should be treated
+    fn error_class(&self) -> ErrorClass;
+}
+
+impl Classify for BluetoothError {
+    fn error_class(&self) -> ErrorClass {
+        match self {
+            BluetoothError::Timeout(_) => ErrorClass::Timeout,
+            BluetoothError::ConnectionFailed(_)
+            | BluetoothError::ScanFailed(_)
+            | BluetoothError::DeviceDisconnected(_)
+            | BluetoothError::ApiError(_)
+            | BluetoothError::Other(_)
+            | BluetoothError::AdapterRefreshFailed { .. }
+            | BluetoothError::AdapterScanFailed { .. } => ErrorClass::Transient,
+            BluetoothError::NoAdapter
+            | BluetoothError::RadioOff
+            | BluetoothError::InvalidData(_)
+            | BluetoothError::DeviceNotFound(_)
+            | BluetoothError::PermissionDenied(_)
+            | BluetoothError::AdapterNotAvailable { .. } => ErrorClass::Permanent,
+        }
+    }
+}
+
+impl Classify for io::Error {
+    fn error_class(&self) -> ErrorClass {
+        // Sharing and lock violations: another process has the file open
+        if matches!(self.raw_os_error(), Some(32) | Some(33)) && cfg!(windows) {
+            return ErrorClass::Transient;
+        }
+        match self.kind() {
+            io::ErrorKind::TimedOut => ErrorClass::Timeout,
+            io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock => ErrorClass::Transient,
+            // A read-only file or folder stays that way however often it is tried
+            _ => ErrorClass::Permanent,
+        }
+    }
+}
+
+/// An error replacing a file by renaming another over it
+///
+/// Windows reports the target being open in another process as access denied, so that
+/// is retried here, unlike for other IO errors.
+#[derive(Debug)]
+pub struct ReplaceError(pub io::Error);
+
+impl Classify for ReplaceError {
+    fn error_class(&self) -> ErrorClass {
+        match self.0.kind() {
+            io::ErrorKind::PermissionDenied if cfg!(windows) => ErrorClass::Transient,
+            _ => self.0.error_class(),
+        }
+    }
+}
+
+impl Display for ReplaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Group of operations sharing one policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDomain {
+    /// Adapter setup and device connections
+    Bluetooth,
+    /// Runs of the external scanner
+    Scanner,
+    /// Writes of settings and battery data
+    Storage,
+}
+
+/// When and how often a failed operation is tried again
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included
+    pub max_attempts: u32,
+
+    /// Wait after the first failure, in milliseconds
+    pub initial_delay_ms: u64,
+
+    /// Longest wait between attempts, in milliseconds
+    pub max_delay_ms: u64,
+
+    /// Each wait is this many times the previous one
+    pub backoff_factor: u32,
+
+    /// Waits are varied randomly by up to this percentage, so clients don't retry in step
+    pub jitter_percent: u32,
+
+    /// Whether timeouts are retried like transient errors
+    pub retry_timeouts: bool,
+}
+
+impl RetryPolicy {
+    /// Policy for `domain` when the settings don't override it
+    pub fn default_for(domain: RetryDomain) -> Self {
+        match domain {
+            RetryDomain::Bluetooth => Self {
+                max_attempts: 3,
+                initial_delay_ms: 500,
+                max_delay_ms: 5_000,
+                backoff_factor: 2,
+                jitter_percent: 20,
+                retry_timeouts: true,
+            },
+            RetryDomain::Scanner => Self {
+                max_attempts: 3,
+                initial_delay_ms: 5_000,
+                max_delay_ms: 300_000,
+                backoff_factor: 2,
+                jitter_percent: 10,
+                retry_timeouts: true,
+            },
+            RetryDomain::Storage => Self {
+                max_attempts: 4,
+                initial_delay_ms: 50,
+                max_delay_ms: 1_000,
+                backoff_factor: 3,
+                jitter_percent: 25,
+                retry_timeouts: false,
+            },
+        }
+    }
+
+    /// Whether an error of `class` is tried again after `attempts` attempts
+    pub fn allows(&self, class: ErrorClass, attempts: u32) -> bool {
+        let retried = match class {
+            ErrorClass::Transient => true,
+            ErrorClass::Timeout => self.retry_timeouts,
+            ErrorClass::Permanent => false,
+        };
+        retried && attempts < self.max_attempts
+    }
+
+    /// Wait after `failures` failures in a row, before jitter
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = u64::from(self.backoff_factor.max(1));
+        let mut delay = self.initial_delay_ms;
+        for _ in 1..failures {
+            delay = delay.saturating_mul(factor);
+            if delay >= self.max_delay_ms {
+                break;
+            }
+        }
+        Duration::from_millis(delay.min(self.max_delay_ms))
+    }
+
+    /// Wait after `failures` failures in a row, varied by the jitter
+    pub fn jittered_delay(&self, failures: u32) -> Duration {
+        let delay = self.delay(failures).as_millis() as u64;
+        let spread = delay * u64::from(self.jitter_percent.min(100)) / 100;
+        if spread == 0 {
+            return Duration::from_millis(delay);
+        }
+        let offset = random() % (2 * spread + 1);
+        Duration::from_millis(delay - spread + offset)
+    }
+
+    /// Why the policy can't be used, if it can't
+    pub fn problem(&self) -> Option<&'static str> {
+        if self.max_attempts == 0 {
+            Some("At least one attempt must be made")
+        } else if self.backoff_factor == 0 {
+            Some("The backoff factor must be at least 1")
+        } else if self.jitter_percent > 100 {
+            Some("Jitter cannot exceed 100%")
+        } else if self.initial_delay_ms > self.max_delay_ms {
+            Some("The initial delay cannot exceed the maximum delay")
+        } else {
+            None
+        }
+    }
+}
+
+/// Policies for every domain, as kept in the settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Adapter setup and device connections
+    #[serde(default = "default_bluetooth_policy")]
+    pub bluetooth: RetryPolicy,
+
+    /// Runs of the external scanner; it never gives up, so only its delays are used
+    #[serde(default = "default_scanner_policy")]
+    pub scanner: RetryPolicy,
+
+    /// Writes of settings and battery data
+    #[serde(default = "default_storage_policy")]
+    pub storage: RetryPolicy,
+}
+
+impl RetryConfig {
+    /// Policy for `domain`
+    pub fn policy(&self, domain: RetryDomain) -> RetryPolicy {
+        match domain {
+            RetryDomain::Bluetooth => self.bluetooth,
+            RetryDomain::Scanner => self.scanner,
+            RetryDomain::Storage => self.storage,
+        }
+    }
+
+    /// Each policy with the name of its setting
+    pub fn policies(&self) -> [(&'static str, &RetryPolicy); 3] {
+        [
+            ("bluetooth", &self.bluetooth),
+            ("scanner", &self.scanner),
+            ("storage", &self.storage),
+        ]
+    }
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            bluetooth: default_bluetooth_policy(),
+            scanner: default_scanner_policy(),
+            storage: default_storage_policy(),
+        }
+    }
+}
+
+fn default_bluetooth_policy() -> RetryPolicy {
+    RetryPolicy::default_for(RetryDomain::Bluetooth)
+}
+
+fn default_scanner_policy() -> RetryPolicy {
+    RetryPolicy::default_for(RetryDomain::Scanner)
+}
+
+fn default_storage_policy() -> RetryPolicy {
+    RetryPolicy::default_for(RetryDomain::Storage)
+}
+
+/// Use the policies in `config` from now on
+pub fn configure(config: &RetryConfig) {
+    if let Ok(mut policies) = POLICIES.lock() {
+        *policies = Some(config.clone());
+    }
+}
+
+/// Current policy for `domain`
+pub fn policy(domain: RetryDomain) -> RetryPolicy {
+    POLICIES
+        .lock()
+        .ok()
+        .and_then(|policies| policies.as_ref().map(|config| config.policy(domain)))
+        .unwrap_or_else(|| RetryPolicy::default_for(domain))
+}
+
+/// Count retries in `manager` from now on
+pub fn report_to(manager: Arc<Mutex<ErrorManager>>) {
+    if let Ok(mut reporter) = REPORTER.lock() {
+        *reporter = Some(manager);
+    }
+}
+
+/// Count a retry of `operation`, or giving up on it, in the error manager
+pub(crate) fn report(operation: &str, gave_up: bool) {
+    let manager = REPORTER.lock().ok().and_then(|reporter| reporter.clone());
+    if let Some(manager) = manager {
+        if let Ok(mut manager) = manager.lock() {
+            manager.record_retry(operation, gave_up);
+        }
+    }
+}
+
+/// Pseudo-random number for jitter; a fresh `RandomState` is seeded differently each time
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Attempts at one operation under a policy
+///
+/// Call [`Retry::failed`] after each failed attempt; it says how long to wait before the
+/// next one, or `None` to give up.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    operation: String,
+    policy: RetryPolicy,
+    attempts: u32,
+}
+
+impl Retry {
+    /// Attempts at `operation` under the current policy for `domain`
+    pub fn new(operation: impl Into<String>, domain: RetryDomain) -> Self {
+        Self::with_policy(operation, policy(domain))
+    }
+
+    /// Attempts at `operation` under `policy`
+    pub fn with_policy(operation: impl Into<String>, policy: RetryPolicy) -> Self {
+        Self {
+            operation: operation.into(),
+            policy,
+            attempts: 0,
+        }
+    }
+
+    /// Attempts that have failed so far
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Record a failed attempt and return the wait before the next, or `None` to give up
+    pub fn failed<E: Classify + Display>(&mut self, error: &E) -> Option<Duration> {
+        self.attempts += 1;
+        let class = error.error_class();
+        if self.policy.allows(class, self.attempts) {
+            let delay = self.policy.jittered_delay(self.attempts);
+            log::warn!(
+                "{} failed (attempt {}/{}): {}; retrying in {}ms",
+                self.operation,
+                self.attempts,
+                self.policy.max_attempts,
+                error,
+                delay.as_millis()
+            );
+            report(&self.operation, false);
+            Some(delay)
+        } else {
+            if class != ErrorClass::Permanent {
+                log::error!(
+                    "{} failed after {} attempts: {}",
+                    self.operation,
+                    self.attempts,
+                    error
+                );
+                report(&self.operation, true);
+            }
+            None
+        }
+    }
+
+    /// Record that the last attempt worked
+    pub fn succeeded(&self) {
+        if self.attempts > 0 {
+            log::info!(
+                "{} succeeded after {} failed attempts",
+                self.operation,
+                self.attempts
+            );
+        }
+    }
+}
+
+/// Run `attempt` until it succeeds or the policy for `domain` gives up, sleeping between
+pub fn retry_blocking<T, E: Classify + Display>(
+    operation: &str,
+    domain: RetryDomain,
+    mut attempt: impl FnMut() -> Result<T, E>,
+) -> Result<T, E> {
+    let mut retry = Retry::new(operation, domain);
+    loop {
+        match attempt() {
+            Ok(value) => {
+                retry.succeeded();
+                return Ok(value);
+            }
+            Err(e) => match retry.failed(&e) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return Err(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay_ms: 100,
+            max_delay_ms: 1_000,
+            backoff_factor: 2,
+            jitter_percent: 0,
+            retry_timeouts: false,
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_and_is_capped() {
+        let policy = policy();
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(3), Duration::from_millis(400));
+        assert_eq!(policy.delay(40), Duration::from_millis(1_000));
+
+        let jittered = RetryPolicy {
+            jitter_percent: 20,
+            ..policy
+        };
+        for _ in 0..50 {
+            let delay = jittered.jittered_delay(1).as_millis();
+            assert!((80..=120).contains(&delay), "{}", delay);
+        }
+    }
+
+    #[test]
+    fn test_error_class_decides_retries() {
+        let policy = policy();
+        assert!(policy.allows(ErrorClass::Transient, 2));
+        assert!(!policy.allows(ErrorClass::Transient, 3));
+        assert!(!policy.allows(ErrorClass::Timeout, 1));
+        assert!(!policy.allows(ErrorClass::Permanent, 1));
+
+        assert_eq!(
+            BluetoothError::RadioOff.error_class(),
+            ErrorClass::Permanent
+        );
+        assert_eq!(
+            io::Error::from(io::ErrorKind::TimedOut).error_class(),
+            ErrorClass::Timeout
+        );
+
+        // A read-only settings file isn't worth waiting for; a file being replaced may be
+        let denied = || io::Error::from(io::ErrorKind::PermissionDenied);
+        assert_eq!(denied().error_class(), ErrorClass::Permanent);
+        let replacing = if cfg!(windows) {
+            ErrorClass::Transient
+        } else {
+            ErrorClass::Permanent
+        };
+        assert_eq!(ReplaceError(denied()).error_class(), replacing);
+        assert_eq!(
+            ReplaceError(io::Error::from(io::ErrorKind::Interrupted)).error_class(),
+            ErrorClass::Transient
+        );
+    }
+
+    #[test]
+    fn test_retry_blocking_stops_at_max_attempts() {
+        let mut calls = 0;
+        let result: Result<(), io::Error> =
+            retry_blocking("test write", RetryDomain::Storage, || {
+                calls += 1;
+                Err(io::Error::from(io::ErrorKind::Interrupted))
+            });
+        assert!(result.is_err());
+        assert_eq!(
+            calls,
+            RetryPolicy::default_for(RetryDomain::Storage).max_attempts
+        );
+
+        let mut calls = 0;
+        let result: Result<(), io::Error> =
+            retry_blocking("test write", RetryDomain::Storage, || {
+                calls += 1;
+                Err(io::Error::from(io::ErrorKind::NotFound))
+            });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_settings_without_retry_section_use_defaults() {
+        let config: RetryConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, RetryConfig::default());
+        assert!(config
+            .policies()
+            .iter()
+            .all(|(_, policy)| policy.problem().is_none()));
+    }
+}
@@ -0,0 +1,435 @@
+//! Rules engine for user-defined actions
+//!
+//! Each battery update is compared with the last known state of the primary device to
+//! find events (a component dropping below a level, charging starting, an AirPod leaving
+//! an ear, a component finishing charging, the device coming back in range, connecting to
+//! or disconnecting from the PC). Enabled rules whose trigger matches an event
+//! fire once per event; a battery rule fires again only after the component has charged
+//! or climbed back above its threshold.
+
+use thiserror::Error;
+
+use crate::config::{ActionRule, BatteryComponent, RuleAction, RuleTrigger};
+
+/// Component names, indexed like [`DeviceSnapshot::levels`]
+const COMPONENT_NAMES: [&str; 3] = ["Left AirPod", "Right AirPod", "Case"];
+
+/// Errors raised while running a rule's action
+#[derive(Debug, Error)]
+pub enum RuleError {
+    /// The command could not be started
+    #[error("Failed to run command '{0}': {1}")]
+    CommandFailed(String, String),
+}
+
+/// State of the primary device at one battery update
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSnapshot {
+    /// Display name of the device
+    pub name: String,
+    /// Battery levels (left, right, case)
+    pub levels: [Option<u8>; 3],
+    /// Charging flags (left, right, case)
+    pub charging: [bool; 3],
+    /// In-ear flags (left, right)
+    pub in_ear: [Option<bool>; 2],
+    /// Whether the device is connected to this PC, not only in range
+    pub connected: bool,
+}
+
+impl DeviceSnapshot {
+    /// Whether a component is at or below `threshold` and not charging
+    fn is_low(&self, component: usize, threshold: u8) -> bool {
+        !self.charging[component] && self.levels[component].is_some_and(|level| level <= threshold)
+    }
+
+    /// Keep earlier readings for components missing from this one
+    fn merged_onto(mut self, previous: &DeviceSnapshot) -> Self {
+        for (level, previous) in self.levels.iter_mut().zip(previous.levels) {
+            *level = level.or(previous);
+        }
+        for (in_ear, previous) in self.in_ear.iter_mut().zip(previous.in_ear) {
+            *in_ear = in_ear.or(previous);
+        }
+        self
+    }
+}
+
+/// Details of the event that fired a rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventContext {
+    /// Device name
+    pub device: String,
+    /// Component involved, if the event concerns one
+    pub component: Option<&'static str>,
+    /// Battery level of that component, if known
+    pub level: Option<u8>,
+}
+
+impl EventContext {
+    /// Replace `{device}`, `{component}` and `{level}` in `text`
+    pub fn expand(&self, text: &str) -> String {
+        text.replace("{device}", &self.device)
+            .replace("{component}", self.component.unwrap_or_default())
+            .replace(
+                "{level}",
+                &self
+                    .level
+                    .map(|level| level.to_string())
+                    .unwrap_or_default(),
+            )
+    }
+}
+
+/// A rule that fired, with the event it fired for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiredAction {
+    /// Name of the rule
+    pub rule: String,
+    /// Action to run
+    pub action: RuleAction,
+    /// Event details
+    pub context: EventContext,
+}
+
+/// Finds the rules to fire as device state changes
+#[derive(Debug, Clone, Default)]
+pub struct RuleEngine {
+    /// Last known state of the device
+    last: Option<DeviceSnapshot>,
+    /// Whether the device was in range at the last update
+    present: bool,
+}
+
+impl RuleEngine {
+    /// Create an engine that has not seen a device yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare `current` with the last update and return the actions to run
+    ///
+    /// `current` is `None` while the device is out of range.
+    pub fn evaluate(
+        &mut self,
+        rules: &[ActionRule],
+        current: Option<DeviceSnapshot>,
+    ) -> Vec<FiredAction> {
+        let Some(current) = current else {
+            // Leaving range while connected is a disconnection too
+            let was_connected =
+                self.present && self.last.as_ref().is_some_and(|last| last.connected);
+            self.present = false;
+            let Some(last) = self.last.as_mut().filter(|_| was_connected) else {
+                return Vec::new();
+            };
+            last.connected = false;
+            return rules
+                .iter()
+                .filter(|rule| rule.enabled && rule.trigger == RuleTrigger::DeviceDisconnected)
+                .map(|rule| FiredAction {
+                    rule: rule.name.clone(),
+                    action: rule.action.clone(),
+                    context: EventContext {
+                        device: last.name.clone(),
+                        component: None,
+                        level: None,
+                    },
+                })
+                .collect();
+        };
+
+        let reconnected = !self.present && self.last.is_some();
+        let previous = self.last.take();
+        let current = match &previous {
+            Some(previous) => current.merged_onto(previous),
+            None => current,
+        };
+
+        let mut fired = Vec::new();
+        for rule in rules.iter().filter(|rule| rule.enabled) {
+            for (component, level) in
+                Self::events(rule.trigger, previous.as_ref(), &current, reconnected)
+            {
+                fired.push(FiredAction {
+                    rule: rule.name.clone(),
+                    action: rule.action.clone(),
+                    context: EventContext {
+                        device: current.name.clone(),
+                        component,
+                        level,
+                    },
+                });
+            }
+        }
+
+        self.last = Some(current);
+        self.present = true;
+        fired
+    }
+
+    /// Events matching `trigger`, as (component, level) pairs
+    fn events(
+        trigger: RuleTrigger,
+        previous: Option<&DeviceSnapshot>,
+        current: &DeviceSnapshot,
+        reconnected: bool,
+    ) -> Vec<(Option<&'static str>, Option<u8>)> {
+        let event = |index: usize| (Some(COMPONENT_NAMES[index]), current.levels[index]);
+
+        match trigger {
+            RuleTrigger::BatteryBelow {
+                component,
+                threshold,
+            } => components(component)
+                .filter(|&index| {
+                    current.is_low(index, threshold)
+                        && !previous.is_some_and(|previous| previous.is_low(index, threshold))
+                })
+                .map(event)
+                .collect(),
+            RuleTrigger::ChargingStarted { component } => {
+                let Some(previous) = previous else {
+                    return Vec::new();
+                };
+                components(component)
+                    .filter(|&index| current.charging[index] && !previous.charging[index])
+                    .map(event)
+                    .collect()
+            }
+            RuleTrigger::BudsRemoved => {
+                let Some(previous) = previous else {
+                    return Vec::new();
+                };
+                (0..2)
+                    .filter(|&index| {
+                        previous.in_ear[index] == Some(true) && current.in_ear[index] == Some(false)
+                    })
+                    .map(event)
+                    .collect()
+            }
+            RuleTrigger::DeviceReconnected if reconnected => vec![(None, None)],
+            RuleTrigger::DeviceReconnected => Vec::new(),
+            RuleTrigger::DeviceConnected
+                if current.connected
+                    && previous.is_some_and(|previous| reconnected || !previous.connected) =>
+            {
+                vec![(None, None)]
+            }
+            RuleTrigger::DeviceDisconnected
+                if !current.connected && previous.is_some_and(|previous| previous.connected) =>
+            {
+                vec![(None, None)]
+            }
+            RuleTrigger::DeviceConnected | RuleTrigger::DeviceDisconnected => Vec::new(),
+            RuleTrigger::FullyCharged { component } => {
+                let Some(previous) = previous else {
+                    return Vec::new();
+                };
+                components(component)
+                    .filter(|&index| {
+                        current.levels[index] == Some(100)
+                            && previous.levels[index].is_some_and(|level| level < 100)
+                    })
+                    .map(event)
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Indices of the components a trigger watches
+fn components(component: BatteryComponent) -> impl Iterator<Item = usize> {
+    match component {
+        BatteryComponent::Any => 0..3,
+        BatteryComponent::Left => 0..1,
+        BatteryComponent::Right => 1..2,
+        BatteryComponent::Case => 2..3,
+    }
+}
+
+/// Run a rule's command through the system shell without waiting for it
+///
+/// Event details are passed in environment variables rather than substituted into the
+/// command line, so a device name is never parsed as shell syntax.
+pub fn run_command(command: &str, context: &EventContext) -> Result<(), RuleError> {
+    let mut process = if cfg!(target_os = "windows") {
+        let mut process = std::process::Command::new("cmd");
+        process.args(["/C", command]);
+        process
+    } else {
+        let mut process = std::process::Command::new("sh");
+        process.args(["-c", command]);
+        process
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        process.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    process
+        .env("RUSTPODS_DEVICE", &context.device)
+        .env("RUSTPODS_COMPONENT", context.component.unwrap_or_default())
+        .env(
+            "RUSTPODS_LEVEL",
+            context
+                .level
+                .map(|level| level.to_string())
+                .unwrap_or_default(),
+        )
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| RuleError::CommandFailed(command.to_string(), e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SoundAlertEvent;
+
+    fn rule(trigger: RuleTrigger) -> ActionRule {
+        ActionRule {
+            name: "test".to_string(),
+            enabled: true,
+            trigger,
+            action: RuleAction::PlaySound { path: None },
+        }
+    }
+
+    fn snapshot(left: u8, left_charging: bool, left_in_ear: bool) -> DeviceSnapshot {
+        DeviceSnapshot {
+            name: "AirPods Pro".to_string(),
+            levels: [Some(left), Some(80), None],
+            charging: [left_charging, false, false],
+            in_ear: [Some(left_in_ear), Some(true)],
+            connected: true,
+        }
+    }
+
+    #[test]
+    fn test_battery_rule_fires_once_until_recharged() {
+        let rules = [rule(RuleTrigger::BatteryBelow {
+            component: BatteryComponent::Left,
+            threshold: 20,
+        })];
+        let mut engine = RuleEngine::new();
+
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(30, false, true)))
+            .is_empty());
+        let fired = engine.evaluate(&rules, Some(snapshot(20, false, true)));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(
+            fired[0].context.expand("{component} at {level}%"),
+            "Left AirPod at 20%"
+        );
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(15, false, true)))
+            .is_empty());
+
+        // Charging re-arms the rule
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(15, true, true)))
+            .is_empty());
+        assert_eq!(
+            engine
+                .evaluate(&rules, Some(snapshot(15, false, true)))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_transition_rules() {
+        let rules = [
+            rule(RuleTrigger::ChargingStarted {
+                component: BatteryComponent::Any,
+            }),
+            rule(RuleTrigger::BudsRemoved),
+            rule(RuleTrigger::DeviceReconnected),
+        ];
+        let mut engine = RuleEngine::new();
+
+        // The first sighting is not a transition
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(50, false, true)))
+            .is_empty());
+
+        let fired = engine.evaluate(&rules, Some(snapshot(50, true, false)));
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[0].context.component, Some("Left AirPod"));
+
+        assert!(engine.evaluate(&rules, None).is_empty());
+        let fired = engine.evaluate(&rules, Some(snapshot(50, true, false)));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].context.component, None);
+    }
+
+    #[test]
+    fn test_connection_rules() {
+        let rules = [
+            rule(RuleTrigger::DeviceConnected),
+            rule(RuleTrigger::DeviceDisconnected),
+        ];
+        let nearby = DeviceSnapshot {
+            connected: false,
+            ..snapshot(50, false, false)
+        };
+        let mut engine = RuleEngine::new();
+
+        assert!(engine.evaluate(&rules, Some(nearby.clone())).is_empty());
+        assert_eq!(
+            engine
+                .evaluate(&rules, Some(snapshot(50, false, true)))
+                .len(),
+            1
+        );
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(50, false, true)))
+            .is_empty());
+
+        // Dropping to nearby and leaving range while connected both disconnect, once
+        assert_eq!(engine.evaluate(&rules, Some(nearby)).len(), 1);
+        assert_eq!(
+            engine
+                .evaluate(&rules, Some(snapshot(50, false, true)))
+                .len(),
+            1
+        );
+        assert_eq!(engine.evaluate(&rules, None).len(), 1);
+        assert!(engine.evaluate(&rules, None).is_empty());
+    }
+
+    #[test]
+    fn test_sound_alerts_fire_as_rules() {
+        let mut ui = crate::config::UiConfig::default();
+        ui.sound_alerts.enabled = true;
+        ui.sound_alerts.disconnect = false;
+        ui.sound_alerts
+            .set_sound(SoundAlertEvent::FullyCharged, "done.wav");
+        let rules = ui.sound_alerts.rules(&ui);
+        // One low battery rule per component, and fully charged
+        assert_eq!(rules.len(), 4);
+
+        let mut engine = RuleEngine::new();
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(95, true, true)))
+            .is_empty());
+        let fired = engine.evaluate(&rules, Some(snapshot(100, true, true)));
+        assert_eq!(fired.len(), 1);
+        assert_eq!(
+            fired[0].action,
+            RuleAction::PlaySound {
+                path: Some("done.wav".to_string())
+            }
+        );
+        assert!(engine
+            .evaluate(&rules, Some(snapshot(100, false, true)))
+            .is_empty());
+
+        ui.sound_alerts.enabled = false;
+        assert!(ui.sound_alerts.rules(&ui).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+//! Restarting RustPods and safe mode
+//!
+//! "Restart RustPods" starts a new process with the same arguments and then closes this
+//! one. Safe mode (`--safe-mode`, or "Restart in Safe Mode") leaves out what is most
+//! likely to keep a broken profile from starting: the battery history is neither read
+//! nor written, action rules and spoken announcements are off, and the theme, font and
+//! window backdrop are the defaults. The settings left out are overrides for the run, so
+//! `settings.json` keeps them and a normal restart brings them back.
+
+use std::ffi::OsString;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Command line flag that starts RustPods in safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+/// Settings put back to their defaults in safe mode, as dot-paths
+pub const SAFE_MODE_DEFAULTS: &[&str] = &[
+    "actions.rules",
+    "ui.speech.enabled",
+    "ui.theme",
+    "ui.theme_schedule",
+    "ui.font_family",
+    "ui.font_scale",
+    "ui.backdrop",
+];
+
+/// Whether this process runs in safe mode
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Run this process in safe mode; call before the configuration is loaded
+pub fn enable() {
+    SAFE_MODE.store(true, Ordering::SeqCst);
+    log::warn!("Running in safe mode: battery history, action rules and custom themes are off");
+}
+
+/// Whether this process runs in safe mode
+pub fn is_enabled() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// Arguments for the new process: `args` with the safe mode flag added or removed
+pub fn restart_args<I>(args: I, safe_mode: bool) -> Vec<OsString>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut args: Vec<OsString> = args
+        .into_iter()
+        .filter(|arg| arg != SAFE_MODE_FLAG)
+        .collect();
+    if safe_mode {
+        args.push(SAFE_MODE_FLAG.into());
+    }
+    args
+}
+
+/// Start a new RustPods process, in safe mode or not
+///
+/// The caller exits once this returns `Ok`, after flushing what it has to save.
+pub fn restart(safe_mode: bool) -> io::Result<()> {
+    restart_with(std::env::args_os().skip(1), safe_mode)
+}
+
+/// Like [`restart`], with `args` instead of this process's arguments
+pub fn restart_with<I>(args: I, safe_mode: bool) -> io::Result<()>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let exe = std::env::current_exe()?;
+    std::process::Command::new(exe)
+        .args(restart_args(args, safe_mode))
+        .spawn()?;
+    log::info!(
+        "Started a new RustPods process{}",
+        if safe_mode { " in safe mode" } else { "" }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_args_toggle_safe_mode() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            restart_args(args(&["--info"]), true),
+            args(&["--info", SAFE_MODE_FLAG])
+        );
+        assert_eq!(
+            restart_args(args(&[SAFE_MODE_FLAG, "--info"]), false),
+            args(&["--info"])
+        );
+        // Restarting in safe mode from safe mode doesn't repeat the flag
+        assert_eq!(
+            restart_args(args(&[SAFE_MODE_FLAG]), true),
+            args(&[SAFE_MODE_FLAG])
+        );
+    }
+}
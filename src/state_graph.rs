@@ -0,0 +1,394 @@
+//! Graphviz export of state machines and message flow
+//!
+//! `rustpods graph` prints DOT graphs of the device detection states shown by the UI,
+//! the presence states a tracked device goes through, and which parts of the UI send
+//! which [`Message`](crate::ui::Message) to the application state. Hand-drawn diagrams
+//! of these drift as soon as a variant is added, so every graph here is built from the
+//! definitions themselves: the state lists and transition tables the code checks its
+//! own transitions against, and the source of the message enum and its senders.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use crate::monitor::Presence;
+use crate::ui::state::DeviceDetectionState;
+
+/// A transition a state machine may make
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Transition {
+    /// State the transition starts from, or `None` for any state
+    pub from: Option<&'static str>,
+    /// State the transition leads to
+    pub to: &'static str,
+    /// What causes the transition
+    pub trigger: &'static str,
+}
+
+impl Transition {
+    /// Whether this transition covers moving from `from` to `to`
+    pub fn allows(&self, from: &str, to: &str) -> bool {
+        self.to == to && self.from.map_or(true, |state| state == from)
+    }
+}
+
+/// Whether `transitions` contain a move from `from` to `to`
+///
+/// Staying in the same state is always allowed.
+pub fn is_allowed(transitions: &[Transition], from: &str, to: &str) -> bool {
+    from == to || transitions.iter().any(|t| t.allows(from, to))
+}
+
+/// A state machine as drawn
+#[derive(Debug, Clone, Copy)]
+pub struct StateMachine {
+    /// Graph name
+    pub name: &'static str,
+    /// State the machine starts in
+    pub initial: &'static str,
+    /// Every state
+    pub states: &'static [&'static str],
+    /// Every transition
+    pub transitions: &'static [Transition],
+}
+
+impl StateMachine {
+    /// The UI's device detection states
+    pub fn device_detection() -> Self {
+        Self {
+            name: "device_detection",
+            initial: DeviceDetectionState::Idle.name(),
+            states: DeviceDetectionState::NAMES,
+            transitions: DeviceDetectionState::TRANSITIONS,
+        }
+    }
+
+    /// Presence of a device tracked by [`monitor`](crate::monitor::monitor)
+    pub fn presence() -> Self {
+        Self {
+            name: "tracker_presence",
+            initial: Presence::Unseen.name(),
+            states: Presence::NAMES,
+            transitions: Presence::TRANSITIONS,
+        }
+    }
+
+    /// The machine as a DOT digraph
+    ///
+    /// A transition from any state is drawn from every other state.
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("digraph {} {{\n    rankdir=LR;\n", self.name);
+        dot.push_str("    start [shape=point];\n");
+        for state in self.states {
+            let _ = writeln!(dot, "    {} [shape=box, style=rounded];", state);
+        }
+        let _ = writeln!(dot, "    start -> {};", self.initial);
+        for transition in self.transitions {
+            let sources: Vec<&str> = match transition.from {
+                Some(from) => vec![from],
+                None => self
+                    .states
+                    .iter()
+                    .copied()
+                    .filter(|state| *state != transition.to)
+                    .collect(),
+            };
+            for from in sources {
+                let _ = writeln!(
+                    dot,
+                    "    {} -> {} [label={}];",
+                    from,
+                    transition.to,
+                    quote(transition.trigger)
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Source files that send messages, with the name they are drawn under
+const MESSAGE_SENDERS: &[(&str, &str, &str)] = &[
+    ("App state", "ui/state.rs", include_str!("ui/state.rs")),
+    (
+        "State manager",
+        "ui/state_manager.rs",
+        include_str!("ui/state_manager.rs"),
+    ),
+    (
+        "Main window",
+        "ui/main_window.rs",
+        include_str!("ui/main_window.rs"),
+    ),
+    (
+        "Settings window",
+        "ui/settings_window.rs",
+        include_str!("ui/settings_window.rs"),
+    ),
+    (
+        "Settings",
+        "ui/components/settings_view.rs",
+        include_str!("ui/components/settings_view.rs"),
+    ),
+    (
+        "Title bar",
+        "ui/components/title_bar.rs",
+        include_str!("ui/components/title_bar.rs"),
+    ),
+    (
+        "Error banner",
+        "ui/components/error_banner.rs",
+        include_str!("ui/components/error_banner.rs"),
+    ),
+    (
+        "Dialogs",
+        "ui/components/dialog.rs",
+        include_str!("ui/components/dialog.rs"),
+    ),
+    (
+        "Lid popup",
+        "ui/components/airpods_popup.rs",
+        include_str!("ui/components/airpods_popup.rs"),
+    ),
+    (
+        "Telemetry view",
+        "ui/components/telemetry_view.rs",
+        include_str!("ui/components/telemetry_view.rs"),
+    ),
+    (
+        "Troubleshoot view",
+        "ui/components/troubleshoot_view.rs",
+        include_str!("ui/components/troubleshoot_view.rs"),
+    ),
+    (
+        "Undo notice",
+        "ui/components/undo_notice.rs",
+        include_str!("ui/components/undo_notice.rs"),
+    ),
+    (
+        "Scanner status",
+        "ui/components/scanner_status.rs",
+        include_str!("ui/components/scanner_status.rs"),
+    ),
+    (
+        "Unknown model notice",
+        "ui/components/unknown_model_notice.rs",
+        include_str!("ui/components/unknown_model_notice.rs"),
+    ),
+    (
+        "Software rendering notice",
+        "ui/components/software_rendering_notice.rs",
+        include_str!("ui/components/software_rendering_notice.rs"),
+    ),
+    (
+        "Keyboard",
+        "ui/keyboard_shortcuts.rs",
+        include_str!("ui/keyboard_shortcuts.rs"),
+    ),
+    (
+        "Keyboard focus",
+        "ui/accessibility.rs",
+        include_str!("ui/accessibility.rs"),
+    ),
+    (
+        "System tray",
+        "ui/system_tray.rs",
+        include_str!("ui/system_tray.rs"),
+    ),
+    (
+        "Window visibility",
+        "ui/window_visibility.rs",
+        include_str!("ui/window_visibility.rs"),
+    ),
+];
+
+/// Variants of the message enum, in declaration order
+pub fn message_variants() -> Vec<&'static str> {
+    let source = include_str!("ui/message.rs");
+    let Some(start) = source.find("pub enum Message {") else {
+        return Vec::new();
+    };
+    source[start..]
+        .lines()
+        .skip(1)
+        .take_while(|line| *line != "}")
+        .filter(|line| line.starts_with("    ") && !line.starts_with("     "))
+        .map(str::trim_start)
+        .filter(|line| line.starts_with(|c: char| c.is_ascii_uppercase()))
+        .map(|line| {
+            let end = line
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(line.len());
+            &line[..end]
+        })
+        .collect()
+}
+
+/// Messages constructed in `source`, leaving out match arms that only handle them
+fn messages_sent(source: &str, variants: &[&'static str]) -> BTreeSet<&'static str> {
+    let mut sent = BTreeSet::new();
+    for line in source.lines() {
+        let trimmed = line.trim_start();
+        // `Message::X => ...` and `| Message::Y` are the receiving end
+        let is_arm = (trimmed.starts_with("Message::") || trimmed.starts_with("| Message::"))
+            && (trimmed.contains("=>") || trimmed.ends_with('|') || trimmed.ends_with('{'));
+        if is_arm || trimmed.starts_with("//") {
+            continue;
+        }
+        for (index, _) in line.match_indices("Message::") {
+            let rest = &line[index + "Message::".len()..];
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            if let Some(variant) = variants.iter().find(|v| **v == &rest[..end]) {
+                sent.insert(*variant);
+            }
+        }
+    }
+    sent
+}
+
+/// Which parts of the UI send which messages, as a DOT digraph
+///
+/// Every message is handled by the application state. Messages no listed file
+/// constructs by name, such as those only passed around as constructors, are drawn
+/// without a sender.
+pub fn message_flow_dot() -> String {
+    let variants = message_variants();
+    let mut dot = String::from("digraph message_flow {\n    rankdir=LR;\n");
+    dot.push_str("    node [shape=box, style=rounded];\n");
+    dot.push_str("    update [label=\"AppState::update\", shape=doubleoctagon];\n");
+    for variant in &variants {
+        let _ = writeln!(
+            dot,
+            "    msg_{} [label={}, shape=note];",
+            variant,
+            quote(variant)
+        );
+        let _ = writeln!(dot, "    msg_{} -> update;", variant);
+    }
+    for (index, (name, path, source)) in MESSAGE_SENDERS.iter().enumerate() {
+        let _ = writeln!(
+            dot,
+            "    sender_{} [label={}, tooltip={}];",
+            index,
+            quote(name),
+            quote(path)
+        );
+        for variant in messages_sent(source, &variants) {
+            let _ = writeln!(dot, "    sender_{} -> msg_{};", index, variant);
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Graph selected on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Graph {
+    /// Device detection states
+    Detection,
+    /// Tracker presence states
+    Presence,
+    /// Message routing
+    Messages,
+}
+
+impl Graph {
+    /// Every graph, in the order they are printed
+    pub const ALL: [Graph; 3] = [Graph::Detection, Graph::Presence, Graph::Messages];
+
+    /// Graph for a command line name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "detection" => Some(Graph::Detection),
+            "presence" => Some(Graph::Presence),
+            "messages" => Some(Graph::Messages),
+            _ => None,
+        }
+    }
+
+    /// The graph in DOT
+    pub fn to_dot(self) -> String {
+        match self {
+            Graph::Detection => StateMachine::device_detection().to_dot(),
+            Graph::Presence => StateMachine::presence().to_dot(),
+            Graph::Messages => message_flow_dot(),
+        }
+    }
+}
+
+/// `text` as a quoted DOT string
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_machines_only_name_their_own_states() {
+        for machine in [StateMachine::device_detection(), StateMachine::presence()] {
+            assert!(machine.states.contains(&machine.initial));
+            for transition in machine.transitions {
+                assert!(machine.states.contains(&transition.to), "{:?}", transition);
+                if let Some(from) = transition.from {
+                    assert!(machine.states.contains(&from), "{:?}", transition);
+                }
+            }
+            assert!(machine.to_dot().starts_with("digraph"));
+        }
+    }
+
+    #[test]
+    fn test_message_flow_covers_the_enum() {
+        let variants = message_variants();
+        assert_eq!(variants.first(), Some(&"ToggleVisibility"));
+        assert!(variants.contains(&"AirPodsDataLoaded"));
+        assert!(variants.contains(&"ResetSettings"));
+        assert!(!variants.contains(&"Message"));
+
+        let dot = message_flow_dot();
+        assert!(dot.contains("msg_RestoreBatteryProfile -> update;"));
+        // The tray's menu sends these
+        let tray = MESSAGE_SENDERS
+            .iter()
+            .position(|(name, _, _)| *name == "System tray")
+            .unwrap();
+        assert!(dot.contains(&format!("sender_{} -> msg_", tray)));
+
+        let arms = "match message {\n    Message::Tick => {}\n    Message::Exit\n    | Message::ForceQuit => {}\n}\nCommand::perform(f, |_| Message::NoOp)";
+        let sent = messages_sent(arms, &variants);
+        assert_eq!(sent.into_iter().collect::<Vec<_>>(), vec!["NoOp"]);
+    }
+
+    #[test]
+    fn test_every_message_sender_is_drawn() {
+        let ui = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let listed: Vec<&str> = MESSAGE_SENDERS.iter().map(|(_, path, _)| *path).collect();
+        for directory in ["ui", "ui/components"] {
+            for entry in std::fs::read_dir(ui.join(directory)).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().map_or(true, |ext| ext != "rs") {
+                    continue;
+                }
+                let relative = format!(
+                    "{}/{}",
+                    directory,
+                    path.file_name().unwrap().to_string_lossy()
+                );
+                let source = std::fs::read_to_string(&path).unwrap();
+                let sends = !messages_sent(&source, &message_variants()).is_empty();
+                let exempt = ["ui/message.rs", "ui/test_helpers.rs", "ui/mod.rs"];
+                assert!(
+                    !sends
+                        || listed.contains(&relative.as_str())
+                        || exempt.contains(&relative.as_str()),
+                    "{} sends messages but isn't in MESSAGE_SENDERS",
+                    relative
+                );
+            }
+        }
+    }
+}
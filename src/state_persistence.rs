@@ -143,9 +143,13 @@ impl StatePersistenceManager {
         let json = fs::read_to_string(&self.state_path)
             .map_err(|e| format!("Failed to read state file: {}", e))?;
 
-        // Deserialize JSON
-        let state: PersistentState = serde_json::from_str(&json)
-            .map_err(|e| format!("Failed to parse state file: {}", e))?;
+        // Deserialize JSON, moving a file that doesn't parse out of the way of the next save
+        let state: PersistentState = serde_json::from_str(&json).map_err(|e| {
+            if let Err(e) = crate::quarantine::quarantine(&self.state_path, &e.to_string()) {
+                log::error!("Failed to quarantine state file: {}", e);
+            }
+            format!("Failed to parse state file: {}", e)
+        })?;
 
         log::info!("Successfully loaded persistent state");
         Ok(state)
@@ -0,0 +1,248 @@
+//! Application identity registration for Windows notifications
+//!
+//! Unpackaged apps are shown with a generic identity in toasts and Action Center unless
+//! they register an Application User Model ID (AUMID). At startup RustPods:
+//!
+//! 1. sets the AUMID for the current process,
+//! 2. registers the display name and icon under
+//!    `HKCU\Software\Classes\AppUserModelId\<AUMID>`,
+//! 3. creates a Start-menu shortcut carrying the AUMID when the user profile allows it.
+//!
+//! Every step is best-effort; failures are logged and never prevent startup.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[cfg(target_os = "windows")]
+use super::to_wide;
+
+/// Application User Model ID used for notifications and taskbar grouping
+pub const APP_USER_MODEL_ID: &str = "Jay1.RustPods";
+
+/// Display name shown on notifications
+pub const APP_DISPLAY_NAME: &str = "RustPods";
+
+/// Errors raised while registering the application identity
+#[derive(Debug, Error)]
+pub enum AppIdentityError {
+    /// Registration is only meaningful on Windows
+    #[error("Application identity registration is not supported on this platform")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// Register the application identity, logging any step that fails
+pub fn register_app_identity() {
+    match set_process_app_id() {
+        Ok(()) => log::info!("Registered AUMID {}", APP_USER_MODEL_ID),
+        Err(AppIdentityError::Unsupported) => return,
+        Err(e) => log::warn!("Failed to set process AUMID: {}", e),
+    }
+
+    if let Err(e) = register_app_id_key() {
+        log::warn!("Failed to register AUMID display information: {}", e);
+    }
+
+    match start_menu_shortcut_path() {
+        Some(shortcut) if !shortcut.exists() => {
+            if let Err(e) = create_start_menu_shortcut(&shortcut) {
+                log::warn!("Failed to create Start menu shortcut: {}", e);
+            } else {
+                log::info!("Created Start menu shortcut at {}", shortcut.display());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Location of the per-user Start menu shortcut
+pub fn start_menu_shortcut_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| {
+        dir.join("Microsoft")
+            .join("Windows")
+            .join("Start Menu")
+            .join("Programs")
+            .join(format!("{}.lnk", APP_DISPLAY_NAME))
+    })
+}
+
+/// Find the PNG icon used for notifications
+#[cfg(target_os = "windows")]
+fn notification_icon_path() -> Option<PathBuf> {
+    let exe_path = std::env::current_exe().ok()?;
+    let exe_dir = exe_path.parent()?;
+
+    let candidates = [
+        exe_dir.join("logo_ring.png"),
+        exe_dir
+            .join("assets")
+            .join("icons")
+            .join("app")
+            .join("logo_ring.png"),
+        // Project root assets (for development)
+        exe_dir
+            .parent()
+            .and_then(|p| p.parent())
+            .map(|root| {
+                root.join("assets")
+                    .join("icons")
+                    .join("app")
+                    .join("logo_ring.png")
+            })
+            .unwrap_or_default(),
+    ];
+
+    candidates.into_iter().find(|path| path.exists())
+}
+
+#[cfg(target_os = "windows")]
+fn set_process_app_id() -> Result<(), AppIdentityError> {
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+
+    let app_id = to_wide(APP_USER_MODEL_ID);
+    unsafe { SetCurrentProcessExplicitAppUserModelID(PCWSTR(app_id.as_ptr())) }.map_err(|e| {
+        AppIdentityError::Api("SetCurrentProcessExplicitAppUserModelID", e.to_string())
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn set_process_app_id() -> Result<(), AppIdentityError> {
+    Err(AppIdentityError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+fn register_app_id_key() -> Result<(), AppIdentityError> {
+    use windows::core::PCWSTR;
+    use windows::Win32::System::Registry::{
+        RegCloseKey, RegCreateKeyExW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_WRITE,
+        REG_OPTION_NON_VOLATILE, REG_SZ,
+    };
+
+    let sub_key = to_wide(&format!(
+        "Software\\Classes\\AppUserModelId\\{}",
+        APP_USER_MODEL_ID
+    ));
+    let mut key = HKEY::default();
+
+    unsafe {
+        let status = RegCreateKeyExW(
+            HKEY_CURRENT_USER,
+            PCWSTR(sub_key.as_ptr()),
+            0,
+            PCWSTR::null(),
+            REG_OPTION_NON_VOLATILE,
+            KEY_WRITE,
+            None,
+            &mut key,
+            None,
+        );
+        if status.is_err() {
+            return Err(AppIdentityError::Api(
+                "RegCreateKeyExW",
+                format!("{:?}", status),
+            ));
+        }
+
+        let mut values = vec![("DisplayName", APP_DISPLAY_NAME.to_string())];
+        if let Some(icon) = notification_icon_path() {
+            values.push(("IconUri", icon.display().to_string()));
+        }
+
+        let mut result = Ok(());
+        for (name, value) in values {
+            let name = to_wide(name);
+            let data: Vec<u8> = to_wide(&value)
+                .iter()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect();
+            let status = RegSetValueExW(key, PCWSTR(name.as_ptr()), 0, REG_SZ, Some(&data));
+            if status.is_err() {
+                result = Err(AppIdentityError::Api(
+                    "RegSetValueExW",
+                    format!("{:?}", status),
+                ));
+            }
+        }
+
+        let _ = RegCloseKey(key);
+        result
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn register_app_id_key() -> Result<(), AppIdentityError> {
+    Err(AppIdentityError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+fn create_start_menu_shortcut(shortcut: &std::path::Path) -> Result<(), AppIdentityError> {
+    use windows::core::{Interface, PCWSTR, PROPVARIANT};
+    use windows::Win32::Storage::EnhancedStorage::PKEY_AppUserModel_ID;
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, IPersistFile, CLSCTX_INPROC_SERVER,
+        COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+    use windows::Win32::UI::Shell::{IShellLinkW, ShellLink};
+
+    let exe_path =
+        std::env::current_exe().map_err(|e| AppIdentityError::Api("current_exe", e.to_string()))?;
+    let exe = to_wide(&exe_path.display().to_string());
+    let shortcut_path = to_wide(&shortcut.display().to_string());
+    let description = to_wide("AirPods battery monitor");
+
+    unsafe {
+        // S_FALSE (already initialized) is fine; a different threading model is reported by the
+        // calls below failing
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| AppIdentityError::Api("CoCreateInstance(ShellLink)", e.to_string()))?;
+        link.SetPath(PCWSTR(exe.as_ptr()))
+            .map_err(|e| AppIdentityError::Api("IShellLinkW::SetPath", e.to_string()))?;
+        link.SetIconLocation(PCWSTR(exe.as_ptr()), 0)
+            .map_err(|e| AppIdentityError::Api("IShellLinkW::SetIconLocation", e.to_string()))?;
+        link.SetDescription(PCWSTR(description.as_ptr()))
+            .map_err(|e| AppIdentityError::Api("IShellLinkW::SetDescription", e.to_string()))?;
+
+        let store: IPropertyStore = link
+            .cast()
+            .map_err(|e| AppIdentityError::Api("IPropertyStore", e.to_string()))?;
+        let app_id = PROPVARIANT::from(APP_USER_MODEL_ID);
+        store
+            .SetValue(&PKEY_AppUserModel_ID, &app_id)
+            .map_err(|e| AppIdentityError::Api("IPropertyStore::SetValue", e.to_string()))?;
+        store
+            .Commit()
+            .map_err(|e| AppIdentityError::Api("IPropertyStore::Commit", e.to_string()))?;
+
+        let file: IPersistFile = link
+            .cast()
+            .map_err(|e| AppIdentityError::Api("IPersistFile", e.to_string()))?;
+        file.Save(PCWSTR(shortcut_path.as_ptr()), true)
+            .map_err(|e| AppIdentityError::Api("IPersistFile::Save", e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn create_start_menu_shortcut(_shortcut: &std::path::Path) -> Result<(), AppIdentityError> {
+    Err(AppIdentityError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_menu_shortcut_path() {
+        if let Some(path) = start_menu_shortcut_path() {
+            assert!(path.ends_with("Start Menu/Programs/RustPods.lnk"));
+        }
+    }
+}
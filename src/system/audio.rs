@@ -0,0 +1,241 @@
+//! Audio endpoint integration
+//!
+//! Uses the Windows Core Audio API to find out whether the AirPods are the active playback
+//! device and, on request, to make them the default output.
+
+use thiserror::Error;
+
+/// Errors raised while querying or changing audio endpoints
+#[derive(Debug, Error)]
+pub enum AudioError {
+    /// Audio endpoint access is only implemented on Windows
+    #[error("Audio endpoint integration is not supported on this platform")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+
+    /// No playback endpoint belongs to the device
+    #[error("No audio output found for {0}")]
+    EndpointNotFound(String),
+}
+
+/// A playback endpoint known to the system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioEndpoint {
+    /// Endpoint ID, as used by the Core Audio API
+    pub id: String,
+    /// Friendly name, e.g. "Headphones (AirPods Pro)"
+    pub name: String,
+}
+
+/// Where audio is routed relative to a Bluetooth device
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioRouting {
+    /// Playback endpoint belonging to the device, if it exposes one
+    pub endpoint: Option<AudioEndpoint>,
+    /// Whether that endpoint is the current default playback device
+    pub is_default_output: bool,
+}
+
+/// Check whether an endpoint name refers to the given Bluetooth device
+///
+/// Windows names Bluetooth endpoints after the device ("Headphones (Jay's AirPods Pro)",
+/// "AirPods Pro Stereo"), so a case-insensitive substring match is enough. Generic "AirPods"
+/// names are matched as a fallback when the device name is not part of the endpoint name.
+pub fn endpoint_matches(endpoint_name: &str, device_name: &str) -> bool {
+    let endpoint_name = endpoint_name.to_lowercase();
+    let device_name = device_name.trim().to_lowercase();
+
+    (!device_name.is_empty() && endpoint_name.contains(&device_name))
+        || (device_name.contains("airpods") && endpoint_name.contains("airpods"))
+}
+
+/// Find the playback endpoint for a device and whether it is the default output
+pub fn audio_routing(device_name: &str) -> Result<AudioRouting, AudioError> {
+    let endpoint = output_devices()?
+        .into_iter()
+        .find(|endpoint| endpoint_matches(&endpoint.name, device_name));
+    let is_default_output = match (&endpoint, default_output_device()?) {
+        (Some(endpoint), Some(default)) => endpoint.id == default.id,
+        _ => false,
+    };
+
+    Ok(AudioRouting {
+        endpoint,
+        is_default_output,
+    })
+}
+
+/// Make the playback endpoint for a device the default output
+pub fn switch_output_to(device_name: &str) -> Result<AudioEndpoint, AudioError> {
+    let endpoint = output_devices()?
+        .into_iter()
+        .find(|endpoint| endpoint_matches(&endpoint.name, device_name))
+        .ok_or_else(|| AudioError::EndpointNotFound(device_name.to_string()))?;
+
+    set_default_output_device(&endpoint.id)?;
+    log::info!("Switched audio output to {}", endpoint.name);
+    Ok(endpoint)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{AudioEndpoint, AudioError};
+    use crate::system::to_wide;
+    use windows::core::{Interface, GUID, HRESULT, PCWSTR, PWSTR};
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        eConsole, eMultimedia, eRender, ERole, IMMDevice, IMMDeviceEnumerator, MMDeviceEnumerator,
+        DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_ALL, COINIT_MULTITHREADED,
+        STGM_READ,
+    };
+
+    /// Undocumented policy config client used by the Sound control panel to change defaults
+    const CLSID_POLICY_CONFIG_CLIENT: GUID =
+        GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+    /// IPolicyConfig (Windows 7 and later)
+    const IID_POLICY_CONFIG: GUID = GUID::from_u128(0xf8679f50_850a_41cf_9c72_430f290290c8);
+    /// Vtable slot of IPolicyConfig::SetDefaultEndpoint (3 IUnknown + 10 preceding methods)
+    const SET_DEFAULT_ENDPOINT_SLOT: usize = 13;
+
+    fn api_error(call: &'static str) -> impl FnOnce(windows::core::Error) -> AudioError {
+        move |e| AudioError::Api(call, e.to_string())
+    }
+
+    fn enumerator() -> Result<IMMDeviceEnumerator, AudioError> {
+        unsafe {
+            // Called from blocking worker threads; an existing apartment is fine
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)
+                .map_err(api_error("CoCreateInstance(MMDeviceEnumerator)"))
+        }
+    }
+
+    unsafe fn take_string(value: PWSTR) -> String {
+        let result = value.to_string().unwrap_or_default();
+        CoTaskMemFree(Some(value.0 as *const _));
+        result
+    }
+
+    unsafe fn describe(device: &IMMDevice) -> Result<AudioEndpoint, AudioError> {
+        let id = take_string(device.GetId().map_err(api_error("IMMDevice::GetId"))?);
+        let store = device
+            .OpenPropertyStore(STGM_READ)
+            .map_err(api_error("IMMDevice::OpenPropertyStore"))?;
+        let name = store
+            .GetValue(&PKEY_Device_FriendlyName)
+            .map_err(api_error("IPropertyStore::GetValue"))?
+            .to_string();
+        Ok(AudioEndpoint { id, name })
+    }
+
+    pub fn output_devices() -> Result<Vec<AudioEndpoint>, AudioError> {
+        unsafe {
+            let collection = enumerator()?
+                .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+                .map_err(api_error("IMMDeviceEnumerator::EnumAudioEndpoints"))?;
+            let count = collection
+                .GetCount()
+                .map_err(api_error("IMMDeviceCollection::GetCount"))?;
+
+            let mut endpoints = Vec::with_capacity(count as usize);
+            for index in 0..count {
+                let device = collection
+                    .Item(index)
+                    .map_err(api_error("IMMDeviceCollection::Item"))?;
+                endpoints.push(describe(&device)?);
+            }
+            Ok(endpoints)
+        }
+    }
+
+    pub fn default_output_device() -> Result<Option<AudioEndpoint>, AudioError> {
+        unsafe {
+            match enumerator()?.GetDefaultAudioEndpoint(eRender, eConsole) {
+                Ok(device) => describe(&device).map(Some),
+                // E_NOTFOUND: no playback device at all
+                Err(e) if e.code() == HRESULT(0x80070490u32 as i32) => Ok(None),
+                Err(e) => Err(AudioError::Api(
+                    "IMMDeviceEnumerator::GetDefaultAudioEndpoint",
+                    e.to_string(),
+                )),
+            }
+        }
+    }
+
+    pub fn set_default_output_device(id: &str) -> Result<(), AudioError> {
+        type SetDefaultEndpoint =
+            unsafe extern "system" fn(*mut core::ffi::c_void, PCWSTR, ERole) -> HRESULT;
+
+        let wide = to_wide(id);
+
+        unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let client: windows::core::IUnknown =
+                CoCreateInstance(&CLSID_POLICY_CONFIG_CLIENT, None, CLSCTX_ALL)
+                    .map_err(api_error("CoCreateInstance(PolicyConfigClient)"))?;
+
+            let mut raw = core::ptr::null_mut();
+            client
+                .query(&IID_POLICY_CONFIG, &mut raw)
+                .ok()
+                .map_err(api_error("QueryInterface(IPolicyConfig)"))?;
+            // Take ownership so the reference is released on every path
+            let policy = windows::core::IUnknown::from_raw(raw);
+
+            let vtable = *(raw as *const *const usize);
+            let set_default: SetDefaultEndpoint =
+                core::mem::transmute(*vtable.add(SET_DEFAULT_ENDPOINT_SLOT));
+
+            for role in [eConsole, eMultimedia] {
+                set_default(policy.as_raw(), PCWSTR(wide.as_ptr()), role)
+                    .ok()
+                    .map_err(api_error("IPolicyConfig::SetDefaultEndpoint"))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::{AudioEndpoint, AudioError};
+
+    pub fn output_devices() -> Result<Vec<AudioEndpoint>, AudioError> {
+        Err(AudioError::Unsupported)
+    }
+
+    pub fn default_output_device() -> Result<Option<AudioEndpoint>, AudioError> {
+        Err(AudioError::Unsupported)
+    }
+
+    pub fn set_default_output_device(_id: &str) -> Result<(), AudioError> {
+        Err(AudioError::Unsupported)
+    }
+}
+
+pub use platform::{default_output_device, output_devices, set_default_output_device};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_matches_device_name() {
+        assert!(endpoint_matches(
+            "Headphones (Jay's AirPods Pro)",
+            "Jay's AirPods Pro"
+        ));
+        assert!(endpoint_matches("AirPods Pro Stereo", "AirPods Pro"));
+        // Renamed buds still match a generic AirPods endpoint
+        assert!(endpoint_matches("Headphones (AirPods)", "AirPods Max"));
+        assert!(!endpoint_matches("Speakers (Realtek Audio)", "AirPods Pro"));
+        assert!(!endpoint_matches("Speakers (Realtek Audio)", "  "));
+    }
+}
@@ -0,0 +1,124 @@
+//! Mica and Acrylic window backdrops on Windows 11
+//!
+//! The backdrop is requested through `DWMWA_SYSTEMBACKDROP_TYPE` on RustPods' windows.
+//! Windows 10 and early Windows 11 builds reject the attribute, in which case the
+//! caller keeps the solid theme background.
+
+use thiserror::Error;
+
+use crate::config::WindowBackdrop;
+
+/// Errors raised while applying a window backdrop
+#[derive(Debug, Error)]
+pub enum BackdropError {
+    /// Backdrops are only available on Windows 11 (22H2 or later)
+    #[error("Window backdrops are not supported on this system")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// Apply a backdrop to this process's top-level windows
+///
+/// `dark` selects the dark variant of the material to match the active palette.
+/// Returns the number of windows updated; zero means no window exists yet.
+pub fn apply_window_backdrop(backdrop: WindowBackdrop, dark: bool) -> Result<usize, BackdropError> {
+    platform::apply_window_backdrop(backdrop, dark)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::BackdropError;
+    use crate::config::WindowBackdrop;
+    use windows::Win32::Foundation::{BOOL, HWND};
+    use windows::Win32::Graphics::Dwm::{
+        DwmExtendFrameIntoClientArea, DwmSetWindowAttribute, DWMSBT_MAINWINDOW, DWMSBT_NONE,
+        DWMSBT_TRANSIENTWINDOW, DWMWA_SYSTEMBACKDROP_TYPE, DWMWA_USE_IMMERSIVE_DARK_MODE,
+    };
+    use windows::Win32::UI::Controls::MARGINS;
+
+    pub fn apply_window_backdrop(
+        backdrop: WindowBackdrop,
+        dark: bool,
+    ) -> Result<usize, BackdropError> {
+        let windows = crate::system::process_windows::top_level_windows()
+            .map_err(|e| BackdropError::Api("EnumWindows", e.to_string()))?;
+
+        // Helper windows (e.g. the tray icon's) may reject attributes; only fail when no
+        // window accepted the backdrop
+        let mut updated = 0;
+        let mut last_error = None;
+        for hwnd in windows {
+            match apply_to_window(hwnd, backdrop, dark) {
+                Ok(()) => updated += 1,
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if updated == 0 => Err(e),
+            _ => Ok(updated),
+        }
+    }
+
+    fn apply_to_window(
+        hwnd: HWND,
+        backdrop: WindowBackdrop,
+        dark: bool,
+    ) -> Result<(), BackdropError> {
+        let backdrop_type = match backdrop {
+            WindowBackdrop::Solid => DWMSBT_NONE,
+            WindowBackdrop::Mica => DWMSBT_MAINWINDOW,
+            WindowBackdrop::Acrylic => DWMSBT_TRANSIENTWINDOW,
+        };
+        let dark_mode = BOOL::from(dark);
+        // Extending the frame over the whole client area lets the backdrop show through
+        let inset = if backdrop == WindowBackdrop::Solid {
+            0
+        } else {
+            -1
+        };
+        let margins = MARGINS {
+            cxLeftWidth: inset,
+            cxRightWidth: inset,
+            cyTopHeight: inset,
+            cyBottomHeight: inset,
+        };
+
+        unsafe {
+            // Windows 10 rejects the backdrop attribute; report it so callers fall back
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_SYSTEMBACKDROP_TYPE,
+                &backdrop_type as *const _ as *const core::ffi::c_void,
+                std::mem::size_of_val(&backdrop_type) as u32,
+            )
+            .map_err(|_| BackdropError::Unsupported)?;
+
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_USE_IMMERSIVE_DARK_MODE,
+                &dark_mode as *const _ as *const core::ffi::c_void,
+                std::mem::size_of_val(&dark_mode) as u32,
+            )
+            .map_err(|e| BackdropError::Api("DwmSetWindowAttribute", e.to_string()))?;
+
+            DwmExtendFrameIntoClientArea(hwnd, &margins)
+                .map_err(|e| BackdropError::Api("DwmExtendFrameIntoClientArea", e.to_string()))
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::BackdropError;
+    use crate::config::WindowBackdrop;
+
+    pub fn apply_window_backdrop(
+        _backdrop: WindowBackdrop,
+        _dark: bool,
+    ) -> Result<usize, BackdropError> {
+        Err(BackdropError::Unsupported)
+    }
+}
@@ -0,0 +1,71 @@
+//! Screen capture exclusion for RustPods windows
+//!
+//! The battery popup and overlay are drawn inside the application's own top-level
+//! windows, so applying `WDA_EXCLUDEFROMCAPTURE` to every window owned by this process
+//! keeps them out of screen recordings, shared screens and streams. Excluded windows
+//! still render normally on the local display. Requires Windows 10 version 2004 or later.
+
+use thiserror::Error;
+
+/// Errors raised while changing the capture affinity of windows
+#[derive(Debug, Error)]
+pub enum CaptureError {
+    /// Capture exclusion is only available on Windows
+    #[error("Screen capture exclusion is not supported on this platform")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// Exclude (or re-include) this process's top-level windows from screen capture
+///
+/// Returns the number of windows whose affinity was updated. Zero means no window
+/// has been created yet, so the caller should try again later.
+pub fn set_excluded_from_capture(excluded: bool) -> Result<usize, CaptureError> {
+    platform::set_excluded_from_capture(excluded)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::CaptureError;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+    };
+
+    pub fn set_excluded_from_capture(excluded: bool) -> Result<usize, CaptureError> {
+        let windows = crate::system::process_windows::top_level_windows()
+            .map_err(|e| CaptureError::Api("EnumWindows", e.to_string()))?;
+
+        let affinity = if excluded {
+            WDA_EXCLUDEFROMCAPTURE
+        } else {
+            WDA_NONE
+        };
+
+        let mut updated = 0;
+        for hwnd in windows {
+            // Helper windows (e.g. the tray icon's) may reject the affinity; that is harmless
+            match unsafe { SetWindowDisplayAffinity(hwnd, affinity) } {
+                Ok(()) => updated += 1,
+                Err(e) => crate::debug_log!(
+                    "ui",
+                    "SetWindowDisplayAffinity failed for {:?}: {}",
+                    hwnd,
+                    e
+                ),
+            }
+        }
+        Ok(updated)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::CaptureError;
+
+    pub fn set_excluded_from_capture(_excluded: bool) -> Result<usize, CaptureError> {
+        Err(CaptureError::Unsupported)
+    }
+}
@@ -0,0 +1,135 @@
+//! Windows Credential Manager storage for secrets
+//!
+//! Passwords and tokens are kept as generic credentials named `RustPods/<name>`, visible
+//! under "Windows Credentials" in Credential Manager. Windows encrypts them for the
+//! signed-in user and keeps them on this machine only.
+
+use thiserror::Error;
+
+/// Prefix of the credential names RustPods owns
+pub const CREDENTIAL_PREFIX: &str = "RustPods/";
+
+/// Errors raised while reading or writing credentials
+#[derive(Debug, Error)]
+pub enum CredentialError {
+    /// Credential Manager is only available on Windows
+    #[error("The credential store is not supported on this platform")]
+    Unsupported,
+
+    /// No credential is stored under the name
+    #[error("No credential named {0}")]
+    NotFound(String),
+
+    /// The stored value isn't text
+    #[error("Credential {0} is not valid UTF-8")]
+    InvalidValue(String),
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// Read the credential stored under `name`
+pub fn read_credential(name: &str) -> Result<String, CredentialError> {
+    platform::read(&target_name(name), name)
+}
+
+/// Store `value` under `name`, replacing any earlier value
+pub fn write_credential(name: &str, value: &str) -> Result<(), CredentialError> {
+    platform::write(&target_name(name), value)
+}
+
+/// Remove the credential stored under `name`; removing a missing one is not an error
+pub fn delete_credential(name: &str) -> Result<(), CredentialError> {
+    match platform::delete(&target_name(name), name) {
+        Err(CredentialError::NotFound(_)) => Ok(()),
+        result => result,
+    }
+}
+
+fn target_name(name: &str) -> String {
+    format!("{}{}", CREDENTIAL_PREFIX, name)
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::CredentialError;
+    use crate::system::to_wide;
+    use windows::core::{HSTRING, PWSTR};
+    use windows::Win32::Foundation::ERROR_NOT_FOUND;
+    use windows::Win32::Security::Credentials::{
+        CredDeleteW, CredFree, CredReadW, CredWriteW, CREDENTIALW, CRED_PERSIST_LOCAL_MACHINE,
+        CRED_TYPE_GENERIC,
+    };
+
+    fn api_error(api: &'static str, error: windows::core::Error, name: &str) -> CredentialError {
+        if error.code() == ERROR_NOT_FOUND.to_hresult() {
+            CredentialError::NotFound(name.to_string())
+        } else {
+            CredentialError::Api(api, error.to_string())
+        }
+    }
+
+    pub fn read(target: &str, name: &str) -> Result<String, CredentialError> {
+        let mut credential: *mut CREDENTIALW = std::ptr::null_mut();
+        unsafe {
+            CredReadW(
+                &HSTRING::from(target),
+                CRED_TYPE_GENERIC,
+                0,
+                &mut credential,
+            )
+        }
+        .map_err(|e| api_error("CredReadW", e, name))?;
+
+        // Copy the blob out before the credential is freed
+        let bytes = unsafe {
+            let credential = &*credential;
+            std::slice::from_raw_parts(
+                credential.CredentialBlob,
+                credential.CredentialBlobSize as usize,
+            )
+            .to_vec()
+        };
+        unsafe { CredFree(credential as *const _) };
+
+        String::from_utf8(bytes).map_err(|_| CredentialError::InvalidValue(name.to_string()))
+    }
+
+    pub fn write(target: &str, value: &str) -> Result<(), CredentialError> {
+        let mut target = to_wide(target);
+        let mut blob = value.as_bytes().to_vec();
+        let credential = CREDENTIALW {
+            Type: CRED_TYPE_GENERIC,
+            TargetName: PWSTR(target.as_mut_ptr()),
+            CredentialBlobSize: blob.len() as u32,
+            CredentialBlob: blob.as_mut_ptr(),
+            Persist: CRED_PERSIST_LOCAL_MACHINE,
+            ..Default::default()
+        };
+        unsafe { CredWriteW(&credential, 0) }
+            .map_err(|e| CredentialError::Api("CredWriteW", e.to_string()))
+    }
+
+    pub fn delete(target: &str, name: &str) -> Result<(), CredentialError> {
+        unsafe { CredDeleteW(&HSTRING::from(target), CRED_TYPE_GENERIC, 0) }
+            .map_err(|e| api_error("CredDeleteW", e, name))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::CredentialError;
+
+    pub fn read(_target: &str, _name: &str) -> Result<String, CredentialError> {
+        Err(CredentialError::Unsupported)
+    }
+
+    pub fn write(_target: &str, _value: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::Unsupported)
+    }
+
+    pub fn delete(_target: &str, _name: &str) -> Result<(), CredentialError> {
+        Err(CredentialError::Unsupported)
+    }
+}
@@ -0,0 +1,184 @@
+//! Free space on the drive RustPods keeps its data on
+//!
+//! When the drive fills up, every history append and profile save fails with an IO error
+//! and the log fills with the same warning. Writers ask [`writes_allowed`] first: while
+//! free space is below [`LOW_DISK_SPACE`] the battery history, profile, snapshots and
+//! audit log stay in memory and are written once space is freed. Writes the user asked
+//! for, like an import, can't wait: they fail with [`ensure_room`] instead. Free space is
+//! read at most every [`RECHECK_INTERVAL`], and running low and recovering are each
+//! logged once.
+
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+/// Free space below which history and journal writes are paused
+pub const LOW_DISK_SPACE: u64 = 50 * 1024 * 1024;
+
+/// How long a free space reading is trusted before the drive is asked again
+pub const RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Last reading, shared by every writer
+static MONITOR: Mutex<SpaceMonitor> = Mutex::new(SpaceMonitor::new());
+
+/// Errors raised while reading free disk space
+#[derive(Debug, Error)]
+pub enum DiskSpaceError {
+    /// Free space can only be read on Windows
+    #[error("Reading free disk space is not supported on this platform")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// Bytes available to this user on the drive holding `path`
+///
+/// `path` doesn't have to exist yet; its nearest existing folder is asked.
+pub fn free_space(path: &Path) -> Result<u64, DiskSpaceError> {
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path);
+    platform::free_space(existing)
+}
+
+/// Whether writes to `path` should go ahead
+///
+/// They do when free space can't be read, so an unsupported platform or API failure
+/// never stops saving.
+pub fn writes_allowed(path: &Path) -> bool {
+    let Ok(mut monitor) = MONITOR.lock() else {
+        return true;
+    };
+    let now = Instant::now();
+    if monitor.is_due(now) {
+        let free = free_space(path).ok();
+        monitor.record(free, now);
+    }
+    !monitor.low
+}
+
+/// Fail with [`io::ErrorKind::StorageFull`] where [`writes_allowed`] would pause writes
+pub fn ensure_room(path: &Path) -> io::Result<()> {
+    if writes_allowed(path) {
+        return Ok(());
+    }
+    Err(io::Error::new(
+        io::ErrorKind::StorageFull,
+        format!(
+            "only {} MB free on the data drive",
+            storage_pressure().unwrap_or_default() / (1024 * 1024)
+        ),
+    ))
+}
+
+/// Whether the last reading found the data drive low on space, and how much was free
+pub fn storage_pressure() -> Option<u64> {
+    MONITOR
+        .lock()
+        .ok()
+        .filter(|monitor| monitor.low)
+        .and_then(|monitor| monitor.free)
+}
+
+/// Free space readings and whether writes are paused
+#[derive(Debug)]
+struct SpaceMonitor {
+    checked: Option<Instant>,
+    free: Option<u64>,
+    low: bool,
+}
+
+impl SpaceMonitor {
+    const fn new() -> Self {
+        Self {
+            checked: None,
+            free: None,
+            low: false,
+        }
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        match self.checked {
+            Some(checked) => now.duration_since(checked) >= RECHECK_INTERVAL,
+            None => true,
+        }
+    }
+
+    /// Take a new reading, logging when writes pause or resume
+    fn record(&mut self, free: Option<u64>, now: Instant) {
+        self.checked = Some(now);
+        self.free = free;
+        let low = free.is_some_and(|free| free < LOW_DISK_SPACE);
+        if low && !self.low {
+            log::warn!(
+                "Only {} MB free on the data drive; keeping battery history in memory until space is freed",
+                free.unwrap_or_default() / (1024 * 1024)
+            );
+        } else if !low && self.low {
+            log::info!("Disk space freed; writing battery history again");
+        }
+        self.low = low;
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::DiskSpaceError;
+    use std::path::Path;
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    pub fn free_space(path: &Path) -> Result<u64, DiskSpaceError> {
+        let mut available = 0u64;
+        unsafe {
+            GetDiskFreeSpaceExW(
+                &HSTRING::from(path.as_os_str()),
+                Some(&mut available as *mut u64),
+                None,
+                None,
+            )
+        }
+        .map_err(|e| DiskSpaceError::Api("GetDiskFreeSpaceExW", e.to_string()))?;
+        Ok(available)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::DiskSpaceError;
+    use std::path::Path;
+
+    pub fn free_space(_path: &Path) -> Result<u64, DiskSpaceError> {
+        Err(DiskSpaceError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_pause_below_threshold_until_space_is_freed() {
+        let start = Instant::now();
+        let mut monitor = SpaceMonitor::new();
+        assert!(monitor.is_due(start));
+
+        monitor.record(Some(LOW_DISK_SPACE - 1), start);
+        assert!(monitor.low);
+        assert!(!monitor.is_due(start + Duration::from_secs(1)));
+        assert!(monitor.is_due(start + RECHECK_INTERVAL));
+
+        // An unreadable drive doesn't stop writes
+        monitor.record(None, start + RECHECK_INTERVAL);
+        assert!(!monitor.low);
+
+        monitor.record(Some(LOW_DISK_SPACE * 2), start + RECHECK_INTERVAL * 2);
+        assert!(!monitor.low);
+    }
+}
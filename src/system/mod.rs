@@ -0,0 +1,31 @@
+//! Integrations with operating system services
+
+pub mod app_identity;
+pub mod audio;
+pub mod backdrop;
+pub mod capture;
+pub mod credentials;
+pub mod disk_space;
+pub mod notifications;
+pub(crate) mod process_windows;
+pub mod proxy;
+pub mod sound;
+pub mod speech;
+pub mod taskbar;
+
+pub use app_identity::{register_app_identity, AppIdentityError, APP_USER_MODEL_ID};
+pub use audio::{AudioEndpoint, AudioError, AudioRouting};
+pub use backdrop::{apply_window_backdrop, BackdropError};
+pub use capture::{set_excluded_from_capture, CaptureError};
+pub use credentials::CredentialError;
+pub use disk_space::{ensure_room, storage_pressure, writes_allowed, DiskSpaceError};
+pub use notifications::{focus_assist_active, NotificationError, NotificationKind, Notifier};
+pub use proxy::Proxy;
+pub use sound::{play_sound, SoundError};
+pub use speech::{LowBatteryAnnouncer, SpeechError, SpeechSynthesizer};
+pub use taskbar::{set_taskbar_badge, TaskbarBadge, TaskbarError};
+
+/// Encode a string as a NUL-terminated UTF-16 buffer for Win32 calls
+pub fn to_wide(value: &str) -> Vec<u16> {
+    value.encode_utf16().chain(std::iter::once(0)).collect()
+}
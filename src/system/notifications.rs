@@ -0,0 +1,476 @@
+//! Desktop notifications
+//!
+//! Notifications are shown as WinRT toasts attributed to the registered app identity. Where
+//! toasts are unavailable (LTSC editions, group policy, a missing Start-menu shortcut) the
+//! notifier falls back to classic `Shell_NotifyIcon` balloon tips, so alerts are never
+//! silently dropped.
+//!
+//! While the user doesn't want to be disturbed (quiet hours or Focus Assist), anything
+//! short of a critical alert is held back and summed up in one notification afterwards.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// Notifications kept for the summary shown after a quiet period
+const MAX_HELD: usize = 20;
+
+/// How long a fallback balloon tip keeps its tray icon alive
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const BALLOON_LIFETIME: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Errors raised while showing notifications
+#[derive(Debug, Error)]
+pub enum NotificationError {
+    /// Notifications are only implemented on Windows
+    #[error("Notifications are not supported on this platform")]
+    Unsupported,
+
+    /// Toasts cannot be shown on this system
+    #[error("Toast notifications unavailable: {0}")]
+    ToastUnavailable(String),
+
+    /// The balloon tip fallback failed
+    #[error("Balloon tip failed: {0}")]
+    Balloon(String),
+}
+
+/// Severity of a notification, used for the balloon tip icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// General information
+    Info,
+    /// Something needs attention, e.g. low battery
+    Warning,
+    /// Shown even while quiet, e.g. a nearly empty battery
+    Critical,
+}
+
+/// Whether notifications are held back, and what has been held
+#[derive(Debug, Default)]
+struct QuietState {
+    /// Non-critical notifications are not shown
+    quiet: bool,
+    /// Keep what was not shown for a summary, rather than dropping it
+    hold: bool,
+    /// Notifications not shown, as "title: body"
+    held: Vec<String>,
+}
+
+/// Shows notifications, remembering when toasts have failed
+#[derive(Debug, Clone, Default)]
+pub struct Notifier {
+    /// Set once a toast fails so later notifications go straight to balloon tips
+    toasts_unavailable: Arc<AtomicBool>,
+    /// Quiet hours state shared by every clone
+    quiet: Arc<Mutex<QuietState>>,
+}
+
+impl Notifier {
+    /// Create a notifier that tries toasts first
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether notifications are currently routed to balloon tips
+    pub fn using_fallback(&self) -> bool {
+        self.toasts_unavailable.load(Ordering::Relaxed)
+    }
+
+    /// Whether non-critical notifications are being held back
+    pub fn is_quiet(&self) -> bool {
+        self.quiet.lock().map(|state| state.quiet).unwrap_or(false)
+    }
+
+    /// Start or end a quiet period
+    ///
+    /// While quiet, non-critical notifications are kept if `hold` is set and dropped
+    /// otherwise. When the period ends, anything kept is shown as one summary.
+    pub fn set_quiet(&self, quiet: bool, hold: bool) {
+        let held = {
+            let Ok(mut state) = self.quiet.lock() else {
+                return;
+            };
+            state.hold = hold;
+            if state.quiet == quiet {
+                return;
+            }
+            state.quiet = quiet;
+            log::info!(
+                "Notifications {}",
+                if quiet { "held back" } else { "resumed" }
+            );
+            std::mem::take(&mut state.held)
+        };
+
+        if let Some((title, body)) = held_summary(&held) {
+            self.notify(&title, &body, NotificationKind::Info);
+        }
+    }
+
+    /// Show a notification in the background
+    pub fn notify(&self, title: &str, body: &str, kind: NotificationKind) {
+        if kind != NotificationKind::Critical {
+            if let Ok(mut state) = self.quiet.lock() {
+                if state.quiet {
+                    crate::debug_log!("ui", "Quiet, not showing notification '{}'", title);
+                    if state.hold && state.held.len() < MAX_HELD {
+                        state.held.push(format!("{}: {}", title, body));
+                    }
+                    return;
+                }
+            }
+        }
+
+        let title = title.to_string();
+        let body = body.to_string();
+        let toasts_unavailable = Arc::clone(&self.toasts_unavailable);
+
+        std::thread::spawn(move || {
+            if !toasts_unavailable.load(Ordering::Relaxed) {
+                match toast_support().and_then(|()| show_toast(&title, &body)) {
+                    Ok(()) => return,
+                    Err(NotificationError::Unsupported) => return,
+                    Err(e) => {
+                        log::warn!("{} - falling back to balloon tips", e);
+                        toasts_unavailable.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+
+            if let Err(e) = show_balloon(&title, &body, kind) {
+                log::error!("Failed to show notification '{}': {}", title, e);
+            }
+        });
+    }
+}
+
+/// Title and text of the notification summing up those held while quiet
+pub fn held_summary(held: &[String]) -> Option<(String, String)> {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in held {
+        if !lines.contains(&line.as_str()) {
+            lines.push(line);
+        }
+    }
+    match held.len() {
+        0 => None,
+        1 => Some(("While you were away".to_string(), held[0].clone())),
+        count => Some((
+            format!("{} notifications while you were away", count),
+            lines.join("\n"),
+        )),
+    }
+}
+
+/// Whether Windows is asking apps not to disturb
+///
+/// True while Focus Assist is on, or while Windows reports presentation mode or a
+/// full-screen application.
+#[cfg(target_os = "windows")]
+pub fn focus_assist_active() -> bool {
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    let busy = matches!(
+        unsafe { SHQueryUserNotificationState() },
+        Ok(QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN | QUNS_PRESENTATION_MODE | QUNS_QUIET_TIME)
+    );
+    busy || focus_assist_profile().is_some_and(|profile| profile != 0)
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn focus_assist_active() -> bool {
+    false
+}
+
+/// Focus Assist profile: 0 off, 1 priority only, 2 alarms only
+///
+/// Windows has no public API for this; the shell publishes it as a WNF state, read here
+/// through `ntdll`. `None` if the state can't be read, e.g. on older Windows versions.
+#[cfg(target_os = "windows")]
+fn focus_assist_profile() -> Option<u32> {
+    use std::ffi::c_void;
+    use windows::core::{s, w};
+    use windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress};
+
+    /// WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED
+    const QUIET_HOURS_PROFILE: u64 = 0x0D83_063E_A3BF_1C75;
+
+    type QueryWnfStateData = unsafe extern "system" fn(
+        state_name: *const u64,
+        type_id: *const c_void,
+        scope: *const c_void,
+        change_stamp: *mut u32,
+        buffer: *mut c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+
+    unsafe {
+        let ntdll = GetModuleHandleW(w!("ntdll.dll")).ok()?;
+        let query: QueryWnfStateData =
+            std::mem::transmute(GetProcAddress(ntdll, s!("NtQueryWnfStateData"))?);
+
+        let mut change_stamp = 0u32;
+        let mut profile = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = query(
+            &QUIET_HOURS_PROFILE,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut c_void,
+            &mut size,
+        );
+        (status >= 0 && size as usize == std::mem::size_of::<u32>()).then_some(profile)
+    }
+}
+
+/// Escape text for inclusion in toast XML
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Build the toast XML payload
+pub fn toast_xml(title: &str, body: &str) -> String {
+    format!(
+        "<toast><visual><binding template=\"ToastGeneric\"><text>{}</text><text>{}</text></binding></visual></toast>",
+        escape_xml(title),
+        escape_xml(body)
+    )
+}
+
+/// Copy `text` into a fixed-size, null-terminated UTF-16 buffer, truncating if needed
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+fn copy_wide<const N: usize>(buffer: &mut [u16; N], text: &str) {
+    for (slot, unit) in buffer
+        .iter_mut()
+        .zip(text.encode_utf16().take(N - 1).chain(std::iter::once(0)))
+    {
+        *slot = unit;
+    }
+}
+
+/// Check that toasts can be delivered for the registered app identity
+#[cfg(target_os = "windows")]
+pub fn toast_support() -> Result<(), NotificationError> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::{NotificationSetting, ToastNotificationManager};
+
+    // Unpackaged apps need a shortcut carrying the AUMID for toasts to be delivered
+    if !super::app_identity::start_menu_shortcut_path().is_some_and(|path| path.exists()) {
+        return Err(NotificationError::ToastUnavailable(
+            "Start menu shortcut is missing".to_string(),
+        ));
+    }
+
+    let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(
+        super::app_identity::APP_USER_MODEL_ID,
+    ))
+    .map_err(|e| NotificationError::ToastUnavailable(e.to_string()))?;
+
+    match notifier.Setting() {
+        Ok(NotificationSetting::Enabled) => Ok(()),
+        Ok(setting) => Err(NotificationError::ToastUnavailable(format!(
+            "toasts disabled (setting {})",
+            setting.0
+        ))),
+        Err(e) => Err(NotificationError::ToastUnavailable(e.to_string())),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn toast_support() -> Result<(), NotificationError> {
+    Err(NotificationError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+fn show_toast(title: &str, body: &str) -> Result<(), NotificationError> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    let unavailable = |e: windows::core::Error| NotificationError::ToastUnavailable(e.to_string());
+
+    let document = XmlDocument::new().map_err(unavailable)?;
+    document
+        .LoadXml(&HSTRING::from(toast_xml(title, body)))
+        .map_err(unavailable)?;
+    let toast = ToastNotification::CreateToastNotification(&document).map_err(unavailable)?;
+
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(
+        super::app_identity::APP_USER_MODEL_ID,
+    ))
+    .and_then(|notifier| notifier.Show(&toast))
+    .map_err(unavailable)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn show_toast(_title: &str, _body: &str) -> Result<(), NotificationError> {
+    Err(NotificationError::Unsupported)
+}
+
+/// Show a balloon tip from a short-lived tray icon
+///
+/// The main tray icon is owned by `tray-icon`, which does not expose its window handle, so the
+/// balloon is attached to a temporary icon that is removed once the balloon has timed out.
+#[cfg(target_os = "windows")]
+fn show_balloon(title: &str, body: &str, kind: NotificationKind) -> Result<(), NotificationError> {
+    use crate::system::to_wide;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{HINSTANCE, HWND};
+    use windows::Win32::UI::Shell::{
+        Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_TIP, NIIF_ERROR, NIIF_INFO, NIIF_WARNING,
+        NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DestroyIcon, DestroyWindow, LoadIconW, LoadImageW, HICON, HMENU,
+        HWND_MESSAGE, IDI_APPLICATION, IMAGE_ICON, LR_LOADFROMFILE, WINDOW_EX_STYLE, WINDOW_STYLE,
+    };
+
+    unsafe {
+        let hwnd: HWND = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            w!("STATIC"),
+            w!("RustPods Notifications"),
+            WINDOW_STYLE::default(),
+            0,
+            0,
+            0,
+            0,
+            HWND_MESSAGE,
+            HMENU::default(),
+            HINSTANCE::default(),
+            None,
+        )
+        .map_err(|e| NotificationError::Balloon(format!("CreateWindowExW failed: {}", e)))?;
+
+        // Prefer the app icon next to the executable, falling back to the stock icon
+        let icon_path = std::env::current_exe().ok().and_then(|exe| {
+            let path = exe.parent()?.join("logo_ring.ico");
+            path.exists().then_some(path)
+        });
+        let (icon, owned_icon) = match icon_path.and_then(|path| {
+            let wide = to_wide(&path.display().to_string());
+            LoadImageW(
+                HINSTANCE::default(),
+                PCWSTR(wide.as_ptr()),
+                IMAGE_ICON,
+                16,
+                16,
+                LR_LOADFROMFILE,
+            )
+            .ok()
+        }) {
+            Some(handle) => (HICON(handle.0), true),
+            None => (
+                LoadIconW(HINSTANCE::default(), IDI_APPLICATION).unwrap_or_default(),
+                false,
+            ),
+        };
+
+        let mut data = NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: hwnd,
+            uID: 1,
+            uFlags: NIF_ICON | NIF_TIP | NIF_INFO,
+            hIcon: icon,
+            dwInfoFlags: match kind {
+                NotificationKind::Info => NIIF_INFO,
+                NotificationKind::Warning => NIIF_WARNING,
+                NotificationKind::Critical => NIIF_ERROR,
+            },
+            ..Default::default()
+        };
+        copy_wide(&mut data.szTip, super::app_identity::APP_DISPLAY_NAME);
+        copy_wide(&mut data.szInfoTitle, title);
+        copy_wide(&mut data.szInfo, body);
+
+        let shown = Shell_NotifyIconW(NIM_ADD, &data).as_bool();
+        if shown {
+            std::thread::sleep(BALLOON_LIFETIME);
+            let _ = Shell_NotifyIconW(NIM_DELETE, &data);
+        }
+
+        if owned_icon {
+            let _ = DestroyIcon(icon);
+        }
+        let _ = DestroyWindow(hwnd);
+
+        if shown {
+            Ok(())
+        } else {
+            Err(NotificationError::Balloon(
+                "Shell_NotifyIconW(NIM_ADD) failed".to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn show_balloon(
+    _title: &str,
+    _body: &str,
+    _kind: NotificationKind,
+) -> Result<(), NotificationError> {
+    Err(NotificationError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_xml_is_escaped() {
+        let xml = toast_xml("Low battery", "Left <AirPod> & \"case\"");
+        assert!(xml.contains("<text>Low battery</text>"));
+        assert!(xml.contains("Left &lt;AirPod&gt; &amp; &quot;case&quot;"));
+    }
+
+    #[test]
+    fn test_held_notifications_are_summarized() {
+        assert_eq!(held_summary(&[]), None);
+
+        let held = vec![
+            "Low battery: Case at 10%".to_string(),
+            "Low battery: Case at 10%".to_string(),
+            "Rule: Charging started".to_string(),
+        ];
+        let (title, body) = held_summary(&held).unwrap();
+        assert_eq!(title, "3 notifications while you were away");
+        assert_eq!(body, "Low battery: Case at 10%\nRule: Charging started");
+
+        // Critical notifications still go through while quiet
+        let notifier = Notifier::new();
+        notifier.set_quiet(true, true);
+        notifier.notify(
+            "Low battery",
+            "Left AirPod at 20%",
+            NotificationKind::Warning,
+        );
+        assert_eq!(notifier.quiet.lock().unwrap().held.len(), 1);
+        notifier.notify("Battery critical", "Case at 3%", NotificationKind::Critical);
+        assert_eq!(notifier.quiet.lock().unwrap().held.len(), 1);
+    }
+
+    #[test]
+    fn test_copy_wide_truncates() {
+        let mut buffer = [0xffffu16; 4];
+        copy_wide(&mut buffer, "RustPods");
+        assert_eq!(&buffer[..3], &"Rus".encode_utf16().collect::<Vec<_>>()[..]);
+        assert_eq!(buffer[3], 0);
+    }
+}
@@ -0,0 +1,39 @@
+//! Enumeration of the top-level windows owned by this process
+//!
+//! iced doesn't expose the native window handle, so window-level integrations (capture
+//! exclusion, backdrops) find RustPods' windows by process instead.
+
+#[cfg(target_os = "windows")]
+use windows::Win32::Foundation::HWND;
+
+/// Top-level windows owned by the current process, including hidden helper windows
+#[cfg(target_os = "windows")]
+pub(crate) fn top_level_windows() -> windows::core::Result<Vec<HWND>> {
+    use windows::Win32::Foundation::LPARAM;
+    use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+
+    let mut windows: Vec<HWND> = Vec::new();
+    unsafe {
+        EnumWindows(
+            Some(collect_process_window),
+            LPARAM(&mut windows as *mut Vec<HWND> as isize),
+        )
+    }?;
+    Ok(windows)
+}
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn collect_process_window(
+    hwnd: HWND,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::BOOL {
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    let mut process_id = 0u32;
+    GetWindowThreadProcessId(hwnd, Some(&mut process_id as *mut u32));
+    if process_id == std::process::id() {
+        let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+        windows.push(hwnd);
+    }
+    true.into()
+}
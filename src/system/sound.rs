@@ -0,0 +1,65 @@
+//! Alert sounds
+//!
+//! Sounds are played asynchronously through `PlaySound`, so a long WAV file never holds
+//! up the caller. Without a file the system "Exclamation" sound is used, which follows
+//! the user's sound scheme.
+
+use thiserror::Error;
+
+/// Errors raised while playing a sound
+#[derive(Debug, Error)]
+pub enum SoundError {
+    /// Sound playback is only implemented on Windows
+    #[error("Sound playback is not supported on this platform")]
+    Unsupported,
+
+    /// The sound could not be played
+    #[error("Failed to play sound: {0}")]
+    PlaybackFailed(String),
+}
+
+/// Play a WAV file, or the system alert sound when `path` is `None`
+#[cfg(target_os = "windows")]
+pub fn play_sound(path: Option<&str>) -> Result<(), SoundError> {
+    use crate::system::to_wide;
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::HMODULE;
+    use windows::Win32::Media::Audio::{
+        PlaySoundW, SND_ALIAS, SND_ASYNC, SND_FILENAME, SND_NODEFAULT,
+    };
+
+    let played = match path {
+        Some(path) => {
+            let wide = to_wide(path);
+            unsafe {
+                PlaySoundW(
+                    PCWSTR(wide.as_ptr()),
+                    HMODULE::default(),
+                    SND_FILENAME | SND_ASYNC | SND_NODEFAULT,
+                )
+            }
+        }
+        None => unsafe {
+            PlaySoundW(
+                w!("SystemExclamation"),
+                HMODULE::default(),
+                SND_ALIAS | SND_ASYNC,
+            )
+        },
+    };
+
+    if played.as_bool() {
+        Ok(())
+    } else {
+        Err(SoundError::PlaybackFailed(
+            path.unwrap_or("system alert sound").to_string(),
+        ))
+    }
+}
+
+/// Play a WAV file, or the system alert sound when `path` is `None`
+#[cfg(not(target_os = "windows"))]
+pub fn play_sound(path: Option<&str>) -> Result<(), SoundError> {
+    log::debug!("Sound not available, skipping {:?}", path);
+    Err(SoundError::Unsupported)
+}
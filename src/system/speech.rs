@@ -0,0 +1,208 @@
+//! Text-to-speech battery announcements
+//!
+//! Announcements are spoken through the Windows Speech API (`System.Speech`), driven
+//! from a hidden PowerShell process so the UI thread never blocks on synthesis.
+
+use thiserror::Error;
+
+use crate::airpods::ThresholdLatch;
+use crate::config::SpeechConfig;
+
+/// Errors raised while speaking an announcement
+#[derive(Debug, Error)]
+pub enum SpeechError {
+    /// Speech synthesis is not available on this platform
+    #[error("Speech synthesis is not supported on this platform")]
+    Unsupported,
+
+    /// The speech process could not be started
+    #[error("Failed to start speech synthesis: {0}")]
+    SpawnFailed(String),
+}
+
+/// Speaks announcements using the system speech synthesizer
+#[derive(Debug, Clone, Default)]
+pub struct SpeechSynthesizer;
+
+impl SpeechSynthesizer {
+    /// Create a new synthesizer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Speak `text` with the voice and rate from `config`
+    ///
+    /// Returns as soon as synthesis has started; speech continues in the background.
+    #[cfg(target_os = "windows")]
+    pub fn speak(&self, text: &str, config: &SpeechConfig) -> Result<(), SpeechError> {
+        use std::os::windows::process::CommandExt;
+
+        // Text and voice are passed through the environment so they are never parsed as script
+        let script = "Add-Type -AssemblyName System.Speech; \
+            $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+            if ($env:RUSTPODS_SPEECH_VOICE) { try { $s.SelectVoice($env:RUSTPODS_SPEECH_VOICE) } catch {} }; \
+            $s.Rate = [int]$env:RUSTPODS_SPEECH_RATE; \
+            $s.Speak($env:RUSTPODS_SPEECH_TEXT)";
+
+        std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("RUSTPODS_SPEECH_TEXT", text)
+            .env(
+                "RUSTPODS_SPEECH_VOICE",
+                config.voice.as_deref().unwrap_or_default(),
+            )
+            .env(
+                "RUSTPODS_SPEECH_RATE",
+                config.rate.clamp(-10, 10).to_string(),
+            )
+            .creation_flags(0x08000000) // CREATE_NO_WINDOW
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| SpeechError::SpawnFailed(e.to_string()))
+    }
+
+    /// Speak `text` with the voice and rate from `config`
+    #[cfg(not(target_os = "windows"))]
+    pub fn speak(&self, text: &str, _config: &SpeechConfig) -> Result<(), SpeechError> {
+        log::debug!("Speech not available, skipping announcement: {}", text);
+        Err(SpeechError::Unsupported)
+    }
+}
+
+/// Tracks which components have already been announced as low
+///
+/// Each component is announced once when it first drops to or below the threshold and
+/// re-armed once it climbs back above it (e.g. after charging).
+#[derive(Debug, Clone, Default)]
+pub struct LowBatteryAnnouncer {
+    announced: ThresholdLatch,
+}
+
+impl LowBatteryAnnouncer {
+    /// Create a new announcer with nothing announced yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check a component's level and return the announcement to speak, if any
+    pub fn check(
+        &mut self,
+        component: &'static str,
+        level: Option<u8>,
+        charging: bool,
+        threshold: u8,
+    ) -> Option<String> {
+        let level = level?;
+        self.announced
+            .check(component, Some(level), charging, threshold)
+            .then(|| format!("{} at {} percent", component, number_to_words(level)))
+    }
+}
+
+/// Build the on-demand battery status announcement
+pub fn battery_status_announcement(
+    left: Option<u8>,
+    right: Option<u8>,
+    case: Option<u8>,
+) -> Option<String> {
+    let parts: Vec<String> = [
+        ("Left AirPod", left),
+        ("Right AirPod", right),
+        ("Case", case),
+    ]
+    .iter()
+    .filter_map(|(name, level)| {
+        level.map(|level| format!("{} at {} percent", name, number_to_words(level)))
+    })
+    .collect();
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
+
+/// Spell out a percentage (0-100) so every voice reads it the same way
+pub fn number_to_words(value: u8) -> String {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    match value {
+        0..=19 => ONES[value as usize].to_string(),
+        20..=99 => {
+            let tens = TENS[(value / 10) as usize];
+            match value % 10 {
+                0 => tens.to_string(),
+                ones => format!("{}-{}", tens, ONES[ones as usize]),
+            }
+        }
+        _ => "one hundred".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_to_words() {
+        assert_eq!(number_to_words(0), "zero");
+        assert_eq!(number_to_words(15), "fifteen");
+        assert_eq!(number_to_words(40), "forty");
+        assert_eq!(number_to_words(73), "seventy-three");
+        assert_eq!(number_to_words(100), "one hundred");
+    }
+
+    #[test]
+    fn test_battery_status_announcement() {
+        assert_eq!(
+            battery_status_announcement(Some(15), None, Some(80)).as_deref(),
+            Some("Left AirPod at fifteen percent, Case at eighty percent")
+        );
+        assert_eq!(battery_status_announcement(None, None, None), None);
+    }
+
+    #[test]
+    fn test_low_battery_announced_once_until_recharged() {
+        let mut announcer = LowBatteryAnnouncer::new();
+
+        assert_eq!(announcer.check("Left AirPod", Some(30), false, 20), None);
+        assert_eq!(
+            announcer
+                .check("Left AirPod", Some(15), false, 20)
+                .as_deref(),
+            Some("Left AirPod at fifteen percent")
+        );
+        assert_eq!(announcer.check("Left AirPod", Some(10), false, 20), None);
+
+        // Charging re-arms the announcement
+        assert_eq!(announcer.check("Left AirPod", Some(10), true, 20), None);
+        assert!(announcer
+            .check("Left AirPod", Some(10), false, 20)
+            .is_some());
+    }
+}
@@ -0,0 +1,270 @@
+//! Battery level on the RustPods taskbar button
+//!
+//! The taskbar button shows the headline level (see `ui.tray_metric`) as its progress
+//! bar through `ITaskbarList3`. When that level is low the bar turns red
+//! and a red dot is laid over the icon, so a low battery is visible without opening the
+//! window or the tray. Only visible windows have a taskbar button; hidden ones are
+//! skipped, so the caller should apply the badge again once the window is shown.
+
+use thiserror::Error;
+
+use crate::ui::headline::Headline;
+
+/// Errors raised while updating the taskbar button
+#[derive(Debug, Error)]
+pub enum TaskbarError {
+    /// Taskbar progress is only available on Windows
+    #[error("Taskbar badges are not supported on this platform")]
+    Unsupported,
+
+    /// A Windows API call failed
+    #[error("{0} failed: {1}")]
+    Api(&'static str, String),
+}
+
+/// What the taskbar button shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskbarBadge {
+    /// No progress bar and no overlay
+    Clear,
+    /// The lowest level as progress; red, with an overlay, when `low`
+    Battery {
+        /// Lowest level, in percent
+        level: u8,
+        /// Whether a component is at or below its low battery threshold
+        low: bool,
+    },
+}
+
+impl TaskbarBadge {
+    /// Badge for the headline level, clear when there is none
+    pub fn from_headline(headline: Option<Headline>) -> Self {
+        match headline {
+            Some(headline) => TaskbarBadge::Battery {
+                level: headline.level,
+                low: headline.low,
+            },
+            None => TaskbarBadge::Clear,
+        }
+    }
+
+    /// Text read out by screen readers for the overlay
+    pub fn description(self) -> String {
+        match self {
+            TaskbarBadge::Clear => String::new(),
+            TaskbarBadge::Battery { level, low: true } => format!("Battery low, {}%", level),
+            TaskbarBadge::Battery { level, low: false } => format!("Battery {}%", level),
+        }
+    }
+}
+
+/// Show `badge` on the taskbar buttons of this process's windows
+///
+/// Returns the number of buttons updated. Zero means no window is visible yet, so the
+/// caller should try again later.
+pub fn set_taskbar_badge(badge: TaskbarBadge) -> Result<usize, TaskbarError> {
+    platform::set_taskbar_badge(badge)
+}
+
+/// Pixels of the low battery overlay: a red dot with a white rim, as BGRA rows
+fn overlay_pixels(size: usize) -> Vec<u8> {
+    let center = (size as f32 - 1.0) / 2.0;
+    let radius = size as f32 / 2.0;
+    let mut pixels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            // Alpha fades over the outermost pixel to smooth the edge
+            let alpha = (radius - distance).clamp(0.0, 1.0);
+            let (b, g, r) = if distance > radius - 2.0 {
+                (255.0, 255.0, 255.0)
+            } else {
+                (40.0, 40.0, 220.0)
+            };
+            // Icons take premultiplied alpha
+            pixels.extend_from_slice(&[
+                (b * alpha) as u8,
+                (g * alpha) as u8,
+                (r * alpha) as u8,
+                (255.0 * alpha) as u8,
+            ]);
+        }
+    }
+    pixels
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{overlay_pixels, TaskbarBadge, TaskbarError};
+    use windows::core::{HSTRING, PCWSTR};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::Graphics::Gdi::{CreateBitmap, DeleteObject};
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+    };
+    use windows::Win32::UI::Shell::{
+        ITaskbarList3, TaskbarList, TBPF_ERROR, TBPF_NOPROGRESS, TBPF_NORMAL,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateIconIndirect, DestroyIcon, IsWindowVisible, HICON, ICONINFO,
+    };
+
+    /// Side of the overlay icon; the taskbar draws overlays at 16x16 logical pixels
+    const OVERLAY_SIZE: usize = 16;
+
+    fn api_error(call: &'static str) -> impl FnOnce(windows::core::Error) -> TaskbarError {
+        move |e| TaskbarError::Api(call, e.to_string())
+    }
+
+    pub fn set_taskbar_badge(badge: TaskbarBadge) -> Result<usize, TaskbarError> {
+        let windows = crate::system::process_windows::top_level_windows()
+            .map_err(api_error("EnumWindows"))?;
+
+        let taskbar: ITaskbarList3 = unsafe {
+            // Called on the UI thread; an existing apartment is fine
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            CoCreateInstance(&TaskbarList, None, CLSCTX_INPROC_SERVER)
+        }
+        .map_err(api_error("CoCreateInstance(TaskbarList)"))?;
+        unsafe { taskbar.HrInit() }.map_err(api_error("HrInit"))?;
+
+        let overlay = match badge {
+            TaskbarBadge::Battery { low: true, .. } => Some(create_overlay_icon()?),
+            _ => None,
+        };
+
+        let mut updated = 0;
+        let mut last_error = None;
+        for hwnd in windows {
+            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+                continue;
+            }
+            match apply_to_window(&taskbar, hwnd, badge, overlay) {
+                Ok(()) => updated += 1,
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        if let Some(icon) = overlay {
+            // The taskbar keeps its own copy
+            let _ = unsafe { DestroyIcon(icon) };
+        }
+
+        match last_error {
+            Some(e) if updated == 0 => Err(e),
+            _ => Ok(updated),
+        }
+    }
+
+    fn apply_to_window(
+        taskbar: &ITaskbarList3,
+        hwnd: HWND,
+        badge: TaskbarBadge,
+        overlay: Option<HICON>,
+    ) -> Result<(), TaskbarError> {
+        unsafe {
+            match badge {
+                TaskbarBadge::Clear => {
+                    taskbar
+                        .SetProgressState(hwnd, TBPF_NOPROGRESS)
+                        .map_err(api_error("SetProgressState"))?;
+                }
+                TaskbarBadge::Battery { level, low } => {
+                    let state = if low { TBPF_ERROR } else { TBPF_NORMAL };
+                    taskbar
+                        .SetProgressState(hwnd, state)
+                        .map_err(api_error("SetProgressState"))?;
+                    taskbar
+                        .SetProgressValue(hwnd, u64::from(level), 100)
+                        .map_err(api_error("SetProgressValue"))?;
+                }
+            }
+            let description = HSTRING::from(badge.description());
+            taskbar
+                .SetOverlayIcon(
+                    hwnd,
+                    overlay.unwrap_or_default(),
+                    PCWSTR(description.as_ptr()),
+                )
+                .map_err(api_error("SetOverlayIcon"))?;
+        }
+        Ok(())
+    }
+
+    fn create_overlay_icon() -> Result<HICON, TaskbarError> {
+        let pixels = overlay_pixels(OVERLAY_SIZE);
+        // The mask is ignored for 32-bit icons but must exist; rows are word aligned
+        let mask = vec![0u8; OVERLAY_SIZE.div_ceil(16) * 2 * OVERLAY_SIZE];
+        unsafe {
+            let size = OVERLAY_SIZE as i32;
+            let color = CreateBitmap(size, size, 1, 32, Some(pixels.as_ptr().cast()));
+            let mask = CreateBitmap(size, size, 1, 1, Some(mask.as_ptr().cast()));
+            let icon = CreateIconIndirect(&ICONINFO {
+                fIcon: true.into(),
+                xHotspot: 0,
+                yHotspot: 0,
+                hbmMask: mask,
+                hbmColor: color,
+            });
+            let _ = DeleteObject(color);
+            let _ = DeleteObject(mask);
+            icon.map_err(api_error("CreateIconIndirect"))
+        }
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    use super::{TaskbarBadge, TaskbarError};
+
+    pub fn set_taskbar_badge(_badge: TaskbarBadge) -> Result<usize, TaskbarError> {
+        Err(TaskbarError::Unsupported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_shows_headline() {
+        use crate::config::TrayMetric;
+
+        assert_eq!(TaskbarBadge::from_headline(None), TaskbarBadge::Clear);
+        assert_eq!(
+            TaskbarBadge::from_headline(Some(Headline {
+                metric: TrayMetric::Lowest,
+                level: 15,
+                low: true,
+                charging: false,
+            })),
+            TaskbarBadge::Battery {
+                level: 15,
+                low: true
+            }
+        );
+        assert_eq!(
+            TaskbarBadge::from_headline(Some(Headline {
+                metric: TrayMetric::Average,
+                level: 60,
+                low: false,
+                charging: true,
+            })),
+            TaskbarBadge::Battery {
+                level: 60,
+                low: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_overlay_is_round() {
+        let pixels = overlay_pixels(16);
+        assert_eq!(pixels.len(), 16 * 16 * 4);
+        // Corners are transparent, the center is opaque red
+        assert_eq!(pixels[3], 0);
+        let center = (8 * 16 + 8) * 4;
+        assert_eq!(pixels[center + 3], 255);
+        assert!(pixels[center + 2] > pixels[center]);
+    }
+}
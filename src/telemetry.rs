@@ -3,8 +3,15 @@
 //! This module provides optional telemetry functionality for error reporting
 //! and anonymous usage analytics with strong privacy protections.
 //! Telemetry is always opt-in and can be disabled at any time.
+//!
+//! Metrics are aggregated locally and flushed to `telemetry/usage.json` in the
+//! RustPods data directory; see `docs/development/telemetry.md` for the format.
+//! Nothing is recorded or written while telemetry is disabled.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use uuid::Uuid;
@@ -58,8 +65,22 @@ pub struct TelemetryManager {
     diagnostics: DiagnosticsInfo,
 }
 
+impl std::fmt::Debug for TelemetryManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TelemetryManager")
+            .field("enabled", &self.enabled)
+            .field("installation_id", &self.installation_id)
+            .field("app_version", &self.app_version)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Version of the flushed telemetry format
+pub const TELEMETRY_FORMAT_VERSION: u32 = 1;
+
 /// Usage metrics collected for telemetry
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UsageMetrics {
     /// Number of application starts
     pub app_starts: u32,
@@ -76,6 +97,12 @@ pub struct UsageMetrics {
     /// Number of successful battery readings
     pub battery_readings: u32,
 
+    /// Number of device scans
+    pub scans: u32,
+
+    /// Number of scans that failed
+    pub failed_scans: u32,
+
     /// Features used (with count)
     pub features_used: HashMap<String, u32>,
 }
@@ -96,7 +123,7 @@ impl TelemetryManager {
         // Generate installation ID if it doesn't exist
         let installation_id = Self::get_or_create_installation_id();
 
-        Self {
+        let mut manager = Self {
             enabled: config.system.enable_telemetry,
             installation_id,
             error_stats: ErrorStats::default(),
@@ -106,7 +133,9 @@ impl TelemetryManager {
             error_handler: None,
             error_queue: Vec::new(),
             diagnostics: DiagnosticsInfo::default(),
-        }
+        };
+        manager.load_local_data();
+        manager
     }
 
     /// Get or create an installation ID
@@ -141,6 +170,73 @@ impl TelemetryManager {
         self.enabled = enabled;
     }
 
+    /// Whether telemetry is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Location of the locally aggregated telemetry
+    pub fn local_data_path() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("RustPods")
+            .join("telemetry")
+            .join("usage.json")
+    }
+
+    /// Continue aggregating from the last flushed report, if any
+    fn load_local_data(&mut self) {
+        let Ok(contents) = std::fs::read_to_string(Self::local_data_path()) else {
+            return;
+        };
+        match serde_json::from_str::<TelemetryReport>(&contents) {
+            Ok(report) => {
+                if let Ok(mut metrics) = self.usage_metrics.lock() {
+                    *metrics = report.usage;
+                }
+                report.errors.apply_to(&mut self.error_stats);
+            }
+            Err(e) => log::warn!("Ignoring unreadable telemetry data: {}", e),
+        }
+    }
+
+    /// Build a report of everything collected so far
+    pub fn report(&self) -> TelemetryReport {
+        self.create_telemetry_payload()
+    }
+
+    /// Write the aggregated data to the local telemetry file
+    ///
+    /// Returns the path written, or `None` when telemetry is disabled.
+    pub fn flush(&self) -> io::Result<Option<PathBuf>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let path = Self::local_data_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.report())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        std::fs::write(&path, json)?;
+        Ok(Some(path))
+    }
+
+    /// Discard all collected data, in memory and on disk
+    pub fn clear(&mut self) -> io::Result<()> {
+        self.error_stats = ErrorStats::default();
+        self.error_queue.clear();
+        if let Ok(mut metrics) = self.usage_metrics.lock() {
+            *metrics = UsageMetrics::default();
+        }
+
+        match std::fs::remove_file(Self::local_data_path()) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
     /// Record an application event for telemetry
     pub fn record_event(&self, event_type: TelemetryEvent) {
         if !self.enabled {
@@ -163,6 +259,12 @@ impl TelemetryManager {
                 TelemetryEvent::BatteryReading => {
                     metrics.battery_readings += 1;
                 }
+                TelemetryEvent::Scan => {
+                    metrics.scans += 1;
+                }
+                TelemetryEvent::ScanFailed => {
+                    metrics.failed_scans += 1;
+                }
                 TelemetryEvent::FeatureUsed(feature) => {
                     *metrics.features_used.entry(feature).or_insert(0) += 1;
                 }
@@ -205,6 +307,7 @@ impl TelemetryManager {
             RustPodsError::Timeout(_) => self.error_stats.bluetooth_errors += 1,
             RustPodsError::Context { .. } => self.error_stats.app_errors += 1,
             RustPodsError::InvalidData(_) => self.error_stats.app_errors += 1,
+            RustPodsError::Quarantined(_) => self.error_stats.app_errors += 1,
         }
 
         // Add to error queue for diagnostic monitoring
@@ -292,21 +395,29 @@ impl TelemetryManager {
     }
 
     /// Create telemetry payload
-    fn create_telemetry_payload(&self) -> TelemetryPayload {
-        let usage_metrics = if let Ok(metrics) = self.usage_metrics.lock() {
+    fn create_telemetry_payload(&self) -> TelemetryReport {
+        let usage = if let Ok(metrics) = self.usage_metrics.lock() {
             metrics.clone()
         } else {
             UsageMetrics::default()
         };
+        let errors = ErrorCounts::from(&self.error_stats);
+        let scan_error_rate = if usage.scans > 0 {
+            (usage.failed_scans as f64 / usage.scans as f64).min(1.0)
+        } else {
+            0.0
+        };
 
-        TelemetryPayload {
+        TelemetryReport {
+            format_version: TELEMETRY_FORMAT_VERSION,
             installation_id: self.installation_id.clone(),
             app_version: self.app_version.clone(),
             os_name: std::env::consts::OS.to_string(),
             os_arch: std::env::consts::ARCH.to_string(),
-            error_stats: self.error_stats.clone(),
-            usage_metrics,
-            timestamp: chrono::Utc::now().to_rfc3339(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            usage,
+            errors,
+            scan_error_rate,
         }
     }
 
@@ -382,34 +493,99 @@ pub enum TelemetryEvent {
     /// Battery reading obtained
     BatteryReading,
 
+    /// Device scan completed (successfully or not)
+    Scan,
+
+    /// Device scan failed
+    ScanFailed,
+
     /// Feature used
     FeatureUsed(String),
 }
 
-/// Telemetry payload for upload
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-struct TelemetryPayload {
+/// Aggregated telemetry, as flushed to disk and shown in the UI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryReport {
+    /// Format version (see `TELEMETRY_FORMAT_VERSION`)
+    pub format_version: u32,
+
     /// Anonymous installation ID
-    installation_id: String,
+    pub installation_id: String,
 
     /// Application version
-    app_version: String,
+    pub app_version: String,
 
     /// Operating system
-    os_name: String,
+    pub os_name: String,
 
     /// Architecture
-    os_arch: String,
+    pub os_arch: String,
 
-    /// Error statistics
-    error_stats: ErrorStats,
+    /// When the report was generated (RFC 3339)
+    pub generated_at: String,
 
     /// Usage metrics
-    usage_metrics: UsageMetrics,
+    #[serde(default)]
+    pub usage: UsageMetrics,
 
-    /// Timestamp
-    timestamp: String,
+    /// Error counts by category
+    #[serde(default)]
+    pub errors: ErrorCounts,
+
+    /// Fraction of scans that failed (0.0-1.0)
+    #[serde(default)]
+    pub scan_error_rate: f64,
+}
+
+/// Error counts by category, without any error messages
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorCounts {
+    /// Bluetooth and device errors
+    pub bluetooth: usize,
+    /// AirPods protocol errors
+    pub airpods: usize,
+    /// User interface errors
+    pub ui: usize,
+    /// Configuration errors
+    pub config: usize,
+    /// Application errors
+    pub app: usize,
+    /// Battery monitoring errors
+    pub battery: usize,
+    /// System errors
+    pub system: usize,
+    /// Critical errors
+    pub critical: usize,
+}
+
+impl ErrorCounts {
+    /// Restore the counts into error statistics
+    fn apply_to(&self, stats: &mut ErrorStats) {
+        stats.bluetooth_errors = self.bluetooth;
+        stats.airpods_errors = self.airpods;
+        stats.ui_errors = self.ui;
+        stats.config_errors = self.config;
+        stats.app_errors = self.app;
+        stats.battery_errors = self.battery;
+        stats.system_errors = self.system;
+        stats.critical_errors = self.critical;
+    }
+}
+
+impl From<&ErrorStats> for ErrorCounts {
+    fn from(stats: &ErrorStats) -> Self {
+        Self {
+            bluetooth: stats.bluetooth_errors,
+            airpods: stats.airpods_errors,
+            ui: stats.ui_errors,
+            config: stats.config_errors,
+            app: stats.app_errors,
+            battery: stats.battery_errors,
+            system: stats.system_errors,
+            critical: stats.critical_errors,
+        }
+    }
 }
 
 /// Initialize telemetry
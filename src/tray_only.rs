@@ -0,0 +1,413 @@
+//! Tray-only mode (`--tray-only`)
+//!
+//! For anyone who only wants the alerts: RustPods runs without its main window, so no
+//! window or renderer is created and the GPU context and most of the UI's memory are
+//! never allocated. Scans run on a background thread at the configured interval. The
+//! tray icon, its tooltip and the first menu item show the levels, low batteries raise
+//! the usual notifications, and clicking the icon shows the levels as a notification.
+//! `ui.tray_only` starts RustPods this way every time, `--tray-only` for one run. "Open
+//! Window" in the tray menu turns the setting off and restarts with the window.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::airpods::battery::AirPodsBatteryInfo;
+use crate::airpods::ThresholdLatch;
+use crate::bluetooth::ScannerSupervisor;
+use crate::config::{AppConfig, BatteryComponent, ChangeSource, KnownDevices, UiConfig};
+use crate::system::{NotificationKind, Notifier};
+use crate::ui::headline::{Headline, Reading};
+use crate::ui::state::ConnectionStatus;
+use crate::ui::system_tray::SystemTrayError;
+use crate::ui::{Message, SystemTrayController};
+
+/// Command line flag that runs RustPods in the tray only
+pub const TRAY_ONLY_FLAG: &str = "--tray-only";
+
+/// How often the loop looks for finished scans and tray clicks
+const TICK: Duration = Duration::from_millis(250);
+
+/// Battery levels of one AirPods set from a scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Levels {
+    pub left: Option<u8>,
+    pub right: Option<u8>,
+    pub case: Option<u8>,
+    /// Whether the left bud, right bud and case are charging
+    pub charging: (bool, bool, bool),
+}
+
+impl Levels {
+    /// Levels reported by a scan; the scanner reports unknown levels as -1
+    pub fn from_scan(airpods: &AirPodsBatteryInfo) -> Self {
+        let level = |level: i32| u8::try_from(level).ok().filter(|level| *level <= 100);
+        Self {
+            left: level(airpods.left_battery),
+            right: level(airpods.right_battery),
+            case: level(airpods.case_battery),
+            charging: (
+                airpods.left_charging,
+                airpods.right_charging,
+                airpods.case_charging,
+            ),
+        }
+    }
+
+    /// Batteries as (label, level, charging) for the tray tooltip
+    pub fn batteries(&self) -> [(&'static str, Option<u8>, bool); 3] {
+        let (left_charging, right_charging, case_charging) = self.charging;
+        [
+            ("L", self.left, left_charging),
+            ("R", self.right, right_charging),
+            ("Case", self.case, case_charging),
+        ]
+    }
+
+    /// The level the tray leads with, by `ui.tray_metric`
+    pub fn headline(&self, ui: &UiConfig) -> Option<Headline> {
+        let readings: Vec<Reading> = [
+            (BatteryComponent::Left, self.left, self.charging.0),
+            (BatteryComponent::Right, self.right, self.charging.1),
+            (BatteryComponent::Case, self.case, self.charging.2),
+        ]
+        .into_iter()
+        .map(|(component, level, charging)| Reading {
+            component,
+            level,
+            threshold: ui.low_battery_threshold_for(component),
+            charging,
+        })
+        .collect();
+        Headline::pick(ui.tray_metric, &readings)
+    }
+
+    /// Levels for a notification, e.g. "Left 80%, Right 75% (charging), Case 60%"
+    pub fn summary(&self) -> String {
+        let (left_charging, right_charging, case_charging) = self.charging;
+        let parts: Vec<String> = [
+            ("Left", self.left, left_charging),
+            ("Right", self.right, right_charging),
+            ("Case", self.case, case_charging),
+        ]
+        .into_iter()
+        .filter_map(|(label, level, charging)| {
+            let level = level?;
+            Some(if charging {
+                format!("{} {}% (charging)", label, level)
+            } else {
+                format!("{} {}%", label, level)
+            })
+        })
+        .collect();
+        if parts.is_empty() {
+            "No battery levels reported yet".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+/// What tray-only mode knows between scans
+#[derive(Debug, Default)]
+pub struct TrayOnly {
+    config: AppConfig,
+    known_devices: KnownDevices,
+    low_battery: ThresholdLatch,
+    /// Address, name and levels of the AirPods shown, from the latest scan
+    device: Option<(String, String, Levels)>,
+}
+
+impl TrayOnly {
+    /// Start with nothing found yet
+    pub fn new(config: AppConfig, known_devices: KnownDevices) -> Self {
+        Self {
+            config,
+            known_devices,
+            ..Self::default()
+        }
+    }
+
+    /// Take in a finished scan
+    ///
+    /// The AirPods shown stay the same while they are still heard, otherwise the first
+    /// set in the scan is shown.
+    pub fn record_scan(&mut self, devices: &[AirPodsBatteryInfo]) {
+        let shown = self.device.as_ref().and_then(|(address, _, _)| {
+            devices
+                .iter()
+                .find(|airpods| &airpods.canonical_address == address)
+        });
+        self.device = shown.or_else(|| devices.first()).map(|airpods| {
+            let name = self
+                .known_devices
+                .find_address(&airpods.canonical_address)
+                .map(|known| known.display_name().to_string())
+                .unwrap_or_else(|| airpods.name.clone());
+            (
+                airpods.canonical_address.clone(),
+                name,
+                Levels::from_scan(airpods),
+            )
+        });
+    }
+
+    /// Whether AirPods were heard in the latest scan
+    pub fn connection(&self) -> ConnectionStatus {
+        if self.device.is_some() {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::NotFound
+        }
+    }
+
+    /// Name and levels of the AirPods shown
+    pub fn device(&self) -> Option<(&str, &Levels)> {
+        self.device
+            .as_ref()
+            .map(|(_, name, levels)| (name.as_str(), levels))
+    }
+
+    /// Notification body and kind for components that just dropped to their low battery
+    /// threshold, as in the window
+    pub fn low_battery_alert(&mut self) -> Option<(String, NotificationKind)> {
+        let (_, _, levels) = self.device.as_ref()?;
+        let ui = &self.config.ui;
+        let (left_charging, right_charging, case_charging) = levels.charging;
+
+        // Each component latches on its own, at its own threshold
+        let low: Vec<(String, u8)> = [
+            (
+                "Left AirPod",
+                BatteryComponent::Left,
+                levels.left,
+                left_charging,
+            ),
+            (
+                "Right AirPod",
+                BatteryComponent::Right,
+                levels.right,
+                right_charging,
+            ),
+            ("Case", BatteryComponent::Case, levels.case, case_charging),
+        ]
+        .into_iter()
+        .filter_map(|(component, part, level, charging)| {
+            let threshold = ui.low_battery_threshold_for(part);
+            let level = level?;
+            self.low_battery
+                .check(component, Some(level), charging, threshold)
+                .then(|| (format!("{} at {}%", component, level), level))
+        })
+        .collect();
+
+        if low.is_empty() || !ui.show_notifications || !ui.show_low_battery_warning {
+            return None;
+        }
+
+        // Nearly empty batteries are worth interrupting quiet hours for
+        let kind = if low
+            .iter()
+            .any(|(_, level)| *level <= self.config.battery.critical_threshold)
+        {
+            NotificationKind::Critical
+        } else {
+            NotificationKind::Warning
+        };
+        let text: Vec<String> = low.into_iter().map(|(text, _)| text).collect();
+        Some((text.join(", "), kind))
+    }
+
+    /// Whether automatic alerts should stay quiet, as in the window
+    fn is_quiet(&self) -> bool {
+        let quiet_hours = &self.config.ui.quiet_hours;
+        quiet_hours.is_active_now()
+            || (quiet_hours.follow_focus_assist && crate::system::focus_assist_active())
+    }
+}
+
+/// Arguments to restart with the window: `args` without the flag or a `--set` that
+/// asks for tray-only mode
+pub fn window_args<I>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut kept = Vec::new();
+    let mut args = args.into_iter().peekable();
+    while let Some(arg) = args.next() {
+        if arg == TRAY_ONLY_FLAG {
+            continue;
+        }
+        let sets_tray_only = args
+            .peek()
+            .is_some_and(|value| value.to_string_lossy().starts_with("ui.tray_only="));
+        if arg == "--set" && sets_tray_only {
+            args.next();
+            continue;
+        }
+        kept.push(arg);
+    }
+    kept
+}
+
+/// Turn `ui.tray_only` off in the settings at `settings_path` and restart with the window
+fn open_window(settings_path: &Path) -> Result<(), String> {
+    // The settings as saved, without this run's `--set` overrides
+    let mut config = AppConfig::load_from_path(settings_path).map_err(|e| e.to_string())?;
+    config.settings_path = settings_path.to_path_buf();
+    config.ui.tray_only = false;
+    config
+        .save_audited(ChangeSource::Ui)
+        .map_err(|e| e.to_string())?;
+    // As with "Restart RustPods", a safe mode run restarts normally
+    crate::safe_mode::restart_with(window_args(std::env::args_os().skip(1)), false)
+        .map_err(|e| e.to_string())
+}
+
+/// Run in the tray until "Exit", "Open Window" or "Restart RustPods" is chosen
+pub fn run(mut config: AppConfig) -> Result<(), SystemTrayError> {
+    crate::system::register_app_identity();
+    log::info!("Running in the tray only, without the main window");
+
+    // The tray labels its first menu item with the levels instead of Show/Hide
+    config.ui.tray_only = true;
+    let (sender, mut messages) = tokio::sync::mpsc::unbounded_channel();
+    let tray = SystemTrayController::start(config.clone(), sender)?;
+    let notifier = Notifier::new();
+    let known_devices = KnownDevices::load_from(&KnownDevices::path_for(&config));
+    let interval = config.bluetooth.scan_interval;
+    let mut state = TrayOnly::new(config, known_devices);
+
+    let supervisor = ScannerSupervisor::new();
+    let (results, scans) = mpsc::channel();
+    let mut scanning = false;
+    let mut next_scan = Instant::now();
+
+    loop {
+        if !scanning && Instant::now() >= next_scan {
+            scanning = true;
+            let supervisor = supervisor.clone();
+            let results = results.clone();
+            std::thread::spawn(move || {
+                let devices = crate::ui::state::get_airpods_from_cli_scanner(&supervisor);
+                let _ = results.send(devices);
+            });
+        }
+        if let Ok(devices) = scans.try_recv() {
+            scanning = false;
+            next_scan = Instant::now() + interval;
+            state.record_scan(&devices);
+
+            tray.update_icon(state.connection());
+            match state.device() {
+                Some((name, levels)) => tray.update_tooltip_with_battery(
+                    Some(name),
+                    levels.headline(&state.config.ui),
+                    &levels.batteries(),
+                ),
+                None => tray.update_tooltip_with_battery(None, None, &[]),
+            }
+
+            notifier.set_quiet(state.is_quiet(), state.config.ui.quiet_hours.summarize_held);
+            if let Some((body, kind)) = state.low_battery_alert() {
+                notifier.notify("Low battery", &body, kind);
+            }
+        }
+
+        while let Ok(message) = messages.try_recv() {
+            match message {
+                // There is no window to show, so the levels come as a notification
+                Message::ToggleWindow | Message::ShowWindow => {
+                    let (title, body) = match state.device() {
+                        Some((name, levels)) => (name.to_string(), levels.summary()),
+                        None => ("RustPods".to_string(), "No AirPods found".to_string()),
+                    };
+                    notifier.notify(&title, &body, NotificationKind::Info);
+                }
+                Message::Restart(safe_mode) => match crate::safe_mode::restart(safe_mode) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => log::error!("Failed to restart RustPods: {}", e),
+                },
+                Message::LeaveTrayOnly => match open_window(&state.config.settings_path) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => {
+                        log::error!("Failed to leave tray-only mode: {}", e);
+                        notifier.notify(
+                            "RustPods",
+                            &format!("Couldn't open the window: {}", e),
+                            NotificationKind::Warning,
+                        );
+                    }
+                },
+                Message::Exit => return Ok(()),
+                _ => {}
+            }
+        }
+
+        std::thread::sleep(TICK);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn airpods(left: i32, left_charging: bool) -> AirPodsBatteryInfo {
+        AirPodsBatteryInfo {
+            left_battery: left,
+            left_charging,
+            right_battery: 70,
+            case_battery: -1,
+            rssi: Some(-60),
+            ..crate::airpods::test_support::report("aabbccddeeff")
+        }
+    }
+
+    #[test]
+    fn test_tray_only_levels_and_low_battery() {
+        let mut config = AppConfig::default();
+        config.ui.show_notifications = true;
+        config.ui.show_low_battery_warning = true;
+        config.ui.low_battery_threshold = 20;
+        let mut state = TrayOnly::new(config, KnownDevices::default());
+        assert_eq!(state.connection(), ConnectionStatus::NotFound);
+
+        state.record_scan(&[airpods(15, false)]);
+        let (name, levels) = state.device().unwrap();
+        assert_eq!(name, "AirPods Pro");
+        assert_eq!(levels.case, None);
+        assert_eq!(levels.summary(), "Left 15%, Right 70%");
+        assert_eq!(state.connection(), ConnectionStatus::Connected);
+
+        let (body, _) = state.low_battery_alert().unwrap();
+        assert_eq!(body, "Left AirPod at 15%");
+        // Announced once until it charges back above the threshold
+        state.record_scan(&[airpods(14, false)]);
+        assert!(state.low_battery_alert().is_none());
+        state.record_scan(&[airpods(30, true)]);
+        assert!(state.low_battery_alert().is_none());
+        state.record_scan(&[airpods(15, false)]);
+        assert!(state.low_battery_alert().is_some());
+
+        state.record_scan(&[]);
+        assert!(state.device().is_none());
+        assert_eq!(state.connection(), ConnectionStatus::NotFound);
+    }
+
+    #[test]
+    fn test_window_args_drop_tray_only() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(
+            window_args(args(&[
+                "--verbose",
+                TRAY_ONLY_FLAG,
+                "--set",
+                "ui.tray_only=true",
+                "--set",
+                "ui.theme=dark",
+            ])),
+            args(&["--verbose", "--set", "ui.theme=dark"])
+        );
+    }
+}
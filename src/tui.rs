@@ -0,0 +1,389 @@
+//! Terminal dashboard (`rustpods tui`)
+//!
+//! A full-screen view of the same scans the GUI runs, for headless machines and for
+//! anyone who lives in a terminal: battery levels of every AirPods set nearby, a
+//! sparkline of the strongest signal, what changed between scans and how the scanner
+//! is doing. Scans run on a background thread at the configured interval, so the
+//! screen keeps responding to keys while the scanner works.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, Paragraph, Sparkline};
+use ratatui::Frame;
+
+use crate::airpods::battery::AirPodsBatteryInfo;
+use crate::bluetooth::{ScanStatistics, ScannerHealth, ScannerSupervisor};
+
+/// Events kept in the events pane
+const MAX_EVENTS: usize = 50;
+
+/// Signal samples kept for the sparkline
+const MAX_RSSI_SAMPLES: usize = 120;
+
+/// Weakest signal drawn in the sparkline; anything weaker draws as empty
+const RSSI_FLOOR: i32 = -100;
+
+/// How often the screen is redrawn while waiting for keys and scans
+const TICK: Duration = Duration::from_millis(250);
+
+/// State shown on the dashboard
+#[derive(Debug, Clone, Default)]
+pub struct Dashboard {
+    /// AirPods in the latest scan
+    devices: Vec<AirPodsBatteryInfo>,
+    /// Strongest signal of each scan above [`RSSI_FLOOR`], oldest first
+    rssi: VecDeque<u64>,
+    /// What changed between scans, newest first
+    events: VecDeque<String>,
+    /// Whether scheduled scans are paused
+    paused: bool,
+    /// Whether a scan is running
+    scanning: bool,
+    /// When the latest scan finished
+    last_scan: Option<DateTime<Local>>,
+    /// Scanner health after the latest scan
+    health: ScannerHealth,
+    /// Scanner totals after the latest scan
+    statistics: ScanStatistics,
+}
+
+impl Dashboard {
+    /// Record a finished scan, logging what changed since the previous one
+    pub fn record_scan(&mut self, devices: Vec<AirPodsBatteryInfo>, at: DateTime<Local>) {
+        let time = at.format("%H:%M:%S");
+        let mut events = Vec::new();
+        for device in &devices {
+            let previous = self
+                .devices
+                .iter()
+                .find(|old| old.canonical_address == device.canonical_address);
+            let Some(previous) = previous else {
+                events.push(format!("{} {} in range", time, device.name));
+                continue;
+            };
+            for (label, old, new) in [
+                ("left", previous.left_battery, device.left_battery),
+                ("right", previous.right_battery, device.right_battery),
+                ("case", previous.case_battery, device.case_battery),
+            ] {
+                if old != new && old >= 0 && new >= 0 {
+                    events.push(format!(
+                        "{} {} {} {}% -> {}%",
+                        time, device.name, label, old, new
+                    ));
+                }
+            }
+            for (label, old, new) in [
+                ("left", previous.left_charging, device.left_charging),
+                ("right", previous.right_charging, device.right_charging),
+                ("case", previous.case_charging, device.case_charging),
+            ] {
+                if old != new {
+                    let change = if new { "started" } else { "stopped" };
+                    events.push(format!(
+                        "{} {} {} charging {}",
+                        time, device.name, label, change
+                    ));
+                }
+            }
+        }
+        for gone in self.devices.iter().filter(|old| {
+            !devices
+                .iter()
+                .any(|device| device.canonical_address == old.canonical_address)
+        }) {
+            events.push(format!("{} {} out of range", time, gone.name));
+        }
+
+        for event in events {
+            self.push_event(event);
+        }
+
+        if let Some(strongest) = devices.iter().filter_map(|device| device.rssi).max() {
+            self.rssi
+                .push_back(strongest.saturating_sub(RSSI_FLOOR).max(0) as u64);
+            if self.rssi.len() > MAX_RSSI_SAMPLES {
+                self.rssi.pop_front();
+            }
+        }
+        self.devices = devices;
+        self.last_scan = Some(at);
+        self.scanning = false;
+    }
+
+    /// Record the scanner's health and totals
+    pub fn set_scanner(&mut self, health: ScannerHealth, statistics: ScanStatistics) {
+        self.health = health;
+        self.statistics = statistics;
+    }
+
+    fn push_event(&mut self, event: String) {
+        self.events.push_front(event);
+        self.events.truncate(MAX_EVENTS);
+    }
+
+    /// Draw the dashboard
+    pub fn render(&self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(8),
+                Constraint::Length(8),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(rows[1]);
+        let side = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(6), Constraint::Min(4)])
+            .split(middle[1]);
+
+        frame.render_widget(Paragraph::new(self.status_line()), rows[0]);
+        self.render_batteries(frame, middle[0]);
+        frame.render_widget(
+            Sparkline::default()
+                .block(Block::default().borders(Borders::ALL).title("Signal"))
+                .data(self.rssi.iter().copied())
+                .max((-RSSI_FLOOR) as u64)
+                .style(Style::default().fg(Color::Cyan)),
+            side[0],
+        );
+        frame.render_widget(
+            Paragraph::new(self.scanner_lines())
+                .block(Block::default().borders(Borders::ALL).title("Scanner")),
+            side[1],
+        );
+        frame.render_widget(
+            List::new(
+                self.events
+                    .iter()
+                    .map(|event| ListItem::new(event.as_str())),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Recent events"),
+            ),
+            rows[2],
+        );
+        frame.render_widget(
+            Paragraph::new("r refresh   p pause   q quit")
+                .style(Style::default().fg(Color::DarkGray)),
+            rows[3],
+        );
+    }
+
+    fn status_line(&self) -> Line<'_> {
+        let state = if self.scanning {
+            Span::styled("scanning…", Style::default().fg(Color::Yellow))
+        } else if self.paused {
+            Span::styled("paused", Style::default().fg(Color::Magenta))
+        } else {
+            Span::styled("watching", Style::default().fg(Color::Green))
+        };
+        let last = self.last_scan.map_or("no scan yet".to_string(), |at| {
+            format!("last scan {}", at.format("%H:%M:%S"))
+        });
+        Line::from(vec![
+            Span::styled("RustPods ", Style::default().add_modifier(Modifier::BOLD)),
+            state,
+            Span::raw(format!("  {}", last)),
+        ])
+    }
+
+    fn render_batteries(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default().borders(Borders::ALL).title("Batteries");
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if self.devices.is_empty() {
+            frame.render_widget(Paragraph::new("No AirPods in range"), inner);
+            return;
+        }
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(1); self.devices.len() * 4])
+            .split(inner);
+        for (index, device) in self.devices.iter().enumerate() {
+            let title = match device.rssi {
+                Some(rssi) => format!("{}  {} dBm", device.name, rssi),
+                None => device.name.clone(),
+            };
+            frame.render_widget(
+                Paragraph::new(title).style(Style::default().add_modifier(Modifier::BOLD)),
+                rows[index * 4],
+            );
+            for (offset, (label, level, charging)) in [
+                ("L", device.left_battery, device.left_charging),
+                ("R", device.right_battery, device.right_charging),
+                ("Case", device.case_battery, device.case_charging),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                frame.render_widget(
+                    battery_gauge(label, level, charging),
+                    rows[index * 4 + 1 + offset],
+                );
+            }
+        }
+    }
+
+    fn scanner_lines(&self) -> Vec<Line<'_>> {
+        let mut lines = Vec::new();
+        if self.health.is_unhealthy() {
+            lines.push(Line::styled(
+                format!(
+                    "Unhealthy: {} failed runs",
+                    self.health.consecutive_failures
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        } else {
+            lines.push(Line::styled("Healthy", Style::default().fg(Color::Green)));
+        }
+        if let Some(error) = &self.health.last_error {
+            lines.push(Line::raw(format!("Last error: {}", error)));
+        }
+        lines.extend(self.statistics.summary_lines().into_iter().map(Line::raw));
+        lines
+    }
+}
+
+/// Gauge of one battery; levels below zero are unknown
+fn battery_gauge(label: &str, level: i32, charging: bool) -> Gauge<'static> {
+    let (ratio, text) = if level < 0 {
+        (0.0, format!("{} unknown", label))
+    } else {
+        let level = level.min(100);
+        let bolt = if charging { " ⚡" } else { "" };
+        (
+            f64::from(level) / 100.0,
+            format!("{} {}%{}", label, level, bolt),
+        )
+    };
+    let color = match level {
+        l if l < 0 => Color::DarkGray,
+        l if l <= 20 => Color::Red,
+        l if l <= 50 => Color::Yellow,
+        _ => Color::Green,
+    };
+    Gauge::default()
+        .gauge_style(Style::default().fg(color))
+        .ratio(ratio)
+        .label(text)
+}
+
+/// Run the dashboard until the user quits, scanning every `interval`
+pub fn run(interval: Duration) -> io::Result<()> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run_loop(&mut terminal, interval);
+    ratatui::try_restore()?;
+    result
+}
+
+fn run_loop(terminal: &mut ratatui::DefaultTerminal, interval: Duration) -> io::Result<()> {
+    let supervisor = ScannerSupervisor::new();
+    let (results, scans) = mpsc::channel();
+    let mut dashboard = Dashboard::default();
+    let mut next_scan = Instant::now();
+
+    loop {
+        if !dashboard.scanning && !dashboard.paused && Instant::now() >= next_scan {
+            dashboard.scanning = true;
+            let supervisor = supervisor.clone();
+            let results = results.clone();
+            std::thread::spawn(move || {
+                let devices = crate::ui::state::get_airpods_from_cli_scanner(&supervisor);
+                let _ = results.send(devices);
+            });
+        }
+        if let Ok(devices) = scans.try_recv() {
+            dashboard.record_scan(devices, Local::now());
+            dashboard.set_scanner(supervisor.health(), supervisor.statistics());
+            next_scan = Instant::now() + interval;
+        }
+
+        terminal.draw(|frame| dashboard.render(frame))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+            KeyCode::Char('r') => {
+                supervisor.retry_now();
+                next_scan = Instant::now();
+            }
+            KeyCode::Char('p') | KeyCode::Char(' ') => dashboard.paused = !dashboard.paused,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    fn airpods(left: i32, case_charging: bool, rssi: i32) -> AirPodsBatteryInfo {
+        AirPodsBatteryInfo {
+            left_battery: left,
+            right_battery: 70,
+            case_battery: 50,
+            case_charging,
+            rssi: Some(rssi),
+            ..crate::airpods::test_support::report("aabbccddeeff")
+        }
+    }
+
+    #[test]
+    fn test_dashboard_logs_changes_and_renders() {
+        let mut dashboard = Dashboard::default();
+        let at = Local::now();
+        dashboard.record_scan(vec![airpods(80, false, -60)], at);
+        dashboard.record_scan(vec![airpods(70, true, -50)], at);
+        dashboard.record_scan(Vec::new(), at);
+
+        let events: Vec<&str> = dashboard.events.iter().map(String::as_str).collect();
+        assert_eq!(events.len(), 4);
+        assert!(events[0].ends_with("AirPods Pro out of range"));
+        assert!(events[1].ends_with("case charging started"));
+        assert!(events[2].ends_with("left 80% -> 70%"));
+        assert!(events[3].ends_with("in range"));
+        assert_eq!(dashboard.rssi, [40, 50]);
+
+        dashboard.record_scan(vec![airpods(70, true, -50)], at);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| dashboard.render(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("AirPods Pro"));
+        assert!(screen.contains("Recent events"));
+    }
+}
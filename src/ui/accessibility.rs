@@ -0,0 +1,193 @@
+//! Keyboard focus and spoken names for the main window
+//!
+//! iced draws every control itself, so neither the keyboard nor a screen reader can
+//! find them on their own. The main window's controls therefore form a focus ring of
+//! their own: Tab and Shift+Tab move through it, Enter or Space activates the focused
+//! control, and Esc closes whatever screen or popup is on top. Each control has a
+//! spoken name, read through the speech synthesizer when it gains focus while a screen
+//! reader is running.
+
+use iced::widget::container;
+use iced::Element;
+
+use crate::ui::theme::{self, Theme};
+use crate::ui::Message;
+
+/// A control in the main window that can take keyboard focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FocusTarget {
+    /// Health badge shown in the title bar when problems are found
+    HealthBadge,
+    /// Settings caption button
+    OpenSettings,
+    /// Minimize caption button
+    Minimize,
+    /// Close caption button
+    Close,
+    /// Recovery button of an error in the banner
+    RecoverError(u64),
+    /// Dismiss button of an error in the banner
+    DismissError(u64),
+    /// Battery widget; read out, not activated
+    Battery(BatteryWidget),
+    /// Button making the AirPods the default audio output
+    SwitchAudio,
+    /// Advanced device info toggle
+    AdvancedInfo,
+    /// Compact/expanded view toggle
+    ViewMode,
+    /// Row of an AirPods set in household mode; read out, not activated
+    HouseholdSet(usize),
+}
+
+/// Which battery a battery widget shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BatteryWidget {
+    /// Left bud
+    Left,
+    /// Right bud
+    Right,
+    /// Charging case
+    Case,
+    /// Headset reporting a single level
+    Single,
+}
+
+impl BatteryWidget {
+    /// Spoken name of the battery
+    pub fn name(self) -> &'static str {
+        match self {
+            BatteryWidget::Left => "Left AirPod",
+            BatteryWidget::Right => "Right AirPod",
+            BatteryWidget::Case => "Case",
+            BatteryWidget::Single => "Battery",
+        }
+    }
+}
+
+impl FocusTarget {
+    /// Message sent when the control is activated with Enter or Space
+    pub fn message(self) -> Option<Message> {
+        match self {
+            FocusTarget::HealthBadge => Some(Message::ShowTroubleshoot),
+            FocusTarget::OpenSettings => Some(Message::OpenSettings),
+            FocusTarget::Minimize => Some(Message::MinimizeWindow),
+            FocusTarget::Close => Some(Message::WindowCloseRequested),
+            FocusTarget::RecoverError(id) => Some(Message::RecoverError(id)),
+            FocusTarget::DismissError(id) => Some(Message::DismissError(id)),
+            FocusTarget::Battery(_) => None,
+            FocusTarget::SwitchAudio => Some(Message::SwitchAudioToAirPods),
+            FocusTarget::AdvancedInfo => Some(Message::ToggleAdvancedInfo),
+            FocusTarget::ViewMode => Some(Message::ToggleViewMode),
+            FocusTarget::HouseholdSet(_) => None,
+        }
+    }
+}
+
+/// Spoken description of a battery widget, e.g. "Left AirPod 80 percent, charging"
+pub fn battery_label(widget: BatteryWidget, level: Option<u8>, charging: bool) -> String {
+    let mut label = match level {
+        Some(level) => format!("{} {} percent", widget.name(), level),
+        None => format!("{} battery unknown", widget.name()),
+    };
+    if charging {
+        label.push_str(", charging");
+    }
+    label
+}
+
+/// Control after (or before) `current` in `order`, wrapping around
+///
+/// Starts from the first (or last) control when nothing is focused or the focused
+/// control has gone away.
+pub fn step(
+    order: &[FocusTarget],
+    current: Option<FocusTarget>,
+    forward: bool,
+) -> Option<FocusTarget> {
+    if order.is_empty() {
+        return None;
+    }
+    let position = current.and_then(|current| order.iter().position(|t| *t == current));
+    let next = match (position, forward) {
+        (Some(i), true) => (i + 1) % order.len(),
+        (Some(i), false) => (i + order.len() - 1) % order.len(),
+        (None, true) => 0,
+        (None, false) => order.len() - 1,
+    };
+    Some(order[next])
+}
+
+/// `style`, or the primary button style while the button has keyboard focus
+pub fn button_style(style: iced::theme::Button, focused: bool) -> iced::theme::Button {
+    if focused {
+        theme::button_style()
+    } else {
+        style
+    }
+}
+
+/// `content` framed while it has keyboard focus
+///
+/// The frame's padding is kept when unfocused so the layout doesn't shift.
+pub fn focus_frame<'a>(
+    content: impl Into<Element<'a, Message, iced::Renderer<Theme>>>,
+    focused: bool,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    let frame = container(content).padding(4);
+    if focused {
+        frame.style(iced::theme::Container::Box).into()
+    } else {
+        frame.into()
+    }
+}
+
+/// Whether a screen reader such as Narrator or NVDA is running
+#[cfg(target_os = "windows")]
+pub fn screen_reader_active() -> bool {
+    use windows::Win32::Foundation::BOOL;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETSCREENREADER, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+    };
+
+    let mut running = BOOL(0);
+    // SAFETY: SPI_GETSCREENREADER writes a single BOOL to the pointer
+    let result = unsafe {
+        SystemParametersInfoW(
+            SPI_GETSCREENREADER,
+            0,
+            Some(&mut running as *mut BOOL as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    };
+    result.is_ok() && running.as_bool()
+}
+
+/// Whether a screen reader is running
+#[cfg(not(target_os = "windows"))]
+pub fn screen_reader_active() -> bool {
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_wraps_around() {
+        let order = [
+            FocusTarget::Battery(BatteryWidget::Left),
+            FocusTarget::Battery(BatteryWidget::Right),
+            FocusTarget::ViewMode,
+        ];
+        assert_eq!(step(&order, None, true), Some(order[0]));
+        assert_eq!(step(&order, None, false), Some(order[2]));
+        assert_eq!(step(&order, Some(order[2]), true), Some(order[0]));
+        assert_eq!(step(&order, Some(order[0]), false), Some(order[2]));
+        assert_eq!(
+            step(&order, Some(FocusTarget::SwitchAudio), true),
+            Some(order[0])
+        );
+        assert_eq!(step(&[], None, true), None);
+    }
+}
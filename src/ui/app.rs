@@ -2,9 +2,13 @@
 // Only use fields in iced::Settings that are supported by all common Iced versions.
 // This file should be rust-analyzer error free.
 
+use crate::ui::fonts;
+use crate::ui::render_backend;
 use crate::ui::state::AppState;
 use crate::ui::utils::load_window_icon;
-use crate::ui::window_management::{DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH};
+use crate::ui::window_management::{
+    initial_window_size, MAX_WINDOW_HEIGHT, MAX_WINDOW_WIDTH, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH,
+};
 use iced::Application;
 
 /// Runs the UI application with system tray support
@@ -17,25 +21,45 @@ pub fn run_ui_with_options(_test_battery: bool) -> iced::Result {
     // Create a channel for communication between UI and controller
     let (controller_sender, controller_receiver) = tokio::sync::mpsc::unbounded_channel();
 
+    // Register the app identity before any window or notification is created so toasts
+    // are attributed to RustPods rather than a generic host
+    crate::system::register_app_identity();
+
     // Load the application icon with error handling
     let icon = load_window_icon();
 
-    // Run the Iced application using AppState with fixed window properties
+    // Reopen at the size the user last resized to, with the chosen font and text size
+    let config = crate::config::AppConfig::load().unwrap_or_default();
+    let size = initial_window_size(&config);
+    let font_scale = fonts::startup_font_scale(&config);
+    crate::ui::theme::set_font_scale(font_scale);
+    crate::ui::direction::apply_locale(&config);
+    crate::ui::palette::apply(&config);
+    crate::ui::keyboard_shortcuts::set_active_bindings(&config.ui.shortcuts);
+    if config.ui.software_rendering {
+        render_backend::use_software();
+    }
+    if render_backend::is_software() {
+        log::info!("Drawing the window with the software renderer");
+    }
+
+    // Run the Iced application using AppState with resizable window properties
     AppState::run(iced::Settings {
         window: iced::window::Settings {
-            size: (DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT),
-            min_size: Some((DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT)),
-            max_size: Some((DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT)),
-            resizable: false,
+            size,
+            min_size: Some((MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT)),
+            max_size: Some((MAX_WINDOW_WIDTH, MAX_WINDOW_HEIGHT)),
+            resizable: true,
             decorations: false, // Custom title bar
-            transparent: false,
+            // Lets a Mica/Acrylic backdrop show through; the solid style stays opaque
+            transparent: true,
             icon,
             ..Default::default()
         },
         flags: (controller_sender, controller_receiver),
         id: None,
-        default_font: iced::Font::with_name("SpaceMono Nerd Font"),
-        default_text_size: 16.0,
+        default_font: fonts::startup_font(&config),
+        default_text_size: crate::ui::theme::BASE_TEXT_SIZE * font_scale,
         antialiasing: false,
         exit_on_close_request: false, // Allow custom handling of close requests for system tray
     })
@@ -0,0 +1,169 @@
+//! Animated transitions for the battery widgets
+//!
+//! Levels glide to a new reading instead of jumping, the charging bolt pulses, and
+//! the rings flash briefly when the AirPods come back after being out of range.
+//! Everything is driven by elapsed time rather than by counting frames, so a dropped
+//! or late frame only makes an animation coarser, never slower, and frames are only
+//! requested while something is actually moving.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ui::accessibility::BatteryWidget;
+
+/// How long a level takes to glide to a new reading
+pub const FILL_DURATION: Duration = Duration::from_millis(600);
+
+/// How long the reconnect flash lasts
+pub const FLASH_DURATION: Duration = Duration::from_millis(500);
+
+/// One full pulse of the charging bolt
+pub const PULSE_PERIOD: Duration = Duration::from_millis(1500);
+
+/// Frame interval while a level or the flash is moving
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(33);
+
+/// Frame interval while only the bolt is pulsing, which needs far fewer frames
+pub const PULSE_FRAME_INTERVAL: Duration = Duration::from_millis(66);
+
+/// A level gliding from one reading to the next
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Tween {
+    from: f32,
+    to: f32,
+    started: Instant,
+}
+
+impl Tween {
+    fn value(&self, now: Instant) -> f32 {
+        let t = progress(self.started, FILL_DURATION, now);
+        // Ease out: quick to respond, gentle to settle
+        let eased = 1.0 - (1.0 - t).powi(3);
+        self.from + (self.to - self.from) * eased
+    }
+
+    fn finished(&self, now: Instant) -> bool {
+        progress(self.started, FILL_DURATION, now) >= 1.0
+    }
+}
+
+/// Fraction of `duration` elapsed since `started`, from 0.0 to 1.0
+fn progress(started: Instant, duration: Duration, now: Instant) -> f32 {
+    (now.saturating_duration_since(started).as_secs_f32() / duration.as_secs_f32()).min(1.0)
+}
+
+/// Animation state of the battery widgets in the main window
+#[derive(Debug, Clone, Default)]
+pub struct BatteryAnimation {
+    /// Level of each widget, gliding towards its latest reading
+    levels: HashMap<BatteryWidget, Tween>,
+    /// Address of the device last shown, kept while it is out of range
+    last_device: Option<String>,
+    /// Whether a device is currently shown
+    shown: bool,
+    /// When the reconnect flash started
+    flash_started: Option<Instant>,
+}
+
+impl BatteryAnimation {
+    /// Record which device is shown, flashing if it is the last one shown coming back
+    pub fn set_device(&mut self, address: Option<&str>, now: Instant) {
+        match address {
+            Some(address) => {
+                if !self.shown && self.last_device.as_deref() == Some(address) {
+                    self.flash_started = Some(now);
+                }
+                if self.last_device.as_deref() != Some(address) {
+                    // Another device's levels shouldn't glide from the previous one's
+                    self.levels.clear();
+                }
+                self.last_device = Some(address.to_string());
+                self.shown = true;
+            }
+            None => self.shown = false,
+        }
+    }
+
+    /// Record the latest reading of `widget`, gliding to it from the level shown now
+    ///
+    /// The first reading is shown straight away.
+    pub fn set_level(&mut self, widget: BatteryWidget, level: f32, now: Instant) {
+        match self.levels.get_mut(&widget) {
+            Some(tween) if tween.to == level => {}
+            Some(tween) => {
+                *tween = Tween {
+                    from: tween.value(now),
+                    to: level,
+                    started: now,
+                }
+            }
+            None => {
+                self.levels.insert(
+                    widget,
+                    Tween {
+                        from: level,
+                        to: level,
+                        started: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Level to draw for `widget`, or `reading` if it has none yet
+    pub fn level(&self, widget: BatteryWidget, reading: f32, now: Instant) -> f32 {
+        self.levels
+            .get(&widget)
+            .map_or(reading, |tween| tween.value(now))
+    }
+
+    /// Strength of the reconnect flash, fading from 1.0 to 0.0
+    pub fn flash(&self, now: Instant) -> f32 {
+        self.flash_started
+            .map_or(0.0, |started| 1.0 - progress(started, FLASH_DURATION, now))
+    }
+
+    /// Whether a level or the flash is still moving
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.flash(now) > 0.0 || self.levels.values().any(|tween| !tween.finished(now))
+    }
+}
+
+/// Phase of the charging pulse at `elapsed` since any fixed point, from 0.0 to 1.0
+pub fn pulse_phase(elapsed: Duration) -> f32 {
+    (elapsed.as_secs_f32() % PULSE_PERIOD.as_secs_f32()) / PULSE_PERIOD.as_secs_f32()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_glide_and_flash_on_reconnect() {
+        let start = Instant::now();
+        let mut animation = BatteryAnimation::default();
+        animation.set_device(Some("aabbccddeeff"), start);
+        animation.set_level(BatteryWidget::Left, 80.0, start);
+        assert_eq!(animation.level(BatteryWidget::Left, 80.0, start), 80.0);
+        assert!(!animation.is_animating(start));
+
+        animation.set_level(BatteryWidget::Left, 60.0, start);
+        let midway = animation.level(BatteryWidget::Left, 60.0, start + FILL_DURATION / 2);
+        assert!(midway > 60.0 && midway < 80.0);
+        assert!(animation.is_animating(start + FILL_DURATION / 2));
+        assert_eq!(
+            animation.level(BatteryWidget::Left, 60.0, start + FILL_DURATION),
+            60.0
+        );
+
+        // Out of range and back
+        let later = start + FILL_DURATION;
+        animation.set_device(None, later);
+        assert_eq!(animation.flash(later), 0.0);
+        animation.set_device(Some("aabbccddeeff"), later);
+        assert_eq!(animation.flash(later), 1.0);
+        assert_eq!(animation.flash(later + FLASH_DURATION), 0.0);
+
+        assert_eq!(pulse_phase(PULSE_PERIOD / 2), 0.5);
+    }
+}
@@ -8,7 +8,7 @@ use iced::{
     Alignment, Color, Element, Length,
 };
 
-use crate::ui::components::view_circular_battery_widget;
+use crate::ui::components::{view_circular_battery_widget_sized, BatteryWidgetSize};
 use crate::ui::state::MergedBluetoothDevice;
 use crate::ui::theme::{self, Theme};
 use crate::ui::Message;
@@ -47,14 +47,20 @@ pub fn view_device_popup(
     // Header row with device name and close button
     let header_row = row![
         // Device name title
-        text(device.name.clone()).size(24.0).style(theme::TEXT),
+        text(device.name.clone())
+            .size(theme::scaled(24.0))
+            .style(theme::TEXT),
         // Spacer to push elements to opposite ends
         Space::with_width(Length::Fill),
         // Close button with temporary text (for debugging)
-        button(text("X").size(16).style(Color::from_rgb(1.0, 0.0, 0.0)))
-            .on_press(Message::ClosePopup)
-            .style(iced::theme::Button::Text)
-            .padding(8)
+        button(
+            text("X")
+                .size(theme::scaled(16.0))
+                .style(Color::from_rgb(1.0, 0.0, 0.0))
+        )
+        .on_press(Message::ClosePopup)
+        .style(iced::theme::Button::Text)
+        .padding(8)
     ]
     .align_items(Alignment::Center);
 
@@ -75,14 +81,15 @@ pub fn view_device_popup(
     let battery_indicators_row = row![
         // Left earbud circular widget
         column![
-            view_circular_battery_widget(
+            view_circular_battery_widget_sized(
                 device
                     .left_battery_fractional
                     .unwrap_or(device.left_battery.unwrap_or(0) as f32),
-                false // For now, charging state is not available in MergedBluetoothDevice
+                false, // For now, charging state is not available in MergedBluetoothDevice
+                BatteryWidgetSize::Popup,
             ),
             text("Left")
-                .size(14)
+                .size(theme::scaled(14.0))
                 .style(theme::TEXT)
                 .horizontal_alignment(Horizontal::Center)
         ]
@@ -90,14 +97,15 @@ pub fn view_device_popup(
         .spacing(5),
         // Right earbud circular widget
         column![
-            view_circular_battery_widget(
+            view_circular_battery_widget_sized(
                 device
                     .right_battery_fractional
                     .unwrap_or(device.right_battery.unwrap_or(0) as f32),
-                false // For now, charging state is not available in MergedBluetoothDevice
+                false, // For now, charging state is not available in MergedBluetoothDevice
+                BatteryWidgetSize::Popup,
             ),
             text("Right")
-                .size(14)
+                .size(theme::scaled(14.0))
                 .style(theme::TEXT)
                 .horizontal_alignment(Horizontal::Center)
         ]
@@ -105,14 +113,15 @@ pub fn view_device_popup(
         .spacing(5),
         // Case circular widget
         column![
-            view_circular_battery_widget(
+            view_circular_battery_widget_sized(
                 device
                     .case_battery_fractional
                     .unwrap_or(device.case_battery.unwrap_or(0) as f32),
-                false // For now, charging state is not available in MergedBluetoothDevice
+                false, // For now, charging state is not available in MergedBluetoothDevice
+                BatteryWidgetSize::Popup,
             ),
             text("Case")
-                .size(14)
+                .size(theme::scaled(14.0))
                 .style(theme::TEXT)
                 .horizontal_alignment(Horizontal::Center)
         ]
@@ -140,12 +149,27 @@ pub fn view_device_popup(
 pub struct AirPodsPopup {
     /// The AirPods device to display
     pub device: MergedBluetoothDevice,
+
+    /// Transient popups (e.g. shown when the case lid opens) dismiss themselves
+    /// and only show battery levels, without connection controls
+    pub transient: bool,
 }
 
 impl AirPodsPopup {
     /// Create a new AirPods popup
     pub fn new(device: MergedBluetoothDevice) -> Self {
-        Self { device }
+        Self {
+            device,
+            transient: false,
+        }
+    }
+
+    /// Create a transient popup shown when the case lid is opened
+    pub fn transient(device: MergedBluetoothDevice) -> Self {
+        Self {
+            device,
+            transient: true,
+        }
     }
 }
 
@@ -153,12 +177,18 @@ impl UiComponent for AirPodsPopup {
     fn view(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
         // Header with device name and close button
         let header = row![
-            text(&self.device.name).size(20).style(theme::TEXT),
+            text(&self.device.name)
+                .size(theme::scaled(20.0))
+                .style(theme::TEXT),
             iced::widget::Space::with_width(Length::Fill),
-            button(text("X").size(14).style(Color::from_rgb(1.0, 0.0, 0.0)))
-                .padding(6)
-                .on_press(Message::ClosePopup)
-                .style(iced::theme::Button::Text)
+            button(
+                text("X")
+                    .size(theme::scaled(14.0))
+                    .style(Color::from_rgb(1.0, 0.0, 0.0))
+            )
+            .padding(6)
+            .on_press(Message::ClosePopup)
+            .style(iced::theme::Button::Text)
         ]
         .align_items(Alignment::Center)
         .padding([20, 20, 10, 20]);
@@ -167,14 +197,15 @@ impl UiComponent for AirPodsPopup {
         let battery_row = row![
             // Left earbud circular widget
             column![
-                view_circular_battery_widget(
+                view_circular_battery_widget_sized(
                     self.device
                         .left_battery_fractional
                         .unwrap_or(self.device.left_battery.unwrap_or(0) as f32),
-                    false // For now, charging state is not available in MergedBluetoothDevice
+                    false, // For now, charging state is not available in MergedBluetoothDevice
+                    BatteryWidgetSize::Popup,
                 ),
                 text("Left")
-                    .size(14)
+                    .size(theme::scaled(14.0))
                     .style(theme::TEXT)
                     .horizontal_alignment(Horizontal::Center)
             ]
@@ -182,14 +213,15 @@ impl UiComponent for AirPodsPopup {
             .spacing(5),
             // Right earbud circular widget
             column![
-                view_circular_battery_widget(
+                view_circular_battery_widget_sized(
                     self.device
                         .right_battery_fractional
                         .unwrap_or(self.device.right_battery.unwrap_or(0) as f32),
-                    false // For now, charging state is not available in MergedBluetoothDevice
+                    false, // For now, charging state is not available in MergedBluetoothDevice
+                    BatteryWidgetSize::Popup,
                 ),
                 text("Right")
-                    .size(14)
+                    .size(theme::scaled(14.0))
                     .style(theme::TEXT)
                     .horizontal_alignment(Horizontal::Center)
             ]
@@ -197,14 +229,15 @@ impl UiComponent for AirPodsPopup {
             .spacing(5),
             // Case circular widget
             column![
-                view_circular_battery_widget(
+                view_circular_battery_widget_sized(
                     self.device
                         .case_battery_fractional
                         .unwrap_or(self.device.case_battery.unwrap_or(0) as f32),
-                    false // For now, charging state is not available in MergedBluetoothDevice
+                    false, // For now, charging state is not available in MergedBluetoothDevice
+                    BatteryWidgetSize::Popup,
                 ),
                 text("Case")
-                    .size(14)
+                    .size(theme::scaled(14.0))
                     .style(theme::TEXT)
                     .horizontal_alignment(Horizontal::Center)
             ]
@@ -215,6 +248,28 @@ impl UiComponent for AirPodsPopup {
         .align_items(Alignment::Start)
         .padding([10, 20]);
 
+        if self.transient {
+            return container(
+                column![
+                    header,
+                    battery_row,
+                    container(
+                        text("Case opened")
+                            .size(theme::scaled(12.0))
+                            .style(theme::SUBTEXT1)
+                            .horizontal_alignment(Horizontal::Center)
+                    )
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding([0, 20, 20, 20])
+                ]
+                .spacing(0),
+            )
+            .style(iced::theme::Container::Box)
+            .width(350)
+            .into();
+        }
+
         // Connect/Disconnect button
         let action_button = if self.device.connected {
             button(text("Disconnect").horizontal_alignment(Horizontal::Center))
@@ -1,14 +1,18 @@
 //! Battery icon and visualization components
 //!
 //! These components provide various ways to display battery status using
-//! the Catppuccin Mocha theme colors.
+//! the Catppuccin Mocha theme colors, with battery states colored by the selected
+//! palette (see [`crate::ui::palette`]).
 
 use iced::{
     alignment,
-    widget::{column, container, progress_bar, row, text, Svg},
+    widget::{column, container, progress_bar, text, Svg},
     Alignment, Color, Element, Length,
 };
 
+use crate::ui::components::svg_icons::svg_open_tag;
+use crate::ui::direction;
+use crate::ui::palette::{self, BatteryState};
 use crate::ui::theme;
 use crate::ui::theme::Theme;
 use crate::ui::Message;
@@ -21,44 +25,64 @@ pub fn battery_display_row<'a>(
     animation_progress: f32,
 ) -> Element<'a, Message, iced::Renderer<Theme>> {
     // Create the label
-    let label_element = text(label).size(16).width(Length::Fixed(50.0));
+    let label_element = text(label)
+        .size(theme::scaled(16.0))
+        .width(Length::Fixed(50.0));
 
     // Create the level text
+    let state = BatteryState::of(level, is_charging);
     let level_text = match level {
         Some(level) => format!("{}%", level),
         None => "N/A".to_string(),
     };
 
-    let level_element = text(level_text)
-        .size(16)
-        .width(Length::Fixed(50.0))
-        .horizontal_alignment(alignment::Horizontal::Right);
+    let level_element = text(palette::level_label(state, &level_text))
+        .size(theme::scaled(16.0))
+        .width(Length::Fixed(if palette::shape_encoding() {
+            70.0
+        } else {
+            50.0
+        }));
 
     // Create the charging indicator
     let charging_element = if is_charging {
         // Pulse animation for charging icon
         let pulse = (1.0 + (animation_progress * 3.0 * std::f32::consts::PI).sin()) * 0.5;
         text("⚡")
-            .size(16)
+            .size(theme::scaled(16.0))
             .style(pulse_color(pulse))
             .width(Length::Fixed(20.0))
     } else {
-        text("").size(16).width(Length::Fixed(20.0))
+        text("")
+            .size(theme::scaled(16.0))
+            .width(Length::Fixed(20.0))
     };
 
-    // Create the progress bar
+    // Create the progress bar; right-to-left layouts fill it from the right by
+    // drawing the empty part as the bar
     let level_f32 = level.unwrap_or(0) as f32 / 100.0;
-    let progress = progress_bar(0.0..=1.0, level_f32)
+    let shown_f32 = if direction::is_rtl() {
+        1.0 - level_f32
+    } else {
+        level_f32
+    };
+    let progress = progress_bar(0.0..=1.0, shown_f32)
         .style(battery_level_style(level, is_charging))
         .height(18.0);
 
     // Combine everything into a row
-    row![
-        label_element,
-        progress.width(Length::Fill),
-        level_element,
-        charging_element,
-    ]
+    direction::row(vec![
+        label_element.into(),
+        progress.width(Length::Fill).into(),
+        level_element
+            .horizontal_alignment(if direction::is_rtl() {
+                alignment::Horizontal::Left
+            } else {
+                alignment::Horizontal::Right
+            })
+            .into(),
+        charging_element.into(),
+    ])
     .spacing(10)
     .align_items(alignment::Alignment::Center)
     .width(Length::Fill)
@@ -77,41 +101,44 @@ pub fn battery_icon_display<'a>(
     let percentage = battery_level as f32 / 100.0;
 
     // Determine color based on level and charging
-    let color = if is_charging {
-        theme::BLUE
-    } else if battery_level <= 20 {
-        theme::RED
-    } else if battery_level <= 50 {
-        theme::YELLOW
-    } else {
-        theme::GREEN
-    };
-
-    // Convert color to hex string for SVG
-    let hex_color = format!(
-        "#{:02X}{:02X}{:02X}",
-        (color.r * 255.0) as u8,
-        (color.g * 255.0) as u8,
-        (color.b * 255.0) as u8
-    );
+    let state = BatteryState::of(Some(battery_level), is_charging);
+    let hex_color = palette::state_hex(state);
 
     // Generate custom colored SVG string for the battery icon
-    let svg_string = create_colored_battery_svg(percentage, is_charging, &hex_color);
+    let marked_state = palette::shape_encoding().then_some(state);
+    let width = theme::pixel_snapped(size);
+    let height = theme::pixel_snapped(size * 0.6); // Aspect ratio of the horizontal battery
+    let svg_string = create_colored_battery_svg(
+        percentage,
+        is_charging,
+        &hex_color,
+        marked_state,
+        (width, height),
+    );
 
     // Convert string to bytes for Handle::from_memory
     let svg_bytes = svg_string.into_bytes();
 
-    // Create SVG element using the actual size parameter (bug fix!)
     let svg_element = Svg::new(iced::widget::svg::Handle::from_memory(svg_bytes))
-        .width(Length::Fixed(size)) // Use the actual size parameter
-        .height(Length::Fixed(size * 0.6)); // Maintain aspect ratio for horizontal battery
+        .width(Length::Fixed(width))
+        .height(Length::Fixed(height));
 
     // Return the SVG directly without container wrapper since colors are baked into SVG
     svg_element.into()
 }
 
 /// Create a colored SVG battery icon with specific hex color
-fn create_colored_battery_svg(percentage: f32, charging: bool, hex_color: &str) -> String {
+///
+/// With `marked_state` set, the fill also carries the state's pattern: hatched for
+/// medium and an exclamation mark for low, so the icon reads without color. `shown_size`
+/// is the logical size the icon is drawn at.
+fn create_colored_battery_svg(
+    percentage: f32,
+    charging: bool,
+    hex_color: &str,
+    marked_state: Option<BatteryState>,
+    shown_size: (f32, f32),
+) -> String {
     // Clamp percentage between 0.0 and 1.0
     let p = percentage.clamp(0.0, 1.0);
 
@@ -141,11 +168,14 @@ fn create_colored_battery_svg(percentage: f32, charging: bool, hex_color: &str)
     // Define neutral color for battery outline - much lighter/whiter for visibility
     let gray_color = "#CDD6F4"; // Much lighter - almost white for excellent visibility
 
-    write!(
-        &mut svg_string,
-        r#"<svg width="60" height="36" viewBox="0 0 60 36" xmlns="http://www.w3.org/2000/svg">"#,
-    )
-    .unwrap();
+    svg_string.push_str(&svg_open_tag(60.0, 36.0, shown_size.0, shown_size.1));
+
+    // Right-to-left layouts mirror the body so the terminal is on the left and the
+    // fill grows from the right
+    let mirror = direction::is_rtl();
+    if mirror {
+        write!(&mut svg_string, r#"<g transform="matrix(-1 0 0 1 60 0)">"#).unwrap();
+    }
 
     // Main battery body outline (horizontal rectangle) - MUCH thicker stroke for visibility
     write!(
@@ -163,10 +193,35 @@ fn create_colored_battery_svg(percentage: f32, charging: bool, hex_color: &str)
 
     // Fill level rectangle with the actual battery level color (grows from left to right)
     if p > 0.01 {
+        let fill = if marked_state == Some(BatteryState::Medium) {
+            write!(
+                &mut svg_string,
+                r#"<defs><pattern id="hatch" width="4" height="4" patternUnits="userSpaceOnUse" patternTransform="rotate(45)"><rect width="2" height="4" fill="{}"/></pattern></defs>"#,
+                hex_color
+            )
+            .unwrap();
+            "url(#hatch)".to_string()
+        } else {
+            hex_color.to_string()
+        };
         write!(
             &mut svg_string,
             r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" rx="1"/>"#,
-            fill_x, fill_y, fill_width, fill_height, hex_color
+            fill_x, fill_y, fill_width, fill_height, fill
+        )
+        .unwrap();
+    }
+
+    if mirror {
+        write!(&mut svg_string, "</g>").unwrap();
+    }
+
+    // Exclamation mark for a low battery (symmetric, so it needs no mirroring)
+    if marked_state == Some(BatteryState::Low) && !charging {
+        write!(
+            &mut svg_string,
+            r#"<rect x="27" y="11" width="2" height="9" fill="{0}"/><rect x="27" y="22" width="2" height="2" fill="{0}"/>"#,
+            gray_color
         )
         .unwrap();
     }
@@ -201,9 +256,14 @@ pub fn battery_with_label<'a>(
         None => "N/A".to_string(),
     };
 
-    let text_element = text(format!("{}: {}", label, level_text))
-        .size((size * 0.25) as u16)
-        .style(battery_text_style(level, is_charging));
+    let state = BatteryState::of(level, is_charging);
+    let text_element = text(format!(
+        "{}: {}",
+        label,
+        palette::level_label(state, &level_text)
+    ))
+    .size(theme::scaled(size * 0.25))
+    .style(battery_text_style(level, is_charging));
 
     // Combine icon and text
     container(
@@ -226,7 +286,7 @@ fn battery_color(level: Option<u8>, is_charging: bool, animation_progress: f32)
     if is_charging {
         // Pulse between two blues
         let pulse = (1.0 + (animation_progress * 2.0 * std::f32::consts::PI).sin()) * 0.5;
-        let base_color = theme::BLUE;
+        let base_color = palette::state_color(BatteryState::Charging);
         let highlight_color =
             Color::from_rgb(base_color.r * 1.2, base_color.g * 1.2, base_color.b * 1.2);
 
@@ -236,44 +296,31 @@ fn battery_color(level: Option<u8>, is_charging: bool, animation_progress: f32)
             b: base_color.b + (highlight_color.b - base_color.b) * pulse,
             a: 1.0,
         }
-    } else if let Some(level) = level {
-        if level <= 20 {
-            theme::RED
-        } else if level <= 50 {
-            theme::PEACH
-        } else {
-            theme::GREEN
-        }
     } else {
-        theme::OVERLAY1
+        palette::state_color(BatteryState::of(level, false))
     }
 }
 
 /// Get a style for the battery level progress bar
 fn battery_level_style(level: Option<u8>, is_charging: bool) -> iced::theme::ProgressBar {
     // Determine the color now, outside the closure
-    let color = if is_charging {
-        theme::BLUE
-    } else if let Some(level) = level {
-        if level <= 20 {
-            theme::RED
-        } else if level <= 50 {
-            theme::PEACH
-        } else {
-            theme::GREEN
-        }
-    } else {
-        theme::OVERLAY1
-    };
+    let color = palette::state_color(BatteryState::of(level, is_charging));
 
     // Create a new color that is owned and can be moved into the closure
     /* color already defined */
     let bg_color = theme::SURFACE1;
 
+    // Mirrored bars draw the empty part as the bar (see `battery_display_row`)
+    let (background, bar) = if direction::is_rtl() {
+        (color, bg_color)
+    } else {
+        (bg_color, color)
+    };
+
     iced::theme::ProgressBar::Custom(Box::new(move |_: &iced::Theme| {
         iced::widget::progress_bar::Appearance {
-            background: bg_color.into(),
-            bar: color.into(),
+            background: background.into(),
+            bar: bar.into(),
             border_radius: 2.0.into(),
         }
     }))
@@ -281,24 +328,15 @@ fn battery_level_style(level: Option<u8>, is_charging: bool) -> iced::theme::Pro
 
 /// Get a text style for the battery level
 fn battery_text_style(level: Option<u8>, is_charging: bool) -> iced::Color {
-    if is_charging {
-        theme::BLUE // Blue for charging
-    } else if let Some(level) = level {
-        if level <= 20 {
-            theme::RED // Red for low battery
-        } else if level <= 50 {
-            theme::YELLOW // Yellow for medium battery
-        } else {
-            theme::GREEN // Green for good battery
-        }
-    } else {
-        theme::TEXT // White-ish text for unknown battery
+    match BatteryState::of(level, is_charging) {
+        BatteryState::Unknown => theme::TEXT, // White-ish text for unknown battery
+        state => palette::state_color(state),
     }
 }
 
 /// Get a pulsing color for charging animation
 fn pulse_color(pulse: f32) -> iced::Color {
-    let base_color = theme::BLUE;
+    let base_color = palette::state_color(BatteryState::Charging);
     let factor = (pulse * std::f32::consts::PI).sin() * 0.4 + 0.6; // Range: 0.2 - 1.0
 
     iced::Color {
@@ -309,10 +347,70 @@ fn pulse_color(pulse: f32) -> iced::Color {
     }
 }
 
-/// Create a circular progress SVG for battery display
-fn create_circular_battery_svg(level: f32, is_charging: bool) -> String {
-    // Clamp level between 0.0 and 100.0
-    let level = level.clamp(0.0, 100.0);
+/// Size of a circular battery widget, for the place it is shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryWidgetSize {
+    /// Lid-open popup, which shows three rings side by side
+    Popup,
+    /// Main window
+    Main,
+    /// Small always-on-top overlay
+    Overlay,
+}
+
+impl BatteryWidgetSize {
+    /// Diameter of the ring in logical pixels
+    pub fn diameter(self) -> f32 {
+        match self {
+            BatteryWidgetSize::Popup => 96.0,
+            BatteryWidgetSize::Main => 120.0,
+            BatteryWidgetSize::Overlay => 56.0,
+        }
+    }
+}
+
+/// Moment of a circular battery widget's animations (see
+/// [`crate::ui::battery_animation`])
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RingAnimation {
+    /// Level the ring is filled to while gliding to a new reading
+    pub fill: Option<f32>,
+    /// Opacity of the charging bolt, pulsing between dim and full
+    pub bolt_opacity: f32,
+    /// Strength of the reconnect flash, from 0.0 to 1.0
+    pub flash: f32,
+    /// Whether the reading is old, drawing the ring and percentage grayed out
+    pub stale: bool,
+}
+
+impl Default for RingAnimation {
+    fn default() -> Self {
+        Self {
+            fill: None,
+            bolt_opacity: 1.0,
+            flash: 0.0,
+            stale: false,
+        }
+    }
+}
+
+impl RingAnimation {
+    /// Bolt opacity at `phase` (0.0 to 1.0) of the charging pulse
+    pub fn pulse_opacity(phase: f32) -> f32 {
+        0.45 + 0.55 * (0.5 + 0.5 * (phase * 2.0 * std::f32::consts::PI).cos())
+    }
+}
+
+/// Create a circular progress SVG for battery display, shown `diameter` logical
+/// pixels wide, at one moment of its animations
+fn create_circular_battery_svg(
+    level: f32,
+    is_charging: bool,
+    diameter: f32,
+    animation: RingAnimation,
+) -> String {
+    // Clamp level between 0.0 and 100.0; the ring follows the gliding level if any
+    let level = animation.fill.unwrap_or(level).clamp(0.0, 100.0);
 
     // SVG circle parameters - increased by 50%
     let radius = 48.0; // Was 32.0
@@ -326,18 +424,33 @@ fn create_circular_battery_svg(level: f32, is_charging: bool) -> String {
 
     // Catppuccin Mocha theme colors
     let bg_color = "#45475a"; // SURFACE1 - dark, subtle color
-    let progress_color = "#cdd6f4"; // TEXT - bright, contrasting color
-    let charging_color = "#f9e2af"; // YELLOW - bright color for lightning bolt
+    let progress_color = if animation.stale {
+        "#7f849c" // OVERLAY1 - grayed out, the reading is old
+    } else {
+        "#cdd6f4" // TEXT - bright, contrasting color
+    };
+    let charging_color = palette::state_hex(BatteryState::Charging);
 
     let mut svg = String::new();
     use std::fmt::Write;
 
-    // Start SVG - increased size from 80x80 to 120x120
-    write!(
-        &mut svg,
-        r#"<svg width="120" height="120" viewBox="0 0 120 120" xmlns="http://www.w3.org/2000/svg">"#
-    )
-    .unwrap();
+    // Drawn in a 120x120 box and rasterized at the size it is shown at
+    svg.push_str(&svg_open_tag(120.0, 120.0, diameter, diameter));
+
+    // Reconnect flash: a glow around the ring that fades out
+    if animation.flash > 0.0 {
+        write!(
+            &mut svg,
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}" stroke-opacity="{:.3}"/>"#,
+            center,
+            center,
+            radius,
+            palette::state_hex(BatteryState::Charging),
+            stroke_width + 6.0,
+            animation.flash.clamp(0.0, 1.0) * 0.6
+        )
+        .unwrap();
+    }
 
     // Background circle
     write!(
@@ -347,19 +460,24 @@ fn create_circular_battery_svg(level: f32, is_charging: bool) -> String {
     )
     .unwrap();
 
-    // Progress arc (only if level > 0)
+    // Progress arc (only if level > 0); right-to-left layouts run it counter-clockwise
     if level > 0.0 {
+        let mirror = if direction::is_rtl() {
+            format!("matrix(-1 0 0 1 {} 0) ", center * 2.0)
+        } else {
+            String::new()
+        };
         write!(&mut svg,
-            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}" stroke-dasharray="{}" stroke-dashoffset="{}" stroke-linecap="round" transform="rotate(-90 {} {})"/>"#,
-            center, center, radius, progress_color, stroke_width, circumference, dash_offset, center, center
+            r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="{}" stroke-width="{}" stroke-dasharray="{}" stroke-dashoffset="{}" stroke-linecap="round" transform="{}rotate(-90 {} {})"/>"#,
+            center, center, radius, progress_color, stroke_width, circumference, dash_offset, mirror, center, center
         ).unwrap();
     }
 
     // Charging lightning bolt icon - scaled and repositioned for larger circle
     if is_charging {
         write!(&mut svg,
-            r#"<path d="M67.5 45L52.5 63H63L56.25 75L75 57H64.5L70.5 45Z" fill="{}" stroke="none"/>"#,
-            charging_color
+            r#"<path d="M67.5 45L52.5 63H63L56.25 75L75 57H64.5L70.5 45Z" fill="{}" fill-opacity="{:.3}" stroke="none"/>"#,
+            charging_color, animation.bolt_opacity.clamp(0.0, 1.0)
         ).unwrap();
     }
 
@@ -367,22 +485,55 @@ fn create_circular_battery_svg(level: f32, is_charging: bool) -> String {
     svg
 }
 
-/// Create a minimalist circular battery widget inspired by modern UI design
+/// Create a minimalist circular battery widget inspired by modern UI design, at the
+/// main window's size
 pub fn view_circular_battery_widget<'a>(
     level: f32,
     is_charging: bool,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    view_circular_battery_widget_sized(level, is_charging, BatteryWidgetSize::Main)
+}
+
+/// Create a circular battery widget at `size`
+///
+/// The ring is vector drawn at the window's scale factor and snapped to whole physical
+/// pixels, so it stays sharp at 150% scaling and above.
+pub fn view_circular_battery_widget_sized<'a>(
+    level: f32,
+    is_charging: bool,
+    size: BatteryWidgetSize,
+) -> Element<'a, Message, iced::Renderer<Theme>> {
+    view_animated_battery_widget(level, is_charging, size, RingAnimation::default())
+}
+
+/// Create a circular battery widget at `size` and one moment of its animations
+///
+/// The percentage shows the reading `level`; only the ring glides.
+pub fn view_animated_battery_widget<'a>(
+    level: f32,
+    is_charging: bool,
+    size: BatteryWidgetSize,
+    animation: RingAnimation,
 ) -> Element<'a, Message, iced::Renderer<Theme>> {
     // Store Catppuccin Mocha theme colors in owned variables that can be moved into the closure
     let bg_color = theme::BASE; // Dark background
     let border_color = theme::SURFACE0; // Subtle border
-    let text_color = theme::TEXT; // Light text
+    let text_color = if animation.stale {
+        theme::OVERLAY1
+    } else {
+        theme::TEXT // Light text
+    };
+
+    // Everything else is proportioned to the ring, laid out for the 120px main size
+    let diameter = theme::pixel_snapped(size.diameter());
+    let ratio = diameter / BatteryWidgetSize::Main.diameter();
 
     // Create circular progress SVG
-    let svg_string = create_circular_battery_svg(level, is_charging);
+    let svg_string = create_circular_battery_svg(level, is_charging, diameter, animation);
     let svg_bytes = svg_string.into_bytes();
     let svg_element = Svg::new(iced::widget::svg::Handle::from_memory(svg_bytes))
-        .width(Length::Fixed(120.0)) // Increased from 80.0 to 120.0
-        .height(Length::Fixed(120.0)); // Increased from 80.0 to 120.0
+        .width(Length::Fixed(diameter))
+        .height(Length::Fixed(diameter));
 
     // Format the level with 1 decimal place if it's not a whole number
     let level_text = if level.fract() == 0.0 {
@@ -390,6 +541,8 @@ pub fn view_circular_battery_widget<'a>(
     } else {
         format!("{:.1}%", level)
     };
+    let state = BatteryState::of(Some(level.clamp(0.0, 100.0) as u8), is_charging);
+    let level_text = palette::level_label(state, &level_text);
 
     // Create the main container with fixed dimensions
     let main_container = container(
@@ -397,17 +550,19 @@ pub fn view_circular_battery_widget<'a>(
             // Circular battery progress indicator
             svg_element,
             // Battery percentage text with fractional support
-            text(level_text).size(24).style(text_color)
+            text(level_text)
+                .size(theme::scaled(24.0 * ratio))
+                .style(text_color)
         ]
-        .spacing(10)
+        .spacing(10.0 * ratio)
         .align_items(Alignment::Center),
     )
-    .width(Length::Fixed(200.0)) // Increased from 160.0 to accommodate larger ring
-    .height(Length::Fixed(200.0)) // Increased from 160.0 to accommodate larger ring
+    .width(Length::Fixed(theme::pixel_snapped(200.0 * ratio)))
+    .height(Length::Fixed(theme::pixel_snapped(200.0 * ratio)))
     .style(iced::theme::Container::Custom(Box::new(
         move |_: &iced::Theme| container::Appearance {
             background: Some(bg_color.into()),
-            border_radius: 24.0.into(),
+            border_radius: (24.0 * ratio).into(),
             border_width: 1.0,
             border_color,
             text_color: None,
@@ -426,31 +581,67 @@ mod tests {
     #[test]
     fn test_create_circular_battery_svg() {
         // Test SVG generation with different battery levels
-        let svg_25 = create_circular_battery_svg(25.0, false);
+        let svg_25 = create_circular_battery_svg(25.0, false, 120.0, RingAnimation::default());
         assert!(svg_25.contains("svg"));
         assert!(svg_25.contains("circle"));
 
-        let svg_75_charging = create_circular_battery_svg(75.0, true);
+        let svg_75_charging =
+            create_circular_battery_svg(75.0, true, 120.0, RingAnimation::default());
         assert!(svg_75_charging.contains("svg"));
         assert!(svg_75_charging.contains("circle"));
         assert!(svg_75_charging.contains("path")); // Lightning bolt
 
         // Test edge cases
-        let svg_0 = create_circular_battery_svg(0.0, false);
+        let svg_0 = create_circular_battery_svg(0.0, false, 120.0, RingAnimation::default());
         assert!(svg_0.contains("svg"));
 
-        let svg_100 = create_circular_battery_svg(100.0, false);
+        let svg_100 = create_circular_battery_svg(100.0, false, 120.0, RingAnimation::default());
         assert!(svg_100.contains("svg"));
 
         // Test clamping
-        let svg_over_100 = create_circular_battery_svg(150.0, true);
+        let svg_over_100 =
+            create_circular_battery_svg(150.0, true, 120.0, RingAnimation::default());
         assert!(svg_over_100.contains("svg"));
 
         // Test fractional levels
-        let svg_fractional = create_circular_battery_svg(67.3, false);
+        let svg_fractional =
+            create_circular_battery_svg(67.3, false, 120.0, RingAnimation::default());
         assert!(svg_fractional.contains("svg"));
     }
 
+    #[test]
+    fn test_animated_svg() {
+        let animation = RingAnimation {
+            fill: Some(40.0),
+            bolt_opacity: RingAnimation::pulse_opacity(0.5),
+            flash: 0.5,
+            stale: false,
+        };
+        let svg = create_circular_battery_svg(80.0, true, 120.0, animation);
+        let still = create_circular_battery_svg(40.0, true, 120.0, RingAnimation::default());
+        assert!(svg.contains(r#"fill-opacity="0.450""#));
+        assert!(svg.contains(r#"stroke-opacity="0.300""#));
+        // The ring is drawn at the gliding level, not the reading
+        let arc = |svg: &str| svg.split("stroke-dashoffset=").nth(1).unwrap()[..12].to_string();
+        assert_eq!(arc(&svg), arc(&still));
+
+        let stale = RingAnimation {
+            stale: true,
+            ..RingAnimation::default()
+        };
+        assert!(create_circular_battery_svg(40.0, false, 120.0, stale).contains("#7f849c"));
+        assert!(!still.contains("#7f849c"));
+    }
+
+    #[test]
+    fn test_svg_rasterized_at_display_scale() {
+        theme::set_display_scale(1.5);
+        let svg = create_circular_battery_svg(50.0, false, 120.0, RingAnimation::default());
+        assert!(svg.contains(r#"width="180" height="180" viewBox="0 0 120 120""#));
+        assert!((theme::pixel_snapped(55.0) * 1.5 - 83.0).abs() < 1e-3);
+        theme::set_display_scale(1.0);
+    }
+
     #[test]
     fn test_view_circular_battery_widget() {
         // Test widget creation with various parameters
@@ -459,6 +650,8 @@ mod tests {
         let widget_0 = view_circular_battery_widget(0.0, false);
         let widget_100 = view_circular_battery_widget(100.0, false);
         let widget_fractional = view_circular_battery_widget(67.3, false);
+        let _ = view_circular_battery_widget_sized(50.0, true, BatteryWidgetSize::Popup);
+        let _ = view_circular_battery_widget_sized(50.0, false, BatteryWidgetSize::Overlay);
 
         // Widgets should be created without panicking
         let _ = widget_25;
@@ -1,6 +1,7 @@
 //! Battery indicator component for displaying AirPods battery status
 
 use crate::ui::{
+    palette::{self, BatteryState},
     theme::{self, Theme},
     Message,
 };
@@ -43,18 +44,21 @@ pub fn view(
         })
         .push(
             // Second Element: Percentage Text
-            Text::new(match level {
-                Some(value) => format!("{}%", value),
-                None => "--".to_string(),
-            })
+            Text::new(palette::level_label(
+                BatteryState::of(level, is_charging),
+                &match level {
+                    Some(value) => format!("{}%", value),
+                    None => "--".to_string(),
+                },
+            ))
             .style(theme::TEXT)
-            .size(16.0),
+            .size(crate::ui::theme::scaled(16.0)),
         )
         .push(
             // Third Element: Label Text
             Text::new(label.to_string())
                 .style(theme::SUBTEXT1)
-                .size(14.0),
+                .size(crate::ui::theme::scaled(14.0)),
         )
         .into()
 }
@@ -146,10 +150,5 @@ fn battery_icon_svg_string(percentage: f32, charging: bool, color: &str) -> Stri
 
 /// Get the appropriate color hex string for the battery based on its level
 fn get_battery_color(level: Option<u8>) -> String {
-    match level {
-        Some(level) if level > 20 => "#a6e3a1".to_string(), // Green for good battery (>20%)
-        Some(level) if level > 10 => "#f9e2af".to_string(), // Yellow for medium battery (>10%)
-        Some(_) => "#f38ba8".to_string(),                   // Red for low battery (<=10%)
-        None => "#7f849c".to_string(),                      // Gray for unknown battery
-    }
+    palette::state_hex(BatteryState::of(level, false))
 }
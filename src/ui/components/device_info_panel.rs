@@ -0,0 +1,90 @@
+//! Advanced device info panel for RustPods
+//!
+//! Shows the extra proximity message fields (color, primary bud, in-ear and lid state,
+//! signal strength) for the displayed device. Fields the scanner didn't report are
+//! left out.
+
+use iced::{
+    widget::{column, text},
+    Alignment, Element, Length,
+};
+
+use crate::ui::state::MergedBluetoothDevice;
+use crate::ui::{direction, theme, theme::Theme, Message};
+
+/// Render the advanced info panel for a device
+pub fn view_device_info_panel(
+    device: &MergedBluetoothDevice,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let mut rows: Vec<(&str, String)> = Vec::new();
+
+    if let Some(color) = &device.color {
+        rows.push(("Color", color.clone()));
+    }
+    if let Some(side) = &device.side {
+        rows.push(("Primary bud", side.clone()));
+    }
+    if device.left_in_ear.is_some() || device.right_in_ear.is_some() {
+        rows.push((
+            "In ear",
+            format!(
+                "L: {}  R: {}",
+                yes_no(device.left_in_ear),
+                yes_no(device.right_in_ear)
+            ),
+        ));
+    }
+    if let Some(both_in_case) = device.both_in_case {
+        rows.push(("Both in case", yes_no(Some(both_in_case)).to_string()));
+    }
+    if let Some(lid_open) = device.case_lid_open {
+        let state = if lid_open { "Open" } else { "Closed" };
+        let lid = match device.switch_count {
+            Some(count) => format!("{} (opened {} times)", state, count),
+            None => state.to_string(),
+        };
+        rows.push(("Case lid", lid));
+    }
+    if let Some(rssi) = device.rssi {
+        rows.push(("Signal", format!("{} dBm", rssi)));
+    }
+    if !device.address.is_empty() {
+        rows.push(("Address", device.address.clone()));
+    }
+
+    if rows.is_empty() {
+        return text("No extended information reported")
+            .size(theme::scaled(12.0))
+            .style(theme::SUBTEXT1)
+            .into();
+    }
+
+    rows.into_iter()
+        .fold(column![].spacing(4), |panel, (label, value)| {
+            panel.push(
+                direction::row(vec![
+                    text(label)
+                        .size(theme::scaled(12.0))
+                        .style(theme::SUBTEXT1)
+                        .width(Length::Fixed(110.0))
+                        .horizontal_alignment(direction::start())
+                        .into(),
+                    text(value)
+                        .size(theme::scaled(12.0))
+                        .style(theme::TEXT)
+                        .into(),
+                ])
+                .align_items(Alignment::Center),
+            )
+        })
+        .align_items(direction::start_alignment())
+        .into()
+}
+
+fn yes_no(value: Option<bool>) -> &'static str {
+    match value {
+        Some(true) => "Yes",
+        Some(false) => "No",
+        None => "?",
+    }
+}
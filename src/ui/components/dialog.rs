@@ -0,0 +1,260 @@
+//! Modal dialogs for RustPods
+//!
+//! A dialog takes over the window until it is answered or cancelled, so actions that
+//! can't be taken back ask first. It can ask for a confirmation, a choice between a few
+//! options or a line of text, and answering sends the message it was built with.
+
+use iced::{
+    widget::{button, column, container, row, text, text_input, Space},
+    Alignment, Element, Length,
+};
+
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Options of a choice shown side by side with the Cancel button
+const MAX_OPTIONS_IN_ROW: usize = 2;
+
+/// What a dialog asks for
+#[derive(Debug, Clone)]
+pub enum DialogKind {
+    /// Yes or no; confirming sends the message
+    Confirm(Box<Message>),
+    /// One of several options; picking one sends the message built from its index
+    Choose {
+        options: Vec<String>,
+        on_choose: fn(usize) -> Message,
+    },
+    /// A line of text; submitting sends the message built from the trimmed text
+    Input {
+        value: String,
+        placeholder: String,
+        on_submit: fn(String) -> Message,
+    },
+}
+
+/// A question shown in place of the current screen
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    pub title: String,
+    pub body: String,
+    /// Label of the button that answers the dialog
+    pub confirm_label: String,
+    /// Whether answering destroys data, which styles the button as a warning
+    pub destructive: bool,
+    pub kind: DialogKind,
+}
+
+impl Dialog {
+    /// Ask before sending `on_confirm`
+    pub fn confirm(
+        title: impl Into<String>,
+        body: impl Into<String>,
+        confirm_label: impl Into<String>,
+        on_confirm: Message,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            confirm_label: confirm_label.into(),
+            destructive: false,
+            kind: DialogKind::Confirm(Box::new(on_confirm)),
+        }
+    }
+
+    /// Ask which of `options` to go ahead with
+    pub fn choose(
+        title: impl Into<String>,
+        body: impl Into<String>,
+        options: Vec<String>,
+        on_choose: fn(usize) -> Message,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            confirm_label: String::new(),
+            destructive: false,
+            kind: DialogKind::Choose { options, on_choose },
+        }
+    }
+
+    /// Ask for a line of text, starting from `value`
+    pub fn input(
+        title: impl Into<String>,
+        body: impl Into<String>,
+        placeholder: impl Into<String>,
+        value: impl Into<String>,
+        on_submit: fn(String) -> Message,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            confirm_label: "Save".to_string(),
+            destructive: false,
+            kind: DialogKind::Input {
+                value: value.into(),
+                placeholder: placeholder.into(),
+                on_submit,
+            },
+        }
+    }
+
+    /// Mark the dialog as destroying data
+    pub fn destructive(mut self) -> Self {
+        self.destructive = true;
+        self
+    }
+
+    /// Replace the text of an input dialog
+    pub fn set_input(&mut self, text: String) {
+        if let DialogKind::Input { value, .. } = &mut self.kind {
+            *value = text;
+        }
+    }
+
+    /// Message to send when the dialog is confirmed or its text submitted
+    pub fn confirmed(&self) -> Option<Message> {
+        match &self.kind {
+            DialogKind::Confirm(message) => Some((**message).clone()),
+            DialogKind::Input {
+                value, on_submit, ..
+            } => Some(on_submit(value.trim().to_string())),
+            DialogKind::Choose { .. } => None,
+        }
+    }
+
+    /// Message to send when option `index` is picked
+    pub fn chosen(&self, index: usize) -> Option<Message> {
+        match &self.kind {
+            DialogKind::Choose { options, on_choose } if index < options.len() => {
+                Some(on_choose(index))
+            }
+            _ => None,
+        }
+    }
+
+    /// Render the dialog centred in the window
+    pub fn view(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let answer_style = || {
+            if self.destructive {
+                iced::theme::Button::Destructive
+            } else {
+                iced::theme::Button::Primary
+            }
+        };
+
+        let mut content = column![
+            text(&self.title).size(theme::scaled(18.0)),
+            text(&self.body)
+                .size(theme::scaled(13.0))
+                .style(theme::SUBTEXT1),
+        ]
+        .spacing(12);
+
+        let mut buttons = row![Space::with_width(Length::Fill)]
+            .spacing(8)
+            .align_items(Alignment::Center);
+
+        match &self.kind {
+            DialogKind::Confirm(_) => {
+                buttons = buttons.push(
+                    button(text(&self.confirm_label).size(theme::scaled(13.0)))
+                        .on_press(Message::DialogConfirmed)
+                        .style(answer_style()),
+                );
+            }
+            // More than a couple of options won't fit side by side, so they are stacked
+            DialogKind::Choose { options, .. } if options.len() > MAX_OPTIONS_IN_ROW => {
+                let stacked = options.iter().enumerate().fold(
+                    column![].spacing(6),
+                    |stacked, (index, option)| {
+                        stacked.push(
+                            button(text(option).size(theme::scaled(13.0)))
+                                .on_press(Message::DialogChose(index))
+                                .style(answer_style())
+                                .width(Length::Fill),
+                        )
+                    },
+                );
+                content = content.push(stacked);
+            }
+            DialogKind::Choose { options, .. } => {
+                for (index, option) in options.iter().enumerate() {
+                    buttons = buttons.push(
+                        button(text(option).size(theme::scaled(13.0)))
+                            .on_press(Message::DialogChose(index))
+                            .style(answer_style()),
+                    );
+                }
+            }
+            DialogKind::Input {
+                value, placeholder, ..
+            } => {
+                content = content.push(
+                    text_input(placeholder, value)
+                        .on_input(Message::DialogInputChanged)
+                        .on_submit(Message::DialogConfirmed)
+                        .width(Length::Fill),
+                );
+                buttons = buttons.push(
+                    button(text(&self.confirm_label).size(theme::scaled(13.0)))
+                        .on_press(Message::DialogConfirmed)
+                        .style(answer_style()),
+                );
+            }
+        }
+
+        buttons = buttons.push(
+            button(text("Cancel").size(theme::scaled(13.0)))
+                .on_press(Message::DialogCancelled)
+                .style(iced::theme::Button::Secondary),
+        );
+
+        container(
+            container(content.push(buttons))
+                .width(Length::Fixed(theme::scaled(320.0)))
+                .padding(20)
+                .style(iced::theme::Container::Box),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialog_answers() {
+        let confirm = Dialog::confirm("Forget?", "", "Forget", Message::UnpairDevice);
+        assert_eq!(confirm.confirmed(), Some(Message::UnpairDevice));
+        assert_eq!(confirm.chosen(0), None);
+
+        let mut input = Dialog::input("Rename", "", "Name", "", Message::SetDeviceName);
+        input.set_input("  Work AirPods ".to_string());
+        assert_eq!(
+            input.confirmed(),
+            Some(Message::SetDeviceName("Work AirPods".to_string()))
+        );
+
+        let choose = Dialog::choose(
+            "Reset",
+            "",
+            vec!["Keep".to_string(), "Forget".to_string()],
+            |index| Message::ResetSettings {
+                keep_pairing: index == 0,
+            },
+        );
+        assert_eq!(choose.confirmed(), None);
+        assert_eq!(
+            choose.chosen(1),
+            Some(Message::ResetSettings {
+                keep_pairing: false
+            })
+        );
+        assert_eq!(choose.chosen(2), None);
+    }
+}
@@ -0,0 +1,86 @@
+//! Error banner component for RustPods
+//!
+//! Lists errors that are still active, each with its recovery action, until the
+//! user dismisses them or the failing subsystem recovers.
+
+use iced::{
+    widget::{button, column, container, row, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::error::ActiveError;
+use crate::ui::accessibility::{self, FocusTarget};
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the error banner, or nothing when there are no active errors
+pub fn view_error_banner(
+    errors: &[ActiveError],
+    focused: Option<FocusTarget>,
+) -> Element<'_, Message, iced::Renderer<Theme>> {
+    if errors.is_empty() {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    }
+
+    let rows = errors.iter().fold(column![].spacing(6), |rows, error| {
+        rows.push(error_row(error, focused))
+    });
+
+    container(rows)
+        .width(Length::Fill)
+        .padding([8, 12])
+        .style(iced::theme::Container::Box)
+        .into()
+}
+
+/// A single error with its recovery and dismiss buttons
+fn error_row(
+    error: &ActiveError,
+    focused: Option<FocusTarget>,
+) -> Element<'_, Message, iced::Renderer<Theme>> {
+    let message = if error.occurrences > 1 {
+        format!("{} (x{})", error.message, error.occurrences)
+    } else {
+        error.message.clone()
+    };
+
+    let mut content = row![
+        text("⚠").size(theme::scaled(14.0)).style(theme::RED),
+        text(message).size(theme::scaled(13.0))
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+    content = content.push(Space::with_width(Length::Fill));
+
+    if let Some(issue) = &error.known_issue {
+        content = content.push(
+            button(text(format!("{} ↗", issue.title)).size(theme::scaled(12.0)))
+                .on_press(Message::OpenLink(issue.url.clone()))
+                .style(theme::secondary_button_style())
+                .padding([2, 8]),
+        );
+    }
+
+    if let Some(label) = error.recovery.button_label() {
+        content = content.push(
+            button(text(label).size(theme::scaled(12.0)))
+                .on_press(Message::RecoverError(error.id))
+                .style(accessibility::button_style(
+                    theme::secondary_button_style(),
+                    focused == Some(FocusTarget::RecoverError(error.id)),
+                ))
+                .padding([2, 8]),
+        );
+    }
+
+    content
+        .push(
+            button(text("×").size(theme::scaled(14.0)))
+                .on_press(Message::DismissError(error.id))
+                .style(accessibility::button_style(
+                    theme::close_button_style(),
+                    focused == Some(FocusTarget::DismissError(error.id)),
+                ))
+                .padding([0, 6]),
+        )
+        .into()
+}
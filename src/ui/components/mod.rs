@@ -4,16 +4,43 @@
 pub mod airpods_popup;
 pub mod battery_icon;
 pub mod battery_indicator;
+pub mod device_info_panel;
+pub mod dialog;
+pub mod error_banner;
+pub mod scanner_status;
 pub mod settings_view;
+pub mod software_rendering_notice;
 pub mod svg_icons;
+pub mod telemetry_view;
+pub mod title_bar;
+pub mod troubleshoot_view;
+pub mod undo_notice;
+pub mod unknown_model_notice;
+pub mod usage_panel;
 pub mod waiting_mode;
 
 // Re-export components for convenience
 pub use airpods_popup::AirPodsPopup;
 pub use battery_icon::{
-    battery_display_row, battery_icon_display, battery_with_label, view_circular_battery_widget,
+    battery_display_row, battery_icon_display, battery_with_label, view_animated_battery_widget,
+    view_circular_battery_widget, view_circular_battery_widget_sized, BatteryWidgetSize,
+    RingAnimation,
 };
 pub use battery_indicator::view as battery_indicator_view;
-pub use settings_view::{BluetoothSetting, SettingsView, SystemSetting, UiSetting};
+pub use device_info_panel::view_device_info_panel;
+pub use dialog::{Dialog, DialogKind};
+pub use error_banner::view_error_banner;
+pub use scanner_status::view_scanner_status;
+pub use settings_view::{
+    ActionSetting, BluetoothSetting, DeviceEdit, RuleDraft, SettingsView, SyntheticFrameDraft,
+    SystemSetting, UiSetting,
+};
+pub use software_rendering_notice::view_software_rendering_notice;
 pub use svg_icons::{battery_icon_svg_string, refresh_icon_svg_string};
+pub use telemetry_view::view_telemetry_data;
+pub use title_bar::view_title_bar;
+pub use troubleshoot_view::view_troubleshoot;
+pub use undo_notice::view_undo_notice;
+pub use unknown_model_notice::view_unknown_model_notice;
+pub use usage_panel::view_usage_panel;
 pub use waiting_mode::WaitingMode;
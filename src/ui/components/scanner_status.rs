@@ -0,0 +1,35 @@
+//! Scanner status component for RustPods
+//!
+//! Shown while the CLI scanner keeps failing and is backing off between attempts, so
+//! battery levels that stop updating don't go unexplained.
+
+use iced::{
+    widget::{button, container, row, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the scanner warning, or nothing while the scanner is healthy
+pub fn view_scanner_status(status: Option<&str>) -> Element<'_, Message, iced::Renderer<Theme>> {
+    let Some(status) = status else {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    };
+
+    let content = row![
+        text(status).size(theme::scaled(13.0)).style(theme::RED),
+        Space::with_width(Length::Fill),
+        button(text("Retry Now").size(theme::scaled(12.0)))
+            .on_press(Message::RetryScanner)
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    container(content)
+        .width(Length::Fill)
+        .padding([8, 12])
+        .style(iced::theme::Container::Box)
+        .into()
+}
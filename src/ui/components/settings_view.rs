@@ -1,10 +1,22 @@
-use crate::config::AppConfig;
+use crate::airpods::ProfileSnapshot;
+use crate::bluetooth::{FrameReport, ScanStatistics, SyntheticPreset, DEFAULT_SYNTHETIC_ADDRESS};
+use crate::config::{
+    canonical_address, ActionRule, AppConfig, AuditEntry, BackupFrequency, BatteryComponent,
+    BatteryPalette, DeviceColor, DeviceFilterEntry, DeviceIcon, KnownDevices, LogCategory,
+    LogLevel, RuleAction, RuleTrigger, ShortcutAction, SoundAlertEvent, ThemeScheduleMode,
+    TrayIconStyle, TrayMetric, WindowBackdrop,
+};
+use crate::ui::components::Dialog;
+use crate::ui::direction::{self, DirectionalRow};
+use crate::ui::file_dialogs::FileAction;
+use crate::ui::keyboard_shortcuts;
+use crate::ui::state::MergedBluetoothDevice;
 use crate::ui::theme as ui_theme;
 use crate::ui::Message;
 use iced::Length;
 use iced::Renderer;
 use iced::{
-    widget::{Checkbox, Column, Container, Row, Text},
+    widget::{Checkbox, Column, Container, Text},
     Element,
 };
 
@@ -14,14 +26,127 @@ pub struct SettingsView {
     config: AppConfig,
     /// Current connected devices for display
     connected_devices: Vec<String>,
+    /// Address or name pattern being typed for a new allow/deny entry
+    device_filter_input: String,
+    /// AirPods in the latest scan, offered for pairing
+    nearby_airpods: Vec<MergedBluetoothDevice>,
+    /// Action rule being composed
+    rule_draft: RuleDraft,
+    /// Action waiting for a key combination in the shortcut editor
+    recording_shortcut: Option<ShortcutAction>,
+    /// Why the last recorded shortcut was rejected
+    shortcut_error: Option<String>,
+    /// Recent settings changes, newest first
+    audit_entries: Vec<AuditEntry>,
+    /// Battery data snapshots that can be restored, newest first
+    profile_snapshots: Vec<ProfileSnapshot>,
+    /// Totals from the scanner, shown in the scanner status panel
+    scan_statistics: ScanStatistics,
+    /// Whether the scanner status panel is open
+    scanner_status_expanded: bool,
+    /// Address a focused scan is limited to
+    focused_address: Option<String>,
+    /// Recent frames from the focused device, oldest first
+    focus_frames: Vec<FrameReport>,
+    /// Synthetic frame being composed in developer mode
+    synthetic_draft: SyntheticFrameDraft,
+    /// Number of synthetic frames currently injected
+    synthetic_frames: usize,
+    /// Every pair the user has paired with
+    known_devices: KnownDevices,
+    /// Tray icons rendered for the current style
+    tray_preview: Option<TrayPreview>,
+}
+
+/// Tray icons shown next to the style picker, and what they were rendered from
+#[derive(Debug, Clone)]
+struct TrayPreview {
+    style: TrayIconStyle,
+    folder: Option<std::path::PathBuf>,
+    dark: bool,
+    /// Connected and disconnected icons, or why the custom set can't be used
+    icons: Result<Vec<iced::widget::image::Handle>, String>,
+}
+
+impl TrayPreview {
+    /// Render the preview for `config`
+    fn new(config: &AppConfig) -> Self {
+        use crate::ui::system_tray::{render_tray_icon, ThemeMode};
+
+        let style = config.ui.tray_icon_style;
+        let folder = config.ui.tray_icon_folder.clone();
+        let dark = ThemeMode::from(config.ui.theme.clone()) == ThemeMode::Dark;
+        let custom = match (style, &folder) {
+            (TrayIconStyle::Custom, Some(folder)) => {
+                crate::assets::tray::load_icon_set(folder).map(Some)
+            }
+            _ => Ok(None),
+        };
+        // A typical level, so the percentage style has something to show
+        let sample = crate::ui::headline::Headline {
+            metric: config.ui.tray_metric,
+            level: 80,
+            low: false,
+            charging: false,
+        };
+        let icons = match (style, &folder, custom) {
+            (TrayIconStyle::Custom, None, _) => Err(format!(
+                "Choose a folder with {} and {}",
+                crate::assets::tray::CUSTOM_CONNECTED,
+                crate::assets::tray::CUSTOM_DISCONNECTED
+            )),
+            (_, _, Err(e)) => Err(e.to_string()),
+            (_, _, Ok(custom)) => [true, false]
+                .into_iter()
+                .map(|connected| {
+                    render_tray_icon(style, dark, connected, Some(sample), custom.as_ref())
+                        .map(|icon| {
+                            let (width, height) = icon.dimensions();
+                            iced::widget::image::Handle::from_pixels(width, height, icon.into_raw())
+                        })
+                        .map_err(|e| e.to_string())
+                })
+                .collect(),
+        };
+        Self {
+            style,
+            folder,
+            dark,
+            icons,
+        }
+    }
+
+    /// Whether this preview still shows what `config` asks for
+    fn matches(&self, config: &AppConfig) -> bool {
+        self.style == config.ui.tray_icon_style
+            && self.folder == config.ui.tray_icon_folder
+            && self.dark
+                == (crate::ui::system_tray::ThemeMode::from(config.ui.theme.clone())
+                    == crate::ui::system_tray::ThemeMode::Dark)
+    }
 }
 
 impl SettingsView {
     /// Create a new settings view
     pub fn new(config: AppConfig) -> Self {
         Self {
-            config,
             connected_devices: Vec::new(),
+            device_filter_input: String::new(),
+            nearby_airpods: Vec::new(),
+            rule_draft: RuleDraft::default(),
+            recording_shortcut: None,
+            shortcut_error: None,
+            audit_entries: Vec::new(),
+            profile_snapshots: Vec::new(),
+            scan_statistics: ScanStatistics::default(),
+            scanner_status_expanded: false,
+            focused_address: None,
+            focus_frames: Vec::new(),
+            synthetic_draft: SyntheticFrameDraft::default(),
+            synthetic_frames: 0,
+            known_devices: KnownDevices::default(),
+            tray_preview: Some(TrayPreview::new(&config)),
+            config,
         }
     }
 
@@ -33,6 +158,13 @@ impl SettingsView {
     /// Update the config
     pub fn update_config(&mut self, config: AppConfig) {
         crate::debug_log!("ui", "SettingsView::update_config called");
+        if !self
+            .tray_preview
+            .as_ref()
+            .is_some_and(|preview| preview.matches(&config))
+        {
+            self.tray_preview = Some(TrayPreview::new(&config));
+        }
         self.config = config;
         crate::debug_log!("ui", "SettingsView::config updated");
     }
@@ -42,6 +174,82 @@ impl SettingsView {
         self.connected_devices = devices;
     }
 
+    /// Update the AirPods offered for pairing
+    pub fn update_nearby_airpods(&mut self, devices: Vec<MergedBluetoothDevice>) {
+        self.nearby_airpods = devices;
+    }
+
+    /// Update the pending allow/deny entry input
+    pub fn set_device_filter_input(&mut self, value: String) {
+        self.device_filter_input = value;
+    }
+
+    /// Update the action rule being composed
+    pub fn set_rule_draft(&mut self, draft: RuleDraft) {
+        self.rule_draft = draft;
+    }
+
+    /// Update the synthetic frame being composed
+    pub fn set_synthetic_draft(&mut self, draft: SyntheticFrameDraft) {
+        self.synthetic_draft = draft;
+    }
+
+    /// Update the number of injected synthetic frames
+    pub fn set_synthetic_frames(&mut self, count: usize) {
+        self.synthetic_frames = count;
+    }
+
+    /// Update the known devices listed in the device section
+    pub fn set_known_devices(&mut self, devices: KnownDevices) {
+        self.known_devices = devices;
+    }
+
+    /// Action waiting for a key combination, if any
+    pub fn recording_shortcut(&self) -> Option<ShortcutAction> {
+        self.recording_shortcut
+    }
+
+    /// Start or stop waiting for a key combination
+    pub fn set_recording_shortcut(&mut self, action: Option<ShortcutAction>) {
+        self.recording_shortcut = action;
+        self.shortcut_error = None;
+    }
+
+    /// Show why a recorded shortcut was rejected
+    pub fn set_shortcut_error(&mut self, error: Option<String>) {
+        self.shortcut_error = error;
+    }
+
+    /// Update the settings changes listed in the advanced section
+    pub fn set_audit_entries(&mut self, entries: Vec<AuditEntry>) {
+        self.audit_entries = entries;
+    }
+
+    /// Update the battery data snapshots offered in the advanced section
+    pub fn set_profile_snapshots(&mut self, snapshots: Vec<ProfileSnapshot>) {
+        self.profile_snapshots = snapshots;
+    }
+
+    /// Update the totals shown in the scanner status panel
+    pub fn set_scan_statistics(&mut self, statistics: ScanStatistics) {
+        self.scan_statistics = statistics;
+    }
+
+    /// Update the device a focused scan is limited to and its recent frames
+    pub fn set_focused_scan(&mut self, address: Option<String>, frames: Vec<FrameReport>) {
+        // Focusing opens the panel so the frames are in view
+        if address.is_some() && self.focused_address.is_none() {
+            self.scanner_status_expanded = true;
+        }
+        self.focused_address = address;
+        self.focus_frames = frames;
+    }
+
+    /// Open or close the scanner status panel
+    pub fn toggle_scanner_status(&mut self) {
+        self.scanner_status_expanded = !self.scanner_status_expanded;
+    }
+
     /// Update bluetooth settings
     pub fn update_bluetooth_setting(&mut self, setting: BluetoothSetting) {
         crate::debug_log!(
@@ -57,12 +265,415 @@ impl SettingsView {
                     Some(value.trim().to_string())
                 };
             }
+            BluetoothSetting::OnlyPairedDevice(value) => {
+                self.config.bluetooth.only_paired_device = value;
+            }
+            BluetoothSetting::AllowListedOnly(value) => {
+                self.config.bluetooth.allow_listed_only = value;
+            }
+            BluetoothSetting::AllowDevice(entry) => {
+                if !self.config.bluetooth.allow_list.contains(&entry) {
+                    self.config.bluetooth.allow_list.push(entry);
+                }
+                self.device_filter_input.clear();
+            }
+            BluetoothSetting::DenyDevice(entry) => {
+                if !self.config.bluetooth.deny_list.contains(&entry) {
+                    self.config.bluetooth.deny_list.push(entry);
+                }
+                self.device_filter_input.clear();
+            }
+            BluetoothSetting::RemoveAllowedDevice(index) => {
+                if index < self.config.bluetooth.allow_list.len() {
+                    self.config.bluetooth.allow_list.remove(index);
+                }
+            }
+            BluetoothSetting::RemoveDeniedDevice(index) => {
+                if index < self.config.bluetooth.deny_list.len() {
+                    self.config.bluetooth.deny_list.remove(index);
+                }
+            }
+        }
+    }
+
+    /// Pairing section: pick your AirPods from the latest scan
+    fn pairing_settings(&self) -> Column<'_, Message, Renderer<ui_theme::Theme>> {
+        let fingerprint = self.config.bluetooth.paired_fingerprint.as_ref();
+
+        let mut section = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(10)
+            .push(
+                Text::new("My AirPods")
+                    .style(ui_theme::TEXT)
+                    .size(ui_theme::scaled(16.0)),
+            );
+
+        section = match fingerprint {
+            Some(fingerprint) => section.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(format!(
+                            "Paired with {} (seen at {} addresses)",
+                            fingerprint.model_name,
+                            fingerprint.addresses.len()
+                        ))
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fill),
+                    )
+                    .push(
+                        iced::widget::button("Rename")
+                            .on_press(Message::ShowDialog(Box::new(Dialog::input(
+                                "Rename AirPods",
+                                "Shown in the main window and notifications",
+                                "Custom device name",
+                                self.config
+                                    .bluetooth
+                                    .paired_device_name
+                                    .clone()
+                                    .unwrap_or_default(),
+                                Message::SetDeviceName,
+                            ))))
+                            .style(iced::theme::Button::Secondary),
+                    )
+                    .push(
+                        iced::widget::button("Forget")
+                            .on_press(Message::ShowDialog(Box::new(
+                                Dialog::confirm(
+                                    format!("Forget {}?", fingerprint.model_name),
+                                    "RustPods will stop following these AirPods as their address changes.",
+                                    "Forget",
+                                    Message::UnpairDevice,
+                                )
+                                .destructive(),
+                            )))
+                            .style(iced::theme::Button::Destructive),
+                    ),
+            ),
+            None => section.push(
+                Text::new("Pick your AirPods so RustPods recognizes them as their address changes")
+                    .style(ui_theme::SUBTEXT1),
+            ),
+        };
+
+        let waiting: Vec<&String> = self
+            .config
+            .bluetooth
+            .tracked_devices
+            .iter()
+            .filter(|address| {
+                !self
+                    .nearby_airpods
+                    .iter()
+                    .any(|device| canonical_address(&device.address).as_ref() == Some(*address))
+            })
+            .collect();
+
+        if self.nearby_airpods.is_empty() && waiting.is_empty() {
+            section = section.push(Text::new("No AirPods in range").style(ui_theme::SUBTEXT1));
+        }
+
+        for device in &self.nearby_airpods {
+            let is_paired =
+                fingerprint.is_some_and(|fingerprint| fingerprint.knows_address(&device.address));
+            let details = [
+                device.color.clone(),
+                device.rssi.map(|rssi| format!("{} dBm", rssi)),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ");
+
+            let is_focused = self.focused_address.as_ref().is_some_and(|focused| {
+                canonical_address(&device.address).as_ref() == Some(focused)
+            });
+            let focus = iced::widget::button(if is_focused { "Unfocus" } else { "Focus" })
+                .on_press(Message::FocusScan(
+                    (!is_focused).then(|| device.address.clone()),
+                ))
+                .style(iced::theme::Button::Secondary);
+
+            let action: Element<'_, Message, Renderer<ui_theme::Theme>> = if is_paired {
+                Text::new("Paired").style(ui_theme::SUBTEXT1).into()
+            } else {
+                iced::widget::button("Pair")
+                    .on_press(Message::PairDevice(device.address.clone()))
+                    .style(iced::theme::Button::Secondary)
+                    .into()
+            };
+
+            section = section.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(device.name.as_str())
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fill),
+                    )
+                    .push(Text::new(details).style(ui_theme::SUBTEXT1))
+                    .push(focus)
+                    .push(action),
+            );
+        }
+
+        // Placeholders for tracked addresses, replaced by the device once it advertises
+        for address in waiting {
+            section = section.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(format_address(address))
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fill),
+                    )
+                    .push(Text::new("Waiting for advertisements").style(ui_theme::SUBTEXT1))
+                    .push(
+                        iced::widget::button("Stop Tracking")
+                            .on_press(Message::UntrackDeviceAddress(address.clone()))
+                            .style(iced::theme::Button::Secondary),
+                    ),
+            );
+        }
+
+        section.push(
+            iced::widget::button("Track device by address…")
+                .on_press(Message::ShowDialog(Box::new(Dialog::input(
+                    "Track Device by Address",
+                    "RustPods will show this device as soon as it advertises, even if it hasn't been seen yet.",
+                    "AA:BB:CC:DD:EE:FF or device ID",
+                    "",
+                    Message::TrackDeviceAddress,
+                ))))
+                .style(iced::theme::Button::Secondary),
+        )
+    }
+
+    /// Known devices section: nickname, icon, color and primary flag of each pair
+    fn known_device_settings(&self) -> Column<'_, Message, Renderer<ui_theme::Theme>> {
+        let mut section = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(10)
+            .push(
+                Text::new("Known AirPods")
+                    .style(ui_theme::TEXT)
+                    .size(ui_theme::scaled(16.0)),
+            );
+
+        if self.known_devices.is_empty() {
+            return section.push(
+                Text::new("AirPods you pair with are listed here to name and color them")
+                    .style(ui_theme::SUBTEXT1),
+            );
+        }
+
+        for device in &self.known_devices.devices {
+            let id = device.id();
+            let primary: Element<'_, Message, Renderer<ui_theme::Theme>> = if device.primary {
+                Text::new("Primary").style(ui_theme::SUBTEXT1).into()
+            } else {
+                iced::widget::button("Make Primary")
+                    .on_press(Message::EditKnownDevice(
+                        id.clone(),
+                        DeviceEdit::MakePrimary,
+                    ))
+                    .style(iced::theme::Button::Secondary)
+                    .into()
+            };
+            let nickname_id = id.clone();
+            let icon_id = id.clone();
+            let color_id = id.clone();
+
+            section = section.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(device.icon.glyph().to_string())
+                            .style(ui_theme::device_color(device.color))
+                            .width(Length::Fixed(20.0)),
+                    )
+                    .push(
+                        iced::widget::text_input(
+                            &device.fingerprint.model_name,
+                            device.nickname.as_deref().unwrap_or(""),
+                        )
+                        .on_input(move |value| {
+                            Message::EditKnownDevice(
+                                nickname_id.clone(),
+                                DeviceEdit::Nickname(value),
+                            )
+                        })
+                        .width(Length::Fill),
+                    )
+                    .push(iced::widget::pick_list(
+                        &DeviceIcon::ALL[..],
+                        Some(device.icon),
+                        move |icon| {
+                            Message::EditKnownDevice(icon_id.clone(), DeviceEdit::Icon(icon))
+                        },
+                    ))
+                    .push(iced::widget::pick_list(
+                        &DeviceColor::ALL[..],
+                        Some(device.color),
+                        move |color| {
+                            Message::EditKnownDevice(color_id.clone(), DeviceEdit::Color(color))
+                        },
+                    ))
+                    .push(primary)
+                    .push(
+                        iced::widget::button("Forget")
+                            .on_press(Message::ShowDialog(Box::new(
+                                Dialog::confirm(
+                                    format!("Forget {}?", device.display_name()),
+                                    "Its nickname, icon and color will be removed.",
+                                    "Forget",
+                                    Message::EditKnownDevice(id, DeviceEdit::Forget),
+                                )
+                                .destructive(),
+                            )))
+                            .style(iced::theme::Button::Destructive),
+                    ),
+            );
+        }
+        section
+    }
+
+    /// Allow/deny list section
+    fn device_filter_settings(&self) -> Column<'_, Message, Renderer<ui_theme::Theme>> {
+        let bluetooth = &self.config.bluetooth;
+
+        let paired_hint = match &bluetooth.paired_device_id {
+            Some(address) => format!("Paired device: {}", address),
+            None => "The device shown when this is turned on becomes the paired device".to_string(),
+        };
+        let only_paired = Checkbox::new(
+            "Only my paired device",
+            bluetooth.only_paired_device,
+            |value| Message::UpdateBluetoothSetting(BluetoothSetting::OnlyPairedDevice(value)),
+        );
+
+        let allow_listed_only = Checkbox::new(
+            "Only listed addresses",
+            bluetooth.allow_listed_only,
+            |value| Message::UpdateBluetoothSetting(BluetoothSetting::AllowListedOnly(value)),
+        );
+
+        let entry = DeviceFilterEntry::parse(&self.device_filter_input);
+        let entry_input = iced::widget::text_input(
+            "Address (AA:BB:CC:DD:EE:FF), device ID or name, e.g. *Alex*",
+            &self.device_filter_input,
+        )
+        .on_input(Message::DeviceFilterInputChanged)
+        .width(Length::Fill);
+        let allow_button =
+            iced::widget::button("Allow")
+                .on_press_maybe(entry.clone().map(|entry| {
+                    Message::UpdateBluetoothSetting(BluetoothSetting::AllowDevice(entry))
+                }))
+                .style(iced::theme::Button::Secondary);
+        let deny_button =
+            iced::widget::button("Ignore")
+                .on_press_maybe(entry.map(|entry| {
+                    Message::UpdateBluetoothSetting(BluetoothSetting::DenyDevice(entry))
+                }))
+                .style(iced::theme::Button::Secondary);
+
+        let mut table = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(6);
+        let rows = bluetooth
+            .allow_list
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| ("Allow", entry, BluetoothSetting::RemoveAllowedDevice(index)))
+            .chain(
+                bluetooth
+                    .deny_list
+                    .iter()
+                    .enumerate()
+                    .map(|(index, entry)| {
+                        ("Ignore", entry, BluetoothSetting::RemoveDeniedDevice(index))
+                    }),
+            );
+        for (action, entry, remove) in rows {
+            let kind = match entry {
+                DeviceFilterEntry::Address(_) => "Address",
+                DeviceFilterEntry::NamePattern(_) => "Name",
+            };
+            table = table.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(action)
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(60.0)),
+                    )
+                    .push(
+                        Text::new(kind)
+                            .style(ui_theme::SUBTEXT1)
+                            .width(Length::Fixed(70.0)),
+                    )
+                    .push(
+                        Text::new(entry.value())
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        iced::widget::button("Remove")
+                            .on_press(Message::UpdateBluetoothSetting(remove))
+                            .style(iced::theme::Button::Destructive),
+                    ),
+            );
+        }
+        if bluetooth.allow_list.is_empty() && bluetooth.deny_list.is_empty() {
+            table = table.push(
+                Text::new("No entries; every nearby device is shown").style(ui_theme::SUBTEXT1),
+            );
         }
+
+        Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(15)
+            .push(
+                Text::new("Device Filtering")
+                    .style(ui_theme::TEXT)
+                    .size(ui_theme::scaled(16.0)),
+            )
+            .push(only_paired)
+            .push(
+                Text::new(paired_hint)
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+            )
+            .push(allow_listed_only)
+            .push(
+                Text::new(
+                    "Ignores everything except allowed addresses, tracked devices and your paired AirPods; name patterns don't count",
+                )
+                .style(ui_theme::SUBTEXT1)
+                .size(ui_theme::scaled(12.0)),
+            )
+            .push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .push(entry_input)
+                    .push(allow_button)
+                    .push(deny_button),
+            )
+            .push(table)
     }
 
     /// Bluetooth settings section
     pub fn bluetooth_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
-        let title = Text::new("Device Settings").size(20).style(ui_theme::TEXT);
+        let title = Text::new("Device Settings")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
 
         // Device naming section - show if we have connected devices
         let device_section = if !self.connected_devices.is_empty() {
@@ -86,11 +697,16 @@ impl SettingsView {
             .width(Length::Fill);
 
             Column::new()
+                .align_items(direction::start_alignment())
                 .spacing(15)
-                .push(Text::new("Connected Device").style(ui_theme::TEXT).size(16))
+                .push(
+                    Text::new("Connected Device")
+                        .style(ui_theme::TEXT)
+                        .size(ui_theme::scaled(16.0)),
+                )
                 .push(Text::new(format!("Device: {}", display_name)).style(ui_theme::TEXT))
                 .push(
-                    Row::new()
+                    DirectionalRow::new()
                         .spacing(10)
                         .push(
                             Text::new("Custom Name:")
@@ -101,11 +717,12 @@ impl SettingsView {
                 )
         } else {
             Column::new()
+                .align_items(direction::start_alignment())
                 .spacing(10)
                 .push(
                     Text::new("No Device Connected")
                         .style(ui_theme::TEXT)
-                        .size(16),
+                        .size(ui_theme::scaled(16.0)),
                 )
                 .push(
                     Text::new("Connect your AirPods to customize device settings")
@@ -115,39 +732,75 @@ impl SettingsView {
 
         // Battery Intelligence section
         let intelligence_section = Column::new()
+            .align_items(direction::start_alignment())
             .spacing(15)
             .push(
                 Text::new("Battery Intelligence")
                     .style(ui_theme::TEXT)
-                    .size(16),
+                    .size(ui_theme::scaled(16.0)),
             )
             .push(Text::new("Manage battery learning profiles and data").style(ui_theme::SUBTEXT1))
             .push(
-                Row::new()
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(Checkbox::new(
+                        "Show estimated mAh",
+                        self.config.ui.show_mah_estimates,
+                        |value| Message::UpdateUiSetting(UiSetting::ShowMahEstimates(value)),
+                    ))
+                    .push(
+                        Text::new("Charge left from the model's capacity, in the details and exported data")
+                            .style(ui_theme::SUBTEXT1)
+                            .size(ui_theme::scaled(12.0)),
+                    ),
+            )
+            .push(
+                DirectionalRow::new()
                     .spacing(10)
                     .push(
                         iced::widget::button("Open Profile Folder")
                             .on_press(Message::OpenProfileFolder)
                             .style(iced::theme::Button::Secondary),
                     )
+                    .push(
+                        iced::widget::button("Export Data…")
+                            .on_press(Message::ChooseFile(FileAction::ExportBatteryData))
+                            .style(iced::theme::Button::Secondary),
+                    )
                     .push(
                         iced::widget::button("Purge All Profiles")
-                            .on_press(Message::PurgeProfiles)
+                            .on_press(Message::ShowDialog(Box::new(
+                                Dialog::confirm(
+                                    "Purge all profiles?",
+                                    "Everything RustPods has learned about your batteries will be deleted.",
+                                    "Purge",
+                                    Message::PurgeProfiles,
+                                )
+                                .destructive(),
+                            )))
                             .style(iced::theme::Button::Destructive),
                     ),
             );
 
         Column::new()
+            .align_items(direction::start_alignment())
             .spacing(25)
             .push(title)
             .push(device_section)
+            .push(self.pairing_settings())
+            .push(self.known_device_settings())
+            .push(self.device_filter_settings())
             .push(intelligence_section)
+            .width(Length::Fill)
             .into()
     }
 
     /// UI settings section  
     pub fn ui_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
-        let title = Text::new("Interface").size(20).style(ui_theme::TEXT);
+        let title = Text::new("Interface")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
 
         let minimize_to_tray = Checkbox::new(
             "Minimize to tray on close",
@@ -155,68 +808,1650 @@ impl SettingsView {
             |value| Message::UpdateUiSetting(UiSetting::MinimizeToTrayOnClose(value)),
         );
 
-        Container::new(
-            Column::new()
-                .spacing(15)
-                .push(title)
-                .push(minimize_to_tray)
-                .width(Length::Fill),
-        )
-        .width(Length::Fill)
-        .into()
-    }
+        let lid_open_popup = Checkbox::new(
+            "Show battery popup when the case is opened",
+            self.config.ui.show_lid_open_popup,
+            |value| Message::UpdateUiSetting(UiSetting::ShowLidOpenPopup(value)),
+        );
 
-    /// System settings section
-    pub fn system_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
-        let title = Text::new("System").size(20).style(ui_theme::TEXT);
+        let open_near_tray = Checkbox::new(
+            "Open next to the system tray",
+            self.config.ui.open_near_tray,
+            |value| Message::UpdateUiSetting(UiSetting::OpenNearTray(value)),
+        );
 
-        let startup_option = Checkbox::new(
-            "Start on system startup",
-            self.config.system.launch_at_startup,
-            |value| Message::UpdateSystemSetting(SystemSetting::StartOnBoot(value)),
+        let audio_switch = Checkbox::new(
+            "Offer to switch audio output to AirPods",
+            self.config.ui.show_audio_switch_button,
+            |value| Message::UpdateUiSetting(UiSetting::ShowAudioSwitchButton(value)),
         );
 
-        Container::new(
-            Column::new()
-                .spacing(15)
-                .push(title)
-                .push(startup_option)
-                .width(Length::Fill),
-        )
-        .width(Length::Fill)
-        .into()
-    }
-}
+        let household_mode = Checkbox::new(
+            "Show every AirPods set nearby (shared household)",
+            self.config.ui.household_mode,
+            |value| Message::UpdateUiSetting(UiSetting::HouseholdMode(value)),
+        );
 
-/// Bluetooth settings enum
-#[derive(Debug, Clone, PartialEq)]
-pub enum BluetoothSetting {
-    /// Custom device name
-    DeviceName(String),
-}
+        let exclude_from_capture = Checkbox::new(
+            "Hide RustPods from screen recordings and sharing",
+            self.config.ui.exclude_from_capture,
+            |value| Message::UpdateUiSetting(UiSetting::ExcludeFromCapture(value)),
+        );
 
-/// UI settings enum
-#[derive(Debug, Clone, PartialEq)]
-pub enum UiSetting {
-    /// Theme
-    Theme(ui_theme::Theme),
-    /// Show notifications
-    ShowNotifications(bool),
-    /// Start minimized
-    StartMinimized(bool),
-    /// Show percentage in tray
-    ShowPercentageInTray(bool),
-    /// Show low battery warning
-    ShowLowBatteryWarning(bool),
-    /// Low battery threshold
-    LowBatteryThreshold(u8),
-    /// Minimize to tray when close button is pressed
-    MinimizeToTrayOnClose(bool),
-}
+        let taskbar_badge = Checkbox::new(
+            "Show battery level on the taskbar button",
+            self.config.ui.taskbar_badge,
+            |value| Message::UpdateUiSetting(UiSetting::TaskbarBadge(value)),
+        );
 
-/// System settings enum
-#[derive(Debug, Clone, PartialEq)]
-pub enum SystemSetting {
-    /// Start on boot
-    StartOnBoot(bool),
+        let speech = &self.config.ui.speech;
+        let speech_enabled =
+            Checkbox::new("Spoken battery announcements", speech.enabled, |value| {
+                Message::UpdateUiSetting(UiSetting::SpeechEnabled(value))
+            });
+
+        let low_battery_warning = Checkbox::new(
+            "Warn when battery is low",
+            self.config.ui.show_low_battery_warning,
+            |value| Message::UpdateUiSetting(UiSetting::ShowLowBatteryWarning(value)),
+        );
+
+        let mut content = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(15)
+            .push(title)
+            .push(low_battery_warning);
+
+        if self.config.ui.show_low_battery_warning {
+            for component in [
+                BatteryComponent::Left,
+                BatteryComponent::Right,
+                BatteryComponent::Case,
+            ] {
+                // Slider values need `From<u8>`, so drive it with i16 and narrow back
+                let threshold = self.config.ui.low_battery_threshold_for(component);
+                content = content.push(
+                    DirectionalRow::new()
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .push(
+                            Text::new(format!("{}: {}%", component, threshold))
+                                .style(ui_theme::TEXT)
+                                .width(Length::Fixed(120.0)),
+                        )
+                        .push(
+                            iced::widget::slider(0i16..=100, threshold as i16, move |value| {
+                                Message::UpdateUiSetting(UiSetting::ComponentLowBatteryThreshold(
+                                    component,
+                                    value as u8,
+                                ))
+                            })
+                            .step(5)
+                            .width(Length::Fill),
+                        ),
+                );
+            }
+        }
+
+        let stale_after = self.config.ui.stale_after_minutes;
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new(if stale_after == 0 {
+                        "Gray out: never".to_string()
+                    } else {
+                        format!("Gray out after: {} min", stale_after)
+                    })
+                    .style(ui_theme::TEXT)
+                    .width(Length::Fixed(120.0)),
+                )
+                .push(
+                    iced::widget::slider(0i16..=60, stale_after.min(60) as i16, |value| {
+                        Message::UpdateUiSetting(UiSetting::StaleAfterMinutes(value as u32))
+                    })
+                    .width(Length::Fill),
+                ),
+        );
+
+        content = content
+            .push(minimize_to_tray)
+            .push(lid_open_popup)
+            .push(open_near_tray)
+            .push(audio_switch)
+            .push(household_mode)
+            .push(exclude_from_capture)
+            .push(taskbar_badge)
+            .push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new("Headline level:")
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(120.0)),
+                    )
+                    .push(iced::widget::pick_list(
+                        &TrayMetric::ALL[..],
+                        Some(self.config.ui.tray_metric),
+                        |metric| Message::UpdateUiSetting(UiSetting::TrayMetric(metric)),
+                    ))
+                    .push(
+                        Text::new("Shown first in the tray and on the taskbar button")
+                            .style(ui_theme::SUBTEXT1)
+                            .size(ui_theme::scaled(12.0)),
+                    ),
+            )
+            .push(speech_enabled);
+
+        if speech.enabled {
+            let announce_low = Checkbox::new(
+                "Announce low battery",
+                speech.announce_low_battery,
+                |value| Message::UpdateUiSetting(UiSetting::SpeechAnnounceLowBattery(value)),
+            );
+
+            let voice_input = iced::widget::text_input(
+                "System default voice",
+                speech.voice.as_deref().unwrap_or(""),
+            )
+            .on_input(|value| Message::UpdateUiSetting(UiSetting::SpeechVoice(value)))
+            .width(Length::Fill);
+
+            // Slider values need `From<u8>`, so drive it with i16 and narrow back
+            let rate_slider = iced::widget::slider(-10i16..=10, speech.rate as i16, |value| {
+                Message::UpdateUiSetting(UiSetting::SpeechRate(value as i8))
+            })
+            .width(Length::Fill);
+
+            content = content
+                .push(announce_low)
+                .push(
+                    DirectionalRow::new()
+                        .spacing(10)
+                        .push(
+                            Text::new("Voice:")
+                                .style(ui_theme::TEXT)
+                                .width(Length::Fixed(120.0)),
+                        )
+                        .push(voice_input),
+                )
+                .push(
+                    DirectionalRow::new()
+                        .spacing(10)
+                        .push(
+                            Text::new(format!("Rate: {}", speech.rate))
+                                .style(ui_theme::TEXT)
+                                .width(Length::Fixed(120.0)),
+                        )
+                        .push(rate_slider),
+                )
+                .push(Text::new("Press Ctrl+B to hear battery status").style(ui_theme::SUBTEXT1));
+        }
+
+        let sound_alerts = &self.config.ui.sound_alerts;
+        content = content.push(Checkbox::new(
+            "Sound alerts",
+            sound_alerts.enabled,
+            |value| Message::UpdateUiSetting(UiSetting::SoundAlertsEnabled(value)),
+        ));
+        if sound_alerts.enabled {
+            for event in SoundAlertEvent::ALL {
+                content = content.push(
+                    DirectionalRow::new()
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .push(
+                            Checkbox::new(
+                                event.to_string(),
+                                sound_alerts.plays(event),
+                                move |value| {
+                                    Message::UpdateUiSetting(UiSetting::SoundAlert(event, value))
+                                },
+                            )
+                            .width(Length::Fixed(120.0)),
+                        )
+                        .push(
+                            iced::widget::text_input(
+                                "WAV file (empty for the system alert sound)",
+                                sound_alerts.sound(event).map_or("", String::as_str),
+                            )
+                            .on_input(move |value| {
+                                Message::UpdateUiSetting(UiSetting::SoundAlertFile(event, value))
+                            })
+                            .width(Length::Fill),
+                        ),
+                );
+            }
+            content = content.push(
+                Text::new("Sounds are not played during quiet hours")
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+            );
+        }
+
+        let theme_schedule = &self.config.ui.theme_schedule;
+        let schedule_hint = match theme_schedule.mode {
+            ThemeScheduleMode::Off => String::new(),
+            ThemeScheduleMode::FixedTimes => format!(
+                "Light from {:02}:00, dark from {:02}:00",
+                theme_schedule.light_hour, theme_schedule.dark_hour
+            ),
+            ThemeScheduleMode::SunriseSunset => {
+                match (theme_schedule.latitude, theme_schedule.longitude) {
+                    (Some(lat), Some(lon)) => format!("Location {:.2}, {:.2}", lat, lon),
+                    _ => "Set ui.theme_schedule.latitude/longitude; using fixed times until then"
+                        .to_string(),
+                }
+            }
+        };
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new("Theme schedule:")
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fixed(120.0)),
+                )
+                .push(iced::widget::pick_list(
+                    &ThemeScheduleMode::ALL[..],
+                    Some(theme_schedule.mode),
+                    |mode| Message::UpdateUiSetting(UiSetting::ThemeSchedule(mode)),
+                ))
+                .push(
+                    Text::new(schedule_hint)
+                        .style(ui_theme::SUBTEXT1)
+                        .size(ui_theme::scaled(12.0)),
+                ),
+        );
+
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new("Backdrop:")
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fixed(120.0)),
+                )
+                .push(iced::widget::pick_list(
+                    &WindowBackdrop::ALL[..],
+                    Some(self.config.ui.backdrop),
+                    |backdrop| Message::UpdateUiSetting(UiSetting::Backdrop(backdrop)),
+                ))
+                .push(
+                    Text::new("Mica and Acrylic need Windows 11")
+                        .style(ui_theme::SUBTEXT1)
+                        .size(ui_theme::scaled(12.0)),
+                ),
+        );
+
+        // The preview follows the picked style and theme before saving
+        let mut tray_icon_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                Text::new("Tray icon:")
+                    .style(ui_theme::TEXT)
+                    .width(Length::Fixed(120.0)),
+            )
+            .push(iced::widget::pick_list(
+                &TrayIconStyle::ALL[..],
+                Some(self.config.ui.tray_icon_style),
+                |style| Message::UpdateUiSetting(UiSetting::TrayIconStyle(style)),
+            ));
+        if self.config.ui.tray_icon_style == TrayIconStyle::Custom {
+            tray_icon_row = tray_icon_row.push(
+                iced::widget::button("Choose Folder…")
+                    .on_press(Message::ChooseFile(FileAction::ChooseTrayIconFolder))
+                    .style(iced::theme::Button::Secondary),
+            );
+        }
+        match self.tray_preview.as_ref().map(|preview| &preview.icons) {
+            Some(Ok(icons)) => {
+                for icon in icons {
+                    tray_icon_row = tray_icon_row.push(
+                        iced::widget::image(icon.clone())
+                            .width(Length::Fixed(32.0))
+                            .height(Length::Fixed(32.0)),
+                    );
+                }
+            }
+            Some(Err(problem)) => {
+                tray_icon_row = tray_icon_row.push(
+                    Text::new(problem.clone())
+                        .style(ui_theme::YELLOW)
+                        .size(ui_theme::scaled(12.0)),
+                );
+            }
+            None => {}
+        }
+        content = content.push(tray_icon_row);
+
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(Checkbox::new(
+                    "Use software rendering",
+                    self.config.ui.software_rendering,
+                    |value| Message::UpdateUiSetting(UiSetting::SoftwareRendering(value)),
+                ))
+                .push(
+                    Text::new("For VMs and remote desktop; applies after a restart")
+                        .style(ui_theme::SUBTEXT1)
+                        .size(ui_theme::scaled(12.0)),
+                ),
+        );
+
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(Checkbox::new(
+                    "Run in the tray only",
+                    self.config.ui.tray_only,
+                    |value| Message::UpdateUiSetting(UiSetting::TrayOnly(value)),
+                ))
+                .push(
+                    Text::new(
+                        "No window from the next start, just the tray and notifications. Open Window in the tray menu brings it back",
+                    )
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+                ),
+        );
+
+        // Battery colors for color vision deficiencies, with optional shape marks
+        content = content
+            .push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new("Battery colors:")
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(120.0)),
+                    )
+                    .push(iced::widget::pick_list(
+                        &BatteryPalette::ALL[..],
+                        Some(self.config.ui.battery_palette),
+                        |palette| Message::UpdateUiSetting(UiSetting::BatteryPalette(palette)),
+                    )),
+            )
+            .push(Checkbox::new(
+                "Mark battery states with shapes as well as color",
+                self.config.ui.shape_encoding,
+                |value| Message::UpdateUiSetting(UiSetting::ShapeEncoding(value)),
+            ));
+
+        // Right-to-left locales mirror the layout right away
+        let locale_options: Vec<LocaleOption> = std::iter::once(LocaleOption::system())
+            .chain(
+                direction::SUPPORTED_LOCALES
+                    .iter()
+                    .map(|(tag, label)| LocaleOption::new(tag, label)),
+            )
+            .collect();
+        let selected_locale = match &self.config.ui.locale {
+            Some(tag) => locale_options
+                .iter()
+                .find(|option| option.tag.as_deref() == Some(tag.as_str()))
+                .cloned()
+                .unwrap_or_else(|| LocaleOption::new(tag, tag)),
+            None => LocaleOption::system(),
+        };
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new("Language:")
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fixed(120.0)),
+                )
+                .push(iced::widget::pick_list(
+                    locale_options,
+                    Some(selected_locale),
+                    |option| Message::UpdateUiSetting(UiSetting::Locale(option.tag)),
+                )),
+        );
+
+        // The bundled font comes first; picking it clears the custom family
+        let font_options: Vec<String> = std::iter::once(ui_theme::FONT_FAMILY.to_string())
+            .chain(crate::ui::fonts::installed_font_families().iter().cloned())
+            .collect();
+        let selected_font = self
+            .config
+            .ui
+            .font_family
+            .clone()
+            .unwrap_or_else(|| ui_theme::FONT_FAMILY.to_string());
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new("Font:")
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fixed(120.0)),
+                )
+                .push(
+                    iced::widget::pick_list(font_options, Some(selected_font), |family| {
+                        Message::UpdateUiSetting(UiSetting::FontFamily(family))
+                    })
+                    .width(Length::Fill),
+                ),
+        );
+
+        // Slider values need `From<u8>`, so drive it with whole percentages
+        let font_percent = (self.config.ui.font_scale * 100.0).round() as i16;
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .align_items(iced::Alignment::Center)
+                .push(
+                    Text::new(format!("Text size: {}%", font_percent))
+                        .style(ui_theme::TEXT)
+                        .width(Length::Fixed(120.0)),
+                )
+                .push(
+                    iced::widget::slider(
+                        (crate::ui::fonts::MIN_FONT_SCALE * 100.0) as i16
+                            ..=(crate::ui::fonts::MAX_FONT_SCALE * 100.0) as i16,
+                        font_percent,
+                        |value| {
+                            Message::UpdateUiSetting(UiSetting::FontScale(value as f32 / 100.0))
+                        },
+                    )
+                    .step(5)
+                    .width(Length::Fill),
+                ),
+        );
+        content = content.push(
+            Text::new("Font changes apply the next time RustPods starts")
+                .style(ui_theme::SUBTEXT1)
+                .size(ui_theme::scaled(12.0)),
+        );
+
+        let quiet_hours = &self.config.ui.quiet_hours;
+        let quiet_hours_enabled = Checkbox::new(
+            format!(
+                "Quiet hours ({} - {})",
+                TimeOfDay(quiet_hours.start_minutes()),
+                TimeOfDay(quiet_hours.end_minutes())
+            ),
+            quiet_hours.enabled,
+            |value| Message::UpdateUiSetting(UiSetting::QuietHoursEnabled(value)),
+        );
+        content = content.push(quiet_hours_enabled);
+
+        if quiet_hours.enabled {
+            let times: Vec<TimeOfDay> =
+                (0..48).map(|half_hour| TimeOfDay(half_hour * 30)).collect();
+            content = content.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new("From:")
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(120.0)),
+                    )
+                    .push(iced::widget::pick_list(
+                        times.clone(),
+                        Some(TimeOfDay(quiet_hours.start_minutes())),
+                        |time| Message::UpdateUiSetting(UiSetting::QuietHoursStart(time.0)),
+                    ))
+                    .push(Text::new("to").style(ui_theme::TEXT))
+                    .push(iced::widget::pick_list(
+                        times,
+                        Some(TimeOfDay(quiet_hours.end_minutes())),
+                        |time| Message::UpdateUiSetting(UiSetting::QuietHoursEnd(time.0)),
+                    )),
+            );
+
+            let mut days = DirectionalRow::new().spacing(5).push(
+                Text::new("Starting on:")
+                    .style(ui_theme::TEXT)
+                    .width(Length::Fixed(120.0)),
+            );
+            for day in QUIET_HOURS_DAYS {
+                let included = quiet_hours.days.contains(&day);
+                days = days.push(
+                    iced::widget::button(Text::new(day.to_string()).size(ui_theme::scaled(12.0)))
+                        .on_press(Message::UpdateUiSetting(UiSetting::QuietHoursDay(
+                            day, !included,
+                        )))
+                        .style(if included {
+                            iced::theme::Button::Primary
+                        } else {
+                            iced::theme::Button::Secondary
+                        }),
+                );
+            }
+
+            content = content
+                .push(days)
+                .push(Checkbox::new(
+                    "Also stay quiet while Focus Assist is on",
+                    quiet_hours.follow_focus_assist,
+                    |value| Message::UpdateUiSetting(UiSetting::QuietHoursFollowFocusAssist(value)),
+                ))
+                .push(Checkbox::new(
+                    "Show a summary of held notifications afterwards",
+                    quiet_hours.summarize_held,
+                    |value| Message::UpdateUiSetting(UiSetting::QuietHoursSummarizeHeld(value)),
+                ))
+                .push(
+                    Text::new("Critical battery warnings are always shown")
+                        .style(ui_theme::SUBTEXT1)
+                        .size(ui_theme::scaled(12.0)),
+                );
+        }
+
+        Container::new(content.width(Length::Fill))
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Action rules section: list of rules and an editor for new ones
+    pub fn action_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
+        let title = Text::new("Actions")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
+
+        let mut rules = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(6);
+        for (index, rule) in self.config.actions.rules.iter().enumerate() {
+            rules = rules.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(Checkbox::new("", rule.enabled, move |value| {
+                        Message::UpdateActionSetting(ActionSetting::RuleEnabled(index, value))
+                    }))
+                    .push(
+                        Column::new()
+                            .align_items(direction::start_alignment())
+                            .push(Text::new(rule.name.as_str()).style(ui_theme::TEXT))
+                            .push(
+                                Text::new(format!("When {}: {}", rule.trigger, rule.action))
+                                    .style(ui_theme::SUBTEXT1)
+                                    .size(ui_theme::scaled(12.0)),
+                            )
+                            .width(Length::Fill),
+                    )
+                    .push(
+                        iced::widget::button("Remove")
+                            .on_press(Message::UpdateActionSetting(ActionSetting::RemoveRule(
+                                index,
+                            )))
+                            .style(iced::theme::Button::Destructive),
+                    ),
+            );
+        }
+        if self.config.actions.rules.is_empty() {
+            rules = rules.push(Text::new("No rules yet").style(ui_theme::SUBTEXT1));
+        }
+
+        let draft = &self.rule_draft;
+        let edit = |change: fn(&mut RuleDraft, String)| {
+            let draft = draft.clone();
+            move |value: String| {
+                let mut draft = draft.clone();
+                change(&mut draft, value);
+                Message::RuleDraftChanged(draft)
+            }
+        };
+
+        let mut trigger_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(Text::new("When").style(ui_theme::TEXT))
+            .push(iced::widget::pick_list(
+                &TriggerKind::ALL[..],
+                Some(draft.trigger),
+                {
+                    let draft = draft.clone();
+                    move |trigger| {
+                        Message::RuleDraftChanged(RuleDraft {
+                            trigger,
+                            ..draft.clone()
+                        })
+                    }
+                },
+            ));
+        if matches!(
+            draft.trigger,
+            TriggerKind::BatteryBelow | TriggerKind::ChargingStarted | TriggerKind::FullyCharged
+        ) {
+            trigger_row = trigger_row.push(iced::widget::pick_list(
+                &BatteryComponent::ALL[..],
+                Some(draft.component),
+                {
+                    let draft = draft.clone();
+                    move |component| {
+                        Message::RuleDraftChanged(RuleDraft {
+                            component,
+                            ..draft.clone()
+                        })
+                    }
+                },
+            ));
+        }
+        if draft.trigger == TriggerKind::BatteryBelow {
+            trigger_row = trigger_row
+                .push(
+                    iced::widget::text_input("20", &draft.threshold)
+                        .on_input(edit(|draft, value| draft.threshold = value))
+                        .width(Length::Fixed(50.0)),
+                )
+                .push(Text::new("%").style(ui_theme::TEXT));
+        }
+
+        let action_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(Text::new("Then").style(ui_theme::TEXT))
+            .push(iced::widget::pick_list(
+                &ActionKind::ALL[..],
+                Some(draft.action),
+                {
+                    let draft = draft.clone();
+                    move |action| {
+                        Message::RuleDraftChanged(RuleDraft {
+                            action,
+                            ..draft.clone()
+                        })
+                    }
+                },
+            ))
+            .push(
+                iced::widget::text_input(draft.action.placeholder(), &draft.argument)
+                    .on_input(edit(|draft, value| draft.argument = value))
+                    .width(Length::Fill),
+            );
+
+        let name_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                iced::widget::text_input("Rule name (optional)", &draft.name)
+                    .on_input(edit(|draft, value| draft.name = value))
+                    .width(Length::Fill),
+            )
+            .push(
+                iced::widget::button("Add Rule")
+                    .on_press_maybe(
+                        draft
+                            .to_rule()
+                            .map(|rule| Message::UpdateActionSetting(ActionSetting::AddRule(rule))),
+                    )
+                    .style(iced::theme::Button::Secondary),
+            );
+
+        Container::new(
+            Column::new()
+                .align_items(direction::start_alignment())
+                .spacing(15)
+                .push(title)
+                .push(
+                    Text::new(
+                        "Notification text and sound paths may use {device}, {component} and {level}; \
+                         commands get them as RUSTPODS_DEVICE, RUSTPODS_COMPONENT and RUSTPODS_LEVEL",
+                    )
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+                )
+                .push(rules)
+                .push(trigger_row)
+                .push(action_row)
+                .push(name_row)
+                .width(Length::Fill),
+        )
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Keyboard shortcut editor
+    pub fn shortcut_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
+        let title = Text::new("Keyboard Shortcuts")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
+
+        let bindings = &self.config.ui.shortcuts;
+        let mut content = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(10)
+            .push(title);
+
+        for action in ShortcutAction::ALL {
+            let recording = self.recording_shortcut == Some(action);
+            let shortcut = if recording {
+                "Press keys… (Esc cancels)".to_string()
+            } else {
+                keyboard_shortcuts::binding(bindings, action).to_string()
+            };
+            let change: Element<'_, Message, Renderer<ui_theme::Theme>> = if recording {
+                iced::widget::button("Cancel")
+                    .on_press(Message::CancelShortcutRecording)
+                    .style(iced::theme::Button::Secondary)
+                    .into()
+            } else {
+                iced::widget::button("Change")
+                    .on_press(Message::RecordShortcut(action))
+                    .style(iced::theme::Button::Secondary)
+                    .into()
+            };
+
+            content = content.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(action.to_string())
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(160.0)),
+                    )
+                    .push(
+                        Text::new(shortcut)
+                            .style(if recording {
+                                ui_theme::LAVENDER
+                            } else {
+                                ui_theme::SUBTEXT1
+                            })
+                            .width(Length::Fill),
+                    )
+                    .push(change),
+            );
+        }
+
+        if let Some(error) = &self.shortcut_error {
+            content = content.push(
+                Text::new(error.as_str())
+                    .style(ui_theme::RED)
+                    .size(ui_theme::scaled(12.0)),
+            );
+        }
+
+        content = content.push(
+            iced::widget::button("Reset to Defaults")
+                .on_press_maybe((!bindings.is_empty()).then(|| {
+                    Message::ShowDialog(Box::new(Dialog::confirm(
+                        "Reset shortcuts?",
+                        "Every keyboard shortcut goes back to its default key.",
+                        "Reset",
+                        Message::UpdateUiSetting(UiSetting::ResetShortcuts),
+                    )))
+                }))
+                .style(iced::theme::Button::Secondary),
+        );
+
+        Container::new(content.width(Length::Fill))
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// System settings section
+    pub fn system_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
+        let title = Text::new("System")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
+
+        let startup_option = Checkbox::new(
+            "Start on system startup",
+            self.config.system.launch_at_startup,
+            |value| Message::UpdateSystemSetting(SystemSetting::StartOnBoot(value)),
+        );
+
+        let telemetry_option = Checkbox::new(
+            "Collect anonymous usage statistics (stored locally, never sent)",
+            self.config.system.enable_telemetry,
+            |value| Message::UpdateSystemSetting(SystemSetting::EnableTelemetry(value)),
+        );
+
+        let privacy_option = Checkbox::new(
+            "Hide device addresses in logs, diagnostics and exports",
+            self.config.system.privacy_mode,
+            |value| Message::UpdateSystemSetting(SystemSetting::PrivacyMode(value)),
+        );
+
+        let known_issues_option = Checkbox::new(
+            "Check online weekly for new known issues and workarounds",
+            self.config.system.refresh_known_issues,
+            |value| Message::UpdateSystemSetting(SystemSetting::RefreshKnownIssues(value)),
+        );
+
+        let backup = &self.config.system.backup;
+        let mut backup_option = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                Text::new("Automatic backups:")
+                    .style(ui_theme::TEXT)
+                    .width(Length::Fixed(120.0)),
+            )
+            .push(iced::widget::pick_list(
+                &BackupFrequency::ALL[..],
+                Some(backup.frequency),
+                |frequency| Message::UpdateSystemSetting(SystemSetting::BackupFrequency(frequency)),
+            ));
+        if backup.frequency != BackupFrequency::Off {
+            let folder = backup
+                .folder
+                .clone()
+                .unwrap_or_else(|| crate::backup::BackupRoots::default().default_backup_folder());
+            backup_option = backup_option
+                .push(
+                    iced::widget::button("Change Folder…")
+                        .on_press(Message::ChooseFile(FileAction::ChooseBackupFolder))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    Text::new(format!(
+                        "Keeps the newest {} in {}",
+                        backup.keep,
+                        folder.display()
+                    ))
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+                );
+        }
+
+        let telemetry_data = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                iced::widget::button("View Collected Data")
+                    .on_press(Message::ShowTelemetryData)
+                    .style(iced::theme::Button::Secondary),
+            )
+            .push(
+                Text::new("Scan counts, error rates and feature usage only")
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(12.0)),
+            );
+
+        // Restarting from safe mode goes back to a normal run
+        let restart = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                iced::widget::button("Restart RustPods")
+                    .on_press(Message::Restart(false))
+                    .style(iced::theme::Button::Secondary),
+            );
+        let restart = if crate::safe_mode::is_enabled() {
+            restart.push(
+                Text::new("Running in safe mode: battery history, rules and custom themes are off")
+                    .style(ui_theme::YELLOW)
+                    .size(ui_theme::scaled(12.0)),
+            )
+        } else {
+            restart.push(
+                iced::widget::button("Restart in Safe Mode")
+                    .on_press(Message::Restart(true))
+                    .style(iced::theme::Button::Secondary),
+            )
+        };
+
+        Container::new(
+            Column::new()
+                .align_items(direction::start_alignment())
+                .spacing(15)
+                .push(title)
+                .push(startup_option)
+                .push(telemetry_option)
+                .push(telemetry_data)
+                .push(privacy_option)
+                .push(known_issues_option)
+                .push(backup_option)
+                .push(restart)
+                .width(Length::Fill),
+        )
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Advanced section: recent settings changes from the audit log and battery data
+    /// snapshots to roll back to
+    pub fn advanced_settings(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
+        let title = Text::new("Advanced")
+            .size(ui_theme::scaled(20.0))
+            .style(ui_theme::TEXT);
+
+        let mut content = Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(8)
+            .push(title)
+            .push(
+                Text::new("Recent settings changes")
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(14.0)),
+            );
+
+        if self.audit_entries.is_empty() {
+            content = content.push(
+                Text::new("No settings changes recorded yet")
+                    .style(ui_theme::OVERLAY1)
+                    .size(ui_theme::scaled(12.0)),
+            );
+        }
+
+        for entry in &self.audit_entries {
+            content = content.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .push(
+                        Text::new(entry.timestamp.format("%Y-%m-%d %H:%M").to_string())
+                            .style(ui_theme::OVERLAY1)
+                            .size(ui_theme::scaled(12.0)),
+                    )
+                    .push(
+                        Text::new(entry.source.to_string())
+                            .style(ui_theme::LAVENDER)
+                            .size(ui_theme::scaled(12.0)),
+                    )
+                    .push(
+                        Text::new(format!(
+                            "{}: {} → {}",
+                            entry.field, entry.old_value, entry.new_value
+                        ))
+                        .style(ui_theme::TEXT)
+                        .size(ui_theme::scaled(12.0)),
+                    ),
+            );
+        }
+
+        let restore_snapshot: Element<'_, Message, Renderer<ui_theme::Theme>> =
+            if self.profile_snapshots.is_empty() {
+                Text::new("No snapshots saved yet")
+                    .style(ui_theme::OVERLAY1)
+                    .size(ui_theme::scaled(12.0))
+                    .into()
+            } else {
+                iced::widget::pick_list(
+                    self.profile_snapshots.clone(),
+                    None::<ProfileSnapshot>,
+                    Message::ConfirmRestoreProfileSnapshot,
+                )
+                .placeholder("Choose a snapshot…")
+                .into()
+            };
+        content = content
+            .push(
+                Text::new("Restore previous profile state")
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(14.0)),
+            )
+            .push(restore_snapshot);
+
+        // Changes apply straight away; Default follows the global log level
+        content = content.push(
+            Text::new("Log levels")
+                .style(ui_theme::SUBTEXT1)
+                .size(ui_theme::scaled(14.0)),
+        );
+        for category in LogCategory::ALL {
+            let level = self
+                .config
+                .system
+                .category_log_levels
+                .get(&category)
+                .cloned();
+            content = content.push(
+                DirectionalRow::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Text::new(format!("{}:", category))
+                            .style(ui_theme::TEXT)
+                            .width(Length::Fixed(120.0)),
+                    )
+                    .push(iced::widget::pick_list(
+                        &CategoryLevelChoice::ALL[..],
+                        Some(CategoryLevelChoice(level)),
+                        move |choice| {
+                            Message::UpdateSystemSetting(SystemSetting::CategoryLogLevel(
+                                category, choice.0,
+                            ))
+                        },
+                    )),
+            );
+        }
+
+        content = content.push(
+            iced::widget::button(
+                Text::new(if self.scanner_status_expanded {
+                    "▾ Scanner status"
+                } else {
+                    "▸ Scanner status"
+                })
+                .size(ui_theme::scaled(14.0)),
+            )
+            .on_press(Message::ToggleScannerStatus)
+            .style(iced::theme::Button::Text),
+        );
+        if self.scanner_status_expanded {
+            for line in self.scan_statistics.summary_lines() {
+                content = content.push(
+                    Text::new(line)
+                        .style(ui_theme::SUBTEXT1)
+                        .size(ui_theme::scaled(12.0)),
+                );
+            }
+            if let Some(address) = &self.focused_address {
+                content = content.push(
+                    DirectionalRow::new()
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center)
+                        .push(
+                            Text::new(format!("Scanning only {}", format_address(address)))
+                                .style(ui_theme::LAVENDER)
+                                .size(ui_theme::scaled(12.0))
+                                .width(Length::Fill),
+                        )
+                        .push(
+                            iced::widget::button("Scan All Devices")
+                                .on_press(Message::FocusScan(None))
+                                .style(iced::theme::Button::Secondary),
+                        ),
+                );
+                if self.focus_frames.is_empty() {
+                    content = content.push(
+                        Text::new("Waiting for a frame from this device")
+                            .style(ui_theme::OVERLAY1)
+                            .size(ui_theme::scaled(12.0)),
+                    );
+                }
+                for frame in self.focus_frames.iter().rev() {
+                    content = content.push(
+                        Text::new(frame.to_string())
+                            .font(iced::Font::MONOSPACE)
+                            .style(ui_theme::TEXT)
+                            .size(ui_theme::scaled(11.0)),
+                    );
+                }
+            }
+        }
+
+        content = content.push(Checkbox::new(
+            "Developer mode",
+            self.config.system.developer_mode,
+            |value| Message::UpdateSystemSetting(SystemSetting::DeveloperMode(value)),
+        ));
+        if self.config.system.developer_mode {
+            content = content.push(self.synthetic_frames_panel());
+        }
+
+        content = content.push(
+            DirectionalRow::new()
+                .spacing(10)
+                .push(
+                    iced::widget::button("Export Settings…")
+                        .on_press(Message::ChooseFile(FileAction::ExportSettings))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    iced::widget::button("Import Settings…")
+                        .on_press(Message::ChooseFile(FileAction::ImportSettings))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    iced::widget::button("Back Up All Data…")
+                        .on_press(Message::ChooseFile(FileAction::BackUpAllData))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    iced::widget::button("Restore from Backup…")
+                        .on_press(Message::ChooseFile(FileAction::RestoreBackup))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    iced::widget::button("Save Diagnostics…")
+                        .on_press(Message::ChooseFile(FileAction::SaveDiagnostics))
+                        .style(iced::theme::Button::Secondary),
+                )
+                .push(
+                    iced::widget::button("Report a Problem…")
+                        .on_press(Message::ReportProblem)
+                        .style(iced::theme::Button::Secondary),
+                ),
+        );
+
+        content = content.push(
+            iced::widget::button("Reset All Settings")
+                .on_press(Message::ShowDialog(Box::new(
+                    Dialog::choose(
+                        "Reset all settings?",
+                        "Every setting goes back to its default. You can undo this for a few seconds afterwards.",
+                        vec!["Keep Paired AirPods".to_string(), "Reset Everything".to_string()],
+                        |index| Message::ResetSettings {
+                            keep_pairing: index == 0,
+                        },
+                    )
+                    .destructive(),
+                )))
+                .style(iced::theme::Button::Destructive),
+        );
+
+        Container::new(content.width(Length::Fill))
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// Developer panel for injecting synthetic advertisement frames
+    fn synthetic_frames_panel(&self) -> Element<'_, Message, Renderer<ui_theme::Theme>> {
+        let draft = &self.synthetic_draft;
+        let edit = |change: fn(&mut SyntheticFrameDraft, String)| {
+            let draft = draft.clone();
+            move |value: String| {
+                let mut draft = draft.clone();
+                change(&mut draft, value);
+                Message::SyntheticDraftChanged(draft)
+            }
+        };
+
+        let input_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                iced::widget::pick_list(&SyntheticPreset::ALL[..], None, {
+                    let draft = draft.clone();
+                    move |preset: SyntheticPreset| {
+                        Message::SyntheticDraftChanged(SyntheticFrameDraft {
+                            payload: preset.payload().to_string(),
+                            ..draft.clone()
+                        })
+                    }
+                })
+                .placeholder("Preset"),
+            )
+            .push(
+                iced::widget::text_input(DEFAULT_SYNTHETIC_ADDRESS, &draft.address)
+                    .on_input(edit(|draft, value| draft.address = value))
+                    .width(Length::Fixed(160.0)),
+            )
+            .push(
+                iced::widget::text_input("Apple manufacturer data (hex)", &draft.payload)
+                    .on_input(edit(|draft, value| draft.payload = value))
+                    .font(iced::Font::MONOSPACE)
+                    .width(Length::Fill),
+            )
+            .push(
+                iced::widget::button("Inject")
+                    .on_press_maybe(
+                        (!draft.payload.trim().is_empty())
+                            .then(|| Message::InjectSyntheticFrame(draft.clone())),
+                    )
+                    .style(iced::theme::Button::Secondary),
+            );
+
+        let mut status_row = DirectionalRow::new()
+            .spacing(10)
+            .align_items(iced::Alignment::Center)
+            .push(
+                Text::new(match self.synthetic_frames {
+                    0 => "No synthetic frames injected".to_string(),
+                    1 => "1 synthetic frame added to every scan".to_string(),
+                    count => format!("{} synthetic frames added to every scan", count),
+                })
+                .style(ui_theme::SUBTEXT1)
+                .size(ui_theme::scaled(12.0))
+                .width(Length::Fill),
+            );
+        if self.synthetic_frames > 0 {
+            status_row = status_row.push(
+                iced::widget::button("Clear")
+                    .on_press(Message::ClearSyntheticFrames)
+                    .style(iced::theme::Button::Secondary),
+            );
+        }
+
+        Column::new()
+            .align_items(direction::start_alignment())
+            .spacing(8)
+            .push(
+                Text::new("Synthetic frames")
+                    .style(ui_theme::SUBTEXT1)
+                    .size(ui_theme::scaled(14.0)),
+            )
+            .push(input_row)
+            .push(status_row)
+            .into()
+    }
+}
+
+/// Bluetooth settings enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum BluetoothSetting {
+    /// Custom device name
+    DeviceName(String),
+    /// Ignore every device except the paired one
+    OnlyPairedDevice(bool),
+    /// Only process devices whose address is listed
+    AllowListedOnly(bool),
+    /// Add an allow list entry
+    AllowDevice(DeviceFilterEntry),
+    /// Add a deny list entry
+    DenyDevice(DeviceFilterEntry),
+    /// Remove the allow list entry at an index
+    RemoveAllowedDevice(usize),
+    /// Remove the deny list entry at an index
+    RemoveDeniedDevice(usize),
+}
+
+/// Change to a known device
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEdit {
+    /// Nickname; empty clears it
+    Nickname(String),
+    /// Icon shown beside the name
+    Icon(DeviceIcon),
+    /// Color the name is drawn in
+    Color(DeviceColor),
+    /// Follow this device instead of the current primary one
+    MakePrimary,
+    /// Forget the device
+    Forget,
+}
+
+/// UI settings enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum UiSetting {
+    /// Theme
+    Theme(ui_theme::Theme),
+    /// Show notifications
+    ShowNotifications(bool),
+    /// Start minimized
+    StartMinimized(bool),
+    /// Show percentage in tray
+    ShowPercentageInTray(bool),
+    /// Show low battery warning
+    ShowLowBatteryWarning(bool),
+    /// Low battery threshold
+    LowBatteryThreshold(u8),
+    /// Low battery threshold for one component
+    ComponentLowBatteryThreshold(BatteryComponent, u8),
+    /// Minutes without advertisements before readings are grayed out (0 for never)
+    StaleAfterMinutes(u32),
+    /// Minimize to tray when close button is pressed
+    MinimizeToTrayOnClose(bool),
+    /// Show a transient battery popup when the case lid opens
+    ShowLidOpenPopup(bool),
+    /// Open the window in the corner by the system tray
+    OpenNearTray(bool),
+    /// Offer a "Switch audio to AirPods" button
+    ShowAudioSwitchButton(bool),
+    /// List every nearby AirPods set with its own battery row
+    HouseholdMode(bool),
+    /// Exclude RustPods windows from screen capture
+    ExcludeFromCapture(bool),
+    /// Show the battery level on the taskbar button
+    TaskbarBadge(bool),
+    /// Level the tray tooltip and taskbar button lead with
+    TrayMetric(TrayMetric),
+    /// Look of the tray icon
+    TrayIconStyle(TrayIconStyle),
+    /// Folder with the custom tray icon set (picks the custom style)
+    TrayIconFolder(std::path::PathBuf),
+    /// Draw the window without the GPU, from the next start
+    SoftwareRendering(bool),
+    /// Start without the main window, from the next start
+    TrayOnly(bool),
+    /// Show the charge left in mAh in the details and exported data
+    ShowMahEstimates(bool),
+    /// Day/night theme schedule mode
+    ThemeSchedule(ThemeScheduleMode),
+    /// Window backdrop material
+    Backdrop(WindowBackdrop),
+    /// Enable spoken battery announcements
+    SpeechEnabled(bool),
+    /// Announce low battery levels aloud
+    SpeechAnnounceLowBattery(bool),
+    /// Voice used for announcements (empty for system default)
+    SpeechVoice(String),
+    /// Speaking rate (-10 to 10)
+    SpeechRate(i8),
+    /// Enable sound alerts
+    SoundAlertsEnabled(bool),
+    /// Play a sound for one event
+    SoundAlert(SoundAlertEvent, bool),
+    /// WAV file for one event (empty for the system alert sound)
+    SoundAlertFile(SoundAlertEvent, String),
+    /// Suppress automatic audible alerts and hold notifications during quiet hours
+    QuietHoursEnabled(bool),
+    /// Quiet hours start, in minutes past midnight
+    QuietHoursStart(u16),
+    /// Quiet hours end, in minutes past midnight
+    QuietHoursEnd(u16),
+    /// Include or exclude a day quiet hours start on
+    QuietHoursDay(chrono::Weekday, bool),
+    /// Stay quiet while Focus Assist is on
+    QuietHoursFollowFocusAssist(bool),
+    /// Summarize held notifications when quiet hours end
+    QuietHoursSummarizeHeld(bool),
+    /// Interface font family (the bundled font's name selects the default)
+    FontFamily(String),
+    /// Text size multiplier
+    FontScale(f32),
+    /// Locale deciding layout direction (`None` follows the system)
+    Locale(Option<String>),
+    /// Colors for battery levels and charging
+    BatteryPalette(BatteryPalette),
+    /// Mark battery states with shapes as well as color
+    ShapeEncoding(bool),
+    /// Bind an action to a shortcut (text such as `Ctrl+R`)
+    Shortcut(ShortcutAction, String),
+    /// Restore every shortcut to its default
+    ResetShortcuts,
+}
+
+/// Entry in the language picker
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LocaleOption {
+    tag: Option<String>,
+    label: String,
+}
+
+impl LocaleOption {
+    fn new(tag: &str, label: &str) -> Self {
+        Self {
+            tag: Some(tag.to_string()),
+            label: format!("{} ({})", label, tag),
+        }
+    }
+
+    fn system() -> Self {
+        Self {
+            tag: None,
+            label: "System default".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for LocaleOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+/// System settings enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum SystemSetting {
+    /// Start on boot
+    StartOnBoot(bool),
+    /// Collect anonymous usage statistics locally
+    EnableTelemetry(bool),
+    /// Hash device addresses in logs, diagnostics and exports
+    PrivacyMode(bool),
+    /// Download the known issues index weekly
+    RefreshKnownIssues(bool),
+    /// Show developer tools
+    DeveloperMode(bool),
+    /// How often all data is backed up
+    BackupFrequency(BackupFrequency),
+    /// Folder scheduled backups are written to
+    BackupFolder(std::path::PathBuf),
+    /// Level for one log category; `None` follows the global level
+    CategoryLogLevel(LogCategory, Option<LogLevel>),
+}
+
+/// Level picked for a log category in the Advanced section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryLevelChoice(pub Option<LogLevel>);
+
+impl CategoryLevelChoice {
+    /// All choices, in the order shown in settings
+    pub const ALL: [CategoryLevelChoice; 6] = [
+        CategoryLevelChoice(None),
+        CategoryLevelChoice(Some(LogLevel::Error)),
+        CategoryLevelChoice(Some(LogLevel::Warn)),
+        CategoryLevelChoice(Some(LogLevel::Info)),
+        CategoryLevelChoice(Some(LogLevel::Debug)),
+        CategoryLevelChoice(Some(LogLevel::Trace)),
+    ];
+}
+
+impl std::fmt::Display for CategoryLevelChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(level) => write!(f, "{}", level),
+            None => write!(f, "Default"),
+        }
+    }
+}
+
+/// Action rule settings enum
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionSetting {
+    /// Add a rule
+    AddRule(ActionRule),
+    /// Enable or disable the rule at an index
+    RuleEnabled(usize, bool),
+    /// Remove the rule at an index
+    RemoveRule(usize),
+}
+
+/// Event picked in the rule editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TriggerKind {
+    /// Battery at or below a level
+    #[default]
+    BatteryBelow,
+    /// Charging started
+    ChargingStarted,
+    /// AirPod removed from an ear
+    BudsRemoved,
+    /// AirPods back in range
+    DeviceReconnected,
+    /// AirPods connected to this PC
+    DeviceConnected,
+    /// AirPods disconnected from this PC
+    DeviceDisconnected,
+    /// A component reached 100%
+    FullyCharged,
+}
+
+impl TriggerKind {
+    /// All events, in the order shown in settings
+    pub const ALL: [TriggerKind; 7] = [
+        TriggerKind::BatteryBelow,
+        TriggerKind::ChargingStarted,
+        TriggerKind::BudsRemoved,
+        TriggerKind::DeviceReconnected,
+        TriggerKind::DeviceConnected,
+        TriggerKind::DeviceDisconnected,
+        TriggerKind::FullyCharged,
+    ];
+}
+
+impl std::fmt::Display for TriggerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TriggerKind::BatteryBelow => write!(f, "Battery below"),
+            TriggerKind::ChargingStarted => write!(f, "Charging started"),
+            TriggerKind::BudsRemoved => write!(f, "AirPod removed"),
+            TriggerKind::DeviceReconnected => write!(f, "Reconnected"),
+            TriggerKind::DeviceConnected => write!(f, "Connected to PC"),
+            TriggerKind::DeviceDisconnected => write!(f, "Disconnected from PC"),
+            TriggerKind::FullyCharged => write!(f, "Fully charged"),
+        }
+    }
+}
+
+/// Action picked in the rule editor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActionKind {
+    /// Show a notification
+    #[default]
+    Notify,
+    /// Play a sound
+    PlaySound,
+    /// Run a shell command
+    RunCommand,
+}
+
+impl ActionKind {
+    /// All actions, in the order shown in settings
+    pub const ALL: [ActionKind; 3] = [
+        ActionKind::Notify,
+        ActionKind::PlaySound,
+        ActionKind::RunCommand,
+    ];
+
+    /// Placeholder for the argument field
+    fn placeholder(self) -> &'static str {
+        match self {
+            ActionKind::Notify => "Message, e.g. {component} at {level}%",
+            ActionKind::PlaySound => "WAV file (empty for the system alert sound)",
+            ActionKind::RunCommand => "Command line",
+        }
+    }
+}
+
+impl std::fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionKind::Notify => write!(f, "Show notification"),
+            ActionKind::PlaySound => write!(f, "Play sound"),
+            ActionKind::RunCommand => write!(f, "Run command"),
+        }
+    }
+}
+
+/// Rule being composed in the settings editor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleDraft {
+    /// Rule name (derived from the trigger when empty)
+    pub name: String,
+    /// Event
+    pub trigger: TriggerKind,
+    /// Component for battery and charging events
+    pub component: BatteryComponent,
+    /// Level for battery events, as typed
+    pub threshold: String,
+    /// Action
+    pub action: ActionKind,
+    /// Notification text, sound path or command, depending on the action
+    pub argument: String,
+}
+
+impl Default for RuleDraft {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            trigger: TriggerKind::default(),
+            component: BatteryComponent::default(),
+            threshold: "20".to_string(),
+            action: ActionKind::default(),
+            argument: String::new(),
+        }
+    }
+}
+
+impl RuleDraft {
+    /// The rule described by the draft, if it is complete
+    pub fn to_rule(&self) -> Option<ActionRule> {
+        let trigger = match self.trigger {
+            TriggerKind::BatteryBelow => RuleTrigger::BatteryBelow {
+                component: self.component,
+                threshold: self
+                    .threshold
+                    .trim()
+                    .parse()
+                    .ok()
+                    .filter(|threshold| *threshold <= 100)?,
+            },
+            TriggerKind::ChargingStarted => RuleTrigger::ChargingStarted {
+                component: self.component,
+            },
+            TriggerKind::BudsRemoved => RuleTrigger::BudsRemoved,
+            TriggerKind::DeviceReconnected => RuleTrigger::DeviceReconnected,
+            TriggerKind::DeviceConnected => RuleTrigger::DeviceConnected,
+            TriggerKind::DeviceDisconnected => RuleTrigger::DeviceDisconnected,
+            TriggerKind::FullyCharged => RuleTrigger::FullyCharged {
+                component: self.component,
+            },
+        };
+
+        let name = match self.name.trim() {
+            "" => trigger.to_string(),
+            name => name.to_string(),
+        };
+        let argument = self.argument.trim();
+        let action = match self.action {
+            ActionKind::Notify => RuleAction::Notify {
+                title: name.clone(),
+                body: argument.to_string(),
+            },
+            ActionKind::PlaySound => RuleAction::PlaySound {
+                path: (!argument.is_empty()).then(|| argument.to_string()),
+            },
+            ActionKind::RunCommand if argument.is_empty() => return None,
+            ActionKind::RunCommand => RuleAction::RunCommand {
+                command: argument.to_string(),
+            },
+        };
+
+        Some(ActionRule {
+            name,
+            enabled: true,
+            trigger,
+            action,
+        })
+    }
+}
+
+/// Synthetic frame being composed in developer mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticFrameDraft {
+    /// Address the frame appears to come from
+    pub address: String,
+    /// Apple manufacturer data, as typed
+    pub payload: String,
+}
+
+impl Default for SyntheticFrameDraft {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_SYNTHETIC_ADDRESS.to_string(),
+            payload: String::new(),
+        }
+    }
+}
+
+/// Canonical address written as `AA:BB:CC:DD:EE:FF`
+fn format_address(address: &str) -> String {
+    address
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| String::from_utf8_lossy(pair).to_uppercase())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Days offered for quiet hours, in calendar order
+const QUIET_HOURS_DAYS: [chrono::Weekday; 7] = [
+    chrono::Weekday::Mon,
+    chrono::Weekday::Tue,
+    chrono::Weekday::Wed,
+    chrono::Weekday::Thu,
+    chrono::Weekday::Fri,
+    chrono::Weekday::Sat,
+    chrono::Weekday::Sun,
+];
+
+/// Time of day offered in the quiet hours pickers, in minutes past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimeOfDay(u16);
+
+impl std::fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.0 / 60, self.0 % 60)
+    }
 }
@@ -0,0 +1,42 @@
+//! Software rendering notice component for RustPods
+//!
+//! Shown in a session started with the software renderer because hardware rendering
+//! failed, asking whether to keep using it in later sessions.
+
+use iced::{
+    widget::{button, container, row, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the software rendering notice, or nothing when it isn't shown
+pub fn view_software_rendering_notice(
+    shown: bool,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    if !shown {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    }
+
+    let content = row![
+        text("Hardware rendering failed, so RustPods is using software rendering")
+            .size(theme::scaled(13.0)),
+        Space::with_width(Length::Fill),
+        button(text("Keep Using").size(theme::scaled(12.0)))
+            .on_press(Message::KeepSoftwareRendering)
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+        button(text("Dismiss").size(theme::scaled(12.0)))
+            .on_press(Message::DismissSoftwareRenderingNotice)
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    container(content)
+        .width(Length::Fill)
+        .padding([8, 12])
+        .style(iced::theme::Container::Box)
+        .into()
+}
@@ -1,6 +1,24 @@
 use iced::Color;
 use std::fmt::Write;
 
+use crate::ui::theme;
+
+/// Opening `<svg>` tag for a drawing with the given view box, shown `width` by
+/// `height` logical pixels
+///
+/// The intrinsic size is given in physical pixels at the current scale factor, so
+/// the drawing is rasterized at the resolution it is shown at rather than scaled up.
+pub fn svg_open_tag(view_width: f32, view_height: f32, width: f32, height: f32) -> String {
+    let scale = theme::display_scale();
+    format!(
+        r#"<svg width="{}" height="{}" viewBox="0 0 {} {}" xmlns="http://www.w3.org/2000/svg">"#,
+        (width * scale).round().max(1.0),
+        (height * scale).round().max(1.0),
+        view_width,
+        view_height
+    )
+}
+
 /// Generates an SVG string for a refresh/scan icon with an optional rotation animation
 ///
 /// - `animated`: Whether to include a rotation animation
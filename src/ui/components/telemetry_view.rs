@@ -0,0 +1,110 @@
+//! Collected telemetry screen for RustPods
+//!
+//! Shows exactly what the local telemetry file contains, so users can check what
+//! was aggregated before deciding to keep sharing it.
+
+use iced::{
+    widget::{button, column, row, scrollable, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::telemetry::{TelemetryManager, TelemetryReport};
+use crate::ui::components::Dialog;
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the collected data screen for the given report
+pub fn view_telemetry_data(
+    report: TelemetryReport,
+    enabled: bool,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let header = row![
+        button(text("← Back").size(theme::scaled(14.0)))
+            .on_press(Message::CloseTelemetryData)
+            .style(iced::theme::Button::Secondary)
+            .padding([5, 10]),
+        Space::with_width(Length::Fixed(10.0)),
+        text("Collected Data")
+            .size(theme::scaled(24.0))
+            .style(theme::TEXT),
+        Space::with_width(Length::Fill),
+        button(text("Clear Data").size(theme::scaled(14.0)))
+            .on_press(Message::ShowDialog(Box::new(
+                Dialog::confirm(
+                    "Clear collected data?",
+                    "All locally collected usage data will be deleted.",
+                    "Clear Data",
+                    Message::ClearTelemetryData,
+                )
+                .destructive(),
+            )))
+            .style(iced::theme::Button::Destructive)
+            .padding([5, 10]),
+    ]
+    .align_items(Alignment::Center);
+
+    let status = if enabled {
+        format!(
+            "Collection is on. Data is kept in {} and never sent.",
+            TelemetryManager::local_data_path().display()
+        )
+    } else {
+        "Collection is off. Nothing new is recorded.".to_string()
+    };
+
+    let total_errors = report.errors.bluetooth
+        + report.errors.airpods
+        + report.errors.ui
+        + report.errors.config
+        + report.errors.app
+        + report.errors.battery
+        + report.errors.system;
+
+    let summary = column![
+        summary_row("Scans", report.usage.scans.to_string()),
+        summary_row(
+            "Scan error rate",
+            format!("{:.1}%", report.scan_error_rate * 100.0)
+        ),
+        summary_row(
+            "Battery readings",
+            report.usage.battery_readings.to_string()
+        ),
+        summary_row("Errors", total_errors.to_string()),
+        summary_row(
+            "Features used",
+            report.usage.features_used.values().sum::<u32>().to_string()
+        ),
+    ]
+    .spacing(8);
+
+    let json = serde_json::to_string_pretty(&report)
+        .unwrap_or_else(|e| format!("Failed to render data: {}", e));
+
+    column![
+        header,
+        text(status)
+            .size(theme::scaled(12.0))
+            .style(theme::SUBTEXT1),
+        summary,
+        text("Raw data")
+            .size(theme::scaled(16.0))
+            .style(theme::TEXT),
+        scrollable(text(json).size(theme::scaled(12.0)).style(theme::SUBTEXT1))
+            .height(Length::Fill),
+    ]
+    .spacing(15)
+    .padding(25)
+    .into()
+}
+
+/// A single label/value line in the summary
+fn summary_row(label: &str, value: String) -> Element<'static, Message, iced::Renderer<Theme>> {
+    row![
+        text(label)
+            .style(theme::SUBTEXT1)
+            .width(Length::Fixed(140.0)),
+        text(value).style(theme::TEXT),
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
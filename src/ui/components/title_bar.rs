@@ -0,0 +1,138 @@
+//! Custom title bar for RustPods
+//!
+//! Replaces the native window chrome with a themed bar holding the app logo and title
+//! on the left and the settings, minimize and close caption buttons on the right.
+//! Its geometry comes from `window_management` so native hit-testing lines up with
+//! what is drawn. When the background health check finds problems, a badge in front
+//! of the caption buttons opens the troubleshoot screen.
+
+use iced::widget::svg::Handle as SvgHandle;
+use iced::{
+    widget::{button, container, mouse_area, text, Space, Svg},
+    Alignment, Element, Length,
+};
+
+use crate::diagnostics::HealthStatus;
+use crate::ui::accessibility::{self, FocusTarget};
+use crate::ui::window_management::{
+    CAPTION_BUTTON_SIZE, CAPTION_BUTTON_SPACING, TITLE_BAR_HEIGHT, TITLE_BAR_PADDING,
+};
+use crate::ui::{direction, theme, theme::Theme, Message};
+
+/// Padding between a caption button's edge and its icon
+const CAPTION_ICON_PADDING: f32 = 5.0;
+
+/// Render the title bar, highlighting the caption button with keyboard focus
+pub fn view_title_bar(
+    title: &str,
+    focused: Option<FocusTarget>,
+    health: HealthStatus,
+) -> Element<'_, Message, iced::Renderer<Theme>> {
+    let style = |target: FocusTarget, style: iced::theme::Button| {
+        accessibility::button_style(style, focused == Some(target))
+    };
+
+    // The logo keeps its gap on the title's side in either direction
+    let logo_padding = if direction::is_rtl() {
+        [0, 0, 0, 8]
+    } else {
+        [0, 8, 0, 0]
+    };
+    let brand = direction::row(vec![
+        container(
+            Svg::new(SvgHandle::from_memory(crate::assets::app::LOGO_SVG))
+                .width(Length::Fixed(24.0))
+                .height(Length::Fixed(24.0)),
+        )
+        .padding(logo_padding)
+        .into(),
+        text(title)
+            .size(theme::scaled(20.0))
+            .style(theme::TEXT)
+            .into(),
+    ])
+    .align_items(Alignment::Center);
+
+    let mut items = vec![brand.into(), Space::with_width(Length::Fill).into()];
+    if health != HealthStatus::Healthy {
+        items.push(health_badge(
+            health,
+            style(FocusTarget::HealthBadge, theme::settings_button_style()),
+        ));
+    }
+    items.extend([
+        caption_button(
+            crate::assets::ui::SETTINGS_ICON,
+            Message::OpenSettings,
+            style(FocusTarget::OpenSettings, theme::settings_button_style()),
+        ),
+        caption_button(
+            crate::assets::ui::MINIMIZE_ICON,
+            Message::MinimizeWindow,
+            style(FocusTarget::Minimize, theme::settings_button_style()),
+        ),
+        // Close goes through the close request so minimize-to-tray is honored
+        caption_button(
+            crate::assets::ui::CLOSE_ICON,
+            Message::WindowCloseRequested,
+            style(FocusTarget::Close, theme::close_button_style()),
+        ),
+    ]);
+
+    // Caption buttons move to the left edge in right-to-left layouts
+    let bar = container(
+        direction::row(items)
+            .spacing(CAPTION_BUTTON_SPACING)
+            .align_items(Alignment::Center),
+    )
+    .width(Length::Fill)
+    .height(Length::Fixed(TITLE_BAR_HEIGHT))
+    .center_y()
+    .padding([0.0, TITLE_BAR_PADDING]);
+
+    // On Windows the caption is dragged natively; elsewhere fall back to an iced drag
+    mouse_area(bar)
+        .on_press(Message::WindowDragStart(iced::Point::new(0.0, 0.0)))
+        .into()
+}
+
+/// A caption-sized warning sign, red when batteries can't be read at all
+fn health_badge(
+    health: HealthStatus,
+    style: iced::theme::Button,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let icon_size = CAPTION_BUTTON_SIZE - 2.0 * CAPTION_ICON_PADDING;
+    let color = match health {
+        HealthStatus::Unhealthy => theme::RED,
+        _ => theme::YELLOW,
+    };
+    button(
+        container(text("⚠").size(theme::scaled(16.0)).style(color))
+            .width(Length::Fixed(icon_size))
+            .height(Length::Fixed(icon_size))
+            .center_x()
+            .center_y(),
+    )
+    .on_press(Message::ShowTroubleshoot)
+    .style(style)
+    .padding(CAPTION_ICON_PADDING)
+    .into()
+}
+
+/// A square caption button with an SVG icon
+fn caption_button(
+    icon: &'static [u8],
+    message: Message,
+    style: iced::theme::Button,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let icon_size = CAPTION_BUTTON_SIZE - 2.0 * CAPTION_ICON_PADDING;
+    button(
+        Svg::new(SvgHandle::from_memory(icon))
+            .width(Length::Fixed(icon_size))
+            .height(Length::Fixed(icon_size)),
+    )
+    .on_press(message)
+    .style(style)
+    .padding(CAPTION_ICON_PADDING)
+    .into()
+}
@@ -0,0 +1,122 @@
+//! Troubleshoot screen for RustPods
+//!
+//! Lists what the background health check found, each problem with what can be done
+//! about it, so nobody has to run diagnostics from the command line to find out why
+//! batteries stopped updating. The full report can be saved from here as well.
+
+use iced::{
+    widget::{button, column, row, scrollable, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::diagnostics::{DiagnosticIssue, DiagnosticResult, IssueSeverity};
+use crate::ui::file_dialogs::FileAction;
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the troubleshoot screen for the last health check, if any
+pub fn view_troubleshoot(
+    result: Option<&DiagnosticResult>,
+    checking: bool,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let check_again = button(text("Check Again").size(theme::scaled(14.0)))
+        .style(iced::theme::Button::Secondary)
+        .padding([5, 10]);
+    let header = row![
+        button(text("← Back").size(theme::scaled(14.0)))
+            .on_press(Message::CloseTroubleshoot)
+            .style(iced::theme::Button::Secondary)
+            .padding([5, 10]),
+        Space::with_width(Length::Fixed(10.0)),
+        text("Troubleshoot")
+            .size(theme::scaled(24.0))
+            .style(theme::TEXT),
+        Space::with_width(Length::Fill),
+        // Only one check runs at a time
+        if checking {
+            check_again
+        } else {
+            check_again.on_press(Message::HealthCheckTick)
+        },
+        button(text("Save Full Report…").size(theme::scaled(14.0)))
+            .on_press(Message::ChooseFile(FileAction::SaveDiagnostics))
+            .style(iced::theme::Button::Secondary)
+            .padding([5, 10]),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    let status = match (result, checking) {
+        (_, true) => "Checking…".to_string(),
+        (None, false) => "Not checked yet".to_string(),
+        (Some(result), false) => format!(
+            "Last checked at {}",
+            result
+                .timestamp
+                .with_timezone(&chrono::Local)
+                .format("%H:%M")
+        ),
+    };
+
+    let problems: Vec<&DiagnosticIssue> = result
+        .map(|result| result.problems().collect())
+        .unwrap_or_default();
+    let body: Element<'static, Message, iced::Renderer<Theme>> = if problems.is_empty() {
+        text("No problems found")
+            .size(theme::scaled(16.0))
+            .style(theme::TEXT)
+            .into()
+    } else {
+        scrollable(
+            problems
+                .into_iter()
+                .fold(column![].spacing(20), |list, issue| {
+                    list.push(view_issue(issue))
+                }),
+        )
+        .height(Length::Fill)
+        .into()
+    };
+
+    column![
+        header,
+        text(status)
+            .size(theme::scaled(12.0))
+            .style(theme::SUBTEXT1),
+        body,
+    ]
+    .spacing(15)
+    .padding(25)
+    .into()
+}
+
+/// One problem with the steps that may fix it
+fn view_issue(issue: &DiagnosticIssue) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let color = match issue.severity {
+        IssueSeverity::Critical => theme::RED,
+        _ => theme::YELLOW,
+    };
+    let title = row![
+        text("⚠").size(theme::scaled(16.0)).style(color),
+        text(issue.title.clone())
+            .size(theme::scaled(16.0))
+            .style(theme::TEXT),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    let mut steps = column![
+        title,
+        text(issue.description.clone())
+            .size(theme::scaled(12.0))
+            .style(theme::SUBTEXT1),
+    ]
+    .spacing(6);
+    for solution in &issue.solutions {
+        steps = steps.push(
+            text(format!("• {}", solution))
+                .size(theme::scaled(13.0))
+                .style(theme::TEXT),
+        );
+    }
+    steps.into()
+}
@@ -0,0 +1,35 @@
+//! Undo notice component for RustPods
+//!
+//! Shown for a few seconds after settings are saved, with a button that puts the
+//! previous settings back.
+
+use iced::{
+    widget::{button, container, row, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the undo notice, or nothing when there is nothing to undo
+pub fn view_undo_notice(notice: Option<&str>) -> Element<'_, Message, iced::Renderer<Theme>> {
+    let Some(notice) = notice else {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    };
+
+    let content = row![
+        text(notice).size(theme::scaled(13.0)),
+        Space::with_width(Length::Fill),
+        button(text("Undo").size(theme::scaled(12.0)))
+            .on_press(Message::UndoSettingsSave)
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    container(content)
+        .width(Length::Fill)
+        .padding([8, 12])
+        .style(iced::theme::Container::Box)
+        .into()
+}
@@ -0,0 +1,46 @@
+//! Unknown model notice component for RustPods
+//!
+//! Shown once a session when the scanner hears AirPods-like frames from a model the
+//! model table doesn't know, offering to save a capture to attach to an issue.
+
+use iced::{
+    widget::{button, container, row, text, Space},
+    Alignment, Element, Length,
+};
+
+use crate::ui::file_dialogs::FileAction;
+use crate::ui::{theme, theme::Theme, Message};
+
+/// Render the unknown model notice, or nothing when every model heard is known
+pub fn view_unknown_model_notice(
+    model_id: Option<u16>,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let Some(model_id) = model_id else {
+        return Space::with_height(Length::Fixed(0.0)).into();
+    };
+
+    let content = row![
+        text(format!(
+            "Unknown AirPods model detected (0x{:04X}) — help us support it",
+            model_id
+        ))
+        .size(theme::scaled(13.0)),
+        Space::with_width(Length::Fill),
+        button(text("Save Capture").size(theme::scaled(12.0)))
+            .on_press(Message::ChooseFile(FileAction::SaveModelCapture))
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+        button(text("Dismiss").size(theme::scaled(12.0)))
+            .on_press(Message::DismissUnknownModel)
+            .style(theme::secondary_button_style())
+            .padding([2, 8]),
+    ]
+    .spacing(8)
+    .align_items(Alignment::Center);
+
+    container(content)
+        .width(Length::Fill)
+        .padding([8, 12])
+        .style(iced::theme::Container::Box)
+        .into()
+}
@@ -0,0 +1,110 @@
+//! Usage summary panel for RustPods
+//!
+//! Shows listening time and average earbud drain derived from completed sessions, and
+//! the charge cycles counted per month.
+
+use iced::{
+    widget::{column, row, text, Space},
+    Alignment, Element, Length,
+};
+use std::time::Duration;
+
+use crate::airpods::battery_intelligence::{format_cycles, UsageSummary};
+use crate::ui::{theme::Theme, Message};
+
+/// Months of charge cycles listed in the panel
+const MONTHS_SHOWN: usize = 6;
+
+/// Render the usage panel for the given summary
+pub fn view_usage_panel(
+    summary: Option<&UsageSummary>,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let title = text("Usage")
+        .size(crate::ui::theme::scaled(20.0))
+        .style(crate::ui::theme::TEXT);
+
+    let Some(summary) =
+        summary.filter(|summary| summary.sessions_this_week > 0 || !summary.monthly.is_empty())
+    else {
+        return column![
+            title,
+            text("No listening sessions recorded yet").style(crate::ui::theme::SUBTEXT1),
+        ]
+        .spacing(15)
+        .into();
+    };
+
+    let drain = summary
+        .average_drain_per_hour
+        .map(|rate| format!("{:.1}% per hour", rate))
+        .unwrap_or_else(|| "Not enough data".to_string());
+
+    let cycles = format!(
+        "{} (case {})",
+        format_cycles(summary.total_discharge.earbud_cycles()),
+        format_cycles(summary.total_discharge.case_cycles())
+    );
+
+    let months =
+        summary
+            .monthly
+            .iter()
+            .take(MONTHS_SHOWN)
+            .fold(column![].spacing(4), |months, report| {
+                let drain = report
+                    .average_drain_per_hour
+                    .map(|rate| format!(", {:.1}%/hour", rate))
+                    .unwrap_or_default();
+                months.push(usage_row(
+                    &report.month.format("%B %Y").to_string(),
+                    format!(
+                        "{}{}",
+                        format_cycles(report.discharge.earbud_cycles()),
+                        drain
+                    ),
+                ))
+            });
+
+    column![
+        title,
+        usage_row("Today", format_listening_time(summary.today)),
+        usage_row(
+            "Last 7 days",
+            format!(
+                "{} ({} sessions)",
+                format_listening_time(summary.this_week),
+                summary.sessions_this_week
+            ),
+        ),
+        usage_row("Average drain", drain),
+        usage_row("Charge cycles", cycles),
+        months,
+    ]
+    .spacing(10)
+    .into()
+}
+
+/// A single label/value line in the usage panel
+fn usage_row(label: &str, value: String) -> Element<'static, Message, iced::Renderer<Theme>> {
+    row![
+        text(label)
+            .style(crate::ui::theme::SUBTEXT1)
+            .width(Length::Fixed(120.0)),
+        Space::with_width(Length::Fixed(10.0)),
+        text(value).style(crate::ui::theme::TEXT),
+    ]
+    .align_items(Alignment::Center)
+    .into()
+}
+
+/// Format a listening duration as hours and minutes
+pub fn format_listening_time(duration: Duration) -> String {
+    let total_minutes = duration.as_secs() / 60;
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m", minutes)
+    }
+}
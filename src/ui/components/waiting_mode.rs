@@ -79,7 +79,7 @@ impl WaitingMode {
         // Use a simple text-based animation for now (can be replaced with SVG later)
         container(
             text("⟲")
-                .size(pulse_size)
+                .size(crate::ui::theme::scaled(pulse_size))
                 .style(crate::ui::theme::BLUE)
                 .horizontal_alignment(Horizontal::Center),
         )
@@ -134,12 +134,12 @@ impl WaitingMode {
 
         column![
             text(primary_message)
-                .size(18.0)
+                .size(crate::ui::theme::scaled(18.0))
                 .style(crate::ui::theme::TEXT)
                 .horizontal_alignment(Horizontal::Center),
             Space::with_height(Length::Fixed(8.0)),
             text(secondary_message)
-                .size(14.0)
+                .size(crate::ui::theme::scaled(14.0))
                 .style(crate::ui::theme::SUBTEXT1)
                 .horizontal_alignment(Horizontal::Center),
         ]
@@ -153,7 +153,7 @@ impl WaitingMode {
             let seconds = next_scan.as_secs();
             if seconds > 0 {
                 return text(format!("Next scan in {}s", seconds))
-                    .size(12.0)
+                    .size(crate::ui::theme::scaled(12.0))
                     .style(crate::ui::theme::OVERLAY1)
                     .horizontal_alignment(Horizontal::Center)
                     .into();
@@ -163,7 +163,7 @@ impl WaitingMode {
         if let Some(last_scan) = self.time_since_last_scan {
             let seconds = last_scan.as_secs();
             return text(format!("Last scan {}s ago", seconds))
-                .size(12.0)
+                .size(crate::ui::theme::scaled(12.0))
                 .style(crate::ui::theme::OVERLAY1)
                 .horizontal_alignment(Horizontal::Center)
                 .into();
@@ -176,20 +176,20 @@ impl WaitingMode {
     fn tips_section(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
         column![
             text("Troubleshooting Tips:")
-                .size(14.0)
+                .size(crate::ui::theme::scaled(14.0))
                 .style(crate::ui::theme::SUBTEXT1),
             Space::with_height(Length::Fixed(8.0)),
             text("• Make sure Bluetooth is enabled")
-                .size(12.0)
+                .size(crate::ui::theme::scaled(12.0))
                 .style(crate::ui::theme::OVERLAY1),
             text("• Ensure AirPods are paired with this device")
-                .size(12.0)
+                .size(crate::ui::theme::scaled(12.0))
                 .style(crate::ui::theme::OVERLAY1),
             text("• Keep the AirPods case open during scanning")
-                .size(12.0)
+                .size(crate::ui::theme::scaled(12.0))
                 .style(crate::ui::theme::OVERLAY1),
             text("• Move closer to your AirPods")
-                .size(12.0)
+                .size(crate::ui::theme::scaled(12.0))
                 .style(crate::ui::theme::OVERLAY1),
         ]
         .spacing(4.0)
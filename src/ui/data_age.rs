@@ -0,0 +1,75 @@
+//! Age of the readings on screen
+//!
+//! AirPods stop advertising once they are in a pocket or out of range, and the window
+//! keeps the last readings it had. The age of those readings comes from the merged
+//! device's `last_seen`; past `ui.stale_after_minutes` the battery display is grayed out
+//! and says when the AirPods were last heard.
+
+use std::time::{Duration, SystemTime};
+
+use crate::ui::state::MergedBluetoothDevice;
+
+/// Time since `device` was last heard, zero if the clock went backwards
+pub fn age(device: &MergedBluetoothDevice, now: SystemTime) -> Duration {
+    now.duration_since(device.last_seen).unwrap_or_default()
+}
+
+/// Whether the readings of `device` are older than `stale_after_minutes`
+///
+/// A threshold of zero never grays the display out.
+pub fn is_stale(device: &MergedBluetoothDevice, stale_after_minutes: u32, now: SystemTime) -> bool {
+    stale_after_minutes > 0
+        && age(device, now) >= Duration::from_secs(u64::from(stale_after_minutes) * 60)
+}
+
+/// "last seen 12 min ago"
+pub fn last_seen_text(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0 => "last seen just now".to_string(),
+        1..=59 => format!("last seen {} min ago", minutes),
+        60..=1439 => format!("last seen {} h ago", minutes / 60),
+        _ => match minutes / 1440 {
+            1 => "last seen 1 day ago".to_string(),
+            days => format!("last seen {} days ago", days),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_staleness_and_last_seen_text() {
+        let now = SystemTime::now();
+        let device = MergedBluetoothDevice {
+            last_seen: now - Duration::from_secs(12 * 60 + 5),
+            ..MergedBluetoothDevice::default()
+        };
+
+        assert_eq!(last_seen_text(age(&device, now)), "last seen 12 min ago");
+        assert!(is_stale(&device, 5, now));
+        assert!(!is_stale(&device, 15, now));
+        assert!(!is_stale(&device, 0, now));
+
+        // A reading from the future is fresh
+        assert_eq!(
+            age(&device, now - Duration::from_secs(3600)),
+            Duration::ZERO
+        );
+
+        assert_eq!(
+            last_seen_text(Duration::from_secs(30)),
+            "last seen just now"
+        );
+        assert_eq!(
+            last_seen_text(Duration::from_secs(3 * 3600)),
+            "last seen 3 h ago"
+        );
+        assert_eq!(
+            last_seen_text(Duration::from_secs(86_400)),
+            "last seen 1 day ago"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+//! Layout direction for right-to-left locales
+//!
+//! Arabic, Hebrew and other right-to-left scripts are read from the right, so the
+//! layout is mirrored when such a locale is selected: rows start at the right edge,
+//! text aligns right, icons sit on the other side of their labels and progress fills
+//! from the right. This only covers layout; translated strings are a separate layer.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use iced::{alignment::Horizontal, widget::Row, Alignment, Element, Length, Pixels};
+
+use crate::config::AppConfig;
+use crate::ui::{theme::Theme, Message};
+
+/// Locale used when neither the config nor the system names one
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Locales offered in settings (tag, label)
+pub const SUPPORTED_LOCALES: &[(&str, &str)] = &[
+    ("en-US", "English"),
+    ("ar", "Arabic"),
+    ("he", "Hebrew"),
+    ("fa", "Persian"),
+    ("ur", "Urdu"),
+];
+
+/// Languages written right to left (ISO 639 codes, including the legacy `iw`)
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ps", "sd", "ug", "ur", "yi",
+];
+
+/// Whether the current layout is mirrored
+static RIGHT_TO_LEFT: AtomicBool = AtomicBool::new(false);
+
+/// Reading direction of the interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutDirection {
+    /// Rows start at the left edge
+    #[default]
+    LeftToRight,
+    /// Rows start at the right edge
+    RightToLeft,
+}
+
+impl LayoutDirection {
+    /// Direction of a BCP 47 locale tag such as `ar-EG` or `he_IL`
+    pub fn for_locale(locale: &str) -> Self {
+        let language = locale
+            .split(['-', '_', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if RTL_LANGUAGES.contains(&language.as_str()) {
+            Self::RightToLeft
+        } else {
+            Self::LeftToRight
+        }
+    }
+
+    /// Whether this direction is right to left
+    pub fn is_rtl(self) -> bool {
+        self == Self::RightToLeft
+    }
+}
+
+/// Locale the layout follows: the configured one, else the system's
+pub fn effective_locale(config: &AppConfig) -> String {
+    config
+        .ui
+        .locale
+        .clone()
+        .or_else(sys_locale::get_locale)
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+/// Apply the layout direction of the configured locale
+pub fn apply_locale(config: &AppConfig) {
+    let locale = effective_locale(config);
+    let direction = LayoutDirection::for_locale(&locale);
+    crate::debug_log!("ui", "Locale {} uses {:?} layout", locale, direction);
+    set_layout_direction(direction);
+}
+
+/// Set the direction every view is laid out in
+pub fn set_layout_direction(direction: LayoutDirection) {
+    RIGHT_TO_LEFT.store(direction.is_rtl(), Ordering::Relaxed);
+}
+
+/// Current layout direction
+pub fn layout_direction() -> LayoutDirection {
+    if RIGHT_TO_LEFT.load(Ordering::Relaxed) {
+        LayoutDirection::RightToLeft
+    } else {
+        LayoutDirection::LeftToRight
+    }
+}
+
+/// Whether the current layout is mirrored
+pub fn is_rtl() -> bool {
+    layout_direction().is_rtl()
+}
+
+/// Items in reading order: reversed for right-to-left layouts
+pub fn mirrored<T>(mut items: Vec<T>) -> Vec<T> {
+    if is_rtl() {
+        items.reverse();
+    }
+    items
+}
+
+/// A row whose children run from the reading start
+pub fn row<'a>(
+    children: Vec<Element<'a, Message, iced::Renderer<Theme>>>,
+) -> Row<'a, Message, iced::Renderer<Theme>> {
+    Row::with_children(mirrored(children))
+}
+
+/// Drop-in for `Row::new()` that lays its children out in reading order
+///
+/// Children are collected as they are pushed and reversed when the row is built, so
+/// builder chains written left to right mirror without being rewritten.
+pub struct DirectionalRow<'a> {
+    children: Vec<Element<'a, Message, iced::Renderer<Theme>>>,
+    spacing: Pixels,
+    align_items: Alignment,
+    width: Length,
+}
+
+impl<'a> Default for DirectionalRow<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> DirectionalRow<'a> {
+    /// Create an empty row
+    pub fn new() -> Self {
+        Self {
+            children: Vec::new(),
+            spacing: Pixels(0.0),
+            align_items: Alignment::Start,
+            width: Length::Shrink,
+        }
+    }
+
+    /// Add a child after the previous one in reading order
+    pub fn push(mut self, child: impl Into<Element<'a, Message, iced::Renderer<Theme>>>) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    /// Set the space between children
+    pub fn spacing(mut self, spacing: impl Into<Pixels>) -> Self {
+        self.spacing = spacing.into();
+        self
+    }
+
+    /// Set the vertical alignment of children
+    pub fn align_items(mut self, align: Alignment) -> Self {
+        self.align_items = align;
+        self
+    }
+
+    /// Set the width of the row
+    pub fn width(mut self, width: impl Into<Length>) -> Self {
+        self.width = width.into();
+        self
+    }
+}
+
+impl<'a> From<DirectionalRow<'a>> for Element<'a, Message, iced::Renderer<Theme>> {
+    fn from(row_builder: DirectionalRow<'a>) -> Self {
+        row(row_builder.children)
+            .spacing(row_builder.spacing)
+            .align_items(row_builder.align_items)
+            .width(row_builder.width)
+            .into()
+    }
+}
+
+/// Horizontal text alignment at the reading start
+pub fn start() -> Horizontal {
+    if is_rtl() {
+        Horizontal::Right
+    } else {
+        Horizontal::Left
+    }
+}
+
+/// Cross-axis alignment for columns whose items hug the reading start
+pub fn start_alignment() -> Alignment {
+    if is_rtl() {
+        Alignment::End
+    } else {
+        Alignment::Start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_for_locale() {
+        assert_eq!(
+            LayoutDirection::for_locale("ar-EG"),
+            LayoutDirection::RightToLeft
+        );
+        assert_eq!(
+            LayoutDirection::for_locale("he_IL.UTF-8"),
+            LayoutDirection::RightToLeft
+        );
+        assert_eq!(
+            LayoutDirection::for_locale("en-US"),
+            LayoutDirection::LeftToRight
+        );
+        // Language codes are matched whole, not by prefix
+        assert_eq!(
+            LayoutDirection::for_locale("fr-FR"),
+            LayoutDirection::LeftToRight
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Native open and save dialogs
+//!
+//! Import and export actions ask where to read or write with the platform's file
+//! dialog rather than using fixed paths. The dialogs are asynchronous, so the UI keeps
+//! drawing while one is open.
+
+use std::path::PathBuf;
+
+/// A file the user is asked to pick
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAction {
+    /// Where to write the current settings
+    ExportSettings,
+    /// Settings file to load into the settings window
+    ImportSettings,
+    /// Where to write the battery intelligence profile
+    ExportBatteryData,
+    /// Where to write a diagnostic report
+    SaveDiagnostics,
+    /// Where to write a capture of an unknown model's advertisements
+    SaveModelCapture,
+    /// Where to write a backup of all application data
+    BackUpAllData,
+    /// Backup to restore all application data from
+    RestoreBackup,
+    /// Folder scheduled backups are written to
+    ChooseBackupFolder,
+    /// Folder with a custom tray icon set
+    ChooseTrayIconFolder,
+}
+
+impl FileAction {
+    /// Whether the dialog picks an existing file rather than a place to write one
+    pub fn opens_file(self) -> bool {
+        matches!(self, Self::ImportSettings | Self::RestoreBackup)
+    }
+
+    /// Whether the dialog picks a folder
+    pub fn picks_folder(self) -> bool {
+        matches!(self, Self::ChooseBackupFolder | Self::ChooseTrayIconFolder)
+    }
+
+    /// Title of the dialog
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::ExportSettings => "Export Settings",
+            Self::ImportSettings => "Import Settings",
+            Self::ExportBatteryData => "Export Battery Data",
+            Self::SaveDiagnostics => "Save Diagnostic Report",
+            Self::SaveModelCapture => "Save Advertisement Capture",
+            Self::BackUpAllData => "Back Up All Data",
+            Self::RestoreBackup => "Restore from Backup",
+            Self::ChooseBackupFolder => "Choose Backup Folder",
+            Self::ChooseTrayIconFolder => "Choose Tray Icon Folder",
+        }
+    }
+
+    /// Name and extensions of the file type filter
+    fn filter(self) -> (&'static str, &'static [&'static str]) {
+        match self {
+            Self::ExportSettings | Self::ImportSettings => ("RustPods settings", &["json"]),
+            Self::ExportBatteryData => ("Battery profile", &["json"]),
+            Self::SaveDiagnostics => ("Text report", &["txt"]),
+            Self::SaveModelCapture => ("Advertisement capture", &["json"]),
+            Self::BackUpAllData | Self::RestoreBackup | Self::ChooseBackupFolder => {
+                ("RustPods backup", &["zip"])
+            }
+            Self::ChooseTrayIconFolder => ("Tray icons", &["ico"]),
+        }
+    }
+
+    /// File name suggested when saving
+    fn default_file_name(self) -> String {
+        let date = chrono::Local::now().format("%Y%m%d");
+        match self {
+            Self::ExportSettings | Self::ImportSettings => {
+                format!("rustpods_settings_{}.json", date)
+            }
+            Self::ExportBatteryData => format!("rustpods_battery_{}.json", date),
+            Self::SaveDiagnostics => format!("rustpods_diagnostic_{}.txt", date),
+            Self::SaveModelCapture => format!("rustpods_unknown_model_{}.json", date),
+            Self::BackUpAllData | Self::RestoreBackup | Self::ChooseBackupFolder => {
+                format!("rustpods_backup_{}.zip", date)
+            }
+            Self::ChooseTrayIconFolder => crate::assets::tray::CUSTOM_CONNECTED.to_string(),
+        }
+    }
+}
+
+/// Show the dialog for `action`; `None` if it was cancelled
+pub async fn choose_file(action: FileAction) -> Option<PathBuf> {
+    let (filter_name, extensions) = action.filter();
+    let mut dialog = rfd::AsyncFileDialog::new()
+        .set_title(action.title())
+        .add_filter(filter_name, extensions);
+    if let Some(documents) = dirs::document_dir() {
+        dialog = dialog.set_directory(documents);
+    }
+
+    let file = if action.picks_folder() {
+        dialog.pick_folder().await
+    } else if action.opens_file() {
+        dialog.pick_file().await
+    } else {
+        dialog
+            .set_file_name(action.default_file_name())
+            .save_file()
+            .await
+    };
+    file.map(|file| file.path().to_path_buf())
+}
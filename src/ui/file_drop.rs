@@ -0,0 +1,72 @@
+//! Files dropped onto the window
+//!
+//! A dropped file is read and checked before anything is offered, so a file RustPods
+//! can't use is turned away with a message instead of failing half way through an
+//! import. What a valid file would replace is confirmed in a dialog first.
+
+use std::path::Path;
+
+use crate::airpods::DeviceBatteryProfile;
+use crate::config::AppConfig;
+
+/// What a dropped file turned out to be
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DroppedFile {
+    /// Settings that pass validation
+    Settings,
+    /// Battery profile backup for the named device
+    BatteryProfile { device_name: String },
+}
+
+/// Work out what `path` holds, or why it can't be used
+pub fn identify(path: &Path) -> Result<DroppedFile, String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let json: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|_| "Only RustPods settings and battery profiles can be dropped".to_string())?;
+
+    if json.get("device_address").is_some() {
+        let profile: DeviceBatteryProfile = serde_json::from_value(json)
+            .map_err(|e| format!("The battery profile is damaged: {}", e))?;
+        return Ok(DroppedFile::BatteryProfile {
+            device_name: profile.device_name,
+        });
+    }
+
+    if json.get("bluetooth").is_some() || json.get("ui").is_some() {
+        AppConfig::load_from_path(path).map_err(|e| format!("The settings are invalid: {}", e))?;
+        return Ok(DroppedFile::Settings);
+    }
+
+    Err("Only RustPods settings and battery profiles can be dropped".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_dropped_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let settings = dir.path().join("settings.json");
+        AppConfig::default().save_to_path(&settings).unwrap();
+        assert_eq!(identify(&settings), Ok(DroppedFile::Settings));
+
+        let profile = dir.path().join("profile.json");
+        std::fs::write(
+            &profile,
+            serde_json::to_string(&DeviceBatteryProfile::new("AirPods Pro", "aabbcc")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            identify(&profile),
+            Ok(DroppedFile::BatteryProfile {
+                device_name: "AirPods Pro".to_string()
+            })
+        );
+
+        let other = dir.path().join("notes.txt");
+        std::fs::write(&other, "hello").unwrap();
+        assert!(identify(&other).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+//! Interface font selection for RustPods
+//!
+//! The bundled SpaceMono Nerd Font is used unless the user picked an installed font
+//! family in the appearance settings. Iced fixes the default font when the window is
+//! created, so a new choice applies on the next start.
+
+use std::sync::OnceLock;
+
+use crate::config::AppConfig;
+use crate::ui::theme::FONT_FAMILY;
+
+/// Smallest font size multiplier
+pub const MIN_FONT_SCALE: f32 = 0.75;
+/// Largest font size multiplier
+pub const MAX_FONT_SCALE: f32 = 2.0;
+
+/// Font families installed on the system, sorted and deduplicated
+///
+/// Scanning the system fonts takes a moment, so the list is built once on first use.
+pub fn installed_font_families() -> &'static [String] {
+    static FAMILIES: OnceLock<Vec<String>> = OnceLock::new();
+    FAMILIES.get_or_init(|| {
+        let mut database = fontdb::Database::new();
+        database.load_system_fonts();
+        let mut families: Vec<String> = database
+            .faces()
+            .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+            .collect();
+        families.sort_by_key(|name| name.to_lowercase());
+        families.dedup();
+        families
+    })
+}
+
+/// Font the window is created with
+pub fn startup_font(config: &AppConfig) -> iced::Font {
+    match config.ui.font_family.as_deref().map(str::trim) {
+        Some(family) if !family.is_empty() && family != FONT_FAMILY => {
+            // Iced keeps font names for the life of the program; this runs once at startup
+            iced::Font::with_name(Box::leak(family.to_string().into_boxed_str()))
+        }
+        _ => iced::Font::with_name(FONT_FAMILY),
+    }
+}
+
+/// Font size multiplier from the config, kept within the supported range
+pub fn startup_font_scale(config: &AppConfig) -> f32 {
+    config.ui.font_scale.clamp(MIN_FONT_SCALE, MAX_FONT_SCALE)
+}
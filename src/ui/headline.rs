@@ -0,0 +1,236 @@
+//! The one battery level shown where there is no room for all of them
+//!
+//! The tray tooltip leads with a single level and the taskbar button has room for just
+//! one. Which level that is comes from `ui.tray_metric`: the earbud that will run out
+//! first, the average of both earbuds, or one component. When the chosen component
+//! isn't reported, as with the case closed or a single-battery device, the lowest
+//! earbud is shown instead.
+
+use std::fmt;
+
+use crate::config::{BatteryComponent, TrayMetric, UiConfig};
+use crate::ui::state::MergedBluetoothDevice;
+
+/// One battery of a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reading {
+    /// Which battery; `Any` for devices reporting a single level
+    pub component: BatteryComponent,
+    /// Level in percent, if reported
+    pub level: Option<u8>,
+    /// Low battery threshold for the component
+    pub threshold: u8,
+    /// Whether the battery is charging
+    pub charging: bool,
+}
+
+impl Reading {
+    fn is_low(&self, level: u8) -> bool {
+        !self.charging && level <= self.threshold
+    }
+}
+
+/// The level to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Headline {
+    /// How the level was worked out, after any fallback
+    pub metric: TrayMetric,
+    /// Level in percent
+    pub level: u8,
+    /// Whether a battery counted is at or below its threshold and not charging
+    pub low: bool,
+    /// Whether the batteries counted are charging
+    pub charging: bool,
+}
+
+impl Headline {
+    /// Headline for `readings` by `metric`, `None` if no level is known
+    pub fn pick(metric: TrayMetric, readings: &[Reading]) -> Option<Self> {
+        let component = match metric {
+            TrayMetric::Left => Some(BatteryComponent::Left),
+            TrayMetric::Right => Some(BatteryComponent::Right),
+            TrayMetric::Case => Some(BatteryComponent::Case),
+            TrayMetric::Lowest | TrayMetric::Average => None,
+        };
+        let single = component.and_then(|component| {
+            let reading = readings.iter().find(|r| r.component == component)?;
+            let level = reading.level?.min(100);
+            Some(Headline {
+                metric,
+                level,
+                low: reading.is_low(level),
+                charging: reading.charging,
+            })
+        });
+
+        // The case never counts towards the earbuds
+        let buds: Vec<(&Reading, u8)> = readings
+            .iter()
+            .filter(|r| r.component != BatteryComponent::Case)
+            .filter_map(|r| r.level.map(|level| (r, level.min(100))))
+            .collect();
+        single.or_else(|| match metric {
+            TrayMetric::Average => Self::average(&buds),
+            _ => Self::lowest(&buds),
+        })
+    }
+
+    /// Headline for the primary device by the configured metric
+    ///
+    /// `charging` is whether the left bud, right bud and case are charging.
+    pub fn for_device(
+        device: &MergedBluetoothDevice,
+        charging: (bool, bool, bool),
+        ui: &UiConfig,
+    ) -> Option<Self> {
+        Self::pick(ui.tray_metric, &readings(device, charging, ui))
+    }
+
+    fn lowest(buds: &[(&Reading, u8)]) -> Option<Self> {
+        let (reading, level) = buds.iter().min_by_key(|(_, level)| *level)?;
+        Some(Headline {
+            metric: TrayMetric::Lowest,
+            level: *level,
+            // Any earbud past its own threshold counts, not just the lowest
+            low: buds.iter().any(|(r, level)| r.is_low(*level)),
+            charging: reading.charging,
+        })
+    }
+
+    fn average(buds: &[(&Reading, u8)]) -> Option<Self> {
+        if buds.is_empty() {
+            return None;
+        }
+        let total: u32 = buds.iter().map(|(_, level)| u32::from(*level)).sum();
+        let count = buds.len() as u32;
+        let level = ((total + count / 2) / count) as u8;
+        Some(Headline {
+            metric: TrayMetric::Average,
+            level,
+            // Charging earbuds are on their way up, so only the others decide
+            low: buds.iter().any(|(r, _)| r.is_low(level)),
+            charging: buds.iter().all(|(r, _)| r.charging),
+        })
+    }
+}
+
+impl fmt::Display for Headline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.metric {
+            TrayMetric::Lowest => "Lowest",
+            TrayMetric::Average => "Average",
+            TrayMetric::Left => "Left",
+            TrayMetric::Right => "Right",
+            TrayMetric::Case => "Case",
+        };
+        write!(f, "{} {}%", label, self.level)?;
+        if self.charging {
+            write!(f, " \u{26a1}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The batteries of `device` with their thresholds
+pub fn readings(
+    device: &MergedBluetoothDevice,
+    (left_charging, right_charging, case_charging): (bool, bool, bool),
+    ui: &UiConfig,
+) -> Vec<Reading> {
+    let reading = |component, level, charging| Reading {
+        component,
+        level,
+        threshold: ui.low_battery_threshold_for(component),
+        charging,
+    };
+    if device.left_battery.is_none() && device.right_battery.is_none() {
+        return vec![reading(BatteryComponent::Any, device.battery, false)];
+    }
+    vec![
+        reading(BatteryComponent::Left, device.left_battery, left_charging),
+        reading(
+            BatteryComponent::Right,
+            device.right_battery,
+            right_charging,
+        ),
+        reading(BatteryComponent::Case, device.case_battery, case_charging),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(component: BatteryComponent, level: Option<u8>, charging: bool) -> Reading {
+        Reading {
+            component,
+            level,
+            threshold: 20,
+            charging,
+        }
+    }
+
+    fn buds(left: Option<u8>, right: Option<u8>, case: Option<u8>) -> Vec<Reading> {
+        vec![
+            reading(BatteryComponent::Left, left, false),
+            reading(BatteryComponent::Right, right, false),
+            reading(BatteryComponent::Case, case, false),
+        ]
+    }
+
+    #[test]
+    fn test_headline_by_metric() {
+        let levels = buds(Some(80), Some(61), Some(10));
+        let level = |metric| Headline::pick(metric, &levels).map(|h| h.level);
+        assert_eq!(level(TrayMetric::Lowest), Some(61));
+        assert_eq!(level(TrayMetric::Average), Some(71));
+        assert_eq!(level(TrayMetric::Left), Some(80));
+        assert_eq!(level(TrayMetric::Right), Some(61));
+        assert_eq!(level(TrayMetric::Case), Some(10));
+
+        // Only the case metric looks at the case
+        assert!(!Headline::pick(TrayMetric::Lowest, &levels).unwrap().low);
+        assert!(Headline::pick(TrayMetric::Case, &levels).unwrap().low);
+
+        assert_eq!(
+            Headline::pick(TrayMetric::Lowest, &buds(None, None, None)),
+            None
+        );
+        assert_eq!(Headline::pick(TrayMetric::Average, &[]), None);
+    }
+
+    #[test]
+    fn test_missing_component_falls_back_to_lowest() {
+        let headline = Headline::pick(TrayMetric::Case, &buds(Some(80), Some(60), None)).unwrap();
+        assert_eq!(headline.metric, TrayMetric::Lowest);
+        assert_eq!(headline.level, 60);
+        assert_eq!(headline.to_string(), "Lowest 60%");
+
+        // Devices with a single battery show it whatever the metric
+        let single = [reading(BatteryComponent::Any, Some(45), false)];
+        for metric in TrayMetric::ALL {
+            assert_eq!(Headline::pick(metric, &single).unwrap().level, 45);
+        }
+    }
+
+    #[test]
+    fn test_charging_earbuds_are_never_low() {
+        let levels = [
+            reading(BatteryComponent::Left, Some(10), true),
+            reading(BatteryComponent::Right, Some(90), false),
+        ];
+        let lowest = Headline::pick(TrayMetric::Lowest, &levels).unwrap();
+        assert_eq!(
+            (lowest.level, lowest.low, lowest.charging),
+            (10, false, true)
+        );
+        assert_eq!(lowest.to_string(), "Lowest 10% \u{26a1}");
+
+        let low = Headline::pick(TrayMetric::Lowest, &buds(Some(80), Some(15), None)).unwrap();
+        assert!(low.low);
+
+        // An average at the threshold is low unless every earbud is charging
+        let average = Headline::pick(TrayMetric::Average, &buds(Some(10), Some(30), None)).unwrap();
+        assert_eq!((average.level, average.low), (20, true));
+    }
+}
@@ -2,14 +2,113 @@
 //!
 //! This module provides keyboard shortcut handling for the application,
 //! including global shortcuts, keymaps, and shortcut configuration.
+//!
+//! Refresh, open settings, toggle compact view and quit can be rebound in settings;
+//! rebinds are stored in `ui.shortcuts` as text such as `Ctrl+Shift+R`. The remaining
+//! shortcuts are fixed, and no two shortcuts may share a key combination.
 
 use iced::keyboard::{self, KeyCode, Modifiers};
 use iced::Event;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
 
+use crate::config::ShortcutAction;
 use crate::ui::Message;
 
+/// Names of the keys a shortcut can use, as shown and as written in the config
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::A, "A"),
+    (KeyCode::B, "B"),
+    (KeyCode::C, "C"),
+    (KeyCode::D, "D"),
+    (KeyCode::E, "E"),
+    (KeyCode::F, "F"),
+    (KeyCode::G, "G"),
+    (KeyCode::H, "H"),
+    (KeyCode::I, "I"),
+    (KeyCode::J, "J"),
+    (KeyCode::K, "K"),
+    (KeyCode::L, "L"),
+    (KeyCode::M, "M"),
+    (KeyCode::N, "N"),
+    (KeyCode::O, "O"),
+    (KeyCode::P, "P"),
+    (KeyCode::Q, "Q"),
+    (KeyCode::R, "R"),
+    (KeyCode::S, "S"),
+    (KeyCode::T, "T"),
+    (KeyCode::U, "U"),
+    (KeyCode::V, "V"),
+    (KeyCode::W, "W"),
+    (KeyCode::X, "X"),
+    (KeyCode::Y, "Y"),
+    (KeyCode::Z, "Z"),
+    (KeyCode::Key0, "0"),
+    (KeyCode::Key1, "1"),
+    (KeyCode::Key2, "2"),
+    (KeyCode::Key3, "3"),
+    (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"),
+    (KeyCode::Key6, "6"),
+    (KeyCode::Key7, "7"),
+    (KeyCode::Key8, "8"),
+    (KeyCode::Key9, "9"),
+    (KeyCode::F1, "F1"),
+    (KeyCode::F2, "F2"),
+    (KeyCode::F3, "F3"),
+    (KeyCode::F4, "F4"),
+    (KeyCode::F5, "F5"),
+    (KeyCode::F6, "F6"),
+    (KeyCode::F7, "F7"),
+    (KeyCode::F8, "F8"),
+    (KeyCode::F9, "F9"),
+    (KeyCode::F10, "F10"),
+    (KeyCode::F11, "F11"),
+    (KeyCode::F12, "F12"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Escape, "Esc"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Delete, "Delete"),
+    (KeyCode::Insert, "Insert"),
+    (KeyCode::Home, "Home"),
+    (KeyCode::End, "End"),
+    (KeyCode::PageUp, "PageUp"),
+    (KeyCode::PageDown, "PageDown"),
+    (KeyCode::Left, "Left"),
+    (KeyCode::Right, "Right"),
+    (KeyCode::Up, "Up"),
+    (KeyCode::Down, "Down"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equals, "Equals"),
+    (KeyCode::Slash, "Slash"),
+    (KeyCode::Semicolon, "Semicolon"),
+];
+
+/// Keys that only modify other keys and can't be bound on their own
+const MODIFIER_KEYS: [KeyCode; 8] = [
+    KeyCode::LControl,
+    KeyCode::RControl,
+    KeyCode::LShift,
+    KeyCode::RShift,
+    KeyCode::LAlt,
+    KeyCode::RAlt,
+    KeyCode::LWin,
+    KeyCode::RWin,
+];
+
+/// Shortcuts in use while the window has focus
+static ACTIVE_SHORTCUTS: RwLock<Option<KeyboardShortcutManager>> = RwLock::new(None);
+
+/// Whether the next key press is captured for the shortcut editor
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
 /// Represents a keyboard shortcut
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct KeyboardShortcut {
@@ -73,51 +172,10 @@ impl Display for KeyboardShortcut {
             parts.push("Win");
         }
 
-        let key_name = match self.key {
-            KeyCode::A => "A",
-            KeyCode::B => "B",
-            KeyCode::C => "C",
-            KeyCode::D => "D",
-            KeyCode::E => "E",
-            KeyCode::F => "F",
-            KeyCode::G => "G",
-            KeyCode::H => "H",
-            KeyCode::I => "I",
-            KeyCode::J => "J",
-            KeyCode::K => "K",
-            KeyCode::L => "L",
-            KeyCode::M => "M",
-            KeyCode::N => "N",
-            KeyCode::O => "O",
-            KeyCode::P => "P",
-            KeyCode::Q => "Q",
-            KeyCode::R => "R",
-            KeyCode::S => "S",
-            KeyCode::T => "T",
-            KeyCode::U => "U",
-            KeyCode::V => "V",
-            KeyCode::W => "W",
-            KeyCode::X => "X",
-            KeyCode::Y => "Y",
-            KeyCode::Z => "Z",
-            KeyCode::F1 => "F1",
-            KeyCode::F2 => "F2",
-            KeyCode::F3 => "F3",
-            KeyCode::F4 => "F4",
-            KeyCode::F5 => "F5",
-            KeyCode::F6 => "F6",
-            KeyCode::F7 => "F7",
-            KeyCode::F8 => "F8",
-            KeyCode::F9 => "F9",
-            KeyCode::F10 => "F10",
-            KeyCode::F11 => "F11",
-            KeyCode::F12 => "F12",
-            KeyCode::Space => "Space",
-            KeyCode::Escape => "Esc",
-            KeyCode::Tab => "Tab",
-            KeyCode::Enter => "Enter",
-            _ => "Unknown",
-        };
+        let key_name = KEY_NAMES
+            .iter()
+            .find(|(key, _)| *key == self.key)
+            .map_or("Unknown", |(_, name)| name);
 
         parts.push(key_name);
 
@@ -125,6 +183,184 @@ impl Display for KeyboardShortcut {
     }
 }
 
+impl FromStr for KeyboardShortcut {
+    type Err = String;
+
+    /// Parse text such as `Ctrl+Shift+R`; modifier names are case-insensitive
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::empty();
+        let mut key = None;
+
+        for part in text.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= Modifiers::CTRL,
+                "shift" => modifiers |= Modifiers::SHIFT,
+                "alt" => modifiers |= Modifiers::ALT,
+                "win" | "logo" | "super" => modifiers |= Modifiers::LOGO,
+                _ if key.is_some() => return Err(format!("'{}' names more than one key", text)),
+                name => {
+                    key = KEY_NAMES
+                        .iter()
+                        .find(|(_, known)| known.eq_ignore_ascii_case(name))
+                        .map(|(code, _)| *code);
+                    if key.is_none() {
+                        return Err(format!("Unknown key '{}'", part));
+                    }
+                }
+            }
+        }
+
+        key.map(|key| Self::new(key, modifiers))
+            .ok_or_else(|| format!("'{}' has no key", text))
+    }
+}
+
+/// Message sent by a rebindable action
+pub fn action_message(action: ShortcutAction) -> Message {
+    match action {
+        ShortcutAction::Refresh => Message::StartScan,
+        ShortcutAction::OpenSettings => Message::OpenSettings,
+        ShortcutAction::ToggleViewMode => Message::ToggleViewMode,
+        ShortcutAction::Quit => Message::Exit,
+    }
+}
+
+/// Shortcut an action has until it is rebound
+pub fn default_shortcut(action: ShortcutAction) -> KeyboardShortcut {
+    match action {
+        ShortcutAction::Refresh => KeyboardShortcut::ctrl(KeyCode::R),
+        ShortcutAction::OpenSettings => KeyboardShortcut::ctrl(KeyCode::Comma),
+        ShortcutAction::ToggleViewMode => KeyboardShortcut::ctrl(KeyCode::M),
+        ShortcutAction::Quit => KeyboardShortcut::ctrl(KeyCode::Q),
+    }
+}
+
+/// Shortcut bound to an action, honoring rebinds that parse
+pub fn binding(
+    bindings: &BTreeMap<ShortcutAction, String>,
+    action: ShortcutAction,
+) -> KeyboardShortcut {
+    bindings
+        .get(&action)
+        .and_then(|text| text.parse().ok())
+        .unwrap_or_else(|| default_shortcut(action))
+}
+
+/// Shortcuts that can't be rebound, with what they do
+fn fixed_shortcuts() -> [(KeyboardShortcut, Message, &'static str); 4] {
+    [
+        (
+            KeyboardShortcut::ctrl(KeyCode::H),
+            Message::ToggleVisibility,
+            "Show/hide application window",
+        ),
+        (
+            KeyboardShortcut::ctrl(KeyCode::Period),
+            Message::CloseSettings,
+            "Close settings",
+        ),
+        (
+            KeyboardShortcut::ctrl_shift(KeyCode::S),
+            Message::SaveSettings,
+            "Save settings",
+        ),
+        (
+            KeyboardShortcut::ctrl(KeyCode::B),
+            Message::SpeakBatteryStatus,
+            "Speak battery status",
+        ),
+    ]
+}
+
+/// Keyboard navigation in the main window (see [`crate::ui::accessibility`]), with
+/// what each key does
+fn navigation(key: KeyCode, modifiers: Modifiers) -> Option<(Message, &'static str)> {
+    match (key, modifiers) {
+        (KeyCode::Tab, m) if m.is_empty() => Some((Message::FocusNext, "Move focus forward")),
+        (KeyCode::Tab, Modifiers::SHIFT) => Some((Message::FocusPrevious, "Move focus back")),
+        (KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space, m) if m.is_empty() => {
+            Some((Message::ActivateFocused, "Activate the focused control"))
+        }
+        (KeyCode::Escape, m) if m.is_empty() => Some((Message::EscapePressed, "Close")),
+        _ => None,
+    }
+}
+
+/// What already uses `shortcut`, if binding it to `action` would clash
+pub fn conflict(
+    bindings: &BTreeMap<ShortcutAction, String>,
+    action: ShortcutAction,
+    shortcut: &KeyboardShortcut,
+) -> Option<String> {
+    if let Some((_, _, description)) = fixed_shortcuts()
+        .into_iter()
+        .find(|(fixed, _, _)| fixed == shortcut)
+    {
+        return Some(description.to_string());
+    }
+    if let Some((_, description)) = navigation(shortcut.key, shortcut.modifiers) {
+        return Some(description.to_string());
+    }
+
+    ShortcutAction::ALL
+        .into_iter()
+        .filter(|other| *other != action)
+        .find(|other| binding(bindings, *other) == *shortcut)
+        .map(|other| other.to_string())
+}
+
+/// Rebuild the shortcuts in use from the configured rebinds
+pub fn set_active_bindings(bindings: &BTreeMap<ShortcutAction, String>) {
+    if let Ok(mut active) = ACTIVE_SHORTCUTS.write() {
+        *active = Some(KeyboardShortcutManager::with_bindings(bindings));
+    }
+}
+
+/// Capture the next key press for the shortcut editor instead of running shortcuts
+pub fn set_recording(recording: bool) {
+    RECORDING.store(recording, Ordering::Relaxed);
+}
+
+/// Handle a key press with the shortcuts in use
+///
+/// While recording, the key press is returned as [`Message::ShortcutRecorded`] (Esc
+/// cancels) and no shortcut runs.
+pub fn handle_active_event(event: Event) -> Option<Message> {
+    if RECORDING.load(Ordering::Relaxed) {
+        return match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: KeyCode::Escape,
+                modifiers,
+            }) if modifiers.is_empty() => Some(Message::CancelShortcutRecording),
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if !MODIFIER_KEYS.contains(&key_code) => Some(Message::ShortcutRecorded(
+                KeyboardShortcut::new(key_code, modifiers),
+            )),
+            _ => None,
+        };
+    }
+
+    if let Event::Keyboard(keyboard::Event::KeyPressed {
+        key_code,
+        modifiers,
+    }) = event
+    {
+        if let Some((message, _)) = navigation(key_code, modifiers) {
+            return Some(message);
+        }
+    }
+
+    match ACTIVE_SHORTCUTS.read() {
+        Ok(active) => match active.as_ref() {
+            Some(manager) => manager.handle_event(&event),
+            None => KeyboardShortcutManager::default().handle_event(&event),
+        },
+        Err(_) => None,
+    }
+}
+
 /// Keyboard shortcut manager for the application
 #[derive(Debug, Clone)]
 pub struct KeyboardShortcutManager {
@@ -153,28 +389,26 @@ impl KeyboardShortcutManager {
         self.shortcuts.insert(shortcut, message);
     }
 
+    /// Create a manager with the fixed shortcuts and the given rebinds
+    pub fn with_bindings(bindings: &BTreeMap<ShortcutAction, String>) -> Self {
+        let mut manager = Self::new();
+        manager.register_bindings(bindings);
+        manager
+    }
+
     /// Register the default keyboard shortcuts for the application
     pub fn register_default_shortcuts(&mut self) {
-        // Main application shortcuts
-        self.register(KeyboardShortcut::ctrl(KeyCode::Q), Message::Exit);
-        self.register(
-            KeyboardShortcut::ctrl(KeyCode::H),
-            Message::ToggleVisibility,
-        );
-
-        // Settings shortcuts
-        self.register(
-            KeyboardShortcut::ctrl(KeyCode::Comma),
-            Message::OpenSettings,
-        );
-        self.register(
-            KeyboardShortcut::ctrl(KeyCode::Period),
-            Message::CloseSettings,
-        );
-        self.register(
-            KeyboardShortcut::ctrl_shift(KeyCode::S),
-            Message::SaveSettings,
-        );
+        self.register_bindings(&BTreeMap::new());
+    }
+
+    /// Register the fixed shortcuts, and the rebindable ones with any rebinds applied
+    fn register_bindings(&mut self, bindings: &BTreeMap<ShortcutAction, String>) {
+        for (shortcut, message, _) in fixed_shortcuts() {
+            self.register(shortcut, message);
+        }
+        for action in ShortcutAction::ALL {
+            self.register(binding(bindings, action), action_message(action));
+        }
     }
 
     /// Process keyboard events and generate corresponding messages
@@ -208,10 +442,13 @@ impl KeyboardShortcutManager {
         for (shortcut, message) in &self.shortcuts {
             let description = match message {
                 Message::Exit => "Exit application",
+                Message::StartScan => "Refresh",
+                Message::ToggleViewMode => "Toggle compact view",
                 Message::ToggleVisibility => "Show/hide application window",
                 Message::OpenSettings => "Open settings",
                 Message::CloseSettings => "Close settings",
                 Message::SaveSettings => "Save settings",
+                Message::SpeakBatteryStatus => "Speak battery status",
                 _ => continue, // Skip messages without descriptions
             };
 
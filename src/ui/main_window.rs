@@ -2,23 +2,66 @@
 //!
 //! Implements the main UI window component with device list and battery status display.
 
-use iced::widget::svg::Handle as SvgHandle;
+use std::time::{Duration, Instant, SystemTime};
+
 use iced::{
     alignment::Horizontal,
-    widget::{button, column, container, mouse_area, row, text, Space, Svg},
-    Alignment, Command, Element, Length,
+    widget::{button, column, container, text, Space},
+    Alignment, Color, Command, Element, Length,
 };
 
+use crate::airpods::battery_intelligence::{EstimationAccuracy, MIN_ACCURACY_SAMPLES};
+use crate::airpods::capacity::MahEstimate;
 use crate::airpods::DetectedAirPods;
 use crate::bluetooth::AirPodsBatteryStatus;
-use crate::config::AppConfig;
+use crate::config::{AppConfig, KnownDevices};
+use crate::diagnostics::HealthStatus;
+use crate::error::ActiveError;
+use crate::system::AudioRouting;
+use crate::ui::accessibility::{self, battery_label, BatteryWidget, FocusTarget};
+use crate::ui::battery_animation::BatteryAnimation;
+use crate::ui::data_age;
+use crate::ui::direction;
 use crate::ui::theme;
 use crate::ui::Message;
 use crate::ui::UiComponent;
 
-use crate::ui::components::WaitingMode;
-use crate::ui::state::{DeviceDetectionState, MergedBluetoothDevice};
+use crate::ui::components::usage_panel::format_listening_time;
+use crate::ui::components::{
+    battery_with_label, view_animated_battery_widget, view_device_info_panel, view_error_banner,
+    view_scanner_status, view_software_rendering_notice, view_title_bar, view_undo_notice,
+    view_unknown_model_notice, BatteryWidgetSize, RingAnimation, WaitingMode,
+};
+use crate::ui::state::{ConnectionStatus, DeviceDetectionState, DeviceType, MergedBluetoothDevice};
 use crate::ui::theme::Theme;
+use crate::ui::window_management::{
+    BatteryArrangement, ResponsiveLayout, DEFAULT_WINDOW_HEIGHT, DEFAULT_WINDOW_WIDTH,
+};
+
+/// Which view tree the main window shows for a device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    /// Just the battery percentages
+    Compact,
+    /// Battery widgets with charging, estimates, signal and health
+    #[default]
+    Expanded,
+}
+
+/// Readings only shown in the expanded view
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpandedDetails {
+    /// Whether the left bud, right bud and case are charging
+    pub charging: [bool; 3],
+    /// Estimated time until the left bud, right bud and case reach 10%
+    pub time_to_critical: [Option<Duration>; 3],
+    /// Battery health score (0.0 to 1.0) learned from past readings
+    pub health_score: Option<f32>,
+    /// How close estimates have come to the readings that followed them
+    pub estimation_accuracy: Option<EstimationAccuracy>,
+    /// Charge left in mAh, when turned on and the model's capacity is known
+    pub mah_remaining: Option<MahEstimate>,
+}
 
 /// Main window component
 #[derive(Debug, Clone)]
@@ -49,6 +92,48 @@ pub struct MainWindow {
 
     /// Waiting mode component for when no devices are detected
     pub waiting_mode: WaitingMode,
+
+    /// Audio output routing for the displayed device
+    pub audio_routing: Option<AudioRouting>,
+
+    /// Errors shown in the banner below the title bar
+    pub active_errors: Vec<ActiveError>,
+
+    /// Current window size (logical width, height), used to pick the layout
+    pub window_size: (u32, u32),
+
+    /// Compact or expanded view, kept for the rest of the session
+    pub view_mode: ViewMode,
+
+    /// Readings for the expanded view
+    pub expanded_details: ExpandedDetails,
+
+    /// Notice offering to undo the last settings save
+    pub undo_notice: Option<String>,
+
+    /// Warning shown while the scanner keeps failing
+    pub scanner_status: Option<String>,
+
+    /// Unknown model to ask for a capture of, if any
+    pub unknown_model: Option<u16>,
+
+    /// Ask whether to keep the software renderer this session fell back to
+    pub software_rendering_notice: bool,
+
+    /// Control with keyboard focus, if any
+    pub focused: Option<FocusTarget>,
+
+    /// Result of the last background health check
+    pub health: HealthStatus,
+
+    /// Problems found by the last background health check
+    pub health_problems: usize,
+
+    /// Gliding levels and reconnect flash of the battery widgets
+    pub battery_animation: BatteryAnimation,
+
+    /// Nicknames, icons and colors of the user's AirPods
+    pub known_devices: KnownDevices,
 }
 
 impl Default for MainWindow {
@@ -75,6 +160,20 @@ impl MainWindow {
             show_airpods_dialog: false,
             device_detection_state: DeviceDetectionState::Scanning,
             waiting_mode: WaitingMode::new(),
+            audio_routing: None,
+            active_errors: Vec::new(),
+            window_size: (DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT),
+            view_mode: ViewMode::default(),
+            expanded_details: ExpandedDetails::default(),
+            undo_notice: None,
+            scanner_status: None,
+            unknown_model: None,
+            software_rendering_notice: false,
+            focused: None,
+            health: HealthStatus::Healthy,
+            health_problems: 0,
+            battery_animation: BatteryAnimation::default(),
+            known_devices: KnownDevices::default(),
         }
     }
 
@@ -85,17 +184,76 @@ impl MainWindow {
     }
 
     /// Set the window size and return a new instance
-    pub fn with_window_size(self, _size: (u32, u32)) -> Self {
-        // Window size not directly used in MainWindow, but needed for compatibility
+    pub fn with_window_size(mut self, size: (u32, u32)) -> Self {
+        self.window_size = size;
         self
     }
 
+    /// Start animations for the device now shown: levels glide to its latest readings,
+    /// and the rings flash if it has just come back
+    pub fn sync_battery_animation(&mut self, now: Instant) {
+        let Some(device) = self.shown_device() else {
+            self.battery_animation.set_device(None, now);
+            return;
+        };
+        let address = device.address.clone();
+        let levels = Self::battery_levels(device);
+        self.battery_animation.set_device(Some(&address), now);
+        for (widget, level) in levels {
+            self.battery_animation.set_level(widget, level, now);
+        }
+    }
+
+    /// Whether the battery widgets need frames: something is moving, or a bolt pulses
+    pub fn is_animating(&self, now: Instant) -> bool {
+        self.view_mode == ViewMode::Expanded
+            && self.shown_device().is_some()
+            && self.household_sets().is_empty()
+            && (self.battery_animation.is_animating(now)
+                || self.expanded_details.charging.iter().any(|c| *c))
+    }
+
+    /// Levels the circular widgets show for `device`, preferring fractional estimates
+    fn battery_levels(device: &MergedBluetoothDevice) -> Vec<(BatteryWidget, f32)> {
+        if device.device_type == DeviceType::Other {
+            return vec![(BatteryWidget::Single, device.battery.unwrap_or(0) as f32)];
+        }
+        vec![
+            (
+                BatteryWidget::Left,
+                device
+                    .left_battery_fractional
+                    .unwrap_or(device.left_battery.unwrap_or(0) as f32),
+            ),
+            (
+                BatteryWidget::Right,
+                device
+                    .right_battery_fractional
+                    .unwrap_or(device.right_battery.unwrap_or(0) as f32),
+            ),
+        ]
+    }
+
+    /// Layout for the current window size
+    pub fn layout(&self) -> ResponsiveLayout {
+        ResponsiveLayout::for_size(self.window_size.0, self.window_size.1)
+    }
+
     /// Toggle advanced display mode
     pub fn toggle_advanced_display(&mut self) -> Command<Message> {
         self.advanced_display_mode = !self.advanced_display_mode;
         Command::none()
     }
 
+    /// Switch between the compact and expanded views
+    pub fn toggle_view_mode(&mut self) -> Command<Message> {
+        self.view_mode = match self.view_mode {
+            ViewMode::Compact => ViewMode::Expanded,
+            ViewMode::Expanded => ViewMode::Compact,
+        };
+        Command::none()
+    }
+
     /// Toggle advanced display mode and return a command
     pub fn toggle_display_mode(&mut self) -> Command<Message> {
         let _ = self.toggle_advanced_display();
@@ -152,6 +310,7 @@ impl MainWindow {
     pub fn update_device_detection_state(&mut self, state: DeviceDetectionState) {
         self.device_detection_state = state.clone();
         self.waiting_mode.update_detection_state(state);
+        self.sync_battery_animation(Instant::now());
     }
 
     /// Update waiting mode animation
@@ -159,6 +318,178 @@ impl MainWindow {
         self.waiting_mode.update_animation(progress);
     }
 
+    /// Device whose batteries are shown, if any
+    fn shown_device(&self) -> Option<&MergedBluetoothDevice> {
+        self.merged_devices
+            .first()
+            .filter(|_| self.device_detection_state.has_active_device())
+    }
+
+    /// Name of a device with its icon, and the color to draw it in
+    ///
+    /// Known devices show their nickname; otherwise AirPods fall back to the custom
+    /// device name from the settings.
+    fn device_title(&self, device: &MergedBluetoothDevice) -> (String, Color) {
+        if let Some(known) = self.known_devices.find_address(&device.address) {
+            return (
+                format!("{} {}", known.icon.glyph(), known.display_name()),
+                theme::device_color(known.color),
+            );
+        }
+        let name = self
+            .config
+            .bluetooth
+            .paired_device_name
+            .as_ref()
+            .filter(|_| device.device_type == DeviceType::AirPods)
+            .unwrap_or(&device.name);
+        (name.clone(), theme::TEXT)
+    }
+
+    /// Name of a device for screen readers, without its icon
+    fn spoken_name<'a>(&'a self, device: &'a MergedBluetoothDevice) -> &'a str {
+        self.known_devices
+            .find_address(&device.address)
+            .map_or(device.name.as_str(), |known| known.display_name())
+    }
+
+    /// Sets listed in household mode, or none when a single set is shown
+    ///
+    /// Household mode only changes the view once more than one AirPods set is nearby.
+    fn household_sets(&self) -> Vec<&MergedBluetoothDevice> {
+        if !self.config.ui.household_mode || self.shown_device().is_none() {
+            return Vec::new();
+        }
+        let sets: Vec<&MergedBluetoothDevice> = self
+            .merged_devices
+            .iter()
+            .filter(|device| device.device_type == DeviceType::AirPods)
+            .collect();
+        if sets.len() > 1 {
+            sets
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Controls reachable with Tab, in the order they are drawn
+    pub fn focus_targets(&self) -> Vec<FocusTarget> {
+        let mut targets = Vec::new();
+        if self.health != HealthStatus::Healthy {
+            targets.push(FocusTarget::HealthBadge);
+        }
+        targets.extend([
+            FocusTarget::OpenSettings,
+            FocusTarget::Minimize,
+            FocusTarget::Close,
+        ]);
+        for error in &self.active_errors {
+            if error.recovery.button_label().is_some() {
+                targets.push(FocusTarget::RecoverError(error.id));
+            }
+            targets.push(FocusTarget::DismissError(error.id));
+        }
+
+        let Some(device) = self.shown_device() else {
+            return targets;
+        };
+        let sets = self.household_sets();
+        if !sets.is_empty() {
+            targets.extend((0..sets.len()).map(FocusTarget::HouseholdSet));
+            return targets;
+        }
+        let single = device.device_type == DeviceType::Other;
+        let batteries: &[BatteryWidget] = match (single, self.view_mode) {
+            (true, _) => &[BatteryWidget::Single],
+            (false, ViewMode::Compact) => &[
+                BatteryWidget::Left,
+                BatteryWidget::Right,
+                BatteryWidget::Case,
+            ],
+            (false, ViewMode::Expanded) => &[BatteryWidget::Left, BatteryWidget::Right],
+        };
+        let show_details = self.view_mode == ViewMode::Expanded && self.layout().show_details;
+        if show_details && self.shows_audio_switch() {
+            targets.push(FocusTarget::SwitchAudio);
+        }
+        targets.extend(
+            batteries
+                .iter()
+                .map(|battery| FocusTarget::Battery(*battery)),
+        );
+        if show_details {
+            targets.push(FocusTarget::AdvancedInfo);
+        }
+        targets.push(FocusTarget::ViewMode);
+        targets
+    }
+
+    /// What a screen reader should say for a control
+    pub fn accessible_label(&self, target: FocusTarget) -> String {
+        match target {
+            FocusTarget::HealthBadge => match self.health_problems {
+                1 => "1 problem found, button".to_string(),
+                count => format!("{} problems found, button", count),
+            },
+            FocusTarget::OpenSettings => "Settings, button".to_string(),
+            FocusTarget::Minimize => "Minimize, button".to_string(),
+            FocusTarget::Close => "Close, button".to_string(),
+            FocusTarget::RecoverError(id) | FocusTarget::DismissError(id) => {
+                let error = self.active_errors.iter().find(|error| error.id == id);
+                let message = error.map_or("", |error| error.message.as_str());
+                match (
+                    target,
+                    error.and_then(|error| error.recovery.button_label()),
+                ) {
+                    (FocusTarget::RecoverError(_), Some(label)) => {
+                        format!("{}, button. {}", label, message)
+                    }
+                    _ => format!("Dismiss error, button. {}", message),
+                }
+            }
+            FocusTarget::Battery(widget) => {
+                let device = self.shown_device();
+                let [left, right, case] = self.expanded_details.charging;
+                let (level, charging) = match widget {
+                    BatteryWidget::Left => (device.and_then(|d| d.left_battery), left),
+                    BatteryWidget::Right => (device.and_then(|d| d.right_battery), right),
+                    BatteryWidget::Case => (device.and_then(|d| d.case_battery), case),
+                    BatteryWidget::Single => (device.and_then(|d| d.battery), false),
+                };
+                battery_label(widget, level, charging)
+            }
+            FocusTarget::SwitchAudio => "Switch audio to AirPods, button".to_string(),
+            FocusTarget::AdvancedInfo => if self.advanced_display_mode {
+                "Hide advanced info, button"
+            } else {
+                "Advanced device info, button"
+            }
+            .to_string(),
+            FocusTarget::ViewMode => match self.view_mode {
+                ViewMode::Compact => "Expanded view, button".to_string(),
+                ViewMode::Expanded => "Compact view, button".to_string(),
+            },
+            FocusTarget::HouseholdSet(index) => {
+                let Some(device) = self.household_sets().get(index).copied() else {
+                    return String::new();
+                };
+                let [left, right, case] = device.charging;
+                format!(
+                    "{}: {}; {}; {}",
+                    self.spoken_name(device),
+                    battery_label(BatteryWidget::Left, device.left_battery, left),
+                    battery_label(BatteryWidget::Right, device.right_battery, right),
+                    battery_label(BatteryWidget::Case, device.case_battery, case)
+                )
+            }
+        }
+    }
+
+    /// Whether the control with keyboard focus is `target`
+    fn has_focus(&self, target: FocusTarget) -> bool {
+        self.focused == Some(target)
+    }
+
     // Update the view method to use the helper methods
     fn view_content(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
         crate::debug_log!(
@@ -167,53 +498,13 @@ impl MainWindow {
             self.merged_devices.len()
         );
 
-        // Custom title bar header (Discord-style) - make it draggable
-        let header_row = mouse_area(
-            container(
-                row![
-                    // App logo and title - this area should be draggable
-                    mouse_area(
-                        row![
-                            // App logo icon
-                            container(
-                                Svg::new(SvgHandle::from_memory(crate::assets::app::LOGO_SVG))
-                                    .width(Length::Fixed(24.0))
-                                    .height(Length::Fixed(24.0))
-                            )
-                            .padding([0, 8, 0, 0]), // Right padding to separate from text
-                            // App title/brand
-                            text("RustPods").size(20.0).style(crate::ui::theme::TEXT)
-                        ]
-                        .align_items(Alignment::Center)
-                        .spacing(0)
-                    )
-                    .on_press(Message::WindowDragStart(iced::Point::new(0.0, 0.0))),
-                    Space::with_width(Length::Fill),
-                    // Window controls
-                    button(
-                        Svg::new(SvgHandle::from_memory(crate::assets::ui::SETTINGS_ICON))
-                            .width(Length::Fixed(21.0))
-                            .height(Length::Fixed(21.0))
-                    )
-                    .on_press(Message::OpenSettings)
-                    .style(crate::ui::theme::settings_button_style())
-                    .padding(5),
-                    button(
-                        Svg::new(SvgHandle::from_memory(crate::assets::ui::CLOSE_ICON))
-                            .width(Length::Fixed(21.0))
-                            .height(Length::Fixed(21.0))
-                    )
-                    .on_press(Message::Exit)
-                    .style(crate::ui::theme::close_button_style())
-                    .padding(5)
-                ]
-                .spacing(6)
-                .align_items(Alignment::Center),
-            )
-            .width(Length::Fill)
-            .padding([8, 12, 8, 12]), // Custom title bar padding
-        )
-        .on_press(Message::WindowDragStart(iced::Point::new(0.0, 0.0))); // Make entire title bar draggable
+        // Custom title bar with caption buttons
+        let title = if crate::safe_mode::is_enabled() {
+            "RustPods (Safe Mode)"
+        } else {
+            "RustPods"
+        };
+        let header_row = view_title_bar(title, self.focused, self.health);
 
         // Determine what content to show based on device detection state
         let main_content =
@@ -221,97 +512,13 @@ impl MainWindow {
                 // Show waiting mode when no devices are detected or not connected
                 crate::debug_log!("ui", "No devices detected, showing waiting mode");
                 self.waiting_mode.view()
+            } else if !self.household_sets().is_empty() {
+                self.household_view()
             } else if let Some(device) = self.merged_devices.first() {
-                // Show battery widgets when devices are connected
-                // Use fractional battery levels if available, otherwise fall back to integer levels
-                let left_battery = device
-                    .left_battery_fractional
-                    .unwrap_or(device.left_battery.unwrap_or(0) as f32);
-                let right_battery = device
-                    .right_battery_fractional
-                    .unwrap_or(device.right_battery.unwrap_or(0) as f32);
-
-                crate::debug_log!(
-                    "ui",
-                    "Showing battery UI for device: {} - L:{:.1}% R:{:.1}%",
-                    device.name,
-                    left_battery,
-                    right_battery
-                );
-
-                // Get custom device name from config if available
-                let display_name = self
-                    .config
-                    .bluetooth
-                    .paired_device_name
-                    .as_ref()
-                    .unwrap_or(&device.name);
-
-                // Main layout with device name at top and battery widgets below
-                container(
-                    column![
-                        // Device name at the top
-                        container(
-                            text(display_name)
-                                .size(18)
-                                .style(theme::TEXT)
-                                .horizontal_alignment(Horizontal::Center)
-                        )
-                        .width(Length::Fill)
-                        .center_x()
-                        .padding([0, 0, 15, 0]), // Bottom padding to separate from battery widgets
-                        // Two-column layout: each battery centered in its half of the window
-                        container(
-                            row![
-                                // Left column - Left earbud centered in left half
-                                container(
-                                    column![
-                                        crate::ui::components::view_circular_battery_widget(
-                                            left_battery,
-                                            false // TODO: Add charging status when available
-                                        ),
-                                        text("Left")
-                                            .size(14)
-                                            .style(theme::TEXT)
-                                            .horizontal_alignment(Horizontal::Center)
-                                    ]
-                                    .align_items(Alignment::Center)
-                                    .spacing(5)
-                                )
-                                .width(Length::FillPortion(1))
-                                .center_x(),
-                                // Right column - Right earbud centered in right half
-                                container(
-                                    column![
-                                        crate::ui::components::view_circular_battery_widget(
-                                            right_battery,
-                                            false // TODO: Add charging status when available
-                                        ),
-                                        text("Right")
-                                            .size(14)
-                                            .style(theme::TEXT)
-                                            .horizontal_alignment(Horizontal::Center)
-                                    ]
-                                    .align_items(Alignment::Center)
-                                    .spacing(5)
-                                )
-                                .width(Length::FillPortion(1))
-                                .center_x()
-                            ]
-                            .width(Length::Fill)
-                        )
-                        .width(Length::Fill)
-                        .height(Length::Fill)
-                        .center_x()
-                    ]
-                    .align_items(Alignment::Center)
-                    .spacing(0),
-                )
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .center_x()
-                .center_y()
-                .into()
+                match self.view_mode {
+                    ViewMode::Compact => self.compact_view(device),
+                    ViewMode::Expanded => self.expanded_view(device),
+                }
             } else {
                 // Fallback to waiting mode
                 crate::debug_log!("ui", "Fallback to waiting mode");
@@ -323,6 +530,16 @@ impl MainWindow {
             column![
                 // Title bar stays at the top with proper background
                 header_row,
+                // Persistent errors with their recovery actions
+                view_error_banner(&self.active_errors, self.focused),
+                // Offer to undo a settings save for a few seconds
+                view_undo_notice(self.undo_notice.as_deref()),
+                // Explain why readings have stopped while the scanner is failing
+                view_scanner_status(self.scanner_status.as_deref()),
+                // Ask for a capture of a model the model table doesn't know
+                view_unknown_model_notice(self.unknown_model),
+                // Ask whether to keep software rendering after hardware rendering failed
+                view_software_rendering_notice(self.software_rendering_notice),
                 // Main content (battery widgets or waiting mode) centered in the remaining space
                 container(main_content)
                     .width(Length::Fill)
@@ -336,6 +553,477 @@ impl MainWindow {
         .into()
     }
 
+    /// Expanded view: battery widgets with charging, estimates, signal and health
+    fn expanded_view(
+        &self,
+        device: &'_ MergedBluetoothDevice,
+    ) -> Element<'_, Message, iced::Renderer<Theme>> {
+        // Show battery widgets when devices are connected
+        // Use fractional battery levels if available, otherwise fall back to integer levels
+        let left_battery = device
+            .left_battery_fractional
+            .unwrap_or(device.left_battery.unwrap_or(0) as f32);
+        let right_battery = device
+            .right_battery_fractional
+            .unwrap_or(device.right_battery.unwrap_or(0) as f32);
+
+        crate::debug_log!(
+            "ui",
+            "Showing battery UI for device: {} - L:{:.1}% R:{:.1}%",
+            device.name,
+            left_battery,
+            right_battery
+        );
+
+        // Nickname of a known device, or the custom name from the settings
+        let (display_name, name_color) = self.device_title(device);
+
+        let layout = self.layout();
+        let stale = self.is_stale(device);
+        let [left_charging, right_charging, _] = self.expanded_details.charging;
+        let left = self.battery_column(left_battery, left_charging, BatteryWidget::Left, stale);
+        let right = self.battery_column(right_battery, right_charging, BatteryWidget::Right, stale);
+        let batteries: Element<'_, Message, iced::Renderer<Theme>> =
+            if device.device_type == DeviceType::Other {
+                // GATT devices report a single level for the whole headset
+                self.battery_column(
+                    device.battery.unwrap_or(0) as f32,
+                    false,
+                    BatteryWidget::Single,
+                    stale,
+                )
+            } else {
+                match layout.arrangement {
+                    // Two-column layout: each battery centered in its half of the window
+                    BatteryArrangement::SideBySide => direction::row(vec![
+                        container(left)
+                            .width(Length::FillPortion(1))
+                            .center_x()
+                            .into(),
+                        container(right)
+                            .width(Length::FillPortion(1))
+                            .center_x()
+                            .into(),
+                    ])
+                    .width(Length::Fill)
+                    .into(),
+                    // Narrow windows put the left battery above the right one
+                    BatteryArrangement::Stacked => column![left, right]
+                        .spacing(10)
+                        .align_items(Alignment::Center)
+                        .into(),
+                }
+            };
+
+        // Main layout with device name at top and battery widgets below;
+        // secondary sections are dropped first as the window gets shorter
+        let mut content = column![].align_items(Alignment::Center).spacing(0);
+        if layout.show_device_name {
+            content = content.push(
+                container(
+                    column![
+                        text(display_name)
+                            .size(theme::scaled(18.0))
+                            .style(name_color)
+                            .horizontal_alignment(Horizontal::Center),
+                        self.device_status(device)
+                    ]
+                    .align_items(Alignment::Center)
+                    .spacing(2),
+                )
+                .width(Length::Fill)
+                .center_x()
+                .padding([0, 0, 5, 0]),
+            );
+        }
+        if layout.show_details {
+            // Audio routing badge or switch button
+            content = content.push(
+                container(self.audio_status())
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding([0, 0, 10, 0]), // Bottom padding to separate from battery widgets
+            );
+        }
+        content = content.push(
+            container(batteries)
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .center_x(),
+        );
+        if layout.show_details {
+            // Charging, estimates, signal and health
+            content = content.push(
+                container(self.expanded_details_view(device))
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding([0, 0, 10, 0]),
+            );
+            // Toggle and panel for the extended proximity fields
+            content = content.push(
+                container(self.advanced_info(device))
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding([0, 0, 10, 0]),
+            );
+        }
+        content = content.push(
+            container(view_mode_toggle(
+                ViewMode::Compact,
+                self.has_focus(FocusTarget::ViewMode),
+            ))
+            .width(Length::Fill)
+            .center_x()
+            .padding([0, 0, 10, 0]),
+        );
+
+        container(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Compact view: just the battery percentages on one line
+    fn compact_view(
+        &self,
+        device: &MergedBluetoothDevice,
+    ) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let levels: Vec<(BatteryWidget, &str, Option<u8>)> =
+            if device.device_type == DeviceType::Other {
+                vec![(BatteryWidget::Single, "Battery", device.battery)]
+            } else {
+                vec![
+                    (BatteryWidget::Left, "L", device.left_battery),
+                    (BatteryWidget::Right, "R", device.right_battery),
+                    (BatteryWidget::Case, "Case", device.case_battery),
+                ]
+            };
+
+        let stale = self.is_stale(device);
+        let percentages =
+            direction::row(
+                levels
+                    .into_iter()
+                    .map(|(widget, label, level)| {
+                        accessibility::focus_frame(
+                            column![
+                                text(level.map_or_else(
+                                    || "--".to_string(),
+                                    |level| format!("{}%", level)
+                                ))
+                                .size(theme::scaled(28.0))
+                                .style(if stale {
+                                    theme::OVERLAY1
+                                } else {
+                                    theme::TEXT
+                                }),
+                                text(label).size(theme::scaled(12.0)).style(theme::SUBTEXT1),
+                            ]
+                            .align_items(Alignment::Center)
+                            .spacing(2),
+                            self.has_focus(FocusTarget::Battery(widget)),
+                        )
+                    })
+                    .collect(),
+            )
+            .spacing(20)
+            .align_items(Alignment::Center);
+
+        container(
+            column![
+                percentages,
+                self.last_seen_label(device),
+                view_mode_toggle(ViewMode::Expanded, self.has_focus(FocusTarget::ViewMode))
+            ]
+            .spacing(15)
+            .align_items(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+
+    /// Household view: a battery row for every AirPods set nearby
+    fn household_view(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let rows = self
+            .household_sets()
+            .into_iter()
+            .enumerate()
+            .map(|(index, device)| {
+                let [left_charging, right_charging, case_charging] = device.charging;
+                let (name, name_color) = self.device_title(device);
+                let batteries = direction::row(vec![
+                    battery_with_label(
+                        "L",
+                        device.left_battery,
+                        left_charging,
+                        40.0,
+                        self.animation_progress,
+                    ),
+                    battery_with_label(
+                        "R",
+                        device.right_battery,
+                        right_charging,
+                        40.0,
+                        self.animation_progress,
+                    ),
+                    battery_with_label(
+                        "Case",
+                        device.case_battery,
+                        case_charging,
+                        40.0,
+                        self.animation_progress,
+                    ),
+                ])
+                .spacing(20)
+                .align_items(Alignment::Center);
+                accessibility::focus_frame(
+                    container(
+                        column![
+                            text(name).size(theme::scaled(16.0)).style(name_color),
+                            self.device_status(device),
+                            batteries
+                        ]
+                        .spacing(6)
+                        .align_items(Alignment::Center),
+                    )
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding(8)
+                    .style(theme::device_row_style()),
+                    self.has_focus(FocusTarget::HouseholdSet(index)),
+                )
+            });
+
+        container(
+            iced::widget::scrollable(
+                rows.fold(column![].spacing(10), |list, row| list.push(row))
+                    .padding([0, 10]),
+            )
+            .height(Length::Shrink),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+    }
+
+    /// Charging, time-to-critical estimates, signal strength and health
+    fn expanded_details_view(
+        &self,
+        device: &MergedBluetoothDevice,
+    ) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let details = &self.expanded_details;
+        let mut lines: Vec<String> = Vec::new();
+
+        let charging: Vec<&str> = ["Left", "Right", "Case"]
+            .into_iter()
+            .zip(details.charging)
+            .filter_map(|(component, charging)| charging.then_some(component))
+            .collect();
+        if !charging.is_empty() {
+            lines.push(format!("Charging: {}", charging.join(", ")));
+        }
+
+        let estimates: Vec<String> = ["L", "R", "Case"]
+            .into_iter()
+            .zip(details.time_to_critical)
+            .filter_map(|(component, remaining)| {
+                remaining
+                    .map(|remaining| format!("{} {}", component, format_listening_time(remaining)))
+            })
+            .collect();
+        if !estimates.is_empty() {
+            lines.push(format!("Until 10%: {}", estimates.join("  ")));
+        }
+
+        if let Some(mah) = details.mah_remaining {
+            lines.push(format!("About {}", mah));
+        }
+
+        let mut signal_and_health = Vec::new();
+        if let Some(rssi) = device.rssi {
+            signal_and_health.push(format!("Signal {} dBm", rssi));
+        }
+        if let Some(score) = details.health_score {
+            signal_and_health.push(format!("Health {:.0}%", score * 100.0));
+        }
+        if !signal_and_health.is_empty() {
+            lines.push(signal_and_health.join("  ·  "));
+        }
+
+        // Compared with showing the last reading as is, so it's clear whether estimating helps
+        if let Some(earbuds) = details
+            .estimation_accuracy
+            .map(|accuracy| accuracy.earbuds())
+            .filter(|earbuds| earbuds.samples >= MIN_ACCURACY_SAMPLES)
+        {
+            if let (Some(error), Some(bias), Some(baseline)) = (
+                earbuds.mean_absolute_error(),
+                earbuds.bias(),
+                earbuds.baseline_mean_absolute_error(),
+            ) {
+                lines.push(format!(
+                    "Estimates within ±{:.1}% (bias {:+.1}%), last reading ±{:.1}%",
+                    error, bias, baseline
+                ));
+            }
+        }
+
+        lines
+            .into_iter()
+            .fold(column![].spacing(2), |details, line| {
+                details.push(text(line).size(theme::scaled(12.0)).style(theme::SUBTEXT1))
+            })
+            .align_items(Alignment::Center)
+            .into()
+    }
+
+    /// A battery widget with its label underneath
+    fn battery_column(
+        &self,
+        level: f32,
+        is_charging: bool,
+        widget: BatteryWidget,
+        stale: bool,
+    ) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let label = match widget {
+            BatteryWidget::Left => "Left",
+            BatteryWidget::Right => "Right",
+            BatteryWidget::Case => "Case",
+            BatteryWidget::Single => "Battery",
+        };
+        let now = Instant::now();
+        let animation = RingAnimation {
+            fill: Some(self.battery_animation.level(widget, level, now)),
+            bolt_opacity: RingAnimation::pulse_opacity(self.animation_progress),
+            flash: self.battery_animation.flash(now),
+            stale,
+        };
+        accessibility::focus_frame(
+            column![
+                view_animated_battery_widget(
+                    level,
+                    is_charging,
+                    BatteryWidgetSize::Main,
+                    animation
+                ),
+                text(label)
+                    .size(theme::scaled(14.0))
+                    .style(theme::TEXT)
+                    .horizontal_alignment(Horizontal::Center)
+            ]
+            .align_items(Alignment::Center)
+            .spacing(5),
+            self.has_focus(FocusTarget::Battery(widget)),
+        )
+    }
+
+    /// Whether the readings of `device` are old enough to be grayed out
+    fn is_stale(&self, device: &MergedBluetoothDevice) -> bool {
+        data_age::is_stale(
+            device,
+            self.config.ui.stale_after_minutes,
+            SystemTime::now(),
+        )
+    }
+
+    /// Connection state, with when the device was last heard once its readings are stale
+    fn device_status(
+        &self,
+        device: &MergedBluetoothDevice,
+    ) -> Element<'static, Message, iced::Renderer<Theme>> {
+        column![
+            connection_label(device.connection_status()),
+            self.last_seen_label(device)
+        ]
+        .align_items(Alignment::Center)
+        .spacing(2)
+        .into()
+    }
+
+    /// "last seen 12 min ago" for stale readings, nothing otherwise
+    fn last_seen_label(
+        &self,
+        device: &MergedBluetoothDevice,
+    ) -> Element<'static, Message, iced::Renderer<Theme>> {
+        if !self.is_stale(device) {
+            return column![].into();
+        }
+        text(data_age::last_seen_text(data_age::age(
+            device,
+            SystemTime::now(),
+        )))
+        .size(theme::scaled(12.0))
+        .style(theme::OVERLAY1)
+        .horizontal_alignment(Horizontal::Center)
+        .into()
+    }
+
+    /// Whether the audio status offers to switch audio to the AirPods
+    fn shows_audio_switch(&self) -> bool {
+        self.audio_routing.as_ref().is_some_and(|routing| {
+            !routing.is_default_output
+                && routing.endpoint.is_some()
+                && self.config.ui.show_audio_switch_button
+        })
+    }
+
+    /// Show whether the device is the active audio output, or offer to make it so
+    fn audio_status(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
+        match &self.audio_routing {
+            Some(routing) if routing.is_default_output => text("Connected for audio")
+                .size(theme::scaled(12.0))
+                .style(theme::GREEN)
+                .horizontal_alignment(Horizontal::Center)
+                .into(),
+            Some(_) if self.shows_audio_switch() => {
+                button(text("Switch audio to AirPods").size(theme::scaled(12.0)))
+                    .on_press(Message::SwitchAudioToAirPods)
+                    .style(accessibility::button_style(
+                        theme::secondary_button_style(),
+                        self.has_focus(FocusTarget::SwitchAudio),
+                    ))
+                    .padding([2, 8])
+                    .into()
+            }
+            _ => Space::with_height(Length::Fixed(0.0)).into(),
+        }
+    }
+
+    /// Advanced device info toggle, with the panel below it when expanded
+    fn advanced_info(
+        &self,
+        device: &MergedBluetoothDevice,
+    ) -> Element<'_, Message, iced::Renderer<Theme>> {
+        let label = if self.advanced_display_mode {
+            "Hide advanced info"
+        } else {
+            "Advanced device info"
+        };
+        let toggle = button(text(label).size(theme::scaled(12.0)))
+            .on_press(Message::ToggleAdvancedInfo)
+            .style(accessibility::button_style(
+                theme::secondary_button_style(),
+                self.has_focus(FocusTarget::AdvancedInfo),
+            ))
+            .padding([2, 8]);
+
+        if self.advanced_display_mode {
+            column![toggle, view_device_info_panel(device)]
+                .spacing(8)
+                .align_items(Alignment::Center)
+                .into()
+        } else {
+            toggle.into()
+        }
+    }
+
     /// Create a simple battery bar indicator
     #[allow(dead_code)]
     fn create_simple_battery_bar(
@@ -347,18 +1035,47 @@ impl MainWindow {
         // Simple battery indicator using text
         let battery_text = "█".repeat((level / 10) as usize);
         text(battery_text)
-            .size(12)
-            .style(if level > 20 {
-                theme::GREEN
-            } else if level > 10 {
-                theme::YELLOW
-            } else {
-                theme::RED
-            })
+            .size(theme::scaled(12.0))
+            .style(crate::ui::palette::state_color(
+                crate::ui::palette::BatteryState::of(Some(level), false),
+            ))
             .into()
     }
 }
 
+/// Whether the AirPods are connected to this PC or only in range
+fn connection_label(status: ConnectionStatus) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let label = match status {
+        ConnectionStatus::Connected => "\u{25cf} Connected".to_string(),
+        ConnectionStatus::Nearby => "\u{25cb} Nearby, not connected".to_string(),
+        ConnectionStatus::NotFound => status.to_string(),
+    };
+    text(label)
+        .size(theme::scaled(12.0))
+        .style(theme::connection_color(status))
+        .horizontal_alignment(Horizontal::Center)
+        .into()
+}
+
+/// Button switching to the given view mode
+fn view_mode_toggle(
+    target: ViewMode,
+    focused: bool,
+) -> Element<'static, Message, iced::Renderer<Theme>> {
+    let label = match target {
+        ViewMode::Compact => "Compact view",
+        ViewMode::Expanded => "Expanded view",
+    };
+    button(text(label).size(theme::scaled(12.0)))
+        .on_press(Message::ToggleViewMode)
+        .style(accessibility::button_style(
+            theme::secondary_button_style(),
+            focused,
+        ))
+        .padding([2, 8])
+        .into()
+}
+
 impl UiComponent for MainWindow {
     fn view(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
         // Return content directly without any wrapper - use full window space
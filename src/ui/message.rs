@@ -1,10 +1,22 @@
 use std::fmt::Debug;
+use std::path::PathBuf;
 
-use crate::airpods::{battery::AirPodsBatteryInfo, DetectedAirPods};
+use crate::airpods::{battery::AirPodsBatteryInfo, DetectedAirPods, ProfileSnapshot};
 use crate::bluetooth::AirPodsBatteryStatus;
+use crate::bluetooth::ClassicDevice;
 use crate::bluetooth::DiscoveredDevice;
-use crate::config::AppConfig;
-use crate::ui::components::{BluetoothSetting, SystemSetting, UiSetting};
+use crate::config::{AppConfig, RepairAction, ShortcutAction};
+use crate::diagnostics::DiagnosticResult;
+use crate::error::BluetoothError;
+use crate::issue_report::IssueReport;
+use crate::known_issues::KnownIssueIndex;
+use crate::system::AudioRouting;
+use crate::ui::components::{
+    ActionSetting, BluetoothSetting, DeviceEdit, Dialog, RuleDraft, SyntheticFrameDraft,
+    SystemSetting, UiSetting,
+};
+use crate::ui::file_dialogs::FileAction;
+use crate::ui::keyboard_shortcuts::KeyboardShortcut;
 use crate::ui::state::MergedBluetoothDevice;
 use crate::ui::state_manager::ConnectionState;
 use iced::Point;
@@ -24,9 +36,19 @@ pub enum Message {
     /// Force quit the application (ignores minimize to tray setting)
     ForceQuit,
 
+    /// Start a new RustPods process and close this one; in safe mode when `true`
+    Restart(bool),
+
+    /// Stop running in the tray only and start again with the window
+    LeaveTrayOnly,
+
     /// No operation - used internally for subscription management
     NoOp,
 
+    /// The controller channel closed and was replaced; the system tray and power monitor
+    /// need the new sender
+    ControllerChannelRestored(tokio::sync::mpsc::UnboundedSender<Message>),
+
     /// New Bluetooth device discovered
     DeviceDiscovered(DiscoveredDevice),
 
@@ -81,6 +103,9 @@ pub enum Message {
     /// Update a UI setting
     UpdateUiSetting(UiSetting),
 
+    /// Update an action rule setting
+    UpdateActionSetting(ActionSetting),
+
     /// Update a system setting
     UpdateSystemSetting(SystemSetting),
 
@@ -108,9 +133,18 @@ pub enum Message {
     /// Window bounds changed
     WindowBoundsChanged(iced::Rectangle),
 
+    /// Window resized by the user (logical width, height)
+    WindowResized(u32, u32),
+
     /// Window close requested
     WindowCloseRequested,
 
+    /// Minimize the application window (title bar button)
+    MinimizeWindow,
+
+    /// Switch the main window between the compact and expanded views
+    ToggleViewMode,
+
     /// Window minimized
     WindowMinimized,
 
@@ -144,12 +178,33 @@ pub enum Message {
     /// Battery update failed with error message
     BatteryUpdateFailed(String),
 
+    /// Run the scanner now instead of waiting out its backoff
+    RetryScanner,
+
+    /// Open or close the scanner status panel in settings
+    ToggleScannerStatus,
+
+    /// Limit scanning to one canonical address, or go back to every device
+    FocusScan(Option<String>),
+
     /// Toggle auto scan setting
     ToggleAutoScan(bool),
 
     /// Unpair the current device
     UnpairDevice,
 
+    /// Pair with the AirPods at a canonical address from the latest scan
+    PairDevice(String),
+
+    /// Change a known device (carries its stable ID)
+    EditKnownDevice(String, DeviceEdit),
+
+    /// Watch for a Bluetooth address or stable device ID typed in by the user
+    TrackDeviceAddress(String),
+
+    /// Stop watching a canonical address added with `TrackDeviceAddress`
+    UntrackDeviceAddress(String),
+
     /// AirPods data loaded from CLI scanner (async)
     AirPodsDataLoaded(Vec<AirPodsBatteryInfo>),
 
@@ -171,14 +226,185 @@ pub enum Message {
     /// Device scan failed
     ScanFailed(String),
 
+    /// The scan was skipped because the radio is off or may not be used
+    BluetoothUnavailable(BluetoothError),
+
     /// Set custom device name
     SetDeviceName(String),
 
+    /// Edit the address or name pattern for a new allow/deny list entry
+    DeviceFilterInputChanged(String),
+
+    /// Edit the action rule being composed in settings
+    RuleDraftChanged(RuleDraft),
+
+    /// Edit the synthetic frame being composed in developer mode
+    SyntheticDraftChanged(SyntheticFrameDraft),
+
+    /// Decode a synthetic frame and add it to every scan result
+    InjectSyntheticFrame(SyntheticFrameDraft),
+
+    /// Remove every injected synthetic frame
+    ClearSyntheticFrames,
+
+    /// Wait for the key combination to bind to an action
+    RecordShortcut(ShortcutAction),
+
+    /// Key combination pressed while recording a shortcut
+    ShortcutRecorded(KeyboardShortcut),
+
+    /// Stop recording a shortcut without changing it
+    CancelShortcutRecording,
+
+    /// Restore the settings as they were when the settings window opened
+    RevertSettings,
+
+    /// Restore the settings from before the last save
+    UndoSettingsSave,
+
+    /// Auto-dismiss timer for the undo notice fired (carries the notice generation)
+    DismissUndo(u64),
+
+    /// Hide the notice asking for a capture of an unknown model
+    DismissUnknownModel,
+
+    /// Keep using the software renderer this session fell back to
+    KeepSoftwareRendering,
+
+    /// Hide the software rendering notice without changing the settings
+    DismissSoftwareRenderingNotice,
+
     /// Open battery intelligence profile folder
     OpenProfileFolder,
 
     /// Purge all battery intelligence profiles (reset)
     PurgeProfiles,
+
+    /// System is about to suspend (sleep or hibernate)
+    SystemSuspended,
+
+    /// System resumed from suspend
+    SystemResumed,
+
+    /// Frame of the battery widget animations
+    BatteryAnimationFrame,
+
+    /// Speak the current battery status aloud
+    SpeakBatteryStatus,
+
+    /// Move keyboard focus to the next control in the main window
+    FocusNext,
+
+    /// Move keyboard focus to the previous control in the main window
+    FocusPrevious,
+
+    /// Activate the control with keyboard focus
+    ActivateFocused,
+
+    /// Close the dialog, popup or screen on top
+    EscapePressed,
+
+    /// Auto-dismiss timer for the lid-open popup fired (carries the popup generation)
+    DismissLidPopup(u64),
+
+    /// Audio routing for the current device was refreshed
+    AudioRoutingUpdated(Option<AudioRouting>),
+
+    /// Classic Bluetooth connection state was refreshed (None if it can't be queried)
+    ClassicConnectionsUpdated(Option<Vec<ClassicDevice>>),
+
+    /// Make the AirPods the default audio output
+    SwitchAudioToAirPods,
+
+    /// Show or hide the advanced device info panel
+    ToggleAdvancedInfo,
+
+    /// Dismiss an error from the error banner
+    DismissError(u64),
+
+    /// Run the recovery action for an error in the error banner
+    RecoverError(u64),
+
+    /// Re-check the day/night theme schedule
+    ThemeScheduleTick,
+
+    /// Show the locally collected telemetry data
+    ShowTelemetryData,
+
+    /// Close the telemetry data screen
+    CloseTelemetryData,
+
+    /// Delete the locally collected telemetry data
+    ClearTelemetryData,
+
+    /// Run the background health check
+    HealthCheckTick,
+
+    /// The background health check finished (None if it crashed)
+    HealthChecked(Option<Box<DiagnosticResult>>),
+
+    /// Show the problems found by the health check
+    ShowTroubleshoot,
+
+    /// Close the troubleshoot screen
+    CloseTroubleshoot,
+
+    /// Show a dialog in place of the current screen
+    ShowDialog(Box<Dialog>),
+
+    /// Text of the open input dialog changed
+    DialogInputChanged(String),
+
+    /// The open dialog was confirmed or its text submitted
+    DialogConfirmed,
+
+    /// An option of the open choice dialog was picked (carries its index)
+    DialogChose(usize),
+
+    /// The open dialog was dismissed without an answer
+    DialogCancelled,
+
+    /// Restore the default settings, optionally keeping the paired AirPods
+    ResetSettings { keep_pairing: bool },
+
+    /// Repair a settings file that failed to load
+    RepairSettings(RepairAction),
+
+    /// Ask for the file to import from or export to
+    ChooseFile(FileAction),
+
+    /// Gather a problem report and open a new GitHub issue with it
+    ReportProblem,
+
+    /// Problem report gathered and ready to send
+    ProblemReportReady(Box<IssueReport>),
+
+    /// Open a web page, such as a known issue's workaround
+    OpenLink(String),
+
+    /// Known issues download finished (carries the new index, or `None` if there was none)
+    KnownIssuesRefreshed(Option<Box<KnownIssueIndex>>),
+
+    /// File dialog closed (carries the path, or `None` if cancelled)
+    FileChosen(FileAction, Option<PathBuf>),
+
+    /// A file was dropped onto the window
+    FileDropped(PathBuf),
+
+    /// Replace the battery profile with the backup at the path
+    RestoreBatteryProfile(PathBuf),
+
+    /// Replace all application data with the backup at the path
+    RestoreAllData(PathBuf),
+
+    /// Ask before putting the battery data back the way it was in a snapshot
+    ConfirmRestoreProfileSnapshot(ProfileSnapshot),
+
+    /// Put the battery data back the way it was in a snapshot
+    RestoreProfileSnapshot(ProfileSnapshot),
+
+    /// A scheduled backup finished, or failed with the error
+    ScheduledBackupFinished(Result<crate::backup::ScheduledBackup, String>),
 }
 
 impl PartialEq for Message {
@@ -188,7 +414,11 @@ impl PartialEq for Message {
             (Self::ToggleWindow, Self::ToggleWindow) => true,
             (Self::Exit, Self::Exit) => true,
             (Self::ForceQuit, Self::ForceQuit) => true,
+            (Self::Restart(a), Self::Restart(b)) => a == b,
             (Self::NoOp, Self::NoOp) => true,
+            (Self::ControllerChannelRestored(a), Self::ControllerChannelRestored(b)) => {
+                a.same_channel(b)
+            }
             (Self::DeviceDiscovered(a), Self::DeviceDiscovered(b)) => a == b,
             (Self::DeviceUpdated(a), Self::DeviceUpdated(b)) => a == b,
             (Self::SelectDevice(a), Self::SelectDevice(b)) => a == b,
@@ -206,8 +436,23 @@ impl PartialEq for Message {
             (Self::MergedScanResult(a), Self::MergedScanResult(b)) => a.len() == b.len(),
             (Self::SettingsChanged(a), Self::SettingsChanged(b)) => a == b,
             (Self::UpdateBluetoothSetting(a), Self::UpdateBluetoothSetting(b)) => a == b,
+            (Self::DeviceFilterInputChanged(a), Self::DeviceFilterInputChanged(b)) => a == b,
+            (Self::RuleDraftChanged(a), Self::RuleDraftChanged(b)) => a == b,
+            (Self::SyntheticDraftChanged(a), Self::SyntheticDraftChanged(b)) => a == b,
+            (Self::InjectSyntheticFrame(a), Self::InjectSyntheticFrame(b)) => a == b,
+            (Self::ClearSyntheticFrames, Self::ClearSyntheticFrames) => true,
+            (Self::RecordShortcut(a), Self::RecordShortcut(b)) => a == b,
+            (Self::ShortcutRecorded(a), Self::ShortcutRecorded(b)) => a == b,
+            (Self::CancelShortcutRecording, Self::CancelShortcutRecording) => true,
+            (Self::RevertSettings, Self::RevertSettings) => true,
+            (Self::UndoSettingsSave, Self::UndoSettingsSave) => true,
+            (Self::DismissUndo(a), Self::DismissUndo(b)) => a == b,
+            (Self::DismissUnknownModel, Self::DismissUnknownModel) => true,
+            (Self::KeepSoftwareRendering, Self::KeepSoftwareRendering) => true,
+            (Self::DismissSoftwareRenderingNotice, Self::DismissSoftwareRenderingNotice) => true,
             (Self::UpdateUiSetting(a), Self::UpdateUiSetting(b)) => a == b,
             (Self::UpdateSystemSetting(a), Self::UpdateSystemSetting(b)) => a == b,
+            (Self::UpdateActionSetting(a), Self::UpdateActionSetting(b)) => a == b,
             (Self::OpenSettings, Self::OpenSettings) => true,
             (Self::SaveSettings, Self::SaveSettings) => true,
             (Self::CloseSettings, Self::CloseSettings) => true,
@@ -216,7 +461,10 @@ impl PartialEq for Message {
             (Self::WindowDragMove(a), Self::WindowDragMove(b)) => a == b,
             (Self::WindowPositionChanged(a), Self::WindowPositionChanged(b)) => a == b,
             (Self::WindowBoundsChanged(a), Self::WindowBoundsChanged(b)) => a == b,
+            (Self::WindowResized(a, b), Self::WindowResized(c, d)) => a == c && b == d,
             (Self::WindowCloseRequested, Self::WindowCloseRequested) => true,
+            (Self::MinimizeWindow, Self::MinimizeWindow) => true,
+            (Self::ToggleViewMode, Self::ToggleViewMode) => true,
             (Self::WindowMinimized, Self::WindowMinimized) => true,
             (Self::WindowRestored, Self::WindowRestored) => true,
             (Self::WindowMaximized, Self::WindowMaximized) => true,
@@ -230,6 +478,13 @@ impl PartialEq for Message {
             (Self::BatteryUpdateFailed(a), Self::BatteryUpdateFailed(b)) => a == b,
             (Self::ToggleAutoScan(a), Self::ToggleAutoScan(b)) => a == b,
             (Self::UnpairDevice, Self::UnpairDevice) => true,
+            (Self::PairDevice(a), Self::PairDevice(b)) => a == b,
+            (Self::EditKnownDevice(a, x), Self::EditKnownDevice(b, y)) => a == b && x == y,
+            (Self::RetryScanner, Self::RetryScanner) => true,
+            (Self::ToggleScannerStatus, Self::ToggleScannerStatus) => true,
+            (Self::FocusScan(a), Self::FocusScan(b)) => a == b,
+            (Self::TrackDeviceAddress(a), Self::TrackDeviceAddress(b)) => a == b,
+            (Self::UntrackDeviceAddress(a), Self::UntrackDeviceAddress(b)) => a == b,
             (Self::AirPodsDataLoaded(a), Self::AirPodsDataLoaded(b)) => a.len() == b.len(),
             (Self::ClosePopup, Self::ClosePopup) => true,
             (Self::ConnectDevice, Self::ConnectDevice) => true,
@@ -238,6 +493,57 @@ impl PartialEq for Message {
 
             (Self::ScanCompleted, Self::ScanCompleted) => true,
             (Self::ScanFailed(a), Self::ScanFailed(b)) => a == b,
+            (Self::BluetoothUnavailable(a), Self::BluetoothUnavailable(b)) => {
+                a.to_string() == b.to_string()
+            }
+            (Self::SystemSuspended, Self::SystemSuspended) => true,
+            (Self::SystemResumed, Self::SystemResumed) => true,
+            (Self::BatteryAnimationFrame, Self::BatteryAnimationFrame) => true,
+            (Self::SpeakBatteryStatus, Self::SpeakBatteryStatus) => true,
+            (Self::FocusNext, Self::FocusNext) => true,
+            (Self::FocusPrevious, Self::FocusPrevious) => true,
+            (Self::ActivateFocused, Self::ActivateFocused) => true,
+            (Self::EscapePressed, Self::EscapePressed) => true,
+            (Self::DismissLidPopup(a), Self::DismissLidPopup(b)) => a == b,
+            (Self::AudioRoutingUpdated(a), Self::AudioRoutingUpdated(b)) => a == b,
+            (Self::ClassicConnectionsUpdated(a), Self::ClassicConnectionsUpdated(b)) => a == b,
+            (Self::SwitchAudioToAirPods, Self::SwitchAudioToAirPods) => true,
+            (Self::ToggleAdvancedInfo, Self::ToggleAdvancedInfo) => true,
+            (Self::DismissError(a), Self::DismissError(b)) => a == b,
+            (Self::RecoverError(a), Self::RecoverError(b)) => a == b,
+            (Self::ThemeScheduleTick, Self::ThemeScheduleTick) => true,
+            (Self::ShowTelemetryData, Self::ShowTelemetryData) => true,
+            (Self::CloseTelemetryData, Self::CloseTelemetryData) => true,
+            (Self::ClearTelemetryData, Self::ClearTelemetryData) => true,
+            (Self::HealthCheckTick, Self::HealthCheckTick) => true,
+            (Self::HealthChecked(a), Self::HealthChecked(b)) => {
+                a.as_ref().map(|r| r.timestamp) == b.as_ref().map(|r| r.timestamp)
+            }
+            (Self::ShowTroubleshoot, Self::ShowTroubleshoot) => true,
+            (Self::CloseTroubleshoot, Self::CloseTroubleshoot) => true,
+            (Self::ShowDialog(a), Self::ShowDialog(b)) => a.title == b.title,
+            (Self::DialogInputChanged(a), Self::DialogInputChanged(b)) => a == b,
+            (Self::DialogConfirmed, Self::DialogConfirmed) => true,
+            (Self::DialogChose(a), Self::DialogChose(b)) => a == b,
+            (Self::DialogCancelled, Self::DialogCancelled) => true,
+            (Self::ChooseFile(a), Self::ChooseFile(b)) => a == b,
+            (Self::ReportProblem, Self::ReportProblem) => true,
+            (Self::ProblemReportReady(a), Self::ProblemReportReady(b)) => a == b,
+            (Self::OpenLink(a), Self::OpenLink(b)) => a == b,
+            (Self::KnownIssuesRefreshed(a), Self::KnownIssuesRefreshed(b)) => a == b,
+            (Self::FileChosen(a, b), Self::FileChosen(c, d)) => a == c && b == d,
+            (Self::FileDropped(a), Self::FileDropped(b)) => a == b,
+            (Self::RestoreBatteryProfile(a), Self::RestoreBatteryProfile(b)) => a == b,
+            (Self::RestoreAllData(a), Self::RestoreAllData(b)) => a == b,
+            (Self::ConfirmRestoreProfileSnapshot(a), Self::ConfirmRestoreProfileSnapshot(b)) => {
+                a == b
+            }
+            (Self::RestoreProfileSnapshot(a), Self::RestoreProfileSnapshot(b)) => a == b,
+            (Self::ScheduledBackupFinished(a), Self::ScheduledBackupFinished(b)) => a == b,
+            (Self::ResetSettings { keep_pairing: a }, Self::ResetSettings { keep_pairing: b }) => {
+                a == b
+            }
+            (Self::RepairSettings(a), Self::RepairSettings(b)) => a == b,
             _ => false,
         }
     }
@@ -1,19 +1,30 @@
 //! UI module for the application
 
 // Module exports
+pub mod accessibility;
 mod app;
+pub mod battery_animation;
 pub mod components;
+pub mod data_age;
+pub mod direction;
+pub mod file_dialogs;
+pub mod file_drop;
+pub mod fonts;
+pub mod headline;
 mod message;
 pub mod state;
 pub mod state_manager;
 mod system_tray;
-// mod system_tray_controller; // Keep controller disabled for now
+pub mod system_tray_controller;
 pub mod form_validation;
 pub mod keyboard_shortcuts;
 mod main_window;
+pub mod palette;
+pub mod render_backend;
 mod settings_window;
 pub mod test_helpers;
 pub mod theme;
+pub mod theme_schedule;
 pub mod utils;
 pub mod window_management;
 pub mod window_visibility;
@@ -23,10 +34,10 @@ pub use app::{run_ui, run_ui_with_options};
 pub use message::Message;
 pub use state::AppState;
 pub use system_tray::SystemTray;
-// pub use system_tray_controller::SystemTrayController; // Keep controller disabled
+pub use system_tray_controller::SystemTrayController;
 pub use form_validation::{FormValidator, ValidationRule};
 pub use keyboard_shortcuts::{handle_events, KeyboardShortcut, KeyboardShortcutManager};
-pub use main_window::MainWindow;
+pub use main_window::{ExpandedDetails, MainWindow, ViewMode};
 pub use settings_window::SettingsWindow;
 pub use state_manager::StateManager;
 pub use window_management::{DragRegion, WindowInteraction};
@@ -0,0 +1,190 @@
+//! Battery state colors, including palettes for color vision deficiencies
+//!
+//! Battery levels are shown on a low / medium / good ramp with a separate color for
+//! charging. The standard ramp runs from red to green, the pair people with red-green
+//! color blindness confuse, so alternative palettes drawn from the Okabe-Ito and IBM
+//! color-blind safe sets can be selected. With shape encoding on, every state also gets
+//! its own mark, so no state is told apart by hue alone.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use iced::Color;
+
+use crate::config::{AppConfig, BatteryPalette};
+
+/// Levels at or below this are low
+pub const LOW_LEVEL: u8 = 20;
+
+/// Levels at or below this (and above [`LOW_LEVEL`]) are medium
+pub const MEDIUM_LEVEL: u8 = 50;
+
+/// Selected palette, as an index into [`BatteryPalette::ALL`]
+static PALETTE: AtomicU8 = AtomicU8::new(0);
+
+/// Whether states are marked with shapes as well as color
+static SHAPE_ENCODING: AtomicBool = AtomicBool::new(false);
+
+/// State a battery indicator shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryState {
+    /// Level not reported
+    Unknown,
+    /// At or below [`LOW_LEVEL`]
+    Low,
+    /// At or below [`MEDIUM_LEVEL`]
+    Medium,
+    /// Above [`MEDIUM_LEVEL`]
+    Good,
+    /// Charging, whatever the level
+    Charging,
+}
+
+impl BatteryState {
+    /// State of a component with this level and charging flag
+    pub fn of(level: Option<u8>, charging: bool) -> Self {
+        match level {
+            _ if charging => Self::Charging,
+            None => Self::Unknown,
+            Some(level) if level <= LOW_LEVEL => Self::Low,
+            Some(level) if level <= MEDIUM_LEVEL => Self::Medium,
+            Some(_) => Self::Good,
+        }
+    }
+
+    /// Mark shown next to the level when shape encoding is on
+    pub fn glyph(self) -> &'static str {
+        match self {
+            Self::Unknown => "○",
+            Self::Low => "▲",
+            Self::Medium => "◐",
+            Self::Good => "●",
+            Self::Charging => "⚡",
+        }
+    }
+}
+
+/// Apply the palette and shape encoding from the config
+pub fn apply(config: &AppConfig) {
+    set_battery_palette(config.ui.battery_palette);
+    set_shape_encoding(config.ui.shape_encoding);
+}
+
+/// Set the palette every battery indicator uses
+pub fn set_battery_palette(palette: BatteryPalette) {
+    let index = BatteryPalette::ALL
+        .iter()
+        .position(|candidate| *candidate == palette)
+        .unwrap_or_default();
+    PALETTE.store(index as u8, Ordering::Relaxed);
+}
+
+/// Current palette
+pub fn battery_palette() -> BatteryPalette {
+    BatteryPalette::ALL
+        .get(PALETTE.load(Ordering::Relaxed) as usize)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Turn shape marks on or off
+pub fn set_shape_encoding(enabled: bool) {
+    SHAPE_ENCODING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether states are marked with shapes as well as color
+pub fn shape_encoding() -> bool {
+    SHAPE_ENCODING.load(Ordering::Relaxed)
+}
+
+/// RGB components of a state in a palette
+fn rgb(palette: BatteryPalette, state: BatteryState) -> [u8; 3] {
+    use BatteryState::*;
+
+    match (palette, state) {
+        // Shared by every palette: Catppuccin overlay
+        (_, Unknown) => [0x7f, 0x84, 0x9c],
+
+        // Catppuccin red, yellow, green and blue
+        (BatteryPalette::Standard, Low) => [0xf3, 0x8b, 0xa8],
+        (BatteryPalette::Standard, Medium) => [0xf9, 0xe2, 0xaf],
+        (BatteryPalette::Standard, Good) => [0xa6, 0xe3, 0xa1],
+        (BatteryPalette::Standard, Charging) => [0x89, 0xb4, 0xfa],
+
+        // IBM: orange, gold and blue, with magenta for charging
+        (BatteryPalette::Deuteranopia, Low) => [0xfe, 0x61, 0x00],
+        (BatteryPalette::Deuteranopia, Medium) => [0xff, 0xb0, 0x00],
+        (BatteryPalette::Deuteranopia, Good) => [0x64, 0x8f, 0xff],
+        (BatteryPalette::Deuteranopia, Charging) => [0xdc, 0x26, 0x7f],
+
+        // Okabe-Ito: reds look dark to protanopes, so low uses the brighter orange
+        (BatteryPalette::Protanopia, Low) => [0xe6, 0x9f, 0x00],
+        (BatteryPalette::Protanopia, Medium) => [0xf0, 0xe4, 0x42],
+        (BatteryPalette::Protanopia, Good) => [0x56, 0xb4, 0xe9],
+        (BatteryPalette::Protanopia, Charging) => [0xcc, 0x79, 0xa7],
+
+        // Red against teal, told apart from pink by lightness
+        (BatteryPalette::Tritanopia, Low) => [0xe8, 0x38, 0x4f],
+        (BatteryPalette::Tritanopia, Medium) => [0xf5, 0xc2, 0xe7],
+        (BatteryPalette::Tritanopia, Good) => [0x2e, 0xc4, 0xb6],
+        (BatteryPalette::Tritanopia, Charging) => [0xcd, 0xd6, 0xf4],
+    }
+}
+
+/// RGB components of a state in the current palette
+pub fn state_rgb(state: BatteryState) -> [u8; 3] {
+    rgb(battery_palette(), state)
+}
+
+/// Color of a state in the current palette
+pub fn state_color(state: BatteryState) -> Color {
+    let [r, g, b] = state_rgb(state);
+    Color::from_rgb8(r, g, b)
+}
+
+/// Hex color (`#rrggbb`) of a state in the current palette, for SVG
+pub fn state_hex(state: BatteryState) -> String {
+    let [r, g, b] = state_rgb(state);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Level text, prefixed with the state's mark when shape encoding is on
+pub fn level_label(state: BatteryState, text: &str) -> String {
+    if shape_encoding() {
+        format!("{} {}", state.glyph(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_battery_state_ramp() {
+        assert_eq!(BatteryState::of(Some(20), false), BatteryState::Low);
+        assert_eq!(BatteryState::of(Some(50), false), BatteryState::Medium);
+        assert_eq!(BatteryState::of(Some(51), false), BatteryState::Good);
+        assert_eq!(BatteryState::of(Some(10), true), BatteryState::Charging);
+        assert_eq!(BatteryState::of(None, false), BatteryState::Unknown);
+    }
+
+    #[test]
+    fn test_palette_states_are_distinct() {
+        let states = [
+            BatteryState::Unknown,
+            BatteryState::Low,
+            BatteryState::Medium,
+            BatteryState::Good,
+            BatteryState::Charging,
+        ];
+        for palette in BatteryPalette::ALL {
+            for (i, a) in states.iter().enumerate() {
+                for b in &states[i + 1..] {
+                    assert_ne!(rgb(palette, *a), rgb(palette, *b), "{:?}", palette);
+                    assert_ne!(a.glyph(), b.glyph());
+                }
+            }
+        }
+    }
+}
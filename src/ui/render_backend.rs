@@ -0,0 +1,209 @@
+//! Picking the renderer, and falling back when hardware rendering fails
+//!
+//! The window normally draws with wgpu. When no GPU adapter can be created iced already
+//! moves on to the software renderer (tiny-skia) by itself, unless `ICED_BACKEND` says
+//! otherwise.
+//! On some VMs and remote desktop sessions an adapter is found but wgpu panics just after
+//! the window is created. RustPods then starts again with the software renderer for that
+//! session only, and asks whether to keep using it; only then is `ui.software_rendering`
+//! turned on, which can be turned off again under Settings → Interface.
+//! `--software-render` uses the software renderer for one run.
+
+use std::ffi::OsString;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Command line flag that draws this run with the software renderer
+pub const SOFTWARE_RENDER_FLAG: &str = "--software-render";
+
+/// Variable iced reads the renderers to try from, in order
+const BACKEND_ENV_VAR: &str = "ICED_BACKEND";
+
+/// Set on a process started because hardware rendering failed
+const FALLBACK_ENV_VAR: &str = "RUSTPODS_RENDER_FALLBACK";
+
+/// A renderer panic later than this after the renderer starts isn't a missing GPU
+const RENDERER_START_GRACE: Duration = Duration::from_secs(10);
+
+/// Crates of the GPU renderer, as they appear in a panic location
+///
+/// Words like "adapter" or "surface" aren't enough: a Bluetooth adapter panics too.
+const RENDERER_CRATES: &[&str] = &["wgpu", "wgpu-core", "wgpu-hal", "iced_wgpu"];
+
+/// Whether this run uses the software renderer
+static SOFTWARE: AtomicBool = AtomicBool::new(false);
+
+/// When the window and its renderer started to be created
+static RENDERER_STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// Use the software renderer for this run; call before the window is created
+pub fn use_software() {
+    SOFTWARE.store(true, Ordering::SeqCst);
+    std::env::set_var(BACKEND_ENV_VAR, "tiny-skia");
+}
+
+/// Whether this run uses the software renderer
+pub fn is_software() -> bool {
+    SOFTWARE.load(Ordering::SeqCst)
+}
+
+/// Whether this process was started because hardware rendering failed
+pub fn fell_back() -> bool {
+    std::env::var_os(FALLBACK_ENV_VAR).is_some()
+}
+
+/// Record that the window and renderer are about to be created
+pub fn mark_renderer_starting() {
+    RENDERER_STARTED.get_or_init(Instant::now);
+}
+
+/// Whether a panic with this message and location should restart with the software
+/// renderer
+pub fn should_fall_back_after_panic(message: &str, location: Option<&str>) -> bool {
+    fall_back_after_panic(
+        is_software(),
+        RENDERER_STARTED.get().map(Instant::elapsed),
+        message,
+        location,
+    )
+}
+
+/// Whether a panic comes from the GPU renderer: raised in one of its crates, or naming
+/// wgpu in its message
+pub fn is_renderer_error(message: &str, location: Option<&str>) -> bool {
+    message.to_lowercase().contains("wgpu")
+        || location.is_some_and(|location| {
+            // Registry sources sit in folders named after the crate and its version,
+            // e.g. `wgpu-core-0.16.1/src/device/mod.rs`
+            location.split(['/', '\\']).any(|folder| {
+                RENDERER_CRATES
+                    .iter()
+                    .any(|name| is_crate_folder(folder, name))
+            })
+        })
+}
+
+/// Whether `folder` is `name` itself or `name` followed by a version
+fn is_crate_folder(folder: &str, name: &str) -> bool {
+    folder.strip_prefix(name).is_some_and(|rest| {
+        rest.is_empty()
+            || rest
+                .strip_prefix('-')
+                .is_some_and(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// Only a renderer panic soon after the hardware renderer started is a missing GPU
+fn fall_back_after_panic(
+    software: bool,
+    since_renderer_start: Option<Duration>,
+    message: &str,
+    location: Option<&str>,
+) -> bool {
+    !software
+        && since_renderer_start.is_some_and(|elapsed| elapsed < RENDERER_START_GRACE)
+        && is_renderer_error(message, location)
+}
+
+/// Arguments for the software rendering process: `args` with the flag added once
+pub fn fallback_args<I>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = OsString>,
+{
+    let mut args: Vec<OsString> = args
+        .into_iter()
+        .filter(|arg| arg != SOFTWARE_RENDER_FLAG)
+        .collect();
+    args.push(SOFTWARE_RENDER_FLAG.into());
+    args
+}
+
+/// Start RustPods again with the software renderer for that session
+///
+/// The settings are left alone; the new session asks whether to keep software rendering.
+/// The caller exits once this returns `Ok`.
+pub fn fall_back() -> io::Result<()> {
+    crate::crash::relaunch_with(fallback_args(std::env::args_os().skip(1)), FALLBACK_ENV_VAR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_args_add_flag_once() {
+        let args = |list: &[&str]| list.iter().map(OsString::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            fallback_args(args(&["--safe-mode"])),
+            args(&["--safe-mode", SOFTWARE_RENDER_FLAG])
+        );
+        assert_eq!(
+            fallback_args(args(&[SOFTWARE_RENDER_FLAG])),
+            args(&[SOFTWARE_RENDER_FLAG])
+        );
+    }
+
+    #[test]
+    fn test_is_renderer_error() {
+        assert!(is_renderer_error(
+            "wgpu error: Validation Error\n\nCaused by:\n    In Surface::configure",
+            None
+        ));
+        assert!(is_renderer_error(
+            "called `Option::unwrap()` on a `None` value",
+            Some("C:\\cargo\\registry\\src\\wgpu-0.16.3\\src\\backend\\direct.rs:3019:5")
+        ));
+        assert!(is_renderer_error(
+            "Parent device is lost",
+            Some("/home/me/.cargo/registry/src/index/wgpu-core-0.16.1/src/device/mod.rs:1:1")
+        ));
+
+        // Bluetooth adapters and surfaces of other kinds aren't the renderer
+        assert!(!is_renderer_error(
+            "No Bluetooth adapter found",
+            Some("src/bluetooth/adapter.rs:42:9")
+        ));
+        assert!(!is_renderer_error(
+            "Failed to open the Bluetooth adapter: device lost",
+            Some("C:\\cargo\\registry\\src\\btleplug-0.11.5\\src\\winrtble\\adapter.rs:60:5")
+        ));
+        assert!(!is_renderer_error(
+            "called `Result::unwrap()` on an `Err` value",
+            Some("src/wgpu_settings.rs:3:1")
+        ));
+
+        assert!(!is_renderer_error(
+            "index out of bounds: the len is 0 but the index is 0",
+            Some("src/ui/state.rs:10:5")
+        ));
+        assert!(!is_renderer_error("Failed to load settings", None));
+    }
+
+    #[test]
+    fn test_fall_back_only_for_early_renderer_panics() {
+        let early = Some(Duration::from_secs(1));
+        let wgpu_panic = "wgpu error: Validation Error";
+
+        assert!(fall_back_after_panic(false, early, wgpu_panic, None));
+        // Any other early panic is a bug, not a missing GPU
+        assert!(!fall_back_after_panic(
+            false,
+            early,
+            "index out of bounds",
+            Some("src/ui/state.rs:10:5")
+        ));
+        // Already drawing in software, or the renderer had been running fine
+        assert!(!fall_back_after_panic(true, early, wgpu_panic, None));
+        assert!(!fall_back_after_panic(
+            false,
+            Some(RENDERER_START_GRACE),
+            wgpu_panic,
+            None
+        ));
+        // Before the window was created there is no renderer to blame
+        assert!(!fall_back_after_panic(false, None, wgpu_panic, None));
+    }
+}
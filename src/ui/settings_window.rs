@@ -1,12 +1,17 @@
 //! Settings window implementation for RustPods
 
-use crate::config::AppConfig;
-use crate::ui::components::SettingsView;
+use crate::airpods::battery_intelligence::UsageSummary;
+use crate::airpods::ProfileSnapshot;
+use crate::bluetooth::{FrameReport, ScanStatistics};
+use crate::config::{AppConfig, AuditEntry, KnownDevices, ShortcutAction};
+use crate::ui::components::{view_usage_panel, RuleDraft, SettingsView, SyntheticFrameDraft};
+use crate::ui::direction;
+use crate::ui::state::MergedBluetoothDevice;
 use crate::ui::theme::{self, Theme};
 use crate::ui::Message;
 use crate::ui::UiComponent;
 use iced::{
-    widget::{button, column, container, row, scrollable, text, Space},
+    widget::{button, column, container, scrollable, text, Space},
     Alignment, Element, Length,
 };
 
@@ -19,6 +24,10 @@ pub struct SettingsWindow {
     has_changes: bool,
     /// Settings view component
     settings_view: SettingsView,
+    /// Listening statistics for the usage panel
+    usage_summary: Option<UsageSummary>,
+    /// Configuration when the window was opened, restored by "Revert changes"
+    original: Option<AppConfig>,
 }
 
 impl SettingsWindow {
@@ -28,6 +37,8 @@ impl SettingsWindow {
             config: config.clone(),
             has_changes: false,
             settings_view: SettingsView::new(config),
+            usage_summary: None,
+            original: None,
         }
     }
 
@@ -43,11 +54,103 @@ impl SettingsWindow {
         self.has_changes = false;
     }
 
+    /// Remember the configuration to revert to, taken when the window opens
+    pub fn set_original(&mut self, config: Option<AppConfig>) {
+        self.original = config;
+    }
+
+    /// Configuration when the window was opened
+    pub fn original(&self) -> Option<&AppConfig> {
+        self.original.as_ref()
+    }
+
+    /// Whether the configuration differs from when the window was opened
+    pub fn can_revert(&self) -> bool {
+        self.original
+            .as_ref()
+            .is_some_and(|original| *original != self.config)
+    }
+
     /// Update connected devices
     pub fn update_connected_devices(&mut self, devices: Vec<String>) {
         self.settings_view.update_connected_devices(devices);
     }
 
+    /// Update the AirPods offered for pairing
+    pub fn update_nearby_airpods(&mut self, devices: Vec<MergedBluetoothDevice>) {
+        self.settings_view.update_nearby_airpods(devices);
+    }
+
+    /// Update the pending allow/deny entry input
+    pub fn set_device_filter_input(&mut self, value: String) {
+        self.settings_view.set_device_filter_input(value);
+    }
+
+    /// Update the action rule being composed
+    pub fn set_rule_draft(&mut self, draft: RuleDraft) {
+        self.settings_view.set_rule_draft(draft);
+    }
+
+    /// Update the synthetic frame being composed
+    pub fn set_synthetic_draft(&mut self, draft: SyntheticFrameDraft) {
+        self.settings_view.set_synthetic_draft(draft);
+    }
+
+    /// Update the number of injected synthetic frames
+    pub fn set_synthetic_frames(&mut self, count: usize) {
+        self.settings_view.set_synthetic_frames(count);
+    }
+
+    /// Action waiting for a key combination in the shortcut editor, if any
+    pub fn recording_shortcut(&self) -> Option<ShortcutAction> {
+        self.settings_view.recording_shortcut()
+    }
+
+    /// Start or stop waiting for a key combination
+    pub fn set_recording_shortcut(&mut self, action: Option<ShortcutAction>) {
+        self.settings_view.set_recording_shortcut(action);
+    }
+
+    /// Show why a recorded shortcut was rejected
+    pub fn set_shortcut_error(&mut self, error: Option<String>) {
+        self.settings_view.set_shortcut_error(error);
+    }
+
+    /// Update the settings changes listed in the advanced section
+    pub fn set_audit_entries(&mut self, entries: Vec<AuditEntry>) {
+        self.settings_view.set_audit_entries(entries);
+    }
+
+    /// Update the battery data snapshots offered in the advanced section
+    pub fn set_profile_snapshots(&mut self, snapshots: Vec<ProfileSnapshot>) {
+        self.settings_view.set_profile_snapshots(snapshots);
+    }
+
+    /// Update the known devices listed in the device section
+    pub fn set_known_devices(&mut self, devices: KnownDevices) {
+        self.settings_view.set_known_devices(devices);
+    }
+
+    /// Update the totals shown in the scanner status panel
+    pub fn set_scan_statistics(&mut self, statistics: ScanStatistics) {
+        self.settings_view.set_scan_statistics(statistics);
+    }
+
+    /// Update the device a focused scan is limited to and its recent frames
+    pub fn set_focused_scan(&mut self, address: Option<String>, frames: Vec<FrameReport>) {
+        self.settings_view.set_focused_scan(address, frames);
+    }
+
+    /// Open or close the scanner status panel
+    pub fn toggle_scanner_status(&mut self) {
+        self.settings_view.toggle_scanner_status();
+    }
+
+    /// Update the listening statistics shown in the usage panel
+    pub fn update_usage_summary(&mut self, summary: Option<UsageSummary>) {
+        self.usage_summary = summary;
+    }
+
     /// Mark that changes have been made
     pub fn mark_changed(&mut self) {
         self.has_changes = true;
@@ -67,58 +170,92 @@ impl SettingsWindow {
 impl UiComponent for SettingsWindow {
     fn view(&self) -> Element<'_, Message, iced::Renderer<Theme>> {
         // Header with back button and title
-        let header = row![
-            button(text("← Back").size(14))
+        let header = direction::row(vec![
+            button(text("← Back").size(theme::scaled(14.0)))
                 .on_press(Message::CloseSettings)
                 .style(iced::theme::Button::Secondary)
-                .padding([5, 10]),
-            Space::with_width(Length::Fixed(10.0)),
-            text("Settings").size(24).style(theme::TEXT),
-            Space::with_width(Length::Fill),
-        ]
+                .padding([5, 10])
+                .into(),
+            Space::with_width(Length::Fixed(10.0)).into(),
+            text("Settings")
+                .size(theme::scaled(24.0))
+                .style(theme::TEXT)
+                .into(),
+            Space::with_width(Length::Fill).into(),
+        ])
         .align_items(Alignment::Center);
 
         // Get all settings sections from the settings view
         let bluetooth_settings = self.settings_view.bluetooth_settings();
+        let usage_panel = view_usage_panel(self.usage_summary.as_ref());
         let ui_settings = self.settings_view.ui_settings();
+        let shortcut_settings = self.settings_view.shortcut_settings();
+        let action_settings = self.settings_view.action_settings();
         let system_settings = self.settings_view.system_settings();
+        let advanced_settings = self.settings_view.advanced_settings();
 
         // Settings info text
         let info_text = text("Settings are saved automatically when changed")
-            .size(12)
+            .size(theme::scaled(12.0))
             .style(theme::SUBTEXT1);
 
         // Action buttons - Save applies changes and closes, Cancel discards changes
-        let save_button = button(text("Save & Close").style(theme::TEXT).size(14))
-            .on_press(Message::SaveSettings)
-            .style(iced::theme::Button::Primary)
-            .padding(10);
+        let save_button = button(
+            text("Save & Close")
+                .style(theme::TEXT)
+                .size(theme::scaled(14.0)),
+        )
+        .on_press(Message::SaveSettings)
+        .style(iced::theme::Button::Primary)
+        .padding(10);
 
-        let cancel_button = button(text("Cancel").style(theme::TEXT).size(14))
+        let cancel_button = button(text("Cancel").style(theme::TEXT).size(theme::scaled(14.0)))
             .on_press(Message::CloseSettings)
             .style(iced::theme::Button::Secondary)
             .padding(10);
 
-        let actions = row![Space::with_width(Length::Fill), cancel_button, save_button]
-            .spacing(10)
-            .align_items(Alignment::Center);
+        let revert_button = button(
+            text("Revert changes")
+                .style(theme::TEXT)
+                .size(theme::scaled(14.0)),
+        )
+        .on_press_maybe(self.can_revert().then_some(Message::RevertSettings))
+        .style(iced::theme::Button::Secondary)
+        .padding(10);
+
+        let actions = direction::row(vec![
+            revert_button.into(),
+            Space::with_width(Length::Fill).into(),
+            cancel_button.into(),
+            save_button.into(),
+        ])
+        .spacing(10)
+        .align_items(Alignment::Center);
 
         // Scrollable content with all settings sections
         let scrollable_content = scrollable(
             column![
                 bluetooth_settings,
                 Space::with_height(Length::Fixed(30.0)),
+                usage_panel,
+                Space::with_height(Length::Fixed(30.0)),
                 ui_settings,
                 Space::with_height(Length::Fixed(30.0)),
+                shortcut_settings,
+                Space::with_height(Length::Fixed(30.0)),
+                action_settings,
+                Space::with_height(Length::Fixed(30.0)),
                 system_settings,
                 Space::with_height(Length::Fixed(30.0)),
+                advanced_settings,
+                Space::with_height(Length::Fixed(30.0)),
                 info_text,
                 Space::with_height(Length::Fixed(20.0)),
                 actions
             ]
             .spacing(15)
             .padding(25)
-            .align_items(Alignment::Start),
+            .align_items(direction::start_alignment()),
         );
 
         let content = column![
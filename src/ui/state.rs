@@ -6,13 +6,45 @@ use tokio::sync::{mpsc, Mutex};
 
 use crate::airpods::battery::AirPodsBatteryInfo;
 use crate::airpods::battery_estimator::BatteryEstimator;
-use crate::airpods::battery_intelligence::BatteryIntelligence;
-use crate::bluetooth::DiscoveredDevice;
-use crate::config::{AppConfig, ConfigError, ConfigManager};
+use crate::airpods::battery_intelligence::{BatteryEstimate, BatteryIntelligence};
+use crate::airpods::capacity::{Capacity, Health, MahEstimate};
+use crate::airpods::household::{self, HouseholdIntelligence};
+use crate::airpods::threshold_latch::ThresholdLatch;
+use crate::backup;
+use crate::bluetooth::{
+    capture_diff, frame_from_payload, preflight, AirPodsBatteryStatus, DiscoveredDevice,
+    FocusedScan, FrameReport, ScannerSupervisor, SyntheticFrames, SCANNER_TIMEOUT,
+};
+use crate::config::{
+    ActionRule, AppConfig, AuditLog, BatteryComponent, ChangeSource, ConfigError, ConfigManager,
+    KnownDevices, RepairAction, RuleAction, ThemeScheduleMode, TrayIconStyle, WindowBackdrop,
+    WindowSize,
+};
+use crate::diagnostics::{DiagnosticLevel, DiagnosticResult, DiagnosticsManager};
+use crate::error::{ErrorManager, RecoveryAction, RustPodsError};
+use crate::issue_report::{self, IssueReport};
+use crate::known_issues::{self, KnownIssueIndex};
+use crate::lifecycle_manager::{LifecycleManager, StartupPhase};
+use crate::quarantine::{self, QuarantinedFile};
+use crate::rules::{DeviceSnapshot, RuleEngine};
+use crate::state_graph::{self, Transition};
+use crate::state_persistence::PersistentState;
+use crate::system::{
+    LowBatteryAnnouncer, NotificationKind, Notifier, SpeechSynthesizer, TaskbarBadge,
+};
+use crate::telemetry::{TelemetryEvent, TelemetryManager};
+use crate::ui::accessibility;
 use crate::ui::{
-    components::{BluetoothSetting, SystemSetting, UiSetting},
-    system_tray::SystemTray,
-    MainWindow, Message, SettingsWindow,
+    components::{
+        ActionSetting, AirPodsPopup, BluetoothSetting, DeviceEdit, Dialog, RuleDraft,
+        SystemSetting, UiSetting,
+    },
+    file_dialogs::FileAction,
+    file_drop::DroppedFile,
+    headline::Headline,
+    system_tray::TrayActivity,
+    system_tray_controller::SystemTrayController,
+    ExpandedDetails, MainWindow, Message, SettingsWindow,
 };
 
 /// Device detection state for managing UI transitions
@@ -41,6 +73,49 @@ pub enum DeviceDetectionState {
 }
 
 impl DeviceDetectionState {
+    /// Name of every state, as returned by [`Self::name`]
+    pub const NAMES: &'static [&'static str] = &[
+        "Idle",
+        "Scanning",
+        "DeviceFound",
+        "DevicesFound",
+        "NoDevicesFound",
+        "Error",
+        "Connected",
+    ];
+
+    /// Transitions the application makes; any other is logged as unexpected
+    pub const TRANSITIONS: &'static [Transition] = &[
+        Transition {
+            from: None,
+            to: "DevicesFound",
+            trigger: "scan found AirPods",
+        },
+        Transition {
+            from: None,
+            to: "NoDevicesFound",
+            trigger: "3 empty scans in a row",
+        },
+        Transition {
+            from: None,
+            to: "Connected",
+            trigger: "GATT battery read",
+        },
+    ];
+
+    /// Name of the state, without its data
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceDetectionState::Idle => "Idle",
+            DeviceDetectionState::Scanning => "Scanning",
+            DeviceDetectionState::DeviceFound { .. } => "DeviceFound",
+            DeviceDetectionState::DevicesFound => "DevicesFound",
+            DeviceDetectionState::NoDevicesFound => "NoDevicesFound",
+            DeviceDetectionState::Error { .. } => "Error",
+            DeviceDetectionState::Connected { .. } => "Connected",
+        }
+    }
+
     /// Check if there's an active device connection
     pub fn has_active_device(&self) -> bool {
         matches!(
@@ -52,6 +127,28 @@ impl DeviceDetectionState {
     }
 }
 
+/// Where the primary AirPods stand with this PC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionStatus {
+    /// Connected to the PC's Bluetooth
+    Connected,
+    /// Advertising in range, but connected to another device or none
+    Nearby,
+    /// Not heard from
+    #[default]
+    NotFound,
+}
+
+impl std::fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Connected => write!(f, "Connected"),
+            ConnectionStatus::Nearby => write!(f, "Nearby"),
+            ConnectionStatus::NotFound => write!(f, "Not found"),
+        }
+    }
+}
+
 /// Main application state
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -92,7 +189,7 @@ pub struct AppState {
     pub settings_error: Option<String>,
 
     /// System tray component
-    pub system_tray: Option<SystemTray>,
+    pub system_tray: Option<SystemTrayController>,
 
     /// Persistent status message (for status feedback)
     pub status_message: Option<String>,
@@ -100,8 +197,11 @@ pub struct AppState {
     /// Current toast/notification message (temporary)
     pub toast_message: Option<String>,
 
-    /// Channel to send messages to the controller
-    pub controller_sender: mpsc::UnboundedSender<Message>,
+    /// Keeps the controller channel open while there is no system tray to hold a sender
+    ///
+    /// With a tray only its sender does, so the channel closes if the tray thread stops
+    /// and the controller subscription replaces it.
+    pub controller_sender: Option<mpsc::UnboundedSender<Message>>,
 
     /// Merged Bluetooth devices
     pub merged_devices: Vec<MergedBluetoothDevice>,
@@ -109,6 +209,9 @@ pub struct AppState {
     /// AirPods data loaded from the CLI scanner
     pub airpods_devices: Vec<AirPodsBatteryInfo>,
 
+    /// Reading last passed to the battery intelligence, so unchanged scans aren't saved again
+    last_intelligence_reading: Option<AirPodsBatteryInfo>,
+
     /// Timestamp of the last AirPods data update
     pub last_update: std::time::Instant,
 
@@ -118,13 +221,165 @@ pub struct AppState {
     /// New intelligent battery management system
     pub battery_intelligence: BatteryIntelligence,
 
+    /// Battery intelligence for the other sets listed in household mode
+    pub household_intelligence: HouseholdIntelligence,
+
     /// Device detection state
     pub device_detection_state: DeviceDetectionState,
 
     /// Consecutive scan failures counter (to prevent flashing on intermittent disconnections)
     pub consecutive_scan_failures: u32,
+
+    /// Whether the last scan was skipped because Bluetooth can't be used
+    pub bluetooth_unavailable: bool,
+
+    /// Whether the last scan failed, as opposed to hearing nothing
+    pub scan_failed: bool,
+
+    /// When the system went to sleep, while it is suspended (scanning is paused)
+    pub suspended_since: Option<std::time::SystemTime>,
+
+    /// Text-to-speech synthesizer for battery announcements
+    pub speech: SpeechSynthesizer,
+
+    /// Tracks which low battery announcements have already been spoken
+    pub low_battery_announcer: LowBatteryAnnouncer,
+
+    /// Desktop notifications (toasts with balloon tip fallback)
+    pub notifier: Notifier,
+
+    /// Palette currently in use (changes with the theme schedule)
+    pub active_theme: crate::ui::theme::Theme,
+
+    /// Backdrop and dark mode last applied to the windows (None until first applied)
+    pub backdrop_applied: Option<(WindowBackdrop, bool)>,
+
+    /// Whether a Mica/Acrylic backdrop is showing behind the window
+    pub backdrop_active: bool,
+
+    /// Capture exclusion last applied to the windows (None until first applied)
+    pub capture_excluded: Option<bool>,
+
+    /// Badge last shown on the taskbar button (None until shown or while hidden)
+    pub taskbar_badge: Option<TaskbarBadge>,
+
+    /// Whether the title bar has been registered for native hit-testing
+    pub caption_hit_testing: bool,
+
+    /// Whether the last window resize still has to be written to the config
+    pub window_size_unsaved: bool,
+
+    /// Tracks errors shown in the main window's error banner
+    pub error_manager: Arc<std::sync::Mutex<ErrorManager>>,
+
+    /// Timeouts and backoff for the CLI scanner process
+    pub scanner_supervisor: ScannerSupervisor,
+
+    /// Single device scanning is limited to while debugging it
+    pub focused_scan: FocusedScan,
+
+    /// Frames injected into every scan result from the developer panel
+    pub synthetic_frames: SyntheticFrames,
+
+    /// Workarounds linked from the error banner, looked up by error code
+    pub known_issues: KnownIssueIndex,
+
+    /// When a known issues download was last considered
+    pub known_issues_checked: Option<std::time::Instant>,
+
+    /// When a scheduled backup was last considered
+    pub backup_checked: Option<std::time::Instant>,
+
+    /// Unknown model IDs the main window has already asked about this session
+    pub unknown_models_prompted: std::collections::HashSet<u16>,
+
+    /// Devices paired with the classic Bluetooth stack, from the last query
+    pub classic_devices: Option<Vec<crate::bluetooth::ClassicDevice>>,
+
+    /// Name and connection of the device audio routing was last read for, and when
+    audio_routing_checked: Option<(String, bool, std::time::Instant)>,
+
+    /// Connection status of the primary device
+    pub connection_status: ConnectionStatus,
+
+    /// Local usage aggregation (records nothing unless the user opted in)
+    pub telemetry: Arc<std::sync::Mutex<TelemetryManager>>,
+
+    /// Whether the collected telemetry data screen is shown
+    pub show_telemetry_data: bool,
+
+    /// Whether the troubleshoot screen is shown
+    pub show_troubleshoot: bool,
+
+    /// Result of the last background health check
+    pub health_check: Option<DiagnosticResult>,
+
+    /// Whether a background health check is running
+    pub health_check_running: bool,
+
+    /// Tracks which low battery notifications have already been shown
+    pub low_battery_notified: ThresholdLatch,
+
+    /// Tracks components that have crossed the critical threshold
+    pub critical_battery_latch: ThresholdLatch,
+
+    /// When the critical battery tray flash stops (None flashes until acknowledged)
+    pub critical_alert_until: Option<std::time::Instant>,
+
+    /// Finds the user's action rules to run as device state changes
+    pub rule_engine: RuleEngine,
+
+    /// Transient popup shown when the case lid opens
+    pub lid_popup: Option<AirPodsPopup>,
+
+    /// Dialog waiting for an answer, shown in place of the current screen
+    pub dialog: Option<Dialog>,
+
+    /// Incremented for every lid popup so stale dismiss timers are ignored
+    pub lid_popup_generation: u64,
+
+    /// Whether the window was shown only for the lid popup and should hide again
+    pub lid_popup_restore_hidden: bool,
+
+    /// Last observed case lid state (None until the first reading)
+    pub last_case_lid_open: Option<bool>,
+
+    /// Nicknames, icons and colors of every pair the user has paired with
+    pub known_devices: KnownDevices,
+
+    /// Whether the known devices couldn't be loaded, so saving them would lose the file
+    pub known_devices_read_only: bool,
+
+    /// Settings from before the last save, while the undo notice is shown
+    pub undo_config: Option<AppConfig>,
+
+    /// Incremented for every undo notice so stale dismiss timers are ignored
+    pub undo_generation: u64,
 }
 
+/// How long the lid-open popup stays on screen
+const LID_POPUP_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Settings changes listed in the advanced section of the settings window
+const AUDIT_ENTRIES_SHOWN: usize = 20;
+
+/// How long a settings save can be undone from the main window
+const UNDO_DURATION: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// How often to check whether the known issues index is due for a download
+const KNOWN_ISSUES_CHECK_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(24 * 60 * 60);
+
+/// How often to check whether a scheduled backup is due
+const BACKUP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// How often audio routing is read again while the primary device stays connected, to
+/// pick up output changes made outside RustPods
+const AUDIO_ROUTING_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// How long the controller subscription waits before replacing a closed channel
+const CONTROLLER_RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
 // Global receiver for controller messages (needed for subscription)
 static CONTROLLER_RECEIVER: OnceLock<Arc<Mutex<Option<mpsc::UnboundedReceiver<Message>>>>> =
     OnceLock::new();
@@ -133,56 +388,74 @@ impl AppState {
     /// Create a new AppState with the given controller sender
     pub fn new(controller_sender: mpsc::UnboundedSender<Message>) -> Self {
         let config = AppConfig::default();
-        let main_window = MainWindow::empty();
-        let settings_window = SettingsWindow::new(config.clone());
-
-        // Initialize battery estimator with saved history data
-        let mut battery_estimator = BatteryEstimator::new();
-        battery_estimator.left_history = config.battery.left_history.clone();
-        battery_estimator.right_history = config.battery.right_history.clone();
-        battery_estimator.case_history = config.battery.case_history.clone();
-
-        // Create and initialize system tray
-        let system_tray = match SystemTray::new(config.clone()) {
-            Ok(mut tray) => {
-                // Set the UI sender for fallback communication
-                tray.set_ui_sender(controller_sender.clone());
-
-                // Initialize the system tray (creates the actual icon and menu)
-                match tray.initialize() {
-                    Ok(_) => {
-                        log::info!("System tray initialized successfully");
-                        Some(tray)
-                    }
-                    Err(e) => {
-                        log::error!("Failed to initialize system tray: {}", e);
-                        None
-                    }
-                }
-            }
-            Err(e) => {
-                log::error!("Failed to create system tray: {}", e);
-                None
-            }
-        };
+        let active_theme = scheduled_or_default_theme(&config);
+        let telemetry = crate::telemetry::init_telemetry(&config);
 
-        // Initialize the new BatteryIntelligence system
+        // Startup phases: storage, tray, then scanner; each degrades rather than stopping
         let battery_intelligence_dir = dirs::data_local_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("RustPods")
             .join("battery_intelligence");
-        let mut battery_intelligence = BatteryIntelligence::new(battery_intelligence_dir);
-
-        // Load existing device profiles
-        if let Err(e) = battery_intelligence.load() {
-            eprintln!(
-                "Warning: Failed to load existing battery intelligence data: {}",
-                e
+        let storage = {
+            let (config, dir) = (config.clone(), battery_intelligence_dir.clone());
+            LifecycleManager::run_phase(StartupPhase::Storage, move || {
+                std::fs::create_dir_all(&dir)
+                    .map_err(|e| format!("Couldn't create {}: {}", dir.display(), e))?;
+                let known_devices = KnownDevices::load_from(&KnownDevices::path_for(&config));
+                let mut battery_intelligence = BatteryIntelligence::new(dir);
+                battery_intelligence.enable_background_saving();
+                if let Err(e) = battery_intelligence.load() {
+                    log::warn!("Failed to load existing battery intelligence data: {}", e);
+                }
+                Ok((known_devices, battery_intelligence))
+            })
+        };
+        // Nothing was loaded, so saving would replace the learned data and known devices
+        // with empty ones
+        let storage = storage.ok().flatten();
+        let known_devices_read_only = storage.is_none();
+        let (known_devices, battery_intelligence) = storage.unwrap_or_else(|| {
+            log::warn!(
+                "Data in {} couldn't be loaded; learning and device changes aren't saved this session",
+                battery_intelligence_dir.display()
             );
+            (KnownDevices::default(), BatteryIntelligence::in_memory())
+        });
+
+        let system_tray = {
+            let (config, sender) = (config.clone(), controller_sender.clone());
+            LifecycleManager::run_phase(StartupPhase::Tray, move || {
+                SystemTrayController::start(config, sender).map_err(|e| e.to_string())
+            })
         }
+        .ok()
+        .flatten();
+
+        let _ = LifecycleManager::run_phase(StartupPhase::Scanner, || {
+            let scanner = cli_scanner_paths()
+                .into_iter()
+                .find(|path| path.exists())
+                .ok_or_else(|| {
+                    "The CLI scanner (airpods_battery_cli.exe) wasn't found".to_string()
+                })?;
+            log::info!("Using the CLI scanner at {}", scanner.display());
+            Ok(())
+        });
+
+        let mut main_window = MainWindow::empty();
+        main_window.known_devices = known_devices.clone();
+        let mut settings_window = SettingsWindow::new(config.clone());
+        settings_window.set_known_devices(known_devices.clone());
+
+        // Initialize battery estimator with saved history data
+        let mut battery_estimator = BatteryEstimator::new();
+        battery_estimator.left_history = config.battery.left_history.clone();
+        battery_estimator.right_history = config.battery.right_history.clone();
+        battery_estimator.case_history = config.battery.case_history.clone();
 
-        // Print initialization message
-        println!("Battery Intelligence system initialized - profiles will be created when devices are detected");
+        // Listen for system suspend/resume so scanning pauses while asleep
+        let _power_monitor =
+            crate::lifecycle_manager::spawn_power_event_monitor(controller_sender.clone());
 
         Self {
             visible: true,
@@ -197,17 +470,64 @@ impl AppState {
             main_window,
             settings_window,
             settings_error: None,
+            controller_sender: system_tray.is_none().then_some(controller_sender),
             system_tray,
             status_message: None,
             toast_message: None,
-            controller_sender,
             merged_devices: Vec::new(),
             airpods_devices: Vec::new(),
+            last_intelligence_reading: None,
             last_update: std::time::Instant::now(),
             battery_estimator,
+            household_intelligence: HouseholdIntelligence::new(
+                battery_intelligence.storage_dir.join("household"),
+                true,
+            ),
             battery_intelligence,
             device_detection_state: DeviceDetectionState::Idle,
             consecutive_scan_failures: 0,
+            bluetooth_unavailable: false,
+            scan_failed: false,
+            suspended_since: None,
+            speech: SpeechSynthesizer::new(),
+            low_battery_announcer: LowBatteryAnnouncer::new(),
+            notifier: Notifier::new(),
+            active_theme,
+            backdrop_applied: None,
+            backdrop_active: false,
+            capture_excluded: None,
+            taskbar_badge: None,
+            caption_hit_testing: false,
+            window_size_unsaved: false,
+            error_manager: Arc::new(std::sync::Mutex::new(ErrorManager::new())),
+            scanner_supervisor: ScannerSupervisor::new(),
+            focused_scan: FocusedScan::new(),
+            synthetic_frames: SyntheticFrames::new(),
+            known_issues: KnownIssueIndex::load(),
+            known_issues_checked: None,
+            backup_checked: None,
+            unknown_models_prompted: std::collections::HashSet::new(),
+            classic_devices: None,
+            audio_routing_checked: None,
+            connection_status: ConnectionStatus::NotFound,
+            telemetry,
+            show_telemetry_data: false,
+            show_troubleshoot: false,
+            health_check: None,
+            health_check_running: false,
+            low_battery_notified: ThresholdLatch::new(),
+            critical_battery_latch: ThresholdLatch::new(),
+            critical_alert_until: None,
+            rule_engine: RuleEngine::new(),
+            lid_popup: None,
+            dialog: None,
+            lid_popup_generation: 0,
+            undo_config: None,
+            undo_generation: 0,
+            lid_popup_restore_hidden: false,
+            last_case_lid_open: None,
+            known_devices,
+            known_devices_read_only,
         }
     }
 
@@ -215,6 +535,8 @@ impl AppState {
     #[cfg(test)]
     pub fn new_for_test(controller_sender: mpsc::UnboundedSender<Message>) -> Self {
         let config = AppConfig::default();
+        let active_theme = scheduled_or_default_theme(&config);
+        let telemetry = crate::telemetry::init_telemetry(&config);
         let config_manager = None;
         let settings_window = SettingsWindow::new(config.clone());
         let main_window = MainWindow::empty();
@@ -235,16 +557,61 @@ impl AppState {
             system_tray: None,
             status_message: None,
             toast_message: None,
-            controller_sender,
+            controller_sender: Some(controller_sender),
             merged_devices: Vec::new(),
             airpods_devices: Vec::new(),
+            last_intelligence_reading: None,
             last_update: std::time::Instant::now(),
             battery_estimator: BatteryEstimator::new(),
-            battery_intelligence: BatteryIntelligence::new(std::path::PathBuf::from(
-                "./test_battery_intelligence",
-            )),
+            battery_intelligence: BatteryIntelligence::in_memory(),
+            household_intelligence: HouseholdIntelligence::new(
+                std::path::PathBuf::from("./test_battery_intelligence/household"),
+                false,
+            ),
             device_detection_state: DeviceDetectionState::Idle,
             consecutive_scan_failures: 0,
+            bluetooth_unavailable: false,
+            scan_failed: false,
+            suspended_since: None,
+            speech: SpeechSynthesizer::new(),
+            low_battery_announcer: LowBatteryAnnouncer::new(),
+            notifier: Notifier::new(),
+            active_theme,
+            backdrop_applied: None,
+            backdrop_active: false,
+            capture_excluded: None,
+            taskbar_badge: None,
+            caption_hit_testing: false,
+            window_size_unsaved: false,
+            error_manager: Arc::new(std::sync::Mutex::new(ErrorManager::new())),
+            scanner_supervisor: ScannerSupervisor::new(),
+            focused_scan: FocusedScan::new(),
+            synthetic_frames: SyntheticFrames::new(),
+            known_issues: KnownIssueIndex::load(),
+            known_issues_checked: None,
+            backup_checked: None,
+            unknown_models_prompted: std::collections::HashSet::new(),
+            classic_devices: None,
+            audio_routing_checked: None,
+            connection_status: ConnectionStatus::NotFound,
+            telemetry,
+            show_telemetry_data: false,
+            show_troubleshoot: false,
+            health_check: None,
+            health_check_running: false,
+            low_battery_notified: ThresholdLatch::new(),
+            critical_battery_latch: ThresholdLatch::new(),
+            critical_alert_until: None,
+            rule_engine: RuleEngine::new(),
+            lid_popup: None,
+            dialog: None,
+            lid_popup_generation: 0,
+            undo_config: None,
+            undo_generation: 0,
+            lid_popup_restore_hidden: false,
+            last_case_lid_open: None,
+            known_devices: KnownDevices::default(),
+            known_devices_read_only: false,
         }
     }
 }
@@ -274,13 +641,26 @@ impl Application for AppState {
 
         log::info!("AppState::new: Creating new application state with system tray communication");
 
-        let app_state = Self::new(controller_sender);
+        let mut app_state = Self::new(controller_sender);
+        let _ = LifecycleManager::run_phase_here(StartupPhase::Ui, || {
+            crate::retry::report_to(app_state.error_manager.clone());
+            app_state.report_settings_problems();
+            app_state.report_quarantined_files();
+            app_state.report_degraded_startup();
+            Ok(())
+        });
+        // Software rendering is only saved once the user chooses to keep it
+        app_state.main_window.software_rendering_notice = crate::ui::render_backend::fell_back();
+        // The window and its renderer are created once this returns
+        crate::ui::render_backend::mark_renderer_starting();
 
         // Return a command that triggers initial AirPods scanning for immediate detection
         log::info!("Scheduling initial AirPods scan on startup");
+        app_state.set_tray_activity(TrayActivity::Scanning);
+        let supervisor = app_state.scanner_supervisor.clone();
         let initial_command = Command::perform(
-            async {
-                tokio::task::spawn_blocking(get_airpods_from_cli_scanner)
+            async move {
+                tokio::task::spawn_blocking(move || get_airpods_from_cli_scanner(&supervisor))
                     .await
                     .unwrap_or_else(|_| Vec::new())
             },
@@ -291,23 +671,28 @@ impl Application for AppState {
     }
 
     fn title(&self) -> String {
-        String::from("RustPods - AirPods Battery Monitor")
+        if crate::safe_mode::is_enabled() {
+            String::from("RustPods - AirPods Battery Monitor (Safe Mode)")
+        } else {
+            String::from("RustPods - AirPods Battery Monitor")
+        }
     }
 
     fn theme(&self) -> Self::Theme {
-        crate::ui::theme::Theme::CatppuccinMocha
+        self.active_theme
+    }
+
+    fn style(&self) -> crate::ui::theme::ApplicationStyle {
+        if self.backdrop_active {
+            crate::ui::theme::ApplicationStyle::Translucent
+        } else {
+            crate::ui::theme::ApplicationStyle::Solid
+        }
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
         crate::debug_log!("ui", "AppState::update: instance at {:p}", self as *const _);
 
-        // Process system tray events
-        if let Some(ref mut system_tray) = self.system_tray {
-            if let Err(e) = system_tray.process_events() {
-                log::error!("Failed to process system tray events: {}", e);
-            }
-        }
-
         match message {
             Message::ToggleVisibility => {
                 self.toggle_visibility();
@@ -315,41 +700,70 @@ impl Application for AppState {
             }
             Message::ToggleWindow => {
                 self.toggle_visibility();
-                Command::none()
+                if self.visible {
+                    self.position_near_tray()
+                } else {
+                    Command::none()
+                }
             }
             Message::Exit => {
                 // Check if minimize to tray is enabled
                 if self.config.ui.minimize_to_tray_on_close {
                     log::info!("Minimizing to tray instead of exiting (hiding window but keeping event loop alive)");
                     self.visible = false;
+                    self.flush_telemetry();
                     // Save settings when minimizing to tray
-                    if let Err(e) = self.config.save() {
+                    if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
                         log::error!("Failed to save settings: {}", e);
                     }
                     iced::window::change_mode(iced::window::Mode::Hidden)
                 } else {
                     log::info!("Exiting application");
+                    self.flush_telemetry();
+                    self.battery_intelligence.flush();
+                    self.household_intelligence.flush();
                     std::process::exit(0);
                 }
             }
             Message::ForceQuit => {
                 log::info!("ForceQuit message received - initiating graceful shutdown");
+                self.flush_telemetry();
+                self.battery_intelligence.flush();
+                self.household_intelligence.flush();
 
                 // Use std::process::exit for force quit to avoid Tokio runtime shutdown issues
                 // Graphics resources are properly cleaned up before this point (verified by testing)
                 std::process::exit(0);
             }
+            Message::Restart(safe_mode) => {
+                // Saves are flushed first so the new process reads what this one wrote
+                self.flush_telemetry();
+                self.battery_intelligence.flush();
+                self.household_intelligence.flush();
+                match crate::safe_mode::restart(safe_mode) {
+                    Ok(()) => std::process::exit(0),
+                    Err(e) => {
+                        log::error!("Failed to restart RustPods: {}", e);
+                        self.toast_message = Some(format!("Couldn't restart RustPods: {}", e));
+                        Command::none()
+                    }
+                }
+            }
             Message::NoOp => {
                 // No operation - used for subscription management, do nothing
                 Command::none()
             }
+            Message::ControllerChannelRestored(sender) => {
+                self.rebind_controller_sender(sender);
+                Command::none()
+            }
             Message::WindowCloseRequested => {
                 log::info!("Window close requested - handling based on minimize to tray setting");
                 if self.config.ui.minimize_to_tray_on_close {
                     log::info!("Minimizing to tray instead of closing");
                     self.visible = false;
                     // Save settings when minimizing to tray
-                    if let Err(e) = self.config.save() {
+                    if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
                         log::error!("Failed to save settings on minimize: {}", e);
                     }
                     // Use Iced's proper window hiding command
@@ -364,12 +778,19 @@ impl Application for AppState {
                     "ShowWindow message received from system tray, current visible: {}",
                     self.visible
                 );
+                // An explicit show replaces any transient lid popup
+                self.lid_popup = None;
+                self.lid_popup_restore_hidden = false;
+                self.acknowledge_critical_alert();
                 if !self.visible {
                     // Window was hidden, need to restore it
                     self.visible = true;
                     log::info!("Window visibility set to true, restoring window from hidden/minimized state");
                     // Use Iced's window restoration command to properly show the window
-                    iced::window::change_mode(iced::window::Mode::Windowed)
+                    Command::batch([
+                        iced::window::change_mode(iced::window::Mode::Windowed),
+                        self.position_near_tray(),
+                    ])
                 } else {
                     // Window is already visible, but may be minimized - force restore
                     log::info!("Window already marked visible, forcing window restore and focus");
@@ -396,14 +817,17 @@ impl Application for AppState {
                 Command::none()
             }
             Message::BatteryStatusUpdated(status) => {
-                let status_clone = status.clone();
-                self.battery_status = Some(status);
-                if let Some(_device) = self.get_selected_device() {
-                    self.main_window = MainWindow::new()
-                        .with_animation_progress(self.animation_progress)
-                        .with_battery_status(status_clone.clone());
+                self.battery_status = Some(status.clone());
+                // Readings for a selected non-Apple device come from its GATT Battery
+                // Service and are shown like AirPods readings
+                if let Some(device) = self
+                    .get_selected_device()
+                    .filter(|device| !device.is_potential_airpods)
+                    .cloned()
+                {
+                    self.merge_gatt_battery(&device, &status);
                 }
-                Command::none()
+                self.main_window.update_battery(status)
             }
             Message::AirPodsConnected(airpods) => {
                 crate::debug_log!("bluetooth", "Connected to AirPods: {:?}", airpods);
@@ -416,9 +840,334 @@ impl Application for AppState {
             }
             Message::BluetoothError(msg) => {
                 crate::debug_log!("ui", "AppState::update: setting toast_message = {:?}", msg);
+                self.raise_error("bluetooth", RustPodsError::Bluetooth(msg.clone()));
                 self.toast_message = Some(msg);
                 Command::none()
             }
+            Message::FocusScan(address) => {
+                match address
+                    .as_deref()
+                    .and_then(crate::config::canonical_address)
+                {
+                    Some(address) => {
+                        log::info!("Focusing scan on {}", address);
+                        self.focused_scan.focus(address);
+                        let focus = self.focused_scan.clone();
+                        self.airpods_devices
+                            .retain(|airpods| focus.allows(&airpods.canonical_address));
+                        self.merged_devices
+                            .retain(|device| focus.allows(&device.address));
+                        self.check_selected_device();
+                    }
+                    None => {
+                        log::info!("Scanning every device again");
+                        self.focused_scan.clear();
+                    }
+                }
+                self.sync_scanner_status();
+                self.update(Message::Tick)
+            }
+            Message::ToggleScannerStatus => {
+                self.settings_window
+                    .set_scan_statistics(self.scanner_supervisor.statistics());
+                self.settings_window.toggle_scanner_status();
+                Command::none()
+            }
+            Message::RetryScanner => {
+                self.scanner_supervisor.retry_now();
+                self.update(Message::Tick)
+            }
+            Message::ScanFailed(msg) => {
+                log::warn!("AirPods scan failed: {}", msg);
+                self.record_telemetry(TelemetryEvent::ScanFailed);
+                self.raise_error("scanner", RustPodsError::Bluetooth(msg));
+                self.scan_failed = true;
+                self.sync_scanner_status();
+                // A failed scan still counts towards the no-devices tolerance
+                self.update(Message::AirPodsDataLoaded(Vec::new()))
+            }
+            Message::BluetoothUnavailable(error) => {
+                log::warn!("Skipping scan: {}", error);
+                self.raise_error("radio", RustPodsError::BluetoothError(error));
+                self.bluetooth_unavailable = true;
+                // Devices can't be heard with the radio off, so they count as gone
+                self.update(Message::AirPodsDataLoaded(Vec::new()))
+            }
+            Message::DismissError(id) => {
+                if let Ok(mut manager) = self.error_manager.lock() {
+                    manager.dismiss(id);
+                }
+                self.sync_active_errors();
+                Command::none()
+            }
+            Message::RecoverError(id) => self.recover_error(id),
+            Message::ReportProblem => {
+                self.record_feature("report_problem");
+                self.toast_message = Some("Gathering a problem report…".to_string());
+                let config = Arc::new(self.config.clone());
+                let error_manager = Arc::clone(&self.error_manager);
+                Command::perform(
+                    async move {
+                        let mut diagnostics =
+                            DiagnosticsManager::new(config, Arc::clone(&error_manager));
+                        diagnostics.set_level(DiagnosticLevel::Basic);
+                        let result = diagnostics.run_diagnostics().await;
+                        if let Err(e) = &result {
+                            log::warn!("Diagnostics for problem report failed: {}", e);
+                        }
+                        let report = match error_manager.lock() {
+                            Ok(manager) => IssueReport::collect(&manager, result.ok().as_ref()),
+                            Err(_) => IssueReport::collect(&ErrorManager::new(), None),
+                        };
+                        Box::new(report)
+                    },
+                    Message::ProblemReportReady,
+                )
+            }
+            Message::ProblemReportReady(report) => {
+                let (url, toast) = match report.url() {
+                    Some(url) => (
+                        url,
+                        "Opened a new GitHub issue; the report is also on the clipboard",
+                    ),
+                    None => (
+                        issue_report::NEW_ISSUE_URL.to_string(),
+                        "Report copied to the clipboard; paste it into the new issue",
+                    ),
+                };
+                self.toast_message = Some(match issue_report::open_in_browser(&url) {
+                    Ok(()) => toast.to_string(),
+                    Err(e) => {
+                        log::warn!("Failed to open issue page: {}", e);
+                        format!(
+                            "Report copied to the clipboard; open {} to file it",
+                            issue_report::NEW_ISSUE_URL
+                        )
+                    }
+                });
+                iced::clipboard::write(report.markdown())
+            }
+            Message::ScheduledBackupFinished(result) => {
+                match result {
+                    Ok(backup) => {
+                        log::info!(
+                            "Scheduled backup of {} files written to {} ({} old backups removed)",
+                            backup.files,
+                            backup.path.display(),
+                            backup.removed
+                        );
+                        self.notifier.notify(
+                            "Backup complete",
+                            &format!("Backed up your RustPods data to {}", backup.path.display()),
+                            NotificationKind::Info,
+                        );
+                    }
+                    Err(e) => {
+                        log::error!("Scheduled backup failed: {}", e);
+                        self.notifier.notify(
+                            "Backup failed",
+                            &format!("RustPods couldn't back up your data: {}", e),
+                            NotificationKind::Warning,
+                        );
+                    }
+                }
+                Command::none()
+            }
+            Message::KnownIssuesRefreshed(index) => {
+                if let Some(index) = index {
+                    self.known_issues = *index;
+                    self.sync_active_errors();
+                }
+                Command::none()
+            }
+            Message::OpenLink(url) => {
+                if let Err(e) = issue_report::open_in_browser(&url) {
+                    log::warn!("Failed to open {}: {}", url, e);
+                    self.toast_message = Some(format!("Couldn't open the browser; see {}", url));
+                }
+                Command::none()
+            }
+            Message::ShowTelemetryData => {
+                self.show_telemetry_data = true;
+                Command::none()
+            }
+            Message::CloseTelemetryData => {
+                self.show_telemetry_data = false;
+                Command::none()
+            }
+            Message::HealthCheckTick => self.run_health_check(),
+            Message::HealthChecked(result) => {
+                self.health_check_running = false;
+                match result {
+                    Some(result) => self.show_health(*result),
+                    None => log::warn!("Health check crashed"),
+                }
+                Command::none()
+            }
+            Message::ShowTroubleshoot => {
+                self.show_troubleshoot = true;
+                // Nothing to show before the first scheduled check
+                if self.health_check.is_none() {
+                    return self.run_health_check();
+                }
+                Command::none()
+            }
+            Message::CloseTroubleshoot => {
+                self.show_troubleshoot = false;
+                Command::none()
+            }
+            Message::ClearTelemetryData => {
+                if let Ok(mut telemetry) = self.telemetry.lock() {
+                    if let Err(e) = telemetry.clear() {
+                        log::warn!("Failed to clear telemetry data: {}", e);
+                    }
+                }
+                Command::none()
+            }
+            Message::ShowDialog(dialog) => {
+                self.dialog = Some(*dialog);
+                Command::none()
+            }
+            Message::DialogInputChanged(text) => {
+                if let Some(dialog) = &mut self.dialog {
+                    dialog.set_input(text);
+                }
+                Command::none()
+            }
+            Message::DialogConfirmed => match self.dialog.take().and_then(|d| d.confirmed()) {
+                Some(message) => self.update(message),
+                None => Command::none(),
+            },
+            Message::DialogChose(index) => match self.dialog.take().and_then(|d| d.chosen(index)) {
+                Some(message) => self.update(message),
+                None => Command::none(),
+            },
+            Message::DialogCancelled => {
+                self.dialog = None;
+                Command::none()
+            }
+            Message::RepairSettings(action) => self.repair_settings(action),
+            Message::ResetSettings { keep_pairing } => {
+                let mut defaults = AppConfig {
+                    settings_path: self.config.settings_path.clone(),
+                    ..AppConfig::default()
+                };
+                if keep_pairing {
+                    defaults.bluetooth.paired_device_id =
+                        self.config.bluetooth.paired_device_id.clone();
+                    defaults.bluetooth.paired_device_name =
+                        self.config.bluetooth.paired_device_name.clone();
+                    defaults.bluetooth.paired_fingerprint =
+                        self.config.bluetooth.paired_fingerprint.clone();
+                }
+
+                // Offered for undo like a save from the settings window
+                self.settings_window.set_original(Some(self.config.clone()));
+                self.restore_config(defaults);
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    log::error!("Failed to save default settings: {}", e);
+                    self.raise_error("config", RustPodsError::Config(e.to_string()));
+                } else {
+                    log::info!("Reset settings to defaults");
+                }
+                self.offer_undo()
+            }
+            Message::ChooseFile(action) => {
+                Command::perform(crate::ui::file_dialogs::choose_file(action), move |path| {
+                    Message::FileChosen(action, path)
+                })
+            }
+            Message::FileChosen(_, None) => Command::none(),
+            Message::FileChosen(action, Some(path)) => self.use_chosen_file(action, path),
+            Message::FileDropped(path) => {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                match crate::ui::file_drop::identify(&path) {
+                    Ok(DroppedFile::Settings) => {
+                        self.dialog = Some(Dialog::confirm(
+                            "Import settings?",
+                            format!(
+                                "The settings in {} will be loaded into the settings window to review before saving.",
+                                name
+                            ),
+                            "Import",
+                            Message::FileChosen(FileAction::ImportSettings, Some(path)),
+                        ));
+                    }
+                    Ok(DroppedFile::BatteryProfile { device_name }) => {
+                        self.dialog = Some(
+                            Dialog::confirm(
+                                "Restore battery profile?",
+                                format!(
+                                    "The battery profile for {} in {} will replace everything RustPods has learned so far.",
+                                    device_name, name
+                                ),
+                                "Restore",
+                                Message::RestoreBatteryProfile(path),
+                            )
+                            .destructive(),
+                        );
+                    }
+                    Err(reason) => {
+                        log::info!("Ignoring dropped file {}: {}", path.display(), reason);
+                        self.toast_message = Some(reason);
+                    }
+                }
+                Command::none()
+            }
+            Message::RestoreBatteryProfile(path) => {
+                self.toast_message = Some(match self.battery_intelligence.import_profile(&path) {
+                    Ok(()) => {
+                        log::info!("Restored battery profile from {}", path.display());
+                        "Restored battery profile".to_string()
+                    }
+                    Err(e) => {
+                        log::error!("Failed to restore battery profile: {}", e);
+                        format!("Failed to restore battery profile: {}", e)
+                    }
+                });
+                Command::none()
+            }
+            Message::RestoreAllData(path) => {
+                self.restore_all_data(&path);
+                Command::none()
+            }
+            Message::ConfirmRestoreProfileSnapshot(snapshot) => {
+                self.dialog = Some(
+                    Dialog::confirm(
+                        "Restore previous profile state?",
+                        format!(
+                            "Battery data from the snapshot taken {} will replace everything RustPods has learned since. The current data is snapshotted first.",
+                            snapshot
+                        ),
+                        "Restore",
+                        Message::RestoreProfileSnapshot(snapshot),
+                    )
+                    .destructive(),
+                );
+                Command::none()
+            }
+            Message::RestoreProfileSnapshot(snapshot) => {
+                self.toast_message = Some(
+                    match self.battery_intelligence.restore_snapshot(&snapshot) {
+                        Ok(()) => "Restored previous battery profile state".to_string(),
+                        Err(e) => {
+                            log::error!("Failed to restore battery data snapshot: {}", e);
+                            format!("Failed to restore snapshot: {}", e)
+                        }
+                    },
+                );
+                self.settings_window
+                    .set_profile_snapshots(self.battery_intelligence.snapshots());
+                Command::none()
+            }
+            Message::ThemeScheduleTick => {
+                self.apply_theme_schedule();
+                self.sync_window_backdrop();
+                Command::none()
+            }
             Message::UpdateBluetoothSetting(setting) => {
                 self.settings_window.mark_changed();
                 self.update_bluetooth_setting(setting);
@@ -429,8 +1178,85 @@ impl Application for AppState {
                 self.settings_window.mark_changed();
                 self.update_ui_setting(setting);
                 self.settings_window.update_config(self.config.clone());
+                self.sync_capture_exclusion();
+                self.apply_theme_schedule();
+                self.sync_window_backdrop();
+                Command::none()
+            }
+            Message::UpdateActionSetting(setting) => {
+                self.settings_window.mark_changed();
+                self.update_action_setting(setting);
+                self.settings_window.update_config(self.config.clone());
+                Command::none()
+            }
+            Message::SyntheticDraftChanged(draft) => {
+                self.settings_window.set_synthetic_draft(draft);
+                Command::none()
+            }
+            Message::InjectSyntheticFrame(draft) => {
+                if !self.config.system.developer_mode {
+                    return Command::none();
+                }
+                let address = match draft.address.trim() {
+                    "" => crate::bluetooth::DEFAULT_SYNTHETIC_ADDRESS,
+                    address => address,
+                };
+                match frame_from_payload(address, &draft.payload) {
+                    Ok(frame) => {
+                        self.synthetic_frames.inject(frame);
+                        self.settings_window
+                            .set_synthetic_frames(self.synthetic_frames.len());
+                        // Run the frame through the pipeline now rather than at the next scan
+                        self.update(Message::AirPodsDataLoaded(self.airpods_devices.clone()))
+                    }
+                    Err(e) => {
+                        self.toast_message = Some(format!("Couldn't inject frame: {}", e));
+                        Command::none()
+                    }
+                }
+            }
+            Message::ClearSyntheticFrames => {
+                self.clear_synthetic_frames();
+                Command::none()
+            }
+            Message::RuleDraftChanged(draft) => {
+                self.settings_window.set_rule_draft(draft);
                 Command::none()
             }
+            Message::RecordShortcut(action) => {
+                self.settings_window.set_recording_shortcut(Some(action));
+                crate::ui::keyboard_shortcuts::set_recording(true);
+                Command::none()
+            }
+            Message::CancelShortcutRecording => {
+                self.settings_window.set_recording_shortcut(None);
+                crate::ui::keyboard_shortcuts::set_recording(false);
+                Command::none()
+            }
+            Message::ShortcutRecorded(shortcut) => {
+                let Some(action) = self.settings_window.recording_shortcut() else {
+                    crate::ui::keyboard_shortcuts::set_recording(false);
+                    return Command::none();
+                };
+                // Keep recording so another combination can be tried
+                if let Some(used_by) = crate::ui::keyboard_shortcuts::conflict(
+                    &self.config.ui.shortcuts,
+                    action,
+                    &shortcut,
+                ) {
+                    self.settings_window.set_shortcut_error(Some(format!(
+                        "{} is already used by {}",
+                        shortcut, used_by
+                    )));
+                    return Command::none();
+                }
+                self.settings_window.set_recording_shortcut(None);
+                crate::ui::keyboard_shortcuts::set_recording(false);
+                self.update(Message::UpdateUiSetting(UiSetting::Shortcut(
+                    action,
+                    shortcut.to_string(),
+                )))
+            }
             Message::UpdateSystemSetting(setting) => {
                 self.settings_window.mark_changed();
                 self.update_system_setting(setting);
@@ -438,34 +1264,92 @@ impl Application for AppState {
                 Command::none()
             }
             Message::OpenSettings => {
+                self.record_feature("settings");
                 self.settings_window.set_validation_error(None);
                 self.settings_window.update_config(self.config.clone());
+                self.settings_window
+                    .update_usage_summary(self.battery_intelligence.get_usage_summary());
+                self.settings_window.set_original(Some(self.config.clone()));
+                self.settings_window.set_audit_entries(
+                    AuditLog::for_config(&self.config).recent(AUDIT_ENTRIES_SHOWN),
+                );
+                self.settings_window
+                    .set_profile_snapshots(self.battery_intelligence.snapshots());
                 self.show_settings = true;
                 Command::none()
             }
             Message::CloseSettings => {
                 self.show_settings = false;
                 self.settings_window.update_config(self.config.clone());
+                self.settings_window.set_original(None);
                 Command::none()
             }
-            Message::SaveSettings => {
-                let updated_config = self.settings_window.config();
-                if let Err(e) = updated_config.validate() {
-                    self.settings_window
-                        .set_validation_error(Some(e.to_string()));
-                    log::error!("Settings validation failed: {}", e);
-                    return Command::none();
+            Message::RevertSettings => {
+                if let Some(original) = self.settings_window.original().cloned() {
+                    log::info!("Reverting settings to when the settings window opened");
+                    self.restore_config(original);
                 }
-                self.config = updated_config.clone();
-                if let Err(e) = self.config.save() {
-                    self.settings_window
-                        .set_validation_error(Some(format!("Failed to save: {}", e)));
-                    log::error!("Settings save failed: {}", e);
+                Command::none()
+            }
+            Message::UndoSettingsSave => {
+                let Some(previous) = self.undo_config.take() else {
                     return Command::none();
-                }
+                };
+                self.main_window.undo_notice = None;
+                self.restore_config(previous);
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    log::error!("Failed to save restored settings: {}", e);
+                    self.raise_error("config", RustPodsError::Config(e.to_string()));
+                } else {
+                    log::info!("Undid the last settings save");
+                }
+                Command::none()
+            }
+            Message::DismissUnknownModel => {
+                self.main_window.unknown_model = None;
+                Command::none()
+            }
+            Message::KeepSoftwareRendering => {
+                self.main_window.software_rendering_notice = false;
+                self.config.ui.software_rendering = true;
+                self.settings_window.update_config(self.config.clone());
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    log::error!("Failed to remember software rendering: {}", e);
+                    self.raise_error("config", RustPodsError::Config(e.to_string()));
+                } else {
+                    log::info!("Software rendering will be used from now on");
+                }
+                Command::none()
+            }
+            Message::DismissSoftwareRenderingNotice => {
+                self.main_window.software_rendering_notice = false;
+                Command::none()
+            }
+            Message::DismissUndo(generation) => {
+                if generation == self.undo_generation {
+                    self.undo_config = None;
+                    self.main_window.undo_notice = None;
+                }
+                Command::none()
+            }
+            Message::SaveSettings => {
+                let updated_config = self.settings_window.config();
+                if let Err(e) = updated_config.validate() {
+                    self.settings_window
+                        .set_validation_error(Some(e.to_string()));
+                    log::error!("Settings validation failed: {}", e);
+                    return Command::none();
+                }
+                self.config = updated_config.clone();
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    self.settings_window
+                        .set_validation_error(Some(format!("Failed to save: {}", e)));
+                    log::error!("Settings save failed: {}", e);
+                    return Command::none();
+                }
                 self.apply_settings();
                 self.show_settings = false;
-                Command::none()
+                self.offer_undo()
             }
             Message::SettingsChanged(config) => {
                 self.config = config.clone();
@@ -488,12 +1372,17 @@ impl Application for AppState {
                     devices.len()
                 );
 
-                // Clear existing devices and update with fresh data from async CLI scanner
-                self.merged_devices.clear();
-                self.merged_devices = devices.clone();
+                // Replace scanned devices with fresh data from async CLI scanner, keeping
+                // GATT devices the scanner doesn't report
+                self.merged_devices
+                    .retain(|device| device.device_type == DeviceType::Other);
+                self.merged_devices.extend(devices.iter().cloned());
+                self.apply_classic_connections();
 
                 // Update the main window with the new devices
-                self.main_window.merged_devices = devices.clone();
+                self.main_window.merged_devices = self.merged_devices.clone();
+                self.main_window
+                    .sync_battery_animation(std::time::Instant::now());
 
                 // Set status message only when no devices are found
                 if devices.is_empty() {
@@ -511,18 +1400,96 @@ impl Application for AppState {
                 Command::none()
             }
             Message::Tick => {
+                if self.suspended_since.is_some() {
+                    crate::debug_log!("ui", "Tick ignored - system is suspended");
+                    return Command::none();
+                }
+                if !self.scanner_supervisor.ready(std::time::Instant::now()) {
+                    crate::debug_log!("ui", "Tick skipped - scanner is backing off");
+                    self.sync_scanner_status();
+                    return Command::none();
+                }
                 crate::debug_log!("ui", "Tick message received - performing continuous scan");
+                self.notifier
+                    .set_quiet(self.is_quiet(), self.config.ui.quiet_hours.summarize_held);
+                if self.window_size_unsaved {
+                    self.window_size_unsaved = false;
+                    if let Err(e) = self.config.save() {
+                        log::warn!("Failed to save window size: {}", e);
+                    }
+                }
+                // Retrying after failed scans counts as the backend restarting
+                self.set_tray_activity(if self.consecutive_scan_failures > 0 {
+                    TrayActivity::Restarting
+                } else {
+                    TrayActivity::Scanning
+                });
                 // Use the continuous scanning function for periodic updates
-                Command::perform(
-                    async {
-                        tokio::task::spawn_blocking(get_airpods_from_cli_scanner_continuous)
-                            .await
-                            .unwrap_or_else(|_| Vec::new())
+                let supervisor = self.scanner_supervisor.clone();
+                let focus = self.focused_scan.clone();
+                let scan = Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            // With the radio off every scan fails without saying why
+                            if let Err(e) = preflight::check() {
+                                return Message::BluetoothUnavailable(e);
+                            }
+                            match get_airpods_from_cli_scanner_continuous(&supervisor, &focus) {
+                                Ok(airpods_data) => Message::AirPodsDataLoaded(airpods_data),
+                                Err(message) => Message::ScanFailed(message),
+                            }
+                        })
+                        .await
+                        .unwrap_or_else(|e| {
+                            Message::ScanFailed(format!("Scanner task failed: {}", e))
+                        })
                     },
-                    Message::AirPodsDataLoaded,
-                )
+                    std::convert::identity,
+                );
+                Command::batch([
+                    scan,
+                    self.refresh_known_issues(),
+                    self.run_scheduled_backup(),
+                ])
             }
-            Message::AirPodsDataLoaded(airpods_data) => {
+            Message::AirPodsDataLoaded(mut airpods_data) => {
+                // Synthetic frames from developer mode join the scan as if received
+                self.synthetic_frames.apply(&mut airpods_data);
+
+                // Follow the paired AirPods to their current address before filtering,
+                // so "only my paired device" keeps working as the address rotates
+                self.track_paired_device(&airpods_data);
+
+                // Neighbors' AirPods and other excluded devices are dropped up front
+                let access = self.config.bluetooth.device_access();
+                if !access.is_open() {
+                    airpods_data.retain(|airpods| {
+                        access.allows(&airpods.canonical_address, Some(&airpods.name))
+                    });
+                }
+                self.set_tray_activity(TrayActivity::Idle);
+                self.record_telemetry(TelemetryEvent::Scan);
+                for _ in &airpods_data {
+                    self.record_telemetry(TelemetryEvent::BatteryReading);
+                }
+                // Any result but the ones sent for a failed or skipped scan means the
+                // scanner worked, even if it heard nothing
+                let scan_failed = std::mem::take(&mut self.scan_failed);
+                let radio_off = std::mem::take(&mut self.bluetooth_unavailable);
+                if !scan_failed && !radio_off {
+                    self.resolve_errors("scanner");
+                }
+                if !radio_off {
+                    self.resolve_errors("radio");
+                }
+                self.sync_scanner_status();
+
+                // A scan that straddled a suspend is stale; the resume handler rescans
+                if self.suspended_since.is_some() {
+                    crate::debug_log!("airpods", "Dropping scan results received during suspend");
+                    return Command::none();
+                }
+
                 // Handle the result of the async AirPods data loading
                 log::info!("AirPods data loaded: {} devices found", airpods_data.len());
                 crate::debug_log!(
@@ -556,7 +1523,7 @@ impl Application for AppState {
 
                     // Only change to NoDevicesFound after 3 consecutive failures
                     // This prevents flashing when the scanner is temporarily intermittent
-                    if self.consecutive_scan_failures >= 3 {
+                    if self.consecutive_scan_failures >= 3 && !self.has_gatt_device() {
                         // Only change state if we're not already in NoDevicesFound
                         if self.device_detection_state != DeviceDetectionState::NoDevicesFound {
                             crate::debug_log!(
@@ -564,8 +1531,10 @@ impl Application for AppState {
                                 "Switching to NoDevicesFound state after {} consecutive failures",
                                 self.consecutive_scan_failures
                             );
-                            self.device_detection_state = DeviceDetectionState::NoDevicesFound;
+                            self.set_detection_state(DeviceDetectionState::NoDevicesFound);
                         }
+                        // The case stops advertising once closed, so treat it as closed
+                        self.last_case_lid_open = Some(false);
                     } else {
                         // Still in tolerance period - keep current state if devices were previously found
                         if self.device_detection_state == DeviceDetectionState::DevicesFound {
@@ -590,7 +1559,7 @@ impl Application for AppState {
                     // Update to DevicesFound state
                     if self.device_detection_state != DeviceDetectionState::DevicesFound {
                         crate::debug_log!("airpods", "Switching to DevicesFound state");
-                        self.device_detection_state = DeviceDetectionState::DevicesFound;
+                        self.set_detection_state(DeviceDetectionState::DevicesFound);
                     }
                 }
 
@@ -600,12 +1569,28 @@ impl Application for AppState {
 
                 // Update the merged devices to include the new AirPods data
                 self.update_merged_devices();
+                self.sync_connection_status();
+                self.sync_expanded_details();
+                self.announce_low_battery();
+                self.notify_low_battery();
+                self.check_critical_battery();
+                self.run_action_rules();
+                self.sync_tray_tooltip();
+                self.sync_taskbar_badge();
+                self.sync_capture_exclusion();
+                self.sync_window_backdrop();
+                self.install_caption_hit_testing();
+                crate::crash::set_state_summary(self.crash_state_summary());
 
                 // Update the main window's device detection state to match the AppState
                 self.main_window
                     .update_device_detection_state(self.device_detection_state.clone());
 
-                Command::none()
+                Command::batch(vec![
+                    self.check_lid_open_popup(),
+                    self.refresh_audio_routing(),
+                    self.refresh_classic_connections(),
+                ])
             }
             // Window drag handling
             Message::WindowDragStart(_point) => {
@@ -616,17 +1601,69 @@ impl Application for AppState {
                 crate::debug_log!("ui", "Window drag ended");
                 Command::none()
             }
+            Message::MinimizeWindow => iced::window::minimize(true),
+            Message::WindowResized(width, height) => {
+                // Minimizing reports a zero size; keep the last real one
+                if width == 0 || height == 0 {
+                    return Command::none();
+                }
+                let (width, height) =
+                    crate::ui::window_management::clamp_window_size(width, height);
+                self.main_window.window_size = (width, height);
+                let size = Some(WindowSize { width, height });
+                if self.config.ui.window_size != size {
+                    // Written on the next tick rather than on every step of a drag
+                    self.config.ui.window_size = size;
+                    self.window_size_unsaved = true;
+                }
+                Command::none()
+            }
+            Message::ToggleAdvancedInfo => self.main_window.toggle_advanced_display(),
+            Message::ToggleViewMode => self.main_window.toggle_view_mode(),
             Message::WindowDragMove(_point) => {
                 // Handle window drag move if needed
                 Command::none()
             }
+            Message::PairDevice(address) => {
+                self.pair_device(&address);
+                Command::none()
+            }
+            Message::UnpairDevice => {
+                self.unpair_device();
+                Command::none()
+            }
+            Message::EditKnownDevice(id, edit) => {
+                self.edit_known_device(&id, edit);
+                Command::none()
+            }
+            Message::TrackDeviceAddress(input) => {
+                self.track_device_address(&input);
+                Command::none()
+            }
+            Message::UntrackDeviceAddress(address) => {
+                self.config
+                    .bluetooth
+                    .tracked_devices
+                    .retain(|tracked| *tracked != address);
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    log::error!("Failed to save tracked devices: {}", e);
+                }
+                self.settings_window.update_config(self.config.clone());
+                self.main_window.config = self.config.clone();
+                self.apply_device_access();
+                Command::none()
+            }
+            Message::DeviceFilterInputChanged(value) => {
+                self.settings_window.set_device_filter_input(value);
+                Command::none()
+            }
             Message::SetDeviceName(name) => {
                 self.config.bluetooth.paired_device_name = if name.trim().is_empty() {
                     None
                 } else {
                     Some(name.trim().to_string())
                 };
-                if let Err(e) = self.config.save() {
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
                     log::error!("Failed to save device name: {}", e);
                 } else {
                     // Update both the settings window and main window with the new config
@@ -705,6 +1742,159 @@ impl Application for AppState {
                 }
                 Command::none()
             }
+            Message::ClosePopup => {
+                let generation = self.lid_popup_generation;
+                self.dismiss_lid_popup(generation)
+            }
+            Message::DismissLidPopup(generation) => self.dismiss_lid_popup(generation),
+            Message::AudioRoutingUpdated(routing) => {
+                self.main_window.audio_routing = routing;
+                Command::none()
+            }
+            Message::ClassicConnectionsUpdated(devices) => {
+                if devices == self.classic_devices {
+                    return Command::none();
+                }
+                self.classic_devices = devices;
+                self.apply_classic_connections();
+                self.main_window.merged_devices = self.merged_devices.clone();
+                self.sync_connected_device_names();
+                self.sync_connection_status();
+                self.run_action_rules();
+                self.sync_tray_tooltip();
+                self.sync_taskbar_badge();
+                // The device may have just connected or disconnected
+                self.refresh_audio_routing()
+            }
+            Message::SwitchAudioToAirPods => {
+                let Some(device) = self.merged_devices.first() else {
+                    return Command::none();
+                };
+                let name = device.name.clone();
+                log::info!("Switching audio output to {}", name);
+                self.record_feature("switch_audio");
+                Command::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || {
+                            if let Err(e) = crate::system::audio::switch_output_to(&name) {
+                                log::warn!("Failed to switch audio output: {}", e);
+                            }
+                            crate::system::audio::audio_routing(&name).ok()
+                        })
+                        .await
+                        .unwrap_or(None)
+                    },
+                    Message::AudioRoutingUpdated,
+                )
+            }
+            Message::SpeakBatteryStatus => {
+                if !self.config.ui.speech.enabled {
+                    crate::debug_log!("ui", "Speech disabled, ignoring battery status request");
+                    return Command::none();
+                }
+                self.record_feature("speak_battery_status");
+                let text = self
+                    .merged_devices
+                    .first()
+                    .and_then(|device| {
+                        crate::system::speech::battery_status_announcement(
+                            device.left_battery,
+                            device.right_battery,
+                            device.case_battery,
+                        )
+                    })
+                    .unwrap_or_else(|| "No AirPods found".to_string());
+                if let Err(e) = self.speech.speak(&text, &self.config.ui.speech) {
+                    log::warn!("Failed to speak battery status: {}", e);
+                }
+                Command::none()
+            }
+            Message::FocusNext | Message::FocusPrevious => {
+                if !self.main_window_shown() {
+                    return Command::none();
+                }
+                let order = self.main_window.focus_targets();
+                let forward = matches!(message, Message::FocusNext);
+                self.main_window.focused =
+                    accessibility::step(&order, self.main_window.focused, forward);
+                self.announce_focus();
+                Command::none()
+            }
+            Message::ActivateFocused => {
+                let Some(target) = self
+                    .main_window
+                    .focused
+                    .filter(|_| self.main_window_shown())
+                else {
+                    return Command::none();
+                };
+                match target.message() {
+                    Some(message) => self.update(message),
+                    // Battery widgets are read out again instead
+                    None => {
+                        self.announce_focus();
+                        Command::none()
+                    }
+                }
+            }
+            Message::EscapePressed => {
+                if self.dialog.is_some() {
+                    self.update(Message::DialogCancelled)
+                } else if self.lid_popup.is_some() {
+                    self.dismiss_lid_popup(self.lid_popup_generation)
+                } else if self.show_telemetry_data {
+                    self.update(Message::CloseTelemetryData)
+                } else if self.show_troubleshoot {
+                    self.update(Message::CloseTroubleshoot)
+                } else if self.show_settings {
+                    self.update(Message::CloseSettings)
+                } else {
+                    self.main_window.focused = None;
+                    Command::none()
+                }
+            }
+            Message::SystemSuspended => {
+                log::info!("System suspending - pausing AirPods scanning");
+                self.suspended_since = Some(std::time::SystemTime::now());
+                if let Err(e) = self.battery_intelligence.save() {
+                    log::error!("Failed to save battery intelligence before suspend: {}", e);
+                }
+                self.battery_intelligence.flush();
+                self.household_intelligence.flush();
+                self.flush_telemetry();
+                Command::none()
+            }
+            Message::SystemResumed => {
+                let Some(suspended_at) = self.suspended_since.take() else {
+                    return Command::none();
+                };
+                log::info!("System resumed - restarting AirPods scanning");
+                self.battery_intelligence
+                    .record_system_suspend(suspended_at, std::time::SystemTime::now());
+                // Give the scanner a clean slate rather than counting the sleep as failures
+                self.consecutive_scan_failures = 0;
+                let command = self.update(Message::Tick);
+                self.set_tray_activity(TrayActivity::Restarting);
+                command
+            }
+            Message::BatteryAnimationFrame => {
+                // Time since any fixed point will do for the pulse phase
+                let elapsed = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default();
+                self.animation_progress = crate::ui::battery_animation::pulse_phase(elapsed);
+                self.main_window.animation_progress = self.animation_progress;
+                Command::none()
+            }
+            Message::AnimationTick => {
+                if self
+                    .critical_alert_until
+                    .is_some_and(|until| std::time::Instant::now() >= until)
+                {
+                    self.acknowledge_critical_alert();
+                }
+                Command::none()
+            }
             _ => {
                 crate::debug_log!(
                     "ui",
@@ -717,8 +1907,38 @@ impl Application for AppState {
     }
 
     fn view(&self) -> iced::Element<'_, Message, iced::Renderer<crate::ui::theme::Theme>> {
+        // Only the main window draws the custom title bar; other screens own that strip
+        crate::ui::window_management::set_native_caption_enabled(
+            self.visible
+                && self.dialog.is_none()
+                && self.lid_popup.is_none()
+                && !self.show_telemetry_data
+                && !self.show_troubleshoot
+                && !self.show_settings,
+        );
+
         if !self.visible {
             iced::widget::text("").into()
+        } else if let Some(dialog) = &self.dialog {
+            dialog.view()
+        } else if let Some(popup) = &self.lid_popup {
+            iced::widget::container(crate::ui::UiComponent::view(popup))
+                .width(iced::Length::Fill)
+                .height(iced::Length::Fill)
+                .center_x()
+                .center_y()
+                .into()
+        } else if self.show_telemetry_data {
+            let (report, enabled) = match self.telemetry.lock() {
+                Ok(telemetry) => (telemetry.report(), telemetry.is_enabled()),
+                Err(_) => return iced::widget::text("Telemetry unavailable").into(),
+            };
+            crate::ui::components::view_telemetry_data(report, enabled)
+        } else if self.show_troubleshoot {
+            crate::ui::components::view_troubleshoot(
+                self.health_check.as_ref(),
+                self.health_check_running,
+            )
         } else if self.show_settings {
             // Just show the settings content with full size - no overlays
             crate::ui::UiComponent::view(&self.settings_window)
@@ -735,67 +1955,108 @@ impl Application for AppState {
         // Timer for periodic CLI scanner updates (every 10 seconds for good responsiveness)
         let timer = time::every(Duration::from_secs(10)).map(|_| Message::Tick);
 
-        // Controller subscription for system tray communication
-        let controller_subscription = iced::subscription::unfold(
-            "controller-messages",
-            (),
-            |_state| async move {
+        // Controller subscription for system tray communication. If every sender is dropped
+        // the channel closes and tray clicks would be lost, so the subscription replaces it
+        // and hands the new sender to `rebind_controller_sender`.
+        let controller_subscription =
+            iced::subscription::unfold("controller-messages", 0u32, |restarts| async move {
                 crate::debug_log!(
                     "ui",
                     "Controller subscription: Checking for messages from system tray"
                 );
                 // Access the global receiver safely
-                if let Some(receiver_arc) = CONTROLLER_RECEIVER.get() {
-                    let mut guard = receiver_arc.lock().await;
-                    if let Some(ref mut receiver) = *guard {
-                        match receiver.recv().await {
-                            Some(message) => {
-                                crate::debug_log!(
-                                    "ui",
-                                    "Controller subscription: Received message: {:?}",
-                                    message
-                                );
-                                log::info!("Controller subscription received message from system tray: {:?}", message);
-                                (message, ())
-                            }
-                            None => {
-                                crate::debug_log!(
-                                    "ui",
-                                    "Controller subscription: Channel closed, no more messages"
-                                );
-                                log::warn!(
-                                    "Controller channel closed - system tray communication lost"
-                                );
-                                // Channel closed, wait and try again
-                                tokio::time::sleep(Duration::from_millis(100)).await;
-                                (Message::NoOp, ())
-                            }
-                        }
-                    } else {
-                        crate::debug_log!("ui", "Controller subscription: No receiver available");
-                        log::warn!("No controller receiver available");
-                        // No receiver, wait and try again
-                        tokio::time::sleep(Duration::from_millis(100)).await;
-                        (Message::NoOp, ())
-                    }
-                } else {
+                let Some(receiver_arc) = CONTROLLER_RECEIVER.get() else {
                     crate::debug_log!("ui", "Controller subscription: CONTROLLER_RECEIVER not set");
                     log::warn!("CONTROLLER_RECEIVER not initialized");
                     // Not initialized yet, wait and try again
                     tokio::time::sleep(Duration::from_millis(100)).await;
-                    (Message::NoOp, ())
+                    return (Message::NoOp, restarts);
+                };
+
+                let mut guard = receiver_arc.lock().await;
+                if let Some(ref mut receiver) = *guard {
+                    if let Some(message) = receiver.recv().await {
+                        crate::debug_log!(
+                            "ui",
+                            "Controller subscription: Received message: {:?}",
+                            message
+                        );
+                        log::info!(
+                            "Controller subscription received message from system tray: {:?}",
+                            message
+                        );
+                        return (message, restarts);
+                    }
+                    log::warn!("Controller channel closed - system tray communication lost");
+                } else {
+                    log::warn!("No controller receiver available");
                 }
-            },
-        );
+
+                // Don't spin if the replacement keeps closing straight away
+                tokio::time::sleep(CONTROLLER_RESTART_DELAY).await;
+                let (sender, receiver) = mpsc::unbounded_channel();
+                *guard = Some(receiver);
+                let restarts = restarts + 1;
+                log::warn!(
+                    "Recreated the controller channel (restart {}); re-binding the system tray",
+                    restarts
+                );
+                (Message::ControllerChannelRestored(sender), restarts)
+            });
+
+        // The tray thread animates the icon itself; this only ends a timed critical flash
+        let tray_animation = if self.critical_alert_until.is_some() {
+            time::every(Duration::from_secs(1)).map(|_| Message::AnimationTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Battery widget frames, only while something moves; a pulsing bolt alone
+        // needs fewer
+        let now = std::time::Instant::now();
+        let battery_animation = if !self.visible || !self.main_window.is_animating(now) {
+            Subscription::none()
+        } else if self.main_window.battery_animation.is_animating(now) {
+            time::every(crate::ui::battery_animation::FRAME_INTERVAL)
+                .map(|_| Message::BatteryAnimationFrame)
+        } else {
+            time::every(crate::ui::battery_animation::PULSE_FRAME_INTERVAL)
+                .map(|_| Message::BatteryAnimationFrame)
+        };
+
+        // Day/night theme switching, only while a schedule is configured
+        let theme_schedule = if self.config.ui.theme_schedule.mode != ThemeScheduleMode::Off {
+            time::every(crate::ui::theme_schedule::THEME_SCHEDULE_INTERVAL)
+                .map(|_| Message::ThemeScheduleTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Background health check for the title bar badge
+        let health_check = time::every(crate::diagnostics::HEALTH_CHECK_INTERVAL)
+            .map(|_| Message::HealthCheckTick);
 
         Subscription::batch(vec![
             timer, // Add the timer subscription for periodic CLI scanner updates
-            iced::subscription::events_with(|event, _status| {
-                if let iced::Event::Window(iced::window::Event::CloseRequested) = event {
+            health_check,
+            tray_animation,
+            battery_animation,
+            theme_schedule,
+            iced::subscription::events_with(|event, status| match event {
+                iced::Event::Window(iced::window::Event::CloseRequested) => {
                     Some(Message::WindowCloseRequested)
-                } else {
-                    None
                 }
+                iced::Event::Window(iced::window::Event::Resized { width, height }) => {
+                    Some(Message::WindowResized(width, height))
+                }
+                iced::Event::Window(iced::window::Event::FileDropped(path)) => {
+                    Some(Message::FileDropped(path))
+                }
+                // Leave keys captured by widgets (e.g. text inputs) alone
+                iced::Event::Keyboard(_) if status == iced::event::Status::Ignored => {
+                    crate::ui::keyboard_shortcuts::handle_active_event(event)
+                }
+                _ => None,
             }),
             controller_subscription, // Add the controller subscription for system tray communication
         ])
@@ -806,11 +2067,41 @@ impl AppState {
     /// Toggle the visibility of the application
     pub fn toggle_visibility(&mut self) {
         self.visible = !self.visible;
+        if self.visible {
+            self.acknowledge_critical_alert();
+        }
+    }
+
+    /// Move the window into the corner by the system tray, if set to open there
+    fn position_near_tray(&self) -> Command<Message> {
+        if !self.config.ui.open_near_tray {
+            return Command::none();
+        }
+        let Some(anchor) = crate::ui::window_management::tray_anchor() else {
+            return Command::none();
+        };
+        let (x, y) = crate::ui::window_management::tray_popup_position(
+            &anchor,
+            self.main_window.window_size,
+        );
+        // iced moves the window in logical pixels of the monitor it is on now
+        let scale = crate::ui::theme::display_scale();
+        iced::window::move_to(
+            (x as f32 / scale).round() as i32,
+            (y as f32 / scale).round() as i32,
+        )
     }
 
     /// Update a device in the devices list
     pub fn update_device(&mut self, device: DiscoveredDevice) {
         let address = device.address.to_string();
+        if !self
+            .config
+            .bluetooth
+            .allows_device(&address, device.name.as_deref())
+        {
+            return;
+        }
         self.devices.insert(address, device);
     }
 
@@ -899,48 +2190,1672 @@ impl AppState {
     fn load_settings(&mut self) -> Result<(), ConfigError> {
         match AppConfig::load() {
             Ok(config) => {
+                crate::config::repair::unprotect(&config.settings_path);
                 self.config = config;
                 Ok(())
             }
             Err(e) => {
                 self.settings_error = Some(format!("Failed to load settings: {}", e));
                 log::error!("Failed to load settings: {}", e);
+                self.raise_error("config", RustPodsError::Config(e.to_string()));
                 Err(e)
             }
         }
     }
 
-    /// Apply settings to the application
-    fn apply_settings(&mut self) {
-        // Update all components with new settings
+    /// Show a startup banner when the settings file can't be used, naming the setting,
+    /// line and value at fault
+    ///
+    /// A file that doesn't parse or validate is protected from saves and a repair dialog
+    /// is offered, rather than the defaults quietly replacing it.
+    fn report_settings_problems(&mut self) {
+        if let Err(e) = AppConfig::load() {
+            log::error!("Failed to load settings: {}", e.detailed());
+            self.settings_error = Some(format!("Failed to load settings: {}", e));
+            if matches!(
+                e,
+                ConfigError::ParseError(_) | ConfigError::ValidationFailed(..)
+            ) {
+                crate::config::repair::protect(&self.config.settings_path);
+                self.dialog = Some(settings_repair_dialog(&e));
+            }
+            self.raise_error("config", e.into());
+        }
+    }
 
-        // Update main window theme
-        // (This would be implemented in a real app by applying theme settings
-        // to all UI components)
+    /// Show a banner for each data file that was set aside because it couldn't be read,
+    /// offering to salvage it
+    fn report_quarantined_files(&mut self) {
+        for file in self.quarantined_files() {
+            let folder = file
+                .path
+                .parent()
+                .map(|folder| folder.display().to_string())
+                .unwrap_or_default();
+            self.raise_error(
+                "quarantine",
+                RustPodsError::Quarantined(format!(
+                    "{} couldn't be read and was moved to {}",
+                    file.file_name(),
+                    folder
+                )),
+            );
+        }
+    }
 
-        // Update system tray configuration if available
-        /*
-        if let Some(system_tray) = &mut self.system_tray {
-            if let Err(e) = system_tray.update_config(self.config.clone()) {
-                log::error!("Failed to update system tray config: {}", e);
+    /// Tell the user which startup phases RustPods is running without
+    fn report_degraded_startup(&mut self) {
+        let report = crate::lifecycle_manager::startup_report();
+        for record in report.degraded() {
+            self.raise_error(
+                "startup",
+                RustPodsError::Lifecycle(format!("Startup phase {}", record)),
+            );
+        }
+    }
+
+    /// Quarantined files that recovery hasn't been attempted for, from every folder
+    /// RustPods keeps data in
+    fn quarantined_files(&self) -> Vec<QuarantinedFile> {
+        let mut folders = vec![
+            self.battery_intelligence.storage_dir.clone(),
+            dirs::data_local_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join("RustPods"),
+        ];
+        if let Some(folder) = KnownDevices::path_for(&self.config).parent() {
+            folders.push(folder.to_path_buf());
+        }
+        folders.sort();
+        folders.dedup();
+        folders
+            .iter()
+            .flat_map(|folder| quarantine::unresolved(folder))
+            .collect()
+    }
+
+    /// Salvage the quarantined files and put what could be read back in place
+    ///
+    /// Each file is marked as dealt with whether or not it could be salvaged, so the
+    /// banner isn't shown again; the file stays in the quarantine folder either way. When
+    /// the drive is low on space nothing is touched, so recovery can be tried again.
+    fn recover_quarantined_files(&mut self) {
+        if let Err(e) = crate::system::ensure_room(&self.battery_intelligence.storage_dir) {
+            log::warn!("Not recovering quarantined files: {}", e);
+            self.toast_message = Some(format!("Couldn't recover the quarantined data: {}", e));
+            return;
+        }
+        let files = self.quarantined_files();
+        let known_devices_path = KnownDevices::path_for(&self.config);
+        let mut recovered = 0;
+        for mut file in files.clone() {
+            let result = if file.original == self.battery_intelligence.profile_path() {
+                self.battery_intelligence
+                    .recover_quarantined(&file)
+                    .map_err(|e| e.to_string())
+            } else if file.original == known_devices_path {
+                quarantine::salvage::<KnownDevices>(&file).map(|devices| {
+                    // The salvaged devices are worth saving even if none were loaded
+                    self.known_devices = devices;
+                    self.known_devices_read_only = false;
+                    self.save_known_devices();
+                })
+            } else {
+                quarantine::restore::<PersistentState>(&file).map(|_| ())
+            };
+            match result {
+                Ok(()) => recovered += 1,
+                Err(e) => log::warn!("Couldn't salvage {}: {}", file.path.display(), e),
+            }
+            if let Err(e) = file.mark_resolved() {
+                log::warn!("Failed to update {}: {}", file.path.display(), e);
             }
         }
-        */
+        self.resolve_errors("quarantine");
+        self.toast_message = Some(if recovered == files.len() {
+            "Recovered the quarantined data".to_string()
+        } else {
+            format!(
+                "Recovered {} of {} files; the rest are kept in the quarantine folder",
+                recovered,
+                files.len()
+            )
+        });
+    }
 
-        log::info!("Settings applied");
+    /// Apply the repair chosen in the settings repair dialog
+    fn repair_settings(&mut self, action: RepairAction) -> Command<Message> {
+        let path = self.config.settings_path.clone();
+        log::info!("Repairing settings: {}", action.label());
+        let repaired = match action {
+            RepairAction::OpenFile => {
+                if let Err(e) = issue_report::open_in_browser(&path.to_string_lossy()) {
+                    log::warn!("Failed to open {}: {}", path.display(), e);
+                }
+                self.toast_message =
+                    Some(format!("Fix {} and choose Reload settings", path.display()));
+                return Command::none();
+            }
+            RepairAction::RestoreBackup => crate::config::repair::restore_backup(&path),
+            RepairAction::ResetInvalidSections => {
+                crate::config::repair::reset_invalid_sections(&path).map(|(config, sections)| {
+                    log::info!("Reset settings sections: {}", sections.join(", "));
+                    config
+                })
+            }
+            RepairAction::ResetAll => crate::config::repair::reset_all(&path),
+        };
+
+        match repaired {
+            Ok(config) => {
+                self.restore_config(config);
+                self.settings_error = None;
+                self.resolve_errors("config");
+                self.toast_message = Some("Settings repaired".to_string());
+            }
+            Err(e) => {
+                log::warn!("Settings repair failed: {}", e);
+                self.toast_message = Some(format!("{} didn't work: {}", action.label(), e));
+                self.dialog = Some(settings_repair_dialog(&e));
+            }
+        }
+        Command::none()
     }
 
-    /// Update a Bluetooth setting
-    fn update_bluetooth_setting(&mut self, setting: BluetoothSetting) {
-        match setting {
-            BluetoothSetting::DeviceName(value) => {
-                self.config.bluetooth.paired_device_name = if value.trim().is_empty() {
-                    None
-                } else {
-                    Some(value.trim().to_string())
-                };
+    /// Switch palettes when the theme schedule enters a new period
+    fn apply_theme_schedule(&mut self) {
+        let theme = scheduled_or_default_theme(&self.config);
+        if theme != self.active_theme {
+            log::info!(
+                "Theme schedule switching from {:?} to {:?}",
+                self.active_theme,
+                theme
+            );
+            self.active_theme = theme;
+        }
+    }
+
+    /// Apply the configured window backdrop, falling back to solid when unsupported
+    fn sync_window_backdrop(&mut self) {
+        let backdrop = self.config.ui.backdrop;
+        let dark = self.active_theme != crate::ui::theme::Theme::Light;
+        let requested = Some((backdrop, dark));
+        // Windows start without a backdrop, so there is nothing to undo initially
+        if self.backdrop_applied == requested
+            || (self.backdrop_applied.is_none() && backdrop == WindowBackdrop::Solid)
+        {
+            return;
+        }
+
+        match crate::system::apply_window_backdrop(backdrop, dark) {
+            Ok(0) => crate::debug_log!("ui", "No windows to apply the backdrop to yet"),
+            Ok(_) => {
+                log::info!("Applied {} window backdrop", backdrop);
+                self.backdrop_active = backdrop != WindowBackdrop::Solid;
+                self.backdrop_applied = requested;
+            }
+            Err(e) => {
+                log::info!(
+                    "{} backdrop unavailable, using solid colors: {}",
+                    backdrop,
+                    e
+                );
+                self.backdrop_active = false;
+                self.backdrop_applied = requested;
+            }
+        }
+    }
+
+    /// Summarize the state for crash reports
+    fn crash_state_summary(&self) -> String {
+        format!(
+            "Detection state: {:?}\nDevices: {}\nConsecutive scan failures: {}\n\
+             Window visible: {}\nSettings open: {}\nSuspended: {}",
+            self.device_detection_state,
+            self.airpods_devices
+                .iter()
+                .map(|device| device.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.consecutive_scan_failures,
+            self.visible,
+            self.show_settings,
+            self.suspended_since.is_some()
+        )
+    }
+
+    /// Apply the screen capture exclusion setting to the windows if it changed
+    fn sync_capture_exclusion(&mut self) {
+        let excluded = self.config.ui.exclude_from_capture;
+        // Windows are created with normal affinity, so there is nothing to undo initially
+        if self.capture_excluded.unwrap_or(false) == excluded {
+            return;
+        }
+
+        match crate::system::set_excluded_from_capture(excluded) {
+            Ok(0) => crate::debug_log!("ui", "No windows to update capture exclusion for yet"),
+            Ok(count) => {
+                log::info!(
+                    "{} screen capture for {} window(s)",
+                    if excluded {
+                        "Excluded from"
+                    } else {
+                        "Restored"
+                    },
+                    count
+                );
+                self.capture_excluded = Some(excluded);
+            }
+            Err(e) => {
+                log::warn!("Failed to update screen capture exclusion: {}", e);
+                // Don't retry on every scan
+                self.capture_excluded = Some(excluded);
+            }
+        }
+    }
+
+    /// Move to `state`, warning about transitions missing from
+    /// [`DeviceDetectionState::TRANSITIONS`] so the drawn state machine stays accurate
+    fn set_detection_state(&mut self, state: DeviceDetectionState) {
+        let (from, to) = (self.device_detection_state.name(), state.name());
+        if !state_graph::is_allowed(DeviceDetectionState::TRANSITIONS, from, to) {
+            log::warn!("Unexpected device detection transition {} -> {}", from, to);
+        }
+        self.device_detection_state = state;
+    }
+
+    /// Show a GATT battery reading for a selected non-Apple device
+    fn merge_gatt_battery(&mut self, device: &DiscoveredDevice, status: &AirPodsBatteryStatus) {
+        let merged = MergedBluetoothDevice::from_gatt_battery(device, status);
+        self.set_detection_state(DeviceDetectionState::Connected {
+            device_name: merged.name.clone(),
+            device_address: merged.address.clone(),
+        });
+        match self
+            .merged_devices
+            .iter_mut()
+            .find(|existing| existing.address == merged.address)
+        {
+            Some(existing) => *existing = merged,
+            // The selected device is the one the main window shows
+            None => self.merged_devices.insert(0, merged),
+        }
+        self.main_window.merged_devices = self.merged_devices.clone();
+        self.main_window
+            .update_device_detection_state(self.device_detection_state.clone());
+        self.sync_tray_tooltip();
+        self.sync_taskbar_badge();
+    }
+
+    /// Whether a device monitored over GATT is being shown
+    fn has_gatt_device(&self) -> bool {
+        self.merged_devices
+            .iter()
+            .any(|device| device.device_type == DeviceType::Other)
+    }
+
+    /// Register the custom title bar for native hit-testing once the window is visible
+    fn install_caption_hit_testing(&mut self) {
+        if self.caption_hit_testing {
+            return;
+        }
+
+        match crate::ui::window_management::install_native_caption_hit_testing() {
+            Ok(0) => crate::debug_log!("ui", "No visible windows for caption hit-testing yet"),
+            Ok(count) => {
+                log::info!("Native caption hit-testing enabled for {} window(s)", count);
+                self.caption_hit_testing = true;
             }
+            Err(e) => {
+                log::warn!("Failed to enable native caption hit-testing: {}", e);
+                // Don't retry on every scan
+                self.caption_hit_testing = true;
+            }
+        }
+    }
+
+    /// Point the system tray and power monitor at a replacement controller channel
+    fn rebind_controller_sender(&mut self, sender: mpsc::UnboundedSender<Message>) {
+        crate::lifecycle_manager::spawn_power_event_monitor(sender.clone());
+        let tray_took_sender = self
+            .system_tray
+            .as_ref()
+            .is_some_and(|tray| tray.set_ui_sender(sender.clone()));
+        // Without a live tray nothing else holds the channel open for power events
+        self.controller_sender = (!tray_took_sender).then_some(sender);
+        log::info!("Controller channel restored - system tray communication recovered");
+    }
+
+    /// Count an event in the local telemetry (a no-op unless the user opted in)
+    fn record_telemetry(&self, event: TelemetryEvent) {
+        if let Ok(telemetry) = self.telemetry.lock() {
+            telemetry.record_event(event);
+        }
+    }
+
+    /// Count a use of the named feature in the local telemetry
+    fn record_feature(&self, feature: &str) {
+        self.record_telemetry(TelemetryEvent::FeatureUsed(feature.to_string()));
+    }
+
+    /// Write the aggregated telemetry to disk (only when the user opted in)
+    fn flush_telemetry(&self) {
+        if let Ok(telemetry) = self.telemetry.lock() {
+            match telemetry.flush() {
+                Ok(Some(path)) => {
+                    crate::debug_log!("telemetry", "Telemetry flushed to {}", path.display())
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to flush telemetry: {}", e),
+            }
+        }
+    }
+
+    /// Record an error and show it in the error banner until resolved
+    fn raise_error(&mut self, component: &str, error: RustPodsError) {
+        if let Ok(mut manager) = self.error_manager.lock() {
+            manager.raise(component, &error);
+        }
+        if let Ok(mut telemetry) = self.telemetry.lock() {
+            telemetry.record_error(&error);
+        }
+        self.sync_active_errors();
+    }
+
+    /// Clear a component's errors from the banner once it has recovered
+    fn resolve_errors(&mut self, component: &str) {
+        let resolved = match self.error_manager.lock() {
+            Ok(mut manager) => {
+                let had_errors = manager
+                    .active_errors()
+                    .iter()
+                    .any(|error| error.component == component);
+                manager.resolve(component);
+                had_errors
+            }
+            Err(_) => false,
+        };
+        if resolved {
+            self.sync_active_errors();
+        }
+    }
+
+    /// Warn in the main window while the scanner keeps failing, and ask for a capture
+    /// the first time an unknown model is heard
+    fn sync_scanner_status(&mut self) {
+        let statistics = self.scanner_supervisor.statistics();
+        if self.main_window.unknown_model.is_none() {
+            if let Some(model_id) = statistics
+                .unknown_models
+                .keys()
+                .find(|model_id| !self.unknown_models_prompted.contains(model_id))
+            {
+                self.unknown_models_prompted.insert(*model_id);
+                self.main_window.unknown_model = Some(*model_id);
+            }
+        }
+        self.settings_window.set_scan_statistics(statistics);
+        self.settings_window
+            .set_focused_scan(self.focused_scan.address(), self.focused_scan.frames());
+        let health = self.scanner_supervisor.health();
+        self.main_window.scanner_status = health.is_unhealthy().then(|| {
+            let retry = match health.retry_in(std::time::Instant::now()) {
+                Some(wait) => format!("retrying in {}s", wait.as_secs().max(1)),
+                None => "retrying".to_string(),
+            };
+            format!(
+                "Scanner unhealthy after {} failed runs, {}",
+                health.consecutive_failures, retry
+            )
+        });
+    }
+
+    /// Run the background health check off the UI thread, unless one is running
+    fn run_health_check(&mut self) -> Command<Message> {
+        if self.health_check_running {
+            return Command::none();
+        }
+        self.health_check_running = true;
+        let config = Arc::new(self.config.clone());
+        let error_manager = Arc::clone(&self.error_manager);
+        let scanner = self.scanner_supervisor.health();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    DiagnosticsManager::new(config, error_manager).run_health_check(&scanner)
+                })
+                .await
+                .ok()
+                .map(Box::new)
+            },
+            Message::HealthChecked,
+        )
+    }
+
+    /// Show the result of a health check on the title bar badge
+    fn show_health(&mut self, result: DiagnosticResult) {
+        let health = result.health();
+        if health != self.main_window.health {
+            log::info!(
+                "Health check: {:?} -> {:?}",
+                self.main_window.health,
+                health
+            );
+        }
+        for issue in result.problems() {
+            crate::debug_log!("ui", "Health check found: {}", issue.title);
+        }
+        self.main_window.health = health;
+        self.main_window.health_problems = result.problems().count();
+        self.health_check = Some(result);
+    }
+
+    /// Mirror the active errors into the main window, with any known issue for each
+    fn sync_active_errors(&mut self) {
+        if let Ok(manager) = self.error_manager.lock() {
+            self.main_window.active_errors = manager
+                .active_errors()
+                .iter()
+                .cloned()
+                .map(|mut error| {
+                    error.known_issue = self
+                        .known_issues
+                        .lookup(error.code, &error.message)
+                        .cloned();
+                    error
+                })
+                .collect();
+        }
+    }
+
+    /// Stop adding synthetic frames to scan results
+    ///
+    /// The devices they produced drop out like any device that stops advertising.
+    fn clear_synthetic_frames(&mut self) {
+        if !self.synthetic_frames.is_empty() {
+            log::info!("Clearing {} synthetic frames", self.synthetic_frames.len());
+        }
+        self.synthetic_frames.clear();
+        self.settings_window.set_synthetic_frames(0);
+    }
+
+    /// Download the known issues index in the background if the user allows it and
+    /// it hasn't been checked today
+    fn refresh_known_issues(&mut self) -> Command<Message> {
+        if !self.config.system.refresh_known_issues
+            || self
+                .known_issues_checked
+                .is_some_and(|checked| checked.elapsed() < KNOWN_ISSUES_CHECK_INTERVAL)
+        {
+            return Command::none();
+        }
+        self.known_issues_checked = Some(std::time::Instant::now());
+        let proxy = self.config.system.proxy.clone();
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || known_issues::refresh(&proxy))
+                    .await
+                    .ok()
+                    .flatten()
+                    .map(Box::new)
+            },
+            Message::KnownIssuesRefreshed,
+        )
+    }
+
+    /// Back up all data in the background if scheduled backups are on and one is due
+    fn run_scheduled_backup(&mut self) -> Command<Message> {
+        let schedule = &self.config.system.backup;
+        let Some(interval) = schedule.frequency.interval() else {
+            return Command::none();
+        };
+        if self
+            .backup_checked
+            .is_some_and(|checked| checked.elapsed() < BACKUP_CHECK_INTERVAL)
+        {
+            return Command::none();
+        }
+        self.backup_checked = Some(std::time::Instant::now());
+
+        let roots = backup::BackupRoots::default();
+        let folder = schedule
+            .folder
+            .clone()
+            .unwrap_or_else(|| roots.default_backup_folder());
+        let now = chrono::Local::now().naive_local();
+        if !backup::scheduled_backup_due(&folder, interval, now) {
+            return Command::none();
+        }
+
+        // Get the latest learning onto disk so it is in the backup
+        if let Err(e) = self.battery_intelligence.save() {
+            log::warn!("Failed to save battery data before backup: {}", e);
+        }
+        self.battery_intelligence.flush();
+        let keep = schedule.keep as usize;
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    backup::run_scheduled_backup(&folder, &roots, keep, now)
+                        .map_err(|e| e.to_string())
+                })
+                .await
+                .unwrap_or_else(|e| Err(format!("Backup task failed: {}", e)))
+            },
+            Message::ScheduledBackupFinished,
+        )
+    }
+
+    /// Run the recovery action for an error from the banner
+    fn recover_error(&mut self, id: u64) -> Command<Message> {
+        let dismissed = self
+            .error_manager
+            .lock()
+            .ok()
+            .and_then(|mut manager| manager.dismiss(id));
+        let Some(error) = dismissed else {
+            return Command::none();
+        };
+        self.sync_active_errors();
+        self.record_feature("error_recovery");
+        log::info!(
+            "Recovering from {} error via {}",
+            error.component,
+            error.recovery.description()
+        );
+
+        match error.recovery {
+            RecoveryAction::Retry
+            | RecoveryAction::Restart
+            | RecoveryAction::ReconnectBluetooth => {
+                // Rescan immediately as if the scanner had never failed
+                self.consecutive_scan_failures = 0;
+                self.scanner_supervisor.retry_now();
+                self.update(Message::Tick)
+            }
+            RecoveryAction::RecoverQuarantined => {
+                self.recover_quarantined_files();
+                Command::none()
+            }
+            RecoveryAction::TurnOnBluetooth | RecoveryAction::AllowRadioAccess => {
+                let uri = if error.recovery == RecoveryAction::TurnOnBluetooth {
+                    preflight::BLUETOOTH_SETTINGS_URI
+                } else {
+                    preflight::RADIO_PRIVACY_SETTINGS_URI
+                };
+                if let Err(e) = issue_report::open_in_browser(uri) {
+                    log::warn!("Failed to open {}: {}", uri, e);
+                    self.toast_message = Some(format!("Open {} manually", uri));
+                }
+                // The next tick finds out whether it worked and clears the banner
+                Command::none()
+            }
+            RecoveryAction::ReloadConfig => {
+                if self.load_settings().is_ok() {
+                    self.main_window.config = self.config.clone();
+                    self.settings_window.update_config(self.config.clone());
+                    self.settings_error = None;
+                }
+                Command::none()
+            }
+            _ => Command::none(),
+        }
+    }
+
+    /// Apply settings to the application
+    fn apply_settings(&mut self) {
+        // Update all components with new settings
+
+        // Update main window theme
+        // (This would be implemented in a real app by applying theme settings
+        // to all UI components)
+
+        // Update system tray configuration if available
+        if let Some(system_tray) = &self.system_tray {
+            system_tray.update_config(self.config.clone());
+        }
+
+        if let Ok(mut telemetry) = self.telemetry.lock() {
+            telemetry.set_enabled(self.config.system.enable_telemetry);
+        }
+
+        crate::retry::configure(&self.config.system.retry);
+
+        log::info!("Settings applied");
+    }
+
+    /// Show the transient battery popup when the case lid has just been opened
+    fn check_lid_open_popup(&mut self) -> Command<Message> {
+        let Some(device) = self.merged_devices.first() else {
+            return Command::none();
+        };
+        let lid_open = device.case_lid_open == Some(true);
+        let was_open = self.last_case_lid_open.replace(lid_open);
+
+        // Only react to a closed -> open transition that was actually observed
+        if !lid_open || was_open != Some(false) || !self.config.ui.show_lid_open_popup {
+            return Command::none();
+        }
+
+        // Ignore cases that are open but not near this PC
+        if let (Some(min_rssi), Some(rssi)) = (self.config.bluetooth.min_rssi, device.rssi) {
+            if rssi < min_rssi {
+                crate::debug_log!("ui", "Case opened but too far away (RSSI {})", rssi);
+                return Command::none();
+            }
+        }
+
+        log::info!("Case lid opened - showing battery popup");
+        self.lid_popup = Some(AirPodsPopup::transient(device.clone()));
+        self.lid_popup_generation += 1;
+        let generation = self.lid_popup_generation;
+        let dismiss = Command::perform(
+            async move {
+                tokio::time::sleep(LID_POPUP_DURATION).await;
+                generation
+            },
+            Message::DismissLidPopup,
+        );
+
+        if self.visible {
+            dismiss
+        } else {
+            self.visible = true;
+            self.lid_popup_restore_hidden = true;
+            Command::batch(vec![
+                iced::window::change_mode(iced::window::Mode::Windowed),
+                self.position_near_tray(),
+                dismiss,
+            ])
+        }
+    }
+
+    /// Show the undo notice if the save changed anything since the window opened
+    fn offer_undo(&mut self) -> Command<Message> {
+        let previous = self
+            .settings_window
+            .original()
+            .filter(|original| **original != self.config)
+            .cloned();
+        self.settings_window.set_original(None);
+        let Some(previous) = previous else {
+            return Command::none();
+        };
+
+        self.undo_config = Some(previous);
+        self.main_window.undo_notice = Some("Settings saved".to_string());
+        self.undo_generation += 1;
+        let generation = self.undo_generation;
+        Command::perform(
+            async move {
+                tokio::time::sleep(UNDO_DURATION).await;
+                generation
+            },
+            Message::DismissUndo,
+        )
+    }
+
+    /// Carry out an import or export once its file has been picked
+    fn use_chosen_file(&mut self, action: FileAction, path: PathBuf) -> Command<Message> {
+        match action {
+            FileAction::ExportSettings => {
+                self.toast_message = Some(match self.config.save_to_path(&path) {
+                    Ok(()) => format!("Exported settings to {}", path.display()),
+                    Err(e) => {
+                        log::error!("Failed to export settings: {}", e);
+                        format!("Failed to export settings: {}", e)
+                    }
+                });
+            }
+            FileAction::ImportSettings => match AppConfig::load_from_path(&path) {
+                Ok(mut imported) => {
+                    // Dropped files arrive while the main window is showing
+                    if !self.show_settings {
+                        let _ = self.update(Message::OpenSettings);
+                    }
+                    // Loaded as unsaved changes, so they can be reviewed before saving
+                    imported.settings_path = self.config.settings_path.clone();
+                    self.settings_window.update_config(imported);
+                    self.settings_window.mark_changed();
+                    self.toast_message =
+                        Some("Imported settings; review them and save to apply".to_string());
+                }
+                Err(e) => {
+                    log::error!("Failed to import settings from {}: {}", path.display(), e);
+                    self.toast_message = Some(format!("Failed to import settings: {}", e));
+                }
+            },
+            FileAction::ExportBatteryData => {
+                let capacity = self.primary_capacity().map(|(_, capacity)| capacity);
+                self.toast_message = Some(
+                    match self.battery_intelligence.export_profile(&path, capacity) {
+                        Ok(()) => format!("Exported battery data to {}", path.display()),
+                        Err(e) => {
+                            log::error!("Failed to export battery data: {}", e);
+                            format!("Failed to export battery data: {}", e)
+                        }
+                    },
+                );
+            }
+            FileAction::SaveDiagnostics => {
+                let config = Arc::new(self.config.clone());
+                let error_manager = Arc::clone(&self.error_manager);
+                let scan_statistics = self.scanner_supervisor.statistics();
+                return Command::perform(
+                    async move {
+                        let mut diagnostics = DiagnosticsManager::new(config, error_manager);
+                        diagnostics
+                            .set_level(DiagnosticLevel::Complete)
+                            .set_scan_statistics(scan_statistics);
+                        diagnostics.run_diagnostics().await?;
+                        diagnostics.save_diagnostics_to(&path)?;
+                        Ok::<_, std::io::Error>(path)
+                    },
+                    |result| {
+                        Message::ShowToast(match result {
+                            Ok(path) => format!("Saved diagnostic report to {}", path.display()),
+                            Err(e) => format!("Failed to save diagnostic report: {}", e),
+                        })
+                    },
+                );
+            }
+            FileAction::BackUpAllData => {
+                // Get the latest learning onto disk so it is in the backup
+                if let Err(e) = self.battery_intelligence.save() {
+                    log::warn!("Failed to save battery data before backup: {}", e);
+                }
+                self.battery_intelligence.flush();
+                self.toast_message = Some(
+                    match backup::create_backup(&path, &backup::BackupRoots::default()) {
+                        Ok(manifest) => format!(
+                            "Backed up {} files to {}",
+                            manifest.files.len(),
+                            path.display()
+                        ),
+                        Err(e) => {
+                            log::error!("Failed to back up data: {}", e);
+                            format!("Failed to back up data: {}", e)
+                        }
+                    },
+                );
+            }
+            FileAction::ChooseBackupFolder => {
+                self.settings_window.mark_changed();
+                self.update_system_setting(SystemSetting::BackupFolder(path));
+                self.settings_window.update_config(self.config.clone());
+            }
+            FileAction::ChooseTrayIconFolder => {
+                // Only a set that loads is kept, so the tray never falls back unexpectedly
+                match crate::assets::tray::load_icon_set(&path) {
+                    Ok(_) => {
+                        self.settings_window.mark_changed();
+                        self.update_ui_setting(UiSetting::TrayIconFolder(path));
+                        self.settings_window.update_config(self.config.clone());
+                    }
+                    Err(e) => {
+                        log::warn!("Rejected tray icons in {}: {}", path.display(), e);
+                        self.toast_message = Some(format!("Can't use these tray icons: {}", e));
+                    }
+                }
+            }
+            FileAction::RestoreBackup => match backup::read_manifest(&path) {
+                Ok(manifest) => {
+                    let created = chrono::DateTime::parse_from_rfc3339(&manifest.created)
+                        .map(|created| created.format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or(manifest.created);
+                    self.dialog = Some(
+                        Dialog::confirm(
+                            "Restore from backup?",
+                            format!(
+                                "Settings and battery data from the backup made on {} will replace everything RustPods has now.",
+                                created
+                            ),
+                            "Restore",
+                            Message::RestoreAllData(path),
+                        )
+                        .destructive(),
+                    );
+                }
+                Err(e) => {
+                    log::error!("Can't restore from {}: {}", path.display(), e);
+                    self.toast_message = Some(format!("Can't restore from backup: {}", e));
+                }
+            },
+            FileAction::SaveModelCapture => {
+                let Some(model_id) = self.main_window.unknown_model else {
+                    return Command::none();
+                };
+                let payloads = self
+                    .scanner_supervisor
+                    .statistics()
+                    .unknown_models
+                    .get(&model_id)
+                    .map(|sighting| sighting.payloads.clone())
+                    .unwrap_or_default();
+                let capture = capture_diff::unknown_model_capture(model_id, &payloads);
+                self.toast_message = Some(match std::fs::write(&path, capture) {
+                    Ok(()) => {
+                        self.main_window.unknown_model = None;
+                        format!(
+                            "Saved capture to {}; please attach it to a new issue at {}",
+                            path.display(),
+                            issue_report::NEW_ISSUE_URL
+                        )
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save model capture: {}", e);
+                        format!("Failed to save capture: {}", e)
+                    }
+                });
+            }
+        }
+        Command::none()
+    }
+
+    /// Replace all application data with a backup and reload what was restored
+    fn restore_all_data(&mut self, path: &std::path::Path) {
+        // A queued save would overwrite the restored profile
+        self.battery_intelligence.flush();
+        let manifest = match backup::restore_backup(path, &backup::BackupRoots::default()) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                log::error!("Failed to restore backup {}: {}", path.display(), e);
+                self.toast_message = Some(format!("Failed to restore backup: {}", e));
+                return;
+            }
+        };
+
+        match AppConfig::load_from_path(&self.config.settings_path) {
+            Ok(config) => self.restore_config(config),
+            Err(e) => log::error!("Failed to load restored settings: {}", e),
+        }
+        let mut intelligence =
+            BatteryIntelligence::new(self.battery_intelligence.storage_dir.clone());
+        intelligence.enable_background_saving();
+        self.battery_intelligence = intelligence;
+        self.sync_expanded_details();
+
+        self.toast_message = Some(format!(
+            "Restored {} files from the backup made by RustPods {}",
+            manifest.files.len(),
+            manifest.app_version
+        ));
+    }
+
+    /// Replace the live configuration and re-apply what depends on it
+    fn restore_config(&mut self, config: AppConfig) {
+        self.config = config;
+        crate::ui::direction::apply_locale(&self.config);
+        crate::ui::palette::apply(&self.config);
+        crate::ui::keyboard_shortcuts::set_active_bindings(&self.config.ui.shortcuts);
+        crate::logging::set_category_levels(&self.config.system.category_log_levels);
+        self.apply_theme_schedule();
+        self.apply_settings();
+        self.main_window.config = self.config.clone();
+        self.settings_window.update_config(self.config.clone());
+    }
+
+    /// Whether the main window is on screen, rather than a dialog or another screen
+    fn main_window_shown(&self) -> bool {
+        self.visible
+            && self.dialog.is_none()
+            && self.lid_popup.is_none()
+            && !self.show_telemetry_data
+            && !self.show_troubleshoot
+            && !self.show_settings
+    }
+
+    /// Read out the focused control when a screen reader is listening
+    ///
+    /// iced exposes nothing to screen readers, so the name is spoken instead.
+    fn announce_focus(&self) {
+        let Some(target) = self.main_window.focused else {
+            return;
+        };
+        let label = self.main_window.accessible_label(target);
+        crate::debug_log!("ui", "Keyboard focus: {}", label);
+        if accessibility::screen_reader_active() {
+            if let Err(e) = self.speech.speak(&label, &self.config.ui.speech) {
+                log::warn!("Failed to announce focused control: {}", e);
+            }
+        }
+    }
+
+    /// Dismiss the lid popup if `generation` still refers to the current one
+    fn dismiss_lid_popup(&mut self, generation: u64) -> Command<Message> {
+        if self.lid_popup.is_none() || generation != self.lid_popup_generation {
+            return Command::none();
+        }
+
+        self.lid_popup = None;
+        if std::mem::take(&mut self.lid_popup_restore_hidden) {
+            self.visible = false;
+            return iced::window::change_mode(iced::window::Mode::Hidden);
+        }
+        Command::none()
+    }
+
+    /// Show background scanner work on the tray icon
+    fn set_tray_activity(&mut self, activity: TrayActivity) {
+        if let Some(ref system_tray) = self.system_tray {
+            system_tray.set_activity(activity);
+        }
+    }
+
+    /// Query whether the primary device is the active audio output
+    fn refresh_audio_routing(&mut self) -> Command<Message> {
+        let Some(device) = self.merged_devices.first() else {
+            self.main_window.audio_routing = None;
+            self.audio_routing_checked = None;
+            return Command::none();
+        };
+        if !self.audio_routing_due(device) {
+            return Command::none();
+        }
+        let name = device.name.clone();
+        self.audio_routing_checked =
+            Some((name.clone(), device.is_connected, std::time::Instant::now()));
+
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    crate::system::audio::audio_routing(&name)
+                        .map_err(|e| crate::debug_log!("ui", "Audio routing unavailable: {}", e))
+                        .ok()
+                })
+                .await
+                .unwrap_or(None)
+            },
+            Message::AudioRoutingUpdated,
+        )
+    }
+
+    /// Whether to read audio routing for `device` again
+    ///
+    /// Enumerating the audio endpoints is slow, so it is only done when the primary device
+    /// changes, connects or disconnects, and otherwise once a minute.
+    fn audio_routing_due(&self, device: &MergedBluetoothDevice) -> bool {
+        let Some((name, connected, checked)) = &self.audio_routing_checked else {
+            return true;
+        };
+        *name != device.name
+            || *connected != device.is_connected
+            || checked.elapsed() >= AUDIO_ROUTING_REFRESH_INTERVAL
+    }
+
+    /// Query which paired devices the classic Bluetooth stack has connected
+    fn refresh_classic_connections(&self) -> Command<Message> {
+        Command::perform(
+            async move {
+                tokio::task::spawn_blocking(|| {
+                    crate::bluetooth::win32_enum::paired_devices()
+                        .map_err(|e| {
+                            crate::debug_log!("bluetooth", "Classic connections unavailable: {}", e)
+                        })
+                        .ok()
+                })
+                .await
+                .unwrap_or(None)
+            },
+            Message::ClassicConnectionsUpdated,
+        )
+    }
+
+    /// Mark merged AirPods connected only if the classic stack has them connected
+    ///
+    /// Without a classic query every advertising device stays connected, as before.
+    fn apply_classic_connections(&mut self) {
+        let Some(classic) = &self.classic_devices else {
+            return;
+        };
+        for device in &mut self.merged_devices {
+            if device.device_type == DeviceType::AirPods {
+                device.is_connected = crate::bluetooth::is_classically_connected(
+                    classic,
+                    &device.address,
+                    &device.name,
+                );
+            }
+        }
+    }
+
+    /// Work out whether the primary device is connected, nearby or gone, and log and
+    /// show any change
+    fn sync_connection_status(&mut self) {
+        let status = match self.merged_devices.first() {
+            Some(device) if self.device_detection_state != DeviceDetectionState::NoDevicesFound => {
+                device.connection_status()
+            }
+            _ => ConnectionStatus::NotFound,
+        };
+        if status == self.connection_status {
+            return;
+        }
+        log::info!(
+            "Connection status changed: {} -> {}",
+            self.connection_status,
+            status
+        );
+        self.connection_status = status;
+        if let Some(ref system_tray) = self.system_tray {
+            system_tray.update_icon(status);
+        }
+    }
+
+    /// Tell the settings window which devices are connected
+    fn sync_connected_device_names(&mut self) {
+        let connected_device_names: Vec<String> = self
+            .merged_devices
+            .iter()
+            .filter(|device| device.is_connected)
+            .map(|device| device.name.clone())
+            .collect();
+        self.settings_window
+            .update_connected_devices(connected_device_names);
+    }
+
+    /// Refresh the charging, estimate and health readings shown in the expanded view
+    fn sync_expanded_details(&mut self) {
+        let (left, right, case) = self.primary_charging_state();
+        let time_to_critical = self.battery_intelligence.get_battery_estimates().map_or(
+            [None; 3],
+            |(left, right, case)| {
+                [
+                    left.time_to_critical,
+                    right.time_to_critical,
+                    case.time_to_critical,
+                ]
+            },
+        );
+        self.main_window.expanded_details = ExpandedDetails {
+            charging: [left, right, case],
+            time_to_critical,
+            health_score: self
+                .battery_intelligence
+                .device_profile
+                .as_ref()
+                .map(|profile| profile.health_metrics.health_score),
+            estimation_accuracy: self.battery_intelligence.get_estimation_accuracy(),
+            mah_remaining: self.mah_estimate(),
+        };
+    }
+
+    /// The primary device and its model's nominal capacity, if mAh estimates are turned on
+    fn primary_capacity(&self) -> Option<(&MergedBluetoothDevice, Capacity)> {
+        if !self.config.ui.show_mah_estimates {
+            return None;
+        }
+        // The model comes from the same device as the levels, whichever is listed first
+        let device = self.merged_devices.first()?;
+        let airpods = self
+            .airpods_devices
+            .iter()
+            .find(|airpods| airpods.canonical_address == device.address)?;
+        Some((device, Capacity::for_model(airpods.model_id)?))
+    }
+
+    /// Charge left in the primary device's batteries, in mAh
+    fn mah_estimate(&self) -> Option<MahEstimate> {
+        let (device, capacity) = self.primary_capacity()?;
+        let health = self
+            .battery_intelligence
+            .device_profile
+            .as_ref()
+            .map_or(Health::NEW, |profile| {
+                profile.health_metrics.capacity_health()
+            });
+        Some(capacity.estimate(
+            health,
+            device.left_battery,
+            device.right_battery,
+            device.case_battery,
+        ))
+        .filter(|estimate| !estimate.is_empty())
+    }
+
+    /// Charging flags (left, right, case) for the primary device
+    fn primary_charging_state(&self) -> (bool, bool, bool) {
+        self.airpods_devices
+            .first()
+            .map(|airpods| {
+                (
+                    airpods.left_charging,
+                    airpods.right_charging,
+                    airpods.case_charging,
+                )
+            })
+            .unwrap_or((false, false, false))
+    }
+
+    /// Show a notification when a component drops to the low battery threshold
+    fn notify_low_battery(&mut self) {
+        let Some(device) = self.merged_devices.first() else {
+            return;
+        };
+        let ui = &self.config.ui;
+        let (left_charging, right_charging, case_charging) = self.primary_charging_state();
+
+        // Each component latches on its own, at its own threshold
+        let low: Vec<(String, u8)> = [
+            (
+                "Left AirPod",
+                BatteryComponent::Left,
+                device.left_battery,
+                left_charging,
+            ),
+            (
+                "Right AirPod",
+                BatteryComponent::Right,
+                device.right_battery,
+                right_charging,
+            ),
+            (
+                "Case",
+                BatteryComponent::Case,
+                device.case_battery,
+                case_charging,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(component, part, level, charging)| {
+            let threshold = ui.low_battery_threshold_for(part);
+            let level = level?;
+            self.low_battery_notified
+                .check(component, Some(level), charging, threshold)
+                .then(|| (format!("{} at {}%", component, level), level))
+        })
+        .collect();
+
+        if low.is_empty()
+            || !self.config.ui.show_notifications
+            || !self.config.ui.show_low_battery_warning
+        {
+            return;
+        }
+
+        // Nearly empty batteries are worth interrupting quiet hours for
+        let kind = if low
+            .iter()
+            .any(|(_, level)| *level <= self.config.battery.critical_threshold)
+        {
+            NotificationKind::Critical
+        } else {
+            NotificationKind::Warning
+        };
+        let text: Vec<String> = low.into_iter().map(|(text, _)| text).collect();
+        self.notifier.notify("Low battery", &text.join(", "), kind);
+    }
+
+    /// Sum up the charge cycles of the month that just ended, once per month
+    fn notify_monthly_report(&mut self) {
+        let Some(report) = self.battery_intelligence.take_monthly_report() else {
+            return;
+        };
+        log::info!("Monthly battery report: {}", report.summary());
+        if self.config.ui.show_notifications {
+            self.notifier.notify(
+                "Monthly battery report",
+                &report.summary(),
+                NotificationKind::Info,
+            );
+        }
+    }
+
+    /// Whether automatic alerts should stay quiet: during quiet hours, or while Focus
+    /// Assist is on if quiet hours follow it
+    fn is_quiet(&self) -> bool {
+        let quiet_hours = &self.config.ui.quiet_hours;
+        quiet_hours.is_active_now()
+            || (quiet_hours.follow_focus_assist && crate::system::focus_assist_active())
+    }
+
+    /// Start flashing the tray icon when a component drops to the critical threshold
+    fn check_critical_battery(&mut self) {
+        let Some(device) = self.merged_devices.first() else {
+            return;
+        };
+        let threshold = self.config.battery.critical_threshold;
+        let (left_charging, right_charging, case_charging) = self.primary_charging_state();
+
+        let crossed = [
+            ("Left AirPod", device.left_battery, left_charging),
+            ("Right AirPod", device.right_battery, right_charging),
+            ("Case", device.case_battery, case_charging),
+        ]
+        .into_iter()
+        .filter(|&(component, level, charging)| {
+            self.critical_battery_latch
+                .check(component, level, charging, threshold)
+        })
+        .count();
+
+        if crossed == 0 || !self.config.battery.flash_tray_on_critical {
+            return;
+        }
+
+        log::info!("Critical battery level reached - flashing tray icon");
+        let duration = self.config.battery.critical_flash_duration_secs;
+        self.critical_alert_until = (duration > 0)
+            .then(|| std::time::Instant::now() + std::time::Duration::from_secs(duration));
+        if let Some(ref system_tray) = self.system_tray {
+            system_tray.set_critical_alert(true);
+        }
+    }
+
+    /// Run the user's action rules for events since the last battery update
+    fn run_action_rules(&mut self) {
+        let (left_charging, right_charging, case_charging) = self.primary_charging_state();
+        let current = match self.merged_devices.first() {
+            Some(device) => Some(DeviceSnapshot {
+                name: device.name.clone(),
+                levels: [
+                    device.left_battery,
+                    device.right_battery,
+                    device.case_battery,
+                ],
+                charging: [left_charging, right_charging, case_charging],
+                in_ear: [device.left_in_ear, device.right_in_ear],
+                connected: device.is_connected,
+            }),
+            // Only treat the device as gone once the scan tolerance has run out
+            None if self.device_detection_state == DeviceDetectionState::NoDevicesFound => None,
+            None => return,
+        };
+
+        // Sound alerts fire on the same events as the user's rules
+        let rules: Vec<ActionRule> = self
+            .config
+            .actions
+            .rules
+            .iter()
+            .cloned()
+            .chain(self.config.ui.sound_alerts.rules(&self.config.ui))
+            .collect();
+        for fired in self.rule_engine.evaluate(&rules, current) {
+            log::info!("Rule '{}' fired: {}", fired.rule, fired.action);
+            match &fired.action {
+                RuleAction::Notify { title, body } => self.notifier.notify(
+                    &fired.context.expand(title),
+                    &fired.context.expand(body),
+                    NotificationKind::Info,
+                ),
+                RuleAction::PlaySound { path } => {
+                    if self.is_quiet() {
+                        crate::debug_log!("ui", "Quiet hours active, not playing rule sound");
+                        continue;
+                    }
+                    let path = path.as_deref().map(|path| fired.context.expand(path));
+                    if let Err(e) = crate::system::play_sound(path.as_deref()) {
+                        log::warn!("Rule '{}' failed: {}", fired.rule, e);
+                    }
+                }
+                RuleAction::RunCommand { command } => {
+                    if let Err(e) = crate::rules::run_command(command, &fired.context) {
+                        log::warn!("Rule '{}' failed: {}", fired.rule, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Show the primary device's battery levels in the tray tooltip
+    fn sync_tray_tooltip(&mut self) {
+        let charging = self.primary_charging_state();
+        let (left_charging, right_charging, case_charging) = charging;
+        let batteries = match self.merged_devices.first() {
+            Some(device) if device.left_battery.is_none() && device.right_battery.is_none() => {
+                vec![("Battery", device.battery, false)]
+            }
+            Some(device) => vec![
+                ("L", device.left_battery, left_charging),
+                ("R", device.right_battery, right_charging),
+                ("Case", device.case_battery, case_charging),
+            ],
+            None => Vec::new(),
+        };
+        let name = self.merged_devices.first().and_then(|device| {
+            self.known_devices
+                .find_address(&device.address)
+                .map(|known| known.display_name())
+        });
+        let headline = self
+            .merged_devices
+            .first()
+            .and_then(|device| Headline::for_device(device, charging, &self.config.ui));
+        if let Some(ref system_tray) = self.system_tray {
+            system_tray.update_tooltip_with_battery(name, headline, &batteries);
+        }
+    }
+
+    /// Show the primary device's headline level on the taskbar button
+    fn sync_taskbar_badge(&mut self) {
+        // A hidden window has no taskbar button; show the badge again once it is back
+        if !self.visible {
+            self.taskbar_badge = None;
+            return;
+        }
+
+        let charging = self.primary_charging_state();
+        let ui = &self.config.ui;
+        let badge = match self.merged_devices.first() {
+            Some(_) if !ui.taskbar_badge => TaskbarBadge::Clear,
+            Some(device) => TaskbarBadge::from_headline(Headline::for_device(device, charging, ui)),
+            None => TaskbarBadge::Clear,
+        };
+        // Buttons start without progress, so there is nothing to clear initially
+        if self.taskbar_badge == Some(badge)
+            || (self.taskbar_badge.is_none() && badge == TaskbarBadge::Clear)
+        {
+            return;
+        }
+
+        match crate::system::set_taskbar_badge(badge) {
+            Ok(0) => crate::debug_log!("ui", "No taskbar button to show the battery on yet"),
+            Ok(_) => self.taskbar_badge = Some(badge),
+            Err(e) => {
+                crate::debug_log!("ui", "Taskbar badge unavailable: {}", e);
+                // Don't retry on every scan
+                self.taskbar_badge = Some(badge);
+            }
+        }
+    }
+
+    /// Stop the critical battery tray flash
+    fn acknowledge_critical_alert(&mut self) {
+        self.critical_alert_until = None;
+        if let Some(ref system_tray) = self.system_tray {
+            system_tray.set_critical_alert(false);
+        }
+    }
+
+    /// Speak low battery announcements for the primary device
+    ///
+    /// Automatic announcements respect quiet hours; on-demand requests do not.
+    fn announce_low_battery(&mut self) {
+        let speech_config = &self.config.ui.speech;
+        if !speech_config.enabled || !speech_config.announce_low_battery {
+            return;
+        }
+
+        let Some(device) = self.merged_devices.first() else {
+            return;
+        };
+        let ui = &self.config.ui;
+        let (left_charging, right_charging, case_charging) = self.primary_charging_state();
+
+        let announcements: Vec<String> = [
+            (
+                "Left AirPod",
+                BatteryComponent::Left,
+                device.left_battery,
+                left_charging,
+            ),
+            (
+                "Right AirPod",
+                BatteryComponent::Right,
+                device.right_battery,
+                right_charging,
+            ),
+            (
+                "Case",
+                BatteryComponent::Case,
+                device.case_battery,
+                case_charging,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(component, part, level, charging)| {
+            let threshold = ui.low_battery_threshold_for(part);
+            self.low_battery_announcer
+                .check(component, level, charging, threshold)
+        })
+        .collect();
+
+        if announcements.is_empty() {
+            return;
+        }
+
+        if self.is_quiet() {
+            crate::debug_log!("ui", "Quiet hours active, not announcing low battery");
+            return;
+        }
+
+        if let Err(e) = self.speech.speak(&announcements.join(", "), speech_config) {
+            log::warn!("Failed to announce low battery: {}", e);
+        }
+    }
+
+    /// Update a Bluetooth setting
+    fn update_bluetooth_setting(&mut self, setting: BluetoothSetting) {
+        match setting {
+            BluetoothSetting::DeviceName(value) => {
+                self.config.bluetooth.paired_device_name = if value.trim().is_empty() {
+                    None
+                } else {
+                    Some(value.trim().to_string())
+                };
+            }
+            BluetoothSetting::OnlyPairedDevice(value) => {
+                // Pin the device on screen if nothing has been paired yet
+                if value && self.config.bluetooth.paired_device_id.is_none() {
+                    self.config.bluetooth.paired_device_id = self
+                        .merged_devices
+                        .iter()
+                        .find(|device| !device.address.is_empty())
+                        .map(|device| device.address.clone());
+                }
+                self.config.bluetooth.only_paired_device = value;
+            }
+            BluetoothSetting::AllowListedOnly(value) => {
+                self.config.bluetooth.allow_listed_only = value;
+            }
+            BluetoothSetting::AllowDevice(entry) => {
+                if !self.config.bluetooth.allow_list.contains(&entry) {
+                    self.config.bluetooth.allow_list.push(entry);
+                }
+                self.settings_window.set_device_filter_input(String::new());
+            }
+            BluetoothSetting::DenyDevice(entry) => {
+                if !self.config.bluetooth.deny_list.contains(&entry) {
+                    self.config.bluetooth.deny_list.push(entry);
+                }
+                self.settings_window.set_device_filter_input(String::new());
+            }
+            BluetoothSetting::RemoveAllowedDevice(index) => {
+                if index < self.config.bluetooth.allow_list.len() {
+                    self.config.bluetooth.allow_list.remove(index);
+                }
+            }
+            BluetoothSetting::RemoveDeniedDevice(index) => {
+                if index < self.config.bluetooth.deny_list.len() {
+                    self.config.bluetooth.deny_list.remove(index);
+                }
+            }
+        }
+        self.apply_device_access();
+    }
+
+    /// Match a scan against the paired fingerprint and record the pair's current address
+    fn track_paired_device(&mut self, airpods_data: &[AirPodsBatteryInfo]) {
+        let Some(fingerprint) = self.config.bluetooth.paired_fingerprint.as_mut() else {
+            return;
+        };
+        let Some(paired) = fingerprint.find(airpods_data) else {
+            return;
+        };
+
+        let changed = fingerprint.observe(paired);
+        if changed {
+            self.known_devices.remember(fingerprint);
+            self.save_known_devices();
+        }
+        if self.config.bluetooth.paired_device_id.as_deref() != Some(&paired.canonical_address) {
+            crate::debug_log!(
+                "bluetooth",
+                "Paired AirPods now advertising from {}",
+                paired.canonical_address
+            );
+            self.config.bluetooth.paired_device_id = Some(paired.canonical_address.clone());
+        } else if !changed {
+            return;
+        }
+
+        if let Err(e) = self.config.save() {
+            log::warn!("Failed to save paired device fingerprint: {}", e);
+        }
+        self.settings_window.update_config(self.config.clone());
+    }
+
+    /// Pair with the AirPods at a canonical address from the latest scan
+    fn pair_device(&mut self, address: &str) {
+        let Some(airpods) = self
+            .airpods_devices
+            .iter()
+            .find(|airpods| airpods.canonical_address == address)
+        else {
+            self.toast_message = Some("Those AirPods are no longer in range".to_string());
+            return;
+        };
+
+        let fingerprint = crate::airpods::DeviceFingerprint::from_airpods(airpods);
+        log::info!(
+            "Paired with {} ({})",
+            fingerprint.model_name,
+            airpods.canonical_address
+        );
+        self.selected_device = Some(fingerprint.stable_id());
+        self.config.bluetooth.paired_device_id = Some(airpods.canonical_address.clone());
+        self.known_devices.remember(&fingerprint);
+        self.known_devices.set_primary(&fingerprint.stable_id());
+        self.save_known_devices();
+        self.config.bluetooth.paired_fingerprint = Some(fingerprint);
+        if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+            log::error!("Failed to save paired device: {}", e);
+        }
+        self.settings_window.update_config(self.config.clone());
+        self.main_window.config = self.config.clone();
+        self.update_merged_devices();
+        self.sync_expanded_details();
+    }
+
+    /// Forget the paired AirPods
+    fn unpair_device(&mut self) {
+        self.config.bluetooth.paired_fingerprint = None;
+        self.config.bluetooth.paired_device_id = None;
+        self.selected_device = None;
+        // They stay known by nickname, but are no longer the pair RustPods follows
+        for device in &mut self.known_devices.devices {
+            device.primary = false;
+        }
+        self.save_known_devices();
+        if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+            log::error!("Failed to save after unpairing: {}", e);
+        }
+        self.settings_window.update_config(self.config.clone());
+        self.main_window.config = self.config.clone();
+    }
+
+    /// Change the nickname, icon, color or primary flag of a known device, or forget it
+    fn edit_known_device(&mut self, id: &str, edit: DeviceEdit) {
+        match edit {
+            DeviceEdit::Nickname(name) => {
+                if let Some(device) = self.known_devices.get_mut(id) {
+                    device.nickname = (!name.trim().is_empty()).then(|| name.trim().to_string());
+                }
+            }
+            DeviceEdit::Icon(icon) => {
+                if let Some(device) = self.known_devices.get_mut(id) {
+                    device.icon = icon;
+                }
+            }
+            DeviceEdit::Color(color) => {
+                if let Some(device) = self.known_devices.get_mut(id) {
+                    device.color = color;
+                }
+            }
+            DeviceEdit::MakePrimary => {
+                let Some(fingerprint) = self
+                    .known_devices
+                    .get(id)
+                    .map(|device| device.fingerprint.clone())
+                else {
+                    return;
+                };
+                self.known_devices.set_primary(id);
+                self.selected_device = Some(fingerprint.stable_id());
+                self.config.bluetooth.paired_device_id = fingerprint.addresses.last().cloned();
+                self.config.bluetooth.paired_fingerprint = Some(fingerprint);
+                if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+                    log::error!("Failed to save primary device: {}", e);
+                }
+                self.settings_window.update_config(self.config.clone());
+                self.main_window.config = self.config.clone();
+                self.update_merged_devices();
+                self.sync_expanded_details();
+            }
+            DeviceEdit::Forget => {
+                let Some(device) = self.known_devices.remove(id) else {
+                    return;
+                };
+                if device.primary {
+                    self.unpair_device();
+                }
+            }
+        }
+        self.save_known_devices();
+        self.sync_tray_tooltip();
+        self.sync_taskbar_badge();
+    }
+
+    /// Write the known devices next to the settings and show them in both windows
+    ///
+    /// They are only shown when the file couldn't be loaded at startup.
+    fn save_known_devices(&mut self) {
+        if self.known_devices_read_only {
+            log::debug!("Not saving known devices: they couldn't be loaded this session");
+        } else if let Err(e) = self
+            .known_devices
+            .save_to(&KnownDevices::path_for(&self.config))
+        {
+            log::error!("Failed to save known devices: {}", e);
+        }
+        self.settings_window
+            .set_known_devices(self.known_devices.clone());
+        self.main_window.known_devices = self.known_devices.clone();
+    }
+
+    /// Watch an address typed in by the user; it shows as waiting in settings until seen
+    fn track_device_address(&mut self, input: &str) {
+        let Some(address) = crate::config::canonical_address(input) else {
+            self.toast_message = Some(format!("'{}' is not a Bluetooth address", input.trim()));
+            return;
+        };
+        if self.config.bluetooth.tracked_devices.contains(&address) {
+            self.toast_message = Some("That device is already tracked".to_string());
+            return;
         }
+
+        self.config.bluetooth.tracked_devices.push(address);
+        if let Err(e) = self.config.save_audited(ChangeSource::Ui) {
+            log::error!("Failed to save tracked devices: {}", e);
+        }
+        self.settings_window.update_config(self.config.clone());
+        self.main_window.config = self.config.clone();
+    }
+
+    /// Drop devices the allow/deny lists now exclude instead of waiting for the next scan
+    fn apply_device_access(&mut self) {
+        let access = self.config.bluetooth.device_access();
+        if access.is_open() {
+            return;
+        }
+        self.airpods_devices
+            .retain(|airpods| access.allows(&airpods.canonical_address, Some(&airpods.name)));
+        self.merged_devices
+            .retain(|device| access.allows(&device.address, Some(&device.name)));
+        self.devices
+            .retain(|address, device| access.allows(address, device.name.as_deref()));
+        self.check_selected_device();
     }
 
     /// Update a UI setting
@@ -964,9 +3879,174 @@ impl AppState {
             UiSetting::LowBatteryThreshold(value) => {
                 self.config.ui.low_battery_threshold = value;
             }
+            UiSetting::ComponentLowBatteryThreshold(component, value) => {
+                self.config
+                    .ui
+                    .set_low_battery_threshold_for(component, value);
+            }
+            UiSetting::StaleAfterMinutes(minutes) => {
+                self.config.ui.stale_after_minutes = minutes;
+            }
             UiSetting::MinimizeToTrayOnClose(value) => {
                 self.config.ui.minimize_to_tray_on_close = value;
             }
+            UiSetting::ShowLidOpenPopup(value) => {
+                self.config.ui.show_lid_open_popup = value;
+            }
+            UiSetting::OpenNearTray(value) => {
+                self.config.ui.open_near_tray = value;
+            }
+            UiSetting::ShowAudioSwitchButton(value) => {
+                self.config.ui.show_audio_switch_button = value;
+            }
+            UiSetting::HouseholdMode(value) => {
+                self.config.ui.household_mode = value;
+            }
+            UiSetting::ExcludeFromCapture(value) => {
+                self.config.ui.exclude_from_capture = value;
+            }
+            UiSetting::TaskbarBadge(value) => {
+                self.config.ui.taskbar_badge = value;
+                self.sync_taskbar_badge();
+            }
+            UiSetting::TrayMetric(metric) => {
+                self.config.ui.tray_metric = metric;
+                self.sync_tray_tooltip();
+                self.sync_taskbar_badge();
+            }
+            UiSetting::TrayIconStyle(style) => {
+                self.config.ui.tray_icon_style = style;
+                if let Some(system_tray) = &self.system_tray {
+                    system_tray.update_config(self.config.clone());
+                }
+            }
+            UiSetting::TrayIconFolder(folder) => {
+                self.config.ui.tray_icon_folder = Some(folder);
+                self.config.ui.tray_icon_style = TrayIconStyle::Custom;
+                if let Some(system_tray) = &self.system_tray {
+                    system_tray.update_config(self.config.clone());
+                }
+            }
+            UiSetting::SoftwareRendering(value) => {
+                self.config.ui.software_rendering = value;
+            }
+            UiSetting::TrayOnly(value) => {
+                self.config.ui.tray_only = value;
+            }
+            UiSetting::ShowMahEstimates(value) => {
+                self.config.ui.show_mah_estimates = value;
+                self.sync_expanded_details();
+            }
+            UiSetting::ThemeSchedule(mode) => {
+                self.config.ui.theme_schedule.mode = mode;
+            }
+            UiSetting::Backdrop(backdrop) => {
+                self.config.ui.backdrop = backdrop;
+            }
+            UiSetting::SpeechEnabled(value) => {
+                self.config.ui.speech.enabled = value;
+            }
+            UiSetting::SpeechAnnounceLowBattery(value) => {
+                self.config.ui.speech.announce_low_battery = value;
+            }
+            UiSetting::SpeechVoice(value) => {
+                self.config.ui.speech.voice = if value.trim().is_empty() {
+                    None
+                } else {
+                    Some(value)
+                };
+            }
+            UiSetting::SpeechRate(value) => {
+                self.config.ui.speech.rate = value.clamp(-10, 10);
+            }
+            UiSetting::SoundAlertsEnabled(value) => {
+                self.config.ui.sound_alerts.enabled = value;
+            }
+            UiSetting::SoundAlert(event, value) => {
+                self.config.ui.sound_alerts.set_plays(event, value);
+            }
+            UiSetting::SoundAlertFile(event, path) => {
+                self.config.ui.sound_alerts.set_sound(event, &path);
+            }
+            UiSetting::QuietHoursEnabled(value) => {
+                self.config.ui.quiet_hours.enabled = value;
+            }
+            UiSetting::QuietHoursStart(minutes) => {
+                self.config.ui.quiet_hours.start_hour = (minutes / 60) as u8;
+                self.config.ui.quiet_hours.start_minute = (minutes % 60) as u8;
+            }
+            UiSetting::QuietHoursEnd(minutes) => {
+                self.config.ui.quiet_hours.end_hour = (minutes / 60) as u8;
+                self.config.ui.quiet_hours.end_minute = (minutes % 60) as u8;
+            }
+            UiSetting::QuietHoursDay(day, included) => {
+                let days = &mut self.config.ui.quiet_hours.days;
+                days.retain(|other| *other != day);
+                if included {
+                    days.push(day);
+                }
+            }
+            UiSetting::QuietHoursFollowFocusAssist(value) => {
+                self.config.ui.quiet_hours.follow_focus_assist = value;
+            }
+            UiSetting::QuietHoursSummarizeHeld(value) => {
+                self.config.ui.quiet_hours.summarize_held = value;
+            }
+            UiSetting::FontFamily(family) => {
+                self.config.ui.font_family =
+                    Some(family).filter(|family| family != crate::ui::theme::FONT_FAMILY);
+            }
+            UiSetting::FontScale(scale) => {
+                self.config.ui.font_scale = scale;
+            }
+            UiSetting::Locale(locale) => {
+                self.config.ui.locale = locale;
+                crate::ui::direction::apply_locale(&self.config);
+            }
+            UiSetting::BatteryPalette(palette) => {
+                self.config.ui.battery_palette = palette;
+                crate::ui::palette::apply(&self.config);
+            }
+            UiSetting::ShapeEncoding(value) => {
+                self.config.ui.shape_encoding = value;
+                crate::ui::palette::apply(&self.config);
+            }
+            UiSetting::Shortcut(action, shortcut) => {
+                // A binding back to the default is stored as no rebind
+                let is_default = shortcut.parse().ok()
+                    == Some(crate::ui::keyboard_shortcuts::default_shortcut(action));
+                if is_default {
+                    self.config.ui.shortcuts.remove(&action);
+                } else {
+                    self.config.ui.shortcuts.insert(action, shortcut);
+                }
+                crate::ui::keyboard_shortcuts::set_active_bindings(&self.config.ui.shortcuts);
+            }
+            UiSetting::ResetShortcuts => {
+                self.config.ui.shortcuts.clear();
+                crate::ui::keyboard_shortcuts::set_active_bindings(&self.config.ui.shortcuts);
+            }
+        }
+    }
+
+    /// Update an action rule setting
+    fn update_action_setting(&mut self, setting: ActionSetting) {
+        let rules = &mut self.config.actions.rules;
+        match setting {
+            ActionSetting::AddRule(rule) => {
+                rules.push(rule);
+                self.settings_window.set_rule_draft(RuleDraft::default());
+            }
+            ActionSetting::RuleEnabled(index, enabled) => {
+                if let Some(rule) = rules.get_mut(index) {
+                    rule.enabled = enabled;
+                }
+            }
+            ActionSetting::RemoveRule(index) => {
+                if index < rules.len() {
+                    rules.remove(index);
+                }
+            }
         }
     }
 
@@ -977,6 +4057,55 @@ impl AppState {
             SystemSetting::StartOnBoot(value) => {
                 self.config.system.launch_at_startup = value;
             }
+            SystemSetting::EnableTelemetry(value) => {
+                self.config.system.enable_telemetry = value;
+                if let Ok(mut telemetry) = self.telemetry.lock() {
+                    telemetry.set_enabled(value);
+                }
+                if value {
+                    self.flush_telemetry();
+                }
+            }
+            SystemSetting::PrivacyMode(value) => {
+                self.config.system.privacy_mode = value;
+                crate::privacy::apply(&mut self.config.system);
+                log::info!("Privacy mode {}", if value { "on" } else { "off" });
+            }
+            SystemSetting::RefreshKnownIssues(value) => {
+                self.config.system.refresh_known_issues = value;
+                self.known_issues_checked = None;
+            }
+            SystemSetting::BackupFrequency(frequency) => {
+                self.config.system.backup.frequency = frequency;
+                self.backup_checked = None;
+            }
+            SystemSetting::BackupFolder(folder) => {
+                self.config.system.backup.folder = Some(folder);
+                self.backup_checked = None;
+            }
+            SystemSetting::DeveloperMode(value) => {
+                self.config.system.developer_mode = value;
+                if !value {
+                    self.clear_synthetic_frames();
+                }
+            }
+            SystemSetting::CategoryLogLevel(category, level) => {
+                let levels = &mut self.config.system.category_log_levels;
+                match level {
+                    Some(level) => {
+                        levels.insert(category, level);
+                    }
+                    None => {
+                        levels.remove(&category);
+                    }
+                }
+                crate::logging::set_category_levels(levels);
+                log::info!(
+                    "Log level for {} set to {}",
+                    category,
+                    crate::ui::components::settings_view::CategoryLevelChoice(level)
+                );
+            }
         }
 
         // Update system tray if available
@@ -1019,12 +4148,12 @@ impl AppState {
     }
 
     /// Create a command to refresh device data from CLI scanner (now uses continuous mode for reliability)
-    pub fn refresh_device_data_command() -> Command<Message> {
+    pub fn refresh_device_data_command(supervisor: ScannerSupervisor) -> Command<Message> {
         Command::perform(
-            async {
+            async move {
                 // Always use continuous scanning mode for maximum reliability
                 // This ensures we find AirPods regardless of timing quirks
-                let airpods_data = get_airpods_from_cli_scanner();
+                let airpods_data = get_airpods_from_cli_scanner(&supervisor);
                 crate::debug_log!(
                     "bluetooth",
                     "CLI scanner returned {} AirPods devices",
@@ -1078,7 +4207,9 @@ impl AppState {
                         case_lid_open: airpods.case_lid_open,
                         side: airpods.side.map(|s| s.to_string()),
                         both_in_case: airpods.both_in_case,
-                        color: airpods.color.map(|c| c.to_string()),
+                        color: airpods
+                            .color
+                            .map(|c| crate::airpods::DeviceColor::from_code(c as u8).to_string()),
                         switch_count: airpods.switch_count.map(|s| s as u8),
                         is_connected: true,
                         last_seen: std::time::SystemTime::now(),
@@ -1088,6 +4219,11 @@ impl AppState {
                             .clone()
                             .map(|s| s.into_bytes())
                             .unwrap_or_default(),
+                        charging: [
+                            airpods.left_charging,
+                            airpods.right_charging,
+                            airpods.case_charging,
+                        ],
                     })
                     .collect()
             },
@@ -1106,12 +4242,31 @@ impl AppState {
         // Only update merged devices if we have actual AirPods data
         // This prevents clearing devices when the CLI scanner temporarily returns empty results
         if !self.airpods_devices.is_empty() {
-            // Clear existing merged devices only when we have new data to replace them
-            self.merged_devices.clear();
+            // Replace existing merged devices only when we have new data to replace them;
+            // GATT devices aren't reported by the scanner and are kept
+            let previous_devices = std::mem::take(&mut self.merged_devices);
+            self.merged_devices = previous_devices
+                .iter()
+                .filter(|device| device.device_type == DeviceType::Other)
+                .cloned()
+                .collect();
 
-            // Auto-select first device if none selected
+            let household_ids = self.household_device_ids();
+
+            // Auto-select the paired device, or the first device if none is paired
             if self.selected_device.is_none() && !self.airpods_devices.is_empty() {
-                let first_device_id = self.generate_stable_device_id(&self.airpods_devices[0]);
+                let first_device = self
+                    .config
+                    .bluetooth
+                    .paired_fingerprint
+                    .as_ref()
+                    .and_then(|fingerprint| {
+                        self.airpods_devices
+                            .iter()
+                            .find(|airpods| fingerprint.knows_address(&airpods.canonical_address))
+                    })
+                    .unwrap_or(&self.airpods_devices[0]);
+                let first_device_id = self.generate_stable_device_id(first_device);
                 crate::debug_log!(
                     "battery",
                     "Auto-selecting first available device: {}",
@@ -1127,46 +4282,64 @@ impl AppState {
                     if let Some(selected_airpods) = self.airpods_devices.iter().find(|airpods| {
                         self.generate_stable_device_id(airpods) == *selected_device_id
                     }) {
-                        crate::debug_log!(
-                            "battery",
-                            "Updating BatteryIntelligence for selected device: {}",
-                            selected_airpods.name
-                        );
+                        if self
+                            .last_intelligence_reading
+                            .as_ref()
+                            .is_some_and(|last| last.same_readings(selected_airpods))
+                        {
+                            crate::debug_log!(
+                                "battery",
+                                "Readings unchanged for {}, skipping BatteryIntelligence update",
+                                selected_airpods.name
+                            );
+                        } else {
+                            crate::debug_log!(
+                                "battery",
+                                "Updating BatteryIntelligence for selected device: {}",
+                                selected_airpods.name
+                            );
 
-                        // Generate stable device ID for this device (singleton pattern)
-                        let stable_device_id = self.generate_stable_device_id(selected_airpods);
+                            // Generate stable device ID for this device (singleton pattern)
+                            let stable_device_id = self.generate_stable_device_id(selected_airpods);
+
+                            // Ensure device profile exists (singleton pattern - always updates the one profile)
+                            let _is_new_device = self
+                                .battery_intelligence
+                                .ensure_device_profile(&stable_device_id, &selected_airpods.name);
+
+                            // Update the BatteryIntelligence system with device data (singleton pattern)
+                            self.battery_intelligence.update_device_battery(
+                                &stable_device_id,
+                                &selected_airpods.name,
+                                Some(selected_airpods.left_battery.max(0).min(100) as u8),
+                                Some(selected_airpods.right_battery.max(0).min(100) as u8),
+                                Some(selected_airpods.case_battery.max(0).min(100) as u8),
+                                selected_airpods.left_charging,
+                                selected_airpods.right_charging,
+                                selected_airpods.case_charging,
+                                selected_airpods.left_in_ear.unwrap_or(false),
+                                selected_airpods.right_in_ear.unwrap_or(false),
+                                selected_airpods.rssi.map(|r| r as i16),
+                            );
 
-                        // Ensure device profile exists (singleton pattern - always updates the one profile)
-                        let _is_new_device = self
-                            .battery_intelligence
-                            .ensure_device_profile(&stable_device_id, &selected_airpods.name);
-
-                        // Update the BatteryIntelligence system with device data (singleton pattern)
-                        self.battery_intelligence.update_device_battery(
-                            &stable_device_id,
-                            &selected_airpods.name,
-                            Some(selected_airpods.left_battery.max(0).min(100) as u8),
-                            Some(selected_airpods.right_battery.max(0).min(100) as u8),
-                            Some(selected_airpods.case_battery.max(0).min(100) as u8),
-                            selected_airpods.left_charging,
-                            selected_airpods.right_charging,
-                            selected_airpods.case_charging,
-                            selected_airpods.left_in_ear.unwrap_or(false),
-                            selected_airpods.right_in_ear.unwrap_or(false),
-                            selected_airpods.rssi.map(|r| r as i16),
-                        );
+                            self.notify_monthly_report();
 
-                        // Save the BatteryIntelligence data after updates
-                        if let Err(e) = self.battery_intelligence.save() {
-                            eprintln!("Warning: Failed to save battery intelligence data: {}", e);
-                        }
+                            // Save the BatteryIntelligence data after updates
+                            if let Err(e) = self.battery_intelligence.save() {
+                                eprintln!(
+                                    "Warning: Failed to save battery intelligence data: {}",
+                                    e
+                                );
+                            }
 
-                        // Also update the old estimator for backward compatibility during transition
-                        self.battery_estimator.update_real_data(
-                            Some(selected_airpods.left_battery),
-                            Some(selected_airpods.right_battery),
-                            Some(selected_airpods.case_battery),
-                        );
+                            // Also update the old estimator for backward compatibility during transition
+                            self.battery_estimator.update_real_data(
+                                Some(selected_airpods.left_battery),
+                                Some(selected_airpods.right_battery),
+                                Some(selected_airpods.case_battery),
+                            );
+                            self.last_intelligence_reading = Some(selected_airpods.clone());
+                        }
                     } else {
                         crate::debug_log!(
                             "battery",
@@ -1181,6 +4354,13 @@ impl AppState {
                     );
                 }
 
+                // In household mode every other set learns its own drain
+                for (airpods, id) in self.airpods_devices.iter().zip(&household_ids) {
+                    if self.selected_device.as_ref() != Some(id) {
+                        self.household_intelligence.update(id, airpods);
+                    }
+                }
+
                 // Save updated battery estimator data to config (for backward compatibility)
                 let (_left_est, _right_est, _case_est) =
                     self.battery_estimator.get_estimated_levels();
@@ -1290,8 +4470,35 @@ impl AppState {
             };
 
             // Add AirPods devices to the merged devices
-            self.merged_devices
-                .extend(self.airpods_devices.iter().map(|airpods| {
+            let devices: Vec<MergedBluetoothDevice> = self
+                .airpods_devices
+                .iter()
+                .enumerate()
+                .map(|(index, airpods)| {
+                    // Sets listed beside the selected one in household mode use their own
+                    // estimates rather than the selected set's
+                    let (
+                        left_estimate,
+                        right_estimate,
+                        case_estimate,
+                        left_fractional,
+                        right_fractional,
+                        case_fractional,
+                    ) = match household_ids
+                        .get(index)
+                        .filter(|id| self.selected_device.as_ref() != Some(*id))
+                    {
+                        Some(id) => self.household_estimates(id),
+                        None => (
+                            left_estimate,
+                            right_estimate,
+                            case_estimate,
+                            left_fractional,
+                            right_fractional,
+                            case_fractional,
+                        ),
+                    };
+
                     crate::debug_log!(
                         "airpods",
                         "Converting AirPods device: {} - L:{}% R:{}% C:{}%",
@@ -1350,7 +4557,9 @@ impl AppState {
                         case_lid_open: airpods.case_lid_open,
                         side: airpods.side.map(|s| s.to_string()),
                         both_in_case: airpods.both_in_case,
-                        color: airpods.color.map(|c| c.to_string()),
+                        color: airpods
+                            .color
+                            .map(|c| crate::airpods::DeviceColor::from_code(c as u8).to_string()),
                         switch_count: airpods.switch_count.map(|s| s as u8),
                         is_connected: true,
                         last_seen: std::time::SystemTime::now(),
@@ -1360,8 +4569,44 @@ impl AppState {
                             .clone()
                             .map(|s| s.into_bytes())
                             .unwrap_or_default(),
+                        charging: [
+                            airpods.left_charging,
+                            airpods.right_charging,
+                            airpods.case_charging,
+                        ],
+                    }
+                })
+                .collect();
+            self.merged_devices.extend(devices);
+            self.apply_classic_connections();
+
+            // The main window shows the first device, so put the paired AirPods there
+            if let Some(fingerprint) = &self.config.bluetooth.paired_fingerprint {
+                self.merged_devices
+                    .sort_by_key(|device| !fingerprint.knows_address(&device.address));
+            }
+
+            // Nothing the windows show changed, so leave them as they are
+            let unchanged = previous_devices.len() == self.merged_devices.len()
+                && previous_devices
+                    .iter()
+                    .zip(&self.merged_devices)
+                    .all(|(previous, current)| previous.same_display(current));
+            if unchanged {
+                crate::debug_log!("ui", "Merged devices unchanged, skipping window updates");
+                // The readings are the same but were just heard, so they aren't stale
+                for shown in &mut self.main_window.merged_devices {
+                    if let Some(current) = self
+                        .merged_devices
+                        .iter()
+                        .find(|current| current.address == shown.address)
+                    {
+                        shown.last_seen = current.last_seen;
                     }
-                }));
+                }
+                self.status_message = None;
+                return;
+            }
 
             // Update the main window with the new merged devices
             self.main_window.merged_devices = self.merged_devices.clone();
@@ -1372,14 +4617,14 @@ impl AppState {
             );
 
             // Update settings window with connected device names
-            let connected_device_names: Vec<String> = self
-                .merged_devices
-                .iter()
-                .filter(|device| device.is_connected || device.connected)
-                .map(|device| device.name.clone())
-                .collect();
-            self.settings_window
-                .update_connected_devices(connected_device_names);
+            self.sync_connected_device_names();
+            self.settings_window.update_nearby_airpods(
+                self.merged_devices
+                    .iter()
+                    .filter(|device| device.device_type == DeviceType::AirPods)
+                    .cloned()
+                    .collect(),
+            );
 
             // Clear status message when devices are found - only keep it for warnings/errors
             self.status_message = None;
@@ -1418,10 +4663,70 @@ impl AppState {
         );
     }
 
+    /// Identifiers of the sets in the latest scan when household mode is on
+    fn household_device_ids(&self) -> Vec<String> {
+        if !self.config.ui.household_mode {
+            return Vec::new();
+        }
+        let ids = self
+            .airpods_devices
+            .iter()
+            .map(|airpods| self.generate_stable_device_id(airpods))
+            .collect();
+        let addresses: Vec<&str> = self
+            .airpods_devices
+            .iter()
+            .map(|airpods| airpods.canonical_address.as_str())
+            .collect();
+        household::distinct_device_ids(ids, &addresses)
+    }
+
+    /// Display estimates for a set tracked in household mode, in the same form as the
+    /// selected set's
+    #[allow(clippy::type_complexity)]
+    fn household_estimates(
+        &self,
+        device_id: &str,
+    ) -> (
+        Option<u8>,
+        Option<u8>,
+        Option<u8>,
+        Option<f32>,
+        Option<f32>,
+        Option<f32>,
+    ) {
+        if !self.config.battery.enable_estimation {
+            return (None, None, None, None, None, None);
+        }
+        let Some((left, right, case)) = self.household_intelligence.estimates(device_id) else {
+            return (None, None, None, None, None, None);
+        };
+        let level = |estimate: &BatteryEstimate| {
+            (estimate.level >= 0.0).then(|| estimate.level.round().min(100.0) as u8)
+        };
+        let fractional =
+            |estimate: &BatteryEstimate| (estimate.level >= 0.0).then(|| estimate.level.round());
+        (
+            level(&left),
+            level(&right),
+            level(&case),
+            fractional(&left),
+            fractional(&right),
+            fractional(&case),
+        )
+    }
+
     /// Generate a stable device identifier that handles MAC address randomization
     /// This uses device model and user preferences to create consistent identifiers
     /// across MAC address changes due to privacy randomization
     fn generate_stable_device_id(&self, airpods: &AirPodsBatteryInfo) -> String {
+        // Priority 0: the paired AirPods keep one identifier across address rotations
+        if let Some(fingerprint) = &self.config.bluetooth.paired_fingerprint {
+            if fingerprint.knows_address(&airpods.canonical_address) {
+                return fingerprint.stable_id();
+            }
+        }
+
         // Priority 1: If user has set a custom device name, use that as the stable identifier
         if let Some(custom_name) = &self.config.bluetooth.paired_device_name {
             if !custom_name.trim().is_empty()
@@ -1448,7 +4753,7 @@ impl AppState {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MergedBluetoothDevice {
     pub name: String,
     pub address: String,
@@ -1475,6 +4780,8 @@ pub struct MergedBluetoothDevice {
     pub last_seen: std::time::SystemTime,
     pub rssi: Option<i16>,
     pub manufacturer_data: Vec<u8>,
+    /// Whether the left bud, right bud and case are charging
+    pub charging: [bool; 3],
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -1483,6 +4790,49 @@ pub enum DeviceType {
     Other,
 }
 
+impl MergedBluetoothDevice {
+    /// Connected if the PC has the device connected, otherwise nearby since it was heard
+    pub fn connection_status(&self) -> ConnectionStatus {
+        if self.is_connected {
+            ConnectionStatus::Connected
+        } else {
+            ConnectionStatus::Nearby
+        }
+    }
+
+    /// Whether both show the same thing, ignoring when each was seen
+    pub fn same_display(&self, other: &Self) -> bool {
+        *self
+            == Self {
+                last_seen: self.last_seen,
+                ..other.clone()
+            }
+    }
+
+    /// A non-Apple device whose level was read from its GATT Battery Service
+    pub fn from_gatt_battery(device: &DiscoveredDevice, status: &AirPodsBatteryStatus) -> Self {
+        let level = status.battery.left.or(status.battery.right);
+        Self {
+            name: device
+                .name
+                .clone()
+                .unwrap_or_else(|| "Bluetooth headphones".to_string()),
+            address: device.address.to_string(),
+            paired: true,
+            connected: true,
+            device_type: DeviceType::Other,
+            battery: level,
+            left_battery: status.battery.left,
+            right_battery: status.battery.right,
+            case_battery: status.battery.case,
+            is_connected: true,
+            last_seen: std::time::SystemTime::now(),
+            rssi: device.rssi,
+            ..Self::default()
+        }
+    }
+}
+
 impl Default for MergedBluetoothDevice {
     fn default() -> Self {
         Self {
@@ -1510,17 +4860,40 @@ impl Default for MergedBluetoothDevice {
             last_seen: std::time::SystemTime::UNIX_EPOCH,
             rssi: None,
             manufacturer_data: Vec::new(),
+            charging: [false; 3],
         }
     }
 }
 
+/// Ask how to repair a settings file that failed to load
+fn settings_repair_dialog(error: &ConfigError) -> Dialog {
+    Dialog::choose(
+        "Repair settings?",
+        format!(
+            "{}\n\nRustPods is using default settings for now and won't save over the file until it is repaired.",
+            error
+        ),
+        RepairAction::ALL
+            .iter()
+            .map(|action| action.label().to_string())
+            .collect(),
+        |index| Message::RepairSettings(RepairAction::ALL[index]),
+    )
+}
+
+/// Theme chosen by the schedule, or the default palette when scheduling is off
+fn scheduled_or_default_theme(config: &AppConfig) -> crate::ui::theme::Theme {
+    crate::ui::theme_schedule::scheduled_theme(&config.ui.theme_schedule, chrono::Local::now())
+        .unwrap_or(crate::ui::theme::Theme::CatppuccinMocha)
+}
+
 /// Async function to scan for AirPods without blocking the UI
 #[allow(dead_code)]
-async fn async_scan_for_airpods() -> Vec<AirPodsBatteryInfo> {
+async fn async_scan_for_airpods(supervisor: ScannerSupervisor) -> Vec<AirPodsBatteryInfo> {
     use tokio::task;
 
     // Run the CLI scanner in a blocking task to avoid blocking the async runtime
-    task::spawn_blocking(get_airpods_from_cli_scanner)
+    task::spawn_blocking(move || get_airpods_from_cli_scanner(&supervisor))
         .await
         .unwrap_or_else(|_| {
             log::error!("Failed to execute CLI scanner task");
@@ -1528,12 +4901,8 @@ async fn async_scan_for_airpods() -> Vec<AirPodsBatteryInfo> {
         })
 }
 
-/// Get AirPods data from the CLI scanner
-#[allow(dead_code)]
-fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
-    use std::process::Command as ProcessCommand;
-
-    // Get the executable path and its directory
+/// Places the CLI scanner is looked for, in order
+pub(crate) fn cli_scanner_paths() -> Vec<std::path::PathBuf> {
     let exe_path =
         std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("./rustpods.exe"));
     let exe_dir = exe_path
@@ -1541,17 +4910,7 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
         .unwrap_or_else(|| std::path::Path::new("."));
     let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
 
-    crate::debug_log!("bluetooth", "CLI Scanner Path Resolution Debug");
-    crate::debug_log!("bluetooth", "Executable path: {}", exe_path.display());
-    crate::debug_log!("bluetooth", "Executable directory: {}", exe_dir.display());
-    crate::debug_log!(
-        "bluetooth",
-        "Current working directory: {}",
-        current_dir.display()
-    );
-
-    // Try multiple possible locations for the CLI scanner
-    let cli_paths = vec![
+    vec![
         // 1. Same directory as the executable (most likely when running from target/release)
         exe_dir.join("airpods_battery_cli.exe"),
         // 2. bin folder relative to current working directory
@@ -1571,7 +4930,35 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
             .join("build")
             .join("Release")
             .join("airpods_battery_cli.exe"),
-    ];
+    ]
+}
+
+/// Get AirPods data from the CLI scanner
+#[allow(dead_code)]
+pub(crate) fn get_airpods_from_cli_scanner(
+    supervisor: &ScannerSupervisor,
+) -> Vec<AirPodsBatteryInfo> {
+    use std::process::Command as ProcessCommand;
+
+    // Get the executable path and its directory
+    let exe_path =
+        std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("./rustpods.exe"));
+    let exe_dir = exe_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+
+    crate::debug_log!("bluetooth", "CLI Scanner Path Resolution Debug");
+    crate::debug_log!("bluetooth", "Executable path: {}", exe_path.display());
+    crate::debug_log!("bluetooth", "Executable directory: {}", exe_dir.display());
+    crate::debug_log!(
+        "bluetooth",
+        "Current working directory: {}",
+        current_dir.display()
+    );
+
+    // Try multiple possible locations for the CLI scanner
+    let cli_paths = cli_scanner_paths();
 
     crate::debug_log!(
         "bluetooth",
@@ -1608,7 +4995,7 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
         command.creation_flags(0x08000000); // CREATE_NO_WINDOW
     }
 
-    match command.output() {
+    match supervisor.run(&mut command, SCANNER_TIMEOUT) {
         Ok(output) => {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -1622,40 +5009,12 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
                 if let Ok(cli_result) =
                     serde_json::from_str::<crate::bluetooth::cli_scanner::CliScannerResult>(&stdout)
                 {
+                    supervisor.record_result(&cli_result);
                     let mut airpods_devices = Vec::new();
 
                     for device in &cli_result.devices {
                         if let Some(airpods_data) = &device.airpods_data {
-                            // Create canonical address (lowercased, colon-free MAC address)
-                            let canonical_address = device.address.replace(":", "").to_lowercase();
-
-                            // Use canonical address as the primary identifier (no decimal conversion)
-                            let address = u64::from_str_radix(&canonical_address, 16).unwrap_or(0);
-
-                            let airpods_info = crate::airpods::battery::AirPodsBatteryInfo {
-                                address,
-                                canonical_address,
-                                name: airpods_data.model.clone(),
-                                model_id: 0, // Not provided by CLI scanner
-                                left_battery: airpods_data.left_battery,
-                                right_battery: airpods_data.right_battery,
-                                case_battery: airpods_data.case_battery,
-                                left_charging: airpods_data.left_charging,
-                                right_charging: airpods_data.right_charging,
-                                case_charging: airpods_data.case_charging,
-                                left_in_ear: None,   // Not provided by CLI scanner
-                                right_in_ear: None,  // Not provided by CLI scanner
-                                case_lid_open: None, // Not provided by CLI scanner
-                                side: None,          // Not provided by CLI scanner
-                                both_in_case: None,  // Not provided by CLI scanner
-                                color: None,         // Not provided by CLI scanner
-                                switch_count: None,  // Not provided by CLI scanner
-                                rssi: None,          // Not provided by CLI scanner
-                                timestamp: None,     // Not provided by CLI scanner
-                                raw_manufacturer_data: None, // Not provided by CLI scanner
-                            };
-
-                            airpods_devices.push(airpods_info);
+                            airpods_devices.push(airpods_from_cli(&device.address, airpods_data));
                         }
                     }
 
@@ -1667,6 +5026,7 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
                     airpods_devices
                 } else {
                     log::error!("Failed to parse CLI scanner JSON output");
+                    supervisor.record_parse_failure();
                     log::error!(
                         "Raw output preview: {}",
                         stdout.chars().take(200).collect::<String>()
@@ -1694,9 +5054,13 @@ fn get_airpods_from_cli_scanner() -> Vec<AirPodsBatteryInfo> {
 }
 
 /// Continuous CLI scanner for periodic updates every 10 seconds
-/// This function is called by the timer subscription to maintain fresh data
+/// This function is called by the timer subscription to maintain fresh data.
+/// Failures are returned so they can be surfaced in the error banner.
 #[allow(dead_code)]
-fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
+fn get_airpods_from_cli_scanner_continuous(
+    supervisor: &ScannerSupervisor,
+    focus: &FocusedScan,
+) -> Result<Vec<AirPodsBatteryInfo>, String> {
     use std::process::Command as ProcessCommand;
 
     // Get the executable path and its directory
@@ -1717,27 +5081,7 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
     );
 
     // Try multiple possible locations for the CLI scanner
-    let cli_paths = vec![
-        // 1. Same directory as the executable (most likely when running from target/release)
-        exe_dir.join("airpods_battery_cli.exe"),
-        // 2. bin folder relative to current working directory
-        current_dir.join("bin").join("airpods_battery_cli.exe"),
-        // 3. bin folder relative to executable directory (if exe is in subdir)
-        exe_dir.join("bin").join("airpods_battery_cli.exe"),
-        // 4. Project root if we're in target/release (go up 2 levels)
-        exe_dir
-            .parent()
-            .and_then(|p| p.parent())
-            .map(|project_root| project_root.join("bin").join("airpods_battery_cli.exe"))
-            .unwrap_or_default(),
-        // 5. Development location relative to current working directory
-        current_dir
-            .join("scripts")
-            .join("airpods_battery_cli")
-            .join("build")
-            .join("Release")
-            .join("airpods_battery_cli.exe"),
-    ];
+    let cli_paths = cli_scanner_paths();
 
     crate::debug_log!(
         "bluetooth",
@@ -1773,7 +5117,8 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
         }
         None => {
             log::error!("Continuous scan - No CLI scanner found in any of the expected locations!");
-            return Vec::new();
+            supervisor.record_failure("scanner executable not found".to_string());
+            return Err("AirPods scanner (airpods_battery_cli.exe) not found".to_string());
         }
     };
 
@@ -1788,7 +5133,7 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
         command.creation_flags(0x08000000); // CREATE_NO_WINDOW
     }
 
-    match command.output() {
+    match supervisor.run(&mut command, SCANNER_TIMEOUT) {
         Ok(output) => {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -1802,40 +5147,19 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
                 if let Ok(cli_result) =
                     serde_json::from_str::<crate::bluetooth::cli_scanner::CliScannerResult>(&stdout)
                 {
+                    supervisor.record_result(&cli_result);
                     let mut airpods_devices = Vec::new();
 
                     for device in &cli_result.devices {
+                        // A focused scan drops every other device before it is parsed
+                        if !focus.allows(&device.address) {
+                            continue;
+                        }
+                        if focus.address().is_some() {
+                            focus.record(FrameReport::from_cli(device));
+                        }
                         if let Some(airpods_data) = &device.airpods_data {
-                            // Create canonical address (lowercased, colon-free MAC address)
-                            let canonical_address = device.address.replace(":", "").to_lowercase();
-
-                            // Use canonical address as the primary identifier (no decimal conversion)
-                            let address = u64::from_str_radix(&canonical_address, 16).unwrap_or(0);
-
-                            let airpods_info = crate::airpods::battery::AirPodsBatteryInfo {
-                                address,
-                                canonical_address,
-                                name: airpods_data.model.clone(),
-                                model_id: 0, // Not provided by CLI scanner
-                                left_battery: airpods_data.left_battery,
-                                right_battery: airpods_data.right_battery,
-                                case_battery: airpods_data.case_battery,
-                                left_charging: airpods_data.left_charging,
-                                right_charging: airpods_data.right_charging,
-                                case_charging: airpods_data.case_charging,
-                                left_in_ear: None,   // Not provided by CLI scanner
-                                right_in_ear: None,  // Not provided by CLI scanner
-                                case_lid_open: None, // Not provided by CLI scanner
-                                side: None,          // Not provided by CLI scanner
-                                both_in_case: None,  // Not provided by CLI scanner
-                                color: None,         // Not provided by CLI scanner
-                                switch_count: None,  // Not provided by CLI scanner
-                                rssi: None,          // Not provided by CLI scanner
-                                timestamp: None,     // Not provided by CLI scanner
-                                raw_manufacturer_data: None, // Not provided by CLI scanner
-                            };
-
-                            airpods_devices.push(airpods_info);
+                            airpods_devices.push(airpods_from_cli(&device.address, airpods_data));
                         }
                     }
 
@@ -1844,14 +5168,15 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
                         "Continuous scan found {} AirPods devices",
                         airpods_devices.len()
                     );
-                    airpods_devices
+                    Ok(airpods_devices)
                 } else {
                     log::error!("Continuous scan - Failed to parse CLI scanner JSON output");
+                    supervisor.record_parse_failure();
                     log::error!(
                         "Continuous scan - Raw output preview: {}",
                         stdout.chars().take(200).collect::<String>()
                     );
-                    Vec::new()
+                    Err("AirPods scanner returned unreadable output".to_string())
                 }
             } else {
                 log::error!(
@@ -1862,13 +5187,270 @@ fn get_airpods_from_cli_scanner_continuous() -> Vec<AirPodsBatteryInfo> {
                     "Continuous scan - CLI scanner stderr: {}",
                     String::from_utf8_lossy(&output.stderr)
                 );
-                Vec::new()
+                Err(match output.status.code() {
+                    Some(code) => format!("AirPods scanner failed with exit code {}", code),
+                    None => "AirPods scanner was terminated".to_string(),
+                })
             }
         }
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+            log::error!("Continuous scan - CLI scanner hung and was stopped: {}", e);
+            Err(format!(
+                "AirPods scanner stopped responding and was restarted after {}s",
+                SCANNER_TIMEOUT.as_secs()
+            ))
+        }
         Err(e) => {
             log::error!("Continuous scan - Failed to execute CLI scanner: {}", e);
-            // Already logged with log::error! above
-            Vec::new()
+            Err(format!("Failed to run AirPods scanner: {}", e))
+        }
+    }
+}
+
+/// A CLI scanner report of the AirPods at `address`
+///
+/// The scanner only reports the model name, levels and charging; the rest is left unset.
+fn airpods_from_cli(
+    address: &str,
+    airpods_data: &crate::bluetooth::cli_scanner::CliAirPodsData,
+) -> AirPodsBatteryInfo {
+    // Create canonical address (lowercased, colon-free MAC address)
+    let canonical_address = address.replace(":", "").to_lowercase();
+
+    // Use canonical address as the primary identifier (no decimal conversion)
+    let address = u64::from_str_radix(&canonical_address, 16).unwrap_or(0);
+
+    AirPodsBatteryInfo {
+        address,
+        canonical_address,
+        name: airpods_data.model.clone(),
+        model_id: 0,
+        left_battery: airpods_data.left_battery,
+        right_battery: airpods_data.right_battery,
+        case_battery: airpods_data.case_battery,
+        left_charging: airpods_data.left_charging,
+        right_charging: airpods_data.right_charging,
+        case_charging: airpods_data.case_charging,
+        left_in_ear: None,
+        right_in_ear: None,
+        case_lid_open: None,
+        side: None,
+        both_in_case: None,
+        color: None,
+        switch_count: None,
+        rssi: None,
+        timestamp: None,
+        raw_manufacturer_data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::airpods::test_support::report;
+    use crate::ui::data_age;
+    use std::time::{Duration, SystemTime};
+
+    fn test_state() -> AppState {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut state = AppState::new_for_test(tx);
+        state.config.battery.enable_estimation = false;
+        state
+    }
+
+    #[test]
+    fn test_known_devices_that_could_not_be_loaded_are_not_saved() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut state = test_state();
+        state.config.settings_path = temp_dir.path().join("settings.json");
+        let path = KnownDevices::path_for(&state.config);
+
+        state.known_devices_read_only = true;
+        state.unpair_device();
+        assert!(!path.exists());
+
+        state.known_devices_read_only = false;
+        state.unpair_device();
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_repeated_scans_keep_devices_fresh() {
+        let mut state = test_state();
+        state.airpods_devices = vec![report("aabbccddeeff")];
+        state.update_merged_devices();
+        assert_eq!(state.main_window.merged_devices.len(), 1);
+
+        // The same readings, heard again scan after scan
+        let long_ago = SystemTime::now() - Duration::from_secs(3600);
+        state.main_window.merged_devices[0].last_seen = long_ago;
+        for _ in 0..3 {
+            state.airpods_devices = vec![report("aabbccddeeff")];
+            state.update_merged_devices();
         }
+
+        let shown = &state.main_window.merged_devices[0];
+        assert!(shown.last_seen > long_ago);
+        assert!(!data_age::is_stale(
+            shown,
+            state.config.ui.stale_after_minutes,
+            SystemTime::now()
+        ));
+    }
+    /// Feed a scan of one case with its lid open or closed to the lid popup
+    fn scan_lid(state: &mut AppState, lid_open: bool) {
+        state.airpods_devices = vec![AirPodsBatteryInfo {
+            case_lid_open: Some(lid_open),
+            ..report("aabbccddeeff")
+        }];
+        state.update_merged_devices();
+        let _ = state.check_lid_open_popup();
+    }
+
+    #[test]
+    fn test_lid_popup_shows_once_per_lid_open() {
+        let mut state = test_state();
+        state.config.ui.show_lid_open_popup = true;
+
+        scan_lid(&mut state, false);
+        assert!(state.lid_popup.is_none());
+        scan_lid(&mut state, true);
+        assert!(state.lid_popup.is_some());
+
+        // Dismissed, and not shown again while the lid stays open
+        let generation = state.lid_popup_generation;
+        let _ = state.dismiss_lid_popup(generation);
+        assert!(state.lid_popup.is_none());
+        scan_lid(&mut state, true);
+        assert!(state.lid_popup.is_none());
+
+        // Until the lid is closed and opened again
+        scan_lid(&mut state, false);
+        scan_lid(&mut state, true);
+        assert!(state.lid_popup.is_some());
+        // The first popup's dismiss timer leaves the new one alone
+        let _ = state.dismiss_lid_popup(generation);
+        assert!(state.lid_popup.is_some());
+    }
+
+    #[test]
+    fn test_lid_popup_hides_the_window_it_opened() {
+        let mut state = test_state();
+        state.config.ui.show_lid_open_popup = true;
+        state.visible = false;
+
+        // A lid already open when first heard wasn't seen opening
+        scan_lid(&mut state, true);
+        assert!(state.lid_popup.is_none());
+
+        scan_lid(&mut state, false);
+        scan_lid(&mut state, true);
+        assert!(state.lid_popup.is_some());
+        assert!(state.visible);
+
+        let _ = state.dismiss_lid_popup(state.lid_popup_generation);
+        assert!(state.lid_popup.is_none());
+        assert!(!state.visible);
+    }
+
+    #[test]
+    fn test_mah_estimate_uses_the_shown_device_model() {
+        let mut state = test_state();
+        state.config.ui.show_mah_estimates = true;
+        state.airpods_devices = vec![
+            report("aaaaaaaaaaaa"),
+            AirPodsBatteryInfo {
+                model_id: 0x2013,
+                left_battery: 100,
+                right_battery: 100,
+                case_battery: 100,
+                ..report("bbbbbbbbbbbb")
+            },
+        ];
+        state.update_merged_devices();
+        // As when the second device is the paired one and is listed first
+        state
+            .merged_devices
+            .sort_by_key(|device| device.address != "bbbbbbbbbbbb");
+
+        let estimate = state.mah_estimate().unwrap();
+        assert_eq!(estimate.left, Some(35));
+        assert_eq!(estimate.case, Some(345));
+    }
+
+    #[test]
+    fn test_audio_routing_is_read_when_the_device_connects() {
+        let mut state = test_state();
+        state.airpods_devices = vec![report("aabbccddeeff")];
+        state.update_merged_devices();
+        let _ = state.refresh_audio_routing();
+        assert!(state.audio_routing_checked.is_some());
+
+        // Another scan of the same device doesn't enumerate the endpoints again
+        let device = state.merged_devices[0].clone();
+        assert!(!state.audio_routing_due(&device));
+
+        let disconnected = MergedBluetoothDevice {
+            is_connected: !device.is_connected,
+            ..device
+        };
+        assert!(state.audio_routing_due(&disconnected));
+    }
+
+    #[test]
+    fn test_empty_scan_clears_scanner_error() {
+        let has_scanner_error = |state: &AppState| {
+            state
+                .error_manager
+                .lock()
+                .unwrap()
+                .active_errors()
+                .iter()
+                .any(|error| error.component == "scanner")
+        };
+        let mut state = test_state();
+
+        let _ = state.update(Message::ScanFailed("scanner exited".to_string()));
+        assert!(has_scanner_error(&state));
+
+        // Nothing nearby is still a scan that worked
+        let _ = state.update(Message::AirPodsDataLoaded(Vec::new()));
+        assert!(!has_scanner_error(&state));
+    }
+
+    #[test]
+    fn test_closed_controller_channel_is_rebound() {
+        use crate::ui::system_tray_controller::TrayCommand;
+        use tokio::sync::mpsc::error::TryRecvError;
+
+        let mut state = test_state();
+        let (tray, tray_commands) = SystemTrayController::detached();
+        state.system_tray = Some(tray);
+
+        // What the controller subscription sends after the old channel closed
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let _ = state.update(Message::ControllerChannelRestored(sender));
+        let Ok(TrayCommand::SetUiSender(tray_sender)) = tray_commands.try_recv() else {
+            panic!("the tray wasn't given the new sender");
+        };
+        crate::lifecycle_manager::power_event_sender()
+            .expect("the power monitor wasn't given the new sender")
+            .send(Message::SystemResumed)
+            .unwrap();
+        assert!(matches!(receiver.try_recv(), Ok(Message::SystemResumed)));
+
+        // Only the tray keeps the channel open, so losing it closes the channel again
+        drop(tray_sender);
+        assert!(matches!(
+            receiver.try_recv(),
+            Err(TryRecvError::Disconnected)
+        ));
+
+        // With the tray thread gone the state holds the channel open itself
+        drop(tray_commands);
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let _ = state.update(Message::ControllerChannelRestored(sender));
+        assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+        assert!(crate::lifecycle_manager::power_event_sender().is_some());
     }
 }
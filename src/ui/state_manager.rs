@@ -288,6 +288,7 @@ impl StateManager {
                                 battery: Some(crate::airpods::AirPodsBattery::default()),
                                 rssi: device.rssi,
                                 is_connected: false,
+                                extras: None,
                                 last_seen: std::time::Instant::now(),
                             };
                             self.notify_ui(Message::AirPodsConnected(airpods));
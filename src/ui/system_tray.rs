@@ -1,9 +1,11 @@
 //! System tray implementation for RustPods
 
-use crate::config::{AppConfig, Theme as ConfigTheme};
+use crate::assets::tray::IconSet;
+use crate::config::{AppConfig, Theme as ConfigTheme, TrayIconStyle};
+use crate::ui::headline::Headline;
 use crate::ui::message::Message;
+use crate::ui::state::ConnectionStatus;
 use log;
-use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc::UnboundedSender;
 use tray_icon::menu::MenuItem as TrayMenuItem;
@@ -52,6 +54,277 @@ pub enum SystemTrayError {
     IconLoad(String),
 }
 
+/// Background work shown by animating the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayActivity {
+    /// Nothing in flight; the static icon reflects fresh data
+    Idle,
+    /// A scan is in flight (rotating arc)
+    Scanning,
+    /// The scanner backend is restarting after failures or a resume (pulse)
+    Restarting,
+}
+
+/// Number of frames in one animation cycle
+pub const TRAY_ANIMATION_FRAMES: usize = 12;
+
+/// Delay between animation frames
+pub const TRAY_ANIMATION_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Size of the tray icon bitmap
+pub const TRAY_ICON_SIZE: u32 = 32;
+
+/// Digits 0-9 for the percentage icon, 3x5 pixels each; one row per byte, top row
+/// first, leftmost pixel in the highest of the three bits
+const DIGIT_GLYPHS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Decode a bundled tray icon at tray size
+fn bundled_icon(dark: bool, connected: bool) -> Result<image::RgbaImage, SystemTrayError> {
+    let icon = image::load_from_memory(crate::assets::tray::bundled(dark, connected))
+        .map_err(|e| SystemTrayError::IconLoad(format!("Failed to decode bundled icon: {}", e)))?;
+    Ok(fit_to_tray(&icon.to_rgba8()))
+}
+
+/// Scale an icon to tray size
+fn fit_to_tray(icon: &image::RgbaImage) -> image::RgbaImage {
+    if icon.dimensions() == (TRAY_ICON_SIZE, TRAY_ICON_SIZE) {
+        return icon.clone();
+    }
+    image::imageops::resize(
+        icon,
+        TRAY_ICON_SIZE,
+        TRAY_ICON_SIZE,
+        image::imageops::FilterType::Triangle,
+    )
+}
+
+/// Keep only the edge of an icon, two pixels wide
+pub fn render_outline(base: &image::RgbaImage) -> image::RgbaImage {
+    let (width, height) = base.dimensions();
+    let solid = |x: i64, y: i64| {
+        x >= 0
+            && y >= 0
+            && x < width as i64
+            && y < height as i64
+            && base.get_pixel(x as u32, y as u32)[3] >= 128
+    };
+
+    let mut image = base.clone();
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let (x, y) = (x as i64, y as i64);
+        let near_edge = (-2..=2).any(|dy| (-2..=2).any(|dx| !solid(x + dx, y + dy)));
+        if !near_edge {
+            pixel[3] = 0;
+        }
+    }
+    image
+}
+
+/// Draw a battery level as the whole icon, in `color`
+pub fn render_percentage_icon(level: u8, color: [u8; 3]) -> image::RgbaImage {
+    let digits: Vec<usize> = level
+        .to_string()
+        .bytes()
+        .map(|digit| (digit - b'0') as usize)
+        .collect();
+    // Glyphs are 3 wide with a 1 pixel gap, then scaled up as far as they fit
+    let text_width = digits.len() as u32 * 4 - 1;
+    let scale = (TRAY_ICON_SIZE / text_width).min(TRAY_ICON_SIZE / 5).max(1);
+    let left = (TRAY_ICON_SIZE.saturating_sub(text_width * scale)) / 2;
+    let top = (TRAY_ICON_SIZE.saturating_sub(5 * scale)) / 2;
+
+    let mut image = image::RgbaImage::new(TRAY_ICON_SIZE, TRAY_ICON_SIZE);
+    for (index, digit) in digits.into_iter().enumerate() {
+        let glyph_left = left + index as u32 * 4 * scale;
+        for (row, bits) in DIGIT_GLYPHS[digit].iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = glyph_left + column * scale + dx;
+                        let y = top + row as u32 * scale + dy;
+                        if x < TRAY_ICON_SIZE && y < TRAY_ICON_SIZE {
+                            image.put_pixel(x, y, image::Rgba([color[0], color[1], color[2], 255]));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Render the static tray icon for a style
+///
+/// The percentage style shows `headline`, in the low battery colour when it is low, and
+/// the filled icon while no level is known. The custom style uses `custom`, the user's
+/// icon set, and the filled icons when there is none.
+pub fn render_tray_icon(
+    style: TrayIconStyle,
+    dark: bool,
+    connected: bool,
+    headline: Option<Headline>,
+    custom: Option<&IconSet>,
+) -> Result<image::RgbaImage, SystemTrayError> {
+    match (style, headline, custom) {
+        (TrayIconStyle::Outline, _, _) => Ok(render_outline(&bundled_icon(dark, connected)?)),
+        (TrayIconStyle::Percentage, Some(headline), _) => {
+            let color = if headline.low {
+                crate::ui::palette::state_rgb(crate::ui::palette::BatteryState::Low)
+            } else if dark {
+                // Catppuccin text and base, for dark and light taskbars
+                [0xcd, 0xd6, 0xf4]
+            } else {
+                [0x1e, 0x1e, 0x2e]
+            };
+            Ok(render_percentage_icon(headline.level, color))
+        }
+        (TrayIconStyle::Custom, _, Some(custom)) => Ok(fit_to_tray(custom.icon(connected))),
+        _ => bundled_icon(dark, connected),
+    }
+}
+
+/// Turn a rendered icon into one the tray can show
+fn to_tray_icon(image: image::RgbaImage) -> Result<Icon, SystemTrayError> {
+    let (width, height) = image.dimensions();
+    Icon::from_rgba(image.into_raw(), width, height)
+        .map_err(|e| SystemTrayError::IconLoad(format!("Failed to build icon: {}", e)))
+}
+
+/// What the static icon was rendered from
+#[derive(Debug, Clone, PartialEq)]
+struct IconKey {
+    style: TrayIconStyle,
+    dark: bool,
+    connected: bool,
+    /// Only kept for the percentage style, the one that shows it
+    headline: Option<(u8, bool)>,
+}
+
+/// Render one animation frame on top of the base tray icon
+///
+/// Scanning draws a rotating arc in the bottom-right corner; restarting pulses the icon and
+/// draws a full ring, so the two states are distinguishable at tray size.
+pub fn render_activity_frame(
+    base: &image::RgbaImage,
+    activity: TrayActivity,
+    frame: usize,
+) -> image::RgbaImage {
+    let mut image = base.clone();
+    if activity == TrayActivity::Idle {
+        return image;
+    }
+
+    let phase = (frame % TRAY_ANIMATION_FRAMES) as f32 / TRAY_ANIMATION_FRAMES as f32;
+    let (width, height) = image.dimensions();
+    let radius = width.min(height) as f32 * 0.2;
+    let center_x = width as f32 - radius - 1.5;
+    let center_y = height as f32 - radius - 1.5;
+
+    if activity == TrayActivity::Restarting {
+        let opacity = 0.6 + 0.4 * (phase * std::f32::consts::TAU).cos().abs();
+        for pixel in image.pixels_mut() {
+            pixel[3] = (pixel[3] as f32 * opacity) as u8;
+        }
+    }
+
+    let (color, track_alpha) = match activity {
+        // Catppuccin blue
+        TrayActivity::Scanning => ([0x89, 0xb4, 0xfa], 90),
+        // Catppuccin yellow
+        _ => ([0xf9, 0xe2, 0xaf], 255),
+    };
+    let arc_start = phase * 360.0;
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let dx = x as f32 + 0.5 - center_x;
+        let dy = y as f32 + 0.5 - center_y;
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        if distance <= radius + 1.0 {
+            // Dark backing disc keeps the indicator readable on any icon colour
+            *pixel = image::Rgba([0x1e, 0x1e, 0x2e, 255]);
+        }
+        if (distance - radius).abs() > 1.0 {
+            continue;
+        }
+
+        let angle = dy.atan2(dx).to_degrees().rem_euclid(360.0);
+        let in_arc = (angle - arc_start).rem_euclid(360.0) < 120.0;
+        let alpha = if in_arc { 255 } else { track_alpha };
+        *pixel = image::Rgba([color[0], color[1], color[2], alpha]);
+    }
+
+    image
+}
+
+/// Render one frame of the critical battery flash
+///
+/// The icon alternates between its normal colours and a tint in the palette's low battery
+/// colour every half cycle.
+pub fn render_alert_frame(base: &image::RgbaImage, frame: usize) -> image::RgbaImage {
+    let mut image = base.clone();
+    if frame % TRAY_ANIMATION_FRAMES >= TRAY_ANIMATION_FRAMES / 2 {
+        return image;
+    }
+
+    let tint = crate::ui::palette::state_rgb(crate::ui::palette::BatteryState::Low).map(u16::from);
+    for pixel in image.pixels_mut() {
+        if pixel[3] == 0 {
+            continue;
+        }
+        for channel in 0..3 {
+            pixel[channel] = ((pixel[channel] as u16 + tint[channel] * 3) / 4) as u8;
+        }
+    }
+    image
+}
+
+/// Tooltip shown while no battery levels are known
+pub const DEFAULT_TOOLTIP: &str = "RustPods - AirPods Battery Monitor";
+
+/// First menu item in tray-only mode while no battery levels are known
+pub const TRAY_ONLY_LEVELS_LABEL: &str = "Battery Levels";
+
+/// Menu item in tray-only mode that brings the window back
+pub const OPEN_WINDOW_LABEL: &str = "Open Window";
+
+/// Tooltip listing each battery, e.g. "L 80% \u{26a1}  R 75%  Case 60%"
+///
+/// Batteries are given as (label, level, charging); those without a level are left
+/// out, and the default tooltip is used when none has one.
+pub fn battery_tooltip(batteries: &[(&str, Option<u8>, bool)]) -> String {
+    let parts: Vec<String> = batteries
+        .iter()
+        .filter_map(|(label, level, charging)| {
+            let level = (*level)?;
+            Some(if *charging {
+                format!("{} {}% \u{26a1}", label, level)
+            } else {
+                format!("{} {}%", label, level)
+            })
+        })
+        .collect();
+    if parts.is_empty() {
+        DEFAULT_TOOLTIP.to_string()
+    } else {
+        parts.join("  ")
+    }
+}
+
 /// Simple window controller for system tray
 #[derive(Debug, Clone)]
 pub struct DirectWindowController {
@@ -95,6 +368,26 @@ impl DirectWindowController {
         Ok(())
     }
 
+    /// Turn tray-only mode off and restart with the window
+    pub fn leave_tray_only(&self) -> Result<(), SystemTrayError> {
+        if let Ok(ui_sender) = self.ui_sender.lock() {
+            if let Some(ref sender) = *ui_sender {
+                let _ = sender.send(Message::LeaveTrayOnly);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restart the application; a safe mode run restarts normally
+    pub fn restart_application(&self) -> Result<(), SystemTrayError> {
+        if let Ok(ui_sender) = self.ui_sender.lock() {
+            if let Some(ref sender) = *ui_sender {
+                let _ = sender.send(Message::Restart(false));
+            }
+        }
+        Ok(())
+    }
+
     pub fn exit_application(&self) -> Result<(), SystemTrayError> {
         if let Ok(ui_sender) = self.ui_sender.lock() {
             if let Some(ref sender) = *ui_sender {
@@ -113,13 +406,16 @@ pub struct SystemTray {
     menu: Option<Menu>,
     /// Menu item IDs
     show_hide_item: Option<TrayMenuItem>,
+    /// Only in tray-only mode
+    open_window_item: Option<TrayMenuItem>,
+    restart_item: Option<TrayMenuItem>,
     exit_item: Option<TrayMenuItem>,
     /// Direct window controller
     window_controller: DirectWindowController,
     /// Application configuration
     config: AppConfig,
     /// Last known connection status
-    is_connected: bool,
+    connection: ConnectionStatus,
     /// Current theme mode
     theme_mode: ThemeMode,
     /// Whether the tray is initialized
@@ -127,13 +423,27 @@ pub struct SystemTray {
     /// Event receiver
     menu_receiver: Option<crossbeam_channel::Receiver<MenuEvent>>,
     tray_receiver: Option<crossbeam_channel::Receiver<TrayIconEvent>>,
+    /// Background work currently shown on the icon
+    activity: TrayActivity,
+    /// Current animation frame
+    animation_frame: usize,
+    /// Last static icon rendered, kept for animation frames and to skip unchanged updates
+    static_icon: Option<(IconKey, image::RgbaImage)>,
+    /// User icon set for the custom style
+    custom_icons: Option<IconSet>,
+    /// Level the percentage style shows
+    headline: Option<Headline>,
+    /// Whether the critical battery flash is active
+    critical_alert: bool,
+    /// Tooltip currently shown, or to show once initialized
+    tooltip: String,
 }
 
 impl std::fmt::Debug for SystemTray {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SystemTray")
             .field("initialized", &self.initialized)
-            .field("is_connected", &self.is_connected)
+            .field("connection", &self.connection)
             .finish()
     }
 }
@@ -144,14 +454,23 @@ impl Clone for SystemTray {
             tray: None, // TrayIcon is not cloneable
             menu: None,
             show_hide_item: None,
+            open_window_item: None,
+            restart_item: None,
             exit_item: None,
             window_controller: self.window_controller.clone(),
             config: self.config.clone(),
-            is_connected: self.is_connected,
+            connection: self.connection,
             theme_mode: self.theme_mode,
             initialized: false,
             menu_receiver: None,
             tray_receiver: None,
+            activity: self.activity,
+            animation_frame: 0,
+            static_icon: None,
+            custom_icons: self.custom_icons.clone(),
+            headline: self.headline,
+            critical_alert: self.critical_alert,
+            tooltip: self.tooltip.clone(),
         }
     }
 }
@@ -160,19 +479,29 @@ impl SystemTray {
     /// Create a new system tray instance
     pub fn new(config: AppConfig) -> Result<Self, SystemTrayError> {
         let theme_mode = ThemeMode::from(config.ui.theme.clone());
+        let custom_icons = load_custom_icons(&config);
 
         Ok(Self {
             tray: None,
             menu: None,
             show_hide_item: None,
+            open_window_item: None,
+            restart_item: None,
             exit_item: None,
             window_controller: DirectWindowController::new(),
             config,
-            is_connected: false,
+            connection: ConnectionStatus::NotFound,
             theme_mode,
             initialized: false,
             menu_receiver: None,
             tray_receiver: None,
+            activity: TrayActivity::Idle,
+            animation_frame: 0,
+            static_icon: None,
+            custom_icons,
+            headline: None,
+            critical_alert: false,
+            tooltip: DEFAULT_TOOLTIP.to_string(),
         })
     }
 
@@ -181,103 +510,34 @@ impl SystemTray {
         self.window_controller.set_ui_sender(sender);
     }
 
-    /// Get the appropriate icon path based on connection status and theme
-    fn get_icon_path(&self) -> String {
-        let theme_str = match self.theme_mode {
-            ThemeMode::Light => "light",
-            ThemeMode::Dark => "dark",
+    /// Render the static icon, or reuse the last one if nothing it shows has changed
+    fn base_icon(&mut self) -> Result<image::RgbaImage, SystemTrayError> {
+        let style = self.config.ui.tray_icon_style;
+        let key = IconKey {
+            style,
+            dark: self.theme_mode == ThemeMode::Dark,
+            // Nearby AirPods aren't connected, so they get the disconnected icon
+            connected: self.connection == ConnectionStatus::Connected,
+            headline: match style {
+                TrayIconStyle::Percentage => self.headline.map(|h| (h.level, h.low)),
+                _ => None,
+            },
         };
-
-        let status_str = if self.is_connected {
-            "connected"
-        } else {
-            "disconnected"
-        };
-
-        // Get the executable directory and construct absolute path
-        let exe_path =
-            std::env::current_exe().unwrap_or_else(|_| std::path::PathBuf::from("./rustpods.exe"));
-        let exe_dir = exe_path
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."));
-
-        // Try multiple possible locations for the icon
-        let icon_paths = vec![
-            // 1. Tray icons in same directory as executable (for release builds)
-            exe_dir.join(format!("rustpods-tray-{}-{}.ico", theme_str, status_str)),
-            // 2. Assets folder relative to executable
-            exe_dir
-                .join("assets")
-                .join("icons")
-                .join("tray")
-                .join(format!("rustpods-tray-{}-{}.ico", theme_str, status_str)),
-            // 3. Project root assets (for development)
-            exe_dir
-                .parent()
-                .and_then(|p| p.parent())
-                .map(|project_root| {
-                    project_root
-                        .join("assets")
-                        .join("icons")
-                        .join("tray")
-                        .join(format!("rustpods-tray-{}-{}.ico", theme_str, status_str))
-                })
-                .unwrap_or_default(),
-            // 4. Current working directory
-            std::env::current_dir()
-                .unwrap_or_default()
-                .join("assets")
-                .join("icons")
-                .join("tray")
-                .join(format!("rustpods-tray-{}-{}.ico", theme_str, status_str)),
-        ];
-
-        // Find the first existing icon file
-        for icon_path in icon_paths {
-            if icon_path.exists() {
-                log::debug!("Found tray icon at: {}", icon_path.display());
-                return icon_path.to_string_lossy().to_string();
+        if let Some((cached, icon)) = &self.static_icon {
+            if *cached == key {
+                return Ok(icon.clone());
             }
         }
 
-        // Fallback to relative path if none found
-        let fallback = format!(
-            "assets/icons/tray/rustpods-tray-{}-{}.ico",
-            theme_str, status_str
-        );
-        log::warn!("No tray icon found, using fallback: {}", fallback);
-        fallback
-    }
-
-    /// Load icon from file path
-    fn load_icon(&self, path: &str) -> Result<Icon, SystemTrayError> {
-        let icon_path = Path::new(path);
-
-        if !icon_path.exists() {
-            return Err(SystemTrayError::IconLoad(format!(
-                "Icon file not found: {}",
-                path
-            )));
-        }
-
-        // For ICO files, we need to use from_path instead of from_rgba
-        if path.ends_with(".ico") {
-            Icon::from_path(icon_path, Some((32, 32))).map_err(|e| {
-                SystemTrayError::IconLoad(format!(
-                    "Failed to create icon from ICO file {}: {}",
-                    path, e
-                ))
-            })
-        } else {
-            // For other formats, try to load as image and convert to RGBA
-            let icon_bytes = std::fs::read(icon_path).map_err(|e| {
-                SystemTrayError::IconLoad(format!("Failed to read icon file {}: {}", path, e))
-            })?;
-
-            Icon::from_rgba(icon_bytes, 32, 32).map_err(|e| {
-                SystemTrayError::IconLoad(format!("Failed to create icon from {}: {}", path, e))
-            })
-        }
+        let icon = render_tray_icon(
+            style,
+            key.dark,
+            key.connected,
+            self.headline,
+            self.custom_icons.as_ref(),
+        )?;
+        self.static_icon = Some((key, icon.clone()));
+        Ok(icon)
     }
 
     /// Initialize the system tray (creates the actual icon and menu)
@@ -290,7 +550,19 @@ impl SystemTray {
         log::info!("Initializing system tray...");
 
         // Create menu items
-        let show_hide_item = TrayMenuItem::new("Show/Hide", true, None);
+        // Without a window, the item shows the levels and shows them in a notification
+        let show_hide_label = if self.config.ui.tray_only {
+            TRAY_ONLY_LEVELS_LABEL
+        } else {
+            "Show/Hide"
+        };
+        let show_hide_item = TrayMenuItem::new(show_hide_label, true, None);
+        let open_window_item = self
+            .config
+            .ui
+            .tray_only
+            .then(|| TrayMenuItem::new(OPEN_WINDOW_LABEL, true, None));
+        let restart_item = TrayMenuItem::new("Restart RustPods", true, None);
         let exit_item = TrayMenuItem::new("Exit", true, None);
 
         // Create menu
@@ -298,17 +570,22 @@ impl SystemTray {
         menu.append(&show_hide_item).map_err(|e| {
             SystemTrayError::MenuItem(format!("Failed to add show/hide item: {}", e))
         })?;
+        if let Some(ref open_window_item) = open_window_item {
+            menu.append(open_window_item).map_err(|e| {
+                SystemTrayError::MenuItem(format!("Failed to add open window item: {}", e))
+            })?;
+        }
+        menu.append(&restart_item)
+            .map_err(|e| SystemTrayError::MenuItem(format!("Failed to add restart item: {}", e)))?;
         menu.append(&exit_item)
             .map_err(|e| SystemTrayError::MenuItem(format!("Failed to add exit item: {}", e)))?;
 
-        // Get icon path and load icon
-        let icon_path = self.get_icon_path();
-        let icon = self.load_icon(&icon_path)?;
+        let icon = to_tray_icon(self.base_icon()?)?;
 
         // Create tray icon
         let tray = TrayIconBuilder::new()
             .with_menu(Box::new(menu.clone()))
-            .with_tooltip("RustPods - AirPods Battery Monitor")
+            .with_tooltip(&self.tooltip)
             .with_icon(icon)
             .build()
             .map_err(|e| SystemTrayError::Creation(format!("Failed to create tray icon: {}", e)))?;
@@ -321,6 +598,8 @@ impl SystemTray {
         self.tray = Some(tray);
         self.menu = Some(menu);
         self.show_hide_item = Some(show_hide_item);
+        self.open_window_item = open_window_item;
+        self.restart_item = Some(restart_item);
         self.exit_item = Some(exit_item);
         self.menu_receiver = Some(menu_channel);
         self.tray_receiver = Some(tray_channel);
@@ -375,6 +654,20 @@ impl SystemTray {
             }
         }
 
+        if let Some(ref open_window_item) = self.open_window_item {
+            if event.id == open_window_item.id() {
+                self.window_controller.leave_tray_only()?;
+                return Ok(());
+            }
+        }
+
+        if let Some(ref restart_item) = self.restart_item {
+            if event.id == restart_item.id() {
+                self.window_controller.restart_application()?;
+                return Ok(());
+            }
+        }
+
         if let Some(ref exit_item) = self.exit_item {
             if event.id == exit_item.id() {
                 self.window_controller.exit_application()?;
@@ -398,57 +691,177 @@ impl SystemTray {
     }
 
     /// Update the tray icon based on connection status
-    pub fn update_icon(&mut self, connected: bool) -> Result<(), SystemTrayError> {
+    pub fn update_icon(&mut self, connection: ConnectionStatus) -> Result<(), SystemTrayError> {
+        if self.connection == connection {
+            // No change needed
+            return Ok(());
+        }
+
+        self.connection = connection;
+
         if !self.initialized {
             log::debug!("System tray not initialized, skipping icon update");
             return Ok(());
         }
 
-        if self.is_connected == connected {
-            // No change needed
+        // The next animation frame picks up the new base icon
+        if self.is_animating() {
             return Ok(());
         }
 
-        self.is_connected = connected;
+        log::debug!("Updating tray icon for {:?}", connection);
+        self.restore_static_icon()
+    }
+
+    /// Whether the icon is currently animating
+    pub fn is_animating(&self) -> bool {
+        self.activity != TrayActivity::Idle || self.critical_alert
+    }
 
-        // Get the icon path and load new icon
-        let icon_path = self.get_icon_path();
-        log::debug!("Updating tray icon to: {}", icon_path);
+    /// Whether the critical battery flash is active
+    pub fn is_critical_alert(&self) -> bool {
+        self.critical_alert
+    }
 
-        let icon = self.load_icon(&icon_path)?;
+    /// Start or stop flashing the icon for a critical battery level
+    pub fn set_critical_alert(&mut self, active: bool) -> Result<(), SystemTrayError> {
+        if self.critical_alert == active {
+            return Ok(());
+        }
 
+        log::debug!("Tray critical alert: {}", active);
+        self.critical_alert = active;
+        self.animation_frame = 0;
+
+        if !self.initialized {
+            return Ok(());
+        }
+
+        if self.is_animating() {
+            self.advance_animation()
+        } else {
+            self.restore_static_icon()
+        }
+    }
+
+    /// Show the static icon for the current style, connection status and theme
+    fn restore_static_icon(&mut self) -> Result<(), SystemTrayError> {
+        let icon = to_tray_icon(self.base_icon()?)?;
         if let Some(ref mut tray) = self.tray {
-            tray.set_icon(Some(icon)).map_err(|e| {
-                SystemTrayError::SetIcon(format!("Failed to set icon '{}': {}", icon_path, e))
-            })?;
+            tray.set_icon(Some(icon))
+                .map_err(|e| SystemTrayError::SetIcon(format!("Failed to set icon: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    /// Start or stop the activity animation
+    pub fn set_activity(&mut self, activity: TrayActivity) -> Result<(), SystemTrayError> {
+        if self.activity == activity {
+            return Ok(());
+        }
+
+        log::debug!("Tray activity: {:?} -> {:?}", self.activity, activity);
+        self.activity = activity;
+        self.animation_frame = 0;
+
+        if !self.initialized {
+            return Ok(());
+        }
+
+        if self.is_animating() {
+            self.advance_animation()
+        } else {
+            self.restore_static_icon()
+        }
+    }
+
+    /// Show the next animation frame (call every `TRAY_ANIMATION_INTERVAL`)
+    pub fn advance_animation(&mut self) -> Result<(), SystemTrayError> {
+        if !self.initialized || !self.is_animating() {
+            return Ok(());
+        }
+
+        let base = self.base_icon()?;
+        let mut frame = render_activity_frame(&base, self.activity, self.animation_frame);
+        if self.critical_alert {
+            frame = render_alert_frame(&frame, self.animation_frame);
+        }
+        self.animation_frame = (self.animation_frame + 1) % TRAY_ANIMATION_FRAMES;
+
+        let icon = to_tray_icon(frame)?;
+        if let Some(ref mut tray) = self.tray {
+            tray.set_icon(Some(icon))
+                .map_err(|e| SystemTrayError::SetIcon(format!("Failed to set frame: {}", e)))?;
         }
 
         Ok(())
     }
 
     /// Update tooltip with battery information
+    ///
+    /// Takes the batteries as (label, level, charging), see [`battery_tooltip`]. The
+    /// tooltip is only replaced when its text changes, so this can be called on every
+    /// scan; a charging flip changes the text and shows straight away. With more than one
+    /// level known, the `headline` level leads on a line of its own. A known device's
+    /// nickname goes in front of the levels. In tray-only mode the first menu item lists
+    /// the levels as well.
     pub fn update_tooltip_with_battery(
         &mut self,
-        left: Option<u8>,
-        right: Option<u8>,
-        case: Option<u8>,
+        device_name: Option<&str>,
+        headline: Option<Headline>,
+        batteries: &[(&str, Option<u8>, bool)],
     ) -> Result<(), SystemTrayError> {
+        let known = batteries
+            .iter()
+            .filter(|(_, level, _)| level.is_some())
+            .count();
+        let levels = match headline {
+            Some(headline) if known > 1 => format!("{}\n{}", headline, battery_tooltip(batteries)),
+            _ => battery_tooltip(batteries),
+        };
+        let mut tooltip = match device_name {
+            Some(name) if known > 0 => format!("{}: {}", name, levels),
+            _ => levels,
+        };
+        if self.connection == ConnectionStatus::Nearby {
+            tooltip.push_str("\nNearby, not connected");
+        }
+
+        if headline != self.headline {
+            self.headline = headline;
+            if self.config.ui.tray_icon_style == TrayIconStyle::Percentage
+                && self.initialized
+                && !self.is_animating()
+            {
+                self.restore_static_icon()?;
+            }
+        }
+
+        if tooltip == self.tooltip {
+            return Ok(());
+        }
+        self.tooltip = tooltip;
         if !self.initialized {
             return Ok(());
         }
 
-        let tooltip = match (left, right, case) {
-            (Some(l), Some(r), Some(c)) => format!("RustPods - L:{}% R:{}% C:{}%", l, r, c),
-            (Some(l), Some(r), None) => format!("RustPods - L:{}% R:{}%", l, r),
-            _ => "RustPods - AirPods Battery Monitor".to_string(),
-        };
+        if self.config.ui.tray_only {
+            if let Some(ref show_hide_item) = self.show_hide_item {
+                let levels = battery_tooltip(batteries);
+                show_hide_item.set_text(if levels == DEFAULT_TOOLTIP {
+                    TRAY_ONLY_LEVELS_LABEL
+                } else {
+                    levels.as_str()
+                });
+            }
+        }
 
         if let Some(ref mut tray) = self.tray {
-            tray.set_tooltip(Some(tooltip.clone()))
+            tray.set_tooltip(Some(self.tooltip.clone()))
                 .map_err(|e| SystemTrayError::Tooltip(format!("Failed to set tooltip: {}", e)))?;
         }
 
-        log::debug!("Updated tray tooltip: {}", tooltip);
+        log::debug!("Updated tray tooltip: {}", self.tooltip);
         Ok(())
     }
 
@@ -458,9 +871,21 @@ impl SystemTray {
     }
 
     /// Update configuration
+    ///
+    /// A new icon style, icon folder or theme shows straight away.
     pub fn update_config(&mut self, config: AppConfig) -> Result<(), SystemTrayError> {
+        let icons_changed = config.ui.tray_icon_style != self.config.ui.tray_icon_style
+            || config.ui.tray_icon_folder != self.config.ui.tray_icon_folder;
         self.config = config;
         self.theme_mode = ThemeMode::from(self.config.ui.theme.clone());
+
+        if icons_changed {
+            self.custom_icons = load_custom_icons(&self.config);
+            self.static_icon = None;
+        }
+        if self.initialized && !self.is_animating() {
+            self.restore_static_icon()?;
+        }
         Ok(())
     }
 
@@ -471,6 +896,8 @@ impl SystemTray {
         }
         self.menu = None;
         self.show_hide_item = None;
+        self.open_window_item = None;
+        self.restart_item = None;
         self.exit_item = None;
         self.menu_receiver = None;
         self.tray_receiver = None;
@@ -479,6 +906,27 @@ impl SystemTray {
     }
 }
 
+/// The user's icon set, when the custom style is picked and the set loads
+fn load_custom_icons(config: &AppConfig) -> Option<IconSet> {
+    if config.ui.tray_icon_style != TrayIconStyle::Custom {
+        return None;
+    }
+    let Some(folder) = &config.ui.tray_icon_folder else {
+        log::warn!("Custom tray icons picked without a folder, using the filled icons");
+        return None;
+    };
+    match crate::assets::tray::load_icon_set(folder) {
+        Ok(icons) => Some(icons),
+        Err(e) => {
+            log::warn!(
+                "Can't use the custom tray icons, using the filled icons: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
 impl Drop for SystemTray {
     fn drop(&mut self) {
         if let Err(e) = self.cleanup() {
@@ -505,4 +953,138 @@ mod tests {
         // Should not panic
         assert!(!tray.initialized);
     }
+
+    #[test]
+    fn test_battery_tooltip() {
+        assert_eq!(
+            battery_tooltip(&[
+                ("L", Some(80), true),
+                ("R", Some(75), false),
+                ("Case", Some(60), false),
+            ]),
+            "L 80% \u{26a1}  R 75%  Case 60%"
+        );
+        assert_eq!(
+            battery_tooltip(&[("L", Some(80), false), ("Case", None, true)]),
+            "L 80%"
+        );
+        assert_eq!(battery_tooltip(&[("L", None, false)]), DEFAULT_TOOLTIP);
+
+        let mut tray = SystemTray::new(AppConfig::default()).unwrap();
+        tray.update_tooltip_with_battery(None, None, &[("L", Some(80), false)])
+            .unwrap();
+        assert_eq!(tray.tooltip, "L 80%");
+        tray.update_tooltip_with_battery(Some("Kitchen Pods"), None, &[("L", Some(80), false)])
+            .unwrap();
+        assert_eq!(tray.tooltip, "Kitchen Pods: L 80%");
+
+        // The headline leads once there is more than one level to pick from
+        let headline = Headline {
+            metric: crate::config::TrayMetric::Average,
+            level: 78,
+            low: false,
+            charging: false,
+        };
+        tray.update_tooltip_with_battery(
+            Some("Kitchen Pods"),
+            Some(headline),
+            &[("L", Some(80), false), ("R", Some(75), false)],
+        )
+        .unwrap();
+        assert_eq!(tray.tooltip, "Kitchen Pods: Average 78%\nL 80%  R 75%");
+        tray.update_tooltip_with_battery(None, Some(headline), &[("L", Some(80), false)])
+            .unwrap();
+        assert_eq!(tray.tooltip, "L 80%");
+
+        // Batteries of AirPods in range but not connected are marked as such
+        tray.update_icon(ConnectionStatus::Nearby).unwrap();
+        tray.update_tooltip_with_battery(None, None, &[("L", Some(80), false)])
+            .unwrap();
+        assert_eq!(tray.tooltip, "L 80%\nNearby, not connected");
+    }
+
+    #[test]
+    fn test_activity_frames() {
+        let base = image::RgbaImage::from_pixel(32, 32, image::Rgba([255, 255, 255, 255]));
+
+        assert_eq!(render_activity_frame(&base, TrayActivity::Idle, 3), base);
+
+        // The scanning arc rotates between frames
+        let first = render_activity_frame(&base, TrayActivity::Scanning, 0);
+        let later = render_activity_frame(&base, TrayActivity::Scanning, 3);
+        assert_ne!(first, base);
+        assert_ne!(first, later);
+
+        // Restarting pulses the rest of the icon
+        let pulse = render_activity_frame(&base, TrayActivity::Restarting, 3);
+        assert!(pulse.get_pixel(0, 0)[3] < 255);
+    }
+
+    #[test]
+    fn test_alert_frames_alternate() {
+        let base = image::RgbaImage::from_pixel(32, 32, image::Rgba([255, 255, 255, 255]));
+
+        assert_ne!(render_alert_frame(&base, 0), base);
+        assert_eq!(render_alert_frame(&base, TRAY_ANIMATION_FRAMES / 2), base);
+    }
+
+    #[test]
+    fn test_tray_icon_styles() {
+        let filled = render_tray_icon(TrayIconStyle::Filled, true, true, None, None).unwrap();
+        assert_eq!(filled.dimensions(), (TRAY_ICON_SIZE, TRAY_ICON_SIZE));
+
+        // The outline keeps the edge and clears the inside
+        let solid = image::RgbaImage::from_pixel(32, 32, image::Rgba([255, 255, 255, 255]));
+        let outline = render_outline(&solid);
+        assert_eq!(outline.get_pixel(0, 0)[3], 255);
+        assert_eq!(outline.get_pixel(16, 16)[3], 0);
+
+        // Percentage falls back to the filled icon until a level is known
+        assert_eq!(
+            render_tray_icon(TrayIconStyle::Percentage, true, true, None, None).unwrap(),
+            filled
+        );
+        let headline = Headline {
+            metric: crate::config::TrayMetric::Lowest,
+            level: 100,
+            low: false,
+            charging: false,
+        };
+        let percentage =
+            render_tray_icon(TrayIconStyle::Percentage, true, true, Some(headline), None).unwrap();
+        assert_eq!(percentage, render_percentage_icon(100, [0xcd, 0xd6, 0xf4]));
+        assert_ne!(
+            render_percentage_icon(100, [255, 255, 255]),
+            render_percentage_icon(99, [255, 255, 255])
+        );
+
+        // Custom without a usable set also shows the filled icon
+        assert_eq!(
+            render_tray_icon(TrayIconStyle::Custom, true, true, None, None).unwrap(),
+            filled
+        );
+        let custom = IconSet {
+            connected: image::RgbaImage::from_pixel(64, 64, image::Rgba([1, 2, 3, 255])),
+            disconnected: image::RgbaImage::new(16, 16),
+        };
+        let icon =
+            render_tray_icon(TrayIconStyle::Custom, true, true, None, Some(&custom)).unwrap();
+        assert_eq!(icon.dimensions(), (TRAY_ICON_SIZE, TRAY_ICON_SIZE));
+        assert_eq!(icon.get_pixel(5, 5), &image::Rgba([1, 2, 3, 255]));
+    }
+
+    #[test]
+    fn test_set_activity_without_tray() {
+        let mut tray = SystemTray::new(AppConfig::default()).unwrap();
+        assert!(!tray.is_animating());
+        tray.set_activity(TrayActivity::Scanning).unwrap();
+        assert!(tray.is_animating());
+        tray.set_activity(TrayActivity::Idle).unwrap();
+        assert!(!tray.is_animating());
+
+        tray.set_critical_alert(true).unwrap();
+        assert!(tray.is_animating());
+        tray.set_critical_alert(false).unwrap();
+        assert!(!tray.is_animating());
+    }
 }
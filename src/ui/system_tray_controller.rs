@@ -1,193 +1,291 @@
-//! Controller for system tray that integrates with app state
-//! This module connects the system tray with the state management system
+//! System tray running on its own thread
+//!
+//! The tray used to live in `AppState`, which only looked at its menu and click events
+//! when iced delivered the next message to `update`. [`SystemTrayController`] starts a
+//! thread that owns the [`SystemTray`]: it dispatches the thread's window messages, turns
+//! menu and icon events into `Message`s for the UI and animates the icon. `AppState`
+//! changes the tray by sending [`TrayCommand`]s over a channel.
 
-use std::sync::mpsc;
-use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Duration;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
-use tokio::task::JoinHandle;
-use tokio::sync::mpsc as tokio_mpsc;
+use tokio::sync::mpsc::UnboundedSender;
 
-use crate::ui::SystemTray;
-use crate::ui::state_manager::StateManager;
-use crate::ui::Message;
 use crate::config::AppConfig;
+use crate::ui::headline::Headline;
+use crate::ui::message::Message;
+use crate::ui::state::ConnectionStatus;
+use crate::ui::system_tray::{SystemTray, SystemTrayError, TrayActivity, TRAY_ANIMATION_INTERVAL};
 
-/// Controls system tray updates based on application state
+/// How long the tray thread waits for a command before checking for tray events again
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// A change to the tray, sent to the tray thread
+#[derive(Debug)]
+pub enum TrayCommand {
+    /// Send menu and click events to a new UI channel
+    SetUiSender(UnboundedSender<Message>),
+    /// Apply changed settings, such as the theme
+    UpdateConfig(Box<AppConfig>),
+    /// Show the icon for a connection status
+    Connection(ConnectionStatus),
+    /// Show battery levels in the tooltip
+    Battery {
+        /// Nickname of the device, if it has one
+        device_name: Option<String>,
+        /// Level to lead with, see [`Headline`]
+        headline: Option<Headline>,
+        /// Batteries as (label, level, charging)
+        batteries: Vec<(String, Option<u8>, bool)>,
+    },
+    /// Show background scanner work on the icon
+    Activity(TrayActivity),
+    /// Start or stop the critical battery flash
+    CriticalAlert(bool),
+    /// Remove the tray icon and stop the thread
+    Shutdown,
+}
+
+/// Handle to the thread that owns the system tray
+#[derive(Debug)]
 pub struct SystemTrayController {
-    /// System tray instance
-    system_tray: Arc<Mutex<SystemTray>>,
-    /// State manager reference
-    state_manager: Arc<StateManager>,
-    /// Update thread handle
-    update_thread: Option<thread::JoinHandle<()>>,
-    /// Whether the controller is running
-    running: Arc<Mutex<bool>>,
-    /// Tokio task handle for battery status notifications
-    notification_task: Option<JoinHandle<()>>,
+    commands: Sender<TrayCommand>,
+    thread: Option<JoinHandle<()>>,
 }
 
 impl SystemTrayController {
-    /// Create a new system tray controller
-    pub fn new(
-        tx: mpsc::Sender<Message>, 
-        config: AppConfig, 
-        state_manager: Arc<StateManager>
-    ) -> Result<Self, String> {
-        // Create system tray
-        let system_tray = match SystemTray::new(tx, config.clone()) {
-            Ok(tray) => tray,
-            Err(e) => return Err(format!("Failed to create system tray: {}", e)),
-        };
-        
-        // Connect state manager
-        let system_tray_ref = Arc::new(Mutex::new(system_tray));
-        if let Ok(mut guard) = system_tray_ref.lock() {
-            if let Err(e) = guard.connect_state_manager(Arc::clone(&state_manager)) {
-                return Err(format!("Failed to connect state manager: {}", e));
+    /// Create the tray on a new thread, returning once its icon is shown
+    ///
+    /// Menu and click events are sent to `ui_sender`.
+    pub fn start(
+        config: AppConfig,
+        ui_sender: UnboundedSender<Message>,
+    ) -> Result<Self, SystemTrayError> {
+        let (commands, receiver) = mpsc::channel();
+        let (ready_sender, ready) = mpsc::sync_channel(1);
+
+        let thread = std::thread::Builder::new()
+            .name("system-tray".to_string())
+            .spawn(move || {
+                // The tray icon's window belongs to the thread that creates it
+                let created = SystemTray::new(config).and_then(|mut tray| {
+                    tray.set_ui_sender(ui_sender);
+                    tray.initialize().map(|_| tray)
+                });
+                match created {
+                    Ok(mut tray) => {
+                        let _ = ready_sender.send(Ok(()));
+                        run(&mut tray, &receiver);
+                    }
+                    Err(e) => {
+                        let _ = ready_sender.send(Err(e));
+                    }
+                }
+            })
+            .map_err(|e| {
+                SystemTrayError::Creation(format!("Failed to start tray thread: {}", e))
+            })?;
+
+        match ready.recv() {
+            Ok(Ok(())) => Ok(Self {
+                commands,
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
             }
-        } else {
-            return Err("Failed to lock system tray".to_string());
+            Err(_) => Err(SystemTrayError::Creation(
+                "Tray thread stopped during startup".to_string(),
+            )),
         }
-        
-        Ok(Self {
-            system_tray: system_tray_ref,
-            state_manager,
-            update_thread: None,
-            running: Arc::new(Mutex::new(false)),
-            notification_task: None,
-        })
     }
-    
-    /// Start the controller
-    pub fn start(&mut self) -> Result<(), String> {
-        // Mark as running
-        if let Ok(mut running) = self.running.lock() {
-            *running = true;
-        } else {
-            return Err("Failed to lock running state".to_string());
+
+    /// Hand a command to the tray thread; returns false if it has stopped
+    fn send(&self, command: TrayCommand) -> bool {
+        let sent = self.commands.send(command).is_ok();
+        if !sent {
+            log::warn!("System tray thread has stopped, tray update dropped");
         }
-        
-        // Create a clone for the thread
-        let system_tray = Arc::clone(&self.system_tray);
-        let running = Arc::clone(&self.running);
-        
-        // Start update thread
-        let handle = thread::spawn(move || {
-            // Update every second
-            loop {
-                // Check if we should stop
-                let should_run = match running.lock() {
-                    Ok(guard) => *guard,
-                    Err(_) => false, // Stop if we can't lock
-                };
-                
-                if !should_run {
-                    break;
-                }
-                
-                // Update the system tray
-                if let Ok(mut guard) = system_tray.lock() {
-                    if let Err(e) = guard.process_state_update() {
-                        log::error!("Error updating system tray: {}", e);
-                    }
-                }
-                
-                // Sleep for a bit to avoid excessive updates
-                thread::sleep(Duration::from_millis(500));
-            }
+        sent
+    }
+
+    /// Send menu and click events to a new UI channel; returns false if the tray thread
+    /// has stopped and dropped the sender
+    pub fn set_ui_sender(&self, sender: UnboundedSender<Message>) -> bool {
+        self.send(TrayCommand::SetUiSender(sender))
+    }
+
+    /// Apply changed settings
+    pub fn update_config(&self, config: AppConfig) {
+        self.send(TrayCommand::UpdateConfig(Box::new(config)));
+    }
+
+    /// Show the icon for a connection status
+    pub fn update_icon(&self, connection: ConnectionStatus) {
+        self.send(TrayCommand::Connection(connection));
+    }
+
+    /// Show battery levels in the tooltip, see [`SystemTray::update_tooltip_with_battery`]
+    pub fn update_tooltip_with_battery(
+        &self,
+        device_name: Option<&str>,
+        headline: Option<Headline>,
+        batteries: &[(&str, Option<u8>, bool)],
+    ) {
+        self.send(TrayCommand::Battery {
+            device_name: device_name.map(str::to_string),
+            headline,
+            batteries: batteries
+                .iter()
+                .map(|(label, level, charging)| (label.to_string(), *level, *charging))
+                .collect(),
         });
-        
-        self.update_thread = Some(handle);
-        
-        Ok(())
     }
-    
-    /// Start the notification task for battery status updates
-    pub fn start_notification_task(
-        &mut self, 
-        ui_sender: tokio_mpsc::UnboundedSender<Message>
-    ) -> Result<(), String> {
-        // Get clones of necessary data
-        let state_manager = Arc::clone(&self.state_manager);
-        let system_tray = Arc::clone(&self.system_tray);
-        
-        // Create task
-        let task = tokio::spawn(async move {
-            // Poll every 5 seconds
-            let mut interval = tokio::time::interval(Duration::from_secs(5));
-            
-            loop {
-                interval.tick().await;
-                
-                // Get current battery status
-                let device_state = state_manager.get_device_state();
-                if let Some(battery_status) = device_state.battery_status {
-                    // Only process if we have a connected device
-                    if device_state.selected_device.is_some() {
-                        // Create a clone for the notification
-                        let status_clone = battery_status.clone();
-                        
-                        // Process in system tray
-                        if let Ok(mut guard) = system_tray.lock() {
-                            if let Err(e) = guard.handle_battery_update(status_clone) {
-                                log::error!("Error updating system tray battery: {}", e);
-                            }
-                        }
-                        
-                        // Send update to UI as well
-                        let _ = ui_sender.send(Message::BatteryStatusUpdated(battery_status));
-                    }
-                }
+
+    /// Show background scanner work on the icon
+    pub fn set_activity(&self, activity: TrayActivity) {
+        self.send(TrayCommand::Activity(activity));
+    }
+
+    /// Start or stop the critical battery flash
+    pub fn set_critical_alert(&self, active: bool) {
+        self.send(TrayCommand::CriticalAlert(active));
+    }
+}
+
+#[cfg(test)]
+impl SystemTrayController {
+    /// A handle without a tray thread, returning the commands sent to it
+    pub(crate) fn detached() -> (Self, Receiver<TrayCommand>) {
+        let (commands, receiver) = mpsc::channel();
+        (
+            Self {
+                commands,
+                thread: None,
+            },
+            receiver,
+        )
+    }
+}
+
+impl Drop for SystemTrayController {
+    fn drop(&mut self) {
+        let _ = self.commands.send(TrayCommand::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            if thread.join().is_err() {
+                log::error!("System tray thread panicked");
             }
-        });
-        
-        self.notification_task = Some(task);
-        
-        Ok(())
+        }
     }
-    
-    /// Stop the controller
-    pub fn stop(&mut self) -> Result<(), String> {
-        // Mark as not running
-        if let Ok(mut running) = self.running.lock() {
-            *running = false;
-        } else {
-            return Err("Failed to lock running state".to_string());
+}
+
+/// Serve tray events and commands until shut down
+fn run(tray: &mut SystemTray, commands: &Receiver<TrayCommand>) {
+    let mut next_frame = Instant::now();
+    loop {
+        platform::pump_messages();
+        if let Err(e) = tray.process_events() {
+            log::error!("Failed to process system tray events: {}", e);
         }
-        
-        // Wait for thread to finish
-        if let Some(handle) = self.update_thread.take() {
-            if let Err(e) = handle.join() {
-                return Err(format!("Failed to join update thread: {:?}", e));
+
+        match commands.recv_timeout(EVENT_POLL_INTERVAL) {
+            Ok(TrayCommand::Shutdown) | Err(RecvTimeoutError::Disconnected) => break,
+            Ok(command) => {
+                if let Err(e) = apply(tray, command) {
+                    log::warn!("Failed to update system tray: {}", e);
+                }
             }
+            Err(RecvTimeoutError::Timeout) => {}
         }
-        
-        // Cancel notification task
-        if let Some(task) = self.notification_task.take() {
-            task.abort();
+
+        if tray.is_animating() && Instant::now() >= next_frame {
+            if let Err(e) = tray.advance_animation() {
+                log::warn!("Failed to animate tray icon: {}", e);
+            }
+            next_frame = Instant::now() + TRAY_ANIMATION_INTERVAL;
         }
-        
-        Ok(())
     }
-    
-    /// Update the system tray with new config
-    pub fn update_config(&mut self, config: AppConfig) -> Result<(), String> {
-        if let Ok(mut guard) = self.system_tray.lock() {
-            guard.update_config(config)
-                .map_err(|e| format!("Failed to update system tray config: {}", e))
-        } else {
-            Err("Failed to lock system tray".to_string())
+
+    if let Err(e) = tray.cleanup() {
+        log::error!("Failed to cleanup system tray: {}", e);
+    }
+    log::debug!("System tray thread stopped");
+}
+
+fn apply(tray: &mut SystemTray, command: TrayCommand) -> Result<(), SystemTrayError> {
+    match command {
+        TrayCommand::SetUiSender(sender) => {
+            tray.set_ui_sender(sender);
+            Ok(())
+        }
+        TrayCommand::UpdateConfig(config) => tray.update_config(*config),
+        TrayCommand::Connection(connection) => tray.update_icon(connection),
+        TrayCommand::Battery {
+            device_name,
+            headline,
+            batteries,
+        } => {
+            let batteries: Vec<(&str, Option<u8>, bool)> = batteries
+                .iter()
+                .map(|(label, level, charging)| (label.as_str(), *level, *charging))
+                .collect();
+            tray.update_tooltip_with_battery(device_name.as_deref(), headline, &batteries)
         }
+        TrayCommand::Activity(activity) => tray.set_activity(activity),
+        TrayCommand::CriticalAlert(active) => tray.set_critical_alert(active),
+        TrayCommand::Shutdown => Ok(()),
     }
 }
 
-impl Drop for SystemTrayController {
-    fn drop(&mut self) {
-        // Ensure we stop the thread
-        if let Err(e) = self.stop() {
-            log::error!("Error stopping system tray controller: {}", e);
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE,
+    };
+
+    /// Dispatch the window messages queued for this thread, which carry the tray's events
+    pub fn pump_messages() {
+        let mut msg = MSG::default();
+        unsafe {
+            while PeekMessageW(&mut msg, HWND::default(), 0, 0, PM_REMOVE).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
         }
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(not(target_os = "windows"))]
+mod platform {
+    /// Tray events arrive without a window message loop on this platform
+    pub fn pump_messages() {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_apply_without_tray_icon() {
+        let mut tray = SystemTray::new(AppConfig::default()).unwrap();
+
+        apply(&mut tray, TrayCommand::Activity(TrayActivity::Scanning)).unwrap();
+        assert!(tray.is_animating());
+        apply(&mut tray, TrayCommand::Activity(TrayActivity::Idle)).unwrap();
+        apply(&mut tray, TrayCommand::CriticalAlert(true)).unwrap();
+        assert!(tray.is_critical_alert());
+
+        apply(
+            &mut tray,
+            TrayCommand::Battery {
+                device_name: Some("Kitchen Pods".to_string()),
+                headline: None,
+                batteries: vec![("L".to_string(), Some(80), false)],
+            },
+        )
+        .unwrap();
+    }
+}
@@ -192,16 +192,34 @@ pub enum Theme {
     CatppuccinMocha,
 }
 
+/// Application background style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApplicationStyle {
+    /// Opaque theme background
+    #[default]
+    Solid,
+    /// Transparent background so a Mica/Acrylic backdrop shows through
+    Translucent,
+}
+
 impl application::StyleSheet for Theme {
-    type Style = ();
+    type Style = ApplicationStyle;
 
-    fn appearance(&self, _style: &Self::Style) -> application::Appearance {
-        match self {
-            Theme::Light => application::Appearance {
+    fn appearance(&self, style: &Self::Style) -> application::Appearance {
+        match (self, style) {
+            (Theme::Light, ApplicationStyle::Translucent) => application::Appearance {
+                background_color: Color::TRANSPARENT,
+                text_color: LIGHT_TEXT,
+            },
+            (_, ApplicationStyle::Translucent) => application::Appearance {
+                background_color: Color::TRANSPARENT,
+                text_color: TEXT,
+            },
+            (Theme::Light, _) => application::Appearance {
                 background_color: LIGHT_BG,
                 text_color: LIGHT_TEXT,
             },
-            Theme::Dark | Theme::System | Theme::CatppuccinMocha => application::Appearance {
+            (_, _) => application::Appearance {
                 background_color: BASE,
                 text_color: TEXT,
             },
@@ -465,6 +483,51 @@ impl text_input::StyleSheet for Theme {
 
 pub static FONT_FAMILY: &str = "SpaceMono Nerd Font";
 
+/// Base text size of the typography scale, for text without an explicit size
+pub const BASE_TEXT_SIZE: f32 = 16.0;
+
+/// User's font size multiplier, stored as `f32` bits
+static FONT_SCALE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0x3f80_0000); // 1.0
+
+/// Set the multiplier applied to every text size
+pub fn set_font_scale(scale: f32) {
+    FONT_SCALE.store(scale.to_bits(), std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Current font size multiplier
+pub fn font_scale() -> f32 {
+    f32::from_bits(FONT_SCALE.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+/// A text size from the typography scale, adjusted by the user's font size setting
+pub fn scaled(size: f32) -> f32 {
+    size * font_scale()
+}
+
+/// Physical pixels per logical pixel of the main window, stored as `f32` bits
+static DISPLAY_SCALE: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0x3f80_0000); // 1.0
+
+/// Record the window's scale factor, e.g. 1.5 at 144 DPI
+pub fn set_display_scale(scale: f32) {
+    if scale.is_finite() && scale > 0.0 {
+        DISPLAY_SCALE.store(scale.to_bits(), std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// Current scale factor of the main window
+pub fn display_scale() -> f32 {
+    f32::from_bits(DISPLAY_SCALE.load(std::sync::atomic::Ordering::Relaxed))
+}
+
+/// `size` rounded so it covers a whole number of physical pixels
+///
+/// Vector graphics drawn at a fractional pixel size are resampled, which blurs them
+/// at scale factors such as 150%.
+pub fn pixel_snapped(size: f32) -> f32 {
+    let scale = display_scale();
+    (size * scale).round().max(1.0) / scale
+}
+
 impl text::StyleSheet for Theme {
     type Style = iced::Color;
 
@@ -800,6 +863,31 @@ pub fn settings_icon_color(theme: &Theme) -> Color {
     }
 }
 
+/// Color a known device's name is drawn in
+pub fn device_color(color: crate::config::DeviceColor) -> Color {
+    use crate::config::DeviceColor;
+    match color {
+        DeviceColor::Default => TEXT,
+        DeviceColor::Red => RED,
+        DeviceColor::Orange => PEACH,
+        DeviceColor::Yellow => YELLOW,
+        DeviceColor::Green => GREEN,
+        DeviceColor::Blue => BLUE,
+        DeviceColor::Purple => MAUVE,
+        DeviceColor::Pink => PINK,
+    }
+}
+
+/// Color a connection status is drawn in
+pub fn connection_color(status: crate::ui::state::ConnectionStatus) -> Color {
+    use crate::ui::state::ConnectionStatus;
+    match status {
+        ConnectionStatus::Connected => GREEN,
+        ConnectionStatus::Nearby => YELLOW,
+        ConnectionStatus::NotFound => OVERLAY1,
+    }
+}
+
 /// Custom container style for the graphical AirPods popup
 /// Creates a styled container with rounded corners and border matching the Catppuccin theme
 fn graphical_popup_appearance(_theme: &iced::Theme) -> container::Appearance {
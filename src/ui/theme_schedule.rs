@@ -0,0 +1,153 @@
+//! Scheduled switching between the light and dark palettes
+//!
+//! Resolves which palette should be active for a point in time, either from fixed
+//! local hours or from sunrise and sunset at the configured location. The UI re-checks
+//! the schedule on a timer and swaps the active theme when the period changes.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
+
+use crate::config::{ThemeScheduleConfig, ThemeScheduleMode};
+use crate::ui::theme::Theme;
+
+/// How often the schedule is re-checked
+pub const THEME_SCHEDULE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Palette used during the day
+pub const DAY_THEME: Theme = Theme::Light;
+
+/// Palette used at night
+pub const NIGHT_THEME: Theme = Theme::CatppuccinMocha;
+
+/// Sun altitude at sunrise/sunset in degrees, accounting for refraction and the sun's radius
+const SUNRISE_ZENITH_DEGREES: f64 = 90.833;
+
+/// The theme the schedule selects at `now`, or `None` when scheduling is off
+pub fn scheduled_theme(schedule: &ThemeScheduleConfig, now: DateTime<Local>) -> Option<Theme> {
+    let is_day = match schedule.mode {
+        ThemeScheduleMode::Off => return None,
+        ThemeScheduleMode::FixedTimes => is_day_at_hour(schedule, now.hour() as u8),
+        ThemeScheduleMode::SunriseSunset => match (schedule.latitude, schedule.longitude) {
+            (Some(latitude), Some(longitude)) => {
+                match sun_times(now.date_naive(), latitude, longitude) {
+                    Some((sunrise, sunset)) => {
+                        let now = now.with_timezone(&Utc);
+                        now >= sunrise && now < sunset
+                    }
+                    None => is_day_at_hour(schedule, now.hour() as u8),
+                }
+            }
+            _ => is_day_at_hour(schedule, now.hour() as u8),
+        },
+    };
+
+    Some(if is_day { DAY_THEME } else { NIGHT_THEME })
+}
+
+/// Whether the given local hour falls in the light period of the fixed schedule
+pub fn is_day_at_hour(schedule: &ThemeScheduleConfig, hour: u8) -> bool {
+    let (light, dark) = (schedule.light_hour, schedule.dark_hour);
+    if light == dark {
+        return true;
+    }
+
+    if light < dark {
+        hour >= light && hour < dark
+    } else {
+        hour >= light || hour < dark
+    }
+}
+
+/// Sunrise and sunset (UTC) on `date` at the given location
+///
+/// Uses the NOAA solar position approximation, which is accurate to a few minutes.
+/// Returns `None` during polar day or night, when the sun doesn't rise or set.
+pub fn sun_times(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    use chrono::Datelike;
+    use std::f64::consts::PI;
+
+    // Fractional year in radians, evaluated at solar noon
+    let gamma = 2.0 * PI / 365.0 * (date.ordinal() as f64 - 1.0);
+
+    // Equation of time in minutes and solar declination in radians
+    let eqtime = 229.18
+        * (0.000075 + 0.001868 * gamma.cos()
+            - 0.032077 * gamma.sin()
+            - 0.014615 * (2.0 * gamma).cos()
+            - 0.040849 * (2.0 * gamma).sin());
+    let declination = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+        - 0.006758 * (2.0 * gamma).cos()
+        + 0.000907 * (2.0 * gamma).sin()
+        - 0.002697 * (3.0 * gamma).cos()
+        + 0.00148 * (3.0 * gamma).sin();
+
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = SUNRISE_ZENITH_DEGREES.to_radians().cos()
+        / (latitude.cos() * declination.cos())
+        - latitude.tan() * declination.tan();
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let hour_angle = cos_hour_angle.acos().to_degrees();
+
+    let midnight = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?);
+    let at_minutes = |minutes: f64| midnight + Duration::seconds((minutes * 60.0).round() as i64);
+    let sunrise = at_minutes(720.0 - 4.0 * (longitude + hour_angle) - eqtime);
+    let sunset = at_minutes(720.0 - 4.0 * (longitude - hour_angle) - eqtime);
+    Some((sunrise, sunset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(mode: ThemeScheduleMode) -> ThemeScheduleConfig {
+        ThemeScheduleConfig {
+            mode,
+            ..ThemeScheduleConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_fixed_hours() {
+        let mut fixed = schedule(ThemeScheduleMode::FixedTimes);
+        assert!(is_day_at_hour(&fixed, 7));
+        assert!(is_day_at_hour(&fixed, 18));
+        assert!(!is_day_at_hour(&fixed, 19));
+        assert!(!is_day_at_hour(&fixed, 3));
+
+        // A light period that wraps past midnight
+        fixed.light_hour = 20;
+        fixed.dark_hour = 4;
+        assert!(is_day_at_hour(&fixed, 23));
+        assert!(!is_day_at_hour(&fixed, 12));
+    }
+
+    #[test]
+    fn test_schedule_off() {
+        assert_eq!(
+            scheduled_theme(&schedule(ThemeScheduleMode::Off), Local::now()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sun_times_london_midsummer() {
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let (sunrise, sunset) = sun_times(date, 51.5074, -0.1278).unwrap();
+
+        // Published times are 03:43 and 20:21 UTC
+        let minutes = |time: DateTime<Utc>| time.hour() as i64 * 60 + time.minute() as i64;
+        assert!((minutes(sunrise) - (3 * 60 + 43)).abs() <= 5);
+        assert!((minutes(sunset) - (20 * 60 + 21)).abs() <= 5);
+    }
+
+    #[test]
+    fn test_sun_times_polar_night() {
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        assert!(sun_times(date, 78.22, 15.65).is_none());
+    }
+}
@@ -159,6 +159,402 @@ pub fn create_draggable<'a, Message>(
 pub const DEFAULT_WINDOW_WIDTH: u32 = 360;
 /// Default window height
 pub const DEFAULT_WINDOW_HEIGHT: u32 = 500;
+/// Smallest window width the user can resize to
+pub const MIN_WINDOW_WIDTH: u32 = 300;
+/// Smallest window height the user can resize to
+pub const MIN_WINDOW_HEIGHT: u32 = 300;
+/// Largest window width the user can resize to
+pub const MAX_WINDOW_WIDTH: u32 = 900;
+/// Largest window height the user can resize to
+pub const MAX_WINDOW_HEIGHT: u32 = 1000;
+
+/// Windows narrower than this stack the batteries when there's room
+pub const STACKED_LAYOUT_MAX_WIDTH: u32 = 340;
+/// Height needed to stack the batteries on top of each other
+pub const STACKED_LAYOUT_MIN_HEIGHT: u32 = 520;
+/// Windows shorter than this hide the audio status and advanced info
+pub const DETAILS_MIN_HEIGHT: u32 = 420;
+/// Windows shorter than this also hide the device name
+pub const DEVICE_NAME_MIN_HEIGHT: u32 = 360;
+
+/// Clamp a window size to the resize limits
+pub fn clamp_window_size(width: u32, height: u32) -> (u32, u32) {
+    (
+        width.clamp(MIN_WINDOW_WIDTH, MAX_WINDOW_WIDTH),
+        height.clamp(MIN_WINDOW_HEIGHT, MAX_WINDOW_HEIGHT),
+    )
+}
+
+/// Size to open the window at: the last size the user chose, or the default
+pub fn initial_window_size(app_config: &AppConfig) -> (u32, u32) {
+    match app_config.ui.window_size {
+        Some(size) => clamp_window_size(size.width, size.height),
+        None => (DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT),
+    }
+}
+
+/// How the left and right batteries are arranged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatteryArrangement {
+    /// Left and right next to each other
+    SideBySide,
+    /// Left above right, for tall narrow windows
+    Stacked,
+}
+
+/// Main window layout for a given window size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponsiveLayout {
+    /// How the batteries are arranged
+    pub arrangement: BatteryArrangement,
+    /// Whether the device name is shown above the batteries
+    pub show_device_name: bool,
+    /// Whether the audio status and advanced info sections are shown
+    pub show_details: bool,
+}
+
+impl ResponsiveLayout {
+    /// Pick the layout for a window of the given logical size
+    pub fn for_size(width: u32, height: u32) -> Self {
+        let arrangement = if width < STACKED_LAYOUT_MAX_WIDTH && height >= STACKED_LAYOUT_MIN_HEIGHT
+        {
+            BatteryArrangement::Stacked
+        } else {
+            BatteryArrangement::SideBySide
+        };
+        Self {
+            arrangement,
+            show_device_name: height >= DEVICE_NAME_MIN_HEIGHT,
+            show_details: height >= DETAILS_MIN_HEIGHT,
+        }
+    }
+}
+
+impl Default for ResponsiveLayout {
+    fn default() -> Self {
+        Self::for_size(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT)
+    }
+}
+
+/// Height of the custom title bar
+pub const TITLE_BAR_HEIGHT: f32 = 40.0;
+/// Horizontal padding inside the custom title bar
+pub const TITLE_BAR_PADDING: f32 = 12.0;
+/// Width and height of a caption button (health badge, settings, minimize, close)
+pub const CAPTION_BUTTON_SIZE: f32 = 31.0;
+/// Gap between caption buttons
+pub const CAPTION_BUTTON_SPACING: f32 = 6.0;
+/// Number of caption buttons on the right of the title bar
+///
+/// Counts the health badge, so its slot stays clickable rather than draggable
+/// whether or not it is shown.
+pub const CAPTION_BUTTON_COUNT: usize = 4;
+
+/// What a point in the window hits, as reported to the OS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleBarHit {
+    /// The draggable caption area of the title bar
+    Caption,
+    /// One of the caption buttons, handled by the UI
+    CaptionButton,
+    /// Window content
+    Client,
+}
+
+/// Width taken up by the caption buttons, including the title bar padding
+pub fn caption_buttons_width() -> f32 {
+    CAPTION_BUTTON_COUNT as f32 * CAPTION_BUTTON_SIZE
+        + (CAPTION_BUTTON_COUNT - 1) as f32 * CAPTION_BUTTON_SPACING
+        + TITLE_BAR_PADDING
+}
+
+/// Classify a point (in logical window coordinates) against the custom title bar
+pub fn title_bar_hit_test(position: Point, window_width: f32) -> TitleBarHit {
+    if position.y < 0.0 || position.y >= TITLE_BAR_HEIGHT {
+        TitleBarHit::Client
+    } else if position.x >= window_width - caption_buttons_width() {
+        TitleBarHit::CaptionButton
+    } else {
+        TitleBarHit::Caption
+    }
+}
+
+/// Thickness of the invisible resize border around the borderless window
+pub const RESIZE_BORDER_WIDTH: f32 = 5.0;
+
+/// Window edge or corner under a point, for resizing the borderless window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeEdge {
+    /// Left edge
+    Left,
+    /// Right edge
+    Right,
+    /// Top edge
+    Top,
+    /// Bottom edge
+    Bottom,
+    /// Top-left corner
+    TopLeft,
+    /// Top-right corner
+    TopRight,
+    /// Bottom-left corner
+    BottomLeft,
+    /// Bottom-right corner
+    BottomRight,
+}
+
+/// Find the resize edge under a point (in logical window coordinates), if any
+pub fn resize_edge_hit_test(
+    position: Point,
+    window_width: f32,
+    window_height: f32,
+) -> Option<ResizeEdge> {
+    let left = position.x < RESIZE_BORDER_WIDTH;
+    let right = position.x >= window_width - RESIZE_BORDER_WIDTH;
+    let top = position.y < RESIZE_BORDER_WIDTH;
+    let bottom = position.y >= window_height - RESIZE_BORDER_WIDTH;
+
+    match (left, right, top, bottom) {
+        (true, _, true, _) => Some(ResizeEdge::TopLeft),
+        (_, true, true, _) => Some(ResizeEdge::TopRight),
+        (true, _, _, true) => Some(ResizeEdge::BottomLeft),
+        (_, true, _, true) => Some(ResizeEdge::BottomRight),
+        (true, _, _, _) => Some(ResizeEdge::Left),
+        (_, true, _, _) => Some(ResizeEdge::Right),
+        (_, _, true, _) => Some(ResizeEdge::Top),
+        (_, _, _, true) => Some(ResizeEdge::Bottom),
+        _ => None,
+    }
+}
+
+/// Enable or disable native caption hit-testing for the custom title bar
+///
+/// Only the main view draws the title bar; other screens put their own controls there.
+pub fn set_native_caption_enabled(enabled: bool) {
+    NATIVE_CAPTION_ENABLED.store(enabled, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Whether the title bar is currently on screen
+static NATIVE_CAPTION_ENABLED: std::sync::atomic::AtomicBool =
+    std::sync::atomic::AtomicBool::new(true);
+
+/// Report the custom title bar to Windows as a real caption
+///
+/// The caption area answers `WM_NCHITTEST` with `HTCAPTION`, so dragging it gets
+/// native behavior: Aero Snap to screen edges, snap assist and the system menu.
+/// Caption buttons stay client area so the UI handles them. A thin border around the
+/// window answers with the matching resize edge, since the borderless window has no
+/// frame to grab. Only visible windows are hooked; returns how many windows were hooked.
+#[cfg(target_os = "windows")]
+pub fn install_native_caption_hit_testing() -> std::io::Result<usize> {
+    use windows::Win32::UI::Shell::SetWindowSubclass;
+    use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+
+    let mut hooked = 0;
+    let windows = crate::system::process_windows::top_level_windows()
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    for hwnd in windows {
+        if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
+            continue;
+        }
+        if unsafe { SetWindowSubclass(hwnd, Some(caption_subclass_proc), CAPTION_SUBCLASS_ID, 0) }
+            .as_bool()
+        {
+            hooked += 1;
+            match unsafe { windows::Win32::UI::HiDpi::GetDpiForWindow(hwnd) } {
+                0 => {}
+                dpi => crate::ui::theme::set_display_scale(dpi as f32 / 96.0),
+            }
+        }
+    }
+    Ok(hooked)
+}
+
+/// Report the custom title bar to the OS as a real caption (Windows only)
+#[cfg(not(target_os = "windows"))]
+pub fn install_native_caption_hit_testing() -> std::io::Result<usize> {
+    Ok(0)
+}
+
+/// Identifies RustPods' subclass so installing twice replaces rather than stacks it
+#[cfg(target_os = "windows")]
+const CAPTION_SUBCLASS_ID: usize = 0x5250_4342;
+
+#[cfg(target_os = "windows")]
+unsafe extern "system" fn caption_subclass_proc(
+    hwnd: windows::Win32::Foundation::HWND,
+    message: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+    _subclass_id: usize,
+    _ref_data: usize,
+) -> windows::Win32::Foundation::LRESULT {
+    use windows::Win32::Foundation::{LRESULT, POINT, RECT};
+    use windows::Win32::Graphics::Gdi::ScreenToClient;
+    use windows::Win32::UI::HiDpi::GetDpiForWindow;
+    use windows::Win32::UI::Shell::DefSubclassProc;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetClientRect, IsZoomed, HTBOTTOM, HTBOTTOMLEFT, HTBOTTOMRIGHT, HTCAPTION, HTCLIENT,
+        HTLEFT, HTRIGHT, HTTOP, HTTOPLEFT, HTTOPRIGHT, WM_DPICHANGED, WM_NCHITTEST,
+    };
+
+    // Moved to a monitor with another scale; the new DPI is in the low word
+    if message == WM_DPICHANGED {
+        crate::ui::theme::set_display_scale((wparam.0 & 0xFFFF) as f32 / 96.0);
+    }
+
+    let result = DefSubclassProc(hwnd, message, wparam, lparam);
+    if message != WM_NCHITTEST || result.0 != HTCLIENT as isize {
+        return result;
+    }
+
+    // Screen coordinates are packed as signed 16-bit values
+    let mut point = POINT {
+        x: (lparam.0 & 0xFFFF) as i16 as i32,
+        y: ((lparam.0 >> 16) & 0xFFFF) as i16 as i32,
+    };
+    let mut client = RECT::default();
+    if !ScreenToClient(hwnd, &mut point).as_bool() || GetClientRect(hwnd, &mut client).is_err() {
+        return result;
+    }
+
+    let scale = match GetDpiForWindow(hwnd) {
+        0 => 1.0,
+        dpi => dpi as f32 / 96.0,
+    };
+    let position = Point::new(point.x as f32 / scale, point.y as f32 / scale);
+    let width = (client.right - client.left) as f32 / scale;
+    let height = (client.bottom - client.top) as f32 / scale;
+
+    // A maximized window has no edges to drag
+    if !IsZoomed(hwnd).as_bool() {
+        if let Some(edge) = resize_edge_hit_test(position, width, height) {
+            let hit = match edge {
+                ResizeEdge::Left => HTLEFT,
+                ResizeEdge::Right => HTRIGHT,
+                ResizeEdge::Top => HTTOP,
+                ResizeEdge::Bottom => HTBOTTOM,
+                ResizeEdge::TopLeft => HTTOPLEFT,
+                ResizeEdge::TopRight => HTTOPRIGHT,
+                ResizeEdge::BottomLeft => HTBOTTOMLEFT,
+                ResizeEdge::BottomRight => HTBOTTOMRIGHT,
+            };
+            return LRESULT(hit as isize);
+        }
+    }
+
+    if !NATIVE_CAPTION_ENABLED.load(std::sync::atomic::Ordering::Relaxed) {
+        return result;
+    }
+    match title_bar_hit_test(position, width) {
+        TitleBarHit::Caption => LRESULT(HTCAPTION as isize),
+        TitleBarHit::CaptionButton | TitleBarHit::Client => result,
+    }
+}
+
+/// Gap between a popup opened near the tray and the taskbar, in logical pixels
+const TRAY_POPUP_MARGIN: f32 = 12.0;
+
+/// Screen edge the taskbar is docked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskbarEdge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+/// Where the taskbar is, and the monitor showing it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrayAnchor {
+    /// Edge the taskbar is docked to
+    pub edge: TaskbarEdge,
+    /// The monitor's work area, the screen less the taskbar, in physical pixels as
+    /// (left, top, right, bottom)
+    pub work_area: (i32, i32, i32, i32),
+    /// Physical pixels per logical pixel on that monitor
+    pub scale: f32,
+}
+
+/// Top-left corner, in physical pixels, for a window of `size` logical pixels opened
+/// beside the notification area
+///
+/// The notification area is at the far end of the taskbar, so the window goes in the
+/// matching corner of the work area: bottom right under a bottom taskbar, top right
+/// under a top one and at the bottom beside a vertical one. It is sized for the tray's
+/// monitor, which may scale differently from the one the window is on now.
+pub fn tray_popup_position(anchor: &TrayAnchor, size: (u32, u32)) -> (i32, i32) {
+    let (left, top, right, bottom) = anchor.work_area;
+    let margin = (TRAY_POPUP_MARGIN * anchor.scale).round() as i32;
+    let width = (size.0 as f32 * anchor.scale).round() as i32;
+    let height = (size.1 as f32 * anchor.scale).round() as i32;
+
+    let x = match anchor.edge {
+        TaskbarEdge::Left => left + margin,
+        _ => right - margin - width,
+    };
+    let y = match anchor.edge {
+        TaskbarEdge::Top => top + margin,
+        _ => bottom - margin - height,
+    };
+    // A window bigger than the work area keeps its title bar on screen
+    (x.max(left), y.max(top))
+}
+
+/// The taskbar's edge and monitor, from the shell
+#[cfg(target_os = "windows")]
+pub fn tray_anchor() -> Option<TrayAnchor> {
+    use windows::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromRect, MONITORINFO, MONITOR_DEFAULTTOPRIMARY,
+    };
+    use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+    use windows::Win32::UI::Shell::{
+        SHAppBarMessage, ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETTASKBARPOS, APPBARDATA,
+    };
+
+    let mut taskbar = APPBARDATA {
+        cbSize: std::mem::size_of::<APPBARDATA>() as u32,
+        ..Default::default()
+    };
+    if unsafe { SHAppBarMessage(ABM_GETTASKBARPOS, &mut taskbar) } == 0 {
+        log::debug!("Taskbar position not available");
+        return None;
+    }
+    let edge = match taskbar.uEdge {
+        ABE_LEFT => TaskbarEdge::Left,
+        ABE_TOP => TaskbarEdge::Top,
+        ABE_RIGHT => TaskbarEdge::Right,
+        _ => TaskbarEdge::Bottom,
+    };
+
+    let monitor = unsafe { MonitorFromRect(&taskbar.rc, MONITOR_DEFAULTTOPRIMARY) };
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        return None;
+    }
+    let (mut dpi_x, mut dpi_y) = (96, 96);
+    let scale =
+        match unsafe { GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) } {
+            Ok(()) => dpi_x as f32 / 96.0,
+            Err(_) => 1.0,
+        };
+
+    let work = info.rcWork;
+    Some(TrayAnchor {
+        edge,
+        work_area: (work.left, work.top, work.right, work.bottom),
+        scale,
+    })
+}
+
+/// The taskbar's edge and monitor (Windows only)
+#[cfg(not(target_os = "windows"))]
+pub fn tray_anchor() -> Option<TrayAnchor> {
+    None
+}
 
 /// Create a drag region that allows the user to move the window
 pub fn create_drag_region(
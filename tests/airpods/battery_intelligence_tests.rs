@@ -7,6 +7,8 @@ use rustpods::airpods::battery_intelligence::{
     BatteryEvent, BatteryEventType, BatteryIntelligence, DepletionRateBuffer, DepletionRateSample,
     DepletionTarget,
 };
+use rustpods::airpods::SnapshotReason;
+use rustpods::quarantine;
 
 // Unit tests for the BatteryIntelligence module
 //
@@ -584,6 +586,80 @@ fn test_purge_profiles() {
     std::fs::remove_dir_all(test_dir).ok();
 }
 
+#[test]
+fn test_purge_can_be_rolled_back() {
+    let test_dir = TempDir::new().unwrap();
+    let mut intelligence = BatteryIntelligence::new(test_dir.path().to_path_buf());
+    intelligence.update_device_battery(
+        "test_address",
+        "Test AirPods",
+        Some(80),
+        Some(75),
+        Some(90),
+        false,
+        false,
+        false,
+        false,
+        false,
+        None,
+    );
+    intelligence.save().expect("Failed to save profile");
+
+    intelligence.purge_all_profiles().expect("Failed to purge");
+    let snapshots = intelligence.snapshots();
+    assert_eq!(snapshots.len(), 1);
+    assert_eq!(snapshots[0].reason, SnapshotReason::Purge);
+
+    intelligence
+        .restore_snapshot(&snapshots[0])
+        .expect("Failed to restore snapshot");
+    let profile = intelligence
+        .device_profile
+        .as_ref()
+        .expect("Profile should be restored");
+    assert_eq!(profile.device_name, "Test AirPods");
+}
+
+#[test]
+fn test_unreadable_profile_is_quarantined_and_recovered() {
+    let test_dir = TempDir::new().unwrap();
+    let mut intelligence = BatteryIntelligence::new(test_dir.path().to_path_buf());
+    intelligence.update_device_battery(
+        "test_address",
+        "Test AirPods",
+        Some(80),
+        Some(75),
+        Some(90),
+        false,
+        false,
+        false,
+        false,
+        false,
+        None,
+    );
+    intelligence.save().expect("Failed to save profile");
+
+    // A write cut short
+    let profile_path = intelligence.profile_path();
+    let json = std::fs::read_to_string(&profile_path).unwrap();
+    std::fs::write(&profile_path, &json[..json.len() - 2]).unwrap();
+
+    let mut reloaded = BatteryIntelligence::new(test_dir.path().to_path_buf());
+    assert!(reloaded.device_profile.is_none());
+    assert!(!profile_path.exists());
+    let quarantined = quarantine::unresolved(test_dir.path());
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].original, profile_path);
+
+    reloaded
+        .recover_quarantined(&quarantined[0])
+        .expect("Failed to recover profile");
+    assert_eq!(
+        reloaded.device_profile.as_ref().unwrap().device_name,
+        "Test AirPods"
+    );
+}
+
 // Helper extension trait for Duration
 trait DurationExt {
     fn from_mins(minutes: u64) -> Self;
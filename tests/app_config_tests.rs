@@ -4,7 +4,7 @@ use std::fs;
 use std::time::Duration;
 use tempfile::tempdir;
 
-use rustpods::config::{AppConfig, ConfigError, Theme};
+use rustpods::config::{AppConfig, ConfigError, ProxyMode, Theme};
 
 /// Test default AppConfig settings
 #[test]
@@ -141,6 +141,16 @@ fn test_validation() {
         config.validate(),
         Err(ConfigError::ValidationFailed(_, _))
     ));
+
+    // A manual proxy needs somewhere to connect to
+    config = AppConfig::default();
+    config.system.proxy.mode = ProxyMode::Manual;
+    assert!(matches!(
+        config.validate(),
+        Err(ConfigError::ValidationFailed(field, _)) if field == "system.proxy.host"
+    ));
+    config.system.proxy.host = "proxy.example.com".to_string();
+    assert!(config.validate().is_ok());
 }
 
 #[test]
@@ -165,8 +175,8 @@ fn test_invalid_file() {
     let result = AppConfig::load_from_path(&file_path);
     assert!(result.is_err(), "Loading invalid JSON should fail");
     match result {
-        Err(ConfigError::SerializationError(_)) => {}
-        _ => panic!("Expected SerializationError but got {:?}", result),
+        Err(ConfigError::ParseError(e)) => assert_eq!(e.file.as_deref(), Some(file_path.as_path())),
+        _ => panic!("Expected ParseError but got {:?}", result),
     }
 
     // Cleanup
@@ -230,6 +230,7 @@ fn test_cli_data_to_airpods_conversion() {
             rssi: Some(-60),
             name: Some(model_name.to_string()),
             is_connected: true,
+            extras: None,
             last_seen: Instant::now(),
         };
         
@@ -89,6 +89,7 @@ pub fn create_test_airpods(
         rssi: Some(-60),
         last_seen: Instant::now(),
         is_connected: false,
+        extras: None,
     }
 }
 
@@ -213,6 +213,7 @@ fn test_manual_cli_data_conversion() {
         rssi: Some(-60), // Default value for testing
         name: Some("AirPods Pro".to_string()),
         is_connected: true,
+        extras: None,
         last_seen: Instant::now(),
     };
 
@@ -172,6 +172,7 @@ pub fn create_test_airpods(device_type: AirPodsType, address: Option<&str>) -> D
         rssi: Some(-60),
         last_seen: std::time::Instant::now(),
         is_connected: false,
+        extras: None,
     }
 }
 
@@ -267,8 +267,8 @@ fn test_invalid_config_files() {
     let result = AppConfig::load_from_path(&empty_file_path);
     assert!(result.is_err());
     match result {
-        Err(ConfigError::SerializationError(_)) => {} // Expected
-        _ => panic!("Expected SerializationError for empty file"),
+        Err(ConfigError::ParseError(_)) => {} // Expected
+        _ => panic!("Expected ParseError for empty file"),
     }
 
     // Test invalid JSON
@@ -277,8 +277,8 @@ fn test_invalid_config_files() {
     let result = AppConfig::load_from_path(&invalid_json_path);
     assert!(result.is_err());
     match result {
-        Err(ConfigError::SerializationError(_)) => {} // Expected
-        _ => panic!("Expected SerializationError for invalid JSON"),
+        Err(ConfigError::ParseError(e)) => assert!(e.syntax),
+        _ => panic!("Expected ParseError for invalid JSON"),
     }
 
     // Test incomplete JSON (missing required fields)
@@ -87,3 +87,34 @@ fn test_save_error_handling() {
     // In a real implementation, we would test that errors from AppConfig::save_to_path
     // are properly handled
 }
+
+/// Test that active errors are deduplicated, resolved and dismissed
+#[test]
+fn test_active_error_lifecycle() {
+    use rustpods::error::{ErrorManager, RecoveryAction, RustPodsError};
+
+    let mut manager = ErrorManager::new();
+    let error = RustPodsError::Bluetooth("adapter not found".to_string());
+
+    let first = manager.raise("scanner", &error);
+    let second = manager.raise("scanner", &error);
+    assert_eq!(first, second, "Repeated errors should not stack");
+    assert_eq!(manager.active_errors().len(), 1);
+    assert_eq!(manager.active_errors()[0].occurrences, 2);
+    assert_eq!(
+        manager.active_errors()[0].recovery,
+        RecoveryAction::ReconnectBluetooth
+    );
+    assert_eq!(
+        RecoveryAction::ReconnectBluetooth.button_label(),
+        Some("Retry")
+    );
+
+    let config_error = manager.raise("config", &RustPodsError::Config("bad value".to_string()));
+    manager.resolve("scanner");
+    assert_eq!(manager.active_errors().len(), 1);
+
+    assert!(manager.dismiss(config_error).is_some());
+    assert!(manager.active_errors().is_empty());
+    assert_eq!(manager.get_error_history().len(), 3);
+}
@@ -6,7 +6,7 @@
 
 use std::time::Duration;
 
-use rustpods::config::{AppConfig, ConfigError, Theme};
+use rustpods::config::{AppConfig, ConfigError, Theme, TrayIconStyle};
 // use rustpods::ui::{SettingsWindow, SettingsTab};
 use iced::widget::text;
 use iced::Element;
@@ -420,6 +420,28 @@ impl SettingsView {
                     Some(value.trim().to_string())
                 };
             }
+            BluetoothSetting::OnlyPairedDevice(value) => {
+                self.config.bluetooth.only_paired_device = value;
+            }
+            BluetoothSetting::AllowListedOnly(value) => {
+                self.config.bluetooth.allow_listed_only = value;
+            }
+            BluetoothSetting::AllowDevice(entry) => {
+                self.config.bluetooth.allow_list.push(entry);
+            }
+            BluetoothSetting::DenyDevice(entry) => {
+                self.config.bluetooth.deny_list.push(entry);
+            }
+            BluetoothSetting::RemoveAllowedDevice(index) => {
+                if index < self.config.bluetooth.allow_list.len() {
+                    self.config.bluetooth.allow_list.remove(index);
+                }
+            }
+            BluetoothSetting::RemoveDeniedDevice(index) => {
+                if index < self.config.bluetooth.deny_list.len() {
+                    self.config.bluetooth.deny_list.remove(index);
+                }
+            }
         }
     }
 
@@ -443,9 +465,124 @@ impl SettingsView {
             UiSetting::LowBatteryThreshold(value) => {
                 self.config.ui.low_battery_threshold = if value > 100 { 100 } else { value };
             }
+            UiSetting::ComponentLowBatteryThreshold(component, value) => {
+                self.config
+                    .ui
+                    .set_low_battery_threshold_for(component, value);
+            }
+            UiSetting::StaleAfterMinutes(minutes) => {
+                self.config.ui.stale_after_minutes = minutes;
+            }
             UiSetting::MinimizeToTrayOnClose(value) => {
                 self.config.ui.minimize_to_tray_on_close = value;
             }
+            UiSetting::ShowLidOpenPopup(value) => {
+                self.config.ui.show_lid_open_popup = value;
+            }
+            UiSetting::ShowAudioSwitchButton(value) => {
+                self.config.ui.show_audio_switch_button = value;
+            }
+            UiSetting::HouseholdMode(value) => {
+                self.config.ui.household_mode = value;
+            }
+            UiSetting::ExcludeFromCapture(value) => {
+                self.config.ui.exclude_from_capture = value;
+            }
+            UiSetting::TaskbarBadge(value) => {
+                self.config.ui.taskbar_badge = value;
+            }
+            UiSetting::TrayMetric(metric) => {
+                self.config.ui.tray_metric = metric;
+            }
+            UiSetting::TrayIconStyle(style) => {
+                self.config.ui.tray_icon_style = style;
+            }
+            UiSetting::TrayIconFolder(folder) => {
+                self.config.ui.tray_icon_folder = Some(folder);
+                self.config.ui.tray_icon_style = TrayIconStyle::Custom;
+            }
+            UiSetting::SoftwareRendering(value) => {
+                self.config.ui.software_rendering = value;
+            }
+            UiSetting::TrayOnly(value) => {
+                self.config.ui.tray_only = value;
+            }
+            UiSetting::ShowMahEstimates(value) => {
+                self.config.ui.show_mah_estimates = value;
+            }
+            UiSetting::ThemeSchedule(mode) => {
+                self.config.ui.theme_schedule.mode = mode;
+            }
+            UiSetting::Backdrop(backdrop) => {
+                self.config.ui.backdrop = backdrop;
+            }
+            UiSetting::SpeechEnabled(value) => {
+                self.config.ui.speech.enabled = value;
+            }
+            UiSetting::SpeechAnnounceLowBattery(value) => {
+                self.config.ui.speech.announce_low_battery = value;
+            }
+            UiSetting::SpeechVoice(value) => {
+                self.config.ui.speech.voice = Some(value);
+            }
+            UiSetting::SpeechRate(value) => {
+                self.config.ui.speech.rate = value;
+            }
+            UiSetting::SoundAlertsEnabled(value) => {
+                self.config.ui.sound_alerts.enabled = value;
+            }
+            UiSetting::SoundAlert(event, value) => {
+                self.config.ui.sound_alerts.set_plays(event, value);
+            }
+            UiSetting::SoundAlertFile(event, path) => {
+                self.config.ui.sound_alerts.set_sound(event, &path);
+            }
+            UiSetting::QuietHoursEnabled(value) => {
+                self.config.ui.quiet_hours.enabled = value;
+            }
+            UiSetting::QuietHoursStart(minutes) => {
+                self.config.ui.quiet_hours.start_hour = (minutes / 60) as u8;
+                self.config.ui.quiet_hours.start_minute = (minutes % 60) as u8;
+            }
+            UiSetting::QuietHoursEnd(minutes) => {
+                self.config.ui.quiet_hours.end_hour = (minutes / 60) as u8;
+                self.config.ui.quiet_hours.end_minute = (minutes % 60) as u8;
+            }
+            UiSetting::QuietHoursDay(day, included) => {
+                let days = &mut self.config.ui.quiet_hours.days;
+                days.retain(|other| *other != day);
+                if included {
+                    days.push(day);
+                }
+            }
+            UiSetting::QuietHoursFollowFocusAssist(value) => {
+                self.config.ui.quiet_hours.follow_focus_assist = value;
+            }
+            UiSetting::QuietHoursSummarizeHeld(value) => {
+                self.config.ui.quiet_hours.summarize_held = value;
+            }
+            UiSetting::FontFamily(family) => {
+                self.config.ui.font_family =
+                    Some(family).filter(|family| family != rustpods::ui::theme::FONT_FAMILY);
+            }
+            UiSetting::FontScale(scale) => {
+                self.config.ui.font_scale = scale;
+            }
+            UiSetting::Locale(locale) => {
+                self.config.ui.locale = locale;
+            }
+            UiSetting::BatteryPalette(palette) => {
+                self.config.ui.battery_palette = palette;
+            }
+            UiSetting::ShapeEncoding(value) => {
+                self.config.ui.shape_encoding = value;
+            }
+            UiSetting::Shortcut(action, shortcut) => {
+                self.config.ui.shortcuts.insert(action, shortcut);
+            }
+            UiSetting::ResetShortcuts => {
+                self.config.ui.shortcuts.clear();
+            }
         }
     }
 
@@ -454,6 +591,35 @@ impl SettingsView {
             SystemSetting::StartOnBoot(value) => {
                 self.config.system.launch_at_startup = value;
             }
+            SystemSetting::EnableTelemetry(value) => {
+                self.config.system.enable_telemetry = value;
+            }
+            SystemSetting::PrivacyMode(value) => {
+                self.config.system.privacy_mode = value;
+            }
+            SystemSetting::RefreshKnownIssues(value) => {
+                self.config.system.refresh_known_issues = value;
+            }
+            SystemSetting::DeveloperMode(value) => {
+                self.config.system.developer_mode = value;
+            }
+            SystemSetting::BackupFrequency(frequency) => {
+                self.config.system.backup.frequency = frequency;
+            }
+            SystemSetting::BackupFolder(folder) => {
+                self.config.system.backup.folder = Some(folder);
+            }
+            SystemSetting::CategoryLogLevel(category, level) => match level {
+                Some(level) => {
+                    self.config
+                        .system
+                        .category_log_levels
+                        .insert(category, level);
+                }
+                None => {
+                    self.config.system.category_log_levels.remove(&category);
+                }
+            },
         }
     }
 
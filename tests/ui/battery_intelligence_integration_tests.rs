@@ -3,6 +3,7 @@
 //! Tests the integration between the new BatteryIntelligence system and the UI state management
 
 use rustpods::airpods::battery::AirPodsBatteryInfo;
+use rustpods::airpods::test_support::report;
 use rustpods::ui::state::AppState;
 use std::time::SystemTime;
 
@@ -36,25 +37,17 @@ fn test_battery_intelligence_device_update() {
     // Create test AirPods data
     let airpods = AirPodsBatteryInfo {
         address: 123456789,
-        canonical_address: "75bcd15".to_string(), // hex representation of 123456789
         name: "Test AirPods Pro".to_string(),
         model_id: 0x2014, // AirPods Pro 2
         left_battery: 85,
-        left_charging: false,
-        right_battery: 80,
-        right_charging: false,
         case_battery: 95,
         case_charging: true,
         left_in_ear: Some(true),
         right_in_ear: Some(true),
         case_lid_open: Some(false),
-        side: None,
         both_in_case: Some(false),
-        color: None,
-        switch_count: None,
         rssi: Some(-45),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("75bcd15")
     };
 
     // Update the state with AirPods data
@@ -84,25 +77,17 @@ fn test_battery_intelligence_fractional_estimates() {
     // Create test AirPods data
     let airpods = AirPodsBatteryInfo {
         address: 987654321,
-        canonical_address: "3ade68b1".to_string(), // hex representation of 987654321
         name: "Test AirPods".to_string(),
         model_id: 0x200F, // AirPods 2
         left_battery: 75,
-        left_charging: false,
         right_battery: 70,
-        right_charging: false,
         case_battery: 50,
-        case_charging: false,
         left_in_ear: Some(false),
         right_in_ear: Some(false),
         case_lid_open: Some(true),
-        side: None,
         both_in_case: Some(true),
-        color: None,
-        switch_count: None,
         rssi: Some(-55),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("3ade68b1")
     };
 
     // Update the state with AirPods data
@@ -138,25 +123,16 @@ fn test_battery_intelligence_significance_filtering() {
     // Create AirPods data
     let mut airpods = AirPodsBatteryInfo {
         address: 333444555,
-        canonical_address: "13dde4b3".to_string(), // hex representation of 333444555
         name: "Test AirPods Pro 2".to_string(),
-        model_id: 0x200E,
         left_battery: 90,
-        left_charging: false,
         right_battery: 90,
-        right_charging: false,
         case_battery: 100,
-        case_charging: false,
         left_in_ear: Some(true),
         right_in_ear: Some(true),
         case_lid_open: Some(false),
-        side: None,
         both_in_case: Some(false),
-        color: None,
-        switch_count: None,
         rssi: Some(-45),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("13dde4b3")
     };
 
     // First update
@@ -224,25 +200,16 @@ fn test_battery_intelligence_charging_state_detection() {
     // Create AirPods data - not charging
     let mut airpods = AirPodsBatteryInfo {
         address: 444555666,
-        canonical_address: "1a83f92a".to_string(), // hex representation of 444555666
         name: "Test AirPods Max".to_string(),
         model_id: 0x200A,
         left_battery: 60,
-        left_charging: false,
         right_battery: 60,
-        right_charging: false,
         case_battery: 0, // AirPods Max don't have a case
-        case_charging: false,
         left_in_ear: Some(true),
         right_in_ear: Some(true),
-        case_lid_open: None,
-        side: None,
         both_in_case: Some(false),
-        color: None,
-        switch_count: None,
         rssi: Some(-35),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("1a83f92a")
     };
 
     // First update - not charging
@@ -331,30 +298,20 @@ fn test_battery_intelligence_multiple_devices() {
     // Create multiple AirPods devices
     let airpods1 = AirPodsBatteryInfo {
         address: 111111111,
-        canonical_address: "69f6bcf".to_string(), // hex representation of 111111111
         name: "AirPods Pro 1".to_string(),
         model_id: 0x2014,
-        left_battery: 80,
-        left_charging: false,
         right_battery: 75,
-        right_charging: false,
         case_battery: 90,
-        case_charging: false,
         left_in_ear: Some(true),
         right_in_ear: Some(true),
         case_lid_open: Some(false),
-        side: None,
         both_in_case: Some(false),
-        color: None,
-        switch_count: None,
         rssi: Some(-45),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("69f6bcf")
     };
 
     let airpods2 = AirPodsBatteryInfo {
         address: 222222222,
-        canonical_address: "d3ec5ce".to_string(), // hex representation of 222222222
         name: "AirPods Pro 2".to_string(),
         model_id: 0x2014,
         left_battery: 65,
@@ -366,13 +323,9 @@ fn test_battery_intelligence_multiple_devices() {
         left_in_ear: Some(false),
         right_in_ear: Some(false),
         case_lid_open: Some(true),
-        side: None,
         both_in_case: Some(true),
-        color: None,
-        switch_count: None,
         rssi: Some(-50),
-        timestamp: None,
-        raw_manufacturer_data: None,
+        ..report("d3ec5ce")
     };
 
     // Update with multiple devices
@@ -39,6 +39,7 @@ fn test_airpods_popup_construction() {
         is_connected: true,
         last_seen: SystemTime::now(),
         manufacturer_data: Vec::new(),
+        charging: [false; 3],
     };
 
     // Create the component
@@ -79,6 +80,7 @@ fn test_airpods_popup_view_rendering() {
         is_connected: true,
         last_seen: SystemTime::now(),
         manufacturer_data: Vec::new(),
+        charging: [false; 3],
     };
 
     // Create the component
@@ -119,6 +121,7 @@ fn test_airpods_popup_with_missing_battery_info() {
         is_connected: true,
         last_seen: SystemTime::now(),
         manufacturer_data: Vec::new(),
+        charging: [false; 3],
     };
 
     // Create the component
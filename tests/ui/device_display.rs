@@ -106,6 +106,7 @@ fn create_test_airpods(
         }),
         rssi: Some(-60),
         is_connected: true,
+        extras: None,
         last_seen: Instant::now(),
     }
 }
@@ -5,7 +5,7 @@
 
 use rustpods::config::AppConfig;
 use rustpods::ui::state::MergedBluetoothDevice;
-use rustpods::ui::{theme::Theme, MainWindow, UiComponent};
+use rustpods::ui::{theme::Theme, MainWindow, UiComponent, ViewMode};
 // Integration tests for UI components
 
 /// Test complete AirPods detection and display workflow
@@ -98,6 +98,17 @@ fn test_ui_state_transitions() {
         let _ = advanced_mode;
     }
 
+    // Compact and expanded views toggle back and forth
+    assert_eq!(window.view_mode, ViewMode::Expanded);
+    let _ = window.toggle_view_mode();
+    assert_eq!(window.view_mode, ViewMode::Compact);
+    {
+        let compact = window.view();
+        let _ = compact;
+    }
+    let _ = window.toggle_view_mode();
+    assert_eq!(window.view_mode, ViewMode::Expanded);
+
     // All transitions should render without issues
 }
 
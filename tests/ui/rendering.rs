@@ -88,6 +88,7 @@ fn create_test_airpods(
         }),
         last_seen: Instant::now(),
         is_connected: true,
+        extras: None,
     }
 }
 
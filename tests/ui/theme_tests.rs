@@ -79,7 +79,7 @@ fn test_all_themes_defined() {
     // Test that each theme can provide styles for basic UI elements
     for theme in themes {
         // Test application style
-        let app_style = <Theme as application::StyleSheet>::appearance(&theme, &());
+        let app_style = <Theme as application::StyleSheet>::appearance(&theme, &Default::default());
         assert!(
             app_style.background_color != Color::TRANSPARENT,
             "Theme {:?} should provide a valid background color",
@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ui::window_management::{WindowInteraction, DragRegion};
+    use crate::ui::window_management::{
+        caption_buttons_width, clamp_window_size, resize_edge_hit_test, title_bar_hit_test,
+        tray_popup_position, BatteryArrangement, DragRegion, ResizeEdge, ResponsiveLayout,
+        TaskbarEdge, TitleBarHit, TrayAnchor, WindowInteraction, DEFAULT_WINDOW_HEIGHT,
+        DEFAULT_WINDOW_WIDTH, MAX_WINDOW_WIDTH, MIN_WINDOW_HEIGHT, TITLE_BAR_HEIGHT,
+    };
     use iced::Point;
 
     #[test]
@@ -51,4 +56,87 @@ mod tests {
         let new_position = interaction.calculate_window_position(Point::new(150.0, 120.0));
         assert_eq!(new_position, Some(Point::new(50.0, 20.0)));
     }
-} 
+
+    #[test]
+    fn test_title_bar_hit_test() {
+        let width = DEFAULT_WINDOW_WIDTH as f32;
+        assert_eq!(title_bar_hit_test(Point::new(50.0, 10.0), width), TitleBarHit::Caption);
+        assert_eq!(
+            title_bar_hit_test(Point::new(width - 5.0, 10.0), width),
+            TitleBarHit::CaptionButton
+        );
+        assert_eq!(
+            title_bar_hit_test(Point::new(width - caption_buttons_width() - 1.0, 10.0), width),
+            TitleBarHit::Caption
+        );
+        assert_eq!(
+            title_bar_hit_test(Point::new(50.0, TITLE_BAR_HEIGHT), width),
+            TitleBarHit::Client
+        );
+    }
+
+    #[test]
+    fn test_responsive_layout() {
+        let default = ResponsiveLayout::for_size(DEFAULT_WINDOW_WIDTH, DEFAULT_WINDOW_HEIGHT);
+        assert_eq!(default.arrangement, BatteryArrangement::SideBySide);
+        assert!(default.show_device_name && default.show_details);
+
+        // Tall and narrow stacks the batteries
+        let narrow = ResponsiveLayout::for_size(310, 640);
+        assert_eq!(narrow.arrangement, BatteryArrangement::Stacked);
+
+        // Narrow but short keeps them side by side and drops the extra sections
+        let small = ResponsiveLayout::for_size(310, 340);
+        assert_eq!(small.arrangement, BatteryArrangement::SideBySide);
+        assert!(!small.show_device_name && !small.show_details);
+    }
+
+    #[test]
+    fn test_window_size_clamping_and_resize_edges() {
+        assert_eq!(clamp_window_size(10_000, 0), (MAX_WINDOW_WIDTH, MIN_WINDOW_HEIGHT));
+        assert_eq!(clamp_window_size(400, 600), (400, 600));
+
+        assert_eq!(
+            resize_edge_hit_test(Point::new(1.0, 1.0), 360.0, 500.0),
+            Some(ResizeEdge::TopLeft)
+        );
+        assert_eq!(
+            resize_edge_hit_test(Point::new(359.0, 250.0), 360.0, 500.0),
+            Some(ResizeEdge::Right)
+        );
+        assert_eq!(resize_edge_hit_test(Point::new(180.0, 250.0), 360.0, 500.0), None);
+    }
+
+    #[test]
+    fn test_tray_popup_position() {
+        // 1920x1080 at 150% with a 72px taskbar along the bottom
+        let bottom = TrayAnchor {
+            edge: TaskbarEdge::Bottom,
+            work_area: (0, 0, 1920, 1008),
+            scale: 1.5,
+        };
+        assert_eq!(
+            tray_popup_position(&bottom, (360, 500)),
+            (1902 - 540, 990 - 750)
+        );
+
+        // A secondary monitor left of the primary, taskbar on its left edge
+        let left = TrayAnchor {
+            edge: TaskbarEdge::Left,
+            work_area: (-1860, 0, 0, 1080),
+            scale: 1.0,
+        };
+        assert_eq!(tray_popup_position(&left, (360, 500)), (-1848, 568));
+
+        let top = TrayAnchor {
+            edge: TaskbarEdge::Top,
+            work_area: (0, 40, 1280, 720),
+            scale: 1.0,
+        };
+        assert_eq!(tray_popup_position(&top, (360, 500)), (908, 52));
+
+        // Too tall for the work area: the title bar stays on screen
+        assert_eq!(tray_popup_position(&top, (360, 1000)).1, 52);
+        assert_eq!(tray_popup_position(&bottom, (360, 1000)).1, 0);
+    }
+}